@@ -0,0 +1,63 @@
+#!/bin/bash
+
+# Report Generation Benchmark
+# Usage: ./bench_report.sh [findings] [format...]
+#
+# Builds a results file with many findings out of an analysis of the sample
+# contracts and times rendering it, to catch regressions in report speed.
+
+set -e
+
+echo "⏱️  SecureChain Report Benchmark"
+echo "================================"
+
+FINDINGS=${1:-1500}
+shift || true
+FORMATS=${*:-markdown html}
+BENCH_DIR="target/bench_report"
+RESULTS="$BENCH_DIR/results_${FINDINGS}.json"
+
+# Build if needed
+if [ ! -f "./target/release/securechain" ]; then
+    echo "🏗️  Building SecureChain..."
+    cargo build --release
+fi
+
+mkdir -p "$BENCH_DIR"
+
+# The fixture: findings of the sample contracts repeated across severities and files
+if [ ! -f "$RESULTS" ]; then
+    echo "🧪 Building a results file with $FINDINGS findings..."
+    ./target/release/securechain --json analyze -i test_contracts -t evm > "$BENCH_DIR/seed.json"
+    python3 - "$BENCH_DIR/seed.json" "$RESULTS" "$FINDINGS" <<'EOF'
+import copy, json, sys, uuid
+
+seed_path, out_path, count = sys.argv[1], sys.argv[2], int(sys.argv[3])
+results = json.load(open(seed_path))
+seed = results["vulnerabilities"]
+if not seed:
+    sys.exit("The sample contracts produced no findings to repeat")
+
+severities = ["Critical", "High", "Medium", "Low", "Informational"]
+findings = []
+for i in range(count):
+    finding = copy.deepcopy(seed[i % len(seed)])
+    finding["id"] = str(uuid.uuid4())
+    finding["severity"] = severities[i % len(severities)]
+    finding["file_path"] = "contracts/Module%03d.sol" % (i % 150)
+    finding["line_number"] = 10 + i % 400
+    findings.append(finding)
+results["vulnerabilities"] = findings
+json.dump(results, open(out_path, "w"))
+EOF
+fi
+
+for FORMAT in $FORMATS; do
+    echo ""
+    echo "📄 $FORMAT"
+    /usr/bin/time -f "   %es elapsed, %MKB peak memory" \
+        ./target/release/securechain report -r "$RESULTS" -o "$FORMAT" -f "$BENCH_DIR/report.$FORMAT"
+done
+
+echo ""
+echo "✅ Benchmark complete! Reports are in $BENCH_DIR"
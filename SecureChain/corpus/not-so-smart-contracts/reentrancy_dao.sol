@@ -0,0 +1,16 @@
+// not-so-smart-contracts: Reentrancy (The DAO)
+pragma solidity 0.8.19;
+
+contract Reentrancy {
+    mapping(address => uint256) private userBalance;
+
+    function addToBalance() external payable {
+        userBalance[msg.sender] += msg.value;
+    }
+
+    function withdrawBalance() external {
+        (bool ok, ) = msg.sender.call{value: userBalance[msg.sender]}("");
+        require(ok);
+        userBalance[msg.sender] = 0;
+    }
+}
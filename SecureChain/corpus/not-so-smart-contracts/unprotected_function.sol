@@ -0,0 +1,24 @@
+// not-so-smart-contracts: Unprotected function
+pragma solidity 0.8.19;
+
+contract Unprotected {
+    address private owner;
+
+    modifier onlyowner() {
+        require(msg.sender == owner);
+        _;
+    }
+
+    constructor() {
+        owner = msg.sender;
+    }
+
+    // Anyone can take ownership
+    function changeOwner(address newOwner) public {
+        owner = newOwner;
+    }
+
+    function withdraw() external onlyowner {
+        payable(owner).transfer(address(this).balance);
+    }
+}
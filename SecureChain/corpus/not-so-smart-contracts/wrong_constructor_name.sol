@@ -0,0 +1,20 @@
+// not-so-smart-contracts: Wrong constructor name
+pragma solidity 0.4.15;
+
+contract Missing {
+    address private owner;
+
+    modifier onlyowner() {
+        require(msg.sender == owner);
+        _;
+    }
+
+    // Misspelt, so anyone can call it and become owner
+    function missing() public {
+        owner = msg.sender;
+    }
+
+    function withdraw() public onlyowner {
+        owner.transfer(this.balance);
+    }
+}
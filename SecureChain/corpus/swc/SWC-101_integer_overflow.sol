@@ -0,0 +1,12 @@
+// SWC-101: Integer Overflow and Underflow
+pragma solidity 0.4.24;
+
+contract IntegerOverflowAdd {
+    mapping(address => uint256) public balanceOf;
+
+    function transfer(address to, uint256 value) public {
+        require(balanceOf[msg.sender] >= value);
+        balanceOf[msg.sender] -= value;
+        balanceOf[to] += value;
+    }
+}
@@ -0,0 +1,10 @@
+// SWC-103: Floating Pragma
+pragma solidity ^0.8.0;
+
+contract PragmaNotLocked {
+    uint256 public x = 1;
+
+    function set(uint256 value) external {
+        x = value;
+    }
+}
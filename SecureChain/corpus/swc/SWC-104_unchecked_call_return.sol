@@ -0,0 +1,8 @@
+// SWC-104: Unchecked Call Return Value
+pragma solidity 0.8.19;
+
+contract ReturnValue {
+    function callNotChecked(address callee) external {
+        callee.call(abi.encodeWithSignature("doSomething()"));
+    }
+}
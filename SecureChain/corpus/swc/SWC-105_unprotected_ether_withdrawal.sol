@@ -0,0 +1,16 @@
+// SWC-105: Unprotected Ether Withdrawal
+pragma solidity 0.8.19;
+
+contract Wallet {
+    address payable public owner;
+
+    constructor() {
+        owner = payable(msg.sender);
+    }
+
+    function deposit() external payable {}
+
+    function withdrawTo(address payable to, uint256 amount) external {
+        to.transfer(amount);
+    }
+}
@@ -0,0 +1,10 @@
+// SWC-106: Unprotected SELFDESTRUCT Instruction
+pragma solidity 0.8.19;
+
+contract SimpleSuicide {
+    function deposit() external payable {}
+
+    function sudicideAnyone() external {
+        selfdestruct(payable(msg.sender));
+    }
+}
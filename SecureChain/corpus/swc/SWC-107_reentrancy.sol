@@ -0,0 +1,18 @@
+// SWC-107: Reentrancy
+pragma solidity 0.8.19;
+
+contract SimpleDAO {
+    mapping(address => uint256) public credit;
+
+    function donate(address to) external payable {
+        credit[to] += msg.value;
+    }
+
+    function withdraw(uint256 amount) external {
+        if (credit[msg.sender] >= amount) {
+            (bool ok, ) = msg.sender.call{value: amount}("");
+            require(ok);
+            credit[msg.sender] -= amount;
+        }
+    }
+}
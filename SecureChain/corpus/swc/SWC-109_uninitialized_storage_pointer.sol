@@ -0,0 +1,21 @@
+// SWC-109: Uninitialized Storage Pointer
+pragma solidity 0.4.24;
+
+contract NameRegistrar {
+    bool public unlocked = false;
+
+    struct NameRecord {
+        bytes32 name;
+        address mappedAddress;
+    }
+
+    mapping(address => NameRecord) public registeredNameRecord;
+
+    function register(bytes32 _name, address _mappedAddress) public {
+        NameRecord newRecord;
+        newRecord.name = _name;
+        newRecord.mappedAddress = _mappedAddress;
+        registeredNameRecord[_mappedAddress] = newRecord;
+        require(unlocked);
+    }
+}
@@ -0,0 +1,15 @@
+// SWC-112: Delegatecall to Untrusted Callee
+pragma solidity 0.8.19;
+
+contract Proxy {
+    address public owner;
+
+    constructor() {
+        owner = msg.sender;
+    }
+
+    function forward(address callee, bytes calldata data) external {
+        (bool ok, ) = callee.delegatecall(data);
+        require(ok);
+    }
+}
@@ -0,0 +1,17 @@
+// SWC-113: DoS with Failed Call
+pragma solidity 0.8.19;
+
+interface IPayee {
+    function notify(uint256 amount) external;
+}
+
+contract Refunder {
+    address[] private refundAddresses;
+    mapping(address => uint256) public refunds;
+
+    function refundAll() external {
+        for (uint256 i = 0; i < refundAddresses.length; i++) {
+            IPayee(refundAddresses[i]).notify(refunds[refundAddresses[i]]);
+        }
+    }
+}
@@ -0,0 +1,15 @@
+// SWC-115: Authorization through tx.origin
+pragma solidity 0.8.19;
+
+contract MyContract {
+    address owner;
+
+    constructor() {
+        owner = msg.sender;
+    }
+
+    function sendTo(address payable receiver, uint256 amount) public {
+        require(tx.origin == owner);
+        receiver.transfer(amount);
+    }
+}
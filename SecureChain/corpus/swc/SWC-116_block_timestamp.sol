@@ -0,0 +1,10 @@
+// SWC-116: Block values as a proxy for time
+pragma solidity 0.8.19;
+
+contract TimedCrowdsale {
+    uint256 public constant END = 1546300800;
+
+    function isSaleFinished() public view returns (bool) {
+        return block.timestamp >= END;
+    }
+}
@@ -0,0 +1,14 @@
+// SWC-119: Shadowing State Variables
+pragma solidity 0.4.24;
+
+contract Tokensale {
+    uint256 hardcap = 10000 ether;
+
+    function fetchCap() public constant returns (uint256) {
+        return hardcap;
+    }
+}
+
+contract Presale is Tokensale {
+    uint256 hardcap = 1000 ether;
+}
@@ -0,0 +1,11 @@
+// SWC-120: Weak Sources of Randomness from Chain Attributes
+pragma solidity 0.8.19;
+
+contract GuessTheRandomNumber {
+    function guess(uint256 number) external payable {
+        uint256 answer = uint256(keccak256(abi.encodePacked(blockhash(block.number - 1), block.timestamp)));
+        if (number == answer) {
+            payable(msg.sender).transfer(address(this).balance);
+        }
+    }
+}
@@ -0,0 +1,11 @@
+// SWC-132: Unexpected Ether balance
+pragma solidity 0.8.19;
+
+contract Lockdrop {
+    uint256 public lockedAmount;
+
+    function lock() external payable {
+        lockedAmount += msg.value;
+        assert(address(this).balance == lockedAmount);
+    }
+}
@@ -4,7 +4,7 @@
 //! for integrating smart contract security analysis into your own tools.
 
 use anyhow::Result;
-use bugforgex::{
+use securechain::{
     core::{analyzer::AnalysisEngine, fetcher::ContractFetcher},
     plugins::PluginManager,
     report::generator::ReportGenerator,
@@ -113,7 +113,7 @@ async fn fetch_and_analyze() -> Result<()> {
     let config = Config::default();
     let plugin_manager = PluginManager::new();
     let analysis_engine = AnalysisEngine::new(config.clone(), plugin_manager);
-    let fetcher = ContractFetcher::new(config);
+    let _fetcher = ContractFetcher::new(config);
 
     // Note: This would require a valid Etherscan API key
     // For this example, we'll simulate the process
@@ -528,7 +528,7 @@ async fn generate_comprehensive_report() -> Result<()> {
     println!("📄 Generating reports in multiple formats...");
 
     // Generate Markdown report
-    let markdown_report = report_generator.generate_markdown_report(&results)?;
+    let markdown_report = report_generator.generate_markdown_report_from_results(&results)?;
     let markdown_path = temp_dir.path().join("audit_report.md");
     std::fs::write(&markdown_path, &markdown_report)?;
     println!("✅ Markdown report: {}", markdown_path.display());
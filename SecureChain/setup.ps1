@@ -0,0 +1,49 @@
+# Setup for SecureChain on Windows
+
+$ErrorActionPreference = "Stop"
+
+Write-Host "🔧 Setting up SecureChain - Universal Web3 Security Auditor"
+Write-Host "=========================================================="
+
+function Test-Command($Name) {
+    return [bool](Get-Command $Name -ErrorAction SilentlyContinue)
+}
+
+# Install system dependencies
+Write-Host "📦 Installing system dependencies..."
+if (Test-Command winget) {
+    if (-not (Test-Command py)) { winget install --silent --accept-package-agreements Python.Python.3.11 }
+    if (-not (Test-Command npm)) { winget install --silent --accept-package-agreements OpenJS.NodeJS.LTS }
+    if (-not (Test-Command git)) { winget install --silent --accept-package-agreements Git.Git }
+} else {
+    Write-Host "⚠️  winget not found; make sure Python 3, Node.js and Git are installed."
+}
+
+# Install Rust if not present
+if (-not (Test-Command cargo)) {
+    Write-Host "🦀 Installing Rust..."
+    $rustup = Join-Path $env:TEMP "rustup-init.exe"
+    Invoke-WebRequest -Uri "https://win.rustup.rs/x86_64" -OutFile $rustup
+    & $rustup -y
+    $env:Path += ";$env:USERPROFILE\.cargo\bin"
+}
+
+# Install Python analysis tools; SecureChain falls back to `py -3 -m` when pip's Scripts directory is not on PATH
+Write-Host "🐍 Installing Python security tools..."
+py -3 -m pip install --user slither-analyzer mythril crytic-compile solc-select semgrep
+if ($LASTEXITCODE -ne 0) { Write-Host "⚠️  Some Python tools failed to install (Mythril often needs Visual C++ build tools)." }
+
+# Echidna and Foundry do not ship native Windows builds
+Write-Host "🎲 Echidna and Foundry are not available natively on Windows; use WSL to run fuzzing."
+
+# Install additional tools
+Write-Host "🔍 Installing additional analysis tools..."
+npm install -g solhint @openzeppelin/contracts
+
+# Build SecureChain
+Write-Host "🏗️  Building SecureChain..."
+cargo build --release
+if ($LASTEXITCODE -ne 0) { throw "cargo build failed" }
+
+Write-Host "✅ Setup completed successfully!"
+Write-Host "🚀 You can now run: .\target\release\securechain.exe --help"
@@ -1,11 +1,55 @@
-use crate::core::ai_assist::AIAssistant;
-use crate::core::analyzer::{AnalysisEngine, AnalysisResults};
-use crate::core::fetcher::ContractFetcher;
+use crate::core::ai_log;
+#[cfg(feature = "ai")]
+use crate::core::ai_health;
+use crate::core::analyzer::{AnalysisEngine, AnalysisResults, SCORE_UNIT_LINES};
+use crate::core::annotate::{self, Annotator};
+use crate::core::bytecode::{BytecodeAnalysis, SignatureDb};
+use crate::core::capabilities::Capabilities;
+use crate::core::ci::{self, GithubTarget};
+use crate::core::community::{self, Disposition};
+use crate::core::daemon;
+use crate::core::detector_catalog::{self, Detector};
+use crate::core::detectors;
+use crate::core::diamond;
+use crate::core::error_handling;
+use crate::core::fetcher::{self, ContractFetcher, DeployedContract, FetchItem, FetchManifest, FetchStatus, Fetched};
+use crate::core::history::{RunProfile, StageTimer};
+use crate::core::hook;
+use crate::core::import;
+use crate::core::manifest::RunManifest;
+use crate::core::poc_verify::{self, PocStatus, PocVerifier};
+use crate::core::project_graph::{CallKind, ProjectGraph};
+use crate::core::protocol::{self, ProtocolContract, ProtocolManifest, ProtocolProject};
+use crate::core::remediation::{self, FixStatus};
+use crate::core::rpc::RpcClient;
+use crate::core::selftest;
+use crate::core::server;
+use crate::core::simulation::SimulationSettings;
+use crate::core::snapshot::{self, DriftKind, Snapshot};
+use crate::core::toolchain;
+use crate::core::triage::{self, OverrideRecord};
+use crate::core::validation;
+use crate::core::verification::{self, VerificationStatus};
 use crate::plugins::PluginManager;
+use crate::report::checklist::{Checklist, ItemStatus};
+use crate::report::naming::{self, Deliverable};
+use crate::report::poc::{self, GeneratedPoc, PocOutput, PocPlatform, PocPolicy};
+use crate::report::review::{self, PullRequest, ReviewComment};
+use crate::report::vulnerability::{MinSeverity, Vulnerability};
+use crate::status;
+use crate::utils::artifact;
+use crate::utils::budget;
+use crate::utils::cache;
 use crate::utils::config::Config;
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use crate::utils::error::ToolError;
+use crate::utils::metrics;
+use crate::utils::output;
+use crate::utils::platform;
+use crate::utils::retention;
+use anyhow::{anyhow, Result};
+use clap::{Args, Parser, Subcommand};
 use colored::Colorize;
+use futures::StreamExt;
 use std::path::PathBuf;
 
 /// SecureChain CLI - Universal Web3 Smart Contract Security Auditor
@@ -17,10 +61,34 @@ use std::path::PathBuf;
     long_about = "🚀 QUICK COMMANDS:\n  scan -i contract.sol           # Does everything (recommended)\n  audit -i contract.sol --fuzz    # Full audit with fuzzing\n  analyze -i contract.sol         # Basic static analysis\n\n🎯 Use 'scan' for one-command comprehensive analysis!"
 )]
 pub struct Cli {
+    /// Suppress banner and decorative output; print exactly one JSON document to stdout
+    #[arg(long, visible_alias = "quiet", global = true)]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub budget: BudgetArgs,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+/// Resource budget of the run, over the configured `[budget]`
+#[derive(Args)]
+#[command(next_help_heading = "Resource budget")]
+pub struct BudgetArgs {
+    /// Cap temporary files of the run and its tools, in MB (overrides `budget.max_disk_mb`)
+    #[arg(long, value_name = "MB", global = true)]
+    pub max_disk: Option<u64>,
+
+    /// Cap the memory of each external tool, in MB (overrides `budget.max_memory_mb`)
+    #[arg(long, value_name = "MB", global = true)]
+    pub max_memory: Option<u64>,
+
+    /// Cap the CPU time of each external tool, in seconds (overrides `budget.max_cpu_secs`)
+    #[arg(long, value_name = "SECS", global = true)]
+    pub max_cpu: Option<u64>,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Analyze smart contracts for security vulnerabilities
@@ -41,18 +109,109 @@ pub enum Commands {
         #[arg(long)]
         ai: bool,
 
-        /// Output format (markdown, json, html)
+        /// Output format (markdown, json, html, sarif)
         #[arg(short, long, default_value = "markdown")]
         output: String,
 
         /// Output file path
         #[arg(short = 'f', long)]
         output_file: Option<PathBuf>,
+
+        /// Analyze the input as one EIP-2535 diamond (facets, combined ABI, shared storage)
+        #[arg(long)]
+        diamond: bool,
+
+        /// Write a report formatted for bug bounty submission instead of the audit report
+        #[arg(long)]
+        bounty_scope: bool,
+
+        /// Earlier `--json` results or JSON report to compare against: findings are reported as new, fixed or persistent
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<PathBuf>,
+
+        /// Lowest severity of a new finding that fails the run (defaults to analysis.baseline_fail_on)
+        #[arg(long, requires = "baseline")]
+        fail_on: Option<String>,
+
+        /// Report only findings at or above a severity (critical, high, medium, low, info) or a CVSS score (0-10)
+        #[arg(long, value_name = "SEVERITY")]
+        min_severity: Option<String>,
+
+        /// Analyze only the code reachable from these functions, e.g. Vault.deposit,Vault.withdraw
+        #[arg(long, value_delimiter = ',', value_name = "CONTRACT.FUNCTION")]
+        entrypoints: Vec<String>,
+
+        /// Also run state-dependent checks (actual owner, oracle answers) on an Anvil node
+        #[arg(long)]
+        simulate: bool,
+
+        /// RPC endpoint for the node to fork; a fresh local chain without one
+        #[arg(long, requires = "simulate")]
+        fork_url: Option<String>,
+
+        /// Block to fork at (defaults to the latest)
+        #[arg(long, requires = "fork_url")]
+        fork_block: Option<u64>,
+
+        /// Check contracts at their live address on the fork instead of deploying them, as 0x... or Name=0x...
+        #[arg(long, value_delimiter = ',', requires = "fork_url", value_name = "ADDRESS")]
+        deployed_at: Vec<String>,
+
+        /// Transactions of the forked chain to re-send from their senders before the checks run
+        #[arg(long, value_delimiter = ',', requires = "fork_url", value_name = "HASH")]
+        replay: Vec<String>,
     },
 
     /// Fetch and analyze contracts from blockchain
     Fetch {
-        /// Contract address
+        /// Contract address; several comma-separated addresses fetch in bulk
+        #[arg(short, long, value_delimiter = ',', required_unless_present_any = ["addresses", "manifest"])]
+        address: Vec<String>,
+
+        /// File with one address per line to fetch in bulk (`#` starts a comment)
+        #[arg(long, value_name = "FILE")]
+        addresses: Option<PathBuf>,
+
+        /// Protocol manifest (TOML, or a JSON registry of addresses by network) naming every address to fetch, across networks
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["address", "addresses", "diamond", "rpc_url"])]
+        manifest: Option<PathBuf>,
+
+        /// Skip the addresses the fetch manifest in the output directory already has
+        #[arg(long)]
+        resume: bool,
+
+        /// Network (ethereum, polygon, bsc, arbitrum, optimism, or one under networks.chains)
+        #[arg(short, long, default_value = "ethereum")]
+        network: String,
+
+        /// API key for blockchain explorer
+        #[arg(short = 'k', long)]
+        api_key: Option<String>,
+
+        /// Fetch only from this source (etherscan, sourcify, blockscout) instead of trying the network's sources in turn
+        #[arg(long)]
+        explorer: Option<String>,
+
+        /// Also run analysis after fetching; contracts without verified source are analysed from their bytecode
+        #[arg(long)]
+        analyze: bool,
+
+        /// Directory to write fetched sources and, with --analyze, reports to (default for several addresses: fetched)
+        #[arg(short, long)]
+        output_dir: Option<PathBuf>,
+
+        /// Treat the address as an EIP-2535 diamond: read its facets and cut history and analyze the composed system
+        #[arg(long)]
+        diamond: bool,
+
+        /// JSON-RPC endpoint for on-chain reads: diamond facets and proxy implementation slots (defaults to the network's rpc_url)
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+
+    /// Verify a deployed contract's source on the network's explorer or Sourcify, from the audited source
+    VerifyOnchain {
+        /// Deployed contract address
         #[arg(short, long)]
         address: String,
 
@@ -60,13 +219,49 @@ pub enum Commands {
         #[arg(short, long, default_value = "ethereum")]
         network: String,
 
-        /// API key for blockchain explorer
+        /// Audited contract file or directory (defaults to the input of --results)
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// Analysis results of the audit (`analyze -o json` output), for its compiler metadata
+        #[arg(short, long)]
+        results: Option<PathBuf>,
+
+        /// Contract to verify, when the source defines several
+        #[arg(short, long)]
+        contract: Option<String>,
+
+        /// Exact compiler version the contract was deployed with, e.g. 0.8.24
+        #[arg(long)]
+        compiler: Option<String>,
+
+        /// Optimizer runs, enabling the optimizer
+        #[arg(long)]
+        optimizer_runs: Option<u32>,
+
+        /// EVM version the contract was compiled for
+        #[arg(long)]
+        evm_version: Option<String>,
+
+        /// ABI-encoded constructor arguments, hex
+        #[arg(long)]
+        constructor_args: Option<String>,
+
+        /// Where to submit (etherscan, sourcify)
+        #[arg(long, default_value = "etherscan")]
+        via: String,
+
+        /// API key for the explorer (defaults to ETHERSCAN_API_KEY)
         #[arg(short = 'k', long)]
         api_key: Option<String>,
 
-        /// Also run analysis after fetching
+        /// Also write the solc standard JSON input that is submitted
         #[arg(long)]
-        analyze: bool,
+        save_input: Option<PathBuf>,
+
+        /// Prepare the submission without sending it
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Run comprehensive security audit
@@ -87,9 +282,17 @@ pub enum Commands {
         #[arg(long)]
         fuzz: bool,
 
+        /// Fuzzing backend (echidna, foundry), instead of tools.fuzzer
+        #[arg(long)]
+        fuzzer: Option<String>,
+
         /// Output directory for comprehensive report
         #[arg(short, long, default_value = "audit_results")]
         output_dir: PathBuf,
+
+        /// Show each step's tools, commands and expected duration, and check tools and API keys, without running anything
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Generate PoC exploits for discovered vulnerabilities
@@ -101,6 +304,166 @@ pub enum Commands {
         /// Output directory for exploits
         #[arg(short, long, default_value = "exploits")]
         output_dir: PathBuf,
+
+        /// Comma-separated severities that get a PoC (defaults to reporting.poc_severities)
+        #[arg(long)]
+        severities: Option<String>,
+
+        /// Comma-separated categories that get a PoC, such as Reentrancy,AccessControl (defaults to reporting.poc_categories)
+        #[arg(long)]
+        categories: Option<String>,
+
+        /// Write reproduction steps instead of exploit code
+        #[arg(long)]
+        no_exploit_code: bool,
+    },
+
+    /// Include or exclude single findings from PoC generation during triage, or verify the PoCs
+    #[command(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
+    Poc {
+        /// Analysis results (`analyze --json` output), updated in place
+        #[arg(short, long, required = true)]
+        results: Option<PathBuf>,
+
+        /// Finding id, the start of it, or its number as listed; without one, lists which findings get a PoC
+        #[arg(long)]
+        finding: Option<String>,
+
+        /// Generate a PoC for the finding whatever its severity and category
+        #[arg(long, requires = "finding")]
+        include: bool,
+
+        /// Never generate a PoC for the finding
+        #[arg(long, requires = "finding")]
+        exclude: bool,
+
+        /// Leave the finding to the severity and category policy again
+        #[arg(long, requires = "finding")]
+        reset: bool,
+
+        #[command(subcommand)]
+        action: Option<PocAction>,
+    },
+
+    /// Check claimed fixes by re-running the tools behind each prior finding on the fixed code
+    VerifyFixes {
+        /// Prior analysis results (`analyze --json` output or a JSON report)
+        #[arg(long)]
+        findings: PathBuf,
+
+        /// Path to the fixed contract file or directory
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Output format (markdown, json, html)
+        #[arg(short, long, default_value = "markdown")]
+        output: String,
+
+        /// Output file path
+        #[arg(short = 'f', long)]
+        output_file: Option<PathBuf>,
+
+        /// Golden file of `snapshot record` on the original code, to list behavior the fixes changed
+        #[arg(long)]
+        snapshot: Option<PathBuf>,
+    },
+
+    /// Review each function with AI and leave comments on its lines, optionally as a GitHub pull request review
+    Review {
+        /// Path to contract file or directory
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Post the comments as a review on this pull request (owner/repo#123, needs GITHUB_TOKEN)
+        #[arg(long)]
+        github: Option<String>,
+
+        /// Write the comments to this JSON file
+        #[arg(short = 'f', long)]
+        output_file: Option<PathBuf>,
+    },
+
+    /// Export the audit checklist of prior analysis results, one status per item
+    Checklist {
+        /// Analysis results (`analyze --json` output)
+        #[arg(short, long)]
+        results: PathBuf,
+
+        /// Output format (markdown, csv, json)
+        #[arg(short, long, default_value = "markdown")]
+        output: String,
+
+        /// Output file path
+        #[arg(short = 'f', long)]
+        output_file: Option<PathBuf>,
+
+        /// Standard to check against: scsvs or a TOML/JSON file (defaults to reporting.checklist_standard)
+        #[arg(long)]
+        standard: Option<String>,
+    },
+
+    /// Render a report of prior analysis results
+    Report {
+        /// Analysis results (`analyze --json` output)
+        #[arg(short, long)]
+        results: PathBuf,
+
+        /// Output format (markdown, json, html, sarif)
+        #[arg(short, long, default_value = "markdown")]
+        output: String,
+
+        /// Output file path, written as the report is rendered
+        #[arg(short = 'f', long)]
+        output_file: Option<PathBuf>,
+    },
+
+    /// Change the severity of a finding in analysis results, keeping who changed it and why
+    Override {
+        /// Analysis results (`analyze --json` output), updated in place
+        #[arg(short, long)]
+        results: PathBuf,
+
+        /// Finding id, the start of it, or its number as listed; asked for when left out
+        #[arg(long)]
+        finding: Option<String>,
+
+        /// New severity (critical, high, medium, low, informational); asked for when left out
+        #[arg(short, long)]
+        severity: Option<String>,
+
+        /// Why the tool's severity is wrong; asked for when left out
+        #[arg(short, long)]
+        justification: Option<String>,
+
+        /// Who makes the change (defaults to git's user.name, then $USER)
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Report format of `--output-file` (markdown, json, html, sarif)
+        #[arg(short, long, default_value = "markdown")]
+        output: String,
+
+        /// Also write a report of the updated results to this file
+        #[arg(short = 'f', long)]
+        output_file: Option<PathBuf>,
+    },
+
+    /// Add another audit firm's findings (CSV, JSON or SARIF) to analysis results, skipping those already there
+    Import {
+        /// Findings file exported by the other firm
+        file: PathBuf,
+
+        /// Analysis results (`analyze --json` output) to add the findings to, updated in place
+        #[arg(short, long)]
+        results: PathBuf,
+
+        /// Firm that reported the findings, recorded as their tool
+        #[arg(long)]
+        vendor: String,
+
+        /// Format of the file (csv, json, sarif); told from its name and content when left out
+        #[arg(long)]
+        format: Option<String>,
     },
 
     /// Configure SecureChain settings
@@ -147,203 +510,2254 @@ pub enum Commands {
         #[arg(long)]
         no_fuzz: bool,
 
+        /// Fuzzing backend (echidna, foundry), instead of tools.fuzzer
+        #[arg(long)]
+        fuzzer: Option<String>,
+
         /// Skip AI analysis (faster scan)
         #[arg(long)]
         no_ai: bool,
-    },
-}
 
-/// Execute CLI commands
-pub async fn execute_command(cli: Cli, config: Config) -> Result<()> {
-    match cli.command {
-        Commands::Analyze { input, target, depth, ai, output, output_file } => {
-            handle_analyze(input, target, depth, ai, output, output_file, config).await
-        }
-        Commands::Fetch { address, network, api_key, analyze } => {
-            handle_fetch(address, network, api_key, analyze, config).await
-        }
-        Commands::Audit { input, target, ai, fuzz, output_dir } => {
-            handle_audit(input, target, ai, fuzz, output_dir, config).await
-        }
-        Commands::Exploit { results, output_dir } => {
-            handle_exploit(results, output_dir, config).await
-        }
-        Commands::Config { key, value, list } => {
-            handle_config(key, value, list, config).await
-        }
-        Commands::Update { all, db, ai } => {
-            handle_update(all, db, ai, config).await
-        }
-        Commands::Scan { input, target, no_fuzz, no_ai } => {
-            handle_scan(input, target, !no_fuzz, !no_ai, config).await
-        }
-    }
-}
-
-/// Handle analyze command
-async fn handle_analyze(
-    input: PathBuf,
-    target: String,
-    depth: String,
-    ai: bool,
-    output: String,
-    output_file: Option<PathBuf>,
-    config: Config,
-) -> Result<()> {
-    println!("🔍 {} Smart Contract Analysis", "Starting".bright_green());
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        /// Compile and run each generated Solidity PoC with forge, so reports only call confirmed findings verified
+        #[arg(long)]
+        verify_pocs: bool,
 
-    // Initialize components
-    let plugin_manager = PluginManager::new();
-    let analysis_engine = AnalysisEngine::new(config.clone(), plugin_manager);
+        /// RPC URL to run the PoCs against a fork of, such as an Anvil node
+        #[arg(long, requires = "verify_pocs")]
+        fork_url: Option<String>,
+    },
 
-    // Perform analysis
-    let results = analysis_engine
-        .analyze_contracts(&input, &target, &depth, ai)
-        .await?;
+    /// Write the built-in detectors' findings as file/line/severity/message records for editors without LSP
+    Annotate {
+        /// Contract file or directory to annotate; paths in the file are relative to it
+        #[arg(short, long, default_value = ".")]
+        input: PathBuf,
 
-    // Generate report
-    let report_generator = crate::report::generator::ReportGenerator::new(config);
-    let report = report_generator.generate_report(&results, &output)?;
+        /// Annotations file to write
+        #[arg(long, default_value = annotate::DEFAULT_OUTPUT)]
+        out: PathBuf,
 
-    // Output results
-    if let Some(output_path) = output_file {
-        std::fs::write(&output_path, &report)?;
-        println!("📄 Report saved to: {}", output_path.display());
-    } else {
-        println!("{}", report);
-    }
+        /// Keep running and rewrite the file whenever a contract changes
+        #[arg(long)]
+        watch: bool,
 
-    println!("✅ Analysis completed successfully!");
-    Ok(())
-}
+        /// How often `--watch` looks for changed files, in milliseconds
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+    },
 
-/// Handle fetch command
-async fn handle_fetch(
-    address: String,
-    network: String,
-    api_key: Option<String>,
-    analyze: bool,
-    config: Config,
-) -> Result<()> {
-    println!("🔗 {} Contract from {}", "Fetching".bright_green(), network);
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    /// Print the inter-contract call graph or the import graph of a contract directory
+    Graph {
+        /// Contract file or directory
+        #[arg(short, long)]
+        input: PathBuf,
 
-    let fetcher = ContractFetcher::new(config.clone());
-    let contracts = fetcher.fetch_contracts(&network, &address, api_key.as_deref()).await?;
+        /// Graph to draw (calls, imports)
+        #[arg(short, long, default_value = "calls")]
+        kind: String,
 
-    println!("✅ Successfully fetched {} contracts", contracts.len());
+        /// Output format (dot, mermaid, json)
+        #[arg(short, long, default_value = "dot")]
+        format: String,
 
-    if analyze {
-        println!("\n🔍 {} Analysis", "Starting".bright_green());
-        let plugin_manager = PluginManager::new();
-        let analysis_engine = AnalysisEngine::new(config, plugin_manager);
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 
-        for contract in contracts {
-            let temp_path = std::env::temp_dir().join(format!("{}.sol", contract.name));
-            std::fs::write(&temp_path, &contract.source_code)?;
+    /// Manage the git pre-commit hook
+    Hook {
+        #[command(subcommand)]
+        action: HookAction,
+    },
 
-            let results = analysis_engine
-                .analyze_contracts(&temp_path, "evm", "standard", false)
-                .await?;
+    /// Check pull requests in CI, reporting findings on the changed files where the author sees them
+    Ci {
+        #[command(subcommand)]
+        action: CiAction,
+    },
 
-            println!("📊 Contract: {} - {} vulnerabilities found", 
-                     contract.name, results.vulnerabilities.len());
-        }
-    }
+    /// Keep a warm analysis engine running that analyze, audit and scan delegate to
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonAction,
+    },
 
-    Ok(())
-}
+    /// Serve analyses over an HTTP API: POST /analyze, POST /probe, GET /results/{id}
+    Serve {
+        /// Port to listen on
+        #[arg(short, long, default_value = "8080")]
+        port: u16,
 
-/// Handle audit command
-async fn handle_audit(
-    input: PathBuf,
-    target: String,
-    ai: bool,
-    fuzz: bool,
-    output_dir: PathBuf,
-    config: Config,
-) -> Result<()> {
-    println!("🛡️  {} Comprehensive Security Audit", "Starting".bright_green());
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        /// Address to listen on; anything but loopback exposes the API to the network
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+    },
 
-    // Step 1: Static Analysis
-    println!("\n{} Step 1: Static Analysis", "🔍".bright_green());
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    /// Show the local usage summary (enable with `config --key general.metrics_enabled --value true`)
+    Stats {
+        /// Delete the recorded metrics and start over
+        #[arg(long)]
+        reset: bool,
+    },
 
-    let plugin_manager = PluginManager::new();
-    let analysis_engine = AnalysisEngine::new(config.clone(), plugin_manager);
+    /// Show what was sent to AI providers, from the upload audit log
+    AiLog {
+        /// Only uploads on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
 
-    let analysis_results = analysis_engine
-        .analyze_contracts(&input, &target, "deep", ai)
-        .await?;
+        /// Only uploads to this provider (local, openai, anthropic, azure, gemini, openrouter)
+        #[arg(long)]
+        provider: Option<String>,
+    },
 
-    println!("✅ Found {} vulnerabilities", analysis_results.vulnerabilities.len());
+    /// List and describe the built-in detectors, by the ids configuration and suppression rules use
+    Detectors {
+        #[command(subcommand)]
+        action: DetectorsAction,
+    },
 
-    // Step 2: Fuzzing Analysis
+    /// Analyze a bundled corpus of known-vulnerable contracts and report the detection rate per category
+    Selftest {
+        /// Analysis depth (quick, standard, deep)
+        #[arg(short, long, default_value = "standard")]
+        depth: String,
+
+        /// Fail when fewer than this percentage of the corpus cases are detected
+        #[arg(long)]
+        min_rate: Option<f64>,
+    },
+
+    /// Record a contract's observable behavior to a golden file, or check the code against one
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+
+    /// Share finding patterns as community rule packs, and manage the installed packs
+    Rules {
+        #[command(subcommand)]
+        action: RulesAction,
+    },
+
+    /// Install pinned versions of the analysis tools in a private prefix, used before PATH
+    Install {
+        /// Tool to install (slither, mythril, echidna, solc, foundry); all of them when left out
+        #[arg(long)]
+        tool: Option<String>,
+
+        /// Version to install instead of the pinned one
+        #[arg(long, requires = "tool")]
+        version: Option<String>,
+
+        /// SHA-256 the release download must have, for releases without a listed checksum
+        #[arg(long, requires = "tool")]
+        sha256: Option<String>,
+
+        /// Install an unpinned version, or a release without a known checksum, without verifying it
+        #[arg(long)]
+        allow_unverified: bool,
+
+        #[command(subcommand)]
+        action: Option<InstallAction>,
+    },
+
+    /// Manage the on-disk analysis cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Remove cached analyses, run history and leftover temporary files past the retention policy
+    Purge {
+        /// List what would be removed without removing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RulesAction {
+    /// Export anonymized patterns of findings to a shareable pack
+    Export {
+        /// Path to analysis results file
+        #[arg(short, long)]
+        results: PathBuf,
+
+        /// Findings to export, by number as listed or id (all when left out)
+        #[arg(long)]
+        finding: Vec<String>,
+
+        /// What the findings turned out to be (confirmed, false-positive)
+        #[arg(short, long, default_value = "confirmed")]
+        disposition: String,
+
+        /// Pack name
+        #[arg(short, long, default_value = "shared")]
+        name: String,
+
+        /// Pack file to write, or to add the rules to when it exists
+        #[arg(short = 'f', long, default_value = "community-rules.json")]
+        file: PathBuf,
+    },
+
+    /// Install a rule pack, matched on every EVM analysis from then on
+    Import {
+        /// Pack file
+        file: PathBuf,
+    },
+
+    /// List the installed rule packs
+    List,
+
+    /// Uninstall a rule pack
+    Remove {
+        /// Pack name, as `rules list` shows it
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum InstallAction {
+    /// List the installed tool versions
+    List,
+
+    /// Remove installed versions of a tool
+    Uninstall {
+        /// Tool to uninstall
+        #[arg(long)]
+        tool: String,
+
+        /// Version to remove (all versions when left out)
+        #[arg(long)]
+        version: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PocAction {
+    /// Compile each Solidity PoC with forge and run it, recording whether it exploited the finding
+    Verify {
+        /// Analysis results (`analyze --json` output), updated in place
+        #[arg(short, long)]
+        results: PathBuf,
+
+        /// Finding id, the start of it, or its number as listed; without one, every finding that gets a PoC
+        #[arg(long)]
+        finding: Option<String>,
+
+        /// RPC URL to fork, such as an Anvil node or a mainnet endpoint; without one, PoCs deploy locally
+        #[arg(long)]
+        fork_url: Option<String>,
+
+        /// Directory of PoCs written by `scan`, verified as edited instead of generated afresh
+        #[arg(long)]
+        pocs: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CacheAction {
+    /// Remove every cached contract analysis
+    Clear,
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotAction {
+    /// Record selectors, events, storage layout and, with --address, view outputs
+    Record {
+        /// Path to contract file or directory
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Golden file to write
+        #[arg(short = 'f', long, default_value = snapshot::DEFAULT_FILE)]
+        file: PathBuf,
+
+        /// Deployment to call the view functions of, with canonical inputs
+        #[arg(short, long)]
+        address: Option<String>,
+
+        /// Contract deployed at --address (needed when the input has several)
+        #[arg(short, long)]
+        contract: Option<String>,
+
+        /// Network of the deployment
+        #[arg(short, long, default_value = "ethereum")]
+        network: String,
+
+        /// JSON-RPC endpoint for the calls (defaults to the network's rpc_url)
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+
+    /// List the differences from a golden file; fails on removed or changed behavior
+    Check {
+        /// Path to contract file or directory
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Golden file to compare with
+        #[arg(short = 'f', long, default_value = snapshot::DEFAULT_FILE)]
+        file: PathBuf,
+
+        /// Deployment to call the view functions of, with canonical inputs
+        #[arg(short, long)]
+        address: Option<String>,
+
+        /// Contract deployed at --address (needed when the input has several)
+        #[arg(short, long)]
+        contract: Option<String>,
+
+        /// Network of the deployment
+        #[arg(short, long, default_value = "ethereum")]
+        network: String,
+
+        /// JSON-RPC endpoint for the calls (defaults to the network's rpc_url)
+        #[arg(long)]
+        rpc_url: Option<String>,
+
+        /// Also fail on added functions, events, storage and contracts
+        #[arg(long)]
+        strict: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum HookAction {
+    /// Install a pre-commit hook that scans staged contracts
+    Install {
+        /// Lowest severity that blocks a commit (defaults to analysis.hook_fail_on)
+        #[arg(long)]
+        fail_on: Option<String>,
+
+        /// Overwrite an existing pre-commit hook
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Remove the SecureChain pre-commit hook
+    Uninstall,
+
+    /// Scan staged changes (invoked by the installed hook)
+    Run {
+        /// Lowest severity that blocks a commit (defaults to analysis.hook_fail_on)
+        #[arg(long)]
+        fail_on: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CiAction {
+    /// Analyse the contract files a pull request changes and post the findings as GitHub check annotations
+    Github {
+        /// Repository root
+        #[arg(short, long, default_value = ".")]
+        input: PathBuf,
+
+        /// Base of the diff (defaults to origin/$GITHUB_BASE_REF in pull request workflows)
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Head of the diff
+        #[arg(long, default_value = "HEAD")]
+        head: String,
+
+        /// Analysis depth (quick, standard, deep)
+        #[arg(short, long, default_value = "standard")]
+        depth: String,
+
+        /// Lowest severity that fails the check (defaults to analysis.ci_fail_on)
+        #[arg(long)]
+        fail_on: Option<String>,
+
+        /// Repository as owner/repo (defaults to $GITHUB_REPOSITORY)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Commit to attach the check run to (defaults to the pull request head)
+        #[arg(long)]
+        sha: Option<String>,
+
+        /// Pull request number for --review (defaults to the workflow event's)
+        #[arg(long)]
+        pr: Option<u64>,
+
+        /// Also post the findings as a review on the pull request
+        #[arg(long)]
+        review: bool,
+
+        /// Analyse and print the findings without posting anything to GitHub
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DaemonAction {
+    /// Run the daemon in the foreground until stopped
+    Start,
+
+    /// Stop the running daemon
+    Stop,
+
+    /// Show whether a daemon is running and what it has cached
+    Status,
+}
+
+#[derive(Subcommand)]
+pub enum DetectorsAction {
+    /// List the detectors of a platform and whether each is enabled
+    List {
+        /// Target platform (evm, solana, move, cairo, ink)
+        #[arg(short, long, default_value = "evm")]
+        target: String,
+    },
+
+    /// Show what a detector looks for, with an example
+    Describe {
+        /// Detector id, as `detectors list` shows it
+        id: String,
+    },
+}
+
+impl Cli {
+    /// Commands that must stay fast and quiet skip the banner and tool checks
+    pub fn is_lightweight(&self) -> bool {
+        matches!(
+            self.command,
+            Commands::Hook { .. } | Commands::Daemon { .. } | Commands::Serve { .. } | Commands::Stats { .. }
+                | Commands::AiLog { .. }
+                | Commands::Annotate { .. }
+                | Commands::Graph { .. }
+                | Commands::Checklist { .. }
+                | Commands::Report { .. }
+                | Commands::Override { .. }
+                | Commands::Import { .. }
+                | Commands::Poc { .. }
+                | Commands::Snapshot { .. }
+                | Commands::Rules { .. }
+                | Commands::Install { .. }
+                | Commands::Cache { .. }
+                | Commands::Purge { .. }
+                | Commands::Detectors { .. }
+        )
+    }
+
+    /// Subcommand name, as usage metrics record it
+    pub fn command_name(&self) -> &'static str {
+        match self.command {
+            Commands::Analyze { .. } => "analyze",
+            Commands::Fetch { .. } => "fetch",
+            Commands::VerifyOnchain { .. } => "verify-onchain",
+            Commands::Audit { .. } => "audit",
+            Commands::Exploit { .. } => "exploit",
+            Commands::VerifyFixes { .. } => "verify-fixes",
+            Commands::Review { .. } => "review",
+            Commands::Checklist { .. } => "checklist",
+            Commands::Report { .. } => "report",
+            Commands::Override { .. } => "override",
+            Commands::Import { .. } => "import",
+            Commands::Poc { .. } => "poc",
+            Commands::Config { .. } => "config",
+            Commands::Update { .. } => "update",
+            Commands::Scan { .. } => "scan",
+            Commands::Annotate { .. } => "annotate",
+            Commands::Graph { .. } => "graph",
+            Commands::Hook { .. } => "hook",
+            Commands::Ci { .. } => "ci",
+            Commands::Daemon { .. } => "daemon",
+            Commands::Serve { .. } => "serve",
+            Commands::Stats { .. } => "stats",
+            Commands::AiLog { .. } => "ai-log",
+            Commands::Selftest { .. } => "selftest",
+            Commands::Detectors { .. } => "detectors",
+            Commands::Snapshot { .. } => "snapshot",
+            Commands::Rules { .. } => "rules",
+            Commands::Install { .. } => "install",
+            Commands::Cache { .. } => "cache",
+            Commands::Purge { .. } => "purge",
+        }
+    }
+}
+
+/// Execute CLI commands
+pub async fn execute_command(cli: Cli, config: Config) -> Result<()> {
+    match cli.command {
+        Commands::Analyze {
+            input,
+            target,
+            depth,
+            ai,
+            output,
+            output_file,
+            diamond,
+            bounty_scope,
+            baseline,
+            fail_on,
+            min_severity,
+            entrypoints,
+            simulate,
+            fork_url,
+            fork_block,
+            deployed_at,
+            replay,
+        } => {
+            let gate = baseline.map(|file| (file, fail_on.unwrap_or_else(|| config.analysis.baseline_fail_on.clone())));
+            let simulation = match simulate {
+                true => Some(SimulationSettings {
+                    fork_url,
+                    fork_block,
+                    deployed_at: SimulationSettings::parse_deployed_at(&deployed_at)?,
+                    replay,
+                }),
+                false => None,
+            };
+            handle_analyze(input, target, depth, ai, output, output_file, diamond, bounty_scope, gate, min_severity, entrypoints, simulation, config)
+                .await
+        }
+        Commands::Fetch { mut address, addresses, manifest, resume, network, api_key, explorer, analyze, output_dir, diamond, rpc_url } => {
+            if let Some(file) = addresses {
+                address.extend(read_address_list(&file)?);
+            }
+            if let Some(manifest) = manifest {
+                return handle_fetch_protocol(manifest, api_key, explorer, analyze, output_dir, resume, config).await;
+            }
+            let mut config = config;
+            if let Some(explorer) = &explorer {
+                restrict_fetch_sources(&mut config, &network, explorer)?;
+            }
+            if address.len() > 1 || resume {
+                if diamond {
+                    return Err(anyhow!("--diamond fetches a single address"));
+                }
+                return handle_fetch_bulk(address, network, api_key, analyze, output_dir, resume, rpc_url, config).await;
+            }
+            let address = address.pop().ok_or_else(|| anyhow!("No address to fetch"))?;
+            if diamond {
+                handle_fetch_diamond(address, network, api_key, output_dir, rpc_url, config).await
+            } else {
+                handle_fetch(address, network, api_key, analyze, output_dir, rpc_url, config).await
+            }
+        }
+        Commands::VerifyOnchain {
+            address,
+            network,
+            input,
+            results,
+            contract,
+            compiler,
+            optimizer_runs,
+            evm_version,
+            constructor_args,
+            via,
+            api_key,
+            save_input,
+            dry_run,
+        } => {
+            let overrides = verification::Overrides { contract, compiler, optimizer_runs, evm_version, constructor_args };
+            handle_verify_onchain(address, network, input, results, overrides, via, api_key, save_input, dry_run, config).await
+        }
+        Commands::Audit { input, target, ai, fuzz, fuzzer, output_dir, dry_run } => {
+            handle_audit(input, target, ai, fuzz, output_dir, dry_run, with_fuzzer(config, fuzzer)?).await
+        }
+        Commands::Exploit { results, output_dir, severities, categories, no_exploit_code } => {
+            let mut config = config;
+            if let Some(severities) = severities {
+                config.set_value("reporting.poc_severities", &severities)?;
+            }
+            if let Some(categories) = categories {
+                config.set_value("reporting.poc_categories", &categories)?;
+            }
+            if no_exploit_code {
+                config.reporting.no_exploit_code = true;
+            }
+            handle_exploit(results, output_dir, config).await
+        }
+        Commands::VerifyFixes { findings, input, output, output_file, snapshot } => {
+            handle_verify_fixes(findings, input, output, output_file, snapshot, config).await
+        }
+        Commands::Review { input, github, output_file } => {
+            handle_review(input, github, output_file, config).await
+        }
+        Commands::Checklist { results, output, output_file, standard } => {
+            handle_checklist(results, output, output_file, standard, config).await
+        }
+        Commands::Report { results, output, output_file } => {
+            handle_report(results, output, output_file, config).await
+        }
+        Commands::Override { results, finding, severity, justification, author, output, output_file } => {
+            let change = triage::Change { finding, severity, justification, author };
+            handle_override(results, change, output, output_file, config).await
+        }
+        Commands::Import { file, results, vendor, format } => handle_import(file, results, vendor, format, config).await,
+        Commands::Poc { action: Some(PocAction::Verify { results, finding, fork_url, pocs }), .. } => {
+            handle_poc_verify(results, finding, fork_url, pocs, config).await
+        }
+        Commands::Poc { results, finding, include, exclude, reset, action: None } => {
+            let results = results.ok_or_else(|| anyhow!("--results is required"))?;
+            let flag = match (include, exclude, reset) {
+                (true, false, false) => Some(Some(true)),
+                (false, true, false) => Some(Some(false)),
+                (false, false, true) => Some(None),
+                (false, false, false) if finding.is_none() => None,
+                (false, false, false) => return Err(anyhow!("Give --include, --exclude or --reset for the finding")),
+                _ => return Err(anyhow!("--include, --exclude and --reset are exclusive")),
+            };
+            handle_poc(results, finding.zip(flag), config).await
+        }
+        Commands::Config { key, value, list } => {
+            handle_config(key, value, list, config).await
+        }
+        Commands::Update { all, db, ai } => {
+            handle_update(all, db, ai, config).await
+        }
+        Commands::Scan { input, target, no_fuzz, fuzzer, no_ai, verify_pocs, fork_url } => {
+            handle_scan(input, target, !no_fuzz, !no_ai, verify_pocs, fork_url, with_fuzzer(config, fuzzer)?).await
+        }
+        Commands::Annotate { input, out, watch, interval } => handle_annotate(input, out, watch, interval, config).await,
+        Commands::Graph { input, kind, format, output } => handle_graph(input, kind, format, output, config).await,
+        Commands::Hook { action } => {
+            handle_hook(action, config).await
+        }
+        Commands::Ci { action } => {
+            handle_ci(action, config).await
+        }
+        Commands::Daemon { action } => {
+            handle_daemon(action, config).await
+        }
+        Commands::Serve { port, host } => {
+            server::serve(config, &host, port).await
+        }
+        Commands::Stats { reset } => {
+            handle_stats(reset, config).await
+        }
+        Commands::AiLog { since, provider } => {
+            handle_ai_log(since, provider, config).await
+        }
+        Commands::Selftest { depth, min_rate } => {
+            handle_selftest(depth, min_rate, config).await
+        }
+        Commands::Detectors { action } => {
+            handle_detectors(action, config).await
+        }
+        Commands::Snapshot { action } => {
+            handle_snapshot(action, config).await
+        }
+        Commands::Rules { action } => {
+            handle_rules(action).await
+        }
+        Commands::Install { tool, version, sha256, allow_unverified, action } => {
+            let verification = toolchain::Verification { sha256: sha256.as_deref(), allow_unverified };
+            handle_install(tool, version, verification, action).await
+        }
+        Commands::Cache { action } => {
+            handle_cache(action, config).await
+        }
+        Commands::Purge { dry_run } => {
+            handle_purge(dry_run, config).await
+        }
+    }
+}
+
+/// Handle analyze command
+#[allow(clippy::too_many_arguments)]
+async fn handle_analyze(
+    input: PathBuf,
+    target: String,
+    depth: String,
+    ai: bool,
+    output: String,
+    output_file: Option<PathBuf>,
+    diamond: bool,
+    bounty_scope: bool,
+    baseline: Option<(PathBuf, String)>,
+    min_severity: Option<String>,
+    entrypoints: Vec<String>,
+    simulation: Option<SimulationSettings>,
+    config: Config,
+) -> Result<()> {
+    // A bad threshold should fail before the analysis, not after it
+    let threshold = baseline.as_ref().map(|(_, fail_on)| hook::parse_threshold(fail_on)).transpose()?;
+    let min_severity = min_severity
+        .map(|min| min.parse::<MinSeverity>().map_err(|e| anyhow!("Invalid --min-severity: {}", e)))
+        .transpose()?;
+    let exit_code = config.analysis.baseline_exit_code;
+
+    status!("🔍 {} Smart Contract Analysis", "Starting".bright_green());
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    // Initialize components
+    let plugin_manager = PluginManager::new();
+    let mut analysis_engine = AnalysisEngine::new(config.clone(), plugin_manager).with_entrypoints(entrypoints);
+    if let Some(settings) = simulation {
+        analysis_engine = analysis_engine.with_simulation(settings);
+    }
+
+    // Perform analysis
+    let mut results = run_analysis(&analysis_engine, &input, &target, &depth, ai).await?;
+    if diamond {
+        analysis_engine.apply_diamond_analysis(&mut results, &input, None).await?;
+    }
+    if let Some((baseline_path, _)) = &baseline {
+        analysis_engine.apply_baseline(&mut results, baseline_path)?;
+    }
+    // Filtered after the baseline comparison, so hidden findings are not reported as fixed
+    if let Some(min_severity) = &min_severity {
+        let before = results.vulnerabilities.len();
+        results.vulnerabilities.retain(|vuln| min_severity.admits(vuln));
+        analysis_engine.rescore(&mut results);
+        status!("🔽 {} finding(s) below --min-severity left out", before - results.vulnerabilities.len());
+    }
+
+    // Generate report
+    let report_generator = crate::report::generator::ReportGenerator::new(config);
+    if let Some(output_path) = output_file {
+        if bounty_scope {
+            artifact::write_atomic(&output_path, report_generator.generate_bounty_report(&results)?)?;
+        } else {
+            report_generator.write_report(&results, &output, &output_path)?;
+        }
+        status!("📄 Report saved to: {}", output_path.display());
+    } else if !output::is_json_mode() {
+        let report = if bounty_scope {
+            report_generator.generate_bounty_report(&results)?
+        } else {
+            report_generator.generate_report(&results, &output)?
+        };
+        println!("{}", report);
+    }
+
+    if output::is_json_mode() {
+        output::emit_json(&results)?;
+    }
+
+    // The CI gate: new findings at or above the threshold fail the run
+    if let (Some(comparison), Some(threshold)) = (&results.baseline, threshold) {
+        let blocking = comparison.blocking(&results.vulnerabilities, &threshold);
+        if !blocking.is_empty() {
+            for vuln in &blocking {
+                let location = match vuln.line_number {
+                    Some(line) => format!("{}:{}", vuln.file_path, line),
+                    None => vuln.file_path.clone(),
+                };
+                status!("  🆕 {} [{}] {}", location, vuln.severity, vuln.title);
+            }
+            status!("❌ {} new finding(s) at or above {} since the baseline", blocking.len(), threshold);
+            if exit_code != 0 {
+                std::process::exit(exit_code);
+            }
+        }
+    }
+
+    status!("✅ Analysis completed successfully!");
+    Ok(())
+}
+
+/// Handle fetch command
+async fn handle_fetch(
+    address: String,
+    network: String,
+    api_key: Option<String>,
+    analyze: bool,
+    output_dir: Option<PathBuf>,
+    rpc_url: Option<String>,
+    config: Config,
+) -> Result<()> {
+    status!("🔗 {} Contract from {}", "Fetching".bright_green(), network);
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    let fetcher = ContractFetcher::new(config.clone()).with_rpc_url(rpc_url.clone());
+    let contracts = fetcher.fetch_contracts(&network, &address, api_key.as_deref()).await?;
+
+    status!("✅ Successfully fetched {} contracts", contracts.len());
+    if contracts.is_empty() && !analyze {
+        status!("⚠️  {} has no verified source; pass --analyze to analyse its bytecode instead", address);
+    }
+
+    // Show the constructor arguments the contracts were deployed with
+    for contract in &contracts {
+        match crate::core::deployment::analyze_deployment(contract) {
+            Ok(Some(deployment)) => {
+                status!("\n🏗️  Deployment parameters for {}:", contract.name);
+                for param in &deployment.parameters {
+                    status!("   {} {} = {}", param.type_name, param.name, param.value);
+                }
+                for finding in &deployment.findings {
+                    status!("   ⚠️  [{}] {}", finding.severity, finding.title);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Could not decode constructor arguments of {}: {}", contract.name, e),
+        }
+    }
+
+    // Sources are kept in the output directory, in their verified layout for multi-file contracts
+    if let Some(dir) = &output_dir {
+        let files = fetcher::persist_sources(dir, &address, &contracts)?;
+        status!("📁 {} source files saved to {}", files.len(), fetcher::source_dir(dir, &address).display());
+    }
+
+    let mut analyses = Vec::new();
+    if analyze {
+        status!("\n🔍 {} Analysis", "Starting".bright_green());
+        let plugin_manager = PluginManager::new();
+        let analysis_engine = AnalysisEngine::new(config.clone(), plugin_manager);
+        let report_generator = crate::report::generator::ReportGenerator::new(config.clone());
+
+        let results = if contracts.is_empty() {
+            status!("⚠️  {} has no verified source; analysing its bytecode", address);
+            analyze_unverified(&analysis_engine, &address, &network, rpc_url.as_deref(), &config)?
+        } else {
+            let scratch = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).tempdir()?;
+            let root = match &output_dir {
+                Some(dir) => dir.clone(),
+                None => {
+                    fetcher::persist_sources(scratch.path(), &address, &contracts)?;
+                    scratch.path().to_path_buf()
+                }
+            };
+
+            let mut results = analysis_engine
+                .analyze_contracts(&fetcher::source_dir(&root, &address), "evm", "standard", false)
+                .await?;
+            for contract in &contracts {
+                if let Err(e) = analysis_engine.apply_deployment_analysis(&mut results, contract) {
+                    log::warn!("Deployment parameter analysis failed for {}: {}", contract.name, e);
+                }
+            }
+            results
+        };
+
+        let name = contracts.first().map_or(address.as_str(), |contract| contract.name.as_str());
+        status!("📊 Contract: {} - {} vulnerabilities found", name, results.vulnerabilities.len());
+
+        if let Some(dir) = &output_dir {
+            let report_path = dir.join(format!("{}_report.md", name));
+            report_generator.write_report(&results, "markdown", &report_path)?;
+            status!("📄 Report saved to: {}", report_path.display());
+        }
+        analyses.push(results);
+    }
+
+    if output::is_json_mode() {
+        output::emit_json(&serde_json::json!({
+            "contracts": contracts,
+            "results": analyses,
+        }))?;
+    }
+
+    Ok(())
+}
+
+/// Analyse `address`, which has no verified source, from the runtime bytecode on `network`
+fn analyze_unverified(
+    analysis_engine: &AnalysisEngine,
+    address: &str,
+    network: &str,
+    rpc_url: Option<&str>,
+    config: &Config,
+) -> Result<AnalysisResults> {
+    let network_config = config
+        .networks
+        .evm_network(network)
+        .ok_or_else(|| anyhow!("Bytecode analysis needs an EVM network, not {}", network))?;
+    let rpc = RpcClient::new(rpc_url.unwrap_or(&network_config.rpc_url), network_config.timeout);
+    let mut analysis = BytecodeAnalysis::fetch(&rpc, address)?;
+    let mut signatures = SignatureDb::load(config);
+    analysis.resolve(&mut signatures);
+    if let Err(e) = signatures.save() {
+        log::warn!("Could not cache looked up signatures: {}", e);
+    }
+    analysis_engine.analyze_bytecode(analysis)
+}
+
+/// Handle fetch command for several addresses, keeping each source as it arrives
+#[allow(clippy::too_many_arguments)]
+async fn handle_fetch_bulk(
+    addresses: Vec<String>,
+    network: String,
+    api_key: Option<String>,
+    analyze: bool,
+    output_dir: Option<PathBuf>,
+    resume: bool,
+    rpc_url: Option<String>,
+    config: Config,
+) -> Result<()> {
+    let dir = output_dir.unwrap_or_else(|| PathBuf::from("fetched"));
+    std::fs::create_dir_all(&dir)?;
+    let BulkFetch { manifest, fetched, unverified, left } =
+        fetch_into(&dir, &network, addresses, api_key.as_deref(), resume, rpc_url.as_deref(), &config).await?;
+
+    let mut analyses = Vec::new();
+    if analyze && !(fetched.is_empty() && unverified.is_empty()) {
+        status!("\n🔍 {} Analysis", "Starting".bright_green());
+        let analysis_engine = AnalysisEngine::new(config.clone(), PluginManager::new());
+        let report_generator = crate::report::generator::ReportGenerator::new(config.clone());
+        for address in &fetched {
+            let address_dir = fetcher::source_dir(&dir, address);
+            let results = analysis_engine.analyze_contracts(&address_dir, "evm", "standard", false).await?;
+            status!("📊 {} - {} vulnerabilities found", address, results.vulnerabilities.len());
+            let report_path = address_dir.join("report.md");
+            report_generator.write_report(&results, "markdown", &report_path)?;
+            analyses.push(results);
+        }
+        analyses.extend(analyze_unverified_into(&analysis_engine, &report_generator, &dir, &unverified, &network, rpc_url.as_deref(), &config)?);
+    }
+
+    let count = |status: FetchStatus| manifest.items.iter().filter(|item| item.status == status).count();
+    let failed: Vec<&FetchItem> = manifest.items.iter().filter(|item| item.status == FetchStatus::Failed).collect();
+    status!("\n📋 {} Summary", "Fetch".bright_green());
+    status!("   ✅ Fetched: {}", count(FetchStatus::Fetched));
+    status!("   ⚠️  Unverified: {}", count(FetchStatus::Unverified));
+    status!("   ❌ Failed: {}", failed.len());
+    for item in &failed {
+        status!("      {}: {}", item.address, item.error.as_deref().unwrap_or("unknown error"));
+    }
+    status!("📁 Sources and manifest in {}", dir.display());
+
+    if output::is_json_mode() {
+        output::emit_json(&serde_json::json!({
+            "manifest": manifest,
+            "interrupted": left.is_some(),
+            "results": analyses,
+        }))?;
+    }
+
+    if let Some(left) = left {
+        return Err(anyhow!("Fetch interrupted with {} addresses left; rerun with --resume to continue", left));
+    }
+    if !failed.is_empty() {
+        return Err(anyhow!("{} addresses failed to fetch; rerun with --resume to retry them", failed.len()));
+    }
+    Ok(())
+}
+
+/// What fetching a list of addresses into a directory came to
+struct BulkFetch {
+    manifest: FetchManifest,
+    /// Addresses fetched by this run with their sources, and without verified source
+    fetched: Vec<String>,
+    unverified: Vec<String>,
+    /// Addresses not reached before Ctrl-C stopped the fetch
+    left: Option<usize>,
+}
+
+/// Fetch `addresses` from `network` into `dir`, saving each and its fetch manifest entry as it arrives
+async fn fetch_into(
+    dir: &std::path::Path,
+    network: &str,
+    addresses: Vec<String>,
+    api_key: Option<&str>,
+    resume: bool,
+    rpc_url: Option<&str>,
+    config: &Config,
+) -> Result<BulkFetch> {
+    std::fs::create_dir_all(dir)?;
+    let mut manifest = match FetchManifest::load(dir)? {
+        Some(manifest) if resume => manifest,
+        Some(_) => {
+            status!("⚠️  Starting over; pass --resume to skip the addresses {} already has", dir.join(fetcher::MANIFEST_FILE).display());
+            FetchManifest::new(network)
+        }
+        None => FetchManifest::new(network),
+    };
+    if manifest.network != network {
+        return Err(anyhow!("{} was fetched from {}, not {}", dir.display(), manifest.network, network));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    let pending: Vec<String> = addresses
+        .into_iter()
+        .filter(|address| seen.insert(address.to_lowercase()))
+        .filter(|address| !manifest.is_done(address))
+        .collect();
+    let skipped = seen.len() - pending.len();
+
+    status!("🔗 {} {} contracts from {}", "Fetching".bright_green(), pending.len(), network);
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    if skipped > 0 {
+        status!("⏭️  Skipping {} addresses already in the manifest", skipped);
+    }
+
+    let rate_limit = config.networks.evm_network(network).map_or(5, |network| network.rate_limit);
+    let fetcher = ContractFetcher::new(config.clone()).with_rpc_url(rpc_url.map(str::to_string));
+    let total = pending.len();
+    let stream = fetcher.fetch_each(network, pending, api_key, rate_limit);
+    futures::pin_mut!(stream);
+
+    // Each address is saved before the next starts, so Ctrl-C only loses the one in flight
+    let mut fetched = Vec::new();
+    let mut unverified = Vec::new();
+    let mut processed = 0;
+    let mut interrupted = false;
+    loop {
+        let Fetched { address, result } = tokio::select! {
+            next = stream.next() => match next {
+                Some(next) => next,
+                None => break,
+            },
+            _ = tokio::signal::ctrl_c() => {
+                interrupted = true;
+                break;
+            }
+        };
+
+        let item = |status, files, contracts, error: Option<String>, failure| FetchItem {
+            address: address.clone(),
+            status,
+            files,
+            contracts,
+            error,
+            failure,
+            fetched_at: chrono::Utc::now(),
+        };
+        let item = match result {
+            Ok(contracts) if contracts.is_empty() => {
+                status!("   ⚠️  {}: no verified source", address);
+                unverified.push(address.clone());
+                item(FetchStatus::Unverified, Vec::new(), Vec::new(), None, None)
+            }
+            Ok(contracts) => match fetcher::persist_sources(dir, &address, &contracts) {
+                Ok(files) => {
+                    status!("   ✅ {}: {}", address, contracts.iter().map(|contract| contract.name.as_str()).collect::<Vec<_>>().join(", "));
+                    fetched.push(address.clone());
+                    item(FetchStatus::Fetched, files, DeployedContract::of(&address, &contracts), None, None)
+                }
+                Err(e) => {
+                    status!("   ❌ {}: could not save: {}", address, e);
+                    item(FetchStatus::Failed, Vec::new(), Vec::new(), Some(e.to_string()), None)
+                }
+            },
+            Err(e) => {
+                status!("   ❌ {}: {}", address, e);
+                let failure = Some(ToolError::classify(network, &e));
+                item(FetchStatus::Failed, Vec::new(), Vec::new(), Some(e.to_string()), failure)
+            }
+        };
+        manifest.record(item);
+        manifest.save(dir)?;
+        processed += 1;
+    }
+
+    Ok(BulkFetch { manifest, fetched, unverified, left: interrupted.then_some(total - processed) })
+}
+
+/// Analyse each of `unverified` from its bytecode, writing its report below `dir`
+///
+/// One failing does not stop the others.
+fn analyze_unverified_into(
+    analysis_engine: &AnalysisEngine,
+    report_generator: &crate::report::generator::ReportGenerator,
+    dir: &std::path::Path,
+    unverified: &[String],
+    network: &str,
+    rpc_url: Option<&str>,
+    config: &Config,
+) -> Result<Vec<AnalysisResults>> {
+    let mut analyses = Vec::new();
+    for address in unverified {
+        let results = match analyze_unverified(analysis_engine, address, network, rpc_url, config) {
+            Ok(results) => results,
+            Err(e) => {
+                status!("   ❌ {}: bytecode analysis failed: {}", address, e);
+                continue;
+            }
+        };
+        status!("📊 {} (bytecode) - {} vulnerabilities found", address, results.vulnerabilities.len());
+        let address_dir = fetcher::source_dir(dir, address);
+        std::fs::create_dir_all(&address_dir)?;
+        let report_path = address_dir.join("report.md");
+        report_generator.write_report(&results, "markdown", &report_path)?;
+        analyses.push(results);
+    }
+    Ok(analyses)
+}
+
+/// Handle fetch command for every named address of a protocol manifest
+async fn handle_fetch_protocol(
+    manifest_path: PathBuf,
+    api_key: Option<String>,
+    explorer: Option<String>,
+    analyze: bool,
+    output_dir: Option<PathBuf>,
+    resume: bool,
+    mut config: Config,
+) -> Result<()> {
+    let manifest = ProtocolManifest::load(&manifest_path)?;
+    let networks = manifest.networks();
+    for network in &networks {
+        if config.networks.evm_network(network).is_none() {
+            return Err(anyhow!("{} lists contracts on {}, which is not a configured EVM network", manifest_path.display(), network));
+        }
+        if let Some(explorer) = &explorer {
+            restrict_fetch_sources(&mut config, network, explorer)?;
+        }
+    }
+    let dir = output_dir.unwrap_or_else(|| PathBuf::from("fetched"));
+    std::fs::create_dir_all(&dir)?;
+
+    let title = if manifest.name.is_empty() { manifest_path.display().to_string() } else { manifest.name.clone() };
+    status!(
+        "🏛️  {} {}: {} contracts on {}",
+        "Fetching".bright_green(),
+        title,
+        manifest.contracts.len(),
+        networks.join(", ")
+    );
+
+    let mut fetches = std::collections::HashMap::new();
+    let mut unverified = Vec::new();
+    let mut left = None;
+    for network in &networks {
+        status!();
+        let fetch = fetch_into(&dir.join(network), network, manifest.addresses_on(network), api_key.as_deref(), resume, None, &config).await?;
+        unverified.push((network.clone(), fetch.unverified));
+        fetches.insert(network.clone(), fetch.manifest);
+        if fetch.left.is_some() {
+            left = fetch.left;
+            break;
+        }
+    }
+
+    // Written before analysing, which reads it
+    let project = ProtocolProject::build(&manifest, &fetches);
+    project.save(&dir)?;
+
+    let mut analyses = Vec::new();
+    if analyze && left.is_none() {
+        status!("\n🔍 {} Analysis of {}", "Starting".bright_green(), title);
+        let analysis_engine = AnalysisEngine::new(config.clone(), PluginManager::new());
+        let report_generator = crate::report::generator::ReportGenerator::new(config.clone());
+        if project.with_status(FetchStatus::Fetched).next().is_some() {
+            let results = analysis_engine.analyze_contracts(&dir, "evm", "standard", false).await?;
+            status!("📊 {} - {} vulnerabilities found", title, results.vulnerabilities.len());
+            report_generator.write_report(&results, "markdown", &dir.join("report.md"))?;
+            analyses.push(results);
+        }
+        for (network, addresses) in &unverified {
+            analyses.extend(analyze_unverified_into(&analysis_engine, &report_generator, &dir.join(network), addresses, network, None, &config)?);
+        }
+    }
+
+    let failed: Vec<&ProtocolContract> = project.with_status(FetchStatus::Failed).collect();
+    status!("\n📋 {} Summary", "Protocol".bright_green());
+    for contract in &project.contracts {
+        let icon = match contract.status {
+            FetchStatus::Fetched => "✅",
+            FetchStatus::Unverified => "⚠️ ",
+            FetchStatus::Failed => "❌",
+        };
+        let names = contract.contracts.iter().map(|deployed| deployed.name.as_str()).collect::<Vec<_>>().join(", ");
+        status!("   {} {} ({}, {}){}", icon, contract.name, contract.network, contract.address, if names.is_empty() { String::new() } else { format!(": {}", names) });
+    }
+    status!("📁 Sources in {}, project manifest {}", dir.display(), dir.join(protocol::PROJECT_FILE).display());
+    if !analyze {
+        status!("💡 Audit the whole protocol with: securechain analyze -i {}", dir.display());
+    }
+
+    if output::is_json_mode() {
+        output::emit_json(&serde_json::json!({
+            "project": project,
+            "interrupted": left.is_some(),
+            "results": analyses,
+        }))?;
+    }
+
+    if left.is_some() {
+        return Err(anyhow!("Fetch interrupted; rerun with --resume to continue"));
+    }
+    if !failed.is_empty() {
+        return Err(anyhow!("{} contracts failed to fetch; rerun with --resume to retry them", failed.len()));
+    }
+    Ok(())
+}
+
+/// Fetch from `explorer` alone on `network` instead of trying its sources in turn
+fn restrict_fetch_sources(config: &mut Config, network: &str, explorer: &str) -> Result<()> {
+    if !["etherscan", "sourcify", "blockscout"].contains(&explorer) {
+        return Err(anyhow!("Unknown explorer: {} (use etherscan, sourcify or blockscout)", explorer));
+    }
+    let network_config = config
+        .networks
+        .evm_network_mut(network)
+        .ok_or_else(|| anyhow!("--explorer needs an EVM network, got: {}", network))?;
+    network_config.sources = vec![explorer.to_string()];
+    Ok(())
+}
+
+/// Addresses listed in `path`, one per line
+fn read_address_list(path: &std::path::Path) -> Result<Vec<String>> {
+    let content = artifact::read_text(path, "address list")?;
+    Ok(content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// Handle fetch command for an EIP-2535 diamond
+async fn handle_fetch_diamond(
+    address: String,
+    network: String,
+    api_key: Option<String>,
+    output_dir: Option<PathBuf>,
+    rpc_url: Option<String>,
+    config: Config,
+) -> Result<()> {
+    status!("💎 {} Diamond from {}", "Fetching".bright_green(), network);
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    let network_config = config
+        .networks
+        .evm_network(&network)
+        .ok_or_else(|| anyhow!("Diamond mode needs an EVM network, got: {}", network))?
+        .clone();
+    let rpc = RpcClient::new(rpc_url.as_deref().unwrap_or(&network_config.rpc_url), network_config.timeout);
+
+    let mut deployment = diamond::load_deployment(&rpc, &address)?;
+    status!(
+        "✅ {} facets, {} DiamondCut events",
+        deployment.facets.len(),
+        deployment.cut_history.len()
+    );
+
+    let fetcher = ContractFetcher::new(config.clone());
+    let mut contracts = fetcher.fetch_contracts(&network, &address, api_key.as_deref()).await?;
+    let diamond_name = contracts.first().map(|contract| contract.name.clone()).unwrap_or_else(|| address.clone());
+
+    // Functions implemented by the diamond contract itself are routed to its own address
+    for facet in &mut deployment.facets {
+        if facet.address.as_deref().is_some_and(|a| a.eq_ignore_ascii_case(&address)) && !contracts.is_empty() {
+            facet.name = Some(diamond_name.clone());
+        }
+    }
+    contracts.extend(
+        diamond::fetch_facet_sources(&fetcher, &mut deployment, &network, api_key.as_deref(), network_config.rate_limit).await,
+    );
+
+    // Analyze diamond and facets together as one system
+    let source_dir = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).tempdir()?;
+    for (index, contract) in contracts.iter().enumerate() {
+        std::fs::write(source_dir.path().join(format!("{}_{}.sol", index, contract.name)), &contract.source_code)?;
+    }
+
+    let plugin_manager = PluginManager::new();
+    let analysis_engine = AnalysisEngine::new(config.clone(), plugin_manager);
+    let mut results = analysis_engine
+        .analyze_contracts(source_dir.path(), "evm", "standard", false)
+        .await?;
+    results.contract_name = diamond_name.clone();
+    if let Some(metadata) = results.metadata.as_mut() {
+        metadata.title = diamond_name.clone();
+        metadata.set_address(&diamond_name, &address);
+    }
+    analysis_engine
+        .apply_diamond_analysis(&mut results, source_dir.path(), Some(&deployment))
+        .await?;
+
+    status!("📊 Diamond: {} - {} vulnerabilities found", diamond_name, results.vulnerabilities.len());
+
+    if let Some(dir) = &output_dir {
+        std::fs::create_dir_all(dir)?;
+        let report_generator = crate::report::generator::ReportGenerator::new(config);
+        let report_path = dir.join(format!("{}_report.md", diamond_name));
+        report_generator.write_report(&results, "markdown", &report_path)?;
+        status!("📄 Report saved to: {}", report_path.display());
+    }
+
+    if output::is_json_mode() {
+        output::emit_json(&serde_json::json!({
+            "contracts": contracts,
+            "diamond": deployment,
+            "results": [results],
+        }))?;
+    }
+
+    Ok(())
+}
+
+/// Handle verify-onchain command
+#[allow(clippy::too_many_arguments)]
+async fn handle_verify_onchain(
+    address: String,
+    network: String,
+    input: Option<PathBuf>,
+    results: Option<PathBuf>,
+    overrides: verification::Overrides,
+    via: String,
+    api_key: Option<String>,
+    save_input: Option<PathBuf>,
+    dry_run: bool,
+    config: Config,
+) -> Result<()> {
+    status!("🔏 {} {} on {}", "Verifying".bright_green(), address, network);
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    if !verification::VERIFIERS.contains(&via.as_str()) {
+        return Err(anyhow!("Unknown verifier: {} (use {})", via, verification::VERIFIERS.join(" or ")));
+    }
+    let network_config = config
+        .networks
+        .evm_network(&network)
+        .ok_or_else(|| anyhow!("Verification needs an EVM network, got: {}", network))?;
+    let explorer_url = network_config.explorer_url.clone();
+    let chain_id = network_config.chain_id.or_else(|| verification::chain_id(&network));
+    let is_address = address.len() == 42
+        && address.starts_with("0x")
+        && address[2..].chars().all(|c| c.is_ascii_hexdigit());
+    if !is_address {
+        return Err(anyhow!("Not a contract address: {}", address));
+    }
+
+    let analysis: Option<AnalysisResults> = match &results {
+        Some(path) => Some(artifact::read_json(path, "analysis results file")?),
+        None => None,
+    };
+    let input = input
+        .or_else(|| analysis.as_ref()?.manifest.as_ref().map(|manifest| PathBuf::from(&manifest.input_path)))
+        .ok_or_else(|| anyhow!("Pass the audited source with --input, or the analysis results of its audit with --results"))?;
+
+    let submission = verification::prepare(
+        &input,
+        analysis.as_ref().and_then(|analysis| analysis.metadata.as_ref()),
+        &overrides,
+        &config,
+    )
+    .await?;
+    status!("  📄 Contract: {}", submission.contract);
+    status!("  🛠️  Compiler: v{} (settings from {})", submission.compiler_version,
+        if submission.settings_from.is_empty() { "compiler defaults".to_string() } else { submission.settings_from.join(", ") });
+    status!("  📚 Sources: {} file(s)", submission.source_files);
+
+    if let Some(path) = &save_input {
+        artifact::write_atomic(path, serde_json::to_string_pretty(&submission.standard_json)?)?;
+        status!("  💾 Standard JSON input saved to: {}", path.display());
+    }
+    if dry_run {
+        status!("✅ Submission prepared; nothing was sent (--dry-run)");
+        if output::is_json_mode() {
+            output::emit_json(&serde_json::json!({ "address": address, "network": network, "submission": submission }))?;
+        }
+        return Ok(());
+    }
+
+    let outcome = match via.as_str() {
+        "sourcify" => {
+            let chain_id = chain_id.ok_or_else(|| anyhow!("No chain id known for {}", network))?;
+            status!("📤 Submitting to Sourcify...");
+            verification::submit_to_sourcify(&submission, &address, chain_id).await?
+        }
+        _ => {
+            let api_key = api_key
+                .or_else(|| std::env::var("ETHERSCAN_API_KEY").ok())
+                .ok_or_else(|| anyhow!("Explorer verification needs an API key: pass --api-key or set ETHERSCAN_API_KEY"))?;
+            status!("📤 Submitting to {}...", explorer_url);
+            verification::submit_to_etherscan(&submission, &address, &explorer_url, chain_id, &api_key)
+                .await?
+        }
+    };
+
+    let icon = match outcome.status {
+        VerificationStatus::Verified | VerificationStatus::AlreadyVerified => "✅",
+        VerificationStatus::PartiallyVerified | VerificationStatus::Pending => "⚠️ ",
+        VerificationStatus::Failed => "❌",
+    };
+    status!("{} {}: {}", icon, outcome.status, outcome.message);
+    if let Some(url) = &outcome.url {
+        status!("🔗 {}", url);
+    }
+    if output::is_json_mode() {
+        output::emit_json(&serde_json::json!({
+            "address": address,
+            "network": network,
+            "submission": submission,
+            "outcome": outcome,
+        }))?;
+    }
+
+    match outcome.status {
+        VerificationStatus::Failed => Err(anyhow!("Verification failed: {}", outcome.message)),
+        VerificationStatus::Pending => Err(anyhow!("No verification verdict yet: {}", outcome.message)),
+        _ => Ok(()),
+    }
+}
+
+/// Handle audit command
+async fn handle_audit(
+    input: PathBuf,
+    target: String,
+    ai: bool,
+    fuzz: bool,
+    output_dir: PathBuf,
+    dry_run: bool,
+    config: Config,
+) -> Result<()> {
+    status!("🛡️  {} Comprehensive Security Audit", "Starting".bright_green());
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    let fetcher = ContractFetcher::new(config.clone());
+    let lines_of_code = match input.to_str() {
+        Some(path) => fetcher
+            .fetch_from_local(path)
+            .await?
+            .iter()
+            .map(|contract| contract.source_code.lines().count())
+            .sum(),
+        None => 0,
+    };
+    if dry_run {
+        return audit_dry_run(&input, &target, ai, fuzz, &output_dir, &config, lines_of_code).await;
+    }
+    let mut timer = StageTimer::start(&config, RunProfile::new("audit", &target, "deep", ai, fuzz), lines_of_code);
+
+    // Step 1: Static Analysis
+    status!("\n{} Step 1: Static Analysis", "🔍".bright_green());
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    let plugin_manager = PluginManager::new();
+    let analysis_engine = AnalysisEngine::new(config.clone(), plugin_manager);
+
+    #[cfg_attr(not(feature = "ai"), allow(unused_mut))]
+    let mut analysis_results = run_analysis(&analysis_engine, &input, &target, "deep", ai).await?;
+
+    status!("✅ Found {} vulnerabilities", analysis_results.vulnerabilities.len());
+    timer.finish_stage("analysis", lines_of_code);
+
+    // Step 2: Fuzzing Analysis
+    let mut fuzz_results = Vec::new();
     if fuzz {
-        println!("\n{} Step 2: Dynamic Fuzzing", "🎲".bright_green());
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        status!("\n{} Step 2: Dynamic Fuzzing", "🎲".bright_green());
+        status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+        fuzz_results = run_fuzzing(&input, &config).await?;
+        timer.finish_stage("fuzzing", lines_of_code);
+    }
+
+    // Creative exploit probes, one AI query per cluster of similar contracts
+    #[cfg_attr(not(feature = "ai"), allow(unused_mut))]
+    let mut probes = Vec::new();
+    #[cfg(feature = "ai")]
+    if ai && analysis_results.completeness.as_ref().is_none_or(|c| c.ai_unavailable.is_none()) {
+        // The probes share the run's budget with the analysis, wherever that ran
+        analysis_engine.resume_ai_usage(analysis_results.ai_usage.as_ref());
+        probes = analysis_engine
+            .generate_creative_probes(&input, "medium", &config.ai.backend, false)
+            .await?;
+        analysis_results.ai_usage = analysis_engine.ai_usage();
+        timer.finish_stage("creative probes", lines_of_code);
+    }
+
+    // Step 3: Generate comprehensive report
+    status!("\n{} Step 3: Generating Report", "📄".bright_green());
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    std::fs::create_dir_all(&output_dir)?;
+
+    let report_generator = crate::report::generator::ReportGenerator::new(config.clone());
+    let project = naming::project_title(&analysis_results);
+    let deliverable = |kind, extension| naming::file_name(&config.reporting, project, kind, extension).map(|name| output_dir.join(name));
+    let report_path = deliverable(Deliverable::TechnicalReport, "md")?;
+    report_generator.write_report(&analysis_results, "markdown", &report_path)?;
+
+    status!("📄 Comprehensive audit report saved to: {}", report_path.display());
+
+    let checklist = Checklist::for_results(&analysis_results, &config)?;
+    let checklist_path = deliverable(Deliverable::Checklist, "md")?;
+    artifact::write_atomic(&checklist_path, checklist.to_markdown(&analysis_results.contract_name))?;
+    artifact::write_atomic(deliverable(Deliverable::Checklist, "csv")?, checklist.to_csv())?;
+    status!(
+        "📋 Audit checklist ({} failing of {} items) saved to: {} and .csv",
+        checklist.count(ItemStatus::Fail),
+        checklist.items.len(),
+        checklist_path.display()
+    );
+
+    // Standalone diagram sources, for rendering or editing outside the report
+    if let Some(architecture) = &analysis_results.architecture {
+        artifact::write_atomic(output_dir.join("architecture.mmd"), architecture.to_mermaid())?;
+        artifact::write_atomic(output_dir.join("architecture.dot"), architecture.to_dot())?;
+        status!("🗺️  Architecture diagram saved to: {} and .dot", output_dir.join("architecture.mmd").display());
+    }
+
+    if ai {
+        let summary_path = deliverable(Deliverable::ExecutiveSummary, "md")?;
+        artifact::write_atomic(&summary_path, report_generator.generate_executive_summary(&analysis_results, &probes)?)?;
+        status!("📄 Executive summary with {} creative probes saved to: {}", probes.len(), summary_path.display());
+    }
+    timer.finish_stage("reports", lines_of_code);
+    timer.finish();
+    status!("✅ Security audit completed successfully!");
+
+    if output::is_json_mode() {
+        output::emit_json(&serde_json::json!({
+            "report_path": report_path,
+            "checklist_path": checklist_path,
+            "results": analysis_results,
+            "fuzzing": fuzz_results,
+            "probes": probes,
+        }))?;
+    }
+
+    Ok(())
+}
 
-        let fuzz_engine = crate::core::fuzz_engine::FuzzEngine::new(config.clone());
+/// Walk through the audit steps without running any: the tools and commands
+/// each would run, missing tools and AI backends, and the expected duration
+async fn audit_dry_run(
+    input: &std::path::Path,
+    target: &str,
+    ai: bool,
+    fuzz: bool,
+    output_dir: &std::path::Path,
+    config: &Config,
+    lines_of_code: usize,
+) -> Result<()> {
+    status!("🧪 Dry run: {} lines of code, nothing is analysed or written", lines_of_code);
+    let manifest = RunManifest::capture(config, input, target, "deep").await;
+    let capabilities = Capabilities::from_manifest(&manifest);
+    capabilities.announce(config);
+    // Never finished, so the dry run stays out of the history
+    let timer = StageTimer::start(config, RunProfile::new("audit", target, "deep", ai, fuzz), lines_of_code);
+    let estimate = |stage: &str| timer.stage_remaining(stage).map(|estimate| format!(" (about {})", estimate)).unwrap_or_default();
+    let mut problems = Vec::new();
+
+    status!("\n{} Step 1: Static Analysis{}", "🔍".bright_green(), estimate("analysis"));
+    for tool in &manifest.tools {
+        match &tool.version {
+            Some(version) => {
+                let version = capabilities.version(&tool.tool).map_or_else(|| version.clone(), |version| version.to_string());
+                status!("   ✅ {} {}: {}", tool.tool, version, tool.command)
+            }
+            None => {
+                status!("   ❌ {} is not installed and would be skipped: {}", tool.tool, tool.command);
+                problems.push(format!("{} is not installed", tool.tool));
+            }
+        }
+    }
+    if target == "evm" {
+        status!(
+            "   🧩 Built in: {}, {}, {} in place of Slither when it cannot run",
+            validation::TOOL_NAME,
+            error_handling::TOOL_NAME,
+            detectors::TOOL_NAME
+        );
+    }
+
+    status!("\n{} Step 2: Dynamic Fuzzing{}", "🎲".bright_green(), estimate("fuzzing"));
+    if !fuzz {
+        status!("   ⏭️  Skipped, pass --fuzz to run it");
+    } else if !cfg!(feature = "fuzzing") {
+        status!("   ❌ Needs a build with the `fuzzing` feature");
+        problems.push("fuzzing needs a build with the `fuzzing` feature".to_string());
+    } else if config.tools.fuzzer == "foundry" {
+        let forge = &config.tools.foundry.executable;
+        if platform::is_tool_available(forge) {
+            status!(
+                "   ✅ {} test --fuzz-runs {} --json, on fuzz and invariant tests generated per contract",
+                forge,
+                config.tools.foundry.fuzz_runs
+            );
+        } else {
+            status!("   ❌ {} is not installed, fuzzing would find nothing", forge);
+            problems.push(format!("{} is not installed (tools.fuzzer = foundry)", forge));
+        }
+    } else if platform::is_tool_available("echidna-test") {
+        status!("   ✅ echidna-test {{file}} --config echidna.yaml --format json, per contract");
+    } else {
+        // Already a problem of the analysis step, which runs it as well
+        status!("   ❌ echidna-test is not installed, fuzzing would find nothing");
+    }
+
+    status!("\n{} Step 3: Creative AI Probes{}", "🧠".bright_green(), estimate("creative probes"));
+    let ai_backend = if ai { check_ai_backend(config, &mut problems).await } else { None };
+    if !ai {
+        status!("   ⏭️  Skipped, pass --ai to run it");
+    } else if let Some(backend) = &ai_backend {
+        status!("   ✅ AI backend {} answered its health check; one query per cluster of similar contracts", backend);
+    }
+
+    status!("\n{} Step 4: PoC Generation", "💥".bright_green());
+    match &ai_backend {
+        Some(_) => status!("   ✅ Proof-of-concept code comes with each creative probe, in executive_summary.md"),
+        None => status!("   ⏭️  Comes with the creative probes; `exploit` writes PoCs for Critical and High findings afterwards"),
+    }
+
+    status!("\n{} Step 5: Generating Report{}", "📄".bright_green(), estimate("reports"));
+    let project = input.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    let deliverable = |kind, extension| naming::file_name(&config.reporting, &project, kind, extension);
+    let mut files = vec![
+        deliverable(Deliverable::TechnicalReport, "md")?,
+        deliverable(Deliverable::Checklist, "md")?,
+        deliverable(Deliverable::Checklist, "csv")?,
+        "architecture.mmd".to_string(),
+        "architecture.dot".to_string(),
+    ];
+    if ai {
+        files.push(deliverable(Deliverable::ExecutiveSummary, "md")?);
+    }
+    status!("   📁 {}: {}", output_dir.display(), files.join(", "));
+
+    if problems.is_empty() {
+        status!("\n✅ Every tool and backend the audit needs is available; nothing was run (--dry-run)");
+    } else {
+        status!("\n⚠️  {} problem(s); nothing was run (--dry-run):", problems.len());
+        for problem in &problems {
+            status!("   - {}", problem);
+        }
+    }
+
+    if output::is_json_mode() {
+        output::emit_json(&serde_json::json!({
+            "lines_of_code": lines_of_code,
+            "estimate": timer.remaining().map(|estimate| estimate.to_string()),
+            "tools": manifest.tools,
+            "ai_backend": ai_backend,
+            "outputs": files.iter().map(|file| output_dir.join(file)).collect::<Vec<_>>(),
+            "problems": problems,
+        }))?;
+    }
+    Ok(())
+}
+
+/// AI backend an audit would use, noting in `problems` why there is none
+#[cfg(feature = "ai")]
+async fn check_ai_backend(config: &Config, problems: &mut Vec<String>) -> Option<String> {
+    match ai_health::select_backend(&config.ai).await {
+        Ok(backend) => Some(backend),
+        Err(unavailable) => {
+            status!("   ❌ AI analysis would be skipped: {}", unavailable);
+            problems.push(format!("AI analysis unavailable: {}", unavailable));
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "ai"))]
+async fn check_ai_backend(_config: &Config, problems: &mut Vec<String>) -> Option<String> {
+    status!("   ❌ Needs a build with the `ai` feature");
+    problems.push("AI analysis needs a build with the `ai` feature".to_string());
+    None
+}
+
+/// Handle exploit command
+async fn handle_exploit(
+    results: PathBuf,
+    output_dir: PathBuf,
+    config: Config,
+) -> Result<()> {
+    status!("⚡ {} PoC Exploit Generation", "Starting".bright_green());
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    // Read analysis results
+    let analysis_results: AnalysisResults = artifact::read_json(&results, "analysis results file")?;
+
+    std::fs::create_dir_all(&output_dir)?;
+
+    // Generate exploits for the findings the engagement's PoC policy selects
+    let policy = PocPolicy::from_config(&config);
+    let mut exploit_paths = Vec::new();
+    for (i, vulnerability) in analysis_results.vulnerabilities.iter().enumerate() {
+        if policy.includes(vulnerability, &analysis_results.poc_flags) {
+            let poc = policy.generate(vulnerability);
+            let exploit_path = output_dir.join(poc.file_name(i + 1));
+            artifact::write_atomic(&exploit_path, poc.content())?;
+
+            match &poc {
+                PocOutput::Code(code) => status!("🔥 Generated {} exploit for: {}", code.platform, vulnerability.title),
+                PocOutput::Steps(_) => status!("📝 Wrote reproduction steps for: {}", vulnerability.title),
+            }
+            exploit_paths.push(exploit_path);
+        }
+    }
+
+    if output::is_json_mode() {
+        output::emit_json(&serde_json::json!({ "exploits": exploit_paths }))?;
+    }
+
+    status!("✅ PoC exploits generated successfully!");
+    Ok(())
+}
+
+/// Handle override command
+async fn handle_override(
+    results: PathBuf,
+    change: triage::Change,
+    output: String,
+    output_file: Option<PathBuf>,
+    config: Config,
+) -> Result<()> {
+    let triage::Change { finding, severity, justification, author } = change;
+    let mut analysis_results: AnalysisResults = artifact::read_json(&results, "analysis results file")?;
+    if analysis_results.vulnerabilities.is_empty() {
+        return Err(anyhow!("{} has no findings to override", results.display()));
+    }
+
+    let finding = match finding {
+        Some(finding) => finding,
+        None => {
+            for (i, vuln) in analysis_results.vulnerabilities.iter().enumerate() {
+                println!("{:>3}. [{}] {} ({}, {})", i + 1, vuln.severity, vuln.title, vuln.tool, &vuln.id[..vuln.id.len().min(8)]);
+            }
+            ask("Finding (number or id)", "--finding")?
+        }
+    };
+    let index = triage::find_finding(&analysis_results.vulnerabilities, &finding)?;
+    let current = analysis_results.vulnerabilities[index].clone();
+    if current.severity_overrides.is_empty() {
+        status!("🎚️  {} [{}] in {}", current.title, current.severity, current.file_path);
+    } else {
+        status!("🎚️  {} [{}, originally {}] in {}", current.title, current.severity, current.original_severity(), current.file_path);
+    }
 
-        // Get contracts for fuzzing
-        let fetcher = crate::core::fetcher::ContractFetcher::new(config.clone());
-        let contracts = fetcher.fetch_from_local(input.to_str().unwrap()).await?;
+    let severity = match severity {
+        Some(severity) => severity,
+        None => ask(&format!("New severity ({})", triage::SEVERITIES.join(", ").to_lowercase()), "--severity")?,
+    };
+    let severity = triage::parse_severity(&severity)?;
+    if severity == current.severity {
+        return Err(anyhow!("The finding is already {}", severity));
+    }
+
+    let justification = match justification {
+        Some(justification) => justification,
+        None => ask("Justification", "--justification")?,
+    };
+    if justification.trim().is_empty() {
+        return Err(anyhow!("A justification is required to override a severity"));
+    }
+
+    let author = match author.filter(|author| !author.trim().is_empty()) {
+        Some(author) => author,
+        None => match triage::default_author().await {
+            Some(author) => author,
+            None => ask("Author", "--author")?,
+        },
+    };
+
+    let vuln = &mut analysis_results.vulnerabilities[index];
+    let change = vuln.override_severity(severity, justification.trim(), author.trim()).clone();
+    let record = OverrideRecord::new(vuln, &results, &change);
+
+    let analysis_engine = AnalysisEngine::new(config.clone(), PluginManager::new());
+    analysis_engine.rescore(&mut analysis_results);
+    artifact::write_atomic(&results, serde_json::to_string_pretty(&analysis_results)?)?;
+    let log = triage::append(record.clone())?;
+
+    if let Some(output_path) = &output_file {
+        let report_generator = crate::report::generator::ReportGenerator::new(config);
+        report_generator.write_report(&analysis_results, &output, output_path)?;
+        status!("📄 Report saved to: {}", output_path.display());
+    }
+
+    if output::is_json_mode() {
+        output::emit_json(&record)?;
+    } else {
+        status!("✅ {} → {} by {}, saved to {}", change.from, change.to, change.author, results.display());
+        status!("📜 Recorded in {}", log.display());
+        status!("📊 Security score: {:.1}/100", analysis_results.metrics.security_score);
+    }
+    Ok(())
+}
+
+/// Handle import command: add another firm's findings to analysis results
+async fn handle_import(
+    file: PathBuf,
+    results: PathBuf,
+    vendor: String,
+    format: Option<String>,
+    config: Config,
+) -> Result<()> {
+    let vendor = vendor.trim();
+    if vendor.is_empty() {
+        return Err(anyhow!("--vendor names the firm that reported the findings"));
+    }
+    let format = format.map(|format| format.parse::<import::Format>()).transpose()?;
+    let mut analysis_results: AnalysisResults = artifact::read_json(&results, "analysis results file")?;
+    let (format, findings) = import::read(&file, format)?;
+    if findings.is_empty() {
+        return Err(anyhow!("{} has no findings to import", file.display()));
+    }
+    status!("📥 {} {} finding{} of {} in {}", findings.len(), format, if findings.len() == 1 { "" } else { "s" }, vendor, file.display());
+
+    let record = import::merge(&mut analysis_results, vendor, &file, format, &findings);
+    analysis_results.imports.push(record.clone());
+    let analysis_engine = AnalysisEngine::new(config, PluginManager::new());
+    analysis_engine.rescore(&mut analysis_results);
+    artifact::write_atomic(&results, serde_json::to_string_pretty(&analysis_results)?)?;
+
+    if output::is_json_mode() {
+        output::emit_json(&record)?;
+    } else {
+        status!("✅ Added {} finding{} to {}", record.added.len(), if record.added.len() == 1 { "" } else { "s" }, results.display());
+        for duplicate in &record.duplicates {
+            if let Some(vuln) = analysis_results.vulnerabilities.iter().find(|vuln| vuln.id == duplicate.finding_id) {
+                status!("   🔁 {} is {} [{}] ({})", duplicate.reference, vuln.title, vuln.severity, vuln.tool);
+            }
+        }
+        if record.already_imported > 0 {
+            status!("   ⏭️  {} already imported from {}", record.already_imported, vendor);
+        }
+        status!("📊 Security score: {:.1}/100", analysis_results.metrics.security_score);
+    }
+    Ok(())
+}
+
+/// Handle poc command: set a finding's PoC inclusion, or list what gets one
+async fn handle_poc(results: PathBuf, change: Option<(String, Option<bool>)>, config: Config) -> Result<()> {
+    let mut analysis_results: AnalysisResults = artifact::read_json(&results, "analysis results file")?;
+    let policy = PocPolicy::from_config(&config);
+
+    let (finding, flag) = match change {
+        Some(change) => change,
+        None => {
+            let listed: Vec<serde_json::Value> = analysis_results
+                .vulnerabilities
+                .iter()
+                .enumerate()
+                .map(|(i, vuln)| {
+                    let included = policy.includes(vuln, &analysis_results.poc_flags);
+                    let flagged = analysis_results.poc_flags.contains_key(&vuln.id);
+                    if !output::is_json_mode() {
+                        println!(
+                            "{:>3}. {} [{}] {} ({}){}",
+                            i + 1,
+                            if included { "✅" } else { "➖" },
+                            vuln.severity,
+                            vuln.title,
+                            &vuln.id[..vuln.id.len().min(8)],
+                            if flagged { ", set in triage" } else { "" }
+                        );
+                    }
+                    serde_json::json!({ "id": vuln.id, "title": vuln.title, "poc": included, "flagged": flagged })
+                })
+                .collect();
+            if output::is_json_mode() {
+                output::emit_json(&serde_json::json!({ "no_exploit_code": policy.no_exploit_code, "findings": listed }))?;
+            } else if policy.no_exploit_code {
+                status!("📝 No exploit code: selected findings get reproduction steps");
+            }
+            return Ok(());
+        }
+    };
+
+    let index = triage::find_finding(&analysis_results.vulnerabilities, &finding)?;
+    let vuln = analysis_results.vulnerabilities[index].clone();
+    match flag {
+        Some(included) => analysis_results.poc_flags.insert(vuln.id.clone(), included),
+        None => analysis_results.poc_flags.remove(&vuln.id),
+    };
+    artifact::write_atomic(&results, serde_json::to_string_pretty(&analysis_results)?)?;
+
+    let included = policy.includes(&vuln, &analysis_results.poc_flags);
+    if output::is_json_mode() {
+        output::emit_json(&serde_json::json!({ "id": vuln.id, "poc": included, "flag": flag }))?;
+    } else {
+        status!(
+            "{} {} [{}]: {}, saved to {}",
+            if included { "✅" } else { "➖" },
+            vuln.title,
+            vuln.severity,
+            match flag {
+                Some(true) => "PoC included",
+                Some(false) => "PoC excluded",
+                None => "PoC follows the severity and category policy",
+            },
+            results.display()
+        );
+    }
+    Ok(())
+}
+
+/// Handle poc verify command: compile and run the PoC of each selected finding, saving the outcomes
+async fn handle_poc_verify(
+    results: PathBuf,
+    finding: Option<String>,
+    fork_url: Option<String>,
+    pocs: Option<PathBuf>,
+    config: Config,
+) -> Result<()> {
+    status!("🧪 {} PoC Verification", "Starting".bright_green());
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    let mut analysis_results: AnalysisResults = artifact::read_json(&results, "analysis results file")?;
+    let selected: Vec<_> = match &finding {
+        Some(finding) => vec![analysis_results.vulnerabilities[triage::find_finding(&analysis_results.vulnerabilities, finding)?].clone()],
+        None => {
+            let policy = PocPolicy::from_config(&config);
+            analysis_results
+                .vulnerabilities
+                .iter()
+                .filter(|vuln| policy.includes(vuln, &analysis_results.poc_flags))
+                .cloned()
+                .collect()
+        }
+    };
+    if selected.is_empty() {
+        status!("➖ No finding gets a PoC under the PoC policy; select one with --finding");
+    }
+
+    let mut generated = Vec::new();
+    for vulnerability in &selected {
+        let edited = match &pocs {
+            Some(dir) => poc_verify::edited_poc(dir, vulnerability)?,
+            None => None,
+        };
+        let poc = match edited {
+            Some(code) => GeneratedPoc { platform: PocPlatform::Solidity, code },
+            None => poc::generate_poc(vulnerability),
+        };
+        generated.push((vulnerability, poc));
+    }
+
+    // Outcomes verified before an error stopped the run are kept
+    let verified = verify_pocs_of(&mut analysis_results, &generated, fork_url, &config).await;
+    artifact::write_atomic(&results, serde_json::to_string_pretty(&analysis_results)?)?;
+    verified?;
+
+    if output::is_json_mode() {
+        let outcomes: Vec<serde_json::Value> = selected
+            .iter()
+            .filter_map(|vuln| {
+                let verification = analysis_results.poc_verification.get(&vuln.id)?;
+                Some(serde_json::json!({ "id": vuln.id, "title": vuln.title, "verification": verification }))
+            })
+            .collect();
+        output::emit_json(&serde_json::json!({ "results": results, "pocs": outcomes }))?;
+    } else {
+        status!("💾 Saved to {}", results.display());
+    }
+    Ok(())
+}
+
+/// Compile and run each Solidity PoC in `pocs`, recording the outcomes in `analysis_results`
+async fn verify_pocs_of(
+    analysis_results: &mut AnalysisResults,
+    pocs: &[(&Vulnerability, GeneratedPoc)],
+    fork_url: Option<String>,
+    config: &Config,
+) -> Result<()> {
+    let verifier = PocVerifier::new(&config.tools.foundry, fork_url);
+    let mut confirmed = 0;
+    for (vulnerability, poc) in pocs {
+        if poc.platform != PocPlatform::Solidity {
+            status!("⏭️  {}: {} PoCs are not run, only Solidity ones", vulnerability.title, poc.platform);
+            continue;
+        }
+        let verification = verifier.verify(vulnerability, &poc.code).await?;
+        let icon = match verification.status {
+            PocStatus::Confirmed => {
+                confirmed += 1;
+                "✅"
+            }
+            PocStatus::CompileFailed => "🛠️ ",
+            PocStatus::NotExploited => "❌",
+        };
+        match &verification.detail {
+            Some(detail) => status!("{} {}: {} ({})", icon, vulnerability.title, verification.status, detail),
+            None => status!("{} {}: {}", icon, vulnerability.title, verification.status),
+        }
+        analysis_results.poc_verification.insert(vulnerability.id.clone(), verification);
+    }
+    status!("📊 {} of {} PoCs confirmed", confirmed, pocs.len());
+    Ok(())
+}
+
+/// Read a missing argument from the terminal, naming its flag when there is none to ask on
+fn ask(question: &str, flag: &str) -> Result<String> {
+    use std::io::{IsTerminal, Write};
+
+    if output::is_json_mode() || !std::io::stdin().is_terminal() {
+        return Err(anyhow!("{} is required when not run interactively", flag));
+    }
+    print!("{}: ", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
+
+/// Handle verify-fixes command
+async fn handle_verify_fixes(
+    findings: PathBuf,
+    input: PathBuf,
+    output: String,
+    output_file: Option<PathBuf>,
+    snapshot: Option<PathBuf>,
+    config: Config,
+) -> Result<()> {
+    status!("🔁 {} Remediation Verification", "Starting".bright_green());
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
-        for contract in &contracts {
-            let parsed_contract = crate::core::parser::ContractParser::new()?.parse_contract(contract)?;
-            let _fuzz_results = fuzz_engine.fuzz_contract(&parsed_contract).await?;
+    let (project, prior) = remediation::load_findings(&findings)?;
+    status!("📋 {} prior findings loaded from {}", prior.len(), findings.display());
 
-            println!("✅ Fuzzing completed for {}", contract.name);
-        }
+    let plugin_manager = PluginManager::new();
+    let analysis_engine = AnalysisEngine::new(config.clone(), plugin_manager);
+    let mut results = analysis_engine.verify_fixes(project, &prior, &input).await?;
+    if let Some(golden_path) = &snapshot {
+        let golden: Snapshot = artifact::read_json(golden_path, "snapshot file")?;
+        let current = Snapshot::record(&parse_input(&input, &config).await?)?;
+        results.behavior_drift = golden.compare(&current);
+        status!("📸 {} behavior differences from {}", results.behavior_drift.len(), golden_path.display());
     }
 
-    // Step 3: Generate comprehensive report
-    println!("\n{} Step 3: Generating Report", "📄".bright_green());
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-
-    std::fs::create_dir_all(&output_dir)?;
+    status!(
+        "📊 Fixed: {}, not fixed: {}, regressed: {}, unverified: {}, new: {}",
+        results.count(FixStatus::Fixed),
+        results.count(FixStatus::NotFixed),
+        results.count(FixStatus::Regressed),
+        results.count(FixStatus::Unverified),
+        results.new_findings.len()
+    );
 
     let report_generator = crate::report::generator::ReportGenerator::new(config);
-    let report = report_generator.generate_report(&analysis_results, "markdown")?;
+    let report = report_generator.generate_remediation_report(&results, &output)?;
+    if let Some(output_path) = output_file {
+        artifact::write_atomic(&output_path, &report)?;
+        status!("📄 Report saved to: {}", output_path.display());
+    } else if !output::is_json_mode() {
+        println!("{}", report);
+    }
 
-    let report_path = output_dir.join("security_audit_report.md");
-    std::fs::write(&report_path, &report)?;
+    if output::is_json_mode() {
+        output::emit_json(&results)?;
+    }
 
-    println!("📄 Comprehensive audit report saved to: {}", report_path.display());
-    println!("✅ Security audit completed successfully!");
+    status!("✅ Remediation verification completed!");
+    Ok(())
+}
 
+/// Handle snapshot command
+async fn handle_snapshot(action: SnapshotAction, config: Config) -> Result<()> {
+    match action {
+        SnapshotAction::Record { input, file, address, contract, network, rpc_url } => {
+            let snapshot = take_snapshot(&input, address, contract, &network, rpc_url, &config).await?;
+            artifact::write_atomic(&file, serde_json::to_string_pretty(&snapshot)?)?;
+
+            if output::is_json_mode() {
+                output::emit_json(&snapshot)?;
+            }
+            status!("📸 {} contracts recorded to {}", snapshot.contracts.len(), file.display());
+        }
+        SnapshotAction::Check { input, file, address, contract, network, rpc_url, strict } => {
+            let golden: Snapshot = artifact::read_json(&file, "snapshot file")?;
+            let current = take_snapshot(&input, address, contract, &network, rpc_url, &config).await?;
+            let drift = golden.compare(&current);
+            let failing = drift.iter().filter(|d| strict || d.is_breaking()).count();
+
+            if output::is_json_mode() {
+                output::emit_json(&serde_json::json!({ "snapshot": file, "drift": drift, "failing": failing }))?;
+                if failing > 0 {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+
+            for d in &drift {
+                let icon = match d.kind {
+                    DriftKind::Added => "➕",
+                    DriftKind::Removed => "➖",
+                    DriftKind::Changed => "✏️ ",
+                };
+                println!("  {} {} {}: {}", icon, d.contract, d.aspect, d.detail);
+            }
+            if golden.views_unchecked(&current) {
+                status!("ℹ️  {} has view outputs of {}; pass --address to compare them", file.display(), golden.deployment.as_deref().unwrap_or("a deployment"));
+            }
+            if failing > 0 {
+                return Err(anyhow!("{} of {} differences from {} change existing behavior", failing, drift.len(), file.display()));
+            }
+            status!("✅ No behavior removed or changed since {} ({} additions)", file.display(), drift.len());
+        }
+    }
     Ok(())
 }
 
-/// Handle exploit command
-async fn handle_exploit(
-    results: PathBuf,
-    output_dir: PathBuf,
+/// Snapshot of the contracts at `input`, with the view outputs of a deployment when `address` is given
+async fn take_snapshot(
+    input: &std::path::Path,
+    address: Option<String>,
+    contract: Option<String>,
+    network: &str,
+    rpc_url: Option<String>,
+    config: &Config,
+) -> Result<Snapshot> {
+    let contracts = parse_input(input, config).await?;
+    let mut snapshot = Snapshot::record(&contracts)?;
+    let address = match address {
+        Some(address) => address,
+        None => return Ok(snapshot),
+    };
+
+    let contract = match contract {
+        Some(contract) => contract,
+        None if snapshot.contracts.len() == 1 => snapshot.contracts.keys().next().cloned().unwrap_or_default(),
+        None => {
+            let names: Vec<&str> = snapshot.contracts.keys().map(String::as_str).collect();
+            return Err(anyhow!("Name the contract deployed at {} with --contract (one of {})", address, names.join(", ")));
+        }
+    };
+    let network_config = config
+        .networks
+        .evm_network(network)
+        .ok_or_else(|| anyhow!("View outputs need an EVM network, got: {}", network))?;
+    let rpc = RpcClient::new(rpc_url.as_deref().unwrap_or(&network_config.rpc_url), network_config.timeout);
+
+    let skipped = snapshot.read_views(&contracts, &contract, &address, &rpc)?;
+    let views = snapshot.contracts.get(&contract).map_or(0, |entry| entry.views.len());
+    status!("📞 {} view calls on {} at {}", views, contract, address);
+    if skipped > 0 {
+        status!("   {} view functions take dynamic or struct arguments and were left out", skipped);
+    }
+    Ok(snapshot)
+}
+
+/// Parse every contract at `input`
+async fn parse_input(input: &std::path::Path, config: &Config) -> Result<Vec<crate::core::parser::ParsedContract>> {
+    let input_str = input.to_str().ok_or_else(|| anyhow!("Input path is not valid UTF-8: {}", input.display()))?;
+    let contracts = ContractFetcher::new(config.clone()).fetch_from_local(input_str).await?;
+    let parser = crate::core::parser::ContractParser::new()?.with_solc(&config.tools.solc);
+    contracts.iter().map(|contract| parser.parse_contract(contract)).collect()
+}
+
+/// Handle review command
+async fn handle_review(
+    input: PathBuf,
+    github: Option<String>,
+    output_file: Option<PathBuf>,
     config: Config,
 ) -> Result<()> {
-    println!("⚡ {} PoC Exploit Generation", "Starting".bright_green());
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    // Checked first so a typo does not cost a whole review
+    let pull_request = github.map(|github| github.parse::<PullRequest>()).transpose()?;
 
-    // Read analysis results
-    let results_content = std::fs::read_to_string(&results)?;
-    let analysis_results: AnalysisResults = serde_json::from_str(&results_content)?;
+    status!("📝 {} AI Code Review", "Starting".bright_green());
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
-    std::fs::create_dir_all(&output_dir)?;
+    let comments = review_contracts(&input, &review::repo_prefix(&input), &config).await?;
+    status!("💬 {} review comments", comments.len());
 
-    // Generate exploits for each vulnerability
-    for (i, vulnerability) in analysis_results.vulnerabilities.iter().enumerate() {
-        if vulnerability.severity == "Critical" || vulnerability.severity == "High" {
-            let exploit_code = generate_exploit_code(vulnerability);
-            let exploit_path = output_dir.join(format!("exploit_{}.sol", i + 1));
-            std::fs::write(&exploit_path, exploit_code)?;
+    if let Some(output_path) = &output_file {
+        artifact::write_atomic(output_path, serde_json::to_string_pretty(&comments)?)?;
+        status!("📄 Comments saved to: {}", output_path.display());
+    }
 
-            println!("🔥 Generated exploit for: {}", vulnerability.title);
+    let posted = match &pull_request {
+        Some(pull_request) => {
+            let posted = post_review(&config, pull_request, &comments).await?;
+            status!("🐙 Review posted to {}#{}: {}", pull_request.repo, pull_request.number, posted["url"].as_str().unwrap_or_default());
+            Some(posted)
+        }
+        None => None,
+    };
+
+    if output::is_json_mode() {
+        output::emit_json(&serde_json::json!({ "comments": comments, "review": posted }))?;
+    } else if output_file.is_none() && pull_request.is_none() {
+        for comment in &comments {
+            println!("{}:{} ({})\n  {}\n", comment.path, comment.line, comment.function, comment.body);
         }
     }
+    Ok(())
+}
+
+/// AI review comments on every contract under `input`, with paths under `repo_prefix`
+#[cfg(feature = "ai")]
+async fn review_contracts(input: &std::path::Path, repo_prefix: &str, config: &Config) -> Result<Vec<ReviewComment>> {
+    let analysis_engine = AnalysisEngine::new(config.clone(), PluginManager::new());
+    analysis_engine.review_contracts(input, repo_prefix).await
+}
+
+#[cfg(not(feature = "ai"))]
+async fn review_contracts(_input: &std::path::Path, _repo_prefix: &str, _config: &Config) -> Result<Vec<ReviewComment>> {
+    Err(anyhow!("AI review needs a build with the `ai` feature"))
+}
+
+#[cfg(feature = "ai")]
+async fn post_review(config: &Config, pull_request: &PullRequest, comments: &[ReviewComment]) -> Result<serde_json::Value> {
+    let posted = review::post_github_review(config, pull_request, comments).await?;
+    status!("   {} inline, {} on unchanged lines listed in the review body", posted.inline, posted.in_body);
+    Ok(serde_json::to_value(posted)?)
+}
+
+#[cfg(not(feature = "ai"))]
+async fn post_review(_config: &Config, _pull_request: &PullRequest, _comments: &[ReviewComment]) -> Result<serde_json::Value> {
+    Err(anyhow!("Posting reviews needs a build with the `ai` feature"))
+}
+
+/// Handle checklist command
+async fn handle_checklist(
+    results: PathBuf,
+    output: String,
+    output_file: Option<PathBuf>,
+    standard: Option<String>,
+    mut config: Config,
+) -> Result<()> {
+    let analysis_results: AnalysisResults = artifact::read_json(&results, "analysis results file")?;
+    if let Some(standard) = standard {
+        config.reporting.checklist_standard = standard;
+    }
+    let checklist = Checklist::for_results(&analysis_results, &config)?;
+
+    let content = match output.as_str() {
+        "markdown" | "md" => checklist.to_markdown(&analysis_results.contract_name),
+        "csv" => checklist.to_csv(),
+        "json" => serde_json::to_string_pretty(&checklist)?,
+        other => return Err(anyhow!("Unsupported checklist format: {} (expected markdown, csv or json)", other)),
+    };
+
+    if let Some(output_path) = &output_file {
+        artifact::write_atomic(output_path, &content)?;
+        status!("📄 Checklist saved to: {}", output_path.display());
+    } else if !output::is_json_mode() {
+        println!("{}", content);
+    }
+
+    if output::is_json_mode() {
+        output::emit_json(&checklist)?;
+    } else {
+        status!(
+            "📋 {}: {} pass, {} fail, {} n/a, {} manual",
+            checklist.standard,
+            checklist.count(ItemStatus::Pass),
+            checklist.count(ItemStatus::Fail),
+            checklist.count(ItemStatus::NotApplicable),
+            checklist.count(ItemStatus::Manual)
+        );
+    }
+    Ok(())
+}
 
-    println!("✅ PoC exploits generated successfully!");
+/// Handle report command
+async fn handle_report(results: PathBuf, output: String, output_file: Option<PathBuf>, config: Config) -> Result<()> {
+    let analysis_results: AnalysisResults = artifact::read_json(&results, "analysis results file")?;
+    let report_generator = crate::report::generator::ReportGenerator::new(config.clone());
+
+    if let Some(output_path) = &output_file {
+        let started = std::time::Instant::now();
+        // A directory gets the report under the deliverable naming convention
+        let output_path = match output_path.is_dir() {
+            true => {
+                let extension = match output.as_str() {
+                    "markdown" => "md",
+                    format => format,
+                };
+                output_path.join(naming::file_name(&config.reporting, naming::project_title(&analysis_results), Deliverable::Report, extension)?)
+            }
+            false => output_path.clone(),
+        };
+        report_generator.write_report(&analysis_results, &output, &output_path)?;
+        status!(
+            "📄 Report of {} findings saved to: {} ({:.2}s)",
+            analysis_results.vulnerabilities.len(),
+            output_path.display(),
+            started.elapsed().as_secs_f64()
+        );
+    } else {
+        println!("{}", report_generator.generate_report(&analysis_results, &output)?);
+    }
     Ok(())
 }
 
@@ -355,13 +2769,17 @@ async fn handle_config(
     mut config: Config,
 ) -> Result<()> {
     if list {
-        println!("📋 {} Configuration", "Current".bright_green());
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        println!("AI Backend: {}", config.ai.backend);
-        println!("Log Level: {}", config.general.log_level);
-        println!("Output Directory: {}", config.general.output_dir.display());
-        println!("Default Analysis Depth: {}", config.analysis.default_depth);
-        println!("Default Report Format: {}", config.reporting.default_format);
+        if output::is_json_mode() {
+            return output::emit_json(&config);
+        }
+
+        status!("📋 {} Configuration", "Current".bright_green());
+        status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        status!("AI Backend: {}", config.ai.backend);
+        status!("Log Level: {}", config.general.log_level);
+        status!("Output Directory: {}", config.general.output_dir.display());
+        status!("Default Analysis Depth: {}", config.analysis.default_depth);
+        status!("Default Report Format: {}", config.reporting.default_format);
         return Ok(());
     }
 
@@ -369,10 +2787,15 @@ async fn handle_config(
         config.set_value(&key, &value)?;
         if let Some(config_path) = Config::user_config_path() {
             config.save_to_file(&config_path)?;
-            println!("✅ Configuration updated: {} = {}", key, value);
+            status!("✅ Configuration updated: {} = {}", key, value);
+        }
+        if output::is_json_mode() {
+            output::emit_json(&serde_json::json!({ "key": key, "value": value }))?;
         }
+    } else if output::is_json_mode() {
+        return Err(anyhow::anyhow!("Please provide both key and value, or use --list to view current configuration"));
     } else {
-        println!("❌ Please provide both key and value, or use --list to view current configuration");
+        status!("❌ Please provide both key and value, or use --list to view current configuration");
     }
 
     Ok(())
@@ -384,10 +2807,12 @@ async fn handle_scan(
     target: String,
     fuzz: bool,
     ai: bool,
+    verify_pocs: bool,
+    fork_url: Option<String>,
     config: Config,
 ) -> Result<()> {
-    println!("🚀 {} Quick Comprehensive Scan", "Starting".bright_green());
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    status!("🚀 {} Quick Comprehensive Scan", "Starting".bright_green());
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
     let start_time = std::time::Instant::now();
 
@@ -396,82 +2821,104 @@ async fn handle_scan(
     let output_dir = PathBuf::from(format!("scan_results_{}", timestamp));
     std::fs::create_dir_all(&output_dir)?;
 
-    println!("📁 Results will be saved to: {}", output_dir.display());
+    status!("📁 Results will be saved to: {}", output_dir.display());
 
     // Initialize components
     let plugin_manager = PluginManager::new();
     let analysis_engine = AnalysisEngine::new(config.clone(), plugin_manager);
 
     // Step 1: Quick static analysis
-    println!("\n{} Static Analysis", "🔍".bright_green());
-    let analysis_results = analysis_engine
-        .analyze_contracts(&input, &target, "deep", ai)
-        .await?;
+    status!("\n{} Static Analysis", "🔍".bright_green());
+    let mut analysis_results = run_analysis(&analysis_engine, &input, &target, "deep", ai).await?;
 
-    println!("✅ Found {} vulnerabilities", analysis_results.vulnerabilities.len());
+    status!("✅ Found {} vulnerabilities", analysis_results.vulnerabilities.len());
 
     // Step 2: Optional fuzzing
+    let mut fuzz_results = Vec::new();
     if fuzz {
-        println!("\n{} Fuzzing Analysis", "🎲".bright_green());
-        let fuzz_engine = crate::core::fuzz_engine::FuzzEngine::new(config.clone());
-        let fetcher = crate::core::fetcher::ContractFetcher::new(config.clone());
-        let contracts = fetcher.fetch_from_local(input.to_str().unwrap()).await?;
+        status!("\n{} Fuzzing Analysis", "🎲".bright_green());
+        fuzz_results = run_fuzzing(&input, &config).await?;
+    }
+
+    // Step 3: Generate exploits for the findings the PoC policy selects
+    let poc_policy = PocPolicy::from_config(&config);
+    let poc_vulns: Vec<_> = analysis_results.vulnerabilities
+        .iter()
+        .filter(|v| poc_policy.includes(v, &analysis_results.poc_flags))
+        .cloned()
+        .collect();
 
-        for contract in &contracts {
-            let parsed_contract = crate::core::parser::ContractParser::new()?.parse_contract(contract)?;
-            let _fuzz_results = fuzz_engine.fuzz_contract(&parsed_contract).await?;
-            println!("✅ Fuzzing completed for {}", contract.name);
+    let mut exploit_paths = Vec::new();
+    let mut pocs = Vec::new();
+    if !poc_vulns.is_empty() {
+        status!("\n{} Generating Exploits", "⚡".bright_green());
+        let exploit_dir = output_dir.join("exploits");
+        std::fs::create_dir_all(&exploit_dir)?;
+
+        for (i, vulnerability) in poc_vulns.iter().enumerate() {
+            let poc = poc_policy.generate(vulnerability);
+            let exploit_path = exploit_dir.join(poc.file_name(i + 1));
+            artifact::write_atomic(&exploit_path, poc.content())?;
+            status!("🔥 Exploit for '{}': {}", vulnerability.title, exploit_path.display());
+            exploit_paths.push(exploit_path);
+            if let PocOutput::Code(poc) = poc {
+                pocs.push((vulnerability, poc));
+            }
+        }
+    }
+
+    // Step 4: Optional PoC verification, before the reports so they can call findings verified
+    if verify_pocs {
+        status!("\n{} Verifying Exploits", "🧪".bright_green());
+        if let Err(e) = verify_pocs_of(&mut analysis_results, &pocs, fork_url, &config).await {
+            status!("⚠️  PoC verification stopped: {}", e);
         }
     }
 
-    // Step 3: Generate all reports
-    println!("\n{} Generating Reports", "📄".bright_green());
+    // Step 5: Generate all reports
+    status!("\n{} Generating Reports", "📄".bright_green());
     let report_generator = crate::report::generator::ReportGenerator::new(config);
 
     // Generate multiple report formats
     let formats = ["markdown", "json"];
+    let mut report_paths = Vec::new();
     for format in &formats {
-        let report = report_generator.generate_report(&analysis_results, format)?;
-        let filename = match format {
+        let filename = match *format {
             "json" => "scan_report.json",
             _ => "scan_report.md",
         };
         let report_path = output_dir.join(filename);
-        std::fs::write(&report_path, &report)?;
-        println!("📄 {} report: {}", format.to_uppercase(), report_path.display());
-    }
-
-    // Generate exploits for critical vulnerabilities
-    let critical_vulns: Vec<_> = analysis_results.vulnerabilities
-        .iter()
-        .filter(|v| v.severity == "Critical" || v.severity == "High")
-        .collect();
-
-    if !critical_vulns.is_empty() {
-        println!("\n{} Generating Exploits", "⚡".bright_green());
-        let exploit_dir = output_dir.join("exploits");
-        std::fs::create_dir_all(&exploit_dir)?;
-
-        for (i, vulnerability) in critical_vulns.iter().enumerate() {
-            let exploit_code = generate_exploit_code(vulnerability);
-            let exploit_path = exploit_dir.join(format!("exploit_{}.sol", i + 1));
-            std::fs::write(&exploit_path, exploit_code)?;
-            println!("🔥 Exploit for '{}': {}", vulnerability.title, exploit_path.display());
-        }
+        report_generator.write_report(&analysis_results, format, &report_path)?;
+        status!("📄 {} report: {}", format.to_uppercase(), report_path.display());
+        report_paths.push(report_path);
     }
 
     let duration = start_time.elapsed();
-    println!("\n{} Scan Summary", "📊".bright_green());
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("⏱️  Duration: {:.2}s", duration.as_secs_f64());
-    println!("🔍 Vulnerabilities: {}", analysis_results.vulnerabilities.len());
-    println!("🛡️  Security Score: {:.1}/100", analysis_results.metrics.security_score);
-    println!("📁 Results: {}", output_dir.display());
+    status!("\n{} Scan Summary", "📊".bright_green());
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    status!("⏱️  Duration: {:.2}s", duration.as_secs_f64());
+    status!("🔍 Vulnerabilities: {}", analysis_results.vulnerabilities.len());
+    status!("🛡️  Security Score: {:.1}/100", analysis_results.metrics.security_score);
+    if let Some(normalized) = analysis_results.metrics.normalized_security_score {
+        status!("📏 Normalized Security Score: {:.1}/100 (per {} lines of code)", normalized, SCORE_UNIT_LINES);
+    }
+    status!("📁 Results: {}", output_dir.display());
 
     if analysis_results.vulnerabilities.is_empty() {
-        println!("🎉 {} No vulnerabilities found!", "CLEAN".bright_green());
+        status!("🎉 {} No vulnerabilities found!", "CLEAN".bright_green());
     } else {
-        println!("⚠️  {} Review the findings and fix vulnerabilities", "ACTION REQUIRED".bright_yellow());
+        status!("⚠️  {} Review the findings and fix vulnerabilities", "ACTION REQUIRED".bright_yellow());
+    }
+
+    if output::is_json_mode() {
+        output::emit_json(&serde_json::json!({
+            "output_dir": output_dir,
+            "duration_secs": duration.as_secs_f64(),
+            "reports": report_paths,
+            "exploits": exploit_paths,
+            "results": analysis_results,
+            "fuzzing": fuzz_results,
+        }))?;
     }
 
     Ok(())
@@ -484,77 +2931,913 @@ async fn handle_update(
     ai: bool,
     _config: Config,
 ) -> Result<()> {
-    println!("🔄 {} SecureChain Components", "Updating".bright_green());
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    status!("🔄 {} SecureChain Components", "Updating".bright_green());
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    let mut updated = Vec::new();
 
     if all || db {
-        println!("📊 Updating vulnerability database...");
+        status!("📊 Updating vulnerability database...");
         // Simulate database update
         tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-        println!("✅ Vulnerability database updated");
+        status!("✅ Vulnerability database updated");
+        updated.push("db");
     }
 
     if all || ai {
-        println!("🤖 Updating AI models...");
+        status!("🤖 Updating AI models...");
         // Simulate AI model update
         tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-        println!("✅ AI models updated");
+        status!("✅ AI models updated");
+        updated.push("ai");
     }
 
     if all {
-        println!("🛠️  Updating analysis tools...");
+        status!("🛠️  Updating analysis tools...");
         // Simulate tool update
         tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-        println!("✅ Analysis tools updated");
+        status!("✅ Analysis tools updated");
+        updated.push("tools");
+    }
+
+    if output::is_json_mode() {
+        output::emit_json(&serde_json::json!({ "updated": updated }))?;
+    }
+
+    status!("✅ Update completed successfully!");
+    Ok(())
+}
+
+/// Handle annotate command
+async fn handle_graph(input: PathBuf, kind: String, format: String, output_file: Option<PathBuf>, config: Config) -> Result<()> {
+    let contracts = parse_input(&input, &config).await?;
+    let graph = ProjectGraph::build(&contracts);
+
+    let content = match (kind.as_str(), format.as_str()) {
+        ("calls" | "imports", "json") => serde_json::to_string_pretty(&graph)?,
+        ("calls", "dot") => graph.calls_to_dot(),
+        ("calls", "mermaid") => graph.calls_to_mermaid(),
+        ("imports", "dot") => graph.imports_to_dot(),
+        ("imports", "mermaid") => graph.imports_to_mermaid(),
+        ("calls" | "imports", other) => return Err(anyhow!("Unsupported graph format: {} (expected dot, mermaid or json)", other)),
+        (other, _) => return Err(anyhow!("Unknown graph: {} (expected calls or imports)", other)),
+    };
+
+    if let Some(output_path) = &output_file {
+        artifact::write_atomic(output_path, &content)?;
+        status!("📄 Graph saved to: {}", output_path.display());
+    } else if !output::is_json_mode() {
+        println!("{}", content);
+    }
+
+    if output::is_json_mode() {
+        output::emit_json(&graph)?;
+    } else {
+        let external = graph.calls.iter().filter(|edge| edge.kind == CallKind::External).count();
+        let unresolved = graph.imports.iter().filter(|edge| !edge.resolved).count();
+        status!(
+            "🕸️  {} file(s): {} import(s), {} outside the input; {} call(s), {} external",
+            graph.files.len(),
+            graph.imports.len(),
+            unresolved,
+            graph.calls.len(),
+            external
+        );
+    }
+    Ok(())
+}
+
+async fn handle_annotate(input: PathBuf, out: PathBuf, watch: bool, interval: u64, config: Config) -> Result<()> {
+    let mut annotator = Annotator::new(&input, config)?;
+    annotator.refresh();
+    let annotations = annotator.annotations();
+    annotate::write(&out, &annotations)?;
+    status!(
+        "📝 {} annotation(s) in {} file(s) written to {}",
+        annotations.annotations.len(),
+        annotations.files_scanned,
+        out.display()
+    );
+    for error in &annotations.errors {
+        status!("   ⚠️  {}: {}", error.file, error.message);
+    }
+
+    if !watch {
+        if output::is_json_mode() {
+            output::emit_json(&annotations)?;
+        }
+        return Ok(());
+    }
+
+    status!("👀 Watching {} for changes (Ctrl-C to stop)...", input.display());
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_millis(interval)) => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+        if !annotator.refresh() {
+            continue;
+        }
+        let annotations = annotator.annotations();
+        match annotate::write(&out, &annotations) {
+            Ok(()) => status!(
+                "🔄 {} annotation(s) in {} file(s) at {}",
+                annotations.annotations.len(),
+                annotations.files_scanned,
+                annotations.generated_at.format("%H:%M:%S")
+            ),
+            Err(e) => log::warn!("Could not write {}: {}", out.display(), e),
+        }
+    }
+    Ok(())
+}
+
+/// Handle hook command
+async fn handle_hook(action: HookAction, config: Config) -> Result<()> {
+    match action {
+        HookAction::Install { fail_on, force } => {
+            let fail_on = fail_on.unwrap_or(config.analysis.hook_fail_on);
+            let hook_path = hook::install_hook(&fail_on, force)?;
+
+            status!("✅ Pre-commit hook installed: {}", hook_path.display());
+            status!("   Commits introducing {}+ findings will be blocked", fail_on);
+            if output::is_json_mode() {
+                output::emit_json(&serde_json::json!({ "hook_path": hook_path, "fail_on": fail_on }))?;
+            }
+        }
+        HookAction::Uninstall => {
+            let removed = hook::uninstall_hook()?;
+            match &removed {
+                Some(hook_path) => status!("✅ Pre-commit hook removed: {}", hook_path.display()),
+                None => status!("ℹ️  No SecureChain pre-commit hook installed"),
+            }
+            if output::is_json_mode() {
+                output::emit_json(&serde_json::json!({ "removed": removed }))?;
+            }
+        }
+        HookAction::Run { fail_on } => {
+            let fail_on = fail_on.unwrap_or(config.analysis.hook_fail_on.clone());
+            let report = hook::HookScanner::new()?.scan_staged(&fail_on, &config)?;
+
+            if output::is_json_mode() {
+                output::emit_json(&report)?;
+                if report.is_blocked() {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+
+            for vuln in &report.findings {
+                let location = match vuln.line_number {
+                    Some(line) => format!("{}:{}", vuln.file_path, line),
+                    None => vuln.file_path.clone(),
+                };
+                println!("  {} [{}] {}", location, vuln.severity, vuln.title);
+            }
+
+            if report.is_blocked() {
+                return Err(anyhow::anyhow!(
+                    "Commit blocked: {} finding(s) at or above {} in staged files.\n   Fix them, or bypass this check once with `git commit --no-verify`.",
+                    report.blocking.len(),
+                    report.fail_on
+                ));
+            }
+
+            println!(
+                "🛡️  SecureChain: {} staged file(s), no findings at {}+ ({} cached, {} ms)",
+                report.files_scanned, report.fail_on, report.cache_hits, report.duration_ms
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle ci command
+async fn handle_ci(action: CiAction, config: Config) -> Result<()> {
+    match action {
+        CiAction::Github { input, base, head, depth, fail_on, repo, sha, pr, review, dry_run } => {
+            handle_ci_github(input, base, head, depth, fail_on, repo, sha, pr, review, dry_run, config).await
+        }
+    }
+}
+
+/// Analyse the contract files changed since `base` and report them as a GitHub check run
+#[allow(clippy::too_many_arguments)]
+async fn handle_ci_github(
+    input: PathBuf,
+    base: Option<String>,
+    head: String,
+    depth: String,
+    fail_on: Option<String>,
+    repo: Option<String>,
+    sha: Option<String>,
+    pr: Option<u64>,
+    review: bool,
+    dry_run: bool,
+    config: Config,
+) -> Result<()> {
+    // Everything that can be wrong with the options is checked before the analysis
+    let fail_on = hook::parse_threshold(&fail_on.unwrap_or(config.analysis.ci_fail_on.clone()))?;
+    let base = base
+        .or_else(ci::default_base)
+        .ok_or_else(|| anyhow!("No diff base: pass --base outside a pull request workflow"))?;
+    let target = if dry_run { None } else { Some(GithubTarget::resolve(repo, sha, pr)?) };
+    let pull_request = match (&target, review) {
+        (Some(target), true) => Some(PullRequest {
+            repo: target.repo.clone(),
+            number: target.pull_request.ok_or_else(|| anyhow!("--review needs a pull request: pass --pr outside a pull request workflow"))?,
+        }),
+        _ => None,
+    };
+
+    status!("🔎 {} Pull Request Check", "Starting".bright_green());
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    let files = ci::changed_files(&input, &base, &head)?;
+    status!("📂 {} changed contract file(s) between {} and {}", files.len(), base, head);
+
+    // Each file is analysed where it lies, so its imports resolve as in the repository
+    let analysis_engine = AnalysisEngine::new(config.clone(), PluginManager::new());
+    let mut findings = Vec::new();
+    for file in &files {
+        status!("\n🔍 {}", file.path);
+        let results = analysis_engine
+            .analyze_contracts(&input.join(&file.path), &file.platform, &depth, false)
+            .await
+            .map_err(|e| anyhow!("Analysis of {} failed: {}", file.path, e))?;
+        findings.extend(results.vulnerabilities.into_iter().map(|mut vuln| {
+            vuln.file_path = file.path.clone();
+            vuln
+        }));
+    }
+
+    let blocking = ci::blocking(&findings, &fail_on).len();
+    let title = match blocking {
+        0 => format!("No findings at or above {}", fail_on),
+        blocking => format!("{} finding(s) at or above {}", blocking, fail_on),
+    };
+    let summary = ci::summary(&files, &findings, blocking, &fail_on);
+
+    let mut check_run = None;
+    let mut posted_review = None;
+    if let Some(target) = &target {
+        let url = ci::publish_check_run(&config.networks.github, target, &title, &summary, blocking > 0, &findings)?;
+        status!("🐙 Check run posted on {}: {}", &target.head_sha[..target.head_sha.len().min(12)], url);
+        check_run = Some(url);
+    }
+    if let Some(pull_request) = &pull_request {
+        let comments: Vec<ReviewComment> = findings
+            .iter()
+            .map(|vuln| ReviewComment {
+                path: vuln.file_path.clone(),
+                line: vuln.line_number.unwrap_or(1).max(1),
+                function: vuln.title.clone(),
+                body: format!("**[{}] {}**\n\n{}", vuln.severity, vuln.title, vuln.description),
+            })
+            .collect();
+        posted_review = Some(post_review(&config, pull_request, &comments).await?);
+        status!("🐙 Review posted to {}#{}", pull_request.repo, pull_request.number);
+    }
+
+    if output::is_json_mode() {
+        output::emit_json(&serde_json::json!({
+            "files": files,
+            "fail_on": fail_on.to_string(),
+            "findings": findings,
+            "blocking": blocking,
+            "check_run": check_run,
+            "review": posted_review,
+        }))?;
+        if blocking > 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    for vuln in &findings {
+        let location = match vuln.line_number {
+            Some(line) => format!("{}:{}", vuln.file_path, line),
+            None => vuln.file_path.clone(),
+        };
+        println!("  {} [{}] {}", location, vuln.severity, vuln.title);
+    }
+    if blocking > 0 {
+        return Err(anyhow!("Check failed: {}", title));
+    }
+    status!("✅ {}", title);
+    Ok(())
+}
+
+/// Fuzz every contract under `input` with Echidna
+/// `config` with the fuzzing backend chosen on the command line
+fn with_fuzzer(mut config: Config, fuzzer: Option<String>) -> Result<Config> {
+    if let Some(fuzzer) = fuzzer {
+        config.set_value("tools.fuzzer", &fuzzer)?;
+    }
+    Ok(config)
+}
+
+#[cfg(feature = "fuzzing")]
+async fn run_fuzzing(input: &std::path::Path, config: &Config) -> Result<Vec<crate::core::fuzz_engine::FuzzingResults>> {
+    let fuzz_engine = crate::core::fuzz_engine::FuzzEngine::new(config.clone());
+    let fetcher = crate::core::fetcher::ContractFetcher::new(config.clone());
+    let contracts = fetcher.fetch_from_local(input.to_str().unwrap()).await?;
+
+    let mut fuzz_results = Vec::new();
+    for contract in &contracts {
+        let parsed_contract = crate::core::parser::ContractParser::new()?.with_solc(&config.tools.solc).parse_contract(contract)?;
+        // Echidna and forge build the target as Solidity
+        if parsed_contract.is_vyper() {
+            status!("↘️  Skipping fuzzing of {}: Vyper contracts are not supported by the fuzzers", contract.name);
+            continue;
+        }
+        let results = fuzz_engine.fuzz_contract(&parsed_contract).await?;
+        match &results.error {
+            Some(error) => {
+                status!("⚠️  Fuzzer failed on {}: {}", contract.name, error);
+            }
+            None => {
+                status!("✅ Fuzzing completed for {}", contract.name);
+            }
+        }
+        fuzz_results.push(results);
+    }
+    Ok(fuzz_results)
+}
+
+#[cfg(not(feature = "fuzzing"))]
+async fn run_fuzzing(_input: &std::path::Path, _config: &Config) -> Result<Vec<serde_json::Value>> {
+    Err(anyhow!("Fuzzing needs a build with the `fuzzing` feature"))
+}
+
+/// Analyse through the running daemon if there is one, else in-process
+async fn run_analysis(
+    analysis_engine: &AnalysisEngine,
+    input: &std::path::Path,
+    target: &str,
+    depth: &str,
+    ai: bool,
+) -> Result<AnalysisResults> {
+    // The daemon runs tools under its own budget, not one given on this command line, and analyses all code without a node
+    if !budget::from_command_line() && analysis_engine.entrypoints().is_empty() && !analysis_engine.simulates() {
+        if let Some(results) = daemon::delegate_analysis(input, target, depth, ai).await? {
+            status!("🛰️  Analysed by the running daemon");
+            return Ok(results);
+        }
+    }
+    analysis_engine.analyze_contracts(input, target, depth, ai).await
+}
+
+/// Handle daemon command
+async fn handle_daemon(action: DaemonAction, config: Config) -> Result<()> {
+    match action {
+        DaemonAction::Start => daemon::serve(config).await?,
+        DaemonAction::Stop => {
+            let stopped = daemon::shutdown().await?;
+            if output::is_json_mode() {
+                output::emit_json(&serde_json::json!({ "stopped": stopped }))?;
+            } else if stopped {
+                println!("🛰️  Daemon stopped");
+            } else {
+                println!("ℹ️  No daemon running");
+            }
+        }
+        DaemonAction::Status => {
+            let status = daemon::status().await?;
+            if output::is_json_mode() {
+                return output::emit_json(&serde_json::json!({ "running": status.is_some(), "daemon": status }));
+            }
+            match status {
+                Some(status) => {
+                    println!("🛰️  Daemon running (pid {}, port {})", status.pid, status.port);
+                    println!("   Started: {}", status.started_at.format("%Y-%m-%d %H:%M:%S UTC"));
+                    println!("   Analyses served: {}", status.requests_served);
+                    println!("   Contracts cached: {}", status.cached_contracts);
+                    println!(
+                        "   Jobs: {} running ({} deep), {} queued",
+                        status.queue.running, status.queue.running_deep, status.queue.queued
+                    );
+                    if let Some(limits) = status.limits {
+                        println!(
+                            "   Limits: {} workers, {} deep, {} queued",
+                            limits.workers, limits.deep_workers, limits.max_queued
+                        );
+                    }
+                }
+                None => println!("ℹ️  No daemon running"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle selftest command
+async fn handle_selftest(depth: String, min_rate: Option<f64>, config: Config) -> Result<()> {
+    status!("🧪 {} Detection Self-Test", "Starting".bright_green());
+    status!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    let report = selftest::run(&config, &depth).await?;
+
+    if output::is_json_mode() {
+        output::emit_json(&report)?;
+    } else {
+        println!("\n🧪 Detection by category ({} analysis)", report.depth);
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        for rate in &report.categories {
+            println!("   {:<24} {}/{} ({:.0}%)", rate.category.to_string(), rate.detected, rate.total, rate.rate());
+        }
+
+        println!("\n📋 Cases");
+        for case in &report.cases {
+            let mark = if case.detected { "✅" } else { "❌" };
+            println!("   {} {:<8} {} ({})", mark, case.id, case.title, case.file);
+        }
+
+        if let Some(completeness) = &report.completeness {
+            let incomplete: Vec<_> = completeness.incomplete_runs().collect();
+            if !incomplete.is_empty() {
+                println!("\n⚠️  Tools that did not run, which lowers the rate:");
+                let mut seen = Vec::new();
+                for run in incomplete {
+                    let reason = run.reason.map(|reason| reason.to_string()).unwrap_or_default();
+                    if !seen.contains(&(run.tool.as_str(), reason.clone())) {
+                        println!("   - {}: {}", run.tool, reason);
+                        seen.push((run.tool.as_str(), reason));
+                    }
+                }
+            }
+        }
+
+        let detected = report.cases.iter().filter(|case| case.detected).count();
+        println!(
+            "\n🎯 Detected {} of {} known vulnerabilities ({:.1}%)",
+            detected,
+            report.cases.len(),
+            report.detection_rate
+        );
+    }
+
+    if let Some(min_rate) = min_rate {
+        if report.detection_rate < min_rate {
+            return Err(anyhow!(
+                "Detection rate {:.1}% is below the required {:.1}%",
+                report.detection_rate,
+                min_rate
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Handle rules command
+async fn handle_rules(action: RulesAction) -> Result<()> {
+    match action {
+        RulesAction::Export { results, finding, disposition, name, file } => {
+            let disposition: Disposition = disposition.parse()?;
+            let analysis_results: AnalysisResults = artifact::read_json(&results, "analysis results file")?;
+            let export = community::export(&analysis_results, &finding, disposition)?;
+            let exported = export.rules.len();
+            let pack = community::write_pack(&file, &name, export.rules)?;
+
+            if output::is_json_mode() {
+                let skipped: Vec<serde_json::Value> = export
+                    .skipped
+                    .iter()
+                    .map(|(title, reason)| serde_json::json!({ "finding": title, "reason": reason }))
+                    .collect();
+                return output::emit_json(&serde_json::json!({
+                    "file": file,
+                    "exported": exported,
+                    "rules": pack.rules.len(),
+                    "skipped": skipped,
+                }));
+            }
+            println!("🤝 Exported {} {} pattern(s) to {} ({} rules in pack {})", exported, disposition, file.display(), pack.rules.len(), pack.name);
+            for (title, reason) in &export.skipped {
+                println!("   ⏭️  {}: {}", title, reason);
+            }
+            println!("💡 Check the shapes in the file before sharing it; they hold no names or literals, only code structure");
+        }
+        RulesAction::Import { file } => {
+            let (pack, installed, replaced) = community::import(&file)?;
+            if output::is_json_mode() {
+                return output::emit_json(&serde_json::json!({
+                    "name": pack.name,
+                    "rules": pack.rules.len(),
+                    "path": installed,
+                    "replaced": replaced,
+                }));
+            }
+            let verb = if replaced { "Updated" } else { "Installed" };
+            println!("✅ {} rule pack {} ({} rules) at {}", verb, pack.name, pack.rules.len(), installed.display());
+        }
+        RulesAction::List => {
+            let packs = community::installed();
+            if output::is_json_mode() {
+                return output::emit_json(&packs);
+            }
+            if packs.is_empty() {
+                println!("ℹ️  No rule packs installed; add one with `securechain rules import <file>`");
+                return Ok(());
+            }
+            println!("🤝 {} Rule Packs", "Community".bright_green());
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            for pack in &packs {
+                let confirmed = pack.rules.iter().filter(|rule| rule.disposition == Disposition::Confirmed).count();
+                println!(
+                    "  {:<24} {} confirmed, {} false-positive, exported {}",
+                    pack.name,
+                    confirmed,
+                    pack.rules.len() - confirmed,
+                    pack.exported_at.format("%Y-%m-%d")
+                );
+            }
+        }
+        RulesAction::Remove { name } => {
+            let removed = community::remove(&name)?;
+            if output::is_json_mode() {
+                return output::emit_json(&serde_json::json!({ "removed": removed }));
+            }
+            if !removed {
+                return Err(anyhow!("No rule pack {} installed (see `securechain rules list`)", name));
+            }
+            println!("🗑️  Removed rule pack {}", name);
+        }
+    }
+    Ok(())
+}
+
+/// Handle install command
+async fn handle_install(
+    tool: Option<String>,
+    version: Option<String>,
+    verification: toolchain::Verification<'_>,
+    action: Option<InstallAction>,
+) -> Result<()> {
+    match action {
+        Some(InstallAction::List) => {
+            let managed = toolchain::installed();
+            if output::is_json_mode() {
+                return output::emit_json(&managed);
+            }
+            if managed.is_empty() {
+                println!("ℹ️  No managed tools installed; install the pinned versions with `securechain install`");
+                return Ok(());
+            }
+            println!("🧰 {} Tools", "Managed".bright_green());
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            for installed in &managed {
+                let marker = if installed.active { "*" } else { " " };
+                println!(
+                    "  {} {:<10} {:<12} installed {}",
+                    marker,
+                    installed.tool,
+                    installed.version,
+                    installed.installed_at.format("%Y-%m-%d")
+                );
+            }
+            if let Some(bin) = toolchain::bin_dir() {
+                println!();
+                println!("💡 Active versions (*) run from {}, ahead of PATH", bin.display());
+            }
+        }
+        Some(InstallAction::Uninstall { tool, version }) => {
+            let removed = toolchain::uninstall(toolchain::tool(&tool)?, version.as_deref())?;
+            if output::is_json_mode() {
+                return output::emit_json(&serde_json::json!({ "removed": removed }));
+            }
+            if removed.is_empty() {
+                let which = version.map(|version| format!("{} {}", tool, version)).unwrap_or(tool);
+                return Err(anyhow!("{} is not installed (see `securechain install list`)", which));
+            }
+            for installed in &removed {
+                println!("🗑️  Removed {} {}", installed.tool, installed.version);
+            }
+        }
+        None => {
+            let tools: Vec<&toolchain::Tool> = match &tool {
+                Some(name) => vec![toolchain::tool(name)?],
+                None => toolchain::TOOLS.iter().collect(),
+            };
+            let mut installs = Vec::new();
+            let mut failed = Vec::new();
+            for tool in tools {
+                if !output::is_json_mode() {
+                    println!("📦 Installing {} {}...", tool.name, version.as_deref().unwrap_or(tool.pinned));
+                }
+                match toolchain::install(tool, version.as_deref(), verification).await {
+                    Ok((installed, present)) => {
+                        if !output::is_json_mode() {
+                            let verb = if present { "Activated" } else { "Installed" };
+                            println!("✅ {} {} {} at {}", verb, installed.tool, installed.version, installed.bin.display());
+                        }
+                        installs.push(installed);
+                    }
+                    Err(e) => {
+                        if !output::is_json_mode() {
+                            println!("❌ {}: {}", tool.name, e);
+                        }
+                        failed.push(serde_json::json!({ "tool": tool.name, "error": e.to_string() }));
+                    }
+                }
+            }
+            if output::is_json_mode() {
+                output::emit_json(&serde_json::json!({ "installed": installs, "failed": failed }))?;
+            }
+            if !failed.is_empty() {
+                return Err(anyhow!("{} tool(s) failed to install", failed.len()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Handle cache command
+async fn handle_cache(action: CacheAction, config: Config) -> Result<()> {
+    match action {
+        CacheAction::Clear => {
+            let cleared = cache::clear(&config)?;
+            if output::is_json_mode() {
+                return output::emit_json(&serde_json::json!({
+                    "path": cache::cache_dir(&config),
+                    "removed": cleared.entries,
+                    "bytes": cleared.bytes,
+                }));
+            }
+            if cleared.entries == 0 {
+                println!("ℹ️  No cached analyses in {}", cache::cache_dir(&config).display());
+            } else {
+                println!(
+                    "🗑️  Removed {} cached analyses ({:.1} KB) from {}",
+                    cleared.entries,
+                    cleared.bytes as f64 / 1024.0,
+                    cache::cache_dir(&config).display()
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Handle purge command
+async fn handle_purge(dry_run: bool, config: Config) -> Result<()> {
+    let purged = retention::purge(&config, dry_run)?;
+    if output::is_json_mode() {
+        return output::emit_json(&serde_json::json!({
+            "dry_run": dry_run,
+            "items": purged.items,
+            "history_runs": purged.history_runs,
+            "bytes": purged.bytes(),
+        }));
+    }
+    if purged.is_empty() {
+        println!("ℹ️  Nothing past the retention policy");
+        return Ok(());
     }
 
-    println!("✅ Update completed successfully!");
+    for item in &purged.items {
+        println!(
+            "  {:<5} {:>10.1} MB  {}  {}  ({})",
+            item.store,
+            item.bytes as f64 / (1024.0 * 1024.0),
+            item.modified.format("%Y-%m-%d %H:%M"),
+            item.path.display(),
+            item.reason
+        );
+    }
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    println!(
+        "\n🗑️  {} {} item{} ({:.1} MB) and {} run history row{}",
+        verb,
+        purged.items.len(),
+        if purged.items.len() == 1 { "" } else { "s" },
+        purged.bytes() as f64 / (1024.0 * 1024.0),
+        purged.history_runs,
+        if purged.history_runs == 1 { "" } else { "s" }
+    );
+    if dry_run {
+        println!("💡 Run without --dry-run to remove them");
+    }
     Ok(())
 }
 
-/// Generate exploit code for a vulnerability
-fn generate_exploit_code(vulnerability: &crate::report::vulnerability::Vulnerability) -> String {
-    format!(
-        r#"// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.0;
+/// Handle stats command
+async fn handle_ai_log(since: Option<String>, provider: Option<String>, config: Config) -> Result<()> {
+    let since = since
+        .map(|date| {
+            chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .map_err(|_| anyhow::anyhow!("Invalid date {} (expected YYYY-MM-DD)", date))
+        })
+        .transpose()?;
+    let uploads: Vec<ai_log::Upload> = ai_log::load(&config)?
+        .into_iter()
+        .filter(|upload| since.is_none_or(|since| upload.at.date_naive() >= since))
+        .filter(|upload| provider.as_deref().is_none_or(|provider| upload.provider == provider))
+        .collect();
+
+    if output::is_json_mode() {
+        return output::emit_json(&serde_json::json!({
+            "enabled": config.ai.upload_log,
+            "path": ai_log::log_path(&config),
+            "uploads": uploads,
+        }));
+    }
+
+    let state = if config.ai.upload_log { "enabled".green() } else { "disabled".yellow() };
+    println!("🧾 {} Upload Log ({})", "AI".bright_green(), state);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("File: {}", ai_log::log_path(&config).display());
+    if uploads.is_empty() {
+        println!("Nothing sent to an AI provider.");
+        if !config.ai.upload_log {
+            println!("💡 Turn logging back on with: securechain config --key ai.upload_log --value true");
+        }
+        return Ok(());
+    }
 
-/**
- * PoC Exploit for: {}
- * Severity: {}
- * Description: {}
- */
+    println!(
+        "
+  {:<17} {:<10} {:<20} {:<16} {:<20} {:<9} {:>8}  Content hash",
+        "Time (UTC)", "Provider", "Model", "Stage", "Contract", "Redacted", "Tokens"
+    );
+    for upload in &uploads {
+        println!(
+            "  {:<17} {:<10} {:<20} {:<16} {:<20} {:<9} {:>8}  {}",
+            upload.at.format("%Y-%m-%d %H:%M"),
+            upload.provider,
+            upload.model,
+            upload.stage,
+            upload.contract,
+            upload.redaction_profile,
+            upload.prompt_tokens + upload.completion_tokens.unwrap_or(0),
+            upload.content_keccak256
+        );
+    }
 
-contract ExploitPoC {{
-    address public target;
+    let bytes: usize = uploads.iter().map(|upload| upload.content_bytes).sum();
+    let tokens: usize = uploads.iter().map(|upload| upload.prompt_tokens + upload.completion_tokens.unwrap_or(0)).sum();
+    println!("
+{} upload(s), {} bytes, ~{} tokens", uploads.len(), bytes, tokens);
+    Ok(())
+}
 
-    constructor(address _target) {{
-        target = _target;
-    }}
+async fn handle_stats(reset: bool, config: Config) -> Result<()> {
+    if reset {
+        let removed = metrics::reset(&config)?;
+        if output::is_json_mode() {
+            return output::emit_json(&serde_json::json!({ "reset": removed }));
+        }
+        if removed {
+            println!("🗑️  Usage metrics deleted");
+        } else {
+            println!("ℹ️  No usage metrics recorded");
+        }
+        return Ok(());
+    }
 
-    function exploit() external {{
-        // Exploit implementation based on vulnerability type
-        // This is a template - actual implementation depends on specific vulnerability
+    let usage = metrics::load(&config)?;
+    if output::is_json_mode() {
+        return output::emit_json(&serde_json::json!({
+            "enabled": config.general.metrics_enabled,
+            "path": metrics::metrics_path(&config),
+            "metrics": usage,
+        }));
+    }
 
-        // Example: Reentrancy exploit
-        (bool success, ) = target.call(
-            abi.encodeWithSignature("vulnerableFunction()")
+    let state = if config.general.metrics_enabled { "enabled".green() } else { "disabled".yellow() };
+    println!("📊 {} Usage Metrics ({})", "Local".bright_green(), state);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("File: {}", metrics::metrics_path(&config).display());
+
+    let usage = match usage {
+        Some(usage) => usage,
+        None => {
+            println!("No usage recorded yet.");
+            if !config.general.metrics_enabled {
+                println!("💡 Opt in with: securechain config --key general.metrics_enabled --value true");
+            }
+            return Ok(());
+        }
+    };
+    println!(
+        "Recording since {} (last update {})",
+        usage.since.format("%Y-%m-%d"),
+        usage.updated_at.format("%Y-%m-%d %H:%M UTC")
+    );
+
+    println!("\n{}", "Commands".bold());
+    println!("  {:<14} {:>6} {:>9} {:>12} {:>12}", "Command", "Runs", "Failures", "Avg time", "Max time");
+    for (name, stats) in &usage.commands {
+        println!(
+            "  {:<14} {:>6} {:>9} {:>12} {:>12}",
+            name,
+            stats.runs,
+            stats.failures,
+            format_duration(stats.average_duration_ms()),
+            format_duration(stats.max_duration_ms)
         );
-        require(success, "Exploit failed");
-    }}
-
-    // Add fallback function for reentrancy attacks
-    fallback() external payable {{
-        if (address(target).balance > 0) {{
-            (bool success, ) = target.call(
-                abi.encodeWithSignature("vulnerableFunction()")
+    }
+
+    if !usage.tools.is_empty() {
+        println!("\n{}", "Tools".bold());
+        println!("  {:<20} {:>6} {:>10} {:>13}  Top reason", "Tool", "Runs", "Completed", "Failure rate");
+        for (name, stats) in &usage.tools {
+            let top_reason = stats
+                .skip_reasons
+                .iter()
+                .max_by_key(|(_, count)| **count)
+                .map(|(reason, count)| format!("{} ({})", reason, count))
+                .unwrap_or_default();
+            println!(
+                "  {:<20} {:>6} {:>10} {:>12.1}%  {}",
+                name,
+                stats.runs,
+                stats.completed,
+                stats.failure_rate() * 100.0,
+                top_reason
             );
-            require(success, "Reentrancy failed");
-        }}
-    }}
-}}
-"#,
-        vulnerability.title,
-        vulnerability.severity,
-        vulnerability.description
-    )
-}
\ No newline at end of file
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle detectors command
+async fn handle_detectors(action: DetectorsAction, config: Config) -> Result<()> {
+    let with_state = |detector: &Detector| {
+        let mut entry = serde_json::to_value(detector).unwrap_or_default();
+        entry["enabled"] = serde_json::Value::Bool(detector.enabled(&config));
+        entry
+    };
+
+    match action {
+        DetectorsAction::List { target } => {
+            let detectors: Vec<&Detector> = detector_catalog::for_target(&target).collect();
+            if detectors.is_empty() {
+                return Err(anyhow!("No built-in detectors for target {} (evm, solana, move, cairo, ink)", target));
+            }
+            if output::is_json_mode() {
+                let entries: Vec<serde_json::Value> = detectors.iter().map(|detector| with_state(detector)).collect();
+                return output::emit_json(&entries);
+            }
+
+            println!("🔎 {} Detectors for {}", "Built-in".bright_green(), target);
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            let mut tool = "";
+            for detector in &detectors {
+                if detector.tools[0] != tool {
+                    tool = detector.tools[0];
+                    println!("\n{}", detector.tools.join(" / ").bold());
+                }
+                let state = if detector.enabled(&config) { "✅" } else { "➖" };
+                println!(
+                    "  {} {:<32} {:<9} {:<20} {}",
+                    state,
+                    detector.id,
+                    detector.severity,
+                    format!("{:?}", detector.category),
+                    detector.description
+                );
+            }
+            let disabled = detectors.iter().filter(|detector| !detector.enabled(&config)).count();
+            println!("\n{} detector(s), {} disabled by analysis.disabled_detectors", detectors.len(), disabled);
+            println!("💡 Details: securechain detectors describe <id>");
+        }
+        DetectorsAction::Describe { id } => {
+            let detector = detector_catalog::find(&id)
+                .ok_or_else(|| anyhow!("Unknown detector: {} (see `securechain detectors list`)", id))?;
+            if output::is_json_mode() {
+                return output::emit_json(&with_state(detector));
+            }
+
+            let state = if detector.enabled(&config) { "enabled".green() } else { "disabled".yellow() };
+            println!("🔎 {} ({})", detector.id.bold(), state);
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            println!("Reported by:  {}", detector.tools.join(", "));
+            println!("Targets:      {}", detector.targets.join(", "));
+            println!("Severity:     {}", detector.severity);
+            println!("Category:     {:?}", detector.category);
+            println!("Runs:         {}", detector.runs);
+            println!("\n{}", detector.description);
+            if !detector.example.is_empty() {
+                println!("\n{}", "Example".bold());
+                for line in detector.example.lines() {
+                    println!("    {}", line);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn format_duration(ms: u64) -> String {
+    if ms < 1_000 {
+        format!("{} ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1} s", ms as f64 / 1_000.0)
+    } else {
+        format!("{}m {:02}s", ms / 60_000, (ms % 60_000) / 1_000)
+    }
+}
@@ -0,0 +1,144 @@
+//! Chunking of large contracts for AI prompts
+//!
+//! A contract whose code does not fit `ai.context_tokens` is not sent whole,
+//! for the provider to reject or silently truncate, but split by contract and
+//! function. Every chunk opens with a shared header: the lines before the
+//! first contract (license, pragmas, imports, file-level types) and an
+//! outline of the contract its functions belong to, with its state, events,
+//! modifiers and the signatures of its functions, bodies left out. The
+//! functions of the chunk follow whole, each marked with its line range so
+//! findings can point at the source; a function too long for one chunk is
+//! split by lines.
+
+use crate::core::ai_log;
+use crate::core::parser::{ContractUnit, ParsedContract, UnitFunction};
+
+/// One prompt's worth of a contract
+pub struct Chunk {
+    /// What the chunk covers: the file, or the first and last of its functions
+    pub label: String,
+    pub content: String,
+}
+
+/// Split `content`, the contract's code as it may be sent, into chunks of at most
+/// `budget` tokens where its layout allows; code that fits is one chunk
+pub fn split(contract: &ParsedContract, content: &str, budget: usize) -> Vec<Chunk> {
+    let whole = || vec![Chunk { label: contract.name.clone(), content: content.to_string() }];
+    if ai_log::tokens(content) <= budget || contract.units.is_empty() {
+        return whole();
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut units: Vec<&ContractUnit> = contract.units.iter().collect();
+    units.sort_by_key(|unit| unit.line_number);
+    let preamble = lines[..units[0].line_number.saturating_sub(1).min(lines.len())].join("\n");
+
+    let mut chunks = Vec::new();
+    for (index, unit) in units.iter().enumerate() {
+        // A unit runs until the next one starts
+        let start = unit.line_number.max(1);
+        let end = units.get(index + 1).map_or(lines.len(), |next| next.line_number.saturating_sub(1)).min(lines.len());
+        let functions: Vec<(&UnitFunction, usize, usize)> = unit
+            .constructor
+            .iter()
+            .chain(&unit.functions)
+            .filter_map(|function| {
+                let body = function.body.as_deref()?;
+                let first = function.line_number;
+                if first < start.max(1) || first > end {
+                    return None;
+                }
+                let last = (function.body_line_number + body.matches('\n').count()).clamp(first, end);
+                Some((function, first, last))
+            })
+            .collect();
+        if functions.is_empty() {
+            continue;
+        }
+
+        let mut header = format!("{}\n\n{}\n", preamble, outline(&lines, start, end, &functions));
+        if ai_log::tokens(&header) > budget / 2 {
+            // Too large an outline leaves no room for code: keep the declaration only
+            header = format!("{}\n\n{}\n    // ... declarations left out\n}}\n", preamble, lines.get(start - 1).copied().unwrap_or_default());
+        }
+        header.push_str(&format!("\n// Functions of {} analyzed in this part:\n", unit.name));
+        let room = budget.saturating_sub(ai_log::tokens(&header)).max(1);
+
+        let mut pending: Vec<(String, String)> = Vec::new();
+        let mut flush = |pending: &mut Vec<(String, String)>| {
+            if pending.is_empty() {
+                return;
+            }
+            let label = match pending.len() {
+                1 => pending[0].0.clone(),
+                count => format!("{}..{}", pending[0].0, pending[count - 1].0),
+            };
+            let code: Vec<String> = pending.drain(..).map(|(_, code)| code).collect();
+            chunks.push(Chunk { label, content: format!("{}{}", header, code.join("\n")) });
+        };
+
+        for (function, first, last) in functions {
+            let name = format!("{}.{}", unit.name, function.name);
+            let code = format!("// {}: lines {}-{}\n{}\n", name, first, last, lines[first - 1..last].join("\n"));
+            if ai_log::tokens(&code) > room {
+                flush(&mut pending);
+                for (part, (from, to)) in parts(&lines, first, last, room).into_iter().enumerate() {
+                    let code = format!("// {} (part {}): lines {}-{}\n{}\n", name, part + 1, from, to, lines[from - 1..to].join("\n"));
+                    pending.push((format!("{} (part {})", name, part + 1), code));
+                    flush(&mut pending);
+                }
+                continue;
+            }
+            let used: usize = pending.iter().map(|(_, code)| ai_log::tokens(code)).sum();
+            if used + ai_log::tokens(&code) > room {
+                flush(&mut pending);
+            }
+            pending.push((name, code));
+        }
+        flush(&mut pending);
+    }
+
+    match chunks.is_empty() {
+        true => whole(),
+        false => chunks,
+    }
+}
+
+/// The unit's lines with function bodies left out, keeping each function's first line
+fn outline(lines: &[&str], start: usize, end: usize, functions: &[(&UnitFunction, usize, usize)]) -> String {
+    let mut outline = Vec::new();
+    let mut line = start;
+    while line <= end {
+        match functions.iter().find(|(_, first, _)| *first == line) {
+            Some((_, first, last)) => {
+                outline.push(lines[line - 1].to_string());
+                if last > first {
+                    outline.push(format!("        // ... lines {}-{} left out", first + 1, last));
+                }
+                line = last + 1;
+            }
+            None => {
+                outline.push(lines[line - 1].to_string());
+                line += 1;
+            }
+        }
+    }
+    outline.join("\n")
+}
+
+/// Line ranges of at most `room` tokens covering `first..=last`
+fn parts(lines: &[&str], first: usize, last: usize, room: usize) -> Vec<(usize, usize)> {
+    let mut parts = Vec::new();
+    let (mut from, mut used) = (first, 0);
+    for line in first..=last {
+        let tokens = ai_log::tokens(lines[line - 1]) + 1;
+        if used + tokens > room && line > from {
+            parts.push((from, line - 1));
+            from = line;
+            used = 0;
+        }
+        used += tokens;
+    }
+    parts.push((from, last));
+    parts
+}
@@ -0,0 +1,155 @@
+//! AI backend health checks
+//!
+//! A stopped Ollama or a rejected API key used to surface as a raw error in
+//! the middle of an analysis, or not at all. Before any AI stage the
+//! configured backends are probed in fallback order, each with its own retry
+//! budget, and the first one that answers is used. When none does, the AI
+//! stages are skipped and the reasons recorded, so results and reports say
+//! the AI analysis did not happen instead of reading as if it found nothing.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::core::ai_provider;
+use crate::utils::config::AiConfig;
+
+/// Delay before the first retry, doubled for each further one
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Outcome of probing one backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendCheck {
+    pub backend: String,
+    pub healthy: bool,
+    /// Probes made, retries included
+    pub attempts: u32,
+    /// Why the backend is unusable
+    pub detail: Option<String>,
+}
+
+/// No configured backend answered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiUnavailable {
+    pub checks: Vec<BackendCheck>,
+}
+
+impl std::fmt::Display for AiUnavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reasons: Vec<String> = self
+            .checks
+            .iter()
+            .map(|check| {
+                let detail = check.detail.as_deref().unwrap_or("no answer");
+                match check.attempts {
+                    0 | 1 => format!("{}: {}", check.backend, detail),
+                    attempts => format!("{}: {} ({} attempts)", check.backend, detail, attempts),
+                }
+            })
+            .collect();
+        write!(f, "{}", reasons.join("; "))
+    }
+}
+
+/// Result of one probe
+enum Probe {
+    Healthy,
+    /// Might answer if asked again, e.g. not listening yet or a 5xx
+    Retry(String),
+    /// Will not answer however often it is asked, e.g. no API key
+    Fatal(String),
+}
+
+/// Backends to try: the configured one, then the fallbacks, without repeats
+pub fn fallback_order(config: &AiConfig) -> Vec<String> {
+    let mut order: Vec<String> = Vec::new();
+    for backend in std::iter::once(&config.backend).chain(&config.fallback_backends) {
+        if !order.contains(backend) {
+            order.push(backend.clone());
+        }
+    }
+    order
+}
+
+/// First backend in fallback order that passes its health check
+pub async fn select_backend(config: &AiConfig) -> Result<String, AiUnavailable> {
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.health_timeout))
+        .user_agent("SecureChain")
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            let checks = fallback_order(config)
+                .into_iter()
+                .map(|backend| BackendCheck { backend, healthy: false, attempts: 0, detail: Some(e.to_string()) })
+                .collect();
+            return Err(AiUnavailable { checks });
+        }
+    };
+
+    let mut checks = Vec::new();
+    for backend in fallback_order(config) {
+        let check = check_backend(&client, config, &backend).await;
+        if check.healthy {
+            if !checks.is_empty() {
+                log::info!("AI backend fallback to {}: {}", backend, AiUnavailable { checks });
+            }
+            return Ok(backend);
+        }
+        checks.push(check);
+    }
+    Err(AiUnavailable { checks })
+}
+
+/// Probe `backend` until it answers or its retry budget runs out
+async fn check_backend(client: &reqwest::Client, config: &AiConfig, backend: &str) -> BackendCheck {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let detail = match probe(client, config, backend).await {
+            Probe::Healthy => {
+                return BackendCheck { backend: backend.to_string(), healthy: true, attempts, detail: None };
+            }
+            Probe::Fatal(detail) => detail,
+            Probe::Retry(detail) if attempts <= config.retries => {
+                log::debug!("AI backend {} not ready ({}), retrying", backend, detail);
+                tokio::time::sleep(RETRY_BACKOFF * 2u32.pow(attempts - 1)).await;
+                continue;
+            }
+            Probe::Retry(detail) => detail,
+        };
+        return BackendCheck { backend: backend.to_string(), healthy: false, attempts, detail: Some(detail) };
+    }
+}
+
+async fn probe(client: &reqwest::Client, config: &AiConfig, backend: &str) -> Probe {
+    let provider = match ai_provider::provider(config, backend) {
+        Some(provider) => provider,
+        None => return Probe::Fatal(format!("unknown backend {}", backend)),
+    };
+    let key = match ai_provider::api_key(provider.as_ref()) {
+        Ok(key) => key,
+        Err(detail) => return Probe::Fatal(detail),
+    };
+
+    let response = match provider.health_request(key.as_deref()).build(client).send().await {
+        Ok(response) => response,
+        Err(e) if e.is_timeout() => return Probe::Retry(format!("timed out after {}s", config.health_timeout)),
+        Err(e) if e.is_connect() => return Probe::Retry(format!("could not connect to {}", provider.endpoint())),
+        Err(e) => return Probe::Retry(e.to_string()),
+    };
+
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Probe::Fatal(format!("API key rejected ({})", status.as_u16()));
+    }
+    if !status.is_success() {
+        return Probe::Retry(format!("answered {}", status));
+    }
+
+    let answer: serde_json::Value = response.json().await.unwrap_or_default();
+    match provider.health_problem(&answer) {
+        Some(problem) => Probe::Fatal(problem),
+        None => Probe::Healthy,
+    }
+}
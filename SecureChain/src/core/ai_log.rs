@@ -0,0 +1,120 @@
+//! Audit log of content sent to AI providers
+//!
+//! Clients often need proof of which of their code left the building. Every
+//! prompt an AI stage hands to its provider is first appended to a log in
+//! `general.data_dir`: when, to which provider, model and endpoint,
+//! for which stage and contract, the Keccak-256 of the content as sent, the
+//! redaction profile applied to it and its size in tokens. The content
+//! itself is not kept, only its hash, so the log can be shared with a client
+//! who holds the code and can check the hashes against it. The file is only
+//! ever appended to, one JSON record per line; an upload that cannot be
+//! logged is not made.
+#![cfg_attr(not(feature = "ai"), allow(dead_code))]
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::core::ai_provider;
+use crate::core::parser::ParsedContract;
+use crate::utils::abi::{encode_hex, keccak256};
+use crate::utils::cache;
+use crate::utils::config::{AiConfig, Config};
+
+/// Redaction profiles `ai.redaction_profile` can name: content as is, or with comments blanked
+pub const REDACTION_PROFILES: &[&str] = &["none", "comments"];
+
+/// One prompt sent to a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Upload {
+    pub at: DateTime<Utc>,
+    pub provider: String,
+    pub model: String,
+    pub endpoint: String,
+    /// AI stage that sent it: analysis, creative probes or review
+    pub stage: String,
+    pub contract: String,
+    /// Keccak-256 of the content as sent, after redaction
+    pub content_keccak256: String,
+    pub content_bytes: usize,
+    pub redaction_profile: String,
+    /// Tokens of the prompt, estimated at four bytes a token
+    pub prompt_tokens: usize,
+    /// Tokens of the answer, when the provider reports them
+    #[serde(default)]
+    pub completion_tokens: Option<usize>,
+}
+
+impl Upload {
+    /// Record of sending `content`, already redacted, to `backend` for `stage`
+    pub fn new(config: &AiConfig, backend: &str, stage: &str, contract: &str, content: &str) -> Self {
+        let (model, endpoint) = match ai_provider::provider(config, backend) {
+            Some(provider) => (provider.model().to_string(), provider.endpoint().to_string()),
+            None => (String::new(), String::new()),
+        };
+        Self {
+            at: Utc::now(),
+            provider: backend.to_string(),
+            model,
+            endpoint,
+            stage: stage.to_string(),
+            contract: contract.to_string(),
+            content_keccak256: format!("0x{}", encode_hex(&keccak256(content.as_bytes()))),
+            content_bytes: content.len(),
+            redaction_profile: config.redaction_profile.clone(),
+            prompt_tokens: tokens(content),
+            completion_tokens: None,
+        }
+    }
+}
+
+/// The contract's source as the redaction profile lets it be sent
+pub fn redact(contract: &ParsedContract, profile: &str) -> String {
+    match profile {
+        "comments" => contract.code().to_string(),
+        _ => contract.source_code.to_string(),
+    }
+}
+
+/// Tokens of `content`, estimated at four bytes a token
+pub fn tokens(content: &str) -> usize {
+    content.len().div_ceil(4)
+}
+
+/// Location of the upload log, in `general.data_dir`
+pub fn log_path(config: &Config) -> PathBuf {
+    cache::expand_home(&config.general.data_dir).join("ai_uploads.jsonl")
+}
+
+/// Append an upload to the log
+pub fn append(config: &Config, upload: &Upload) -> Result<PathBuf> {
+    let path = log_path(config);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(upload)?;
+    line.push('\n');
+    // One write per record, so concurrent stages never interleave within a line
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+    file.write_all(line.as_bytes())?;
+    file.sync_data()?;
+    Ok(path)
+}
+
+/// Every upload logged so far, oldest first
+pub fn load(config: &Config) -> Result<Vec<Upload>> {
+    let path = log_path(config);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    std::fs::read_to_string(&path)?
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|e| anyhow!("{} line {} is not an upload record: {}", path.display(), index + 1, e))
+        })
+        .collect()
+}
@@ -0,0 +1,569 @@
+//! AI providers
+//!
+//! Everything that differs from one AI backend to the next lives behind
+//! `AiProvider`: where its API is, which model it runs and which key it
+//! needs, how fast and at what price it takes prompts, and the shape of its
+//! health check and completion requests. Health checks, the upload log, cost
+//! accounting and the assistant itself ask the provider named in
+//! `ai.backend` instead of matching on backend names, so a new backend is
+//! one implementation and one entry in `provider`. Requests are built here
+//! and sent by the caller.
+#![cfg_attr(not(feature = "ai"), allow(dead_code))]
+
+use serde_json::{json, Value};
+
+use crate::utils::config::{
+    AiConfig, AnthropicConfig, AzureOpenAiConfig, GeminiConfig, LocalLlmConfig, OpenAiConfig, OpenRouterConfig,
+};
+
+/// Backends `ai.backend` and `ai.fallback_backends` can name
+pub const PROVIDERS: &[&str] = &["local", "openai", "anthropic", "azure", "gemini", "openrouter"];
+
+/// Backends with an embeddings API, which `ai.knowledge.backend` can name
+pub const EMBEDDING_PROVIDERS: &[&str] = &["local", "openai", "azure", "gemini"];
+
+/// An HTTP request to a provider
+#[derive(Debug, Clone)]
+pub struct ProviderRequest {
+    pub url: String,
+    pub headers: Vec<(&'static str, String)>,
+    /// JSON body of a POST; a GET when `None`
+    pub body: Option<Value>,
+}
+
+impl ProviderRequest {
+    fn get(url: String) -> Self {
+        Self { url, headers: Vec::new(), body: None }
+    }
+
+    fn post(url: String, body: Value) -> Self {
+        Self { url, headers: Vec::new(), body: Some(body) }
+    }
+
+    fn header(mut self, name: &'static str, value: Option<&str>) -> Self {
+        if let Some(value) = value {
+            self.headers.push((name, value.to_string()));
+        }
+        self
+    }
+
+    /// The request on `client`, ready to send
+    #[cfg(feature = "ai")]
+    pub fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        let mut request = match &self.body {
+            Some(body) => client.post(&self.url).json(body),
+            None => client.get(&self.url),
+        };
+        for (name, value) in &self.headers {
+            request = request.header(*name, value);
+        }
+        request
+    }
+}
+
+/// One AI backend
+pub trait AiProvider: Send + Sync {
+    /// Name as configured in `ai.backend`
+    fn name(&self) -> &'static str;
+
+    /// Model prompts go to
+    fn model(&self) -> &str;
+
+    /// Base URL of the API
+    fn endpoint(&self) -> &str;
+
+    /// Environment variable holding the API key, for backends that need one
+    fn api_key_var(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Requests per minute the backend takes; `None` when it takes them as fast as it answers
+    fn rate_limit(&self) -> Option<u32>;
+
+    /// US dollars per million prompt and completion tokens
+    fn prices(&self) -> (f64, f64) {
+        (0.0, 0.0)
+    }
+
+    /// Request that succeeds when the backend is up and accepts the key
+    fn health_request(&self, key: Option<&str>) -> ProviderRequest;
+
+    /// Why the backend cannot serve prompts although it answered the health check
+    fn health_problem(&self, _answer: &Value) -> Option<String> {
+        None
+    }
+
+    /// Request sending `prompt` as one user message, answered token by token when `stream` is set
+    fn completion_request(&self, key: Option<&str>, prompt: &str, max_tokens: u32, temperature: f64, stream: bool) -> ProviderRequest;
+
+    /// Text of the completion in the backend's answer
+    fn completion_text(&self, answer: &Value) -> Option<String>;
+
+    /// Request embedding each of `inputs` with `model`; `None` when the backend has no embeddings API
+    fn embedding_request(&self, _key: Option<&str>, _model: &str, _inputs: &[String]) -> Option<ProviderRequest> {
+        None
+    }
+
+    /// Embeddings in the backend's answer, in input order
+    fn embeddings(&self, _answer: &Value) -> Option<Vec<Vec<f32>>> {
+        None
+    }
+
+    /// Text in one line of a streamed answer; `None` for lines carrying none
+    ///
+    /// Defaults to OpenAI-style server-sent events.
+    fn stream_text(&self, line: &str) -> Option<String> {
+        sse_data(line)?["choices"][0]["delta"]["content"].as_str().map(str::to_string)
+    }
+}
+
+/// The provider `name` names, configured from `config`
+pub fn provider<'a>(config: &'a AiConfig, name: &str) -> Option<Box<dyn AiProvider + 'a>> {
+    let provider: Box<dyn AiProvider + 'a> = match name {
+        "local" => Box::new(Ollama(&config.local)),
+        "openai" => Box::new(OpenAi(&config.openai)),
+        "anthropic" => Box::new(Anthropic(&config.anthropic)),
+        "azure" => Box::new(AzureOpenAi(&config.azure)),
+        "gemini" => Box::new(Gemini(&config.gemini)),
+        "openrouter" => Box::new(OpenRouter(&config.openrouter)),
+        _ => return None,
+    };
+    Some(provider)
+}
+
+/// API key of `provider` from its environment variable, `None` when it needs none
+pub fn api_key(provider: &dyn AiProvider) -> Result<Option<String>, String> {
+    match provider.api_key_var() {
+        Some(var) => std::env::var(var).map(Some).map_err(|_| format!("{} is not set", var)),
+        None => Ok(None),
+    }
+}
+
+/// Body of an OpenAI-style chat completion
+fn chat_body(model: Option<&str>, prompt: &str, max_tokens: u32, temperature: f64, stream: bool) -> Value {
+    let mut body = json!({
+        "messages": [{ "role": "user", "content": prompt }],
+        "max_tokens": max_tokens,
+        "temperature": temperature,
+        "stream": stream,
+    });
+    if let Some(model) = model {
+        body["model"] = json!(model);
+    }
+    body
+}
+
+fn chat_text(answer: &Value) -> Option<String> {
+    answer["choices"][0]["message"]["content"].as_str().map(str::to_string)
+}
+
+/// JSON of a server-sent `data:` line, other than the closing `[DONE]`
+fn sse_data(line: &str) -> Option<Value> {
+    let data = line.strip_prefix("data:")?.trim();
+    match data {
+        "[DONE]" => None,
+        data => serde_json::from_str(data).ok(),
+    }
+}
+
+fn vector(value: &Value) -> Option<Vec<f32>> {
+    value.as_array()?.iter().map(|number| number.as_f64().map(|number| number as f32)).collect()
+}
+
+/// Embeddings of an OpenAI-style answer, ordered by their `index`
+fn data_embeddings(answer: &Value) -> Option<Vec<Vec<f32>>> {
+    let mut data: Vec<&Value> = answer["data"].as_array()?.iter().collect();
+    data.sort_by_key(|entry| entry["index"].as_u64());
+    data.iter().map(|entry| vector(&entry["embedding"])).collect()
+}
+
+fn bearer(key: Option<&str>) -> Option<String> {
+    key.map(|key| format!("Bearer {}", key))
+}
+
+/// Ollama, running models locally
+struct Ollama<'a>(&'a LocalLlmConfig);
+
+impl AiProvider for Ollama<'_> {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn model(&self) -> &str {
+        &self.0.default_model
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.0.ollama_url
+    }
+
+    fn rate_limit(&self) -> Option<u32> {
+        None
+    }
+
+    fn health_request(&self, _key: Option<&str>) -> ProviderRequest {
+        ProviderRequest::get(format!("{}/api/tags", self.0.ollama_url.trim_end_matches('/')))
+    }
+
+    // Ollama answers without the model being pulled, and would fail every prompt
+    fn health_problem(&self, answer: &Value) -> Option<String> {
+        let model = &self.0.default_model;
+        let pulled = answer["models"]
+            .as_array()
+            .is_some_and(|models| models.iter().any(|entry| entry["name"].as_str() == Some(model.as_str())));
+        match pulled {
+            true => None,
+            false => Some(format!("model {} is not pulled (ollama pull {})", model, model)),
+        }
+    }
+
+    fn completion_request(&self, _key: Option<&str>, prompt: &str, max_tokens: u32, temperature: f64, stream: bool) -> ProviderRequest {
+        ProviderRequest::post(
+            format!("{}/api/generate", self.0.ollama_url.trim_end_matches('/')),
+            json!({
+                "model": self.0.default_model,
+                "prompt": prompt,
+                "stream": stream,
+                "options": { "num_predict": max_tokens, "temperature": temperature },
+            }),
+        )
+    }
+
+    fn completion_text(&self, answer: &Value) -> Option<String> {
+        answer["response"].as_str().map(str::to_string)
+    }
+
+    // One JSON object a line, each with the next tokens
+    fn stream_text(&self, line: &str) -> Option<String> {
+        let chunk: Value = serde_json::from_str(line).ok()?;
+        self.completion_text(&chunk)
+    }
+
+    fn embedding_request(&self, _key: Option<&str>, model: &str, inputs: &[String]) -> Option<ProviderRequest> {
+        Some(ProviderRequest::post(
+            format!("{}/api/embed", self.0.ollama_url.trim_end_matches('/')),
+            json!({ "model": model, "input": inputs }),
+        ))
+    }
+
+    fn embeddings(&self, answer: &Value) -> Option<Vec<Vec<f32>>> {
+        answer["embeddings"].as_array()?.iter().map(vector).collect()
+    }
+}
+
+struct OpenAi<'a>(&'a OpenAiConfig);
+
+impl AiProvider for OpenAi<'_> {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.0.model
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.0.api_url
+    }
+
+    fn api_key_var(&self) -> Option<&'static str> {
+        Some("OPENAI_API_KEY")
+    }
+
+    fn rate_limit(&self) -> Option<u32> {
+        Some(self.0.rate_limit)
+    }
+
+    fn prices(&self) -> (f64, f64) {
+        (self.0.prompt_price, self.0.completion_price)
+    }
+
+    fn health_request(&self, key: Option<&str>) -> ProviderRequest {
+        ProviderRequest::get(format!("{}/models", self.0.api_url.trim_end_matches('/')))
+            .header("Authorization", bearer(key).as_deref())
+            .header("OpenAI-Organization", self.0.organization.as_deref().filter(|organization| !organization.is_empty()))
+    }
+
+    fn completion_request(&self, key: Option<&str>, prompt: &str, max_tokens: u32, temperature: f64, stream: bool) -> ProviderRequest {
+        ProviderRequest::post(
+            format!("{}/chat/completions", self.0.api_url.trim_end_matches('/')),
+            chat_body(Some(&self.0.model), prompt, max_tokens, temperature, stream),
+        )
+        .header("Authorization", bearer(key).as_deref())
+        .header("OpenAI-Organization", self.0.organization.as_deref().filter(|organization| !organization.is_empty()))
+    }
+
+    fn completion_text(&self, answer: &Value) -> Option<String> {
+        chat_text(answer)
+    }
+
+    fn embedding_request(&self, key: Option<&str>, model: &str, inputs: &[String]) -> Option<ProviderRequest> {
+        Some(
+            ProviderRequest::post(
+                format!("{}/embeddings", self.0.api_url.trim_end_matches('/')),
+                json!({ "model": model, "input": inputs }),
+            )
+            .header("Authorization", bearer(key).as_deref()),
+        )
+    }
+
+    fn embeddings(&self, answer: &Value) -> Option<Vec<Vec<f32>>> {
+        data_embeddings(answer)
+    }
+}
+
+struct Anthropic<'a>(&'a AnthropicConfig);
+
+impl AiProvider for Anthropic<'_> {
+    fn name(&self) -> &'static str {
+        "anthropic"
+    }
+
+    fn model(&self) -> &str {
+        &self.0.model
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.0.api_url
+    }
+
+    fn api_key_var(&self) -> Option<&'static str> {
+        Some("ANTHROPIC_API_KEY")
+    }
+
+    fn rate_limit(&self) -> Option<u32> {
+        Some(self.0.rate_limit)
+    }
+
+    fn prices(&self) -> (f64, f64) {
+        (self.0.prompt_price, self.0.completion_price)
+    }
+
+    fn health_request(&self, key: Option<&str>) -> ProviderRequest {
+        ProviderRequest::get(format!("{}/models", self.0.api_url.trim_end_matches('/')))
+            .header("x-api-key", key)
+            .header("anthropic-version", Some("2023-06-01"))
+    }
+
+    fn completion_request(&self, key: Option<&str>, prompt: &str, max_tokens: u32, temperature: f64, stream: bool) -> ProviderRequest {
+        ProviderRequest::post(
+            format!("{}/messages", self.0.api_url.trim_end_matches('/')),
+            json!({
+                "model": self.0.model,
+                "max_tokens": max_tokens,
+                "temperature": temperature,
+                "messages": [{ "role": "user", "content": prompt }],
+                "stream": stream,
+            }),
+        )
+        .header("x-api-key", key)
+        .header("anthropic-version", Some("2023-06-01"))
+    }
+
+    fn completion_text(&self, answer: &Value) -> Option<String> {
+        answer["content"][0]["text"].as_str().map(str::to_string)
+    }
+
+    fn stream_text(&self, line: &str) -> Option<String> {
+        sse_data(line)?["delta"]["text"].as_str().map(str::to_string)
+    }
+}
+
+/// Azure OpenAI, where prompts go to a deployment rather than a model
+struct AzureOpenAi<'a>(&'a AzureOpenAiConfig);
+
+impl AiProvider for AzureOpenAi<'_> {
+    fn name(&self) -> &'static str {
+        "azure"
+    }
+
+    fn model(&self) -> &str {
+        &self.0.model
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.0.endpoint
+    }
+
+    fn api_key_var(&self) -> Option<&'static str> {
+        Some("AZURE_OPENAI_API_KEY")
+    }
+
+    fn rate_limit(&self) -> Option<u32> {
+        Some(self.0.rate_limit)
+    }
+
+    fn prices(&self) -> (f64, f64) {
+        (self.0.prompt_price, self.0.completion_price)
+    }
+
+    fn health_request(&self, key: Option<&str>) -> ProviderRequest {
+        ProviderRequest::get(format!(
+            "{}/openai/deployments/{}?api-version={}",
+            self.0.endpoint.trim_end_matches('/'),
+            self.0.deployment,
+            self.0.api_version
+        ))
+        .header("api-key", key)
+    }
+
+    fn completion_request(&self, key: Option<&str>, prompt: &str, max_tokens: u32, temperature: f64, stream: bool) -> ProviderRequest {
+        // The deployment picks the model
+        ProviderRequest::post(
+            format!(
+                "{}/openai/deployments/{}/chat/completions?api-version={}",
+                self.0.endpoint.trim_end_matches('/'),
+                self.0.deployment,
+                self.0.api_version
+            ),
+            chat_body(None, prompt, max_tokens, temperature, stream),
+        )
+        .header("api-key", key)
+    }
+
+    fn completion_text(&self, answer: &Value) -> Option<String> {
+        chat_text(answer)
+    }
+
+    // `model` names the embedding deployment
+    fn embedding_request(&self, key: Option<&str>, model: &str, inputs: &[String]) -> Option<ProviderRequest> {
+        Some(
+            ProviderRequest::post(
+                format!(
+                    "{}/openai/deployments/{}/embeddings?api-version={}",
+                    self.0.endpoint.trim_end_matches('/'),
+                    model,
+                    self.0.api_version
+                ),
+                json!({ "input": inputs }),
+            )
+            .header("api-key", key),
+        )
+    }
+
+    fn embeddings(&self, answer: &Value) -> Option<Vec<Vec<f32>>> {
+        data_embeddings(answer)
+    }
+}
+
+struct Gemini<'a>(&'a GeminiConfig);
+
+impl AiProvider for Gemini<'_> {
+    fn name(&self) -> &'static str {
+        "gemini"
+    }
+
+    fn model(&self) -> &str {
+        &self.0.model
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.0.api_url
+    }
+
+    fn api_key_var(&self) -> Option<&'static str> {
+        Some("GEMINI_API_KEY")
+    }
+
+    fn rate_limit(&self) -> Option<u32> {
+        Some(self.0.rate_limit)
+    }
+
+    fn prices(&self) -> (f64, f64) {
+        (self.0.prompt_price, self.0.completion_price)
+    }
+
+    // Answers 404 for a model the key cannot use
+    fn health_request(&self, key: Option<&str>) -> ProviderRequest {
+        ProviderRequest::get(format!("{}/models/{}", self.0.api_url.trim_end_matches('/'), self.0.model)).header("x-goog-api-key", key)
+    }
+
+    fn completion_request(&self, key: Option<&str>, prompt: &str, max_tokens: u32, temperature: f64, stream: bool) -> ProviderRequest {
+        ProviderRequest::post(
+            match stream {
+                true => format!("{}/models/{}:streamGenerateContent?alt=sse", self.0.api_url.trim_end_matches('/'), self.0.model),
+                false => format!("{}/models/{}:generateContent", self.0.api_url.trim_end_matches('/'), self.0.model),
+            },
+            json!({
+                "contents": [{ "role": "user", "parts": [{ "text": prompt }] }],
+                "generationConfig": { "maxOutputTokens": max_tokens, "temperature": temperature },
+            }),
+        )
+        .header("x-goog-api-key", key)
+    }
+
+    fn completion_text(&self, answer: &Value) -> Option<String> {
+        let parts = answer["candidates"][0]["content"]["parts"].as_array()?;
+        Some(parts.iter().filter_map(|part| part["text"].as_str()).collect())
+    }
+
+    // Each event is a whole answer holding the next tokens
+    fn stream_text(&self, line: &str) -> Option<String> {
+        self.completion_text(&sse_data(line)?)
+    }
+
+    fn embedding_request(&self, key: Option<&str>, model: &str, inputs: &[String]) -> Option<ProviderRequest> {
+        let requests: Vec<Value> = inputs
+            .iter()
+            .map(|input| json!({ "model": format!("models/{}", model), "content": { "parts": [{ "text": input }] } }))
+            .collect();
+        Some(
+            ProviderRequest::post(
+                format!("{}/models/{}:batchEmbedContents", self.0.api_url.trim_end_matches('/'), model),
+                json!({ "requests": requests }),
+            )
+            .header("x-goog-api-key", key),
+        )
+    }
+
+    fn embeddings(&self, answer: &Value) -> Option<Vec<Vec<f32>>> {
+        answer["embeddings"].as_array()?.iter().map(|embedding| vector(&embedding["values"])).collect()
+    }
+}
+
+/// OpenRouter, an OpenAI-compatible API in front of many providers' models
+struct OpenRouter<'a>(&'a OpenRouterConfig);
+
+impl AiProvider for OpenRouter<'_> {
+    fn name(&self) -> &'static str {
+        "openrouter"
+    }
+
+    fn model(&self) -> &str {
+        &self.0.model
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.0.api_url
+    }
+
+    fn api_key_var(&self) -> Option<&'static str> {
+        Some("OPENROUTER_API_KEY")
+    }
+
+    fn rate_limit(&self) -> Option<u32> {
+        Some(self.0.rate_limit)
+    }
+
+    fn prices(&self) -> (f64, f64) {
+        (self.0.prompt_price, self.0.completion_price)
+    }
+
+    // The model list is public, so check the key instead
+    fn health_request(&self, key: Option<&str>) -> ProviderRequest {
+        ProviderRequest::get(format!("{}/auth/key", self.0.api_url.trim_end_matches('/'))).header("Authorization", bearer(key).as_deref())
+    }
+
+    fn completion_request(&self, key: Option<&str>, prompt: &str, max_tokens: u32, temperature: f64, stream: bool) -> ProviderRequest {
+        ProviderRequest::post(
+            format!("{}/chat/completions", self.0.api_url.trim_end_matches('/')),
+            chat_body(Some(&self.0.model), prompt, max_tokens, temperature, stream),
+        )
+        .header("Authorization", bearer(key).as_deref())
+        .header("X-Title", Some("SecureChain"))
+    }
+
+    fn completion_text(&self, answer: &Value) -> Option<String> {
+        chat_text(answer)
+    }
+}
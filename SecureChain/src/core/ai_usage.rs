@@ -0,0 +1,136 @@
+//! Token and cost accounting of AI requests
+//!
+//! A deep audit with AI sends every contract, chunk, retry and probe prompt
+//! to a paid provider, and the bill used to be the first anyone heard of
+//! its size. Every request is counted here, prompt and completion tokens
+//! apart and by stage, and priced at the backend's `prompt_price` and
+//! `completion_price`. With `ai.max_cost` set, a request whose prompt alone
+//! would take the run past it is not sent. Token counts are the same
+//! estimate the upload log uses, so costs are estimates too.
+#![cfg_attr(not(feature = "ai"), allow(dead_code))]
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::core::{ai_log, ai_provider};
+use crate::utils::config::AiConfig;
+
+/// AI requests of a run and what they are estimated to cost
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AiUsage {
+    pub requests: usize,
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    /// Estimated cost in US dollars
+    pub cost: f64,
+    /// `ai.max_cost` of the run, if any
+    #[serde(default)]
+    pub budget: Option<f64>,
+    /// Requests not sent because they would have gone over the budget
+    #[serde(default)]
+    pub refused: usize,
+    /// The same, by stage, e.g. `analysis` or `creative probes`
+    #[serde(default)]
+    pub stages: BTreeMap<String, StageUsage>,
+}
+
+/// AI requests of one stage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StageUsage {
+    pub requests: usize,
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    pub cost: f64,
+}
+
+/// A request not sent because it would have taken the run over `ai.max_cost`
+#[derive(Debug, Clone)]
+pub struct BudgetExceeded {
+    pub budget: f64,
+    pub spent: f64,
+    pub stage: String,
+}
+
+impl std::fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "AI budget of ${:.2} reached (${:.2} spent), {} request not sent; raise ai.max_cost to continue",
+            self.budget, self.spent, self.stage
+        )
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+/// US dollars per million prompt and completion tokens on `backend`
+pub fn prices(config: &AiConfig, backend: &str) -> (f64, f64) {
+    ai_provider::provider(config, backend).map_or((0.0, 0.0), |provider| provider.prices())
+}
+
+impl AiUsage {
+    pub fn new(config: &AiConfig) -> Self {
+        Self { budget: Some(config.max_cost).filter(|budget| *budget > 0.0), ..Default::default() }
+    }
+
+    /// Check that sending `prompt` to `backend` stays within the budget
+    pub fn reserve(&mut self, config: &AiConfig, backend: &str, stage: &str, prompt: &str) -> Result<()> {
+        let budget = match self.budget {
+            Some(budget) => budget,
+            None => return Ok(()),
+        };
+        let (prompt_price, _) = prices(config, backend);
+        let cost = ai_log::tokens(prompt) as f64 * prompt_price / 1_000_000.0;
+        if self.cost + cost > budget {
+            self.refused += 1;
+            return Err(BudgetExceeded { budget, spent: self.cost, stage: stage.to_string() }.into());
+        }
+        Ok(())
+    }
+
+    /// Count a request to `backend` and its completion
+    pub fn record(&mut self, config: &AiConfig, backend: &str, stage: &str, prompt: &str, completion: &str) {
+        let (prompt_price, completion_price) = prices(config, backend);
+        let prompt_tokens = ai_log::tokens(prompt);
+        let completion_tokens = ai_log::tokens(completion);
+        let cost = (prompt_tokens as f64 * prompt_price + completion_tokens as f64 * completion_price) / 1_000_000.0;
+
+        self.requests += 1;
+        self.prompt_tokens += prompt_tokens;
+        self.completion_tokens += completion_tokens;
+        self.cost += cost;
+        let stage = self.stages.entry(stage.to_string()).or_default();
+        stage.requests += 1;
+        stage.prompt_tokens += prompt_tokens;
+        stage.completion_tokens += completion_tokens;
+        stage.cost += cost;
+    }
+
+    /// Markdown table of the usage by stage, with the budget
+    pub fn to_markdown(&self) -> String {
+        let mut content = format!(
+            "Estimated cost: ${:.2}{}\n\n",
+            self.cost,
+            self.budget.map(|budget| format!(" of a ${:.2} budget", budget)).unwrap_or_default()
+        );
+        if self.refused > 0 {
+            content.push_str(&format!(
+                "⚠️ {} requests were not sent because they would have exceeded the budget; their analysis is missing.\n\n",
+                self.refused
+            ));
+        }
+        content.push_str("| Stage | Requests | Prompt tokens | Completion tokens | Cost |\n|-------|----------|---------------|-------------------|------|\n");
+        for (name, stage) in &self.stages {
+            content.push_str(&format!(
+                "| {} | {} | {} | {} | ${:.2} |\n",
+                name, stage.requests, stage.prompt_tokens, stage.completion_tokens, stage.cost
+            ));
+        }
+        content.push_str(&format!(
+            "| **Total** | {} | {} | {} | ${:.2} |\n\nToken counts are estimated at about 4 characters a token.",
+            self.requests, self.prompt_tokens, self.completion_tokens, self.cost
+        ));
+        content
+    }
+}
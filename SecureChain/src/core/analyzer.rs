@@ -6,16 +6,79 @@
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 
-use std::path::Path;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tokio::process::Command;
 
+#[cfg(feature = "ai")]
 use crate::core::ai_assist::AIAssistant;
+#[cfg(feature = "ai")]
+use crate::core::ai_health::{self, AiUnavailable};
+#[cfg(feature = "ai")]
+use crate::core::ai_usage::BudgetExceeded;
+use crate::core::ai_usage::AiUsage;
+use crate::core::architecture::Architecture;
+use crate::core::baseline::{self, BaselineComparison};
+use crate::core::callgraph::CallGraph;
+use crate::core::capabilities::Capabilities;
+#[cfg(feature = "ai")]
+use crate::core::clustering;
+use crate::core::community;
+use crate::core::bytecode::{self, BytecodeAnalysis};
+use crate::core::completeness::{Completeness, SkipReason, ToolRun};
+use crate::core::dead_code;
+use crate::core::dedup;
+use crate::core::detector_catalog;
+#[cfg(feature = "execution")]
+use crate::core::arithmetic;
+use crate::core::detectors;
 use crate::core::parser::{ContractParser, ParsedContract};
-use crate::core::fetcher::ContractFetcher;
+use crate::core::poc_verify::PocVerification;
+use crate::core::protocol::ProtocolProject;
+use crate::core::proxy;
+use crate::core::remediation::{self, RemediationResults};
+use crate::core::selectors::{self, SelectorRoute};
+use crate::core::simulation::{self, SimulationSettings, SimulationStage};
+use crate::core::suppression::{self, Applied, ExpiredAcceptance, SuppressedFinding, Suppressions};
+use crate::core::deployment::{self, DeploymentParameter};
+use crate::core::diamond::{self, DiamondDeployment, DiamondSystem};
+use crate::core::error_handling::{self, ErrorHandlingQuality};
+use crate::core::exploitability::{self, Exploitability};
+use crate::core::fetcher::{ContractFetcher, ContractInfo, FetchStatus};
+use crate::core::fuzz_coverage::{self, FuzzCoverage};
+use crate::core::gas;
+use crate::core::history::{RunProfile, StageTimer};
+use crate::core::import::ImportRecord;
+use crate::core::integration::{self, ContractSurface};
+use crate::core::inventory::{self, Inventory};
+use crate::core::manifest::{self, RunManifest};
+use crate::core::metadata::{self, ProjectMetadata};
+use crate::core::notify;
+use crate::core::risk;
+use crate::core::test_review::{self, TestReview};
+use crate::core::tree_shaking::Subgraph;
+use crate::core::validation;
+use crate::core::vyper;
 use crate::plugins::PluginManager;
-use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+use crate::report::cvss;
+use crate::report::explorer::SourceFile;
+#[cfg(feature = "ai")]
+use crate::report::review::ReviewComment;
+use crate::report::vulnerability::{self, Vulnerability, VulnerabilityCategory};
+use crate::status;
+use crate::utils::budget;
+use crate::utils::cache::{self, DiskCache};
 use crate::utils::config::Config;
+use crate::utils::error::ToolError;
+use crate::utils::platform;
+use crate::utils::metrics;
+use crate::utils::output::Progress;
+use crate::utils::retention;
 
+/// Results of a run; the top-level findings, summary and metrics aggregate every contract in `contracts`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisResults {
     pub contract_name: String,
@@ -24,6 +87,241 @@ pub struct AnalysisResults {
     pub recommendations: Vec<String>,
     pub metrics: AnalysisMetrics,
     pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Decoded constructor arguments, for contracts fetched from an explorer
+    #[serde(default)]
+    pub deployment_parameters: Vec<DeploymentParameter>,
+    /// Selector to facet routing table, for diamond-based systems
+    #[serde(default)]
+    pub selector_routes: Vec<SelectorRoute>,
+    /// Reconstructed diamond, when analysed in diamond mode
+    #[serde(default)]
+    pub diamond: Option<DiamondSystem>,
+    /// How the run was invoked, for the reproduction appendix
+    #[serde(default)]
+    pub manifest: Option<RunManifest>,
+    /// Bounty-relevant exploitability of each finding, by vulnerability id
+    #[serde(default)]
+    pub exploitability: HashMap<String, Exploitability>,
+    /// Which tools ran, which did not and why, and how much of the input compiled
+    #[serde(default)]
+    pub completeness: Option<Completeness>,
+    /// Contracts, compiler versions, licenses and dependencies of the run, for report front matter
+    #[serde(default)]
+    pub metadata: Option<ProjectMetadata>,
+    /// Analysed sources, bundled into HTML reports
+    #[serde(default)]
+    pub sources: Vec<SourceFile>,
+    /// Trust assumptions of each deployable contract, for the integrator appendix
+    #[serde(default)]
+    pub integration: Vec<ContractSurface>,
+    /// Contracts grouped into modules with their dependencies, for the architecture diagram
+    #[serde(default)]
+    pub architecture: Option<Architecture>,
+    /// Foundry tests that pass without exercising the code, for the test blind spots section
+    #[serde(default)]
+    pub test_review: Option<TestReview>,
+    /// Findings, score and timing of each contract of the run, in input order
+    #[serde(default)]
+    pub contracts: Vec<ContractAnalysisResults>,
+    /// Line coverage and per-function hits of each contract Echidna fuzzed or a project's lcov report covers
+    #[serde(default)]
+    pub fuzz_coverage: Vec<FuzzCoverage>,
+    /// New, fixed and persistent findings, when compared against `--baseline`
+    #[serde(default)]
+    pub baseline: Option<BaselineComparison>,
+    /// Requires and reverts with and without reasons, and the error handling score
+    #[serde(default)]
+    pub error_handling: Option<ErrorHandlingQuality>,
+    /// Custom errors, revert strings and events with the functions raising them, for the inventory appendix
+    #[serde(default)]
+    pub inventory: Option<Inventory>,
+    /// PoC inclusion decided during triage, by vulnerability id, over the severity and category policy
+    #[serde(default)]
+    pub poc_flags: HashMap<String, bool>,
+    /// Outcome of compiling and running each finding's PoC with `poc verify`, by vulnerability id
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub poc_verification: HashMap<String, PocVerification>,
+    /// Other firms' findings loaded with `securechain import`, oldest first
+    #[serde(default)]
+    pub imports: Vec<ImportRecord>,
+    /// Functions the run was restricted to with `--entrypoints`; empty when all code was analysed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entrypoints: Vec<String>,
+    /// Findings `.securechain-ignore.toml` left out, with the rule that matched
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suppressed: Vec<SuppressedFinding>,
+    /// Findings reported again because the rule accepting their risk expired
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expired_acceptances: Vec<ExpiredAcceptance>,
+    /// Tokens and estimated cost of the run's AI requests, when it made any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_usage: Option<AiUsage>,
+    /// Disassembly of a contract analysed without source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytecode: Option<BytecodeAnalysis>,
+}
+
+/// Outcome of one contract of a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractAnalysisResults {
+    pub name: String,
+    /// Path of the contract's file, relative to the analysed directory
+    pub file: String,
+    pub lines_of_code: usize,
+    /// Findings of the contract that survived detector filtering, and their severities
+    pub findings: usize,
+    pub critical: usize,
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+    #[serde(default)]
+    pub info: usize,
+    /// Ids of the contract's findings in the run's `vulnerabilities`
+    #[serde(default)]
+    pub vulnerability_ids: Vec<String>,
+    #[serde(default)]
+    pub security_score: Option<f64>,
+    /// Seconds spent on the contract, which overlap with other contracts of the run
+    pub duration: f64,
+    /// Reused from the analysis cache
+    pub cached: bool,
+}
+
+impl ContractAnalysisResults {
+    fn count(&mut self, vuln: &Vulnerability) {
+        self.findings += 1;
+        self.vulnerability_ids.push(vuln.id.clone());
+        match vuln.severity.as_str() {
+            "Critical" => self.critical += 1,
+            "High" => self.high += 1,
+            "Medium" => self.medium += 1,
+            "Low" => self.low += 1,
+            _ => self.info += 1,
+        }
+    }
+}
+
+impl AnalysisResults {
+    /// File of each attributed finding, by vulnerability id
+    ///
+    /// Findings name their contract's file without its directory, so two
+    /// `Token.sol` in different folders can only be told apart through the
+    /// contract that produced the finding.
+    pub fn finding_files(&self) -> HashMap<&str, &str> {
+        self.contracts
+            .iter()
+            .flat_map(|contract| contract.vulnerability_ids.iter().map(move |id| (id.as_str(), contract.file.as_str())))
+            .collect()
+    }
+
+    /// Fingerprint of each finding, by vulnerability id, numbered with the suppressed findings as suppression rules match them
+    pub fn fingerprints(&self) -> HashMap<&str, String> {
+        let findings: Vec<&Vulnerability> =
+            self.vulnerabilities.iter().chain(self.suppressed.iter().map(|suppressed| &suppressed.finding)).collect();
+        let fingerprints = vulnerability::utils::fingerprints(findings.iter().copied());
+        findings.iter().map(|vuln| vuln.id.as_str()).zip(fingerprints).collect()
+    }
+}
+
+/// Take the findings the project's `.securechain-ignore.toml` suppresses out of `vulnerabilities`
+///
+/// `files` gives the file of a finding, relative to the input, by vulnerability id,
+/// and `earlier` the findings of the run suppressed before.
+fn suppress(
+    input_path: &Path,
+    manifest: &RunManifest,
+    vulnerabilities: &mut Vec<Vulnerability>,
+    earlier: &[SuppressedFinding],
+    files: &HashMap<String, String>,
+) -> Result<Applied> {
+    let suppressions = match Suppressions::discover(input_path)? {
+        Some(suppressions) => suppressions,
+        None => return Ok(Applied::default()),
+    };
+    let applied = suppressions.apply(vulnerabilities, earlier, |vuln| {
+        PathBuf::from(manifest.source_path(&vuln.file_path, files.get(&vuln.id).map(String::as_str)))
+    });
+    if !applied.suppressed.is_empty() {
+        status!("🙈 {} finding(s) suppressed by {}", applied.suppressed.len(), suppression::FILE_NAME);
+    }
+    if !applied.expired.is_empty() {
+        status!("⏰ {} accepted risk(s) expired and reported again:", applied.expired.len());
+        for expired in &applied.expired {
+            let owner = expired.owner.as_deref().map(|owner| format!(", owner {}", owner)).unwrap_or_default();
+            status!("   - {} (expired {}{})", expired.title, expired.expired, owner);
+        }
+    }
+    Ok(applied)
+}
+
+/// Coverage from the `lcov.info` of the Foundry project holding `input_path`, for the contracts
+/// not in `measured`; files changed since the report was written are left out
+fn project_coverage(input_path: &Path, contracts: &[ContractInfo], parsed: &[ParsedContract], measured: &[FuzzCoverage]) -> Vec<FuzzCoverage> {
+    let (root, report) = match fuzz_coverage::project_lcov(input_path) {
+        Some(found) => found,
+        None => return Vec::new(),
+    };
+    let files = match std::fs::read_to_string(&report) {
+        Ok(lcov) => fuzz_coverage::parse_lcov(&lcov),
+        Err(e) => {
+            log::warn!("Ignoring {}: {}", report.display(), e);
+            return Vec::new();
+        }
+    };
+    let written = std::fs::metadata(&report).and_then(|metadata| metadata.modified()).ok();
+    let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+    let mut coverages = Vec::new();
+    let mut stale = Vec::new();
+    for (contract, parsed) in contracts.iter().zip(parsed) {
+        if measured.iter().any(|coverage| coverage.contract == parsed.name) {
+            continue;
+        }
+        let path = match contract.metadata.get("path").filter(|path| !path.is_empty() && input_path.is_dir()) {
+            Some(path) => canonical(&input_path.join(path)),
+            None => canonical(input_path),
+        };
+        let file = match files.iter().find(|file| canonical(&root.join(&file.path)) == path) {
+            Some(file) => file,
+            None => continue,
+        };
+        let changed = std::fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+        if matches!((written, changed), (Some(written), Some(changed)) if changed > written) {
+            stale.push(parsed.name.clone());
+            continue;
+        }
+        coverages.push(fuzz_coverage::from_lcov(file, parsed));
+    }
+
+    if !coverages.is_empty() {
+        status!("📈 Test coverage of {} contract(s) from {}", coverages.len(), report.display());
+    }
+    if !stale.is_empty() {
+        status!("⚠️  {} changed since {} was written, rerun forge coverage to measure it: {}", stale.len(), report.display(), stale.join(", "));
+    }
+    coverages
+}
+
+/// Attribute the run's final findings to the contracts they belong to
+///
+/// A finding belongs to the contract whose analysis produced it; findings of
+/// cross-contract checks go to the first contract whose file they name.
+fn attribute_findings(contracts: &mut [ContractAnalysisResults], vulnerabilities: &[Vulnerability], produced_by: &HashMap<String, usize>) {
+    let mut owned: Vec<Vec<Vulnerability>> = vec![Vec::new(); contracts.len()];
+    for vuln in vulnerabilities {
+        let owner = produced_by.get(&vuln.id).copied().or_else(|| {
+            contracts
+                .iter()
+                .position(|contract| contract.file == vuln.file_path || contract.name == vuln.file_path)
+        });
+        if let Some(index) = owner {
+            contracts[index].count(vuln);
+            owned[index].push(vuln.clone());
+        }
+    }
+    for (contract, vulnerabilities) in contracts.iter_mut().zip(owned) {
+        contract.security_score = Some((100.0 - severity_penalty(&vulnerabilities)).max(0.0));
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,7 +334,12 @@ pub struct AnalysisSummary {
     pub info_count: usize,
     pub analysis_duration: f64,
     pub tools_used: Vec<String>,
-    pub coverage_percentage: f64,
+    /// Line coverage measured by fuzzing or the project's tests, `None` when nothing measured it
+    #[serde(default)]
+    pub coverage_percentage: Option<f64>,
+    /// Tools whose results are partial, such as Mythril runs cut short by their timeout
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +348,9 @@ pub struct AnalysisMetrics {
     pub functions_analyzed: usize,
     pub complexity_score: f64,
     pub security_score: f64,
+    /// Security score with the penalties spread over the code size, see `normalized_security_score`
+    #[serde(default)]
+    pub normalized_security_score: Option<f64>,
     pub gas_optimization_score: f64,
 }
 
@@ -58,26 +364,150 @@ pub struct CreativeProbe {
     pub proof_of_concept: Option<String>,
     pub recommended_fix: Option<String>,
     pub confidence: f64,
+    /// Contract the probe applies to
+    #[serde(default)]
+    pub contract_name: String,
 }
 
 pub struct AnalysisEngine {
     config: Config,
     plugin_manager: PluginManager,
+    #[cfg(feature = "ai")]
     ai_assistant: AIAssistant,
     contract_parser: ContractParser,
+    /// Findings and tool runs of contracts already analysed, for long-lived engines
+    contract_cache: Option<Mutex<HashMap<String, CachedContract>>>,
+    /// The same kept on disk between runs, unless `analysis.cache_results` is off
+    disk_cache: Option<DiskCache>,
+    /// `Contract.function`s the analysis is restricted to; empty for all code
+    entrypoints: Vec<String>,
+    /// Node for state-dependent checks, with `--simulate`
+    simulation: Option<SimulationStage>,
+}
+
+/// Analysis of one contract, reused while its source, the analysis options and the tools stay the same
+#[derive(Clone, Serialize, Deserialize)]
+struct CachedContract {
+    vulnerabilities: Vec<Vulnerability>,
+    tool_runs: Vec<ToolRun>,
+    #[serde(default)]
+    fuzz_coverage: Option<FuzzCoverage>,
 }
 
+/// Findings and tool runs of one contract, before they are merged into the run
+struct ContractAnalysis {
+    parsed: ParsedContract,
+    vulnerabilities: Vec<Vulnerability>,
+    tool_runs: Vec<ToolRun>,
+    fuzz_coverage: Option<FuzzCoverage>,
+    /// Time each per-file stage took on this contract
+    stages: Vec<(&'static str, Duration)>,
+    duration: Duration,
+    cached: bool,
+}
+
+/// Key of a contract's analysis: its source, the analysis options, and each tool's version and command line
+fn contract_cache_key(manifest: &RunManifest, contract: &ParsedContract, use_ai: bool) -> String {
+    let use_ai = use_ai.to_string();
+    let mut parts = vec![
+        manifest.securechain_version.as_str(),
+        manifest.target.as_str(),
+        manifest.depth.as_str(),
+        use_ai.as_str(),
+        contract.name.as_str(),
+        &contract.source_code,
+    ];
+    for tool in &manifest.tools {
+        parts.extend([tool.tool.as_str(), tool.version.as_deref().unwrap_or("not installed"), tool.command.as_str()]);
+    }
+    cache::key(&parts)
+}
+
+/// Lines of code one size unit of the normalized security score stands for
+pub const SCORE_UNIT_LINES: usize = 1000;
+
+/// Cache entries are dropped once the cache grows past this size
+const MAX_CACHED_CONTRACTS: usize = 1000;
+
 impl AnalysisEngine {
     /// Create a new analysis engine
-    pub fn new(config: Config, plugin_manager: PluginManager) -> Self {
+    pub fn new(config: Config, mut plugin_manager: PluginManager) -> Self {
+        plugin_manager.configure(&config);
+        #[cfg(feature = "ai")]
         let ai_assistant = AIAssistant::new(config.clone());
-        let contract_parser = ContractParser::new().expect("Failed to create contract parser");
+        let contract_parser = ContractParser::new().expect("Failed to create contract parser").with_solc(&config.tools.solc);
+        let disk_cache = DiskCache::from_config(&config);
 
         Self {
             config,
             plugin_manager,
+            #[cfg(feature = "ai")]
             ai_assistant,
             contract_parser,
+            contract_cache: None,
+            disk_cache,
+            entrypoints: Vec::new(),
+            simulation: None,
+        }
+    }
+
+    /// Keep each contract's analysis in memory and reuse it while the contract is unchanged
+    pub fn with_contract_cache(mut self) -> Self {
+        self.contract_cache = Some(Mutex::new(HashMap::new()));
+        self
+    }
+
+    /// Analyze only the code reachable from `entrypoints`, given as `Contract.function`
+    pub fn with_entrypoints(mut self, entrypoints: Vec<String>) -> Self {
+        self.entrypoints = entrypoints;
+        self
+    }
+
+    /// Functions the analysis is restricted to, empty if it covers all code
+    pub fn entrypoints(&self) -> &[String] {
+        &self.entrypoints
+    }
+
+    /// Run the plugins' state-dependent checks on an Anvil node set up as `settings` ask
+    pub fn with_simulation(mut self, settings: SimulationSettings) -> Self {
+        self.simulation = Some(SimulationStage::new(settings));
+        self
+    }
+
+    /// Whether contracts are also checked on a simulation node
+    pub fn simulates(&self) -> bool {
+        self.simulation.is_some()
+    }
+
+    /// Number of contract analyses held in the cache
+    pub fn cached_contracts(&self) -> usize {
+        self.contract_cache
+            .as_ref()
+            .map_or(0, |cache| cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).len())
+    }
+
+    fn cached_contract(&self, key: &str) -> Option<CachedContract> {
+        if let Some(cache) = self.contract_cache.as_ref() {
+            let cache = cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if let Some(cached) = cache.get(key) {
+                return Some(cached.clone());
+            }
+        }
+        self.disk_cache.as_ref()?.get(key)
+    }
+
+    fn cache_contract(&self, key: String, analysis: CachedContract) {
+        if let Some(disk_cache) = self.disk_cache.as_ref() {
+            if let Err(e) = disk_cache.put(&key, &analysis) {
+                log::debug!("Failed to write analysis cache: {}", e);
+            }
+        }
+        if let Some(cache) = self.contract_cache.as_ref() {
+            let mut cache = cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if cache.len() >= MAX_CACHED_CONTRACTS {
+                cache.clear();
+            }
+            cache.insert(key, analysis);
         }
     }
 
@@ -89,10 +519,18 @@ impl AnalysisEngine {
         depth: &str,
         use_ai: bool,
     ) -> Result<AnalysisResults> {
+        if use_ai && !cfg!(feature = "ai") {
+            return Err(anyhow!("AI analysis needs a build with the `ai` feature"));
+        }
         let start_time = std::time::Instant::now();
+        #[cfg(feature = "ai")]
+        self.ai_assistant.resume_usage(None);
         
-        println!("🔍 Starting security analysis...");
-        
+        status!("🔍 Starting security analysis...");
+        let manifest = RunManifest::capture(&self.config, input_path, target, depth).await;
+        let capabilities = Capabilities::from_manifest(&manifest);
+        capabilities.announce(&self.config);
+
         // Fetch contracts
         let fetcher = ContractFetcher::new(self.config.clone());
         let contracts = fetcher.fetch_from_local(input_path.to_str().unwrap()).await?;
@@ -101,52 +539,305 @@ impl AnalysisEngine {
             return Err(anyhow!("No contracts found in the specified path"));
         }
 
+        // Shaken down to what the entrypoints reach before any tool sees the code
+        let subgraph = if self.entrypoints.is_empty() {
+            None
+        } else {
+            let subgraph = Subgraph::resolve(&self.contract_parser, &contracts, &self.entrypoints)?;
+            status!(
+                "🌳 Entrypoints {}: {} of {} functions reached",
+                subgraph.entrypoints.join(", "),
+                subgraph.reached,
+                subgraph.functions
+            );
+            Some(subgraph)
+        };
+        let contracts = match &subgraph {
+            Some(subgraph) => subgraph.shake(contracts),
+            None => contracts,
+        };
+
         let mut all_vulnerabilities = Vec::new();
-        let mut tools_used = Vec::new();
+        let mut completeness = Completeness::new(contracts.len());
         let mut total_functions = 0;
         let mut total_lines = 0;
+        let mut parsed_contracts = Vec::new();
+        let mut contract_results = Vec::new();
+        let mut produced_by = HashMap::new();
+        let mut fuzz_coverages = Vec::new();
+
+        // AI stages need a backend that answers; without one they are skipped and the reason recorded
+        #[cfg(feature = "ai")]
+        let mut ai_unavailable = None;
+        #[cfg(feature = "ai")]
+        let use_ai = use_ai && match self.select_ai_backend().await {
+            Ok(_) => true,
+            Err(unavailable) => {
+                ai_unavailable = Some(unavailable.to_string());
+                false
+            }
+        };
 
-        // Analyze each contract
-        for contract in &contracts {
-            println!("📄 Analyzing contract: {}", contract.name);
-            
-            // Parse contract
-            let parsed_contract = self.contract_parser.parse_contract(contract)?;
-            total_functions += parsed_contract.functions.len();
-            total_lines += parsed_contract.source_code.lines().count();
+        let lines_of_code = contracts.iter().map(|contract| contract.source_code.lines().count()).sum();
+        let mut timer = StageTimer::start(&self.config, RunProfile::new("analyze", target, depth, use_ai, false), lines_of_code);
+
+        // Analyze contracts concurrently, up to `general.max_concurrent_tasks` at a time
+        let workers = self.config.general.max_concurrent_tasks.max(1);
+        if contracts.len() > 1 {
+            status!("📄 Analyzing {} contracts, {} at a time", contracts.len(), workers.min(contracts.len()));
+        }
+        let mut progress = Progress::new("Analyzing", contracts.len());
+        let (run_manifest, run_capabilities, run_subgraph) = (&manifest, &capabilities, subgraph.as_ref());
+        // Built up front rather than in a stream combinator, whose closure would make the run's future
+        // not general over lifetimes, and so not spawnable by the daemon and the HTTP server
+        let pending: Vec<_> = contracts
+            .iter()
+            .enumerate()
+            .map(|(index, contract)| async move {
+                let analysis = self
+                    .analyze_contract(contract, target, depth, use_ai, run_manifest, run_capabilities, run_subgraph)
+                    .await;
+                analysis.map(|analysis| (index, analysis))
+            })
+            .collect();
+        let mut analyses = stream::iter(pending).buffer_unordered(workers);
+        let mut finished = Vec::with_capacity(contracts.len());
+        while let Some((index, analysis)) = analyses.try_next().await? {
+            let contract_lines = analysis.parsed.source_code.lines().count();
+            if analysis.cached {
+                timer.skip(&["static analysis", "dynamic analysis", "AI analysis"], contract_lines);
+            } else {
+                timer.finish_concurrent(&analysis.stages, contract_lines);
+            }
+            status!(
+                "   ✓ {}: {} findings in {:.1}s{}",
+                analysis.parsed.name,
+                analysis.vulnerabilities.len(),
+                analysis.duration.as_secs_f64(),
+                if analysis.cached { " (unchanged, reused)" } else { "" }
+            );
+            progress.advance(&analysis.parsed.name);
+            finished.push((index, analysis));
+            budget::check()?;
+        }
+        drop(analyses);
+        drop(progress);
+
+        // Merged in input order, so results do not depend on which contract finished first
+        finished.sort_by_key(|(index, _)| *index);
+        for (index, analysis) in finished {
+            let contract = &contracts[index];
+            let contract_lines = analysis.parsed.source_code.lines().count();
+            total_functions += analysis.parsed.functions.len();
+            total_lines += contract_lines;
+            produced_by.extend(analysis.vulnerabilities.iter().map(|vuln| (vuln.id.clone(), contract_results.len())));
+            contract_results.push(ContractAnalysisResults {
+                name: analysis.parsed.name.clone(),
+                file: contract.metadata.get("path").cloned().unwrap_or_else(|| contract.name.clone()),
+                lines_of_code: contract_lines,
+                findings: 0,
+                critical: 0,
+                high: 0,
+                medium: 0,
+                low: 0,
+                info: 0,
+                vulnerability_ids: Vec::new(),
+                security_score: None,
+                duration: analysis.duration.as_secs_f64(),
+                cached: analysis.cached,
+            });
+            all_vulnerabilities.extend(analysis.vulnerabilities);
+            completeness.tool_runs.extend(analysis.tool_runs);
+            fuzz_coverages.extend(analysis.fuzz_coverage);
+            parsed_contracts.push(analysis.parsed);
+        }
+
+        // A Foundry project's own test coverage, for the contracts fuzzing measured nothing of
+        if target == "evm" {
+            fuzz_coverages.extend(project_coverage(input_path, &contracts, &parsed_contracts, &fuzz_coverages));
+        }
 
-            // Run static analysis based on target platform
-            let static_vulnerabilities = self.run_static_analysis(&parsed_contract, target, depth).await?;
-            all_vulnerabilities.extend(static_vulnerabilities);
+        // Selector checks need every contract of the run at once
+        let mut selector_routes = Vec::new();
+        if target == "evm" {
+            let selector_analysis = selectors::analyze_selectors(&parsed_contracts)?;
+            all_vulnerabilities.extend(selector_analysis.findings);
+            selector_routes = selector_analysis.routes;
+            completeness.completed("Selector Analysis", None);
+        }
 
-            // Run dynamic analysis if requested
-            if depth == "deep" {
-                let dynamic_vulnerabilities = self.run_dynamic_analysis(&parsed_contract, target).await?;
-                all_vulnerabilities.extend(dynamic_vulnerabilities);
+        let graph = CallGraph::build(&parsed_contracts);
+        if target == "evm" {
+            all_vulnerabilities.extend(dead_code::detect_dead_code(&parsed_contracts, &graph));
+            completeness.completed("Dead Code Analysis", None);
+
+            // A proxy and its implementation are only understood together
+            all_vulnerabilities.extend(proxy::check_proxies(&parsed_contracts));
+            completeness.completed(proxy::TOOL_NAME, None);
+        }
+        // Matched after caching as well, so importing a pack applies to unchanged contracts
+        let packs = if target == "evm" { community::installed() } else { Vec::new() };
+        if !packs.is_empty() {
+            let applied = community::apply(&packs, &parsed_contracts, &mut all_vulnerabilities);
+            if applied.added + applied.suppressed > 0 {
+                status!(
+                    "🤝 Community rules: {} finding(s) added, {} known false positive(s) dropped",
+                    applied.added,
+                    applied.suppressed
+                );
             }
+            completeness.completed(community::TOOL_NAME, None);
+        }
+        // Run-level checks see the blanked functions too
+        if let Some(subgraph) = &subgraph {
+            subgraph.retain(&mut all_vulnerabilities);
+        }
+        // Filtered after caching, so re-enabling a detector needs no cache reset
+        detector_catalog::retain_enabled(&mut all_vulnerabilities, &self.config);
+        let reported = all_vulnerabilities.len();
+        all_vulnerabilities = dedup::merge_duplicates(all_vulnerabilities);
+        if all_vulnerabilities.len() < reported {
+            status!("🔗 Merged {} finding(s) reported by more than one tool", reported - all_vulnerabilities.len());
+        }
+        let files: HashMap<String, String> =
+            produced_by.iter().map(|(id, index)| (id.clone(), contract_results[*index].file.clone())).collect();
+        let Applied { suppressed, expired: expired_acceptances } = suppress(input_path, &manifest, &mut all_vulnerabilities, &[], &files)?;
+        if !expired_acceptances.is_empty() {
+            let notification = suppression::expiry_notification(&input_path.display().to_string(), &expired_acceptances);
+            let sent = notify::send(&self.config.notifications, &notification);
+            if sent > 0 {
+                status!("📣 Expired acceptances sent to {} webhook(s)", sent);
+            }
+        }
+        manifest.stamp(&mut all_vulnerabilities);
+        attribute_findings(&mut contract_results, &all_vulnerabilities, &produced_by);
+
+        let contract_names: Vec<String> = parsed_contracts.iter().map(|contract| contract.name.clone()).collect();
+        // Recorded outside the cache, so a later run with a reachable backend is not told otherwise
+        #[cfg(feature = "ai")]
+        if let Some(reason) = ai_unavailable {
+            for name in &contract_names {
+                completeness.unreachable("AI Assistant", Some(name), &reason);
+            }
+            completeness.ai_unavailable = Some(reason);
+        }
+        completeness.finalize(&contract_names);
+        if self.config.general.metrics_enabled {
+            metrics::note_tool_runs(&self.config, &completeness);
+        }
+        status!("📏 Analysis completeness: {:.1}%", completeness.score);
+        for run in completeness.incomplete_runs() {
+            let reason = run.reason.map(|reason| reason.to_string()).unwrap_or_default();
+            status!("   ⚠️  {} on {}: {}", run.tool, run.contract.as_deref().unwrap_or("all contracts"), reason);
+        }
+        let warnings = partial_results(&completeness);
+        for warning in &warnings {
+            status!("   ⏳ {}", warning);
+        }
+        let ai_usage = self.ai_usage();
+        if let Some(usage) = &ai_usage {
+            status!(
+                "💵 AI usage: {} requests, {} prompt and {} completion tokens, about ${:.2}",
+                usage.requests,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.cost
+            );
+        }
 
-            // Run AI-powered analysis if requested
-            if use_ai {
-                println!("🧠 Running AI-powered analysis...");
-                let ai_vulnerabilities = self.ai_assistant.analyze_contract(&parsed_contract).await?;
-                all_vulnerabilities.extend(ai_vulnerabilities);
-                tools_used.push("AI Assistant".to_string());
+        let exploitability = exploitability::assess(&graph, &all_vulnerabilities);
+        cvss::score_builtin(&mut all_vulnerabilities, &exploitability);
+        vulnerability::utils::sort_vulnerabilities(&mut all_vulnerabilities);
+        let mut project_metadata = metadata::extract(input_path, &parsed_contracts);
+        match ProtocolProject::load(input_path) {
+            Ok(Some(project)) => {
+                project.annotate(&mut project_metadata);
+                let unverified: Vec<String> = project
+                    .with_status(FetchStatus::Unverified)
+                    .map(|contract| format!("{} ({})", contract.name, contract.network))
+                    .collect();
+                status!("🏛️  Protocol {}: {} named contracts", project.name, project.contracts.len());
+                if !unverified.is_empty() {
+                    status!("⚠️  Without verified source, not in this analysis: {}", unverified.join(", "));
+                }
             }
+            Ok(None) => {}
+            Err(e) => log::warn!("Ignoring the protocol project in {}: {}", input_path.display(), e),
+        }
+        let integration = integration::profile(&parsed_contracts, &exploitability);
+        let architecture = Some(Architecture::build(&parsed_contracts)).filter(|architecture| !architecture.components.is_empty());
+        let test_review = if target == "evm" { test_review::review(input_path, &contracts, &parsed_contracts) } else { None };
+        if let Some(review) = &test_review {
+            status!(
+                "🧪 Test suite: {} blind spots in {} of {} tests",
+                review.blind_spots.len(),
+                review.tests_affected,
+                review.tests_reviewed
+            );
+        }
+        let error_handling = if target == "evm" { error_handling::assess(&parsed_contracts) } else { None };
+        if let Some(quality) = &error_handling {
+            status!(
+                "🧯 Error handling: {:.1}/100, {} of {} checks without a reason, {} custom errors",
+                quality.score,
+                quality.without_reason,
+                quality.requires + quality.reverts,
+                quality.custom_errors
+            );
+        }
+        let inventory = if target == "evm" { Some(inventory::build(&parsed_contracts, &exploitability)) } else { None };
+        let inventory = inventory.filter(|inventory| !inventory.is_empty());
+        if let Some(inventory) = &inventory {
+            status!(
+                "📇 Inventory: {} custom errors, {} revert strings, {} events",
+                inventory.errors.len(),
+                inventory.revert_strings.len(),
+                inventory.events.len()
+            );
         }
+        let gas_optimization_score = if target == "evm" { gas::score(&parsed_contracts) } else { 0.0 };
+        if target == "evm" {
+            status!("⛽ Gas optimization: {:.1}/100", gas_optimization_score);
+        }
+        let sources = contracts
+            .iter()
+            .map(|contract| SourceFile {
+                name: contract.name.clone(),
+                path: contract.metadata.get("path").cloned().unwrap_or_else(|| contract.name.clone()),
+                content: contract.source_code.clone(),
+            })
+            .collect();
+
+        timer.finish_stage("cross-contract analysis", lines_of_code);
+        timer.finish();
 
         // Calculate metrics
         let analysis_duration = start_time.elapsed().as_secs_f64();
         let security_score = self.calculate_security_score(&all_vulnerabilities);
+        let normalized_security_score = normalized_security_score(&all_vulnerabilities, total_lines);
         let complexity_score = self.calculate_complexity_score(total_functions, total_lines);
 
         // Generate summary
-        let analysis_summary = self.generate_analysis_summary(&all_vulnerabilities, analysis_duration, &tools_used);
+        let tools_used = completeness.tools_completed();
+        let analysis_summary = self.generate_analysis_summary(
+            &all_vulnerabilities,
+            analysis_duration,
+            &tools_used,
+            warnings,
+            fuzz_coverage::line_coverage(&fuzz_coverages),
+        );
         
         // Generate recommendations
         let recommendations = self.generate_recommendations(&all_vulnerabilities);
 
+        // A multi-contract run is named after the project rather than whichever contract came first
+        let contract_name = match contracts.as_slice() {
+            [contract] => contract.name.clone(),
+            _ => project_metadata.title.clone(),
+        };
+
         Ok(AnalysisResults {
-            contract_name: contracts[0].name.clone(),
+            contract_name,
             vulnerabilities: all_vulnerabilities,
             analysis_summary,
             recommendations,
@@ -155,13 +846,516 @@ impl AnalysisEngine {
                 functions_analyzed: total_functions,
                 complexity_score,
                 security_score,
-                gas_optimization_score: 0.0, // TODO: Implement gas analysis
+                normalized_security_score: Some(normalized_security_score),
+                gas_optimization_score,
+            },
+            timestamp: chrono::Utc::now(),
+            deployment_parameters: Vec::new(),
+            selector_routes,
+            diamond: None,
+            manifest: Some(manifest),
+            exploitability,
+            completeness: Some(completeness),
+            metadata: Some(project_metadata),
+            sources,
+            integration,
+            architecture,
+            test_review,
+            contracts: contract_results,
+            fuzz_coverage: fuzz_coverages,
+            baseline: None,
+            error_handling,
+            inventory,
+            poc_flags: HashMap::new(),
+            poc_verification: HashMap::new(),
+            imports: Vec::new(),
+            entrypoints: self.entrypoints.clone(),
+            suppressed,
+            expired_acceptances,
+            ai_usage,
+            bytecode: None,
+        })
+    }
+
+    /// Tokens and estimated cost of the AI requests of the current run, if it made any
+    pub fn ai_usage(&self) -> Option<AiUsage> {
+        #[cfg(feature = "ai")]
+        {
+            Some(self.ai_assistant.usage()).filter(|usage| usage.requests > 0 || usage.refused > 0)
+        }
+        #[cfg(not(feature = "ai"))]
+        {
+            None
+        }
+    }
+
+    /// Count further AI requests on top of `usage`, within what is left of the budget
+    #[cfg(feature = "ai")]
+    pub fn resume_ai_usage(&self, usage: Option<&AiUsage>) {
+        self.ai_assistant.resume_usage(usage);
+    }
+
+    /// Analyse one contract of a run on its own, reusing a cached analysis when it is unchanged
+    #[allow(clippy::too_many_arguments)]
+    async fn analyze_contract(
+        &self,
+        contract: &ContractInfo,
+        target: &str,
+        depth: &str,
+        use_ai: bool,
+        manifest: &RunManifest,
+        capabilities: &Capabilities,
+        subgraph: Option<&Subgraph>,
+    ) -> Result<ContractAnalysis> {
+        let started = Instant::now();
+        let parsed_contract = self.contract_parser.parse_contract(contract)?;
+
+        // Chain state changes between runs, so simulated analyses are never reused
+        let cache_key = (self.simulation.is_none() && (self.contract_cache.is_some() || self.disk_cache.is_some()))
+            .then(|| contract_cache_key(manifest, &parsed_contract, use_ai));
+        if let Some(cached) = cache_key.as_deref().and_then(|key| self.cached_contract(key)) {
+            return Ok(ContractAnalysis {
+                parsed: parsed_contract,
+                vulnerabilities: cached.vulnerabilities,
+                tool_runs: cached.tool_runs,
+                fuzz_coverage: cached.fuzz_coverage,
+                stages: Vec::new(),
+                duration: started.elapsed(),
+                cached: true,
+            });
+        }
+
+        let mut vulnerabilities = Vec::new();
+        let mut completeness = Completeness::new(1);
+        let mut fuzz_coverage = None;
+        let mut stages = Vec::new();
+
+        // Run static analysis based on target platform
+        let stage_started = Instant::now();
+        vulnerabilities.extend(
+            self.run_static_analysis(
+                &parsed_contract,
+                Path::new(&manifest.input_path),
+                target,
+                depth,
+                capabilities,
+                &mut completeness,
+            )
+            .await?,
+        );
+        stages.push(("static analysis", stage_started.elapsed()));
+
+        // Run dynamic analysis if requested
+        if depth == "deep" {
+            let stage_started = Instant::now();
+            vulnerabilities.extend(
+                self.run_dynamic_analysis(&parsed_contract, target, subgraph, &mut completeness, &mut fuzz_coverage)
+                    .await?,
+            );
+            stages.push(("dynamic analysis", stage_started.elapsed()));
+        } else if target == "evm" {
+            completeness.not_requested("Echidna", Some(&parsed_contract.name), SkipReason::DepthTooLow);
+        }
+
+        // State-dependent checks on the simulation node
+        if let Some(stage) = &self.simulation {
+            let name = Some(parsed_contract.name.as_str());
+            if target != "evm" || parsed_contract.is_vyper() {
+                completeness.not_requested(simulation::TOOL_NAME, name, SkipReason::UnsupportedLanguage);
+            } else {
+                status!("  🔱 Running state-dependent checks on Anvil...");
+                let stage_started = Instant::now();
+                match stage.run(&self.config.tools, &self.plugin_manager, target, &parsed_contract).await {
+                    Ok(simulated) => {
+                        vulnerabilities.extend(simulated);
+                        completeness.completed(simulation::TOOL_NAME, name);
+                    }
+                    Err(e) => {
+                        log::warn!("Simulation skipped: {}", e);
+                        completeness.failed(simulation::TOOL_NAME, name, &e);
+                    }
+                }
+                stages.push(("simulation", stage_started.elapsed()));
+            }
+        }
+
+        // Run AI-powered analysis if requested
+        #[cfg(feature = "ai")]
+        if use_ai {
+            status!("🧠 Running AI-powered analysis of {}...", parsed_contract.name);
+            let stage_started = Instant::now();
+            match self.ai_assistant.analyze_contract(&parsed_contract).await {
+                Ok((ai_vulnerabilities, None)) => {
+                    vulnerabilities.extend(ai_vulnerabilities);
+                    completeness.completed("AI Assistant", Some(&parsed_contract.name));
+                }
+                // Malformed answers are dropped, so their part of the contract went unanalyzed
+                Ok((ai_vulnerabilities, Some(discarded))) => {
+                    vulnerabilities.extend(ai_vulnerabilities);
+                    completeness.partial("AI Assistant", Some(&parsed_contract.name), &discarded);
+                }
+                Err(e) => {
+                    status!("   ⚠️  AI analysis of {} failed: {}", parsed_contract.name, e);
+                    completeness.failed("AI Assistant", Some(&parsed_contract.name), &e);
+                }
+            }
+            stages.push(("AI analysis", stage_started.elapsed()));
+        }
+
+        // Stamped before caching, so reused findings keep the run that first produced them
+        manifest.stamp(&mut vulnerabilities);
+        // A tool stopped by the budget may well finish under a larger one
+        let over_budget = completeness.tool_runs.iter().any(|run| run.reason == Some(SkipReason::BudgetExceeded));
+        if let Some(key) = cache_key.filter(|_| !over_budget) {
+            self.cache_contract(
+                key,
+                CachedContract {
+                    vulnerabilities: vulnerabilities.clone(),
+                    tool_runs: completeness.tool_runs.clone(),
+                    fuzz_coverage: fuzz_coverage.clone(),
+                },
+            );
+        }
+
+        Ok(ContractAnalysis {
+            parsed: parsed_contract,
+            vulnerabilities,
+            tool_runs: completeness.tool_runs,
+            fuzz_coverage,
+            stages,
+            duration: started.elapsed(),
+            cached: false,
+        })
+    }
+
+    /// Compare the findings with those of an earlier run, carrying over when
+    /// persistent findings were first seen and their adjusted severities
+    pub fn apply_baseline(&self, results: &mut AnalysisResults, baseline_path: &Path) -> Result<()> {
+        let (_, prior) = remediation::load_findings(baseline_path)?;
+        let comparison = baseline::compare(&baseline_path.display().to_string(), &prior, &mut results.vulnerabilities);
+        status!(
+            "📊 Since the baseline: {} new, {} fixed, {} persistent",
+            comparison.new.len(),
+            comparison.fixed.len(),
+            comparison.persistent.len()
+        );
+        results.baseline = Some(comparison);
+        self.rescore(results);
+        Ok(())
+    }
+
+    /// Decode the constructor arguments of a fetched contract and add
+    /// findings for suspicious deployment parameters
+    pub fn apply_deployment_analysis(&self, results: &mut AnalysisResults, contract: &ContractInfo) -> Result<()> {
+        if let Some(metadata) = results.metadata.as_mut() {
+            metadata.set_deployment(contract);
+        }
+
+        let analysis = match deployment::analyze_deployment(contract)? {
+            Some(analysis) => analysis,
+            None => return Ok(()),
+        };
+
+        results.vulnerabilities.extend(analysis.findings);
+        results.deployment_parameters = analysis.parameters;
+        self.refresh_summary(results, "Deployment Check");
+
+        Ok(())
+    }
+
+    /// Analyse a contract without verified source from its deployed bytecode alone
+    pub fn analyze_bytecode(&self, analysis: BytecodeAnalysis) -> Result<AnalysisResults> {
+        let start_time = std::time::Instant::now();
+        let named = analysis.selectors.iter().filter(|resolved| !resolved.signatures.is_empty()).count();
+        status!(
+            "🧬 {} bytes of bytecode, {} functions ({} named)",
+            analysis.size,
+            analysis.selectors.len(),
+            named
+        );
+
+        let mut completeness = Completeness::new(1);
+        let vulnerabilities = match self.plugin_manager.bytecode_checks(&analysis, "evm") {
+            Ok(vulnerabilities) => {
+                completeness.completed(bytecode::TOOL_NAME, Some(&analysis.address));
+                vulnerabilities
+            }
+            Err(e) => {
+                completeness.failed(bytecode::TOOL_NAME, Some(&analysis.address), &e);
+                Vec::new()
+            }
+        };
+
+        let warnings = vec![format!(
+            "{} has no verified source: only its bytecode was analysed, so findings cannot show who can reach the code they point at",
+            analysis.address
+        )];
+        let tools_used = completeness.tools_completed();
+        let analysis_summary =
+            self.generate_analysis_summary(&vulnerabilities, start_time.elapsed().as_secs_f64(), &tools_used, warnings, None);
+
+        Ok(AnalysisResults {
+            contract_name: analysis.address.clone(),
+            recommendations: self.generate_recommendations(&vulnerabilities),
+            analysis_summary,
+            metrics: AnalysisMetrics {
+                lines_of_code: 0,
+                functions_analyzed: analysis.selectors.len(),
+                complexity_score: 0.0,
+                security_score: self.calculate_security_score(&vulnerabilities),
+                normalized_security_score: None,
+                gas_optimization_score: 0.0,
             },
+            vulnerabilities,
             timestamp: chrono::Utc::now(),
+            deployment_parameters: Vec::new(),
+            selector_routes: Vec::new(),
+            diamond: None,
+            manifest: None,
+            exploitability: HashMap::new(),
+            completeness: Some(completeness),
+            metadata: None,
+            sources: Vec::new(),
+            integration: Vec::new(),
+            architecture: None,
+            test_review: None,
+            contracts: Vec::new(),
+            fuzz_coverage: Vec::new(),
+            baseline: None,
+            error_handling: None,
+            inventory: None,
+            poc_flags: HashMap::new(),
+            poc_verification: HashMap::new(),
+            imports: Vec::new(),
+            entrypoints: Vec::new(),
+            suppressed: Vec::new(),
+            expired_acceptances: Vec::new(),
+            ai_usage: None,
+            bytecode: Some(analysis),
         })
     }
 
+    /// Analyse the contracts at `input_path` as one EIP-2535 diamond, optionally
+    /// against the facets and cut history of the deployed diamond
+    pub async fn apply_diamond_analysis(
+        &self,
+        results: &mut AnalysisResults,
+        input_path: &Path,
+        deployment: Option<&DiamondDeployment>,
+    ) -> Result<()> {
+        status!("💎 Reconstructing diamond...");
+
+        let fetcher = ContractFetcher::new(self.config.clone());
+        let contracts = fetcher.fetch_from_local(input_path.to_str().unwrap()).await?;
+        let parsed_contracts = contracts
+            .iter()
+            .map(|contract| self.contract_parser.parse_contract(contract))
+            .collect::<Result<Vec<_>>>()?;
+
+        let analysis = diamond::analyze_diamond(&parsed_contracts, deployment)?;
+        status!(
+            "💎 {} facets, {} selectors, {} storage layouts",
+            analysis.system.facets.len(),
+            analysis.system.routes.len(),
+            analysis.system.storage.len()
+        );
+
+        results.vulnerabilities.extend(analysis.findings);
+        results.selector_routes = analysis.system.routes.clone();
+        if let Some(metadata) = results.metadata.as_mut() {
+            for facet in &analysis.system.facets {
+                if let (Some(name), Some(address)) = (&facet.name, &facet.address) {
+                    metadata.set_address(name, address);
+                }
+            }
+        }
+        results.diamond = Some(analysis.system);
+        self.refresh_summary(results, "Diamond Analysis");
+
+        Ok(())
+    }
+
+    /// AI review comments on the functions under `input_path`, with file paths under `repo_prefix`
+    #[cfg(feature = "ai")]
+    pub async fn review_contracts(&self, input_path: &Path, repo_prefix: &str) -> Result<Vec<ReviewComment>> {
+        self.select_ai_backend().await.map_err(|unavailable| anyhow!("AI analysis unavailable: {}", unavailable))?;
+
+        let fetcher = ContractFetcher::new(self.config.clone());
+        let contracts = fetcher.fetch_from_local(input_path.to_str().unwrap()).await?;
+        if contracts.is_empty() {
+            return Err(anyhow!("No contracts found in the specified path"));
+        }
+
+        let mut comments = Vec::new();
+        for contract in &contracts {
+            let parsed_contract = self.contract_parser.parse_contract(contract)?;
+            let relative = contract.metadata.get("path").unwrap_or(&contract.name);
+            let path = format!("{}{}", repo_prefix, relative.replace('\\', "/"));
+            comments.extend(self.ai_assistant.review_contract(&parsed_contract, &path).await?);
+        }
+        Ok(comments)
+    }
+
+    /// Re-run the tools behind `prior` findings on the fixed code at `input_path`
+    /// and mark each finding fixed, not fixed, regressed or unverified
+    pub async fn verify_fixes(&self, project: String, prior: &[Vulnerability], input_path: &Path) -> Result<RemediationResults> {
+        // In the order of RERUNNABLE_TOOLS, so Slither has run before the checks that depend on it
+        let tools: Vec<String> = remediation::RERUNNABLE_TOOLS
+            .iter()
+            .filter(|tool| prior.iter().any(|finding| finding.tool == **tool))
+            .map(|tool| tool.to_string())
+            .collect();
+        let reruns = |tool: &str| tools.iter().any(|t| t == tool);
+        status!("🔁 Re-running {} on the fixed code...", if tools.is_empty() { "no tools".to_string() } else { tools.join(", ") });
+
+        let manifest = RunManifest::capture(&self.config, input_path, "evm", "deep").await;
+        let capabilities = Capabilities::from_manifest(&manifest);
+        capabilities.announce(&self.config);
+        let fetcher = ContractFetcher::new(self.config.clone());
+        let contracts = fetcher.fetch_from_local(input_path.to_str().unwrap()).await?;
+        if contracts.is_empty() {
+            return Err(anyhow!("No contracts found in the specified path"));
+        }
+        let parsed_contracts = contracts
+            .iter()
+            .map(|contract| self.contract_parser.parse_contract(contract))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut completeness = Completeness::new(parsed_contracts.len());
+        let mut current = Vec::new();
+        for contract in &parsed_contracts {
+            let name = Some(contract.name.as_str());
+            let mut contract_findings = Vec::new();
+            let mut lint_findings = Vec::new();
+
+            for tool in &tools {
+                let outcome = match tool.as_str() {
+                    "Slither" => self.run_slither(contract, input_path, &capabilities).await,
+                    "Native Detectors" => Ok(detectors::run_detectors(contract)),
+                    "Parameter Validation" => Ok(validation::check_parameters(contract, !completeness.completed_on("Slither", &contract.name))),
+                    "Error Handling" => Ok(error_handling::check_errors(contract)),
+                    "Gas Analysis" => Ok(gas::check_gas(contract)),
+                    "Mythril" => self.run_mythril(contract, &capabilities).await.map(|(findings, _)| findings),
+                    "Echidna" => self.run_echidna(contract, None).await.map(|(findings, _)| findings),
+                    #[cfg(feature = "execution")]
+                    "Arithmetic Execution" => self.run_arithmetic(contract).await,
+                    "Solhint" => self.run_solhint(contract).await,
+                    "Semgrep" => self.run_semgrep(contract).await,
+                    // Warnings of a partial compilation cannot show that a finding is gone
+                    "Solc" => match self.run_solc(contract).await {
+                        Ok((_, Some(errors))) => Err(anyhow!("compilation failed: {}", errors)),
+                        Ok((findings, None)) => Ok(findings),
+                        Err(e) => Err(e),
+                    },
+                    _ => continue,
+                };
+
+                match outcome {
+                    Ok(findings) if matches!(tool.as_str(), "Solhint" | "Semgrep" | "Solc") => {
+                        lint_findings.extend(findings);
+                        completeness.completed(tool, name);
+                    }
+                    Ok(findings) => {
+                        contract_findings.extend(findings);
+                        completeness.completed(tool, name);
+                    }
+                    Err(e) => {
+                        log::warn!("{} re-run skipped: {}", tool, e);
+                        completeness.failed(tool, name, &e);
+                    }
+                }
+            }
+
+            // Deduplicate as the original analysis did, so removed duplicates do not resurface as new
+            let lint_findings = self.dedupe_against_slither(lint_findings, &contract_findings);
+            current.extend(contract_findings);
+            current.extend(lint_findings);
+        }
+
+        if reruns("Selector Analysis") {
+            current.extend(selectors::analyze_selectors(&parsed_contracts)?.findings);
+            completeness.completed("Selector Analysis", None);
+        }
+        if reruns("Dead Code Analysis") {
+            let graph = CallGraph::build(&parsed_contracts);
+            current.extend(dead_code::detect_dead_code(&parsed_contracts, &graph));
+            completeness.completed("Dead Code Analysis", None);
+        }
+        if reruns(proxy::TOOL_NAME) {
+            current.extend(proxy::check_proxies(&parsed_contracts));
+            completeness.completed(proxy::TOOL_NAME, None);
+        }
+
+        let contract_names: Vec<String> = parsed_contracts.iter().map(|contract| contract.name.clone()).collect();
+        completeness.finalize(&contract_names);
+        if self.config.general.metrics_enabled {
+            metrics::note_tool_runs(&self.config, &completeness);
+        }
+
+        detector_catalog::retain_enabled(&mut current, &self.config);
+        let mut current = dedup::merge_duplicates(current);
+        suppress(input_path, &manifest, &mut current, &[], &HashMap::new())?;
+        cvss::score_builtin(&mut current, &HashMap::new());
+        manifest.stamp(&mut current);
+        let (verifications, new_findings) = remediation::verify(prior, &current, &completeness);
+        Ok(RemediationResults {
+            project,
+            verified_at: chrono::Utc::now(),
+            verifications,
+            new_findings,
+            tools_rerun: completeness.tools_completed(),
+            completeness,
+            behavior_drift: Vec::new(),
+        })
+    }
+
+    /// Recompute summary, recommendations and score after findings were added by `tool`
+    fn refresh_summary(&self, results: &mut AnalysisResults, tool: &str) {
+        detector_catalog::retain_enabled(&mut results.vulnerabilities, &self.config);
+        results.vulnerabilities = dedup::merge_duplicates(std::mem::take(&mut results.vulnerabilities));
+        if let Some(manifest) = &results.manifest {
+            let files: HashMap<String, String> =
+                results.finding_files().into_iter().map(|(id, file)| (id.to_string(), file.to_string())).collect();
+            match suppress(Path::new(&manifest.input_path), manifest, &mut results.vulnerabilities, &results.suppressed, &files) {
+                Ok(applied) => {
+                    results.suppressed.extend(applied.suppressed);
+                    for expired in applied.expired {
+                        if !results.expired_acceptances.iter().any(|known| known.finding_id == expired.finding_id) {
+                            results.expired_acceptances.push(expired);
+                        }
+                    }
+                }
+                Err(e) => status!("⚠️  {}", e),
+            }
+            manifest.stamp(&mut results.vulnerabilities);
+        }
+        cvss::score_builtin(&mut results.vulnerabilities, &results.exploitability);
+        vulnerability::utils::sort_vulnerabilities(&mut results.vulnerabilities);
+        results.analysis_summary.tools_used.push(tool.to_string());
+        if let Some(completeness) = results.completeness.as_mut() {
+            completeness.completed(tool, None);
+            completeness.rescore();
+        }
+        self.rescore(results);
+    }
+
+    /// Recompute summary, recommendations and score from the findings as they now stand
+    pub fn rescore(&self, results: &mut AnalysisResults) {
+        results.analysis_summary = self.generate_analysis_summary(
+            &results.vulnerabilities,
+            results.analysis_summary.analysis_duration,
+            &results.analysis_summary.tools_used,
+            results.analysis_summary.warnings.clone(),
+            fuzz_coverage::line_coverage(&results.fuzz_coverage),
+        );
+        results.recommendations = self.generate_recommendations(&results.vulnerabilities);
+        results.metrics.security_score = self.calculate_security_score(&results.vulnerabilities);
+        results.metrics.normalized_security_score =
+            Some(normalized_security_score(&results.vulnerabilities, results.metrics.lines_of_code));
+    }
+
     /// Generate creative exploit probes using AI
+    #[cfg(feature = "ai")]
     pub async fn generate_creative_probes(
         &self,
         input_path: &Path,
@@ -169,7 +1363,12 @@ impl AnalysisEngine {
         llm_backend: &str,
         generate_poc: bool,
     ) -> Result<Vec<CreativeProbe>> {
-        println!("🎯 Generating creative vulnerability probes...");
+        status!("🎯 Generating creative vulnerability probes...");
+        let llm_backend = match self.select_ai_backend().await {
+            Ok(backend) if backend != self.config.ai.backend => backend,
+            Ok(_) => llm_backend.to_string(),
+            Err(unavailable) => return Err(anyhow!("AI analysis unavailable: {}", unavailable)),
+        };
 
         // Fetch and parse contracts
         let fetcher = ContractFetcher::new(self.config.clone());
@@ -179,57 +1378,228 @@ impl AnalysisEngine {
             return Err(anyhow!("No contracts found in the specified path"));
         }
 
-        let mut all_probes = Vec::new();
+        let parsed_contracts = contracts
+            .iter()
+            .map(|contract| self.contract_parser.parse_contract(contract))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Near-identical contracts share one AI query
+        let clusters = clustering::cluster_contracts(&parsed_contracts, self.config.ai.probe_cluster_threshold);
+        if clusters.len() < parsed_contracts.len() {
+            status!(
+                "🧩 {} contracts grouped into {} clusters, querying the AI {} times",
+                parsed_contracts.len(),
+                clusters.len(),
+                clusters.len()
+            );
+        }
 
-        for contract in &contracts {
-            let parsed_contract = self.contract_parser.parse_contract(contract)?;
-            let probes = self.ai_assistant.generate_creative_probes(
-                &parsed_contract,
+        let mut all_probes = Vec::new();
+        for cluster in &clusters {
+            let representative = &parsed_contracts[cluster.representative];
+            let probes = match self.ai_assistant.generate_creative_probes(
+                representative,
                 creativity,
-                llm_backend,
+                &llm_backend,
                 generate_poc,
-            ).await?;
-            
-            all_probes.extend(probes);
+            ).await {
+                Ok(probes) => probes,
+                // Out of budget: the probes so far are kept, the remaining clusters skipped
+                Err(e) if e.is::<BudgetExceeded>() => {
+                    status!("   ⚠️  {}", e);
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+
+            for &(member, similarity) in &cluster.members {
+                if member == cluster.representative {
+                    all_probes.extend(probes.iter().cloned());
+                } else {
+                    let member_contract = &parsed_contracts[member];
+                    all_probes.extend(probes.iter().map(|probe| adapt_probe(probe, representative, member_contract, similarity)));
+                }
+            }
         }
 
-        println!("✨ Generated {} creative probes", all_probes.len());
+        status!("✨ Generated {} creative probes", all_probes.len());
         Ok(all_probes)
     }
 
+    /// First configured AI backend that passes its health check
+    #[cfg(feature = "ai")]
+    async fn select_ai_backend(&self) -> std::result::Result<String, AiUnavailable> {
+        let selected = ai_health::select_backend(&self.config.ai).await;
+        match &selected {
+            Ok(backend) if *backend != self.config.ai.backend => {
+                status!("🤖 AI backend {} is unreachable, falling back to {}", self.config.ai.backend, backend)
+            }
+            Ok(_) => {}
+            Err(unavailable) => status!("🤖 AI analysis unavailable: {}", unavailable),
+        }
+        if let Ok(backend) = &selected {
+            self.ai_assistant.use_backend(backend);
+        }
+        selected
+    }
+
     /// Run static analysis using various tools
     async fn run_static_analysis(
         &self,
         contract: &ParsedContract,
+        project: &Path,
         target: &str,
         depth: &str,
+        capabilities: &Capabilities,
+        completeness: &mut Completeness,
     ) -> Result<Vec<Vulnerability>> {
         let mut vulnerabilities = Vec::new();
+        let name = Some(contract.name.as_str());
 
         match target {
+            "evm" if contract.is_vyper() => {
+                vulnerabilities.extend(self.run_vyper_analysis(contract, project, capabilities, completeness).await);
+            }
             "evm" => {
                 // Run Slither
-                if let Ok(slither_results) = self.run_slither(contract).await {
-                    vulnerabilities.extend(slither_results);
+                match self.run_slither(contract, project, capabilities).await {
+                    Ok(slither_results) => {
+                        vulnerabilities.extend(slither_results);
+                        completeness.completed("Slither", name);
+                    }
+                    Err(e) => {
+                        log::warn!("Slither analysis skipped: {}", e);
+                        completeness.failed("Slither", name, &e);
+
+                        status!("  🧩 Running native detectors in place of Slither...");
+                        vulnerabilities.extend(detectors::run_detectors(contract));
+                        completeness.completed(detectors::TOOL_NAME, name);
+                    }
                 }
 
+                // Slither has its own zero-address check
+                let zero_checks = !completeness.completed_on("Slither", &contract.name);
+                vulnerabilities.extend(validation::check_parameters(contract, zero_checks));
+                completeness.completed(validation::TOOL_NAME, name);
+
+                vulnerabilities.extend(error_handling::check_errors(contract));
+                completeness.completed(error_handling::TOOL_NAME, name);
+
+                vulnerabilities.extend(gas::check_gas(contract));
+                completeness.completed(gas::TOOL_NAME, name);
+
                 // Run Mythril for deep analysis
                 if depth == "deep" {
-                    if let Ok(mythril_results) = self.run_mythril(contract).await {
-                        vulnerabilities.extend(mythril_results);
+                    match self.run_mythril(contract, capabilities).await {
+                        Ok((mythril_results, None)) => {
+                            vulnerabilities.extend(mythril_results);
+                            completeness.completed("Mythril", name);
+                        }
+                        Ok((mythril_results, Some(missed))) => {
+                            status!("    ⏳ Mythril results are partial: {}", missed);
+                            vulnerabilities.extend(mythril_results);
+                            completeness.partial("Mythril", name, &missed);
+                        }
+                        Err(e) => {
+                            log::warn!("Mythril analysis skipped: {}", e);
+                            completeness.failed("Mythril", name, &e);
+                        }
                     }
+                } else {
+                    completeness.not_requested("Mythril", name, SkipReason::DepthTooLow);
                 }
-            }
-            "move" => {
-                // Run Move Prover
-                if let Ok(move_results) = self.run_move_prover(contract).await {
-                    vulnerabilities.extend(move_results);
+
+                // Execute wrapping arithmetic rather than flag every unchecked block
+                #[cfg(feature = "execution")]
+                if !self.config.tools.arithmetic.enabled {
+                    completeness.not_requested(arithmetic::TOOL_NAME, name, SkipReason::Disabled);
+                } else if depth == "basic" {
+                    completeness.not_requested(arithmetic::TOOL_NAME, name, SkipReason::DepthTooLow);
+                } else {
+                    match self.run_arithmetic(contract).await {
+                        Ok(arithmetic_results) => {
+                            vulnerabilities.extend(arithmetic_results);
+                            completeness.completed(arithmetic::TOOL_NAME, name);
+                        }
+                        Err(e) => {
+                            log::warn!("Arithmetic execution skipped: {}", e);
+                            completeness.failed(arithmetic::TOOL_NAME, name, &e);
+                        }
+                    }
+                }
+
+                // Run linters with security rules
+                let mut lint_results = Vec::new();
+                if self.config.tools.solhint.enabled {
+                    match self.run_solhint(contract).await {
+                        Ok(results) => {
+                            lint_results.extend(results);
+                            completeness.completed("Solhint", name);
+                        }
+                        Err(e) => {
+                            log::warn!("Solhint analysis skipped: {}", e);
+                            completeness.failed("Solhint", name, &e);
+                        }
+                    }
+                } else {
+                    completeness.not_requested("Solhint", name, SkipReason::Disabled);
+                }
+                if self.config.tools.semgrep.enabled {
+                    match self.run_semgrep(contract).await {
+                        Ok(results) => {
+                            lint_results.extend(results);
+                            completeness.completed("Semgrep", name);
+                        }
+                        Err(e) => {
+                            log::warn!("Semgrep analysis skipped: {}", e);
+                            completeness.failed("Semgrep", name, &e);
+                        }
+                    }
+                } else {
+                    completeness.not_requested("Semgrep", name, SkipReason::Disabled);
                 }
+                if self.config.tools.solc.enabled {
+                    match self.run_solc(contract).await {
+                        // Warnings from a partial compilation are still worth reporting
+                        Ok((results, compile_errors)) => {
+                            lint_results.extend(results);
+                            match compile_errors {
+                                Some(errors) => completeness.failed("Solc", name, &anyhow!("compilation failed: {}", errors)),
+                                None => completeness.completed("Solc", name),
+                            }
+                        }
+                        Err(e) => {
+                            log::warn!("Compiler warnings skipped: {}", e);
+                            completeness.failed("Solc", name, &e);
+                        }
+                    }
+                } else {
+                    completeness.not_requested("Solc", name, SkipReason::Disabled);
+                }
+                let lint_results = self.dedupe_against_slither(lint_results, &vulnerabilities);
+                vulnerabilities.extend(lint_results);
             }
-            "cairo" => {
-                // Run Cairo analysis tools
-                if let Ok(cairo_results) = self.run_cairo_analysis(contract).await {
-                    vulnerabilities.extend(cairo_results);
+            "move" | "cairo" | "ink" | "solana" => {
+                // The platform plugin awaits its tools on this runtime rather than starting its own
+                let tool = match target {
+                    "move" => "Move Plugin",
+                    "cairo" => "Cairo Plugin",
+                    "solana" => "Solana Plugin",
+                    _ => "Ink Plugin",
+                };
+                status!("  🧩 Running {} checks...", tool);
+                match self.plugin_manager.analyze_contract(contract, target).await {
+                    Ok(plugin_results) => {
+                        vulnerabilities.extend(plugin_results);
+                        completeness.completed(tool, name);
+                    }
+                    Err(e) => {
+                        log::warn!("{} failed: {}", tool, e);
+                        completeness.failed(tool, name, &e);
+                    }
+                }
+                if target == "move" {
+                    completeness.not_implemented("Move Prover", name);
                 }
             }
             _ => {
@@ -245,14 +1615,32 @@ impl AnalysisEngine {
         &self,
         contract: &ParsedContract,
         target: &str,
+        subgraph: Option<&Subgraph>,
+        completeness: &mut Completeness,
+        fuzz_coverage: &mut Option<FuzzCoverage>,
     ) -> Result<Vec<Vulnerability>> {
         let mut vulnerabilities = Vec::new();
+        let fuzz_targets = subgraph.map(|subgraph| subgraph.fuzz_targets(&contract.name));
 
         match target {
+            "evm" if contract.is_vyper() => {
+                completeness.not_requested("Echidna", Some(&contract.name), SkipReason::UnsupportedLanguage);
+            }
+            "evm" if fuzz_targets.is_some_and(|targets| targets.is_empty()) => {
+                completeness.not_requested("Echidna", Some(&contract.name), SkipReason::OutOfScope);
+            }
             "evm" => {
                 // Run Echidna fuzzing
-                if let Ok(echidna_results) = self.run_echidna(contract).await {
-                    vulnerabilities.extend(echidna_results);
+                match self.run_echidna(contract, fuzz_targets).await {
+                    Ok((echidna_results, coverage)) => {
+                        vulnerabilities.extend(echidna_results);
+                        *fuzz_coverage = coverage;
+                        completeness.completed("Echidna", Some(&contract.name));
+                    }
+                    Err(e) => {
+                        log::warn!("Echidna fuzzing skipped: {}", e);
+                        completeness.failed("Echidna", Some(&contract.name), &e);
+                    }
                 }
             }
             _ => {
@@ -263,103 +1651,540 @@ impl AnalysisEngine {
         Ok(vulnerabilities)
     }
 
-    /// Run Slither static analysis
-    async fn run_slither(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
-        println!("  🔍 Running Slither static analysis...");
+    /// Slither, the Vyper checks and compiler warnings on a Vyper source; the Solidity-only tools are skipped
+    async fn run_vyper_analysis(
+        &self,
+        contract: &ParsedContract,
+        project: &Path,
+        capabilities: &Capabilities,
+        completeness: &mut Completeness,
+    ) -> Vec<Vulnerability> {
+        let mut vulnerabilities = Vec::new();
+        let name = Some(contract.name.as_str());
 
-        // Create temporary file for analysis
-        let temp_file = tempfile::NamedTempFile::new()?;
-        std::fs::write(temp_file.path(), &contract.source_code)?;
+        match self.run_slither(contract, project, capabilities).await {
+            Ok(slither_results) => {
+                vulnerabilities.extend(slither_results);
+                completeness.completed("Slither", name);
+            }
+            Err(e) => {
+                log::warn!("Slither analysis skipped: {}", e);
+                completeness.failed("Slither", name, &e);
+            }
+        }
 
-        // Run Slither
-        let output = Command::new("slither")
-            .arg(temp_file.path())
-            .arg("--json")
-            .arg("-")
-            .output()
-            .await?;
+        status!("  🐍 Running Vyper checks...");
+        vulnerabilities.extend(vyper::run_checks(contract));
+        completeness.completed(vyper::TOOL_NAME, name);
 
-        if !output.status.success() {
-            log::warn!("Slither execution failed: {}", String::from_utf8_lossy(&output.stderr));
-            return Ok(Vec::new());
+        for tool in [validation::TOOL_NAME, error_handling::TOOL_NAME, gas::TOOL_NAME, "Mythril", "Solhint", "Semgrep", "Solc"] {
+            completeness.not_requested(tool, name, SkipReason::UnsupportedLanguage);
+        }
+        #[cfg(feature = "execution")]
+        completeness.not_requested(arithmetic::TOOL_NAME, name, SkipReason::UnsupportedLanguage);
+
+        if self.config.tools.vyper.enabled {
+            match self.run_vyper(contract).await {
+                Ok((results, compile_errors)) => {
+                    vulnerabilities.extend(self.dedupe_against_slither(results, &vulnerabilities));
+                    match compile_errors {
+                        Some(errors) => completeness.failed("Vyper", name, &anyhow!("compilation failed: {}", errors)),
+                        None => completeness.completed("Vyper", name),
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Vyper compiler warnings skipped: {}", e);
+                    completeness.failed("Vyper", name, &e);
+                }
+            }
+        } else {
+            completeness.not_requested("Vyper", name, SkipReason::Disabled);
         }
 
-        // Parse Slither output
+        vulnerabilities
+    }
+
+    /// Run Slither static analysis
+    async fn run_slither(&self, contract: &ParsedContract, project: &Path, capabilities: &Capabilities) -> Result<Vec<Vulnerability>> {
+        status!("  🔍 Running Slither static analysis...");
+        let slither = &self.config.tools.slither;
+
+        // A framework compiles the project in place, so imports resolve; otherwise the file is compiled on its own
+        let framework = slither.compiles_project() && !contract.is_vyper();
+        let temp_file = match framework {
+            true => None,
+            false => {
+                // crytic-compile picks the compiler by extension
+                let suffix = if contract.is_vyper() { ".vy" } else { ".sol" };
+                let temp_file = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).suffix(suffix).tempfile()?;
+                std::fs::write(temp_file.path(), contract.source_code.as_bytes())?;
+                Some(temp_file)
+            }
+        };
+
+        // Run Slither
+        let mut command = platform::async_command(&slither.executable);
+        match &temp_file {
+            Some(temp_file) => command.arg(temp_file.path()),
+            None => command.arg(manifest::project_dir(project)),
+        };
+        // Detectors the installed release lacks would abort the whole run
+        let include = capabilities.slither_detectors(&slither.include_detectors);
+        let exclude = capabilities.slither_detectors(&slither.exclude_detectors);
+        command.args(slither.arguments(&include, &exclude));
+        if contract.is_vyper() {
+            command.arg("--compile-force-framework").arg("vyper");
+        } else if framework {
+            command.arg("--compile-force-framework").arg(&slither.framework);
+        }
+        let output = budget::run(&mut command, Some(Duration::from_secs(slither.timeout))).await?;
+
+        // Slither exits non-zero when it reports findings, so judge the run by its JSON
         let slither_output = String::from_utf8_lossy(&output.stdout);
-        self.parse_slither_output(&slither_output, &contract.name)
+        let report: serde_json::Value =
+            serde_json::from_str(&slither_output).map_err(|_| capabilities.explain("Slither", tool_error(&output)))?;
+        if report.get("success").and_then(|s| s.as_bool()) == Some(false) {
+            let error = report.get("error").and_then(|e| e.as_str()).unwrap_or("unknown error");
+            if error.contains("Compilation") || error.contains("compile") {
+                return Err(anyhow!("compilation failed: {}", error.lines().next().unwrap_or(error)));
+            }
+            return Err(capabilities.explain("Slither", anyhow!("{}", error.lines().next().unwrap_or(error))));
+        }
+
+        // A project run reports on every file, of which this contract's are kept
+        let file = contract.metadata.get("path").unwrap_or(&contract.name);
+        self.parse_slither_output(&slither_output, &contract.name, framework.then_some(file.as_str()))
     }
 
-    /// Run Mythril symbolic execution
-    async fn run_mythril(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
-        println!("  🔮 Running Mythril symbolic execution...");
+    /// Run Mythril symbolic execution within `tools.mythril.timeout`
+    ///
+    /// Returns the findings and, when part of the analysis was cut short,
+    /// what was missed, so deep audits report partial results instead of
+    /// passing them off as complete.
+    async fn run_mythril(&self, contract: &ParsedContract, capabilities: &Capabilities) -> Result<(Vec<Vulnerability>, Option<String>)> {
+        status!("  🔮 Running Mythril symbolic execution...");
+        let mythril = &self.config.tools.mythril;
+
+        // Mythril only compiles files with a .sol extension
+        let temp_file = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).suffix(".sol").tempfile()?;
+        std::fs::write(temp_file.path(), contract.source_code.as_bytes())?;
+
+        let risks = risk::score_functions(contract).unwrap_or_default();
+        let targeted = capabilities.supports_flag("Mythril", "--transaction-sequences");
+        if !targeted && !risks.is_empty() {
+            status!("    ↘️  Installed Mythril cannot target functions, analysing the whole contract");
+        }
+        if risks.is_empty() || !targeted {
+            let (vulnerabilities, cut_short) = self
+                .run_mythril_pass(temp_file.path(), &contract.name, mythril.max_depth, mythril.timeout, None)
+                .await
+                .map_err(|e| capabilities.explain("Mythril", e))?;
+            let missed = cut_short.then(|| format!("exploration stopped at the {}s timeout before reaching depth {}", mythril.timeout, mythril.max_depth));
+            return Ok((vulnerabilities, missed));
+        }
+
+        // Whole-contract runs time out, so spend most of the budget on the riskiest functions
+        let split = mythril.top_functions.min(risks.len());
+        let (top, rest) = risks.split_at(split);
+        for function in top {
+            status!(
+                "    🎯 {}.{} (risk {}: {})",
+                function.contract,
+                function.signature,
+                function.score,
+                function.reasons.join(", ")
+            );
+        }
 
-        // Create temporary file
-        let temp_file = tempfile::NamedTempFile::new()?;
-        std::fs::write(temp_file.path(), &contract.source_code)?;
+        let all_selectors: Vec<&str> = risks.iter().map(|function| function.selector.as_str()).collect();
+        let top_selectors: Vec<&str> = top.iter().map(|function| function.selector.as_str()).collect();
+        let rest_selectors: Vec<&str> = rest.iter().map(|function| function.selector.as_str()).collect();
+
+        let top_budget = if rest.is_empty() { mythril.timeout } else { mythril.timeout * 2 / 3 };
+        let (mut vulnerabilities, top_cut_short) = self
+            .run_mythril_pass(
+                temp_file.path(),
+                &contract.name,
+                mythril.max_depth,
+                top_budget,
+                Some((&all_selectors, &top_selectors)),
+            )
+            .await
+            .map_err(|e| capabilities.explain("Mythril", e))?;
+        let mut missed = Vec::new();
+        if top_cut_short {
+            missed.push(format!("{} riskiest functions not explored to depth {} within {}s", top.len(), mythril.max_depth, top_budget));
+        }
 
-        // Run Mythril
-        let output = Command::new("myth")
-            .arg("analyze")
-            .arg(temp_file.path())
-            .arg("--output")
-            .arg("json")
-            .output()
-            .await?;
+        if !rest.is_empty() {
+            status!("    ↘️  {} remaining functions at depth {}", rest.len(), mythril.reduced_max_depth);
+            // The targeted pass already covered the dangerous surface, so a failure here is not fatal
+            let rest_budget = mythril.timeout - top_budget;
+            let rest_results = match self
+                .run_mythril_pass(
+                    temp_file.path(),
+                    &contract.name,
+                    mythril.reduced_max_depth,
+                    rest_budget,
+                    Some((&all_selectors, &rest_selectors)),
+                )
+                .await
+            {
+                Ok((results, cut_short)) => {
+                    if cut_short {
+                        missed.push(format!("{} remaining functions not explored to depth {} within {}s", rest.len(), mythril.reduced_max_depth, rest_budget));
+                    }
+                    results
+                }
+                Err(e) => {
+                    log::warn!("Mythril pass over the remaining functions failed: {}", e);
+                    missed.push(format!("{} remaining functions not analysed ({})", rest.len(), e));
+                    Vec::new()
+                }
+            };
+            for vuln in rest_results {
+                let duplicate = vulnerabilities
+                    .iter()
+                    .any(|existing| existing.title == vuln.title && existing.line_number == vuln.line_number);
+                if !duplicate {
+                    vulnerabilities.push(vuln);
+                }
+            }
+        }
 
-        if !output.status.success() {
-            log::warn!("Mythril execution failed: {}", String::from_utf8_lossy(&output.stderr));
-            return Ok(Vec::new());
+        let missed = Some(missed.join("; ")).filter(|missed| !missed.is_empty());
+        Ok((vulnerabilities, missed))
+    }
+
+    /// One Mythril run within `budget` seconds, killed when it goes over
+    ///
+    /// With `sequences`, the first transaction may call any state-changing
+    /// function to set up state and the second must call one of the targets.
+    /// Mythril reports what it found so far when its own execution timeout
+    /// stops the exploration, so a run that lasted that long is returned as
+    /// cut short.
+    async fn run_mythril_pass(
+        &self,
+        file: &Path,
+        contract_name: &str,
+        max_depth: u32,
+        budget: u64,
+        sequences: Option<(&[&str], &[&str])>,
+    ) -> Result<(Vec<Vulnerability>, bool)> {
+        let mythril = &self.config.tools.mythril;
+        // Leave room for compilation and reporting within the budget
+        let execution_timeout = (budget * 4 / 5).max(1);
+
+        let mut command = platform::async_command(&mythril.executable);
+        command
+            .args(&mythril.args)
+            .arg(file)
+            .arg("--max-depth")
+            .arg(max_depth.to_string())
+            .arg("--solver-timeout")
+            .arg(mythril.solver_timeout.to_string())
+            .arg("--execution-timeout")
+            .arg(execution_timeout.to_string());
+        if let Some((setup, targets)) = sequences {
+            command
+                .arg("--transaction-count")
+                .arg("2")
+                .arg("--transaction-sequences")
+                .arg(format!("[[{}],[{}]]", setup.join(","), targets.join(",")));
         }
 
-        // Parse Mythril output
+        let started = Instant::now();
+        let output = self.run_with_timeout(&mut command, budget.max(1)).await?;
+        let cut_short = started.elapsed().as_secs() >= execution_timeout;
+
+        // Mythril exits non-zero when it reports issues, so only bail on empty output
         let mythril_output = String::from_utf8_lossy(&output.stdout);
-        self.parse_mythril_output(&mythril_output, &contract.name)
+        if mythril_output.trim().is_empty() {
+            return Err(tool_error(&output));
+        }
+
+        Ok((self.parse_mythril_output(&mythril_output, contract_name)?, cut_short))
     }
 
-    /// Run Echidna fuzzing
-    async fn run_echidna(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
-        println!("  🎲 Running Echidna fuzzing...");
+    /// Run Echidna fuzzing, calling only `fuzz_targets` when given
+    async fn run_echidna(&self, contract: &ParsedContract, fuzz_targets: Option<&[String]>) -> Result<(Vec<Vulnerability>, Option<FuzzCoverage>)> {
+        status!("  🎲 Running Echidna fuzzing...");
 
-        // Create temporary file
-        let temp_file = tempfile::NamedTempFile::new()?;
-        std::fs::write(temp_file.path(), &contract.source_code)?;
+        // The corpus and coverage annotations go next to the source, to be read back below
+        let temp_dir = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).tempdir()?;
+        let contract_path = temp_dir.path().join("Target.sol");
+        let corpus_dir = temp_dir.path().join("corpus");
+        std::fs::write(&contract_path, contract.source_code.as_bytes())?;
 
         // Run Echidna
-        let output = Command::new("echidna-test")
-            .arg(temp_file.path())
-            .arg("--format")
-            .arg("json")
-            .output()
-            .await?;
+        let mut command = platform::async_command("echidna-test");
+        command.arg(&contract_path).arg("--format").arg("json").arg("--corpus-dir").arg(&corpus_dir);
+        if let Some(targets) = fuzz_targets {
+            // JSON is YAML, which Echidna reads its configuration as
+            let config_path = temp_dir.path().join("echidna.yaml");
+            let config = serde_json::json!({ "filterBlacklist": false, "filterFunctions": targets });
+            std::fs::write(&config_path, config.to_string())?;
+            command.arg("--config").arg(&config_path);
+        }
+        let output = budget::run(&mut command, None).await?;
 
         if !output.status.success() {
-            log::warn!("Echidna execution failed: {}", String::from_utf8_lossy(&output.stderr));
-            return Ok(Vec::new());
+            return Err(tool_error(&output));
         }
 
         // Parse Echidna output
         let echidna_output = String::from_utf8_lossy(&output.stdout);
-        self.parse_echidna_output(&echidna_output, &contract.name)
+        let vulnerabilities = self.parse_echidna_output(&echidna_output, &contract.name)?;
+        Ok((vulnerabilities, fuzz_coverage::collect(&corpus_dir, contract)))
     }
 
-    /// Run Move Prover analysis
-    async fn run_move_prover(&self, _contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
-        println!("  📐 Running Move Prover analysis...");
-        
-        // TODO: Implement Move Prover integration
-        Ok(Vec::new())
+    /// Run Solhint with the configured rule packs
+    async fn run_solhint(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        status!("  📏 Running Solhint...");
+        let solhint = &self.config.tools.solhint;
+
+        // Solhint only lints files with a .sol extension
+        let temp_file = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).suffix(".sol").tempfile()?;
+        std::fs::write(temp_file.path(), contract.source_code.as_bytes())?;
+
+        // Without an explicit config, generate one extending the rule packs
+        let generated_config = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).suffix(".json").tempfile()?;
+        let config_path = match &solhint.config_file {
+            Some(path) => path.clone(),
+            None => {
+                let rules = serde_json::json!({ "extends": solhint.rule_packs });
+                std::fs::write(generated_config.path(), rules.to_string())?;
+                generated_config.path().to_path_buf()
+            }
+        };
+
+        let output = self
+            .run_with_timeout(
+                platform::async_command(&solhint.executable)
+                    .arg("--formatter")
+                    .arg("json")
+                    .arg("--config")
+                    .arg(&config_path)
+                    .arg(temp_file.path()),
+                solhint.timeout,
+            )
+            .await?;
+
+        // Solhint exits non-zero when it reports errors, so only bail on empty output
+        let solhint_output = String::from_utf8_lossy(&output.stdout);
+        if solhint_output.trim().is_empty() {
+            return Err(tool_error(&output));
+        }
+
+        self.parse_solhint_output(&solhint_output, &contract.name)
     }
 
-    /// Run Cairo analysis
-    async fn run_cairo_analysis(&self, _contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
-        println!("  🏛️  Running Cairo analysis...");
-        
-        // TODO: Implement Cairo analysis integration
-        Ok(Vec::new())
+    /// Run Semgrep with the configured Solidity rule packs
+    async fn run_semgrep(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        status!("  🔎 Running Semgrep...");
+        let semgrep = &self.config.tools.semgrep;
+
+        let temp_file = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).suffix(".sol").tempfile()?;
+        std::fs::write(temp_file.path(), contract.source_code.as_bytes())?;
+
+        let mut command = platform::async_command(&semgrep.executable);
+        command.arg("scan").arg("--json").arg("--quiet").arg("--metrics=off");
+        for pack in &semgrep.rule_packs {
+            command.arg("--config").arg(pack);
+        }
+        command.arg(temp_file.path());
+
+        let output = self.run_with_timeout(&mut command, semgrep.timeout).await?;
+
+        if !output.status.success() {
+            return Err(tool_error(&output));
+        }
+
+        let semgrep_output = String::from_utf8_lossy(&output.stdout);
+        self.parse_semgrep_output(&semgrep_output, &contract.name)
+    }
+
+    /// Compile with solc and collect its warnings, along with any compilation errors
+    async fn run_solc(&self, contract: &ParsedContract) -> Result<(Vec<Vulnerability>, Option<String>)> {
+        status!("  🛠️  Collecting compiler warnings...");
+        let solc = &self.config.tools.solc;
+
+        // Requesting the ABI makes solc run full analysis without generating bytecode
+        let input = serde_json::json!({
+            "language": "Solidity",
+            "sources": { contract.name.as_str(): { "content": contract.source_code } },
+            "settings": { "outputSelection": { "*": { "*": ["abi"] } } },
+        });
+        let input_file = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).suffix(".json").tempfile()?;
+        std::fs::write(input_file.path(), input.to_string())?;
+
+        let output = self
+            .run_with_timeout(
+                platform::async_command(&solc.executable)
+                    .arg("--standard-json")
+                    .stdin(std::fs::File::open(input_file.path())?),
+                solc.timeout,
+            )
+            .await?;
+
+        let solc_output = String::from_utf8_lossy(&output.stdout);
+        if solc_output.trim().is_empty() {
+            return Err(tool_error(&output));
+        }
+
+        self.parse_solc_output(&solc_output, contract)
+    }
+
+    /// Execute the wrapping arithmetic of a Solidity source in an in-memory EVM
+    /// and report the functions where boundary inputs make it overflow
+    #[cfg(feature = "execution")]
+    async fn run_arithmetic(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        let plan = match arithmetic::plan(contract) {
+            Some(plan) => plan,
+            None => return Ok(Vec::new()),
+        };
+        status!("  🧮 Executing unchecked arithmetic...");
+
+        // Sources for compilers before 0.8 only build in the checked form
+        let original = if plan.checked_by_default {
+            Some(self.compile_creation_code(&contract.name, &contract.source_code).await?)
+        } else {
+            None
+        };
+        let checked = self.compile_creation_code(&contract.name, &plan.checked_source).await?;
+        arithmetic::execute(contract, &plan, original.as_ref(), &checked, &self.config.tools.arithmetic)
+    }
+
+    /// Compile a source with solc and return the creation code of each contract
+    #[cfg(feature = "execution")]
+    async fn compile_creation_code(&self, file: &str, source: &str) -> Result<HashMap<String, Vec<u8>>> {
+        let solc = &self.config.tools.solc;
+        let input = serde_json::json!({
+            "language": "Solidity",
+            "sources": { file: { "content": source } },
+            "settings": { "outputSelection": { "*": { "*": ["evm.bytecode.object"] } } },
+        });
+        let input_file = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).suffix(".json").tempfile()?;
+        std::fs::write(input_file.path(), input.to_string())?;
+
+        let output = self
+            .run_with_timeout(
+                platform::async_command(&solc.executable)
+                    .arg("--standard-json")
+                    .stdin(std::fs::File::open(input_file.path())?),
+                solc.timeout,
+            )
+            .await?;
+
+        let solc_output = String::from_utf8_lossy(&output.stdout);
+        if solc_output.trim().is_empty() {
+            return Err(tool_error(&output));
+        }
+        arithmetic::creation_code(&solc_output, file)
+    }
+
+    /// Compile a Vyper source, returning its warnings and the compilation error if it did not compile
+    async fn run_vyper(&self, contract: &ParsedContract) -> Result<(Vec<Vulnerability>, Option<String>)> {
+        status!("  🛠️  Collecting Vyper compiler warnings...");
+        let vyper_config = &self.config.tools.vyper;
+
+        // The ABI is the cheapest output that still runs every semantic check
+        let source_file = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).suffix(".vy").tempfile()?;
+        std::fs::write(source_file.path(), contract.source_code.as_bytes())?;
+        let output = self
+            .run_with_timeout(
+                platform::async_command(&vyper_config.executable).arg("-f").arg("abi").arg(source_file.path()),
+                vyper_config.timeout,
+            )
+            .await?;
+
+        // Python prints `path:line: UserWarning: message`, newer releases `Warning: message`
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let pattern = regex::Regex::new(r"(?:^|\s)(?:User|Deprecation)?Warning: (.+)")?;
+        let warnings = stderr
+            .lines()
+            .filter_map(|line| pattern.captures(line).map(|captures| captures[1].trim().trim_start_matches("Warning: ").to_string()))
+            .filter(|message| !message.is_empty())
+            .map(|message| self.vyper_warning(&message, contract))
+            .collect();
+
+        if output.status.success() {
+            return Ok((warnings, None));
+        }
+        // Tracebacks end with the exception that stopped compilation
+        let error = stderr
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .rfind(|line| line.contains("Exception") || line.contains("Error"))
+            .map(str::to_string);
+        match error {
+            Some(error) => {
+                log::warn!("vyper could not compile {}: {}", contract.name, error);
+                Ok((warnings, Some(error)))
+            }
+            None => Err(tool_error(&output)),
+        }
+    }
+
+    /// Finding for one Vyper compiler warning
+    fn vyper_warning(&self, message: &str, contract: &ParsedContract) -> Vulnerability {
+        let line_number = regex::Regex::new(r"line (\d+)")
+            .ok()
+            .and_then(|pattern| pattern.captures(message))
+            .and_then(|captures| captures[1].parse::<usize>().ok());
+        let code_snippet = line_number
+            .and_then(|line| contract.source_code.lines().nth(line.saturating_sub(1)))
+            .map(|line| line.trim().to_string());
+        let summary = message.split(". ").next().unwrap_or(message);
+        let summary = summary.split(", line ").next().unwrap_or(summary).trim_end_matches('.');
+        let deprecated = message.to_lowercase().contains("deprecat");
+
+        Vulnerability {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: format!("Vyper Compiler Warning: {}", summary),
+            description: message.to_string(),
+            severity: if deprecated { "Info" } else { "Low" }.to_string(),
+            category: VulnerabilityCategory::CodeQuality,
+            file_path: contract.name.clone(),
+            line_number,
+            code_snippet,
+            recommendation: Some("Resolve the compiler warning; vyper reports it on every build.".to_string()),
+            references: vec!["https://docs.vyperlang.org/en/stable/compiling-a-contract.html".to_string()],
+            cwe_id: None,
+            tool: "Vyper".to_string(),
+            confidence: 0.9,
+            provenance: None,
+            severity_overrides: Vec::new(),
+            cvss: None,
+            detected_by: Vec::new(),
+        }
+    }
+
+    /// Run an external tool, killing it once the timeout expires or it goes over the run's budget
+    async fn run_with_timeout(&self, command: &mut Command, timeout_secs: u64) -> Result<std::process::Output> {
+        budget::run(command, Some(std::time::Duration::from_secs(timeout_secs))).await
+    }
+
+    /// Drop linter findings already reported by Slither, or the native detectors standing in for it, on the same line and category
+    fn dedupe_against_slither(&self, findings: Vec<Vulnerability>, existing: &[Vulnerability]) -> Vec<Vulnerability> {
+        let slither_keys: std::collections::HashSet<(usize, String)> = existing
+            .iter()
+            .filter(|vuln| vuln.tool == "Slither" || vuln.tool == detectors::TOOL_NAME)
+            .filter_map(|vuln| vuln.line_number.map(|line| (line, vuln.category.to_string())))
+            .collect();
+
+        findings
+            .into_iter()
+            .filter(|vuln| match vuln.line_number {
+                Some(line) => !slither_keys.contains(&(line, vuln.category.to_string())),
+                None => true,
+            })
+            .collect()
     }
 
     /// Parse Slither JSON output
-    fn parse_slither_output(&self, output: &str, contract_name: &str) -> Result<Vec<Vulnerability>> {
+    fn parse_slither_output(&self, output: &str, contract_name: &str, file: Option<&str>) -> Result<Vec<Vulnerability>> {
         let mut vulnerabilities = Vec::new();
 
         // Try to parse as JSON
@@ -367,7 +2192,7 @@ impl AnalysisEngine {
             if let Some(results) = json_value.get("results") {
                 if let Some(detectors) = results.get("detectors") {
                     if let Some(detector_array) = detectors.as_array() {
-                        for detector in detector_array {
+                        for detector in detector_array.iter().filter(|detector| file.is_none_or(|file| slither_file_is(detector, file))) {
                             if let Some(vuln) = self.parse_slither_detector(detector, contract_name) {
                                 vulnerabilities.push(vuln);
                             }
@@ -421,6 +2246,10 @@ impl AnalysisEngine {
             cwe_id: None,
             tool: "Slither".to_string(),
             confidence: self.map_confidence(confidence),
+            provenance: None,
+            severity_overrides: Vec::new(),
+            cvss: None,
+            detected_by: Vec::new(),
         })
     }
 
@@ -473,9 +2302,235 @@ impl AnalysisEngine {
             cwe_id: Some(swc_id.to_string()),
             tool: "Mythril".to_string(),
             confidence: 0.8,
+            provenance: None,
+            severity_overrides: Vec::new(),
+            cvss: None,
+            detected_by: Vec::new(),
+        })
+    }
+
+    /// Parse Solhint JSON output
+    fn parse_solhint_output(&self, output: &str, contract_name: &str) -> Result<Vec<Vulnerability>> {
+        let mut vulnerabilities = Vec::new();
+
+        // Solhint emits either a flat list of messages or one entry per file
+        // with a nested `messages` list; the trailing summary entry has neither
+        if let Ok(serde_json::Value::Array(entries)) = serde_json::from_str::<serde_json::Value>(output) {
+            for entry in &entries {
+                let messages = match entry.get("messages").and_then(|m| m.as_array()) {
+                    Some(messages) => messages.iter().collect(),
+                    None => vec![entry],
+                };
+
+                for message in messages {
+                    if let Some(vuln) = self.parse_solhint_message(message, contract_name) {
+                        vulnerabilities.push(vuln);
+                    }
+                }
+            }
+        }
+
+        Ok(vulnerabilities)
+    }
+
+    /// Parse individual Solhint message
+    fn parse_solhint_message(&self, message: &serde_json::Value, contract_name: &str) -> Option<Vulnerability> {
+        let rule_id = message.get("ruleId")?.as_str()?;
+        let text = message.get("message")?.as_str()?;
+        let line_number = message.get("line").and_then(|l| l.as_u64()).map(|l| l as usize);
+
+        let is_error = match message.get("severity") {
+            Some(serde_json::Value::String(severity)) => severity.eq_ignore_ascii_case("error"),
+            Some(serde_json::Value::Number(severity)) => severity.as_u64() == Some(2),
+            _ => false,
+        };
+        let category = self.map_lint_category(rule_id);
+        let severity = match (&category, is_error) {
+            (VulnerabilityCategory::CodeQuality | VulnerabilityCategory::GasOptimization, _) => "Info",
+            (_, true) => "Medium",
+            (_, false) => "Low",
+        };
+
+        Some(Vulnerability {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: format!("Solhint: {}", rule_id),
+            description: text.to_string(),
+            severity: severity.to_string(),
+            category,
+            file_path: contract_name.to_string(),
+            line_number,
+            code_snippet: None,
+            recommendation: Some(format!("Review the {} rule reported by Solhint", rule_id)),
+            references: vec![format!("https://protofire.github.io/solhint/docs/rules.html#{}", rule_id)],
+            cwe_id: None,
+            tool: "Solhint".to_string(),
+            confidence: 0.6,
+            provenance: None,
+            severity_overrides: Vec::new(),
+            cvss: None,
+            detected_by: Vec::new(),
+        })
+    }
+
+    /// Parse Semgrep JSON output
+    fn parse_semgrep_output(&self, output: &str, contract_name: &str) -> Result<Vec<Vulnerability>> {
+        let mut vulnerabilities = Vec::new();
+
+        if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(output) {
+            if let Some(results) = json_value.get("results").and_then(|r| r.as_array()) {
+                for result in results {
+                    if let Some(vuln) = self.parse_semgrep_result(result, contract_name) {
+                        vulnerabilities.push(vuln);
+                    }
+                }
+            }
+        }
+
+        Ok(vulnerabilities)
+    }
+
+    /// Parse individual Semgrep result
+    fn parse_semgrep_result(&self, result: &serde_json::Value, contract_name: &str) -> Option<Vulnerability> {
+        let check_id = result.get("check_id")?.as_str()?;
+        let extra = result.get("extra")?;
+        let message = extra.get("message")?.as_str()?;
+        let line_number = result
+            .get("start")
+            .and_then(|start| start.get("line"))
+            .and_then(|l| l.as_u64())
+            .map(|l| l as usize);
+
+        // Registry rules are namespaced, e.g. `solidity.security.compound-borrowfresh-reentrancy`
+        let rule_name = check_id.rsplit('.').next().unwrap_or(check_id);
+        let metadata = extra.get("metadata");
+
+        let mut references: Vec<String> = metadata
+            .and_then(|m| m.get("references"))
+            .and_then(|r| r.as_array())
+            .map(|refs| refs.iter().filter_map(|r| r.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+        references.push(format!("https://semgrep.dev/r/{}", check_id));
+
+        let cwe_id = metadata
+            .and_then(|m| m.get("cwe"))
+            .and_then(|cwe| match cwe {
+                serde_json::Value::Array(list) => list.first().and_then(|c| c.as_str()),
+                other => other.as_str(),
+            })
+            .and_then(|cwe| cwe.split(':').next())
+            .map(|cwe| cwe.trim().to_string());
+
+        Some(Vulnerability {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: format!("Semgrep: {}", rule_name),
+            description: message.to_string(),
+            severity: self.map_semgrep_severity(extra.get("severity").and_then(|s| s.as_str()).unwrap_or("")),
+            category: self.map_lint_category(check_id),
+            file_path: contract_name.to_string(),
+            line_number,
+            code_snippet: extra.get("lines").and_then(|l| l.as_str()).map(|l| l.trim().to_string()),
+            recommendation: Some(format!("Review the {} rule reported by Semgrep", check_id)),
+            references,
+            cwe_id,
+            tool: "Semgrep".to_string(),
+            confidence: 0.7,
+            provenance: None,
+            severity_overrides: Vec::new(),
+            cvss: None,
+            detected_by: Vec::new(),
+        })
+    }
+
+    /// Parse solc standard JSON output into findings, one per warning, and its compilation errors
+    fn parse_solc_output(&self, output: &str, contract: &ParsedContract) -> Result<(Vec<Vulnerability>, Option<String>)> {
+        let json_value: serde_json::Value = serde_json::from_str(output)?;
+        let diagnostics = json_value.get("errors").and_then(|e| e.as_array()).cloned().unwrap_or_default();
+
+        // Errors stop analysis early, so the warnings would be incomplete
+        let errors: Vec<&str> = diagnostics
+            .iter()
+            .filter(|d| d.get("severity").and_then(|s| s.as_str()) == Some("error"))
+            .filter_map(|d| d.get("message").and_then(|m| m.as_str()))
+            .collect();
+        let errors = if errors.is_empty() {
+            None
+        } else {
+            log::warn!("solc could not compile {}: {}", contract.name, errors.join("; "));
+            Some(errors.join("; "))
+        };
+
+        let warnings = diagnostics
+            .iter()
+            .filter(|d| d.get("severity").and_then(|s| s.as_str()) == Some("warning"))
+            .filter_map(|d| self.parse_solc_warning(d, contract))
+            .collect();
+        Ok((warnings, errors))
+    }
+
+    /// Parse individual solc warning
+    fn parse_solc_warning(&self, warning: &serde_json::Value, contract: &ParsedContract) -> Option<Vulnerability> {
+        let message = warning.get("message")?.as_str()?;
+        let error_code = warning.get("errorCode").and_then(|c| c.as_str()).unwrap_or_default();
+
+        let line_number = warning
+            .get("sourceLocation")
+            .and_then(|location| location.get("start"))
+            .and_then(|start| start.as_u64())
+            .map(|start| {
+                let start = (start as usize).min(contract.source_code.len());
+                contract.source_code[..start].matches('\n').count() + 1
+            });
+        let code_snippet = line_number
+            .and_then(|line| contract.source_code.lines().nth(line - 1))
+            .map(|line| line.trim().to_string());
+
+        let (category, severity) = self.map_solc_warning(error_code, message);
+        let summary = message
+            .split(['.', '!'])
+            .next()
+            .filter(|first| !first.is_empty())
+            .unwrap_or(message);
+
+        Some(Vulnerability {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: format!("Compiler Warning: {}", summary),
+            description: message.to_string(),
+            severity: severity.to_string(),
+            category,
+            file_path: contract.name.clone(),
+            line_number,
+            code_snippet,
+            recommendation: Some("Resolve the compiler warning; solc reports it on every build.".to_string()),
+            references: vec!["https://docs.soliditylang.org/en/latest/using-the-compiler.html#error-types".to_string()],
+            cwe_id: None,
+            tool: "Solc".to_string(),
+            confidence: 0.9,
+            provenance: None,
+            severity_overrides: Vec::new(),
+            cvss: None,
+            detected_by: Vec::new(),
         })
     }
 
+    /// Map a solc warning to a category and severity by error code, falling back to its message
+    fn map_solc_warning(&self, error_code: &str, message: &str) -> (VulnerabilityCategory, &'static str) {
+        let message = message.to_lowercase();
+        match error_code {
+            // Unused local variable, unused function parameter, unreachable code, statement has no effect
+            "2072" | "5667" | "5740" | "6133" => (VulnerabilityCategory::DeadCode, "Info"),
+            // Declaration shadows an existing declaration or builtin
+            "2519" | "8760" | "2319" => (VulnerabilityCategory::CodeQuality, "Low"),
+            // Return value of low-level call not used
+            "9302" => (VulnerabilityCategory::UnhandledExceptions, "Low"),
+            // Function state mutability can be restricted
+            "2018" => (VulnerabilityCategory::GasOptimization, "Info"),
+            _ if message.contains("abi coder") || message.contains("abicoder") => (VulnerabilityCategory::CodeQuality, "Low"),
+            _ if message.contains("shadows") => (VulnerabilityCategory::CodeQuality, "Low"),
+            _ if message.contains("unused") || message.contains("unreachable") => (VulnerabilityCategory::DeadCode, "Info"),
+            _ => (VulnerabilityCategory::CodeQuality, "Info"),
+        }
+    }
+
     /// Parse Echidna output
     fn parse_echidna_output(&self, output: &str, contract_name: &str) -> Result<Vec<Vulnerability>> {
         let mut vulnerabilities = Vec::new();
@@ -497,6 +2552,10 @@ impl AnalysisEngine {
                 cwe_id: None,
                 tool: "Echidna".to_string(),
                 confidence: 0.9,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -524,6 +2583,40 @@ impl AnalysisEngine {
         }
     }
 
+    /// Map Semgrep severity to standard severity
+    fn map_semgrep_severity(&self, severity: &str) -> String {
+        match severity.to_uppercase().as_str() {
+            "ERROR" => "High".to_string(),
+            "WARNING" => "Medium".to_string(),
+            "INFO" => "Low".to_string(),
+            _ => "Medium".to_string(),
+        }
+    }
+
+    /// Map a Solhint/Semgrep rule ID to vulnerability category
+    fn map_lint_category(&self, rule_id: &str) -> VulnerabilityCategory {
+        let rule = rule_id.to_lowercase();
+        if rule.contains("reentran") {
+            VulnerabilityCategory::Reentrancy
+        } else if rule.contains("tx-origin") || rule.contains("access") || rule.contains("owner") {
+            VulnerabilityCategory::AccessControl
+        } else if rule.contains("low-level") || rule.contains("delegatecall") || rule.contains("inline-assembly") {
+            VulnerabilityCategory::LowLevelCalls
+        } else if rule.contains("send-result") || rule.contains("unchecked") || rule.contains("return-value") {
+            VulnerabilityCategory::UnhandledExceptions
+        } else if rule.contains("rely-on-time") || rule.contains("timestamp") {
+            VulnerabilityCategory::TimestampDependence
+        } else if rule.contains("overflow") || rule.contains("underflow") {
+            VulnerabilityCategory::IntegerOverflow
+        } else if rule.contains("gas") {
+            VulnerabilityCategory::GasOptimization
+        } else if rule.contains("security") || rule.contains("avoid-suicide") || rule.contains("compiler-version") {
+            VulnerabilityCategory::Other
+        } else {
+            VulnerabilityCategory::CodeQuality
+        }
+    }
+
     /// Map Slither check to vulnerability category
     fn map_slither_category(&self, check: &str) -> VulnerabilityCategory {
         match check {
@@ -548,24 +2641,7 @@ impl AnalysisEngine {
 
     /// Calculate security score based on vulnerabilities
     fn calculate_security_score(&self, vulnerabilities: &[Vulnerability]) -> f64 {
-        if vulnerabilities.is_empty() {
-            return 100.0;
-        }
-
-        let mut score: f64 = 100.0;
-        
-        for vuln in vulnerabilities {
-            let penalty = match vuln.severity.as_str() {
-                "Critical" => 25.0,
-                "High" => 15.0,
-                "Medium" => 8.0,
-                "Low" => 3.0,
-                _ => 1.0,
-            };
-            score -= penalty;
-        }
-
-        score.max(0.0)
+        (100.0 - severity_penalty(vulnerabilities)).max(0.0)
     }
 
     /// Calculate complexity score
@@ -583,6 +2659,8 @@ impl AnalysisEngine {
         vulnerabilities: &[Vulnerability],
         duration: f64,
         tools_used: &[String],
+        warnings: Vec<String>,
+        coverage_percentage: Option<f64>,
     ) -> AnalysisSummary {
         let mut critical_count = 0;
         let mut high_count = 0;
@@ -609,7 +2687,8 @@ impl AnalysisEngine {
             info_count,
             analysis_duration: duration,
             tools_used: tools_used.to_vec(),
-            coverage_percentage: 85.0, // TODO: Calculate actual coverage
+            coverage_percentage,
+            warnings,
         }
     }
 
@@ -633,3 +2712,89 @@ impl AnalysisEngine {
         recommendations
     }
 }
+
+/// Carry a probe generated for a cluster's representative over to another member
+///
+/// Names of the representative's contracts are replaced by the member's, and
+/// confidence is scaled by how similar the two contracts are.
+#[cfg(feature = "ai")]
+fn adapt_probe(probe: &CreativeProbe, representative: &ParsedContract, member: &ParsedContract, similarity: f64) -> CreativeProbe {
+    let renames: Vec<(&str, &str)> = representative
+        .units
+        .iter()
+        .zip(&member.units)
+        .map(|(from, to)| (from.name.as_str(), to.name.as_str()))
+        .chain(std::iter::once((representative.name.as_str(), member.name.as_str())))
+        .filter(|(from, to)| from != to)
+        .collect();
+    let rename = |text: &str| {
+        renames
+            .iter()
+            .fold(text.to_string(), |text, (from, to)| text.replace(from, to))
+    };
+
+    CreativeProbe {
+        title: rename(&probe.title),
+        description: rename(&probe.description),
+        severity: probe.severity.clone(),
+        attack_vector: rename(&probe.attack_vector),
+        impact: rename(&probe.impact),
+        proof_of_concept: probe.proof_of_concept.as_deref().map(rename),
+        recommended_fix: probe.recommended_fix.as_deref().map(rename),
+        confidence: probe.confidence * similarity,
+        contract_name: member.name.clone(),
+    }
+}
+
+/// Points the security score loses for `vulnerabilities`
+fn severity_penalty(vulnerabilities: &[Vulnerability]) -> f64 {
+    vulnerabilities
+        .iter()
+        .map(|vuln| match vuln.severity.as_str() {
+            "Critical" => 25.0,
+            "High" => 15.0,
+            "Medium" => 8.0,
+            "Low" => 3.0,
+            _ => 1.0,
+        })
+        .sum()
+}
+
+/// Security score comparable across project sizes: the penalties are
+/// divided by the code size in units of `SCORE_UNIT_LINES` lines, with
+/// smaller projects counted as one unit, so the same findings weigh less in
+/// a larger codebase
+pub fn normalized_security_score(vulnerabilities: &[Vulnerability], lines_of_code: usize) -> f64 {
+    let units = (lines_of_code as f64 / SCORE_UNIT_LINES as f64).max(1.0);
+    (100.0 - severity_penalty(vulnerabilities) / units).max(0.0)
+}
+
+/// Whether a Slither result is located in `file`, a path relative to the project
+fn slither_file_is(detector: &serde_json::Value, file: &str) -> bool {
+    let mapping = detector.get("elements").and_then(|elements| elements.get(0)).and_then(|element| element.get("source_mapping"));
+    let located = mapping
+        .and_then(|mapping| mapping.get("filename_relative").or_else(|| mapping.get("filename_absolute")))
+        .and_then(|filename| filename.as_str());
+    // Results without a location cannot be told apart from one contract to the next
+    located.is_some_and(|located| Path::new(located).ends_with(file))
+}
+
+/// Warnings for the tool runs of `completeness` whose results are partial
+fn partial_results(completeness: &Completeness) -> Vec<String> {
+    completeness
+        .partial_runs()
+        .map(|run| {
+            format!(
+                "{} on {} returned partial results: {}",
+                run.tool,
+                run.contract.as_deref().unwrap_or("all contracts"),
+                run.detail.as_deref().unwrap_or("analysis cut short")
+            )
+        })
+        .collect()
+}
+
+/// Error for a tool run that exited without usable output, from its last stderr line
+fn tool_error(output: &std::process::Output) -> anyhow::Error {
+    ToolError::crashed(output.status, &output.stderr).into()
+}
@@ -0,0 +1,214 @@
+//! Editor annotations without a language server
+//!
+//! `annotate` writes the built-in detectors' findings for a source tree as a
+//! flat list of file/line/severity/message records, which editor plugins and
+//! scripts can poll without an LSP client. With `--watch` the tree is polled
+//! and the file rewritten whenever a contract changes; only files whose
+//! modification time or size moved are scanned again. The file is replaced
+//! atomically, so a reader never sees half of it.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::core::detector_catalog;
+use crate::core::fetcher::ContractInfo;
+use crate::core::hook;
+use crate::core::parser::ContractParser;
+use crate::plugins::PluginManager;
+use crate::report::vulnerability::Vulnerability;
+use crate::utils::artifact;
+use crate::utils::config::Config;
+
+/// Where `annotate` writes unless told otherwise
+pub const DEFAULT_OUTPUT: &str = ".securechain/annotations.json";
+
+/// Version of the file layout, raised on incompatible changes
+const FORMAT_VERSION: u32 = 1;
+
+/// Directories never holding the project's own contracts
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target"];
+
+/// One finding, located for an editor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    /// Path relative to the annotated directory, with forward slashes
+    pub file: String,
+    /// 1-based line; findings about the whole file are put on line 1
+    pub line: usize,
+    pub severity: String,
+    /// One-line summary, for the gutter or problems list
+    pub message: String,
+    /// Full description, for hovers
+    pub detail: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommendation: Option<String>,
+    /// Detector id, as `detectors describe` and `disabled_detectors` take it
+    pub detector: String,
+}
+
+/// A file that could not be annotated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnotationError {
+    pub file: String,
+    pub message: String,
+}
+
+/// Contents of the annotations file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotations {
+    pub version: u32,
+    pub root: String,
+    pub generated_at: DateTime<Utc>,
+    pub files_scanned: usize,
+    pub annotations: Vec<Annotation>,
+    #[serde(default)]
+    pub errors: Vec<AnnotationError>,
+}
+
+/// Modification time and size a file was scanned at
+type Stamp = (Option<SystemTime>, u64);
+
+/// Scans a source tree and remembers each file's annotations until it changes
+pub struct Annotator {
+    root: PathBuf,
+    config: Config,
+    plugin_manager: PluginManager,
+    contract_parser: ContractParser,
+    scanned: BTreeMap<PathBuf, (Stamp, std::result::Result<Vec<Annotation>, String>)>,
+}
+
+impl Annotator {
+    pub fn new(root: &Path, config: Config) -> Result<Self> {
+        if !root.exists() {
+            return Err(anyhow!("Path does not exist: {}", root.display()));
+        }
+        Ok(Self {
+            root: root.to_path_buf(),
+            config,
+            plugin_manager: PluginManager::new(),
+            contract_parser: ContractParser::new()?,
+            scanned: BTreeMap::new(),
+        })
+    }
+
+    /// Scan files that are new or changed since the last call and forget
+    /// deleted ones; true if anything changed
+    pub fn refresh(&mut self) -> bool {
+        let files = self.contract_files();
+        let before = self.scanned.len();
+        self.scanned.retain(|path, _| files.contains_key(path));
+        let mut changed = self.scanned.len() != before;
+
+        for (path, stamp) in files {
+            if self.scanned.get(&path).is_some_and(|(scanned_at, _)| *scanned_at == stamp) {
+                continue;
+            }
+            let outcome = self.scan_file(&path).map_err(|e| e.to_string());
+            self.scanned.insert(path, (stamp, outcome));
+            changed = true;
+        }
+        changed
+    }
+
+    /// Annotations of every scanned file, by file and line
+    pub fn annotations(&self) -> Annotations {
+        let mut annotations = Vec::new();
+        let mut errors = Vec::new();
+        for (path, (_, outcome)) in &self.scanned {
+            match outcome {
+                Ok(found) => annotations.extend(found.iter().cloned()),
+                Err(message) => errors.push(AnnotationError { file: self.relative(path), message: message.clone() }),
+            }
+        }
+
+        Annotations {
+            version: FORMAT_VERSION,
+            root: self.root.display().to_string(),
+            generated_at: Utc::now(),
+            files_scanned: self.scanned.len(),
+            annotations,
+            errors,
+        }
+    }
+
+    /// Contract files under the root with their current stamps
+    fn contract_files(&self) -> HashMap<PathBuf, Stamp> {
+        walkdir::WalkDir::new(&self.root)
+            .into_iter()
+            .filter_entry(|entry| {
+                let name = entry.file_name().to_string_lossy();
+                entry.depth() == 0 || !entry.file_type().is_dir() || !(name.starts_with('.') || SKIPPED_DIRS.contains(&&*name))
+            })
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter(|entry| hook::platform_for_path(&entry.path().to_string_lossy()).is_some())
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some((entry.into_path(), (metadata.modified().ok(), metadata.len())))
+            })
+            .collect()
+    }
+
+    /// Run the built-in detectors of the file's platform, as the pre-commit hook does
+    fn scan_file(&self, path: &Path) -> Result<Vec<Annotation>> {
+        let file = self.relative(path);
+        let platform = hook::platform_for_path(&file).ok_or_else(|| anyhow!("not a contract file"))?;
+        let source_code = std::fs::read_to_string(path)?;
+        // Rust sources are only contracts when they use ink!
+        if platform == "ink" && !source_code.contains("#[ink") {
+            return Ok(Vec::new());
+        }
+
+        let contract = ContractInfo {
+            name: file.clone(),
+            address: "".to_string(),
+            source_code,
+            compiler_version: "unknown".to_string(),
+            optimization: false,
+            network: "local".to_string(),
+            verified: false,
+            metadata: HashMap::new(),
+            project: None,
+        };
+        let parsed_contract = self.contract_parser.parse_contract(&contract)?;
+        let mut vulnerabilities = self.plugin_manager.internal_checks(&parsed_contract, platform)?;
+        detector_catalog::retain_enabled(&mut vulnerabilities, &self.config);
+
+        let mut annotations: Vec<Annotation> = vulnerabilities.iter().map(|vuln| annotation(&file, vuln)).collect();
+        annotations.sort_by_key(|annotation| annotation.line);
+        Ok(annotations)
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        let relative = match path.strip_prefix(&self.root) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative,
+            _ => path,
+        };
+        relative.to_string_lossy().replace('\\', "/")
+    }
+}
+
+fn annotation(file: &str, vuln: &Vulnerability) -> Annotation {
+    Annotation {
+        file: file.to_string(),
+        line: vuln.line_number.unwrap_or(1).max(1),
+        severity: vuln.severity.clone(),
+        message: vuln.title.clone(),
+        detail: vuln.description.clone(),
+        recommendation: vuln.recommendation.clone(),
+        detector: detector_catalog::check_id(vuln),
+    }
+}
+
+/// Replace the annotations file, creating its directory on first use
+pub fn write(path: &Path, annotations: &Annotations) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    artifact::write_atomic(path, serde_json::to_string_pretty(annotations)?)?;
+    Ok(())
+}
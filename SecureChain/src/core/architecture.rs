@@ -0,0 +1,305 @@
+//! Architecture overview
+//!
+//! Readers of a report on a multi-contract system first need to know what
+//! talks to what. This module groups the contracts of a run into modules
+//! (tokens, governance, core, periphery, libraries and external
+//! dependencies) and connects them by inheritance, external calls, library
+//! use and deployment, for a diagram at the top of the report. Grouping is
+//! by name and function heuristics, so it orients rather than proves.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::core::parser::{strip_comments, ContractUnit, ParsedContract};
+
+/// Module a contract is grouped into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Module {
+    Tokens,
+    Governance,
+    Core,
+    Periphery,
+    Libraries,
+    /// Interfaces, and contracts inherited or called but not part of the run
+    External,
+}
+
+impl Module {
+    pub const ALL: [Module; 6] = [
+        Module::Tokens,
+        Module::Governance,
+        Module::Core,
+        Module::Periphery,
+        Module::Libraries,
+        Module::External,
+    ];
+
+    fn id(self) -> &'static str {
+        match self {
+            Module::Tokens => "tokens",
+            Module::Governance => "governance",
+            Module::Core => "core",
+            Module::Periphery => "periphery",
+            Module::Libraries => "libraries",
+            Module::External => "external",
+        }
+    }
+}
+
+impl std::fmt::Display for Module {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Module::Tokens => "Tokens",
+            Module::Governance => "Governance",
+            Module::Core => "Core",
+            Module::Periphery => "Periphery",
+            Module::Libraries => "Libraries",
+            Module::External => "External",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// How one contract depends on another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    Inherits,
+    Calls,
+    /// Calls a library's functions
+    Uses,
+    /// Deploys with `new`
+    Creates,
+}
+
+impl std::fmt::Display for EdgeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            EdgeKind::Inherits => "inherits",
+            EdgeKind::Calls => "calls",
+            EdgeKind::Uses => "uses",
+            EdgeKind::Creates => "creates",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// A contract, interface or library in the diagram
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Component {
+    pub name: String,
+    /// `contract`, `abstract contract`, `interface` or `library`; empty outside the run
+    pub kind: String,
+    /// Source file, empty outside the run
+    pub file: String,
+    pub module: Module,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+/// Components of a run and the dependencies between them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Architecture {
+    pub components: Vec<Component>,
+    pub edges: Vec<Edge>,
+}
+
+/// Name fragments of token contracts
+const TOKEN_NAMES: &str = r"(?i)token|erc20|erc721|erc1155|erc4626|coin|nft|stable";
+/// Name fragments of governance contracts
+const GOVERNANCE_NAMES: &str = r"(?i)govern|timelock|vot(e|ing)|dao|multisig|council|proposal|treasury";
+/// Name fragments of periphery contracts
+const PERIPHERY_NAMES: &str = r"(?i)router|helper|lens|adapter|zap|periphery|factory|deployer|oracle|quoter|migrat|mock|faucet";
+
+impl Architecture {
+    /// Group the units of `contracts` and connect them
+    pub fn build(contracts: &[ParsedContract]) -> Self {
+        let token_names = Regex::new(TOKEN_NAMES).expect("valid token pattern");
+        let governance_names = Regex::new(GOVERNANCE_NAMES).expect("valid governance pattern");
+        let periphery_names = Regex::new(PERIPHERY_NAMES).expect("valid periphery pattern");
+
+        let mut architecture = Architecture::default();
+        let mut units: Vec<&ContractUnit> = Vec::new();
+        for contract in contracts {
+            for unit in &contract.units {
+                if architecture.component(&unit.name).is_some() {
+                    continue;
+                }
+                let has = |name: &str| unit.functions.iter().any(|function| function.name == name);
+                let module = match unit.kind.as_str() {
+                    "interface" => Module::External,
+                    "library" => Module::Libraries,
+                    _ if has("propose") || has("castVote") || governance_names.is_match(&unit.name) => Module::Governance,
+                    _ if (has("transfer") && has("balanceOf")) || has("ownerOf") || token_names.is_match(&unit.name) => {
+                        Module::Tokens
+                    }
+                    _ if periphery_names.is_match(&unit.name) => Module::Periphery,
+                    _ => Module::Core,
+                };
+                architecture.components.push(Component {
+                    name: unit.name.clone(),
+                    kind: unit.kind.clone(),
+                    file: contract.name.clone(),
+                    module,
+                });
+                units.push(unit);
+            }
+        }
+
+        let member_call = Regex::new(r"\b([A-Za-z_]\w*)\s*\.\s*[A-Za-z_]\w*\s*[({]").expect("valid member call pattern");
+        let cast_call = Regex::new(r"\b([A-Za-z_]\w*)\s*\(\s*[^()]*(?:\([^()]*\)[^()]*)*\)\s*\.\s*[A-Za-z_]\w*\s*[({]")
+            .expect("valid cast call pattern");
+        let creation = Regex::new(r"\bnew\s+([A-Za-z_]\w*)\s*[({]").expect("valid creation pattern");
+
+        // Inheritance first, so bases outside the run are known when calls are resolved
+        for unit in &units {
+            for parent in &unit.inheritance {
+                architecture.add_edge(&unit.name, parent, EdgeKind::Inherits);
+            }
+        }
+
+        for unit in units.iter().filter(|unit| unit.kind != "interface") {
+            // Declared types of call receivers: state variables, then each function's parameters
+            let state: HashMap<&str, &str> = unit
+                .state_variables
+                .iter()
+                .map(|variable| (variable.name.as_str(), variable.type_name.as_str()))
+                .collect();
+            for function in &unit.functions {
+                let mut typed = state.clone();
+                for (type_name, name) in function.parameter_types.iter().zip(&function.parameter_names) {
+                    typed.insert(name.as_str(), type_name.as_str());
+                }
+                let body = match &function.body {
+                    Some(body) => strip_comments(body),
+                    None => continue,
+                };
+
+                for captures in member_call.captures_iter(&body) {
+                    let receiver = &captures[1];
+                    let target = match typed.get(receiver) {
+                        Some(type_name) => *type_name,
+                        None => receiver,
+                    };
+                    match architecture.component(target).map(|component| component.module) {
+                        Some(Module::Libraries) => architecture.add_edge(&unit.name, target, EdgeKind::Uses),
+                        Some(_) if target != receiver => architecture.add_edge(&unit.name, target, EdgeKind::Calls),
+                        _ => {}
+                    }
+                }
+                for captures in cast_call.captures_iter(&body) {
+                    if architecture.component(&captures[1]).is_some_and(|component| component.module != Module::Libraries) {
+                        architecture.add_edge(&unit.name, &captures[1], EdgeKind::Calls);
+                    }
+                }
+                for captures in creation.captures_iter(&body) {
+                    if architecture.component(&captures[1]).is_some() {
+                        architecture.add_edge(&unit.name, &captures[1], EdgeKind::Creates);
+                    }
+                }
+            }
+        }
+        architecture
+    }
+
+    fn component(&self, name: &str) -> Option<&Component> {
+        self.components.iter().find(|component| component.name == name)
+    }
+
+    /// Add an edge, bringing in `to` as an external component when it is not part of the run
+    fn add_edge(&mut self, from: &str, to: &str, kind: EdgeKind) {
+        if from == to {
+            return;
+        }
+        if self.component(to).is_none() {
+            self.components.push(Component {
+                name: to.to_string(),
+                kind: String::new(),
+                file: String::new(),
+                module: Module::External,
+            });
+        }
+        let edge = Edge { from: from.to_string(), to: to.to_string(), kind };
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+    }
+
+    /// Components of `module`, in declaration order
+    pub fn members(&self, module: Module) -> impl Iterator<Item = &Component> {
+        self.components.iter().filter(move |component| component.module == module)
+    }
+
+    /// Mermaid flowchart, one subgraph per module
+    pub fn to_mermaid(&self) -> String {
+        let mut diagram = String::from("flowchart LR\n");
+        for module in Module::ALL {
+            let mut members = self.members(module).peekable();
+            if members.peek().is_none() {
+                continue;
+            }
+            diagram.push_str(&format!("    subgraph {}[\"{}\"]\n", module.id(), module));
+            for component in members {
+                let label = match component.kind.as_str() {
+                    "" | "contract" => component.name.clone(),
+                    kind => format!("{}<br/><i>{}</i>", component.name, kind),
+                };
+                diagram.push_str(&format!("        c_{}[\"{}\"]\n", component.name, label));
+            }
+            diagram.push_str("    end\n");
+        }
+        for edge in &self.edges {
+            let arrow = match edge.kind {
+                EdgeKind::Inherits => "-.->",
+                _ => "-->",
+            };
+            diagram.push_str(&format!("    c_{} {}|{}| c_{}\n", edge.from, arrow, edge.kind, edge.to));
+        }
+        diagram
+    }
+
+    /// Graphviz digraph, one cluster per module
+    pub fn to_dot(&self) -> String {
+        let mut diagram = String::from("digraph architecture {\n    rankdir=LR;\n    node [shape=box, fontname=\"Helvetica\"];\n");
+        for module in Module::ALL {
+            let mut members = self.members(module).peekable();
+            if members.peek().is_none() {
+                continue;
+            }
+            diagram.push_str(&format!("    subgraph cluster_{} {{\n        label=\"{}\";\n", module.id(), module));
+            for component in members {
+                let shape = match component.kind.as_str() {
+                    "interface" | "" => ", style=dashed",
+                    "library" => ", shape=component",
+                    _ => "",
+                };
+                diagram.push_str(&format!("        \"{}\" [label=\"{}\"{}];\n", component.name, component.name, shape));
+            }
+            diagram.push_str("    }\n");
+        }
+        for edge in &self.edges {
+            let style = match edge.kind {
+                EdgeKind::Inherits => ", style=dashed, arrowhead=empty",
+                _ => "",
+            };
+            diagram.push_str(&format!("    \"{}\" -> \"{}\" [label=\"{}\"{}];\n", edge.from, edge.to, edge.kind, style));
+        }
+        diagram.push_str("}\n");
+        diagram
+    }
+
+    /// Diagram as a fenced markdown block, `graphviz` for dot and anything else for mermaid
+    pub fn to_markdown(&self, format: &str) -> String {
+        match format {
+            "graphviz" | "dot" => format!("```dot\n{}```\n", self.to_dot()),
+            _ => format!("```mermaid\n{}```\n", self.to_mermaid()),
+        }
+    }
+}
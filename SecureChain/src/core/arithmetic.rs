@@ -0,0 +1,554 @@
+//! Exploitability of wrapping arithmetic
+//!
+//! Arithmetic inside `unchecked` blocks, and all arithmetic of sources
+//! written for compilers before 0.8, wraps silently on overflow. Most of it
+//! is a loop counter or a subtraction guarded a line earlier, so flagging
+//! each occurrence buries the few that matter. Instead, each externally
+//! callable function holding such arithmetic is executed: the contract is
+//! compiled as written and again with the arithmetic of its external and
+//! public functions checked, both builds are deployed into an in-memory EVM,
+//! and every function is called on each with boundary values of its
+//! parameter types. A call the build as written accepts but the checked
+//! build rejects with an arithmetic panic (`Panic(0x11)`) has wrapped, and
+//! only those functions are reported.
+//!
+//! Sources for compilers before 0.8 cannot be built as written by the
+//! installed compiler. They are built only in the checked form, with their
+//! internal functions and libraries (SafeMath among them) left unchecked,
+//! so an arithmetic panic there is a wrap in the deployed code.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use revm::db::{CacheDB, EmptyDB};
+use revm::primitives::{Address, Bytes, ExecutionResult, Output, TransactTo};
+use revm::EVM;
+
+use crate::core::parser::{matching_brace, strip_comments, ContractUnit, ParsedContract, UnitFunction};
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+use crate::utils::abi;
+use crate::utils::config::ArithmeticConfig;
+
+pub const TOOL_NAME: &str = "Arithmetic Execution";
+
+/// Selector of `Panic(uint256)`, the revert data of failed compiler checks
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Panic code of an arithmetic overflow or underflow
+const PANIC_OVERFLOW: u8 = 0x11;
+
+/// Every function is called this many times over, so state one call leaves
+/// behind (a deposit of the maximum, say) is there for the next
+const ROUNDS: usize = 2;
+
+/// Sender of every transaction
+const CALLER: [u8; 20] = [0x5c; 20];
+
+/// What to compile and call for one contract file
+pub struct Plan {
+    /// Whether the compiler the source asks for checks arithmetic itself
+    pub checked_by_default: bool,
+    /// The source with the arithmetic of its external and public functions checked
+    pub checked_source: String,
+    deployments: Vec<Deployment>,
+}
+
+/// A contract that can be deployed without constructor arguments, with the functions to call on it
+struct Deployment {
+    unit: String,
+    entries: Vec<Entry>,
+}
+
+/// An externally callable function whose parameters can all be generated
+struct Entry {
+    declared_in: String,
+    name: String,
+    signature: String,
+    selector: [u8; 4],
+    types: Vec<String>,
+    parameter_names: Vec<String>,
+    mutates: bool,
+    /// Lines of wrapping arithmetic in the body; empty for functions only called to set up state
+    lines: Vec<usize>,
+}
+
+/// Result of a call, as far as the comparison of the two builds needs it
+enum Outcome {
+    Success,
+    Overflow,
+    Failure,
+}
+
+/// The functions of a Solidity file to execute, or None when none of its
+/// deployable contracts can reach wrapping arithmetic
+pub fn plan(contract: &ParsedContract) -> Option<Plan> {
+    let checked_by_default = checked_by_default(&contract.pragma_directives);
+    let mut deployments = Vec::new();
+
+    for unit in contract.units.iter().filter(|unit| is_deployable(unit)) {
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+        for base in lineage(&contract.units, unit) {
+            for function in &base.functions {
+                if let Some(entry) = entry(base, function, checked_by_default) {
+                    if seen.insert(entry.signature.clone()) {
+                        entries.push(entry);
+                    }
+                }
+            }
+        }
+        if entries.iter().any(|entry| !entry.lines.is_empty()) {
+            deployments.push(Deployment { unit: unit.name.clone(), entries });
+        }
+    }
+
+    if deployments.is_empty() {
+        return None;
+    }
+    Some(Plan {
+        checked_by_default,
+        checked_source: checked_source(contract, checked_by_default),
+        deployments,
+    })
+}
+
+/// Whether the lowest compiler the version pragma allows checks arithmetic
+/// (0.8.0 on); sources without a pragma are taken to be current
+fn checked_by_default(pragmas: &[String]) -> bool {
+    let version = Regex::new(r"(\^|~|>=|>|<=|<|=)?\s*(\d+)\.(\d+)").expect("valid version pattern");
+    let spec = match pragmas.iter().find_map(|pragma| pragma.trim().strip_prefix("solidity")) {
+        Some(spec) => spec,
+        None => return true,
+    };
+    version
+        .captures_iter(spec)
+        .filter(|captures| !captures.get(1).is_some_and(|operator| operator.as_str().starts_with('<')))
+        .filter_map(|captures| Some((captures[2].parse::<u32>().ok()?, captures[3].parse::<u32>().ok()?)))
+        .min()
+        .is_none_or(|lowest| lowest >= (0, 8))
+}
+
+fn is_deployable(unit: &ContractUnit) -> bool {
+    unit.kind == "contract" && unit.constructor.as_ref().is_none_or(|constructor| constructor.parameter_types.is_empty())
+}
+
+/// The unit and the units of the same file it inherits from, nearest first
+fn lineage<'a>(units: &'a [ContractUnit], unit: &'a ContractUnit) -> Vec<&'a ContractUnit> {
+    let mut chain = vec![unit];
+    let mut index = 0;
+    while index < chain.len() {
+        for base in &chain[index].inheritance {
+            let name = base.split('(').next().unwrap_or(base).trim();
+            if let Some(base) = units.iter().find(|candidate| candidate.name == name && candidate.kind != "interface") {
+                if !chain.iter().any(|known| known.name == base.name) {
+                    chain.push(base);
+                }
+            }
+        }
+        index += 1;
+    }
+    chain
+}
+
+fn is_callable(function: &UnitFunction) -> bool {
+    matches!(function.visibility.as_str(), "public" | "external") && !matches!(function.name.as_str(), "" | "fallback" | "receive")
+}
+
+/// The function as a call target, if it is callable and every parameter is an elementary type
+fn entry(unit: &ContractUnit, function: &UnitFunction, checked_by_default: bool) -> Option<Entry> {
+    if !is_callable(function) {
+        return None;
+    }
+    let types = function.parameter_types.iter().map(|name| abi_type(name)).collect::<Option<Vec<_>>>()?;
+    let signature = format!("{}({})", function.name, types.join(","));
+    let hash = abi::keccak256(signature.as_bytes());
+    let mutates = !matches!(function.state_mutability.as_str(), "view" | "pure");
+    let lines = wrapping_lines(function, checked_by_default);
+    // Read-only functions without wrapping arithmetic cannot set up state for the others
+    if !mutates && lines.is_empty() {
+        return None;
+    }
+
+    Some(Entry {
+        declared_in: unit.name.clone(),
+        name: function.name.clone(),
+        signature,
+        selector: [hash[0], hash[1], hash[2], hash[3]],
+        types,
+        parameter_names: function.parameter_names.clone(),
+        mutates,
+        lines,
+    })
+}
+
+/// Canonical ABI type of an elementary parameter type, None for types no input is generated for
+fn abi_type(type_name: &str) -> Option<String> {
+    let type_name = type_name.trim().trim_end_matches("payable").trim();
+    let canonical = match type_name {
+        "uint" => "uint256".to_string(),
+        "int" => "int256".to_string(),
+        "byte" => "bytes1".to_string(),
+        "address" | "bool" => type_name.to_string(),
+        _ => {
+            let (prefix, size) = ["uint", "int", "bytes"]
+                .iter()
+                .find_map(|prefix| Some((*prefix, type_name.strip_prefix(prefix)?.parse::<usize>().ok()?)))?;
+            let valid = match prefix {
+                "bytes" => (1..=32).contains(&size),
+                _ => size % 8 == 0 && (8..=256).contains(&size),
+            };
+            if !valid {
+                return None;
+            }
+            type_name.to_string()
+        }
+    };
+    Some(canonical)
+}
+
+/// Lines of arithmetic in a function body that wraps instead of reverting:
+/// inside `unchecked` blocks, or anywhere when the compiler does not check it
+fn wrapping_lines(function: &UnitFunction, checked_by_default: bool) -> Vec<usize> {
+    let body = match &function.body {
+        Some(body) => strip_comments(body),
+        None => return Vec::new(),
+    };
+    let operator = Regex::new(r"\+\+|--|[+\-*]=|\*\*|[\w)\]]\s*[+\-*]\s*[\w(]").expect("valid operator pattern");
+    let regions = if checked_by_default { unchecked_blocks(&body) } else { vec![Range { start: 0, end: body.len() }] };
+
+    let mut lines: Vec<usize> = regions
+        .iter()
+        .flat_map(|region| operator.find_iter(&body[region.clone()]).map(move |found| region.start + found.start()))
+        .map(|offset| function.body_line_number + body[..offset].matches('\n').count())
+        .collect();
+    lines.sort_unstable();
+    lines.dedup();
+    lines
+}
+
+/// Byte ranges of the `unchecked` blocks of a body, braces included
+fn unchecked_blocks(body: &str) -> Vec<Range<usize>> {
+    let keyword = Regex::new(r"\bunchecked\s*\{").expect("valid unchecked pattern");
+    keyword
+        .find_iter(body)
+        .filter_map(|found| {
+            let open = found.end() - 1;
+            matching_brace(body, open).map(|close| open..close + 1)
+        })
+        .collect()
+}
+
+/// The source with the arithmetic of every external and public function
+/// checked, and everything else as the compiler the source asks for runs it
+fn checked_source(contract: &ParsedContract, checked_by_default: bool) -> String {
+    let source = &contract.source_code;
+    let stripped = contract.code();
+    let keyword = Regex::new(r"\bunchecked\s*\{").expect("valid unchecked pattern");
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+
+    for unit in &contract.units {
+        let functions = unit.functions.iter().map(|function| (function, !is_callable(function) || unit.kind == "library"));
+        for (function, helper) in functions.chain(unit.constructor.iter().map(|constructor| (constructor, true))) {
+            let body = match &function.body {
+                Some(body) => body,
+                None => continue,
+            };
+            let start = match contract.offset_of(body) {
+                Some(start) => start,
+                None => continue,
+            };
+            let end = start + body.len();
+
+            if checked_by_default && !helper {
+                // Blanking the keyword keeps the block, its scope and every line number
+                for found in keyword.find_iter(&stripped[start..end]) {
+                    let at = start + found.start();
+                    edits.push((at..at + "unchecked".len(), " ".repeat("unchecked".len())));
+                }
+            } else if !checked_by_default && helper {
+                // 0.8 checks everything, so the helpers are put back the way the old compiler ran them
+                edits.push((start..start, " unchecked {".to_string()));
+                edits.push((end..end, "} ".to_string()));
+            }
+        }
+    }
+
+    if !checked_by_default {
+        let pragma = Regex::new(r"pragma\s+solidity[^;]*;").expect("valid pragma pattern");
+        for found in pragma.find_iter(stripped) {
+            edits.push((found.range(), "pragma solidity >=0.8.0;".to_string()));
+        }
+    }
+
+    // Back to front, so earlier offsets still hold
+    edits.sort_by(|a, b| b.0.start.cmp(&a.0.start).then(b.0.end.cmp(&a.0.end)));
+    let mut rewritten = source.to_string();
+    for (range, replacement) in edits {
+        rewritten.replace_range(range, &replacement);
+    }
+    rewritten
+}
+
+/// Creation code of each contract in a solc standard JSON output
+pub fn creation_code(output: &str, file: &str) -> Result<HashMap<String, Vec<u8>>> {
+    let json: serde_json::Value = serde_json::from_str(output)?;
+    let errors: Vec<&str> = json
+        .get("errors")
+        .and_then(|errors| errors.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|diagnostic| diagnostic.get("severity").and_then(|s| s.as_str()) == Some("error"))
+        .filter_map(|diagnostic| diagnostic.get("message").and_then(|m| m.as_str()))
+        .collect();
+    if !errors.is_empty() {
+        return Err(anyhow!("compilation failed: {}", errors.join("; ")));
+    }
+
+    let contracts = json
+        .get("contracts")
+        .and_then(|contracts| contracts.get(file))
+        .and_then(|contracts| contracts.as_object())
+        .ok_or_else(|| anyhow!("solc returned no contracts for {}", file))?;
+    // Unlinked library placeholders do not decode, and such contracts are left out
+    Ok(contracts
+        .iter()
+        .filter_map(|(name, contract)| {
+            let object = contract.pointer("/evm/bytecode/object")?.as_str()?;
+            let code = abi::decode_hex(object).ok().filter(|code| !code.is_empty())?;
+            Some((name.clone(), code))
+        })
+        .collect())
+}
+
+/// Call every planned function with boundary inputs on both builds and
+/// report those where the arithmetic demonstrably wraps
+///
+/// `original` is the build as written, None when the source needs a compiler before 0.8.
+pub fn execute(
+    contract: &ParsedContract,
+    plan: &Plan,
+    original: Option<&HashMap<String, Vec<u8>>>,
+    checked: &HashMap<String, Vec<u8>>,
+    settings: &ArithmeticConfig,
+) -> Result<Vec<Vulnerability>> {
+    let mut findings = Vec::new();
+    let mut reported = HashSet::new();
+
+    for deployment in &plan.deployments {
+        let mut checked_chain = match checked.get(&deployment.unit) {
+            Some(code) => match Chain::deploy(code, settings.gas_limit)? {
+                Some(chain) => chain,
+                None => continue,
+            },
+            None => continue,
+        };
+        let mut original_chain = match original.map(|codes| codes.get(&deployment.unit)) {
+            Some(Some(code)) => match Chain::deploy(code, settings.gas_limit)? {
+                Some(chain) => Some(chain),
+                None => continue,
+            },
+            Some(None) => continue,
+            None => None,
+        };
+
+        for _ in 0..ROUNDS {
+            for entry in &deployment.entries {
+                for inputs in boundary_inputs(&entry.types, settings.max_calls) {
+                    let mut data = entry.selector.to_vec();
+                    data.extend(inputs.iter().flatten());
+
+                    let checked_outcome = checked_chain.call(&data)?;
+                    let accepted = match original_chain.as_mut() {
+                        Some(chain) => matches!(chain.call(&data)?, Outcome::Success),
+                        None => true,
+                    };
+                    if entry.lines.is_empty() || !accepted || !matches!(checked_outcome, Outcome::Overflow) {
+                        continue;
+                    }
+                    if reported.insert((entry.declared_in.clone(), entry.signature.clone())) {
+                        findings.push(wrap_finding(contract, plan, &deployment.unit, entry, &inputs));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(findings)
+}
+
+/// A contract deployed into its own in-memory chain
+struct Chain {
+    evm: EVM<CacheDB<EmptyDB>>,
+    contract: Address,
+}
+
+impl Chain {
+    /// Deploy creation code without constructor arguments; None if the constructor reverts
+    fn deploy(code: &[u8], gas_limit: u64) -> Result<Option<Self>> {
+        let mut evm = EVM::new();
+        evm.database(CacheDB::new(EmptyDB::default()));
+        evm.env.tx.caller = Address::from(CALLER);
+        evm.env.tx.gas_limit = gas_limit;
+        evm.env.tx.transact_to = TransactTo::create();
+        evm.env.tx.data = Bytes::from(code.to_vec());
+
+        match evm.transact_commit().map_err(|e| anyhow!("EVM error: {:?}", e))? {
+            ExecutionResult::Success { output: Output::Create(_, Some(contract)), .. } => Ok(Some(Self { evm, contract })),
+            _ => Ok(None),
+        }
+    }
+
+    fn call(&mut self, data: &[u8]) -> Result<Outcome> {
+        self.evm.env.tx.transact_to = TransactTo::Call(self.contract);
+        self.evm.env.tx.data = Bytes::from(data.to_vec());
+
+        Ok(match self.evm.transact_commit().map_err(|e| anyhow!("EVM error: {:?}", e))? {
+            ExecutionResult::Success { .. } => Outcome::Success,
+            ExecutionResult::Revert { output, .. } if is_overflow_panic(&output) => Outcome::Overflow,
+            _ => Outcome::Failure,
+        })
+    }
+}
+
+fn is_overflow_panic(output: &[u8]) -> bool {
+    output.len() == 36 && output[..4] == PANIC_SELECTOR && output[4..35].iter().all(|b| *b == 0) && output[35] == PANIC_OVERFLOW
+}
+
+/// Combinations of boundary values of the parameter types, at most `limit` of them
+fn boundary_inputs(types: &[String], limit: usize) -> Vec<Vec<[u8; 32]>> {
+    let values: Vec<Vec<[u8; 32]>> = types.iter().map(|abi_type| boundaries(abi_type)).collect();
+    let total = values.iter().try_fold(1usize, |total, options| total.checked_mul(options.len())).unwrap_or(usize::MAX);
+
+    // Counting in mixed radix, the last parameter changing fastest
+    (0..total.min(limit))
+        .map(|mut index| {
+            let mut combination = vec![[0u8; 32]; values.len()];
+            for (slot, options) in values.iter().enumerate().rev() {
+                combination[slot] = options[index % options.len()];
+                index /= options.len();
+            }
+            combination
+        })
+        .collect()
+}
+
+/// Values at the edges of an ABI type's range, the likeliest to overflow first
+fn boundaries(abi_type: &str) -> Vec<[u8; 32]> {
+    let one = low_bits(1);
+    if let Some(bits) = abi_type.strip_prefix("uint").and_then(|bits| bits.parse::<usize>().ok()) {
+        // A square root of the range overflows when multiplied by itself
+        let mut root = [0u8; 32];
+        root[31 - bits / 16] = 1 << ((bits / 2) % 8);
+        return vec![low_bits(bits), one, [0u8; 32], root];
+    }
+    if let Some(bits) = abi_type.strip_prefix("int").and_then(|bits| bits.parse::<usize>().ok()) {
+        let max = low_bits(bits - 1);
+        let min = max.map(|byte| !byte);
+        return vec![max, min, [0xff; 32], one, [0u8; 32]];
+    }
+    if let Some(size) = abi_type.strip_prefix("bytes").and_then(|size| size.parse::<usize>().ok()) {
+        let mut full = [0u8; 32];
+        full[..size].fill(0xff);
+        return vec![full, [0u8; 32]];
+    }
+    match abi_type {
+        "address" => {
+            let mut caller = [0u8; 32];
+            caller[12..].copy_from_slice(&CALLER);
+            vec![caller, [0u8; 32]]
+        }
+        _ => vec![one, [0u8; 32]],
+    }
+}
+
+/// A word with its lowest `bits` bits set
+fn low_bits(bits: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    for bit in 0..bits {
+        word[31 - bit / 8] |= 1 << (bit % 8);
+    }
+    word
+}
+
+/// An input word as it would be written in Solidity
+fn render(abi_type: &str, word: &[u8; 32]) -> String {
+    if abi_type == "address" {
+        return format!("0x{}", abi::encode_hex(&word[12..]));
+    }
+    if abi_type == "bool" {
+        return (word[31] != 0).to_string();
+    }
+    if abi_type.starts_with("bytes") {
+        return format!("0x{}", abi::encode_hex(word));
+    }
+    if abi_type.starts_with("int") && word[0] & 0x80 != 0 {
+        // Two's complement: negate, then print the magnitude
+        let mut magnitude = word.map(|byte| !byte);
+        for byte in magnitude.iter_mut().rev() {
+            let (sum, carry) = byte.overflowing_add(1);
+            *byte = sum;
+            if !carry {
+                break;
+            }
+        }
+        return format!("-{}", abi::to_decimal(&magnitude));
+    }
+    abi::to_decimal(word)
+}
+
+fn wrap_finding(contract: &ParsedContract, plan: &Plan, unit: &str, entry: &Entry, inputs: &[[u8; 32]]) -> Vulnerability {
+    let arguments: Vec<String> = entry
+        .types
+        .iter()
+        .zip(inputs)
+        .enumerate()
+        .map(|(index, (abi_type, word))| match entry.parameter_names.get(index).filter(|name| !name.is_empty()) {
+            Some(name) => format!("{} = {}", name, render(abi_type, word)),
+            None => render(abi_type, word),
+        })
+        .collect();
+    let lines: Vec<String> = entry.lines.iter().map(|line| line.to_string()).collect();
+    let (what, why) = if plan.checked_by_default {
+        ("unchecked arithmetic", "the contract as written accepted the call")
+    } else {
+        ("arithmetic", "the compiler the contract is written for has no overflow checks")
+    };
+    let severity = if entry.mutates { "High" } else { "Medium" };
+
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: format!("Reachable Arithmetic Overflow in {}.{}", entry.declared_in, entry.name),
+        description: format!(
+            "Calling `{}` on {} with ({}) overflows the {} on line{} {}: with the arithmetic checked the call \
+             reverts with Panic(0x11), and {}, so the value wrapped.{}",
+            entry.signature,
+            unit,
+            arguments.join(", "),
+            what,
+            if lines.len() == 1 { "" } else { "s" },
+            lines.join(", "),
+            why,
+            if entry.mutates { "" } else { " The function is read-only, so the wrapped value is returned to its callers." },
+        ),
+        severity: severity.to_string(),
+        category: VulnerabilityCategory::IntegerOverflow,
+        file_path: contract.name.clone(),
+        line_number: entry.lines.first().copied(),
+        code_snippet: None,
+        recommendation: Some(if plan.checked_by_default {
+            "Remove the `unchecked` block, or bound the inputs so the arithmetic cannot wrap.".to_string()
+        } else {
+            "Compile with Solidity 0.8 or later, or use checked arithmetic (SafeMath) for these operations.".to_string()
+        }),
+        references: vec!["https://docs.soliditylang.org/en/latest/control-structures.html#checked-or-unchecked-arithmetic".to_string()],
+        cwe_id: Some("CWE-190".to_string()),
+        tool: TOOL_NAME.to_string(),
+        confidence: 1.0,
+        provenance: None,
+        severity_overrides: Vec::new(),
+        cvss: None,
+        detected_by: Vec::new(),
+    }
+}
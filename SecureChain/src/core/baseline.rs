@@ -0,0 +1,86 @@
+//! Baseline comparison for CI regression checks
+//!
+//! The findings of a run are matched against those of an earlier run, from
+//! its `analyze --json` output or JSON report: same tool, file and title, as
+//! fix verification matches them, since lines move as code changes around a
+//! finding. Findings pair one to one, so a check reported once more than
+//! before counts as new. Matched findings keep when they were first seen and
+//! the severity an auditor gave them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::report::vulnerability::{Severity, Vulnerability};
+
+/// How the findings of a run differ from a baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineComparison {
+    /// Results file compared against
+    pub baseline: String,
+    /// Ids of the run's findings the baseline does not have
+    pub new: Vec<String>,
+    /// Ids of the run's findings the baseline already had
+    pub persistent: Vec<String>,
+    /// Baseline findings the run no longer reports
+    pub fixed: Vec<Vulnerability>,
+}
+
+impl BaselineComparison {
+    /// New findings among `vulnerabilities` at or above `threshold`
+    pub fn blocking<'a>(&'a self, vulnerabilities: &'a [Vulnerability], threshold: &Severity) -> Vec<&'a Vulnerability> {
+        vulnerabilities
+            .iter()
+            .filter(|vuln| vuln.severity_priority() >= threshold.priority() && self.new.contains(&vuln.id))
+            .collect()
+    }
+}
+
+/// Compare the findings of a run with `prior`, the findings of `baseline`
+pub fn compare(baseline: &str, prior: &[Vulnerability], current: &mut [Vulnerability]) -> BaselineComparison {
+    let mut unmatched: HashMap<(&str, &str, &str), Vec<usize>> = HashMap::new();
+    for (index, finding) in prior.iter().enumerate() {
+        unmatched.entry(issue_key(finding)).or_default().push(index);
+    }
+
+    // Findings still on their old line pair first, so a duplicate that moved does not take their match
+    let mut pairs: Vec<Option<usize>> = vec![None; current.len()];
+    for same_line in [true, false] {
+        for (finding, pair) in current.iter().zip(pairs.iter_mut()).filter(|(_, pair)| pair.is_none()) {
+            let candidates = match unmatched.get_mut(&issue_key(finding)) {
+                Some(candidates) => candidates,
+                None => continue,
+            };
+            let position = candidates
+                .iter()
+                .position(|&index| !same_line || prior[index].line_number == finding.line_number);
+            *pair = position.map(|position| candidates.remove(position));
+        }
+    }
+
+    let mut fixed: Vec<usize> = unmatched.into_values().flatten().collect();
+    fixed.sort_unstable();
+
+    let mut new = Vec::new();
+    let mut persistent = Vec::new();
+    for (finding, pair) in current.iter_mut().zip(pairs) {
+        match pair {
+            Some(index) => {
+                finding.merge_provenance(&prior[index]);
+                finding.merge_severity_overrides(&prior[index]);
+                persistent.push(finding.id.clone());
+            }
+            None => new.push(finding.id.clone()),
+        }
+    }
+
+    BaselineComparison {
+        baseline: baseline.to_string(),
+        new,
+        persistent,
+        fixed: fixed.into_iter().map(|index| prior[index].clone()).collect(),
+    }
+}
+
+fn issue_key(vuln: &Vulnerability) -> (&str, &str, &str) {
+    (vuln.tool.as_str(), vuln.file_path.as_str(), vuln.title.as_str())
+}
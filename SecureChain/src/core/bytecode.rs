@@ -0,0 +1,446 @@
+//! Analysis of deployed bytecode
+//!
+//! Many addresses worth auditing have no verified source. For those, the
+//! runtime bytecode is read from the network's RPC endpoint and disassembled,
+//! the opcodes that matter most to a reviewer (SELFDESTRUCT, DELEGATECALL,
+//! CREATE2, CALLCODE, ORIGIN) are located, and the selectors of the function
+//! dispatcher are extracted and named from a bundled signature list, or from
+//! 4byte.directory with `networks.signatures.lookup`. Names looked up online
+//! are cached below `general.cache_dir`. The findings built on this are fewer
+//! and less certain than a source analysis: an opcode being present does not
+//! show who can reach it.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use crate::core::rpc::RpcClient;
+use crate::core::selectors;
+use crate::utils::abi::{decode_hex, encode_hex};
+use crate::utils::artifact;
+use crate::utils::cache;
+use crate::utils::config::{Config, SignatureConfig};
+
+pub const TOOL_NAME: &str = "Bytecode Analysis";
+
+/// Signatures bundled into the binary
+const SIGNATURES: &str = include_str!("../../corpus/signatures.txt");
+
+/// Opcodes whose every use is reported with its offset
+pub const NOTABLE_OPCODES: &[&str] = &["SELFDESTRUCT", "DELEGATECALL", "CREATE2", "CREATE", "CALLCODE", "ORIGIN"];
+
+/// Code of an EIP-1167 minimal proxy around its 20-byte implementation address
+const MINIMAL_PROXY_PREFIX: &str = "363d3d373d3d3d363d73";
+const MINIMAL_PROXY_SUFFIX: &str = "5af43d82803e903d91602b57fd5bf3";
+
+/// One disassembled instruction
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    pub offset: usize,
+    pub opcode: u8,
+    /// Bytes pushed by a PUSH instruction
+    pub immediate: Vec<u8>,
+}
+
+impl Instruction {
+    pub fn name(&self) -> &'static str {
+        opcode_name(self.opcode)
+    }
+}
+
+/// A dispatcher selector and the signatures known to hash to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedSelector {
+    pub selector: String,
+    /// Several when signatures collide; empty when none is known
+    pub signatures: Vec<String>,
+}
+
+/// What the runtime bytecode of one address shows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BytecodeAnalysis {
+    pub address: String,
+    /// Runtime code size in bytes, metadata included
+    pub size: usize,
+    /// Offsets of each notable opcode found
+    pub opcodes: BTreeMap<String, Vec<usize>>,
+    pub selectors: Vec<ResolvedSelector>,
+    /// Implementation of an EIP-1167 minimal proxy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimal_proxy: Option<String>,
+}
+
+impl BytecodeAnalysis {
+    /// Disassemble `code`, deployed at `address`; selectors are left unnamed
+    pub fn from_code(address: &str, code: &[u8]) -> Self {
+        let instructions = disassemble(strip_metadata(code));
+        let mut opcodes: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for instruction in &instructions {
+            if NOTABLE_OPCODES.contains(&instruction.name()) {
+                opcodes.entry(instruction.name().to_string()).or_default().push(instruction.offset);
+            }
+        }
+        Self {
+            address: address.to_string(),
+            size: code.len(),
+            opcodes,
+            selectors: dispatcher_selectors(&instructions)
+                .into_iter()
+                .map(|selector| ResolvedSelector { selector, signatures: Vec::new() })
+                .collect(),
+            minimal_proxy: minimal_proxy(code),
+        }
+    }
+
+    /// Runtime code of `address` from `rpc`, disassembled
+    pub fn fetch(rpc: &RpcClient, address: &str) -> Result<Self> {
+        let code = rpc.request("eth_getCode", serde_json::json!([address, "latest"]))?;
+        let code = decode_hex(code.as_str().unwrap_or_default()).map_err(|e| anyhow!("Invalid eth_getCode result: {}", e))?;
+        if code.is_empty() {
+            return Err(anyhow!("{} has no code; it is not a contract on this network", address));
+        }
+        Ok(Self::from_code(address, &code))
+    }
+
+    /// Name the selectors from `signatures`
+    pub fn resolve(&mut self, signatures: &mut SignatureDb) {
+        for resolved in &mut self.selectors {
+            resolved.signatures = signatures.lookup(&resolved.selector);
+        }
+    }
+
+    /// Offsets of `opcode`, empty when the code never uses it
+    pub fn offsets(&self, opcode: &str) -> &[usize] {
+        self.opcodes.get(opcode).map_or(&[], Vec::as_slice)
+    }
+
+    /// Signatures of the dispatcher whose function name is one of `names`
+    pub fn functions_named(&self, names: &[&str]) -> Vec<&str> {
+        self.selectors
+            .iter()
+            .flat_map(|resolved| resolved.signatures.iter())
+            .filter(|signature| names.contains(&signature.split('(').next().unwrap_or_default()))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Report appendix listing the dispatcher and the notable opcodes
+    pub fn to_markdown(&self) -> String {
+        let mut content = format!("Runtime code of `{}`: {} bytes.\n\n", self.address, self.size);
+        if let Some(implementation) = &self.minimal_proxy {
+            content.push_str(&format!("EIP-1167 minimal proxy to `{}`.\n\n", implementation));
+        }
+        content.push_str("| Selector | Signature |\n|----------|-----------|\n");
+        for resolved in &self.selectors {
+            let signatures = match resolved.signatures.as_slice() {
+                [] => "unknown".to_string(),
+                signatures => signatures.iter().map(|signature| format!("`{}`", signature)).collect::<Vec<_>>().join(" or "),
+            };
+            content.push_str(&format!("| `{}` | {} |\n", resolved.selector, signatures));
+        }
+        if !self.opcodes.is_empty() {
+            content.push_str("\n| Opcode | Offsets |\n|--------|---------|\n");
+            for (opcode, offsets) in &self.opcodes {
+                let offsets: Vec<String> = offsets.iter().map(|offset| format!("{:#06x}", offset)).collect();
+                content.push_str(&format!("| {} | {} |\n", opcode, offsets.join(", ")));
+            }
+        }
+        content
+    }
+}
+
+/// Known signatures by selector: the bundled list, the lookup cache and, if enabled, 4byte.directory
+pub struct SignatureDb {
+    known: HashMap<String, Vec<String>>,
+    looked_up: HashMap<String, Vec<String>>,
+    config: SignatureConfig,
+    cache_path: PathBuf,
+}
+
+impl SignatureDb {
+    pub fn load(config: &Config) -> Self {
+        let mut known: HashMap<String, Vec<String>> = HashMap::new();
+        for signature in SIGNATURES.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            known.entry(selectors::selector(signature)).or_default().push(signature.to_string());
+        }
+        let cache_path = cache::expand_home(&config.general.cache_dir).join("signatures.json");
+        let looked_up = std::fs::read_to_string(&cache_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { known, looked_up, config: config.networks.signatures.clone(), cache_path }
+    }
+
+    /// Signatures hashing to `selector`, fetched online once if unknown and lookups are enabled
+    pub fn lookup(&mut self, selector: &str) -> Vec<String> {
+        if let Some(signatures) = self.known.get(selector).or_else(|| self.looked_up.get(selector)) {
+            return signatures.clone();
+        }
+        if !self.config.lookup {
+            return Vec::new();
+        }
+        match self.fetch(selector) {
+            Ok(signatures) => {
+                self.looked_up.insert(selector.to_string(), signatures.clone());
+                signatures
+            }
+            Err(e) => {
+                log::warn!("Could not look up selector {}: {}", selector, e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Keep the signatures looked up online for the next run
+    pub fn save(&self) -> Result<()> {
+        if self.looked_up.is_empty() {
+            return Ok(());
+        }
+        if let Some(dir) = self.cache_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        artifact::write_atomic(&self.cache_path, serde_json::to_string(&self.looked_up)?)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "explorers")]
+    fn fetch(&self, selector: &str) -> Result<Vec<String>> {
+        let answer: serde_json::Value = ureq::get(&self.config.api_url)
+            .query("hex_signature", selector)
+            .timeout(std::time::Duration::from_secs(self.config.timeout))
+            .call()?
+            .into_json()?;
+        let mut signatures: Vec<String> = answer["results"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|result| result["text_signature"].as_str())
+            // The directory takes submissions unchecked, so only signatures that really hash to the selector are kept
+            .filter(|signature| selectors::selector(signature) == selector)
+            .map(str::to_string)
+            .collect();
+        // Oldest first: the earliest submission is usually the real one, later ones are made to collide
+        signatures.reverse();
+        Ok(signatures)
+    }
+
+    #[cfg(not(feature = "explorers"))]
+    fn fetch(&self, selector: &str) -> Result<Vec<String>> {
+        Err(anyhow!("looking up {} needs a build with the `explorers` feature", selector))
+    }
+}
+
+/// `code` without the CBOR metadata solc appends, whose length is in its last two bytes
+pub fn strip_metadata(code: &[u8]) -> &[u8] {
+    if code.len() < 2 {
+        return code;
+    }
+    let length = u16::from_be_bytes([code[code.len() - 2], code[code.len() - 1]]) as usize;
+    match code.len().checked_sub(length + 2) {
+        // The metadata is a CBOR map of one to three entries
+        Some(start) if length > 0 && (0xa1..=0xa3).contains(&code[start]) => &code[..start],
+        _ => code,
+    }
+}
+
+/// Instructions of `code`; a PUSH running past the end takes what is left
+pub fn disassemble(code: &[u8]) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+    while offset < code.len() {
+        let opcode = code[offset];
+        let pushed = match opcode {
+            0x60..=0x7f => (opcode - 0x5f) as usize,
+            _ => 0,
+        };
+        let end = (offset + 1 + pushed).min(code.len());
+        instructions.push(Instruction { offset, opcode, immediate: code[offset + 1..end].to_vec() });
+        offset += 1 + pushed;
+    }
+    instructions
+}
+
+/// Selectors the dispatcher compares calldata against, as in `DUP1 PUSH4 <selector> EQ PUSH2 <dest> JUMPI`
+pub fn dispatcher_selectors(instructions: &[Instruction]) -> Vec<String> {
+    let mut found = Vec::new();
+    for (index, instruction) in instructions.iter().enumerate() {
+        if instruction.opcode != 0x63 || instruction.immediate.len() != 4 {
+            continue;
+        }
+        // The compiler may place a DUP between the pushed selector and its EQ
+        let compared = instructions[index + 1..]
+            .iter()
+            .take(2)
+            .find(|next| !(0x80..=0x8f).contains(&next.opcode))
+            .is_some_and(|next| next.opcode == 0x14);
+        let selector = format!("0x{}", encode_hex(&instruction.immediate));
+        if compared && selector != "0xffffffff" && !found.contains(&selector) {
+            found.push(selector);
+        }
+    }
+    found
+}
+
+/// Implementation address of an EIP-1167 minimal proxy
+fn minimal_proxy(code: &[u8]) -> Option<String> {
+    let hex = encode_hex(code);
+    let address = hex.strip_prefix(MINIMAL_PROXY_PREFIX)?.strip_suffix(MINIMAL_PROXY_SUFFIX)?;
+    (address.len() == 40).then(|| format!("0x{}", address))
+}
+
+/// Mnemonic of `opcode`, `INVALID` for unassigned ones
+pub fn opcode_name(opcode: u8) -> &'static str {
+    match opcode {
+        0x00 => "STOP",
+        0x01 => "ADD",
+        0x02 => "MUL",
+        0x03 => "SUB",
+        0x04 => "DIV",
+        0x05 => "SDIV",
+        0x06 => "MOD",
+        0x07 => "SMOD",
+        0x08 => "ADDMOD",
+        0x09 => "MULMOD",
+        0x0a => "EXP",
+        0x0b => "SIGNEXTEND",
+        0x10 => "LT",
+        0x11 => "GT",
+        0x12 => "SLT",
+        0x13 => "SGT",
+        0x14 => "EQ",
+        0x15 => "ISZERO",
+        0x16 => "AND",
+        0x17 => "OR",
+        0x18 => "XOR",
+        0x19 => "NOT",
+        0x1a => "BYTE",
+        0x1b => "SHL",
+        0x1c => "SHR",
+        0x1d => "SAR",
+        0x20 => "KECCAK256",
+        0x30 => "ADDRESS",
+        0x31 => "BALANCE",
+        0x32 => "ORIGIN",
+        0x33 => "CALLER",
+        0x34 => "CALLVALUE",
+        0x35 => "CALLDATALOAD",
+        0x36 => "CALLDATASIZE",
+        0x37 => "CALLDATACOPY",
+        0x38 => "CODESIZE",
+        0x39 => "CODECOPY",
+        0x3a => "GASPRICE",
+        0x3b => "EXTCODESIZE",
+        0x3c => "EXTCODECOPY",
+        0x3d => "RETURNDATASIZE",
+        0x3e => "RETURNDATACOPY",
+        0x3f => "EXTCODEHASH",
+        0x40 => "BLOCKHASH",
+        0x41 => "COINBASE",
+        0x42 => "TIMESTAMP",
+        0x43 => "NUMBER",
+        0x44 => "PREVRANDAO",
+        0x45 => "GASLIMIT",
+        0x46 => "CHAINID",
+        0x47 => "SELFBALANCE",
+        0x48 => "BASEFEE",
+        0x49 => "BLOBHASH",
+        0x4a => "BLOBBASEFEE",
+        0x50 => "POP",
+        0x51 => "MLOAD",
+        0x52 => "MSTORE",
+        0x53 => "MSTORE8",
+        0x54 => "SLOAD",
+        0x55 => "SSTORE",
+        0x56 => "JUMP",
+        0x57 => "JUMPI",
+        0x58 => "PC",
+        0x59 => "MSIZE",
+        0x5a => "GAS",
+        0x5b => "JUMPDEST",
+        0x5c => "TLOAD",
+        0x5d => "TSTORE",
+        0x5e => "MCOPY",
+        0x5f => "PUSH0",
+        0x60 => "PUSH1",
+        0x61 => "PUSH2",
+        0x62 => "PUSH3",
+        0x63 => "PUSH4",
+        0x64 => "PUSH5",
+        0x65 => "PUSH6",
+        0x66 => "PUSH7",
+        0x67 => "PUSH8",
+        0x68 => "PUSH9",
+        0x69 => "PUSH10",
+        0x6a => "PUSH11",
+        0x6b => "PUSH12",
+        0x6c => "PUSH13",
+        0x6d => "PUSH14",
+        0x6e => "PUSH15",
+        0x6f => "PUSH16",
+        0x70 => "PUSH17",
+        0x71 => "PUSH18",
+        0x72 => "PUSH19",
+        0x73 => "PUSH20",
+        0x74 => "PUSH21",
+        0x75 => "PUSH22",
+        0x76 => "PUSH23",
+        0x77 => "PUSH24",
+        0x78 => "PUSH25",
+        0x79 => "PUSH26",
+        0x7a => "PUSH27",
+        0x7b => "PUSH28",
+        0x7c => "PUSH29",
+        0x7d => "PUSH30",
+        0x7e => "PUSH31",
+        0x7f => "PUSH32",
+        0x80 => "DUP1",
+        0x81 => "DUP2",
+        0x82 => "DUP3",
+        0x83 => "DUP4",
+        0x84 => "DUP5",
+        0x85 => "DUP6",
+        0x86 => "DUP7",
+        0x87 => "DUP8",
+        0x88 => "DUP9",
+        0x89 => "DUP10",
+        0x8a => "DUP11",
+        0x8b => "DUP12",
+        0x8c => "DUP13",
+        0x8d => "DUP14",
+        0x8e => "DUP15",
+        0x8f => "DUP16",
+        0x90 => "SWAP1",
+        0x91 => "SWAP2",
+        0x92 => "SWAP3",
+        0x93 => "SWAP4",
+        0x94 => "SWAP5",
+        0x95 => "SWAP6",
+        0x96 => "SWAP7",
+        0x97 => "SWAP8",
+        0x98 => "SWAP9",
+        0x99 => "SWAP10",
+        0x9a => "SWAP11",
+        0x9b => "SWAP12",
+        0x9c => "SWAP13",
+        0x9d => "SWAP14",
+        0x9e => "SWAP15",
+        0x9f => "SWAP16",
+        0xa0 => "LOG0",
+        0xa1 => "LOG1",
+        0xa2 => "LOG2",
+        0xa3 => "LOG3",
+        0xa4 => "LOG4",
+        0xf0 => "CREATE",
+        0xf1 => "CALL",
+        0xf2 => "CALLCODE",
+        0xf3 => "RETURN",
+        0xf4 => "DELEGATECALL",
+        0xf5 => "CREATE2",
+        0xfa => "STATICCALL",
+        0xfd => "REVERT",
+        0xfe => "INVALID",
+        0xff => "SELFDESTRUCT",
+        _ => "INVALID",
+    }
+}
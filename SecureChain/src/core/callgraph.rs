@@ -0,0 +1,236 @@
+//! Call graph of the contracts in a run
+//!
+//! Calls are resolved by name across every contract of the run, so a call to
+//! an overridden or inherited function reaches all candidates. This
+//! over-approximates reachability, which keeps reachability-based findings
+//! on the side of caution.
+
+use regex::Regex;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::core::parser::{matching_brace, matching_paren, strip_comments, ParsedContract, UnitFunction};
+
+/// A function of the run
+pub struct FunctionNode<'a> {
+    /// Source file, as findings refer to it in `file_path`
+    pub file: &'a str,
+    pub unit: &'a str,
+    /// `contract`, `abstract contract` or `library`
+    pub unit_kind: &'a str,
+    pub function: &'a UnitFunction,
+    /// Last line of the function, estimated from its body
+    pub end_line: usize,
+}
+
+/// Functions of the run and the calls between them
+pub struct CallGraph<'a> {
+    pub nodes: Vec<FunctionNode<'a>>,
+    callees: Vec<Vec<usize>>,
+    callers: Vec<Vec<usize>>,
+    /// Functions called from constructors, modifiers, `fallback` and `receive`
+    implicit: Vec<usize>,
+    /// Functions called from each modifier, by modifier name
+    modifier_callees: HashMap<String, Vec<usize>>,
+}
+
+impl<'a> CallGraph<'a> {
+    /// Build the graph of every function declared outside interfaces
+    pub fn build(contracts: &'a [ParsedContract]) -> Self {
+        let call_pattern = Regex::new(r"(?:([A-Za-z_]\w*)\s*\.\s*)?\b([A-Za-z_]\w*)\s*\(").expect("valid call pattern");
+
+        let nodes: Vec<FunctionNode<'a>> = contracts
+            .iter()
+            .flat_map(|contract| {
+                contract
+                    .units
+                    .iter()
+                    .filter(|unit| unit.kind != "interface")
+                    .flat_map(move |unit| {
+                        unit.functions.iter().map(move |function| FunctionNode {
+                            file: contract.name.as_str(),
+                            unit: unit.name.as_str(),
+                            unit_kind: unit.kind.as_str(),
+                            function,
+                            // Headers rarely span more than a few lines
+                            end_line: function.line_number + function.body.as_deref().unwrap_or_default().lines().count() + 3,
+                        })
+                    })
+            })
+            .collect();
+
+        let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (index, node) in nodes.iter().enumerate() {
+            by_name.entry(node.function.name.as_str()).or_default().push(index);
+        }
+
+        let resolve = |body: &str| -> Vec<usize> {
+            let mut targets: Vec<usize> = Vec::new();
+            for captures in call_pattern.captures_iter(body) {
+                let name = captures.get(2).unwrap();
+                let candidates = match by_name.get(name.as_str()) {
+                    Some(candidates) => candidates,
+                    None => continue,
+                };
+
+                let receiver = captures.get(1).map(|m| m.as_str());
+                let member = receiver.is_some() || body[..name.start()].trim_end().ends_with('.');
+                for &candidate in candidates {
+                    // `x.f()` is a call into this run only for `super`, `this` and library functions
+                    let resolves = !member
+                        || matches!(receiver, Some("super") | Some("this"))
+                        || nodes[candidate].unit_kind == "library";
+                    if resolves && !targets.contains(&candidate) {
+                        targets.push(candidate);
+                    }
+                }
+            }
+            targets
+        };
+
+        let callees: Vec<Vec<usize>> = nodes
+            .iter()
+            .map(|node| resolve(node.function.body.as_deref().unwrap_or_default()))
+            .collect();
+
+        let mut callers = vec![Vec::new(); nodes.len()];
+        for (caller, targets) in callees.iter().enumerate() {
+            for &target in targets {
+                callers[target].push(caller);
+            }
+        }
+
+        let mut implicit = Vec::new();
+        let mut modifier_callees: HashMap<String, Vec<usize>> = HashMap::new();
+        for contract in contracts {
+            for (modifier, body) in implicit_bodies(&contract.source_code) {
+                let targets = resolve(&body);
+                for &target in &targets {
+                    if !implicit.contains(&target) {
+                        implicit.push(target);
+                    }
+                }
+                if let Some(modifier) = modifier {
+                    let known = modifier_callees.entry(modifier).or_default();
+                    for target in targets {
+                        if !known.contains(&target) {
+                            known.push(target);
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            nodes,
+            callees,
+            callers,
+            implicit,
+            modifier_callees,
+        }
+    }
+
+    /// Functions calling the function at `index`
+    pub fn callers(&self, index: usize) -> &[usize] {
+        &self.callers[index]
+    }
+
+    /// Functions transitively called by the function at `index`, excluding itself
+    pub fn callees_of(&self, index: usize) -> Vec<usize> {
+        let mut seen: HashSet<usize> = HashSet::from([index]);
+        let mut queue: VecDeque<usize> = VecDeque::from([index]);
+        let mut callees = Vec::new();
+        while let Some(current) = queue.pop_front() {
+            for &callee in &self.callees[current] {
+                if seen.insert(callee) {
+                    callees.push(callee);
+                    queue.push_back(callee);
+                }
+            }
+        }
+        callees
+    }
+
+    /// Innermost function of `file` whose span contains `line`
+    pub fn enclosing(&self, file: &str, line: usize) -> Option<usize> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.file == file && node.function.line_number <= line && line <= node.end_line)
+            .max_by_key(|(_, node)| node.function.line_number)
+            .map(|(index, _)| index)
+    }
+
+    /// Whether the function at `index` can be called from outside the contract
+    pub fn is_entry_point(&self, index: usize) -> bool {
+        is_entry_point(self.nodes[index].function)
+    }
+
+    /// Functions reachable from an external entry point, a constructor or a modifier
+    pub fn reachable(&self) -> HashSet<usize> {
+        let mut seen: HashSet<usize> = (0..self.nodes.len())
+            .filter(|index| self.is_entry_point(*index))
+            .chain(self.implicit.iter().copied())
+            .collect();
+        let mut queue: VecDeque<usize> = seen.iter().copied().collect();
+        while let Some(index) = queue.pop_front() {
+            for &callee in &self.callees[index] {
+                if seen.insert(callee) {
+                    queue.push_back(callee);
+                }
+            }
+        }
+        seen
+    }
+
+    /// Functions reachable from `roots`, the roots included, through calls
+    /// and through the modifiers the reached functions apply
+    pub fn reachable_from(&self, roots: &[usize]) -> HashSet<usize> {
+        let mut seen: HashSet<usize> = roots.iter().copied().collect();
+        let mut queue: VecDeque<usize> = seen.iter().copied().collect();
+        while let Some(index) = queue.pop_front() {
+            let through_modifiers = self.nodes[index]
+                .function
+                .modifiers
+                .iter()
+                .filter_map(|modifier| self.modifier_callees.get(modifier.as_str()))
+                .flatten();
+            for &callee in self.callees[index].iter().chain(through_modifiers) {
+                if seen.insert(callee) {
+                    queue.push_back(callee);
+                }
+            }
+        }
+        seen
+    }
+}
+
+pub fn is_entry_point(function: &UnitFunction) -> bool {
+    matches!(function.visibility.as_str(), "external" | "public") || matches!(function.name.as_str(), "fallback" | "receive")
+}
+
+/// Bodies of constructors, modifiers, `fallback` and `receive`, which the parser does not list as functions,
+/// with the modifier's name for modifiers
+fn implicit_bodies(source_code: &str) -> Vec<(Option<String>, String)> {
+    let header_pattern =
+        Regex::new(r"\b(?:constructor|fallback|receive)\s*\(|\bmodifier\s+(\w+)\s*[({]").expect("valid header pattern");
+    let source = strip_comments(source_code);
+
+    header_pattern
+        .captures_iter(&source)
+        .filter_map(|captures| {
+            let header = captures.get(0)?;
+            // Modifiers without parameters open their body straight away
+            let params_end = if source[..header.end()].ends_with('(') {
+                matching_paren(&source, header.end() - 1)?
+            } else {
+                header.end() - 1
+            };
+            let open = params_end + source[params_end..].find(['{', ';'])?;
+            if !source[open..].starts_with('{') {
+                return None;
+            }
+            let close = matching_brace(&source, open)?;
+            Some((captures.get(1).map(|name| name.as_str().to_string()), source[open + 1..close].to_string()))
+        })
+        .collect()
+}
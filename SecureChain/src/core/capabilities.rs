@@ -0,0 +1,190 @@
+//! Versions of external tools and what they support
+//!
+//! Slither and Mythril change their command lines and detector sets between
+//! releases, and a flag or detector the installed version does not know
+//! makes the run fail with an argparse message that says nothing about
+//! versions. The versions recorded in the run manifest are parsed here, so a
+//! run can leave out what the installed tool lacks, warn about releases older
+//! than the oldest one supported, and explain failures that look like a
+//! version mismatch.
+
+use anyhow::anyhow;
+use regex::Regex;
+use std::fmt;
+
+use crate::core::manifest::RunManifest;
+use crate::status;
+use crate::utils::config::Config;
+
+/// Oldest supported release of each tool, and what it is needed for
+const MINIMUM_VERSIONS: &[(&str, Version, &str)] = &[
+    ("Slither", Version::new(0, 8, 0), "`--json -` output with a success flag"),
+    ("Mythril", Version::new(0, 21, 0), "the `analyze` subcommand and `--execution-timeout`"),
+];
+
+/// Flags that appeared after the oldest supported release
+const FLAGS: &[(&str, &str, Version)] = &[("Mythril", "--transaction-sequences", Version::new(0, 23, 0))];
+
+/// Slither detectors that appeared after the oldest supported release, with the release that added them
+const SLITHER_DETECTORS: &[(&str, Version)] = &[
+    ("cyclomatic-complexity", Version::new(0, 9, 1)),
+    ("cache-array-length", Version::new(0, 9, 3)),
+    ("encode-packed-collision", Version::new(0, 9, 3)),
+    ("incorrect-exp", Version::new(0, 9, 3)),
+    ("incorrect-return", Version::new(0, 9, 3)),
+    ("return-leave", Version::new(0, 9, 3)),
+    ("out-of-order-retryable", Version::new(0, 10, 0)),
+    ("gelato-unprotected-randomness", Version::new(0, 10, 1)),
+    ("chronicle-unchecked-price", Version::new(0, 10, 1)),
+    ("pyth-deprecated-functions", Version::new(0, 10, 1)),
+    ("optimism-deprecation", Version::new(0, 10, 1)),
+    ("chainlink-feed-registry", Version::new(0, 10, 2)),
+    ("pyth-unchecked-confidence", Version::new(0, 10, 2)),
+    ("pyth-unchecked-publishtime", Version::new(0, 10, 2)),
+];
+
+/// Parts of tool errors that point at a flag or detector the tool does not know
+const MISMATCH_HINTS: &[&str] = &[
+    "unrecognized arguments",
+    "invalid choice",
+    "no such option",
+    "is not a detector",
+    "unknown detector",
+    "unrecognised",
+];
+
+/// A release number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// First `major.minor[.patch]` in a `--version` line, e.g. `Mythril version v0.24.8`
+    pub fn parse(text: &str) -> Option<Self> {
+        let pattern = Regex::new(r"(\d+)\.(\d+)(?:\.(\d+))?").expect("valid version pattern");
+        let captures = pattern.captures(text)?;
+        let part = |index: usize| captures.get(index).map_or(Some(0), |m| m.as_str().parse().ok());
+        Some(Self::new(part(1)?, part(2)?, part(3)?))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Installed versions of the tools a run invokes
+#[derive(Debug, Clone, Default)]
+pub struct Capabilities {
+    versions: Vec<(String, Version)>,
+}
+
+impl Capabilities {
+    /// Versions from the `--version` lines captured in a run manifest
+    pub fn from_manifest(manifest: &RunManifest) -> Self {
+        Self {
+            versions: manifest
+                .tools
+                .iter()
+                .filter_map(|invocation| Some((invocation.tool.clone(), Version::parse(invocation.version.as_deref()?)?)))
+                .collect(),
+        }
+    }
+
+    /// Installed version of `tool`, `None` when it is missing or its version unknown
+    pub fn version(&self, tool: &str) -> Option<Version> {
+        self.versions.iter().find(|(name, _)| name == tool).map(|(_, version)| *version)
+    }
+
+    /// Show the detected versions, and warn about releases older than
+    /// supported and configured detectors the installed Slither lacks
+    pub fn announce(&self, config: &Config) {
+        if self.versions.is_empty() {
+            return;
+        }
+        let detected: Vec<String> = self.versions.iter().map(|(tool, version)| format!("{} {}", tool, version)).collect();
+        status!("🧰 Tools: {}", detected.join(", "));
+        for (tool, minimum, needed_for) in MINIMUM_VERSIONS {
+            if let Some(version) = self.version(tool).filter(|version| version < minimum) {
+                status!(
+                    "   ⚠️  {} {} is older than {}, the oldest supported release ({}); upgrade it if its runs fail",
+                    tool,
+                    version,
+                    minimum,
+                    needed_for
+                );
+            }
+        }
+
+        let slither = &config.tools.slither;
+        for detector in slither.include_detectors.iter().chain(&slither.exclude_detectors) {
+            if let (Some(installed), Some(added)) = (self.version("Slither"), slither_detector_added(detector)) {
+                if installed < added {
+                    status!("   ⚠️  Slither {} has no {} detector (added in {}), leaving it out", installed, detector, added);
+                }
+            }
+        }
+    }
+
+    /// Whether the installed `tool` accepts `flag`; assumed when its version is unknown
+    pub fn supports_flag(&self, tool: &str, flag: &str) -> bool {
+        let required = FLAGS.iter().find(|(name, known, _)| *name == tool && *known == flag).map(|(_, _, version)| *version);
+        match (self.version(tool), required) {
+            (Some(version), Some(required)) => version >= required,
+            _ => true,
+        }
+    }
+
+    /// The `detectors` the installed Slither has
+    pub fn slither_detectors(&self, detectors: &[String]) -> Vec<String> {
+        detectors
+            .iter()
+            .filter(|detector| match (self.version("Slither"), slither_detector_added(detector)) {
+                (Some(installed), Some(added)) => installed >= added,
+                _ => true,
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// `error` of a `tool` run, marked as a version mismatch when it looks like one
+    pub fn explain(&self, tool: &str, error: anyhow::Error) -> anyhow::Error {
+        let version = match self.version(tool) {
+            Some(version) => version,
+            None => return error,
+        };
+        let message = error.to_string();
+        // Runs cut short by the run's limits say nothing about the version
+        if message.starts_with("timed out") || message.starts_with("budget exceeded") {
+            return error;
+        }
+        let lowercase = message.to_lowercase();
+        let minimum = MINIMUM_VERSIONS.iter().find(|(name, _, _)| *name == tool);
+
+        if let Some((_, minimum, _)) = minimum.filter(|(_, minimum, _)| version < *minimum) {
+            anyhow!("unsupported version: {} {} is older than the supported {}: {}", tool, version, minimum, message)
+        } else if MISMATCH_HINTS.iter().any(|hint| lowercase.contains(hint)) {
+            anyhow!("unsupported version: {} {} rejected the configured arguments: {}", tool, version, message)
+        } else {
+            error
+        }
+    }
+
+    /// `Tool version` of each detected tool, for report metadata
+    pub fn summary(&self) -> Vec<String> {
+        self.versions.iter().map(|(tool, version)| format!("{} {}", tool, version)).collect()
+    }
+}
+
+/// Release that added a Slither detector, `None` for detectors of the oldest supported release
+fn slither_detector_added(detector: &str) -> Option<Version> {
+    SLITHER_DETECTORS.iter().find(|(name, _)| *name == detector).map(|(_, version)| *version)
+}
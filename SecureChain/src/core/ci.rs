@@ -0,0 +1,208 @@
+//! GitHub pull request checks
+//!
+//! `ci github` analyses only the contract files a pull request changes,
+//! taken from `git diff base...head`, and reports the findings where the
+//! author sees them: as annotations of a "SecureChain" check run on the head
+//! commit, and optionally as review comments. Findings at or above
+//! `analysis.ci_fail_on` make the check fail. Inside GitHub Actions the
+//! repository, head commit and pull request come from the workflow's
+//! environment and event; elsewhere they are given on the command line.
+//! Posting needs a `GITHUB_TOKEN` allowed to write checks, and the API URL
+//! is `networks.github.api_url`, so GitHub Enterprise works too.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::process::Command;
+
+use crate::core::hook;
+use crate::report::vulnerability::{Severity, Vulnerability};
+use crate::utils::config::GitHubConfig;
+
+/// Name of the check run on the commit
+pub const CHECK_NAME: &str = "SecureChain";
+
+/// Annotations GitHub accepts per request; more are added by updating the check run
+#[cfg_attr(not(feature = "explorers"), allow(dead_code))]
+const ANNOTATIONS_PER_REQUEST: usize = 50;
+
+/// A contract file changed by the pull request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedFile {
+    /// Path relative to the repository root
+    pub path: String,
+    pub platform: String,
+}
+
+/// The pull request and commit findings are reported on
+#[derive(Debug, Clone)]
+pub struct GithubTarget {
+    /// `owner/repo`
+    pub repo: String,
+    pub head_sha: String,
+    pub pull_request: Option<u64>,
+}
+
+impl GithubTarget {
+    /// Target from the options given, else from the GitHub Actions environment and event
+    pub fn resolve(repo: Option<String>, sha: Option<String>, pull_request: Option<u64>) -> Result<Self> {
+        let event: Value = std::env::var("GITHUB_EVENT_PATH")
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let repo = repo
+            .or_else(|| std::env::var("GITHUB_REPOSITORY").ok())
+            .ok_or_else(|| anyhow!("No repository: pass --repo owner/repo outside GitHub Actions"))?;
+        // On pull_request events GITHUB_SHA is a merge commit GitHub made, not the commit the author pushed
+        let head_sha = match sha.or_else(|| event["pull_request"]["head"]["sha"].as_str().map(str::to_string)) {
+            Some(sha) => sha,
+            None => git(Path::new("."), &["rev-parse", "HEAD"])?.trim().to_string(),
+        };
+        let pull_request = pull_request.or_else(|| event["pull_request"]["number"].as_u64());
+        Ok(Self { repo, head_sha, pull_request })
+    }
+}
+
+/// Default base of the diff: the pull request's target branch in GitHub Actions
+pub fn default_base() -> Option<String> {
+    std::env::var("GITHUB_BASE_REF")
+        .ok()
+        .filter(|base| !base.is_empty())
+        .map(|base| format!("origin/{}", base))
+}
+
+/// Contract files added, copied, modified or renamed between `base` and `head`, from the merge base
+pub fn changed_files(root: &Path, base: &str, head: &str) -> Result<Vec<ChangedFile>> {
+    let range = format!("{}...{}", base, head);
+    let output = git(root, &["diff", "--name-only", "-z", "--diff-filter=ACMR", &range])?;
+    Ok(output
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .filter_map(|path| {
+            let platform = hook::platform_for_path(path)?;
+            // Rust sources are only contracts when they use ink!
+            if platform == "ink" && !std::fs::read_to_string(root.join(path)).is_ok_and(|source| source.contains("#[ink")) {
+                return None;
+            }
+            Some(ChangedFile { path: path.to_string(), platform: platform.to_string() })
+        })
+        .collect())
+}
+
+/// Findings at or above `threshold`, which fail the check
+pub fn blocking<'a>(findings: &'a [Vulnerability], threshold: &Severity) -> Vec<&'a Vulnerability> {
+    findings.iter().filter(|vuln| vuln.severity_priority() >= threshold.priority()).collect()
+}
+
+/// Check run annotation of a finding
+pub fn annotation(vuln: &Vulnerability) -> Value {
+    let level = match vuln.severity.to_lowercase().as_str() {
+        "critical" | "high" => "failure",
+        "medium" => "warning",
+        _ => "notice",
+    };
+    // Findings without a line are pinned to the top of their file
+    let line = vuln.line_number.unwrap_or(1).max(1);
+    let mut message = vuln.description.clone();
+    if let Some(recommendation) = &vuln.recommendation {
+        message.push_str(&format!("\n\nRecommendation: {}", recommendation));
+    }
+    json!({
+        "path": vuln.file_path,
+        "start_line": line,
+        "end_line": line,
+        "annotation_level": level,
+        "title": format!("[{}] {}", vuln.severity, vuln.title).chars().take(255).collect::<String>(),
+        "message": message,
+    })
+}
+
+/// Markdown summary of the check run
+pub fn summary(files: &[ChangedFile], findings: &[Vulnerability], blocking: usize, fail_on: &Severity) -> String {
+    let count = |severity: &str| findings.iter().filter(|vuln| vuln.severity.eq_ignore_ascii_case(severity)).count();
+    let mut summary = format!(
+        "Analysed {} changed contract file(s): {} finding(s), {} at or above {}.\n\n| Critical | High | Medium | Low | Info |\n|---|---|---|---|---|\n| {} | {} | {} | {} | {} |\n",
+        files.len(),
+        findings.len(),
+        blocking,
+        fail_on,
+        count("critical"),
+        count("high"),
+        count("medium"),
+        count("low"),
+        count("info")
+    );
+    if !files.is_empty() {
+        summary.push_str("\nFiles:\n");
+        for file in files {
+            summary.push_str(&format!("\n- `{}`", file.path));
+        }
+    }
+    summary
+}
+
+/// Create the completed check run on the head commit, with every finding as an annotation; its URL
+#[cfg(feature = "explorers")]
+pub fn publish_check_run(github: &GitHubConfig, target: &GithubTarget, title: &str, summary: &str, failed: bool, findings: &[Vulnerability]) -> Result<String> {
+    let token = std::env::var("GITHUB_TOKEN").map_err(|_| anyhow!("Posting a check run needs a GITHUB_TOKEN with checks write access"))?;
+    let base = format!("{}/repos/{}/check-runs", github.api_url.trim_end_matches('/'), target.repo);
+    let annotations: Vec<Value> = findings.iter().map(annotation).collect();
+    let mut batches = annotations.chunks(ANNOTATIONS_PER_REQUEST);
+    let output = |batch: Option<&[Value]>| json!({ "title": title, "summary": summary, "annotations": batch.unwrap_or_default() });
+
+    let created = github_request(
+        github,
+        &token,
+        ureq::post(&base),
+        json!({
+            "name": CHECK_NAME,
+            "head_sha": target.head_sha,
+            "status": "completed",
+            "conclusion": if failed { "failure" } else { "success" },
+            "output": output(batches.next()),
+        }),
+    )?;
+    let id = created["id"].as_u64().ok_or_else(|| anyhow!("GitHub created a check run without an id"))?;
+    for batch in batches {
+        github_request(github, &token, ureq::patch(&format!("{}/{}", base, id)), json!({ "output": output(Some(batch)) }))?;
+    }
+    Ok(created["html_url"].as_str().unwrap_or_default().to_string())
+}
+
+#[cfg(not(feature = "explorers"))]
+pub fn publish_check_run(_github: &GitHubConfig, _target: &GithubTarget, _title: &str, _summary: &str, _failed: bool, _findings: &[Vulnerability]) -> Result<String> {
+    Err(anyhow!("Posting check runs needs a build with the `explorers` feature"))
+}
+
+#[cfg(feature = "explorers")]
+fn github_request(github: &GitHubConfig, token: &str, request: ureq::Request, body: Value) -> Result<Value> {
+    let response = request
+        .timeout(std::time::Duration::from_secs(github.timeout))
+        .set("Authorization", &format!("Bearer {}", token))
+        .set("Accept", "application/vnd.github+json")
+        .set("User-Agent", "SecureChain")
+        .send_json(body);
+    match response {
+        Ok(response) => Ok(response.into_json()?),
+        Err(ureq::Error::Status(status, response)) => {
+            let detail = response.into_string().unwrap_or_default();
+            Err(anyhow!("GitHub rejected the check run ({}): {}", status, detail))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn git(root: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git").arg("-C").arg(root).args(args).output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
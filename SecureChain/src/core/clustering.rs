@@ -0,0 +1,96 @@
+//! Similarity clustering of contracts
+//!
+//! Templated codebases often contain many near-identical contracts, e.g. one
+//! token per market that differs only in name and constants. Clustering them
+//! lets expensive per-contract work such as AI probe generation run once per
+//! cluster, with the result fanned out to every member.
+
+use regex::Regex;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+use crate::core::parser::ParsedContract;
+
+/// Tokens per shingle when fingerprinting source code
+const SHINGLE_SIZE: usize = 5;
+
+/// A group of near-identical contracts
+#[derive(Debug, Clone)]
+pub struct ContractCluster {
+    /// Index of the contract analysed on behalf of the cluster
+    pub representative: usize,
+    /// Indices of all members with their similarity to the representative, representative first
+    pub members: Vec<(usize, f64)>,
+}
+
+/// Group contracts whose similarity to a cluster's representative is at least `threshold`
+pub fn cluster_contracts(contracts: &[ParsedContract], threshold: f64) -> Vec<ContractCluster> {
+    let fingerprints: Vec<HashSet<u64>> = contracts.iter().map(fingerprint).collect();
+    let mut clusters: Vec<ContractCluster> = Vec::new();
+
+    for (index, contract_fingerprint) in fingerprints.iter().enumerate() {
+        let best = clusters
+            .iter_mut()
+            .map(|cluster| {
+                let similarity = jaccard(&fingerprints[cluster.representative], contract_fingerprint);
+                (cluster, similarity)
+            })
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        match best {
+            Some((cluster, similarity)) => cluster.members.push((index, similarity)),
+            None => clusters.push(ContractCluster {
+                representative: index,
+                members: vec![(index, 1.0)],
+            }),
+        }
+    }
+
+    clusters
+}
+
+/// Shingle hashes of the normalised token stream of a contract
+///
+/// Literals and the contract's own unit names are replaced by placeholders,
+/// so contracts generated from one template fingerprint the same.
+fn fingerprint(contract: &ParsedContract) -> HashSet<u64> {
+    let token_pattern = Regex::new(r#""(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'|0x[0-9a-fA-F]+|\d[\d_]*(?:\.\d+)?(?:e\d+)?|[A-Za-z_$][\w$]*|\S"#)
+        .expect("valid token pattern");
+    let own_names: HashSet<&str> = contract.units.iter().map(|unit| unit.name.as_str()).collect();
+    let source = contract.code();
+
+    let tokens: Vec<&str> = token_pattern
+        .find_iter(source)
+        .map(|token| {
+            let text = token.as_str();
+            match text.chars().next() {
+                Some('"') | Some('\'') => "<str>",
+                Some(c) if c.is_ascii_digit() => "<num>",
+                _ if own_names.contains(text) => "<name>",
+                _ => text,
+            }
+        })
+        .collect();
+
+    if tokens.len() < SHINGLE_SIZE {
+        return std::iter::once(hash_tokens(&tokens)).collect();
+    }
+
+    tokens.windows(SHINGLE_SIZE).map(hash_tokens).collect()
+}
+
+fn hash_tokens(tokens: &[&str]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    tokens.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
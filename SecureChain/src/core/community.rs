@@ -0,0 +1,486 @@
+//! Community rule packs
+//!
+//! Organizations auditing similar code keep rediscovering the same issues,
+//! and the same false positives, without a way to pass on what they learned
+//! short of sharing client code. A rule pack carries only the shape of the
+//! code behind a finding: the statement it points at with every identifier
+//! not part of Solidity replaced by a placeholder and literals blanked, along
+//! with the check that reported it and whether it was confirmed or a false
+//! positive. Exporting is explicit and per results file. Imported packs are
+//! matched on every EVM analysis: confirmed shapes become findings of their
+//! own, false-positive shapes drop the findings of their check they match.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::core::analyzer::AnalysisResults;
+use crate::core::detector_catalog;
+use crate::core::parser::{strip_comments, ParsedContract};
+use crate::core::triage;
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+use crate::utils::abi;
+use crate::utils::artifact;
+
+pub const TOOL_NAME: &str = "Community Rules";
+
+/// Version of the pack layout, bumped on incompatible changes
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Shapes with fewer tokens would match all kinds of unrelated code
+const MIN_SHAPE_TOKENS: usize = 5;
+
+/// A statement spans at most this many lines of a shape
+const MAX_SHAPE_LINES: usize = 4;
+
+/// Words kept in shapes: keywords, types, globals and the members that make a statement risky
+const SOLIDITY_WORDS: &[&str] = &[
+    "abi", "address", "assembly", "assert", "balance", "block", "blockhash", "bool", "break", "bytes", "call",
+    "calldata", "chainid", "code", "codehash", "coinbase", "constant", "continue", "delegatecall", "delete",
+    "difficulty", "do", "ecrecover", "else", "emit", "encode", "encodeCall", "encodePacked", "encodeWithSelector",
+    "encodeWithSignature", "external", "false", "for", "function", "gas", "gasleft", "gasprice", "if", "immutable",
+    "internal", "keccak256", "length", "mapping", "memory", "msg", "new", "now", "number", "origin", "payable",
+    "pop", "prevrandao", "private", "public", "pure", "push", "require", "return", "returns", "revert", "selfdestruct",
+    "send", "sender", "sha256", "sig", "staticcall", "storage", "string", "suicide", "this", "timestamp", "transfer",
+    "transferFrom", "true", "try", "tx", "type", "unchecked", "value", "view", "while",
+];
+
+/// What the auditor decided about the findings a rule was exported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Disposition {
+    Confirmed,
+    FalsePositive,
+}
+
+impl fmt::Display for Disposition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Disposition::Confirmed => write!(f, "confirmed"),
+            Disposition::FalsePositive => write!(f, "false-positive"),
+        }
+    }
+}
+
+impl std::str::FromStr for Disposition {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "confirmed" => Ok(Disposition::Confirmed),
+            "false-positive" | "fp" => Ok(Disposition::FalsePositive),
+            other => Err(anyhow!("Unknown disposition: {} (confirmed or false-positive)", other)),
+        }
+    }
+}
+
+/// A shareable set of rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulePack {
+    pub schema_version: u32,
+    pub name: String,
+    pub exported_at: DateTime<Utc>,
+    pub securechain_version: String,
+    pub rules: Vec<CommunityRule>,
+}
+
+/// One anonymized finding pattern
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommunityRule {
+    /// Derived from the check, shape and disposition, so the same pattern exported twice is one rule
+    pub id: String,
+    /// Check that reported the finding, as `detector_catalog::check_id` names it
+    pub check: String,
+    pub title: String,
+    pub description: String,
+    pub severity: String,
+    pub category: VulnerabilityCategory,
+    pub cwe_id: Option<String>,
+    pub disposition: Disposition,
+    /// Tokens of the statement, space separated; `$n` stands for an identifier, `N` for a number, `""` for a string
+    pub shape: String,
+}
+
+/// Rules exported from a results file, and the findings left out with the reason
+pub struct Export {
+    pub rules: Vec<CommunityRule>,
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Changes imported packs made to a run's findings
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Applied {
+    pub added: usize,
+    pub suppressed: usize,
+}
+
+/// Rules for the findings of `results` picked by `selectors` (all when empty)
+pub fn export(results: &AnalysisResults, selectors: &[String], disposition: Disposition) -> Result<Export> {
+    let indices: Vec<usize> = if selectors.is_empty() {
+        (0..results.vulnerabilities.len()).collect()
+    } else {
+        selectors
+            .iter()
+            .map(|selector| triage::find_finding(&results.vulnerabilities, selector))
+            .collect::<Result<_>>()?
+    };
+
+    let mut export = Export { rules: Vec::new(), skipped: Vec::new() };
+    let mut seen = HashSet::new();
+    for index in indices {
+        let vuln = &results.vulnerabilities[index];
+        match rule_for(results, vuln, disposition) {
+            Ok(rule) => {
+                if seen.insert(rule.id.clone()) {
+                    export.rules.push(rule);
+                }
+            }
+            Err(reason) => export.skipped.push((vuln.title.clone(), reason)),
+        }
+    }
+    Ok(export)
+}
+
+/// Rule for one finding, or why it cannot be shared
+fn rule_for(results: &AnalysisResults, vuln: &Vulnerability, disposition: Disposition) -> std::result::Result<CommunityRule, String> {
+    if vuln.tool == TOOL_NAME {
+        return Err("already a community rule".to_string());
+    }
+    let line = vuln.line_number.filter(|line| *line > 0).ok_or("no line number")?;
+    let source = results
+        .sources
+        .iter()
+        .find(|source| source.name == vuln.file_path)
+        .ok_or("source not recorded in the results")?;
+
+    let tokens = tokenize(&strip_comments(&source.content));
+    let shape = statement_shape(&tokens, line).ok_or("no statement on its line")?;
+    if shape.len() < MIN_SHAPE_TOKENS || shape.iter().filter(|token| is_specific(token)).count() < 2 {
+        return Err("statement too generic to match elsewhere".to_string());
+    }
+    let shape = shape.join(" ");
+
+    let check = detector_catalog::check_id(vuln);
+    // Titles and descriptions of findings name client code, so the check describes the rule
+    let (title, description) = match detector_catalog::detector_of(vuln) {
+        Some(detector) => (detector.id.to_string(), detector.description.to_string()),
+        None => {
+            let check_name = vuln.title.split_once(": ").map_or(vuln.title.as_str(), |(_, check)| check);
+            (check_name.to_string(), format!("Pattern {} reported as {}", vuln.tool, check_name))
+        }
+    };
+    Ok(CommunityRule {
+        id: format!("community/{}", &abi::encode_hex(&abi::keccak256(format!("{}\0{}\0{}", check, shape, disposition).as_bytes()))[..12]),
+        check,
+        title,
+        description,
+        severity: vuln.severity.clone(),
+        category: vuln.category.clone(),
+        cwe_id: vuln.cwe_id.clone(),
+        disposition,
+        shape,
+    })
+}
+
+/// Whether a shape token says something about the code, unlike placeholders, literals and punctuation
+fn is_specific(token: &str) -> bool {
+    token.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && !matches!(token, "N" | "function" | "public" | "external" | "internal" | "private" | "view" | "pure" | "returns" | "memory" | "storage" | "calldata")
+}
+
+/// Write `rules` to the pack at `path`, adding them to the pack already there
+pub fn write_pack(path: &Path, name: &str, rules: Vec<CommunityRule>) -> Result<RulePack> {
+    let mut pack = if path.exists() {
+        read_pack(path)?
+    } else {
+        RulePack {
+            schema_version: SCHEMA_VERSION,
+            name: name.to_string(),
+            exported_at: Utc::now(),
+            securechain_version: env!("CARGO_PKG_VERSION").to_string(),
+            rules: Vec::new(),
+        }
+    };
+    for rule in rules {
+        if !pack.rules.iter().any(|existing| existing.id == rule.id) {
+            pack.rules.push(rule);
+        }
+    }
+    pack.exported_at = Utc::now();
+    pack.securechain_version = env!("CARGO_PKG_VERSION").to_string();
+    artifact::write_atomic(path, serde_json::to_string_pretty(&pack)?)?;
+    Ok(pack)
+}
+
+/// Read and check a pack file
+pub fn read_pack(path: &Path) -> Result<RulePack> {
+    let pack: RulePack = artifact::read_json(path, "rule pack")?;
+    if pack.schema_version > SCHEMA_VERSION {
+        return Err(anyhow!(
+            "Rule pack {} has layout version {}, this SecureChain reads up to {}",
+            path.display(),
+            pack.schema_version,
+            SCHEMA_VERSION
+        ));
+    }
+    if pack.name.trim().is_empty() {
+        return Err(anyhow!("Rule pack {} has no name", path.display()));
+    }
+    for rule in &pack.rules {
+        triage::parse_severity(&rule.severity).map_err(|e| anyhow!("Rule {} in {}: {}", rule.id, path.display(), e))?;
+        if rule.shape.split_whitespace().count() < MIN_SHAPE_TOKENS {
+            return Err(anyhow!("Rule {} in {} has a shape too short to match safely", rule.id, path.display()));
+        }
+    }
+    Ok(pack)
+}
+
+/// Directory imported packs are kept in, next to the user configuration
+pub fn packs_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".config/bugforgex/rules"))
+}
+
+/// Install the pack at `path`, replacing an installed pack of the same name
+pub fn import(path: &Path) -> Result<(RulePack, PathBuf, bool)> {
+    let pack = read_pack(path)?;
+    let dir = packs_dir().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+    std::fs::create_dir_all(&dir)?;
+    let installed = dir.join(format!("{}.json", file_stem(&pack.name)));
+    let replaced = installed.exists();
+    artifact::write_atomic(&installed, serde_json::to_string_pretty(&pack)?)?;
+    Ok((pack, installed, replaced))
+}
+
+/// Uninstall the pack named `name`, returning whether it was installed
+pub fn remove(name: &str) -> Result<bool> {
+    let path = match packs_dir() {
+        Some(dir) => dir.join(format!("{}.json", file_stem(name))),
+        None => return Ok(false),
+    };
+    if !path.exists() {
+        return Ok(false);
+    }
+    std::fs::remove_file(path)?;
+    Ok(true)
+}
+
+/// Installed packs; unreadable ones are skipped with a warning
+pub fn installed() -> Vec<RulePack> {
+    let entries = match packs_dir().map(std::fs::read_dir) {
+        Some(Ok(entries)) => entries,
+        _ => return Vec::new(),
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().and_then(|extension| extension.to_str()) == Some("json"))
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .filter_map(|path| match read_pack(path) {
+            Ok(pack) => Some(pack),
+            Err(e) => {
+                log::warn!("Skipping rule pack: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn file_stem(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' }).collect()
+}
+
+/// Match `packs` on `contracts`, adding findings for confirmed shapes and dropping those false-positive shapes match
+pub fn apply(packs: &[RulePack], contracts: &[ParsedContract], vulnerabilities: &mut Vec<Vulnerability>) -> Applied {
+    let mut applied = Applied::default();
+    let sources: Vec<(&str, Vec<Token>)> =
+        contracts.iter().map(|contract| (contract.name.as_str(), tokenize(contract.code()))).collect();
+
+    for pack in packs {
+        for rule in &pack.rules {
+            let shape: Vec<&str> = rule.shape.split_whitespace().collect();
+            for (file, tokens) in &sources {
+                let lines = match_lines(tokens, &shape);
+                if lines.is_empty() {
+                    continue;
+                }
+                match rule.disposition {
+                    Disposition::FalsePositive => {
+                        let before = vulnerabilities.len();
+                        vulnerabilities.retain(|vuln| {
+                            !(vuln.file_path == *file
+                                && vuln.line_number.is_some_and(|line| lines.contains(&line))
+                                && detector_catalog::check_id(vuln) == rule.check)
+                        });
+                        applied.suppressed += before - vulnerabilities.len();
+                    }
+                    Disposition::Confirmed => {
+                        for line in lines {
+                            // The check itself ran here and found it already
+                            let reported = vulnerabilities.iter().any(|vuln| {
+                                vuln.file_path == *file && vuln.line_number == Some(line) && detector_catalog::check_id(vuln) == rule.check
+                            });
+                            if !reported {
+                                vulnerabilities.push(finding(pack, rule, file, line));
+                                applied.added += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    applied
+}
+
+fn finding(pack: &RulePack, rule: &CommunityRule, file: &str, line: usize) -> Vulnerability {
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: format!("{}: {}", TOOL_NAME, rule.title),
+        description: format!("{} Matches a finding confirmed in another audit (pack {}).", rule.description, pack.name),
+        severity: rule.severity.clone(),
+        category: rule.category.clone(),
+        file_path: file.to_string(),
+        line_number: Some(line),
+        code_snippet: None,
+        recommendation: Some(format!("Review the code the way a {} finding would be reviewed", rule.check)),
+        references: vec![format!("{} ({})", rule.id, pack.name)],
+        cwe_id: rule.cwe_id.clone(),
+        tool: TOOL_NAME.to_string(),
+        // Shapes ignore names and literals, so a match is less certain than the original finding
+        confidence: 0.5,
+        provenance: None,
+        severity_overrides: Vec::new(),
+        cvss: None,
+        detected_by: Vec::new(),
+    }
+}
+
+/// A source token with the line it starts on
+struct Token {
+    text: String,
+    line: usize,
+    kind: TokenKind,
+}
+
+#[derive(PartialEq)]
+enum TokenKind {
+    Identifier,
+    Number,
+    String,
+    Symbol,
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    let pattern = Regex::new(
+        r#"(?P<s>"(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*')|(?P<n>0x[0-9a-fA-F_]+|\d[\d_]*(?:\.\d+)?(?:e\d+)?)|(?P<i>[A-Za-z_$][A-Za-z0-9_$]*)|(?P<o>==|!=|<=|>=|&&|\|\||\+=|-=|\*=|/=|\+\+|--|=>|<<|>>|\*\*|\S)"#,
+    )
+    .expect("token pattern");
+
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut last = 0;
+    for captures in pattern.captures_iter(source) {
+        let whole = captures.get(0).expect("match");
+        line += source[last..whole.start()].matches('\n').count();
+        last = whole.start();
+        let kind = if captures.name("s").is_some() {
+            TokenKind::String
+        } else if captures.name("n").is_some() {
+            TokenKind::Number
+        } else if captures.name("i").is_some() {
+            TokenKind::Identifier
+        } else {
+            TokenKind::Symbol
+        };
+        tokens.push(Token { text: whole.as_str().to_string(), line, kind });
+    }
+    tokens
+}
+
+/// Whether an identifier is part of the language rather than of the code under audit
+fn is_solidity_word(word: &str) -> bool {
+    SOLIDITY_WORDS.contains(&word)
+        || ["uint", "int", "bytes"].iter().any(|prefix| {
+            word.strip_prefix(prefix).is_some_and(|size| size.is_empty() || size.chars().all(|c| c.is_ascii_digit()))
+        })
+}
+
+/// Anonymized tokens of the statement starting on `line`
+fn statement_shape(tokens: &[Token], line: usize) -> Option<Vec<String>> {
+    let start = tokens.iter().position(|token| token.line == line)?;
+    let mut names: HashMap<&str, usize> = HashMap::new();
+    let mut shape = Vec::new();
+    // Braces of call options such as `{value: amount}` do not end the statement
+    let mut options = 0;
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        if token.line >= line + MAX_SHAPE_LINES {
+            break;
+        }
+        let opens_options = token.text == "{"
+            && tokens.get(index + 1).is_some_and(|next| next.kind == TokenKind::Identifier)
+            && tokens.get(index + 2).is_some_and(|next| next.text == ":");
+        shape.push(match token.kind {
+            TokenKind::String => "\"\"".to_string(),
+            TokenKind::Number => "N".to_string(),
+            TokenKind::Identifier if !is_solidity_word(&token.text) => {
+                let next = names.len() + 1;
+                format!("${}", names.entry(token.text.as_str()).or_insert(next))
+            }
+            _ => token.text.clone(),
+        });
+        if opens_options {
+            options += 1;
+        } else if token.text == "}" && options > 0 {
+            options -= 1;
+        } else if matches!(token.text.as_str(), ";" | "{" | "}") {
+            break;
+        }
+    }
+    Some(shape)
+}
+
+/// Lines on which a match of `shape` starts
+fn match_lines(tokens: &[Token], shape: &[&str]) -> Vec<usize> {
+    let mut lines = Vec::new();
+    if shape.is_empty() || tokens.len() < shape.len() {
+        return lines;
+    }
+    for start in 0..=tokens.len() - shape.len() {
+        if matches_at(&tokens[start..start + shape.len()], shape) && !lines.contains(&tokens[start].line) {
+            lines.push(tokens[start].line);
+        }
+    }
+    lines
+}
+
+fn matches_at(tokens: &[Token], shape: &[&str]) -> bool {
+    let mut bound: HashMap<&str, &str> = HashMap::new();
+    let mut taken: HashSet<&str> = HashSet::new();
+    for (token, expected) in tokens.iter().zip(shape) {
+        let matched = if let Some(placeholder) = expected.strip_prefix('$') {
+            token.kind == TokenKind::Identifier
+                && !is_solidity_word(&token.text)
+                && match bound.get(placeholder) {
+                    Some(name) => *name == token.text,
+                    // Different placeholders stand for different names
+                    None => taken.insert(token.text.as_str()) && bound.insert(placeholder, token.text.as_str()).is_none(),
+                }
+        } else {
+            match *expected {
+                "N" => token.kind == TokenKind::Number,
+                "\"\"" => token.kind == TokenKind::String,
+                _ => token.text == *expected,
+            }
+        };
+        if !matched {
+            return false;
+        }
+    }
+    true
+}
@@ -0,0 +1,252 @@
+//! Analysis completeness
+//!
+//! A report with no findings only means something if the analysis actually
+//! ran. This module records the outcome of every tool on every contract,
+//! with a machine-readable reason for anything that did not run, and how
+//! many files compiled, and folds both into one completeness percentage.
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::error::ToolError;
+
+/// Outcome of a tool on one contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolStatus {
+    /// Ran to completion
+    Completed,
+    /// Started but did not produce a usable result
+    Failed,
+    /// Could not be started, e.g. not installed or not implemented
+    Unavailable,
+    /// Not requested for this run, e.g. disabled or below the analysis depth
+    NotRequested,
+}
+
+/// Why a tool did not complete
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    NotInstalled,
+    NotImplemented,
+    Disabled,
+    DepthTooLow,
+    TimedOut,
+    CompilationFailed,
+    ToolError,
+    /// No configured AI backend answered its health check
+    BackendUnreachable,
+    /// The tool went over the run's disk, memory or CPU budget
+    BudgetExceeded,
+    /// The installed release is too old for, or rejected, the invocation
+    UnsupportedVersion,
+    /// The tool does not read the contract's source language
+    UnsupportedLanguage,
+    /// Nothing the tool would run on is reached from the run's entrypoints
+    OutOfScope,
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            SkipReason::NotInstalled => "not installed",
+            SkipReason::NotImplemented => "integration not implemented",
+            SkipReason::Disabled => "disabled in configuration",
+            SkipReason::DepthTooLow => "only runs at deep analysis depth",
+            SkipReason::TimedOut => "timed out",
+            SkipReason::CompilationFailed => "compilation failed",
+            SkipReason::ToolError => "tool error",
+            SkipReason::BackendUnreachable => "AI backend unreachable",
+            SkipReason::BudgetExceeded => "resource budget exceeded",
+            SkipReason::UnsupportedVersion => "unsupported tool version",
+            SkipReason::UnsupportedLanguage => "does not support the source language",
+            SkipReason::OutOfScope => "nothing reached from the entrypoints",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// One tool run on one contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolRun {
+    pub tool: String,
+    /// Contract file, or `None` for analyses over the whole run
+    pub contract: Option<String>,
+    pub status: ToolStatus,
+    pub reason: Option<SkipReason>,
+    /// Error message or other details behind the reason
+    pub detail: Option<String>,
+    /// Kind of the error a failed run ended with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<ToolError>,
+}
+
+/// What ran, what did not and why, and how much of the input compiled
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Completeness {
+    pub tool_runs: Vec<ToolRun>,
+    pub files_total: usize,
+    /// Files a compiler-backed tool built, `None` when no such tool ran
+    pub files_compiled: Option<usize>,
+    /// Share of requested tool runs that completed, scaled by the share of files that compiled (0-100)
+    pub score: f64,
+    /// Why the requested AI analysis did not run, when no backend was reachable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_unavailable: Option<String>,
+}
+
+impl Completeness {
+    pub fn new(files_total: usize) -> Self {
+        Self {
+            files_total,
+            ..Self::default()
+        }
+    }
+
+    /// Record a tool run that completed
+    pub fn completed(&mut self, tool: &str, contract: Option<&str>) {
+        self.record(tool, contract, ToolStatus::Completed, None, None, None);
+    }
+
+    /// Record a tool run that completed with part of its analysis cut short, saying what was missed
+    pub fn partial(&mut self, tool: &str, contract: Option<&str>, detail: &str) {
+        self.record(tool, contract, ToolStatus::Completed, Some(SkipReason::TimedOut), Some(detail.to_string()), None);
+    }
+
+    /// Record a tool that was not requested for this run
+    pub fn not_requested(&mut self, tool: &str, contract: Option<&str>, reason: SkipReason) {
+        self.record(tool, contract, ToolStatus::NotRequested, Some(reason), None, None);
+    }
+
+    /// Record a tool whose integration does not exist yet
+    pub fn not_implemented(&mut self, tool: &str, contract: Option<&str>) {
+        self.record(tool, contract, ToolStatus::Unavailable, Some(SkipReason::NotImplemented), None, None);
+    }
+
+    /// Record a tool whose backend service could not be reached
+    #[cfg_attr(not(feature = "ai"), allow(dead_code))]
+    pub fn unreachable(&mut self, tool: &str, contract: Option<&str>, detail: &str) {
+        let failure = ToolError::NetworkError { message: detail.to_string() };
+        self.record(tool, contract, ToolStatus::Unavailable, Some(SkipReason::BackendUnreachable), Some(detail.to_string()), Some(failure));
+    }
+
+    /// Record a tool that failed, classifying the error
+    pub fn failed(&mut self, tool: &str, contract: Option<&str>, error: &anyhow::Error) {
+        let failure = ToolError::classify(tool, error);
+        let message = error.to_string();
+
+        let (status, reason) = match &failure {
+            ToolError::ToolNotFound { .. } => (ToolStatus::Unavailable, SkipReason::NotInstalled),
+            ToolError::ToolTimeout { .. } => (ToolStatus::Failed, SkipReason::TimedOut),
+            _ if message.starts_with("compilation failed") => (ToolStatus::Failed, SkipReason::CompilationFailed),
+            _ if message.starts_with("budget exceeded") => (ToolStatus::Failed, SkipReason::BudgetExceeded),
+            _ if message.starts_with("unsupported version") => (ToolStatus::Failed, SkipReason::UnsupportedVersion),
+            _ => (ToolStatus::Failed, SkipReason::ToolError),
+        };
+        self.record(tool, contract, status, Some(reason), Some(message), Some(failure));
+    }
+
+    fn record(
+        &mut self,
+        tool: &str,
+        contract: Option<&str>,
+        status: ToolStatus,
+        reason: Option<SkipReason>,
+        detail: Option<String>,
+        failure: Option<ToolError>,
+    ) {
+        self.tool_runs.push(ToolRun {
+            tool: tool.to_string(),
+            contract: contract.map(str::to_string),
+            status,
+            reason,
+            detail,
+            failure,
+        });
+    }
+
+    /// Names of the tools that completed at least once, in first-run order
+    pub fn tools_completed(&self) -> Vec<String> {
+        let mut tools: Vec<String> = Vec::new();
+        for run in self.tool_runs.iter().filter(|run| run.status == ToolStatus::Completed) {
+            if !tools.contains(&run.tool) {
+                tools.push(run.tool.clone());
+            }
+        }
+        tools
+    }
+
+    /// Whether `tool` completed on `contract`, or on the whole run
+    pub fn completed_on(&self, tool: &str, contract: &str) -> bool {
+        self.tool_runs.iter().any(|run| {
+            run.tool == tool
+                && run.status == ToolStatus::Completed
+                && run.contract.as_deref().is_none_or(|name| name == contract)
+        })
+    }
+
+    /// Requested tool runs that did not complete
+    pub fn incomplete_runs(&self) -> impl Iterator<Item = &ToolRun> {
+        self.tool_runs
+            .iter()
+            .filter(|run| matches!(run.status, ToolStatus::Failed | ToolStatus::Unavailable))
+    }
+
+    /// Completed tool runs whose results are partial
+    pub fn partial_runs(&self) -> impl Iterator<Item = &ToolRun> {
+        self.tool_runs
+            .iter()
+            .filter(|run| run.status == ToolStatus::Completed && run.reason.is_some())
+    }
+
+    /// Work out which files compiled and the overall score
+    ///
+    /// A file counts as compiled when solc built it, or when solc did not
+    /// run on it and Slither, which compiles first, completed.
+    pub fn finalize(&mut self, contracts: &[String]) {
+        let run_of = |tool: &str, contract: &str| {
+            self.tool_runs
+                .iter()
+                .find(|run| run.tool == tool && run.contract.as_deref() == Some(contract))
+        };
+
+        let mut known = false;
+        let mut compiled = 0;
+        for contract in contracts {
+            let outcome = match run_of("Solc", contract).map(|run| (run.status, run.reason)) {
+                Some((ToolStatus::Completed, _)) => Some(true),
+                Some((_, Some(SkipReason::CompilationFailed))) => Some(false),
+                _ => run_of("Slither", contract)
+                    .filter(|run| run.status == ToolStatus::Completed)
+                    .map(|_| true),
+            };
+            if let Some(built) = outcome {
+                known = true;
+                compiled += built as usize;
+            }
+        }
+        self.files_compiled = known.then_some(compiled);
+        self.rescore();
+    }
+
+    /// Recompute the score after recording further runs
+    pub fn rescore(&mut self) {
+        let requested = self
+            .tool_runs
+            .iter()
+            .filter(|run| run.status != ToolStatus::NotRequested)
+            .count();
+        let completed = self
+            .tool_runs
+            .iter()
+            .filter(|run| run.status == ToolStatus::Completed)
+            .count();
+
+        let tool_share = if requested == 0 { 0.0 } else { completed as f64 / requested as f64 };
+        let file_share = match self.files_compiled {
+            Some(compiled) if self.files_total > 0 => compiled as f64 / self.files_total as f64,
+            _ => 1.0,
+        };
+        self.score = (tool_share * file_share * 1000.0).round() / 10.0;
+    }
+}
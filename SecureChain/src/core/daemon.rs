@@ -0,0 +1,355 @@
+//! Analysis daemon
+//!
+//! Every CLI run starts from nothing: the engine is rebuilt and every tool is
+//! run on every contract again, even when only one file changed. The daemon
+//! keeps one engine alive with a per-contract cache, so repeated scans of the
+//! same project only pay for the contracts that changed. It listens on a
+//! loopback port published, with an access token, in a file only the user can
+//! read; `analyze`, `audit` and `scan` hand their analysis to it when it runs.
+//! Analyses go through a job queue, so concurrent clients share the workers
+//! by priority and a full queue answers 429 instead of piling up work.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Notify;
+
+use crate::core::analyzer::{AnalysisEngine, AnalysisResults};
+use crate::core::jobs::{JobQueue, Priority, QueueLimits, QueueStatus, ResourceClass, QUEUE_FULL_CODE};
+use crate::plugins::PluginManager;
+use crate::status;
+use crate::utils::artifact;
+use crate::utils::config::Config;
+use crate::utils::metrics;
+
+/// How long clients wait for the daemon to accept before analysing locally
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Set to skip the daemon and always analyse in-process
+const NO_DAEMON_ENV: &str = "SECURECHAIN_NO_DAEMON";
+
+/// Queue priority of delegated analyses: low, normal or high
+const PRIORITY_ENV: &str = "SECURECHAIN_PRIORITY";
+
+/// Where a running daemon can be reached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Endpoint {
+    port: u16,
+    /// Shared secret proving a client can read the endpoint file
+    token: String,
+    pid: u32,
+    started_at: DateTime<Utc>,
+}
+
+/// A client request, one JSON line per connection
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum Request {
+    Analyze {
+        token: String,
+        input: PathBuf,
+        target: String,
+        depth: String,
+        ai: bool,
+        #[serde(default)]
+        priority: Priority,
+    },
+    Status {
+        token: String,
+    },
+    Shutdown {
+        token: String,
+    },
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Response {
+    results: Option<AnalysisResults>,
+    status: Option<DaemonStatus>,
+    error: Option<String>,
+    /// Set with `error` when the job was rejected, 429 for a full queue
+    #[serde(default)]
+    code: Option<u16>,
+}
+
+/// State of a running daemon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub port: u16,
+    pub started_at: DateTime<Utc>,
+    pub requests_served: u64,
+    pub cached_contracts: usize,
+    #[serde(default)]
+    pub queue: QueueStatus,
+    pub limits: Option<QueueLimits>,
+}
+
+/// Location of the endpoint file
+fn endpoint_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".cache/bugforgex/daemon.json"))
+}
+
+impl Endpoint {
+    fn load() -> Option<Self> {
+        let content = std::fs::read_to_string(endpoint_path()?).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = endpoint_path().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // The token is the only thing keeping other local users out, so the
+        // file is restricted before it holds one; the atomic write keeps the mode
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            let file = std::fs::OpenOptions::new().write(true).create(true).truncate(false).mode(0o600).open(&path)?;
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+        artifact::write_atomic(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn remove() {
+        if let Some(path) = endpoint_path() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Run the daemon in the foreground until it is stopped
+pub async fn serve(config: Config) -> Result<()> {
+    if let Some(status) = status().await? {
+        return Err(anyhow!("A daemon is already running (pid {}, port {})", status.pid, status.port));
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+    let endpoint = Endpoint {
+        port: listener.local_addr()?.port(),
+        token: uuid::Uuid::new_v4().to_string(),
+        pid: std::process::id(),
+        started_at: Utc::now(),
+    };
+    endpoint.save()?;
+
+    let limits = QueueLimits::from_config(&config);
+    let daemon = Arc::new(Daemon {
+        engine: AnalysisEngine::new(config.clone(), PluginManager::new()).with_contract_cache(),
+        queue: JobQueue::new(limits),
+        endpoint,
+        config,
+        requests_served: AtomicU64::new(0),
+        shutdown: Notify::new(),
+    });
+    status!("🛰️  SecureChain daemon listening on 127.0.0.1:{} (pid {})", daemon.endpoint.port, daemon.endpoint.pid);
+    status!(
+        "   {} workers ({} for deep audits), up to {} queued analyses",
+        limits.workers,
+        limits.deep_workers,
+        limits.max_queued
+    );
+    status!("   analyze, audit and scan now run through this daemon; stop it with `securechain daemon stop`");
+
+    // Each connection gets its own task; the job queue decides when its analysis runs
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    log::warn!("Daemon failed to accept a connection: {}", e);
+                    continue;
+                }
+            },
+            _ = daemon.shutdown.notified() => break,
+            _ = tokio::signal::ctrl_c() => break,
+        };
+
+        let daemon = Arc::clone(&daemon);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &daemon).await {
+                log::warn!("Daemon request failed: {}", e);
+            }
+        });
+    }
+
+    Endpoint::remove();
+    status!(
+        "🛰️  SecureChain daemon stopped after {} requests",
+        daemon.requests_served.load(Ordering::Relaxed)
+    );
+    Ok(())
+}
+
+/// State shared by the connections of a running daemon
+struct Daemon {
+    engine: AnalysisEngine,
+    queue: Arc<JobQueue>,
+    endpoint: Endpoint,
+    config: Config,
+    requests_served: AtomicU64,
+    shutdown: Notify,
+}
+
+async fn handle_connection(stream: TcpStream, daemon: &Daemon) -> Result<()> {
+    let endpoint = &daemon.endpoint;
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    BufReader::new(reader).read_line(&mut line).await?;
+    let request: Request = serde_json::from_str(&line)?;
+
+    let token = match &request {
+        Request::Analyze { token, .. } | Request::Status { token } | Request::Shutdown { token } => token,
+    };
+    if *token != endpoint.token {
+        let response = Response { error: Some("invalid daemon token".to_string()), ..Response::default() };
+        writer.write_all(serde_json::to_string(&response)?.as_bytes()).await?;
+        return Ok(());
+    }
+
+    let mut shutdown = false;
+    let response = match request {
+        Request::Analyze { input, target, depth, ai, priority, .. } => {
+            let class = ResourceClass::for_depth(&depth);
+            match daemon.queue.acquire(class, priority).await {
+                Err(full) => {
+                    status!("🚦 Rejected {} ({}): {}", input.display(), depth, full);
+                    Response {
+                        error: Some(format!("Daemon is busy: {}", full)),
+                        code: Some(QUEUE_FULL_CODE),
+                        ..Response::default()
+                    }
+                }
+                Ok(_permit) => {
+                    status!("📥 Analyzing {} ({}, {})", input.display(), target, depth);
+                    let started = std::time::Instant::now();
+                    let outcome = daemon.engine.analyze_contracts(&input, &target, &depth, ai).await;
+                    daemon.requests_served.fetch_add(1, Ordering::Relaxed);
+                    if daemon.config.general.metrics_enabled {
+                        if let Err(e) = metrics::record(&metrics::metrics_path(&daemon.config), "daemon analyze", started.elapsed(), outcome.is_ok()) {
+                            log::warn!("Could not update usage metrics: {}", e);
+                        }
+                    }
+                    match outcome {
+                        Ok(results) => Response { results: Some(results), ..Response::default() },
+                        Err(e) => Response { error: Some(e.to_string()), ..Response::default() },
+                    }
+                }
+            }
+        }
+        Request::Status { .. } => {
+            let status = DaemonStatus {
+                pid: endpoint.pid,
+                port: endpoint.port,
+                started_at: endpoint.started_at,
+                requests_served: daemon.requests_served.load(Ordering::Relaxed),
+                cached_contracts: daemon.engine.cached_contracts(),
+                queue: daemon.queue.status(),
+                limits: Some(daemon.queue.limits()),
+            };
+            Response { status: Some(status), ..Response::default() }
+        }
+        Request::Shutdown { .. } => {
+            shutdown = true;
+            Response::default()
+        }
+    };
+
+    writer.write_all(serde_json::to_string(&response)?.as_bytes()).await?;
+    writer.shutdown().await?;
+    if shutdown {
+        daemon.shutdown.notify_one();
+    }
+    Ok(())
+}
+
+/// Send a request to the running daemon, `None` when none is reachable
+async fn send(request: impl FnOnce(String) -> Request) -> Result<Option<Response>> {
+    let endpoint = match Endpoint::load() {
+        Some(endpoint) => endpoint,
+        None => return Ok(None),
+    };
+    let stream = match tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(("127.0.0.1", endpoint.port))).await {
+        Ok(Ok(stream)) => stream,
+        // Nothing listens any more, so the daemon died without cleaning up
+        _ => {
+            Endpoint::remove();
+            return Ok(None);
+        }
+    };
+
+    let (mut reader, mut writer) = stream.into_split();
+    let mut line = serde_json::to_string(&request(endpoint.token))?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+
+    let mut content = String::new();
+    reader.read_to_string(&mut content).await?;
+    let response: Response = serde_json::from_str(&content).map_err(|e| anyhow!("Invalid daemon response: {}", e))?;
+    Ok(Some(response))
+}
+
+/// Analyse through the running daemon, `None` when none is reachable or it is disabled
+pub async fn delegate_analysis(input: &Path, target: &str, depth: &str, ai: bool) -> Result<Option<AnalysisResults>> {
+    if std::env::var_os(NO_DAEMON_ENV).is_some() {
+        return Ok(None);
+    }
+    // The daemon resolves paths from its own working directory
+    let input = match input.canonicalize() {
+        Ok(input) => input,
+        Err(_) => return Ok(None),
+    };
+
+    let priority = match std::env::var(PRIORITY_ENV) {
+        Ok(value) => value.parse()?,
+        Err(_) => Priority::default(),
+    };
+
+    let response = send(|token| Request::Analyze {
+        token,
+        input,
+        target: target.to_string(),
+        depth: depth.to_string(),
+        ai,
+        priority,
+    })
+    .await?;
+
+    match response {
+        None => Ok(None),
+        Some(Response { error: Some(error), code: Some(QUEUE_FULL_CODE), .. }) => Err(anyhow!(
+            "{} (429). Retry later, or set {}=1 to analyse in-process",
+            error,
+            NO_DAEMON_ENV
+        )),
+        Some(Response { error: Some(error), .. }) => Err(anyhow!(error)),
+        Some(Response { results: Some(results), .. }) => Ok(Some(results)),
+        Some(_) => Err(anyhow!("Daemon returned no results")),
+    }
+}
+
+/// State of the running daemon, `None` when none is reachable
+pub async fn status() -> Result<Option<DaemonStatus>> {
+    match send(|token| Request::Status { token }).await? {
+        None => Ok(None),
+        Some(Response { error: Some(error), .. }) => Err(anyhow!(error)),
+        Some(response) => Ok(response.status),
+    }
+}
+
+/// Stop the running daemon; `false` when none was running
+pub async fn shutdown() -> Result<bool> {
+    match send(|token| Request::Shutdown { token }).await? {
+        None => Ok(false),
+        Some(Response { error: Some(error), .. }) => Err(anyhow!(error)),
+        Some(_) => Ok(true),
+    }
+}
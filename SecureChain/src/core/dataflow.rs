@@ -0,0 +1,253 @@
+//! Data flow of function parameters
+//!
+//! Checks on inputs are easy to misjudge from text alone: the value is often
+//! copied to a local, validated in a modifier, or handed to an internal
+//! helper that does the check or the write. This module follows one
+//! parameter through its function, the modifiers applied to it and the
+//! internal functions it is passed to, and collects every condition that
+//! tests it, every state variable it is written to and every place ether is
+//! sent to it. The analysis is intra-contract and path-insensitive: a check
+//! anywhere on the way counts, since a failing `require` reverts the writes
+//! made before it as well.
+
+use regex::Regex;
+use std::collections::HashSet;
+
+use crate::core::detectors::lineage;
+use crate::core::parser::{matching_paren, split_top_level, strip_comments, ContractUnit, ParsedContract, UnitFunction};
+
+/// How deep internal calls are followed
+const MAX_DEPTH: usize = 3;
+
+/// Where a parameter's value goes
+#[derive(Debug, Clone, Default)]
+pub struct ParameterFlow {
+    /// Conditions of `require`, `assert` and `if` that test the value
+    pub checks: Vec<String>,
+    /// State variables the value is written to, with the line of the write
+    pub stores: Vec<(String, usize)>,
+    /// Lines where ether is sent to the value or it is called
+    pub sends: Vec<usize>,
+    /// Whether the value is indexed, as an array would be
+    pub indexed: bool,
+}
+
+impl ParameterFlow {
+    /// Whether the value reaches contract state or receives ether
+    pub fn is_used(&self) -> bool {
+        !self.stores.is_empty() || !self.sends.is_empty()
+    }
+
+    /// Line of the first write or send, for reporting
+    pub fn first_use(&self) -> Option<usize> {
+        self.stores.iter().map(|(_, line)| *line).chain(self.sends.iter().copied()).min()
+    }
+}
+
+/// Follow `parameter` of `function`, declared in `unit`
+pub fn trace(contract: &ParsedContract, unit: &ContractUnit, function: &UnitFunction, parameter: &str) -> ParameterFlow {
+    let tracer = Tracer::new(contract, unit);
+    let mut flow = ParameterFlow::default();
+    let names = HashSet::from([parameter.to_string()]);
+
+    if let Some(body) = function.body.as_deref() {
+        let mut visited = HashSet::from([function.name.clone()]);
+        tracer.follow(body, function.body_line_number, names.clone(), 0, &mut visited, &mut flow);
+    }
+    tracer.follow_modifiers(function, parameter, &mut flow);
+    flow
+}
+
+/// The unit a function belongs to, with what the trace looks up repeatedly
+struct Tracer<'a> {
+    contract: &'a ParsedContract,
+    /// Functions of the unit and its bases declared in the same file, nearest first
+    functions: Vec<&'a UnitFunction>,
+    state_variables: HashSet<&'a str>,
+    assignment: Regex,
+    declaration: Regex,
+    send: Regex,
+    call: Regex,
+}
+
+impl<'a> Tracer<'a> {
+    fn new(contract: &'a ParsedContract, unit: &'a ContractUnit) -> Self {
+        let lineage = lineage(contract, unit);
+        Self {
+            contract,
+            functions: lineage.iter().flat_map(|unit| unit.functions.iter()).collect(),
+            state_variables: lineage
+                .iter()
+                .flat_map(|unit| unit.state_variables.iter())
+                .filter(|variable| !variable.is_constant)
+                .map(|variable| variable.name.as_str())
+                .collect(),
+            assignment: Regex::new(r"^\s*([A-Za-z_]\w*)(?:\s*\[[^\]]*\]|\s*\.\s*\w+)*\s*[-+*/%|&^]?=([^=][\s\S]*)$")
+                .expect("valid assignment pattern"),
+            declaration: Regex::new(
+                r"^\s*[A-Za-z_][\w.]*(?:\s*\[[^\]]*\])*(?:\s+(?:memory|storage|calldata|payable))?\s+([A-Za-z_]\w*)\s*=([^=][\s\S]*)$",
+            )
+            .expect("valid declaration pattern"),
+            send: Regex::new(
+                r"(?:\b([A-Za-z_]\w*)|\bpayable\s*\(\s*([A-Za-z_]\w*)\s*\))\s*\.\s*(?:transfer\s*\(|send\s*\(|call\b)",
+            )
+            .expect("valid send pattern"),
+            call: Regex::new(r"\b([A-Za-z_]\w*)\s*\(").expect("valid call pattern"),
+        }
+    }
+
+    /// Collect what happens to `names` in `body`, whose first line is `first_line`
+    fn follow(
+        &self,
+        body: &str,
+        first_line: usize,
+        mut names: HashSet<String>,
+        depth: usize,
+        visited: &mut HashSet<String>,
+        flow: &mut ParameterFlow,
+    ) {
+        for range in statements(body) {
+            let text = &body[range.clone()];
+            let mut statement = text.trim();
+            if !mentions(statement, &names) {
+                continue;
+            }
+            let start = range.start + text.len() - text.trim_start().len();
+            let line = first_line + body[..start].matches('\n').count();
+
+            // Conditions guard the rest of the statement, if any
+            if let Some(rest) = statement.strip_prefix("else").filter(|rest| rest.starts_with(char::is_whitespace)) {
+                statement = rest.trim_start();
+            }
+            let keyword = ["require", "assert", "if", "while"]
+                .into_iter()
+                .find(|keyword| statement.starts_with(keyword) && statement[keyword.len()..].trim_start().starts_with('('));
+            if let Some(keyword) = keyword {
+                let open = statement.find('(').unwrap_or_default();
+                let close = matching_paren(statement, open).unwrap_or(statement.len());
+                let arguments = split_top_level(&statement[open + 1..close], ',');
+                // The message of a `require` is no test of the value
+                let condition = arguments.first().cloned().unwrap_or_default();
+                if keyword != "while" && mentions(&condition, &names) {
+                    flow.checks.push(condition);
+                }
+                statement = statement[(close + 1).min(statement.len())..].trim_start();
+            }
+
+            for captures in self.send.captures_iter(statement) {
+                let receiver = captures.get(1).or_else(|| captures.get(2)).map_or("", |m| m.as_str());
+                if names.contains(receiver) {
+                    flow.sends.push(line);
+                }
+            }
+            if names.iter().any(|name| {
+                Regex::new(&format!(r"\b{}\s*\[", regex::escape(name))).is_ok_and(|index| index.is_match(statement))
+            }) {
+                flow.indexed = true;
+            }
+
+            if let Some(captures) = self.assignment.captures(statement).or_else(|| self.declaration.captures(statement)) {
+                let target = &captures[1];
+                let value = &captures[2];
+                if mentions(value, &names) {
+                    if self.state_variables.contains(target) {
+                        flow.stores.push((target.to_string(), line));
+                    } else {
+                        // Copies carry the value on, e.g. `address admin = newAdmin;`
+                        names.insert(target.to_string());
+                    }
+                }
+            }
+            for variable in &self.state_variables {
+                let push = format!(r"\b{}\s*\.\s*push\s*\(", regex::escape(variable));
+                if let Some(m) = Regex::new(&push).ok().and_then(|push| push.find(statement)) {
+                    let close = matching_paren(statement, m.end() - 1).unwrap_or(statement.len());
+                    if mentions(&statement[m.end()..close], &names) {
+                        flow.stores.push((variable.to_string(), line));
+                    }
+                }
+            }
+
+            if depth < MAX_DEPTH {
+                self.follow_calls(statement, &names, depth, visited, flow);
+            }
+        }
+    }
+
+    /// Follow the value into internal functions it is passed to
+    fn follow_calls(&self, statement: &str, names: &HashSet<String>, depth: usize, visited: &mut HashSet<String>, flow: &mut ParameterFlow) {
+        for captures in self.call.captures_iter(statement) {
+            let whole = captures.get(0).unwrap();
+            // Calls through `this.` or another contract leave the unit
+            if statement[..whole.start()].trim_end().ends_with('.') {
+                continue;
+            }
+            let close = match matching_paren(statement, whole.end() - 1) {
+                Some(close) => close,
+                None => continue,
+            };
+            let arguments = split_top_level(&statement[whole.end()..close], ',');
+            let callee = self.functions.iter().find(|function| {
+                function.name == captures[1]
+                    && function.parameter_names.len() == arguments.len()
+                    && function.visibility != "external"
+                    && !visited.contains(&function.name)
+            });
+            let (callee, body) = match callee.and_then(|callee| Some((*callee, callee.body.as_deref()?))) {
+                Some(found) => found,
+                None => continue,
+            };
+
+            let passed: HashSet<String> = arguments
+                .iter()
+                .zip(&callee.parameter_names)
+                .filter(|(argument, parameter)| !parameter.is_empty() && mentions(argument, names))
+                .map(|(_, parameter)| parameter.clone())
+                .collect();
+            if !passed.is_empty() {
+                visited.insert(callee.name.clone());
+                self.follow(body, callee.body_line_number, passed, depth + 1, visited, flow);
+                visited.remove(&callee.name);
+            }
+        }
+    }
+
+    /// Follow the value into modifiers that take it as an argument
+    fn follow_modifiers(&self, function: &UnitFunction, parameter: &str, flow: &mut ParameterFlow) {
+        // Modifier arguments are only known as words of the header, so a
+        // modifier given the parameter is assumed to test all its own
+        if !function.modifiers.iter().any(|word| word == parameter) {
+            return;
+        }
+        for modifier in &self.contract.modifiers {
+            if modifier.parameters.is_empty() || !function.modifiers.contains(&modifier.name) {
+                continue;
+            }
+            let names = modifier.parameters.iter().map(|parameter| parameter.name.clone()).collect();
+            let body = strip_comments(&modifier.body);
+            let mut visited = HashSet::new();
+            self.follow(&body, modifier.line_number, names, 1, &mut visited, flow);
+        }
+    }
+}
+
+/// Whether `text` uses any of `names` as an identifier
+fn mentions(text: &str, names: &HashSet<String>) -> bool {
+    let mut identifiers = text.split(|c: char| !(c.is_alphanumeric() || c == '_'));
+    identifiers.any(|identifier| names.contains(identifier))
+}
+
+/// Byte ranges of the statements and conditions of a body
+fn statements(body: &str) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for (index, c) in body.char_indices() {
+        if matches!(c, ';' | '{' | '}') {
+            if !body[start..index].trim().is_empty() {
+                ranges.push(start..index);
+            }
+            start = index + 1;
+        }
+    }
+    ranges
+}
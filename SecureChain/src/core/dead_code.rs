@@ -0,0 +1,246 @@
+//! Dead code detection
+//!
+//! Dead code inflates the scope of an audit and often marks a partially
+//! removed feature whose remaining half still matters. Three kinds are
+//! reported under the DeadCode category: internal and private functions no
+//! external entry point reaches, state variables nothing reads or writes,
+//! and branches that can never run because their condition is constant.
+
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::core::callgraph::CallGraph;
+use crate::core::parser::{matching_brace, matching_paren, ParsedContract};
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+
+const TOOL_NAME: &str = "Dead Code Analysis";
+
+/// Find dead functions, unused state variables and constant-condition branches
+pub fn detect_dead_code(contracts: &[ParsedContract], graph: &CallGraph) -> Vec<Vulnerability> {
+    let mut findings = unreachable_functions(graph);
+    findings.extend(unused_state_variables(contracts));
+    findings.extend(constant_branches(contracts));
+    findings
+}
+
+/// Internal and private functions no entry point, constructor or modifier reaches
+fn unreachable_functions(graph: &CallGraph) -> Vec<Vulnerability> {
+    let reachable = graph.reachable();
+
+    graph
+        .nodes
+        .iter()
+        .enumerate()
+        // Library functions exist to be reused by code outside the run
+        .filter(|(index, node)| {
+            !reachable.contains(index)
+                && matches!(node.function.visibility.as_str(), "internal" | "private")
+                && node.function.body.is_some()
+                && node.unit_kind != "library"
+        })
+        .map(|(index, node)| {
+            let callers: Vec<String> = graph
+                .callers(index)
+                .iter()
+                .map(|caller| format!("`{}`", graph.nodes[*caller].function.name))
+                .collect();
+            let context = if callers.is_empty() {
+                "nothing calls it".to_string()
+            } else {
+                format!("it is only called from other dead code ({})", callers.join(", "))
+            };
+
+            finding(
+                node.file,
+                node.function.line_number,
+                format!("Unreachable Function {}.{}", node.unit, node.function.name),
+                format!(
+                    "{} `{}` in {} cannot be reached from any external entry point, constructor or modifier: {}.",
+                    capitalize(&node.function.visibility),
+                    node.function.name,
+                    node.unit,
+                    context
+                ),
+                "CWE-561",
+                "Remove the function, or wire it back in if it belongs to a feature that was only partially removed.",
+            )
+        })
+        .collect()
+}
+
+/// Non-public state variables whose name appears nowhere but in their declaration
+fn unused_state_variables(contracts: &[ParsedContract]) -> Vec<Vulnerability> {
+    let identifier_pattern = Regex::new(r"[A-Za-z_$][\w$]*").expect("valid identifier pattern");
+
+    // Derived contracts in other files may use a variable, so count across the run
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+    for contract in contracts {
+        for identifier in identifier_pattern.find_iter(contract.code()) {
+            *occurrences.entry(identifier.as_str().to_string()).or_default() += 1;
+        }
+    }
+
+    let mut declarations: HashMap<&str, usize> = HashMap::new();
+    for unit in contracts.iter().flat_map(|contract| &contract.units) {
+        for variable in &unit.state_variables {
+            *declarations.entry(variable.name.as_str()).or_default() += 1;
+        }
+    }
+
+    let mut findings = Vec::new();
+    for contract in contracts {
+        for unit in contract.units.iter().filter(|unit| unit.kind != "interface") {
+            // Public variables are part of the ABI through their getter; `__gap` arrays reserve storage
+            let candidates = unit
+                .state_variables
+                .iter()
+                .filter(|variable| variable.visibility != "public" && !variable.name.starts_with("__"));
+
+            for variable in candidates {
+                let uses = occurrences.get(&variable.name).copied().unwrap_or_default();
+                if uses > declarations[variable.name.as_str()] {
+                    continue;
+                }
+
+                let layout_note = if variable.is_constant || variable.is_immutable {
+                    ""
+                } else {
+                    " Removing it shifts the storage layout, so upgradeable contracts should keep the slot as a gap instead."
+                };
+                findings.push(finding(
+                    &contract.name,
+                    variable.line_number,
+                    format!("Unused State Variable {}.{}", unit.name, variable.name),
+                    format!(
+                        "`{} {}` in {} is declared but never read or written.{}",
+                        variable.type_name, variable.name, unit.name, layout_note
+                    ),
+                    "CWE-563",
+                    "Remove the variable, or restore the code that was meant to use it.",
+                ));
+            }
+        }
+    }
+
+    findings
+}
+
+/// `if` and `while` statements whose condition is a literal or a boolean constant
+fn constant_branches(contracts: &[ParsedContract]) -> Vec<Vulnerability> {
+    let constant_pattern = Regex::new(r"\bbool\s+(?:\w+\s+)*?constant\s+(?:\w+\s+)*?(\w+)\s*=\s*(true|false)\s*;")
+        .expect("valid constant pattern");
+    let branch_pattern = Regex::new(r"\b(if|while)\s*\(\s*(!\s*)?(true|false|[A-Za-z_]\w*)\s*\)").expect("valid branch pattern");
+
+    let sources: Vec<&str> = contracts.iter().map(|contract| contract.code()).collect();
+    let constants: HashMap<&str, bool> = sources
+        .iter()
+        .flat_map(|source| constant_pattern.captures_iter(source))
+        .map(|captures| (captures.get(1).unwrap().as_str(), &captures[2] == "true"))
+        .collect();
+
+    let mut findings = Vec::new();
+    for (contract, source) in contracts.iter().zip(&sources) {
+        for captures in branch_pattern.captures_iter(source) {
+            let operand = &captures[3];
+            let value = match operand {
+                "true" => true,
+                "false" => false,
+                name => match constants.get(name) {
+                    Some(value) => *value,
+                    None => continue,
+                },
+            };
+            let negated = captures.get(2).is_some();
+            let value = value != negated;
+            let condition = format!("{}{}", if negated { "!" } else { "" }, operand);
+            let statement = captures.get(0).unwrap();
+            let line_number = source[..statement.start()].matches('\n').count() + 1;
+
+            let dead_part = match (&captures[1], value) {
+                ("while", false) => "The loop body never executes",
+                ("if", false) => "The `if` branch never executes",
+                ("if", true) if has_else(source, statement.end()) => "The `else` branch never executes",
+                _ => continue,
+            };
+
+            findings.push(finding(
+                &contract.name,
+                line_number,
+                format!("Constant Condition {} ({})", &captures[1], condition),
+                format!(
+                    "The condition `{}` is always {}. {}, which usually means a feature was switched off rather than removed.",
+                    condition, value, dead_part
+                ),
+                "CWE-570",
+                "Remove the unreachable branch together with the constant that guards it.",
+            ));
+        }
+    }
+
+    findings
+}
+
+/// Whether the statement starting at `offset` is followed by an `else`
+fn has_else(source: &str, offset: usize) -> bool {
+    let rest = &source[offset..];
+    let start = offset + (rest.len() - rest.trim_start().len());
+
+    let end = if source[start..].starts_with('{') {
+        matching_brace(source, start).map(|close| close + 1)
+    } else {
+        // A single statement runs to its semicolon, skipping nested calls
+        let mut cursor = start;
+        loop {
+            match source[cursor..].find([';', '(']) {
+                Some(i) if source[cursor + i..].starts_with('(') => match matching_paren(source, cursor + i) {
+                    Some(close) => cursor = close + 1,
+                    None => break None,
+                },
+                Some(i) => break Some(cursor + i + 1),
+                None => break None,
+            }
+        }
+    };
+
+    end.is_some_and(|end| {
+        let after = source[end..].trim_start();
+        after.starts_with("else") && !after[4..].starts_with(|c: char| c.is_alphanumeric() || c == '_')
+    })
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn finding(
+    file: &str,
+    line_number: usize,
+    title: String,
+    description: String,
+    cwe_id: &str,
+    recommendation: &str,
+) -> Vulnerability {
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title,
+        description,
+        severity: "Info".to_string(),
+        category: VulnerabilityCategory::DeadCode,
+        file_path: file.to_string(),
+        line_number: Some(line_number),
+        code_snippet: None,
+        recommendation: Some(recommendation.to_string()),
+        references: Vec::new(),
+        cwe_id: Some(cwe_id.to_string()),
+        tool: TOOL_NAME.to_string(),
+        confidence: 0.7,
+        provenance: None,
+        severity_overrides: Vec::new(),
+        cvss: None,
+        detected_by: Vec::new(),
+    }
+}
@@ -0,0 +1,176 @@
+//! Merging of the same finding reported by several tools
+//!
+//! Slither, Mythril, the linters, the AI and the built-in detectors often
+//! report the same issue, such as one reentrancy, each in their own words.
+//! Findings of different tools in the same file, within a few lines of each
+//! other and either of the same category or with near-identical
+//! descriptions, are merged into one: the most severe, most confident report
+//! is kept, takes the highest confidence of the group, and lists every tool
+//! that reported it in `detected_by`. Findings of one tool are never merged
+//! with each other, since a tool reporting twice means two issues. Findings
+//! without a line are only at the same place when they name the same
+//! function or say the same thing.
+
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+
+/// Lines apart two findings can be and still be the same issue
+const LINE_TOLERANCE: usize = 2;
+
+/// Share of description words two findings of different categories need in common to be the same issue
+const DESCRIPTION_SIMILARITY: f64 = 0.6;
+
+/// Merge findings of different tools that report the same issue; the kept
+/// findings stay in the order of the input
+pub fn merge_duplicates(vulnerabilities: Vec<Vulnerability>) -> Vec<Vulnerability> {
+    let traits: Vec<Traits> = vulnerabilities.iter().map(Traits::of).collect();
+
+    // Most severe and most confident first, so each group is led by the report it keeps
+    let mut order: Vec<usize> = (0..vulnerabilities.len()).collect();
+    order.sort_by(|a, b| {
+        let (a, b) = (&vulnerabilities[*a], &vulnerabilities[*b]);
+        b.severity_priority()
+            .cmp(&a.severity_priority())
+            .then(b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal))
+    });
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut groups_by_file: HashMap<&str, Vec<usize>> = HashMap::new();
+    for index in order {
+        let vuln = &vulnerabilities[index];
+        let candidates = groups_by_file.entry(vuln.file_path.as_str()).or_default();
+        let joined = candidates.iter().copied().find(|group| {
+            let members = &groups[*group];
+            let leader = members[0];
+            let tools: HashSet<&str> = members.iter().flat_map(|member| vulnerabilities[*member].tools()).collect();
+            !vuln.tools().iter().any(|tool| tools.contains(tool))
+                && same_issue(&vulnerabilities[leader], vuln, &traits[leader], &traits[index])
+        });
+        match joined {
+            Some(group) => groups[group].push(index),
+            None => {
+                candidates.push(groups.len());
+                groups.push(vec![index]);
+            }
+        }
+    }
+
+    groups.sort_by_key(|members| members[0]);
+    let mut slots: Vec<Option<Vulnerability>> = vulnerabilities.into_iter().map(Some).collect();
+    groups
+        .into_iter()
+        .filter_map(|members| {
+            let mut kept = slots[members[0]].take()?;
+            for member in &members[1..] {
+                if let Some(duplicate) = slots[*member].take() {
+                    absorb(&mut kept, duplicate);
+                }
+            }
+            Some(kept)
+        })
+        .collect()
+}
+
+/// What of a finding's wording places and compares it
+struct Traits {
+    words: HashSet<String>,
+    /// Functions the title, description or snippet names, as in `withdraw(uint256)`
+    functions: HashSet<String>,
+    /// Description in lower case with whitespace collapsed
+    description: String,
+}
+
+impl Traits {
+    fn of(vuln: &Vulnerability) -> Self {
+        let mut functions = HashSet::new();
+        for text in [Some(vuln.title.as_str()), Some(vuln.description.as_str()), vuln.code_snippet.as_deref()].into_iter().flatten() {
+            functions.extend(named_functions(text));
+        }
+        Self {
+            words: description_words(&vuln.description),
+            functions,
+            description: vuln.description.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase(),
+        }
+    }
+}
+
+/// Same location, and the same category or the same description
+fn same_issue(a: &Vulnerability, b: &Vulnerability, a_traits: &Traits, b_traits: &Traits) -> bool {
+    let nearby = match (a.line_number, b.line_number) {
+        (Some(a), Some(b)) => a.abs_diff(b) <= LINE_TOLERANCE,
+        // Without lines the file alone says nothing, so the finding must name the same function or read the same
+        (None, None) => {
+            !a_traits.functions.is_disjoint(&b_traits.functions)
+                || (!a_traits.description.is_empty() && a_traits.description == b_traits.description)
+        }
+        _ => false,
+    };
+    nearby
+        && ((a.category == b.category && a.category != VulnerabilityCategory::Other)
+            || similarity(&a_traits.words, &b_traits.words) >= DESCRIPTION_SIMILARITY)
+}
+
+/// Names called or declared in `text`, Solidity builtins aside
+fn named_functions(text: &str) -> impl Iterator<Item = String> + '_ {
+    static CALL: OnceLock<Regex> = OnceLock::new();
+    let call = CALL.get_or_init(|| Regex::new(r"\b([A-Za-z_][A-Za-z0-9_]*)\s*\(").expect("valid function pattern"));
+    call.captures_iter(text).map(|captures| captures[1].to_string()).filter(|name| {
+        !matches!(
+            name.as_str(),
+            "require" | "revert" | "assert" | "if" | "for" | "while" | "return" | "returns" | "emit" | "function" | "modifier"
+                | "mapping" | "address" | "payable" | "keccak256" | "abi" | "uint256" | "bytes" | "string" | "CWE" | "SWC"
+        )
+    })
+}
+
+/// Fold `duplicate` into `kept`, keeping what `kept` says and filling in what it lacks
+fn absorb(kept: &mut Vulnerability, duplicate: Vulnerability) {
+    let mut tools: Vec<String> = kept.tools().into_iter().map(str::to_string).collect();
+    for tool in duplicate.tools() {
+        if !tools.iter().any(|known| known == tool) {
+            tools.push(tool.to_string());
+        }
+    }
+    kept.detected_by = tools;
+
+    kept.confidence = kept.confidence.max(duplicate.confidence);
+    kept.merge_provenance(&duplicate);
+    for reference in &duplicate.references {
+        if !kept.references.contains(reference) {
+            kept.references.push(reference.clone());
+        }
+    }
+    if kept.cwe_id.is_none() {
+        kept.cwe_id = duplicate.cwe_id;
+    }
+    if kept.recommendation.is_none() {
+        kept.recommendation = duplicate.recommendation;
+    }
+    if kept.code_snippet.is_none() {
+        kept.code_snippet = duplicate.code_snippet;
+    }
+    if kept.line_number.is_none() {
+        kept.line_number = duplicate.line_number;
+    }
+}
+
+/// Words of a description without numbers, addresses and filler, in lower case
+fn description_words(description: &str) -> HashSet<String> {
+    description
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|word| word.len() > 2 && !word.starts_with("0x") && !word.chars().all(|c| c.is_ascii_digit()))
+        .map(str::to_lowercase)
+        .filter(|word| !matches!(word.as_str(), "the" | "and" | "for" | "with" | "from" | "can" | "may" | "this" | "that" | "are" | "was"))
+        .collect()
+}
+
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
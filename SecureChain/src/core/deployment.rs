@@ -0,0 +1,377 @@
+//! Deployment parameter analysis for on-chain contracts
+//!
+//! This module decodes the constructor arguments recorded by block explorers
+//! for a contract's creation transaction and runs sanity checks on them.
+//! Many real incidents stem from bad deployment parameters (a zero owner,
+//! a 100% fee, the wrong token decimals) rather than from bugs in the code.
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::core::fetcher::ContractInfo;
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+use crate::utils::abi::{decode_hex, encode_hex, read_bytes, read_usize, to_decimal, word};
+
+/// Parameter names that usually hold privileged or fund-receiving addresses
+const ROLE_KEYWORDS: &[&str] = &[
+    "owner", "admin", "governance", "governor", "guardian", "treasury", "recipient",
+    "feeto", "minter", "operator", "manager", "oracle", "multisig", "timelock",
+    "vault", "router", "factory", "beneficiary", "pauser", "implementation",
+];
+
+/// Parameter names that usually hold fee or rate values
+const FEE_KEYWORDS: &[&str] = &["fee", "tax", "commission", "bps", "basis", "percent", "pct"];
+
+/// 1e18, i.e. 100% in 18-decimal fixed point
+const WAD: u128 = 1_000_000_000_000_000_000;
+
+/// A decoded constructor argument
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentParameter {
+    pub name: String,
+    pub type_name: String,
+    pub value: String,
+}
+
+/// Decoded constructor arguments and the findings raised on them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentAnalysis {
+    pub parameters: Vec<DeploymentParameter>,
+    pub findings: Vec<Vulnerability>,
+}
+
+/// Decode and check the constructor arguments of a fetched contract.
+///
+/// Returns `None` when the explorer did not provide an ABI constructor or
+/// constructor arguments for the contract.
+pub fn analyze_deployment(contract: &ContractInfo) -> Result<Option<DeploymentAnalysis>> {
+    let abi = contract.metadata.get("abi").map(String::as_str).unwrap_or("");
+    let args = contract.metadata.get("constructor_arguments").map(String::as_str).unwrap_or("");
+
+    if abi.is_empty() || args.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let inputs = match constructor_inputs(abi)? {
+        Some(inputs) if !inputs.is_empty() => inputs,
+        _ => return Ok(None),
+    };
+
+    let data = decode_hex(args).map_err(|e| anyhow!("Invalid constructor arguments: {}", e))?;
+    let parameters = decode_parameters(&inputs, &data);
+    let findings = check_parameters(contract, &parameters);
+
+    Ok(Some(DeploymentAnalysis { parameters, findings }))
+}
+
+/// Extract `(name, type)` pairs of the constructor inputs from an ABI string
+fn constructor_inputs(abi: &str) -> Result<Option<Vec<(String, String)>>> {
+    let abi: serde_json::Value = serde_json::from_str(abi)
+        .map_err(|e| anyhow!("Invalid contract ABI: {}", e))?;
+
+    let constructor = abi
+        .as_array()
+        .and_then(|entries| entries.iter().find(|entry| entry.get("type").and_then(|t| t.as_str()) == Some("constructor")));
+
+    let inputs = constructor
+        .and_then(|constructor| constructor.get("inputs"))
+        .and_then(|inputs| inputs.as_array())
+        .map(|inputs| {
+            inputs
+                .iter()
+                .enumerate()
+                .map(|(i, input)| {
+                    let name = input.get("name").and_then(|n| n.as_str()).unwrap_or("");
+                    let name = if name.is_empty() { format!("arg{}", i) } else { name.to_string() };
+                    let type_name = input.get("type").and_then(|t| t.as_str()).unwrap_or("unknown").to_string();
+                    (name, type_name)
+                })
+                .collect()
+        });
+
+    Ok(inputs)
+}
+
+/// ABI-decode constructor arguments.
+///
+/// Elementary types, `string`/`bytes` and arrays of elementary types are
+/// supported. Decoding stops at the first tuple since its head size cannot
+/// be known without the component types; the remaining parameters are
+/// reported as undecoded.
+fn decode_parameters(inputs: &[(String, String)], data: &[u8]) -> Vec<DeploymentParameter> {
+    let mut parameters = Vec::new();
+    let mut head = 0;
+    let mut decodable = true;
+
+    for (name, type_name) in inputs {
+        let value = if !decodable {
+            "<undecoded>".to_string()
+        } else if type_name.starts_with("tuple") {
+            decodable = false;
+            "<undecoded tuple>".to_string()
+        } else {
+            let (value, head_size) = decode_value(type_name, data, head);
+            head += head_size;
+            value.unwrap_or_else(|| "<malformed>".to_string())
+        };
+
+        parameters.push(DeploymentParameter {
+            name: name.clone(),
+            type_name: type_name.clone(),
+            value,
+        });
+    }
+
+    parameters
+}
+
+/// Decode the value at `head`, returning it with the number of head bytes it occupies
+fn decode_value(type_name: &str, data: &[u8], head: usize) -> (Option<String>, usize) {
+    // Fixed-size array of elementary values, encoded inline
+    if let Some((element, Some(length))) = split_array(type_name) {
+        if !is_dynamic(element) {
+            let values: Option<Vec<String>> = (0..length)
+                .map(|i| decode_elementary(element, word(data, head + i * 32)?))
+                .collect();
+            return (values.map(|v| format!("[{}]", v.join(", "))), length * 32);
+        }
+    }
+
+    if !is_dynamic(type_name) {
+        return (word(data, head).and_then(|w| decode_elementary(type_name, w)), 32);
+    }
+
+    let value = read_usize(data, head).and_then(|offset| match type_name {
+        "string" => read_bytes(data, offset).map(|bytes| format!("\"{}\"", String::from_utf8_lossy(bytes))),
+        "bytes" => read_bytes(data, offset).map(|bytes| format!("0x{}", encode_hex(bytes))),
+        _ => match split_array(type_name) {
+            Some((element, None)) if !is_dynamic(element) => {
+                let length = read_usize(data, offset)?;
+                let values: Option<Vec<String>> = (0..length)
+                    .map(|i| decode_elementary(element, word(data, offset + 32 + i * 32)?))
+                    .collect();
+                values.map(|v| format!("[{}]", v.join(", ")))
+            }
+            _ => None,
+        },
+    });
+
+    (value, 32)
+}
+
+/// Decode a single 32-byte word as an elementary type
+fn decode_elementary(type_name: &str, word: &[u8]) -> Option<String> {
+    if type_name == "address" {
+        return Some(format!("0x{}", encode_hex(&word[12..])));
+    }
+    if type_name == "bool" {
+        return Some((word[31] != 0).to_string());
+    }
+    if let Some(size) = type_name.strip_prefix("bytes") {
+        let size: usize = size.parse().ok()?;
+        return Some(format!("0x{}", encode_hex(&word[..size.min(32)])));
+    }
+    if type_name.starts_with("uint") {
+        return Some(to_decimal(word));
+    }
+    if type_name.starts_with("int") {
+        if word[0] & 0x80 == 0 {
+            return Some(to_decimal(word));
+        }
+        // Two's complement negation
+        let mut negated: Vec<u8> = word.iter().map(|b| !b).collect();
+        for byte in negated.iter_mut().rev() {
+            let (sum, overflow) = byte.overflowing_add(1);
+            *byte = sum;
+            if !overflow {
+                break;
+            }
+        }
+        return Some(format!("-{}", to_decimal(&negated)));
+    }
+
+    None
+}
+
+/// Split `T[]` / `T[k]` into the element type and optional length
+fn split_array(type_name: &str) -> Option<(&str, Option<usize>)> {
+    let open = type_name.rfind('[')?;
+    let length = &type_name[open + 1..type_name.len().checked_sub(1)?];
+    let element = &type_name[..open];
+
+    if length.is_empty() {
+        Some((element, None))
+    } else {
+        length.parse().ok().map(|k| (element, Some(k)))
+    }
+}
+
+fn is_dynamic(type_name: &str) -> bool {
+    match split_array(type_name) {
+        Some((_, None)) => true,
+        Some((element, Some(_))) => is_dynamic(element),
+        None => type_name == "string" || type_name == "bytes" || type_name.starts_with("tuple"),
+    }
+}
+
+/// Run sanity checks on decoded deployment parameters
+fn check_parameters(contract: &ContractInfo, parameters: &[DeploymentParameter]) -> Vec<Vulnerability> {
+    let mut vulnerabilities = Vec::new();
+    let source_decimals = hardcoded_decimals(&contract.source_code);
+
+    for param in parameters {
+        let lower_name = param.name.to_lowercase().replace('_', "");
+
+        // Zero addresses, in particular for privileged roles
+        if param.type_name == "address" && param.value.trim_start_matches("0x").chars().all(|c| c == '0') {
+            let is_role = ROLE_KEYWORDS.iter().any(|keyword| lower_name.contains(keyword));
+            let (title, severity, description) = if is_role {
+                (
+                    format!("Zero Address for Critical Role `{}`", param.name),
+                    "High",
+                    format!("The contract was deployed with the zero address as `{}`. Privileged functions tied to this role are unusable, or funds sent to it are burned.", param.name),
+                )
+            } else {
+                (
+                    format!("Zero Address Constructor Argument `{}`", param.name),
+                    "Medium",
+                    format!("The contract was deployed with the zero address as `{}`, which is rarely intended.", param.name),
+                )
+            };
+
+            vulnerabilities.push(deployment_finding(
+                contract,
+                param,
+                title,
+                description,
+                severity,
+                VulnerabilityCategory::AccessControl,
+                "Verify the deployment parameters and, if the role can be updated, set it to the intended address; otherwise redeploy.",
+            ));
+            continue;
+        }
+
+        if !param.type_name.starts_with("uint") {
+            continue;
+        }
+        let value = parse_uint(&param.value);
+
+        // Fee and rate parameters outside any sensible range
+        if FEE_KEYWORDS.iter().any(|keyword| lower_name.contains(keyword)) {
+            let (scale, limit, warn) = if lower_name.contains("bps") || lower_name.contains("basis") {
+                ("basis points", 10_000, 1_000)
+            } else if lower_name.contains("percent") || lower_name.contains("pct") {
+                ("percent", 100, 25)
+            } else {
+                ("18-decimal fixed point", WAD, WAD / 4)
+            };
+
+            let severity = if value > limit {
+                Some("High")
+            } else if value > warn {
+                Some("Medium")
+            } else {
+                None
+            };
+
+            if let Some(severity) = severity {
+                let description = if value > limit {
+                    format!("`{}` was set to {}, which exceeds 100% when read as {}.", param.name, param.value, scale)
+                } else {
+                    format!("`{}` was set to {}, an unusually high value when read as {}.", param.name, param.value, scale)
+                };
+
+                vulnerabilities.push(deployment_finding(
+                    contract,
+                    param,
+                    format!("Suspicious Fee Parameter `{}`", param.name),
+                    description,
+                    severity,
+                    VulnerabilityCategory::InputValidation,
+                    "Confirm the unit the contract expects for this parameter and that the deployed value matches the intended fee.",
+                ));
+            }
+        }
+
+        // Token decimals that disagree with the code or common assumptions
+        if lower_name.contains("decimals") {
+            if let Some(expected) = source_decimals.filter(|expected| *expected != value) {
+                vulnerabilities.push(deployment_finding(
+                    contract,
+                    param,
+                    format!("Mismatched Token Decimals `{}`", param.name),
+                    format!("`{}` was deployed as {} but the source hard-codes {} decimals. Amount conversions between the two will be off by orders of magnitude.", param.name, param.value, expected),
+                    "High",
+                    VulnerabilityCategory::InputValidation,
+                    "Derive decimals from the token contract at deployment or make sure the deployed value matches the token.",
+                ));
+            } else if value > 18 {
+                vulnerabilities.push(deployment_finding(
+                    contract,
+                    param,
+                    format!("Unusual Token Decimals `{}`", param.name),
+                    format!("`{}` was deployed as {}. Most integrations assume at most 18 decimals.", param.name, param.value),
+                    "Medium",
+                    VulnerabilityCategory::InputValidation,
+                    "Double-check the decimals of the token this contract is meant to handle.",
+                ));
+            }
+        }
+    }
+
+    vulnerabilities
+}
+
+fn deployment_finding(
+    contract: &ContractInfo,
+    param: &DeploymentParameter,
+    title: String,
+    description: String,
+    severity: &str,
+    category: VulnerabilityCategory,
+    recommendation: &str,
+) -> Vulnerability {
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title,
+        description,
+        severity: severity.to_string(),
+        category,
+        file_path: contract.name.clone(),
+        line_number: None,
+        code_snippet: Some(format!("{} {} = {}", param.type_name, param.name, param.value)),
+        recommendation: Some(recommendation.to_string()),
+        references: Vec::new(),
+        cwe_id: Some("CWE-1188".to_string()),
+        tool: "Deployment Check".to_string(),
+        confidence: 0.7,
+        provenance: None,
+        severity_overrides: Vec::new(),
+        cvss: None,
+        detected_by: Vec::new(),
+    }
+}
+
+/// Parse a decoded uint, saturating values that do not fit into u128
+fn parse_uint(value: &str) -> u128 {
+    value.parse().unwrap_or(u128::MAX)
+}
+
+/// Decimals hard-coded in the source, e.g. `decimals = 6` or `return 6;` in `decimals()`
+fn hardcoded_decimals(source_code: &str) -> Option<u128> {
+    let patterns = [
+        r"function\s+decimals\s*\([^)]*\)[^{]*\{\s*return\s+(\d+)\s*;",
+        r"\b_?decimals\s*=\s*(\d+)\s*;",
+    ];
+
+    patterns.iter().find_map(|pattern| {
+        Regex::new(pattern)
+            .ok()?
+            .captures(source_code)?
+            .get(1)?
+            .as_str()
+            .parse()
+            .ok()
+    })
+}
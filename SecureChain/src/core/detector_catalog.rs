@@ -0,0 +1,1075 @@
+//! Catalog of built-in detectors
+//!
+//! Suppression rules and policies need stable names for what they refer to,
+//! but built-in findings only carry a tool and a title. The catalog gives
+//! every built-in check an id, its default severity and category, what it
+//! looks for with an example, and the title pattern that ties a finding back
+//! to it. Native detectors and checks Slither also has reuse Slither's check
+//! names; the others are named after their tool. Ids listed in
+//! `analysis.disabled_detectors` are dropped from results.
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+use crate::utils::config::Config;
+use VulnerabilityCategory::*;
+
+/// One built-in check
+#[derive(Debug, Clone, Serialize)]
+pub struct Detector {
+    /// Stable id, for configuration and suppression rules
+    pub id: &'static str,
+    /// Tools that report its findings, as findings name them
+    pub tools: &'static [&'static str],
+    pub targets: &'static [&'static str],
+    pub severity: &'static str,
+    pub category: VulnerabilityCategory,
+    /// When the check runs
+    pub runs: &'static str,
+    pub description: &'static str,
+    /// Code the check reports
+    pub example: &'static str,
+    /// Pattern of the titles of its findings
+    #[serde(skip)]
+    title: &'static str,
+}
+
+impl Detector {
+    /// Whether `vuln` was reported by this check
+    pub fn matches(&self, vuln: &Vulnerability) -> bool {
+        self.tools.contains(&vuln.tool.as_str())
+            && Regex::new(self.title).is_ok_and(|pattern| pattern.is_match(&vuln.title))
+    }
+
+    /// Whether the check is enabled under `config`
+    pub fn enabled(&self, config: &Config) -> bool {
+        !config.analysis.disabled_detectors.iter().any(|id| id == self.id)
+    }
+}
+
+const EVM: &[&str] = &["evm"];
+
+const fn native(
+    id: &'static str,
+    severity: &'static str,
+    category: VulnerabilityCategory,
+    title: &'static str,
+    description: &'static str,
+    example: &'static str,
+) -> Detector {
+    Detector {
+        id,
+        tools: &["Native Detectors"],
+        targets: EVM,
+        severity,
+        category,
+        runs: "in place of Slither when it is not installed or fails",
+        description,
+        example,
+        title,
+    }
+}
+
+const fn selectors(
+    id: &'static str,
+    severity: &'static str,
+    category: VulnerabilityCategory,
+    title: &'static str,
+    description: &'static str,
+    example: &'static str,
+) -> Detector {
+    Detector {
+        id,
+        tools: &["Selector Analysis"],
+        targets: EVM,
+        severity,
+        category,
+        runs: "on every EVM run, across all contracts of the run",
+        description,
+        example,
+        title,
+    }
+}
+
+const fn dead_code(id: &'static str, title: &'static str, description: &'static str, example: &'static str) -> Detector {
+    Detector {
+        id,
+        tools: &["Dead Code Analysis"],
+        targets: EVM,
+        severity: "Info",
+        category: DeadCode,
+        runs: "on every EVM run, across all contracts of the run",
+        description,
+        example,
+        title,
+    }
+}
+
+const fn validation(
+    id: &'static str,
+    severity: &'static str,
+    title: &'static str,
+    description: &'static str,
+    example: &'static str,
+) -> Detector {
+    Detector {
+        id,
+        tools: &["Parameter Validation"],
+        targets: EVM,
+        severity,
+        category: InputValidation,
+        runs: "on every EVM run, on constructor and setter parameters",
+        description,
+        example,
+        title,
+    }
+}
+
+const fn error_handling(
+    id: &'static str,
+    severity: &'static str,
+    category: VulnerabilityCategory,
+    title: &'static str,
+    description: &'static str,
+    example: &'static str,
+) -> Detector {
+    Detector {
+        id,
+        tools: &["Error Handling"],
+        targets: EVM,
+        severity,
+        category,
+        runs: "on every EVM run, once per contract",
+        description,
+        example,
+        title,
+    }
+}
+
+const fn gas(id: &'static str, title: &'static str, description: &'static str, example: &'static str) -> Detector {
+    Detector {
+        id,
+        tools: &["Gas Analysis"],
+        targets: EVM,
+        severity: "Info",
+        category: GasOptimization,
+        runs: "on every EVM run, on Solidity sources",
+        description,
+        example,
+        title,
+    }
+}
+
+const fn diamond(
+    id: &'static str,
+    severity: &'static str,
+    category: VulnerabilityCategory,
+    title: &'static str,
+    description: &'static str,
+    example: &'static str,
+) -> Detector {
+    Detector {
+        id,
+        tools: &["Diamond Analysis"],
+        targets: EVM,
+        severity,
+        category,
+        runs: "with `analyze --diamond` and `fetch --diamond`",
+        description,
+        example,
+        title,
+    }
+}
+
+const fn arithmetic(
+    id: &'static str,
+    severity: &'static str,
+    category: VulnerabilityCategory,
+    title: &'static str,
+    description: &'static str,
+    example: &'static str,
+) -> Detector {
+    Detector {
+        id,
+        tools: &["Arithmetic Execution"],
+        targets: EVM,
+        severity,
+        category,
+        runs: "on standard and deep EVM runs of Solidity sources, with solc and the `execution` feature",
+        description,
+        example,
+        title,
+    }
+}
+
+const fn proxy(
+    id: &'static str,
+    severity: &'static str,
+    category: VulnerabilityCategory,
+    title: &'static str,
+    description: &'static str,
+    example: &'static str,
+) -> Detector {
+    Detector {
+        id,
+        tools: &["Proxy Analysis"],
+        targets: EVM,
+        severity,
+        category,
+        runs: "on every EVM run, across all contracts of the run",
+        description,
+        example,
+        title,
+    }
+}
+
+const fn deployment(
+    id: &'static str,
+    severity: &'static str,
+    category: VulnerabilityCategory,
+    title: &'static str,
+    description: &'static str,
+    example: &'static str,
+) -> Detector {
+    Detector {
+        id,
+        tools: &["Deployment Check"],
+        targets: EVM,
+        severity,
+        category,
+        runs: "on contracts fetched from an explorer, against their constructor arguments",
+        description,
+        example,
+        title,
+    }
+}
+
+const fn evm_plugin(
+    id: &'static str,
+    severity: &'static str,
+    category: VulnerabilityCategory,
+    title: &'static str,
+    description: &'static str,
+    example: &'static str,
+) -> Detector {
+    Detector {
+        id,
+        tools: &["EVM Plugin"],
+        targets: EVM,
+        severity,
+        category,
+        runs: "in the pre-commit hook on staged Solidity files",
+        description,
+        example,
+        title,
+    }
+}
+
+const fn vyper(
+    id: &'static str,
+    severity: &'static str,
+    category: VulnerabilityCategory,
+    title: &'static str,
+    description: &'static str,
+    example: &'static str,
+) -> Detector {
+    Detector {
+        id,
+        tools: &["Vyper Checks"],
+        targets: EVM,
+        severity,
+        category,
+        runs: "on every EVM run, on Vyper sources",
+        description,
+        example,
+        title,
+    }
+}
+
+const fn rust(
+    id: &'static str,
+    severity: &'static str,
+    category: VulnerabilityCategory,
+    title: &'static str,
+    description: &'static str,
+    example: &'static str,
+) -> Detector {
+    Detector {
+        id,
+        tools: &["Ink Plugin", "Solana Plugin"],
+        targets: &["ink", "solana"],
+        severity,
+        category,
+        runs: "on every Ink! and Solana run, on the Rust syntax tree",
+        description,
+        example,
+        title,
+    }
+}
+
+const fn plugin(
+    target: &'static [&'static str],
+    tool: &'static [&'static str],
+    id: &'static str,
+    severity: &'static str,
+    category: VulnerabilityCategory,
+    title: &'static str,
+    description: &'static str,
+) -> Detector {
+    Detector {
+        id,
+        tools: tool,
+        targets: target,
+        severity,
+        category,
+        runs: "on every run for its platform",
+        description,
+        example: "",
+        title,
+    }
+}
+
+const INK: (&[&str], &[&str]) = (&["ink"], &["Ink Plugin"]);
+const MOVE: (&[&str], &[&str]) = (&["move"], &["Move Plugin"]);
+const CAIRO: (&[&str], &[&str]) = (&["cairo"], &["Cairo Plugin"]);
+
+/// Every built-in check
+pub const CATALOG: &[Detector] = &[
+    // Native detectors, under Slither's check names
+    native(
+        "reentrancy-eth",
+        "High",
+        Reentrancy,
+        r"^Reentrancy \(Ether\): ",
+        "State is written after an external call that sends ether, so the callee can re-enter before the update.",
+        "(bool ok, ) = msg.sender.call{value: amount}(\"\");\nbalances[msg.sender] = 0;",
+    ),
+    native(
+        "reentrancy-no-eth",
+        "Medium",
+        Reentrancy,
+        r"^Reentrancy: ",
+        "State is written after an external call, so the callee can re-enter before the update.",
+        "token.transferFrom(msg.sender, address(this), amount);\ndeposits[msg.sender] += amount;",
+    ),
+    native(
+        "arbitrary-send-eth",
+        "High",
+        AccessControl,
+        r"^Arbitrary Ether Send: ",
+        "An unprotected entry point sends ether to an address the caller chooses.",
+        "function pay(address payable to, uint256 amount) external {\n    to.transfer(amount);\n}",
+    ),
+    native(
+        "controlled-delegatecall",
+        "High",
+        LowLevelCalls,
+        r"^Controlled Delegatecall: ",
+        "An unprotected entry point delegatecalls an address the caller chooses.",
+        "function run(address target, bytes calldata data) external {\n    target.delegatecall(data);\n}",
+    ),
+    native(
+        "suicidal",
+        "High",
+        AccessControl,
+        r"^Unprotected Selfdestruct: ",
+        "Anyone can call a function that selfdestructs the contract.",
+        "function kill() external {\n    selfdestruct(payable(msg.sender));\n}",
+    ),
+    native(
+        "unchecked-lowlevel",
+        "Medium",
+        UnhandledExceptions,
+        r"^Unchecked (?:call|delegatecall|staticcall) Result: ",
+        "The success flag of a low-level call is ignored.",
+        "target.call(data);",
+    ),
+    native(
+        "unchecked-send",
+        "Medium",
+        UnhandledExceptions,
+        r"^Unchecked send Result: ",
+        "The result of send is ignored, so a failed ether transfer goes unnoticed.",
+        "payable(to).send(amount);",
+    ),
+    native(
+        "unchecked-transfer",
+        "High",
+        UnhandledExceptions,
+        r"^Unchecked (?:transfer|transferFrom) Result: ",
+        "The boolean returned by an ERC20 transfer is ignored.",
+        "token.transfer(to, amount);",
+    ),
+    native(
+        "uninitialized-storage",
+        "High",
+        InputValidation,
+        r"^Uninitialized Storage Pointer: ",
+        "A local storage variable is declared without being assigned, so it points at slot 0.",
+        "User storage user;\nuser.balance = 1;",
+    ),
+    native(
+        "tx-origin",
+        "Medium",
+        AccessControl,
+        r"^tx\.origin Authorization: ",
+        "Authorization checks tx.origin, which a phishing contract passes on the owner's behalf.",
+        "require(tx.origin == owner);",
+    ),
+    native(
+        "weak-prng",
+        "High",
+        Cryptography,
+        r"^Weak Randomness: ",
+        "Block data taken modulo a number is used as randomness that miners and callers can predict.",
+        "uint256 winner = uint256(keccak256(abi.encode(block.timestamp))) % players.length;",
+    ),
+    native(
+        "timestamp",
+        "Low",
+        TimestampDependence,
+        r"^Block Timestamp Comparison: ",
+        "A comparison depends on block.timestamp, which the block producer can shift slightly.",
+        "require(block.timestamp >= unlockTime);",
+    ),
+    native(
+        "divide-before-multiply",
+        "Medium",
+        IntegerOverflow,
+        r"^Division Before Multiplication: ",
+        "A division's result is multiplied, losing the precision the division truncated.",
+        "uint256 fee = amount / 100 * rate;",
+    ),
+    native(
+        "incorrect-equality",
+        "Medium",
+        DenialOfService,
+        r"^Strict Balance Equality: ",
+        "A balance is compared with ==, which anyone can break by sending ether or tokens.",
+        "require(address(this).balance == expected);",
+    ),
+    native(
+        "boolean-equal",
+        "Info",
+        CodeQuality,
+        r"^Comparison With Boolean Constant: ",
+        "A boolean is compared with true or false.",
+        "if (paused == true) revert();",
+    ),
+    native(
+        "assembly",
+        "Info",
+        LowLevelCalls,
+        r"^Inline Assembly: ",
+        "Inline assembly bypasses the compiler's safety checks.",
+        "assembly { sstore(0, caller()) }",
+    ),
+    native(
+        "low-level-calls",
+        "Info",
+        LowLevelCalls,
+        r"^Low-Level Call: ",
+        "A low-level call skips the existence and return-data checks of a typed call.",
+        "(bool ok, bytes memory data) = target.call(payload);",
+    ),
+    native(
+        "msg-value-loop",
+        "High",
+        InputValidation,
+        r"^msg\.value in Loop: ",
+        "msg.value is read inside a loop, so the same ether is counted once per iteration.",
+        "for (uint256 i = 0; i < users.length; i++) {\n    balances[users[i]] += msg.value;\n}",
+    ),
+    native(
+        "calls-loop",
+        "Low",
+        DenialOfService,
+        r"^External Call in Loop: ",
+        "An external call is made inside a loop, so one reverting callee blocks the whole loop.",
+        "for (uint256 i = 0; i < payees.length; i++) {\n    payees[i].transfer(share);\n}",
+    ),
+    native(
+        "locked-ether",
+        "Medium",
+        DenialOfService,
+        r"^Contract Locking Ether: ",
+        "A contract can receive ether but has no function that sends it.",
+        "contract Vault {\n    receive() external payable {}\n}",
+    ),
+    native(
+        "shadowing-state",
+        "High",
+        CodeQuality,
+        r"^State Variable Shadowing: ",
+        "A derived contract redeclares a state variable of its base, so the two silently diverge.",
+        "contract Base { address owner; }\ncontract Vault is Base { address owner; }",
+    ),
+    native(
+        "shadowing-builtin",
+        "Low",
+        CodeQuality,
+        r"^Built-in Symbol Shadowing: ",
+        "A variable or parameter is named after a built-in symbol.",
+        "function f(uint256 now) external {}",
+    ),
+    native(
+        "shadowing-local",
+        "Low",
+        CodeQuality,
+        r"^Local Variable Shadowing: ",
+        "A local variable or parameter shadows a state variable.",
+        "address owner;\nfunction f() external { uint256 owner = 1; }",
+    ),
+    native(
+        "solc-version",
+        "Info",
+        CodeQuality,
+        r"^Floating Pragma$",
+        "The pragma allows compiler versions other than the tested one.",
+        "pragma solidity ^0.8.0;",
+    ),
+    // Selector and inheritance checks across the run
+    selectors(
+        "selector-collision",
+        "High",
+        Other,
+        r"^Function Selector Collision on ",
+        "Two function signatures hash to the same 4-byte selector.",
+        "function collate_propagate_storage(bytes16) external {}\nfunction burn(uint256) external {}",
+    ),
+    selectors(
+        "proxy-selector-clash",
+        "High",
+        Other,
+        r"^Proxy Selector Clash: ",
+        "A proxy exposes a selector its implementation also exposes, so calls never reach the implementation.",
+        "contract Proxy { function upgradeTo(address) external {} }\ncontract Impl { function upgradeTo(address) external {} }",
+    ),
+    selectors(
+        "facet-selector-overlap",
+        "High",
+        Other,
+        r"^Selector \S+ Implemented by Multiple Facets$",
+        "Several facets of a diamond implement the same selector.",
+        "contract FacetA { function owner() external view returns (address) {} }\ncontract FacetB { function owner() external view returns (address) {} }",
+    ),
+    selectors(
+        "shadowed-function",
+        "Medium",
+        CodeQuality,
+        r"^Shadowed Function ",
+        "A derived contract redefines a base function without `override`.",
+        "contract Base { function fee() public pure returns (uint256) { return 1; } }",
+    ),
+    selectors(
+        "shadowed-state-variable",
+        "Medium",
+        CodeQuality,
+        r"^Shadowed State Variable ",
+        "A contract redeclares a state variable it inherits.",
+        "contract Child is Parent { uint256 totalSupply; }",
+    ),
+    selectors(
+        "interface-mutability-mismatch",
+        "Medium",
+        Other,
+        r"^State-Changing Implementation of ",
+        "An implementation changes state where its interface declares the function view or pure.",
+        "interface IOracle { function price() external view returns (uint256); }",
+    ),
+    selectors(
+        "interface-return-mismatch",
+        "Medium",
+        Other,
+        r"^Return Type Mismatch in ",
+        "An implementation returns other types than its interface declares.",
+        "interface IVault { function shares() external returns (uint256); }",
+    ),
+    selectors(
+        "non-standard-erc20",
+        "Medium",
+        Other,
+        r"^Non-Standard ERC20 ",
+        "An ERC20 function does not return the bool the standard requires.",
+        "function transfer(address to, uint256 amount) external { /* no return */ }",
+    ),
+    // Dead code across the run
+    dead_code(
+        "unreachable-function",
+        r"^Unreachable Function ",
+        "An internal or private function that no entry point reaches.",
+        "function _legacyFee() internal pure returns (uint256) { return 5; }",
+    ),
+    dead_code(
+        "unused-state-variable",
+        r"^Unused State Variable ",
+        "A state variable that no function reads or writes.",
+        "uint256 private oldRate;",
+    ),
+    dead_code(
+        "constant-condition",
+        r"^Constant Condition ",
+        "A branch guarded by a constant that makes it always or never taken.",
+        "bool constant DEBUG = false;\nif (DEBUG) { emit Debug(x); }",
+    ),
+    // Constructor and setter parameters, followed through modifiers and internal calls
+    Detector {
+        runs: "on every EVM run on which Slither does not complete, since Slither has the same check",
+        ..validation(
+            "missing-zero-check",
+            "Low",
+            r"^Missing Zero-Address Check: ",
+            "An address parameter is stored or sent ether without a check against the zero address.",
+            "function setTreasury(address treasury_) external onlyOwner {\n    treasury = treasury_;\n}",
+        )
+    },
+    validation(
+        "missing-bounds-check",
+        "Medium",
+        r"^Missing Bounds Check: ",
+        "A fee, rate or ratio parameter is stored without being compared to a bound.",
+        "function setFee(uint256 feeBps) external onlyOwner {\n    fee = feeBps;\n}",
+    ),
+    validation(
+        "array-length-mismatch",
+        "Low",
+        r"^Array Length Mismatch: ",
+        "Array parameters are indexed side by side without checking that their lengths are equal.",
+        "function airdrop(address[] calldata to, uint256[] calldata amounts) external {\n    for (uint256 i = 0; i < to.length; i++) token.transfer(to[i], amounts[i]);\n}",
+    ),
+    // Requires and reverts
+    error_handling(
+        "missing-error-message",
+        "Low",
+        CodeQuality,
+        r"^Missing Error Message in ",
+        "A require or revert gives no reason, so callers cannot tell which check failed.",
+        "require(msg.sender == owner);",
+    ),
+    error_handling(
+        "long-revert-string",
+        "Info",
+        GasOptimization,
+        r"^Long Revert String in ",
+        "A revert string is longer than 32 bytes and takes an extra word of bytecode and revert data.",
+        "require(amount <= balance, \"Withdrawal amount exceeds the available balance\");",
+    ),
+    error_handling(
+        "use-custom-errors",
+        "Info",
+        GasOptimization,
+        r"^Revert Strings Instead of Custom Errors in ",
+        "A contract reverts with strings where its version pragma allows Solidity 0.8.4 custom errors.",
+        "pragma solidity ^0.8.20;\nrequire(amount > 0, \"zero amount\");",
+    ),
+    // Gas optimization
+    gas(
+        "storage-in-loop",
+        r"^Storage Access in Loop: ",
+        "A loop reads or writes a state variable, or an array's length, on every iteration instead of a local copy.",
+        "for (uint256 i = 0; i < holders.length; i++) totalPaid += amounts[i];",
+    ),
+    gas(
+        "repeated-storage-read",
+        r"^Repeated Storage Reads: ",
+        "A function reads the same state variable from storage more than once without writing it.",
+        "require(amount <= limit);\nemit Withdrawn(amount, limit);",
+    ),
+    gas(
+        "unpacked-struct",
+        r"^Unpacked Struct: ",
+        "A struct's field order leaves storage slots partly empty, so it takes more slots than it needs.",
+        "struct Position { bool open; uint256 size; address owner; }",
+    ),
+    gas(
+        "memory-parameter",
+        r"^Memory Parameter Could Be Calldata: ",
+        "An external function copies an array, string or struct argument to memory although it never modifies it.",
+        "function batch(address[] memory accounts) external {",
+    ),
+    gas(
+        "constant-candidate",
+        r"^Constant or Immutable Candidates in ",
+        "State variables are never changed after deployment and could be constant or immutable.",
+        "uint256 public fee = 30;\naddress public treasury; // only set in the constructor",
+    ),
+    // Vyper sources
+    vyper(
+        "vyper-broken-reentrancy-lock",
+        "Critical",
+        Reentrancy,
+        r"^Broken Vyper Reentrancy Lock: ",
+        "Functions rely on @nonreentrant and the version pragma allows Vyper 0.2.15, 0.2.16 or 0.3.0, whose locks did not work.",
+        "# @version ^0.2.15\n@external\n@nonreentrant(\"lock\")\ndef remove_liquidity(amount: uint256):",
+    ),
+    vyper(
+        "vyper-reentrancy",
+        "High",
+        Reentrancy,
+        r"^Vyper Reentrancy: ",
+        "An external function without a reentrancy lock writes storage after an external call.",
+        "@external\ndef withdraw():\n    raw_call(msg.sender, b\"\", value=self.balances[msg.sender])\n    self.balances[msg.sender] = 0",
+    ),
+    vyper(
+        "vyper-unchecked-raw-call",
+        "Medium",
+        UnhandledExceptions,
+        r"^Unchecked raw_call: ",
+        "raw_call is told not to revert on failure and its success flag is dropped.",
+        "raw_call(to, b\"\", value=amount, revert_on_failure=False)",
+    ),
+    vyper(
+        "vyper-tx-origin",
+        "High",
+        AccessControl,
+        r"^Vyper tx\.origin Authorization: ",
+        "A function reads tx.origin, usually to authorize the caller.",
+        "assert tx.origin == self.owner",
+    ),
+    vyper(
+        "vyper-unprotected-selfdestruct",
+        "High",
+        AccessControl,
+        r"^Unprotected Vyper selfdestruct: ",
+        "An external function calls selfdestruct without checking msg.sender.",
+        "@external\ndef kill():\n    selfdestruct(msg.sender)",
+    ),
+    vyper(
+        "vyper-unsafe-math",
+        "Low",
+        IntegerOverflow,
+        r"^Unchecked Vyper Arithmetic: ",
+        "A function uses unsafe_add, unsafe_sub, unsafe_mul or unsafe_div, which wrap instead of reverting.",
+        "self.total = unsafe_add(self.total, amount)",
+    ),
+    vyper(
+        "vyper-floating-pragma",
+        "Info",
+        CodeQuality,
+        r"^Floating Vyper Version: ",
+        "The version pragma allows more than one compiler release.",
+        "# @version ^0.3.7",
+    ),
+    vyper(
+        "vyper-missing-pragma",
+        "Low",
+        CodeQuality,
+        r"^Missing Vyper Version Pragma$",
+        "The source has no version pragma.",
+        "",
+    ),
+    // EIP-2535 diamonds
+    diamond(
+        "diamond-unverified-facet",
+        "Medium",
+        Other,
+        r"^Facet .+ Has No Verified Source$",
+        "A facet the diamond routes to has no verified source.",
+        "",
+    ),
+    diamond(
+        "diamond-stale-selectors",
+        "Medium",
+        Other,
+        r"^Selectors Routed to .+ Missing From Its Source$",
+        "The diamond routes selectors to a facet whose source does not define them.",
+        "",
+    ),
+    diamond(
+        "diamond-unrouted-functions",
+        "Info",
+        CodeQuality,
+        r"^Facet .+ Has Functions the Diamond Does Not Route$",
+        "A facet defines external functions the diamond does not route to it.",
+        "",
+    ),
+    diamond(
+        "diamond-missing-loupe",
+        "Low",
+        CodeQuality,
+        r"^Missing Diamond Loupe Functions$",
+        "No facet implements the loupe functions that tooling uses to inspect the diamond.",
+        "",
+    ),
+    diamond(
+        "diamond-missing-cut",
+        "Info",
+        Other,
+        r"^Diamond Has No diamondCut Function$",
+        "No facet exposes the standard diamondCut.",
+        "",
+    ),
+    diamond(
+        "diamond-loupe-history-mismatch",
+        "Medium",
+        Other,
+        r"^Loupe Disagrees With DiamondCut History$",
+        "The loupe reports other routing than the DiamondCut events add up to.",
+        "",
+    ),
+    diamond(
+        "diamond-appstorage-layout",
+        "High",
+        Other,
+        r"^Inconsistent AppStorage Layout$",
+        "Facets declare AppStorage with different field layouts.",
+        "struct AppStorage { uint256 fee; address owner; }\nstruct AppStorage { address owner; uint256 fee; }",
+    ),
+    diamond(
+        "diamond-storage-collision",
+        "Critical",
+        Other,
+        r"^Diamond Storage Position Collision at ",
+        "Two DiamondStorage structs use the same storage position.",
+        "bytes32 constant POSITION = keccak256(\"diamond.storage\"); // in two libraries",
+    ),
+    diamond(
+        "diamond-facet-state",
+        "High",
+        Other,
+        r"^Facet .+ Declares Its Own State Variables$",
+        "A facet declares state variables, which overlap the diamond's storage.",
+        "contract FeeFacet { uint256 fee; }",
+    ),
+    diamond(
+        "diamond-facet-selfdestruct",
+        "High",
+        AccessControl,
+        r"^selfdestruct in Facet ",
+        "Facet code contains selfdestruct, which would destroy the diamond.",
+        "",
+    ),
+    diamond(
+        "diamond-facet-delegatecall",
+        "Medium",
+        LowLevelCalls,
+        r"^delegatecall in Facet ",
+        "Facet code delegatecalls, running other code on the diamond's storage.",
+        "",
+    ),
+    diamond(
+        "diamond-unprotected-cut",
+        "Critical",
+        AccessControl,
+        r"^Unprotected diamondCut in ",
+        "diamondCut has no owner or role check, so anyone can replace the diamond's code.",
+        "function diamondCut(FacetCut[] calldata cuts, address init, bytes calldata data) external {\n    LibDiamond.diamondCut(cuts, init, data);\n}",
+    ),
+    // Unchecked arithmetic executed with boundary inputs
+    arithmetic(
+        "arithmetic-reachable-overflow",
+        "High",
+        IntegerOverflow,
+        r"^Reachable Arithmetic Overflow in ",
+        "Boundary inputs make unchecked (or pre-0.8) arithmetic wrap in an in-memory EVM; Medium for read-only functions.",
+        "function burn(uint256 amount) external {\n    unchecked { balances[msg.sender] -= amount; }\n}",
+    ),
+    // Proxies and upgradeable implementations
+    proxy(
+        "proxy-uninitialized-implementation",
+        "High",
+        AccessControl,
+        r"^Uninitialized Implementation ",
+        "An upgradeable implementation's constructor does not disable its initializers, so anyone can initialize the implementation itself; Medium unless it is UUPS.",
+        "contract Vault is UUPSUpgradeable, OwnableUpgradeable {\n    function initialize() external initializer { __Ownable_init(); }\n}",
+    ),
+    proxy(
+        "proxy-storage-collision",
+        "High",
+        Other,
+        r"^Proxy .+ Declares State Variables$",
+        "A proxy declares state variables, which overlap the implementation's storage from slot 0.",
+        "contract VaultProxy is Proxy {\n    address public implementation;\n}",
+    ),
+    proxy(
+        "proxy-unprotected-upgrade",
+        "Critical",
+        AccessControl,
+        r"^Unprotected Upgrade Function ",
+        "An upgrade function or UUPS `_authorizeUpgrade` override does not check the caller, so anyone can replace the code.",
+        "function _authorizeUpgrade(address) internal override {}",
+    ),
+    // Constructor arguments of deployed contracts
+    deployment(
+        "deployment-zero-address-role",
+        "High",
+        AccessControl,
+        r"^Zero Address for Critical Role ",
+        "A privileged role was deployed as the zero address.",
+        "constructor(address admin) // deployed with admin = 0x0",
+    ),
+    deployment(
+        "deployment-zero-address",
+        "Medium",
+        AccessControl,
+        r"^Zero Address Constructor Argument ",
+        "An address argument was deployed as the zero address.",
+        "constructor(address treasury) // deployed with treasury = 0x0",
+    ),
+    deployment(
+        "deployment-fee",
+        "Medium",
+        InputValidation,
+        r"^Suspicious Fee Parameter ",
+        "A fee or rate was deployed unusually high, or above 100%; High when above 100%.",
+        "constructor(uint256 feeBps) // deployed with feeBps = 50000",
+    ),
+    deployment(
+        "deployment-decimals-mismatch",
+        "High",
+        InputValidation,
+        r"^Mismatched Token Decimals ",
+        "Deployed decimals disagree with the decimals the source hard-codes.",
+        "constructor(uint8 decimals) // deployed as 6, source uses 1e18",
+    ),
+    deployment(
+        "deployment-unusual-decimals",
+        "Medium",
+        InputValidation,
+        r"^Unusual Token Decimals ",
+        "Decimals were deployed above 18.",
+        "constructor(uint8 decimals) // deployed as 24",
+    ),
+    // Pre-commit hook pattern checks
+    evm_plugin(
+        "hook-tx-origin",
+        "High",
+        AccessControl,
+        r"^Use of tx\.origin$",
+        "tx.origin is used for authorization.",
+        "require(tx.origin == owner);",
+    ),
+    evm_plugin(
+        "hook-selfdestruct",
+        "Medium",
+        CodeQuality,
+        r"^Use of selfdestruct/suicide$",
+        "The contract uses selfdestruct.",
+        "selfdestruct(payable(owner));",
+    ),
+    evm_plugin(
+        "hook-unchecked-call",
+        "High",
+        UnhandledExceptions,
+        r"^Unchecked External Call$",
+        "An external call's return value is not checked.",
+        "target.call(data);",
+    ),
+    evm_plugin(
+        "hook-gas-limit-loop",
+        "Medium",
+        DenialOfService,
+        r"^Potential Gas Limit Issue in ",
+        "A function loops in a way that may exceed the block gas limit.",
+        "for (uint256 i = 0; i < holders.length; i++) { ... }",
+    ),
+    // Rust syntax-tree checks for Ink! and Solana
+    rust(
+        "rust-panic",
+        "High",
+        DenialOfService,
+        r"^Panic in (?:message|instruction) ",
+        "An entry point can panic through unwrap, expect, indexing or a panicking macro.",
+        "let amount = self.balances.get(caller).unwrap();",
+    ),
+    rust(
+        "rust-unchecked-arithmetic",
+        "Medium",
+        IntegerOverflow,
+        r"^Unchecked arithmetic in (?:message|instruction) ",
+        "An entry point uses +, - or * on non-constant values, which wrap in release builds.",
+        "self.total = self.total + amount;",
+    ),
+    rust(
+        "rust-unbounded-storage",
+        "Medium",
+        DenialOfService,
+        r"^Unbounded storage growth of ",
+        "A storage vector is pushed to without a length check.",
+        "self.holders.push(caller);",
+    ),
+    rust(
+        "rust-unsafe",
+        "Medium",
+        CodeQuality,
+        r"^Unsafe Rust code$",
+        "An unsafe block or function bypasses memory safety checks.",
+        "unsafe { *ptr = value; }",
+    ),
+    // Ink! plugin checks
+    plugin(INK.0, INK.1, "ink-missing-contract-attribute", "High", CodeQuality, r"^Missing Ink Contract Attribute$", "The module lacks #[ink::contract]."),
+    plugin(INK.0, INK.1, "ink-missing-storage", "High", CodeQuality, r"^Missing Storage Struct$", "No struct is marked #[ink(storage)]."),
+    plugin(INK.0, INK.1, "ink-missing-constructor", "Medium", CodeQuality, r"^Missing Constructor$", "The contract has no #[ink(constructor)]."),
+    plugin(INK.0, INK.1, "ink-immutable-storage-access", "Medium", CodeQuality, r"^Immutable Storage Access$", "A message modifies storage through &self."),
+    plugin(INK.0, INK.1, "ink-missing-mapping-import", "Medium", CodeQuality, r"^Missing Mapping Import$", "Mapping is used without importing it."),
+    plugin(INK.0, INK.1, "ink-message-access-control", "High", AccessControl, r"^Message Without Access Control$", "A public message does not check the caller."),
+    plugin(INK.0, INK.1, "ink-payable-value-check", "Medium", CodeQuality, r"^Payable Message Without Value Check$", "A payable message does not check the transferred value."),
+    plugin(INK.0, INK.1, "ink-message-error-handling", "Low", CodeQuality, r"^Message Without Error Handling$", "A message does not return Result."),
+    plugin(INK.0, INK.1, "ink-event-indexed-fields", "Low", CodeQuality, r"^Event Without Indexed Fields$", "An event has no #[ink(topic)] fields."),
+    plugin(INK.0, INK.1, "ink-event-not-emitted", "Info", CodeQuality, r"^Event Defined But Not Emitted$", "An event is defined but never emitted."),
+    plugin(INK.0, INK.1, "ink-custom-errors", "Info", CodeQuality, r"^Consider Custom Error Types$", "The contract defines no error enum."),
+    plugin(INK.0, INK.1, "ink-missing-tests", "Low", CodeQuality, r"^Missing Unit Tests$", "The contract has no #[ink::test] tests."),
+    plugin(INK.0, INK.1, "ink-integer-overflow", "Medium", IntegerOverflow, r"^Potential Integer Overflow$", "Arithmetic does not use checked operations."),
+    // Move plugin checks
+    plugin(MOVE.0, MOVE.1, "move-unchecked-resource-access", "High", AccessControl, r"^Unchecked Resource Access$", "A resource is moved out without checking it exists."),
+    plugin(MOVE.0, MOVE.1, "move-resource-leak", "Medium", CodeQuality, r"^Potential Resource Leak$", "Resources are created but never consumed."),
+    plugin(MOVE.0, MOVE.1, "move-capability-management", "High", AccessControl, r"^Improper Capability Management$", "A capability is used without signer validation."),
+    plugin(MOVE.0, MOVE.1, "move-magic-abort", "Low", CodeQuality, r"^Magic Number in Abort$", "An abort uses a bare number instead of a named error code."),
+    plugin(MOVE.0, MOVE.1, "move-mutable-global", "Medium", Reentrancy, r"^Mutable Global Storage Access$", "borrow_global_mut is used where reentrancy-like issues can arise."),
+    plugin(MOVE.0, MOVE.1, "move-missing-acquires", "High", CodeQuality, r"^Missing Acquires Declaration$", "A function uses global storage without declaring acquires."),
+    plugin(MOVE.0, MOVE.1, "move-missing-module", "Medium", CodeQuality, r"^Missing Module Declaration$", "The code is not organized in a module."),
+    plugin(MOVE.0, MOVE.1, "move-permissive-visibility", "Low", AccessControl, r"^Overly Permissive Function Visibility$", "Functions are public without friend restrictions."),
+    // Cairo plugin checks
+    plugin(CAIRO.0, CAIRO.1, "cairo-felt-overflow", "Medium", IntegerOverflow, r"^Potential Felt Overflow$", "Felt arithmetic without bounds checks."),
+    plugin(CAIRO.0, CAIRO.1, "cairo-felt-conversion", "Low", CodeQuality, r"^Unchecked Felt Conversion$", "Felt conversions are not checked."),
+    plugin(CAIRO.0, CAIRO.1, "cairo-uninitialized-storage", "Medium", CodeQuality, r"^Uninitialized Storage Variables$", "Storage variables are not initialized."),
+    plugin(CAIRO.0, CAIRO.1, "cairo-unchecked-storage-read", "Low", CodeQuality, r"^Unchecked Storage Access$", "Storage reads are not validated."),
+    plugin(CAIRO.0, CAIRO.1, "cairo-access-control", "High", AccessControl, r"^External Function Without Access Control$", "An external function does not check the caller."),
+    plugin(CAIRO.0, CAIRO.1, "cairo-reentrancy", "High", Reentrancy, r"^Potential Reentrancy$", "External calls are combined with storage writes."),
+    plugin(CAIRO.0, CAIRO.1, "cairo-excessive-asserts", "Low", CodeQuality, r"^Excessive Assert Usage$", "Many assert statements make execution expensive."),
+    plugin(CAIRO.0, CAIRO.1, "cairo-input-validation", "Medium", InputValidation, r"^Missing Input Validation$", "External functions do not validate their inputs."),
+    plugin(CAIRO.0, CAIRO.1, "cairo-namespace", "Info", CodeQuality, r"^Namespace Best Practice$", "The code does not use namespaces."),
+    plugin(CAIRO.0, CAIRO.1, "cairo-memory-management", "Info", CodeQuality, r"^Missing Memory Management$", "Memory allocation functions may be needed."),
+];
+
+/// Detectors that run for `target`
+pub fn for_target(target: &str) -> impl Iterator<Item = &'static Detector> + '_ {
+    CATALOG.iter().filter(move |detector| detector.targets.contains(&target))
+}
+
+/// Detector with id `id`
+pub fn find(id: &str) -> Option<&'static Detector> {
+    CATALOG.iter().find(|detector| detector.id == id)
+}
+
+/// Detector that reported `vuln`, `None` for external tools
+pub fn detector_of(vuln: &Vulnerability) -> Option<&'static Detector> {
+    CATALOG.iter().find(|detector| detector.matches(vuln))
+}
+
+/// Stable id of the check that reported `vuln`: its detector id, or `tool/check` for external tools
+pub fn check_id(vuln: &Vulnerability) -> String {
+    if let Some(detector) = detector_of(vuln) {
+        return detector.id.to_string();
+    }
+    // External tools title their findings `Tool: check`
+    let check = vuln.title.split_once(": ").map_or(vuln.title.as_str(), |(_, check)| check);
+    format!("{}/{}", slug(&vuln.tool), slug(check))
+}
+
+fn slug(text: &str) -> String {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Drop findings of detectors disabled in `config`
+pub fn retain_enabled(vulnerabilities: &mut Vec<Vulnerability>, config: &Config) {
+    if config.analysis.disabled_detectors.is_empty() {
+        return;
+    }
+    vulnerabilities.retain(|vuln| detector_of(vuln).is_none_or(|detector| detector.enabled(config)));
+}
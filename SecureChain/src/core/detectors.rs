@@ -0,0 +1,895 @@
+//! Native detector pack
+//!
+//! Without Slither a run used to report almost nothing, since every other
+//! source of findings is optional too. These detectors reimplement the most
+//! valuable Slither checks on the internal parse and call graph, under the
+//! same check names, so a baseline analysis needs no Python environment.
+//! They work on source text and over-approximate where Slither would use
+//! data flow, so their confidence is lower than Slither's.
+
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+use crate::core::callgraph::CallGraph;
+use crate::core::exploitability;
+use crate::core::parser::{matching_brace, matching_paren, ContractUnit, ParsedContract, UnitFunction};
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+
+pub const TOOL_NAME: &str = "Native Detectors";
+
+/// Modifiers that prevent reentrancy
+pub const REENTRANCY_GUARDS: &[&str] = &["nonReentrant", "noReentrant", "noReentrancy", "lock", "reentrancyGuard"];
+
+const BUILTINS: &[&str] = &[
+    "now", "assert", "require", "revert", "blockhash", "gasleft", "keccak256", "sha256", "sha3", "ripemd160",
+    "ecrecover", "addmod", "mulmod", "selfdestruct", "suicide", "msg", "block", "tx", "abi",
+];
+
+/// Run every detector on one source file
+pub fn run_detectors(contract: &ParsedContract) -> Vec<Vulnerability> {
+    let contracts = std::slice::from_ref(contract);
+    let graph = CallGraph::build(contracts);
+    let context = Context::new(contract);
+
+    let mut findings = Vec::new();
+    for node in &graph.nodes {
+        let function = node.function;
+        let body = match function.body.as_deref() {
+            Some(body) => body,
+            None => continue,
+        };
+        let unit = match context.unit(node.unit) {
+            Some(unit) => unit,
+            None => continue,
+        };
+        let scope = FunctionScope { context: &context, unit, function, body };
+
+        findings.extend(scope.reentrancy(&graph));
+        findings.extend(scope.arbitrary_send());
+        findings.extend(scope.unchecked_results());
+        findings.extend(scope.statement_patterns());
+        findings.extend(scope.loops());
+        findings.extend(scope.shadowing());
+    }
+
+    findings.extend(context.unit_checks());
+    findings
+}
+
+/// Source file under analysis, with what detectors look up repeatedly
+struct Context<'a> {
+    contract: &'a ParsedContract,
+    source: &'a str,
+    /// State variable names of each unit, including inherited ones
+    state_variables: HashMap<&'a str, Vec<&'a str>>,
+    /// Contract and interface names, for spotting external calls
+    contract_types: HashSet<&'a str>,
+}
+
+impl<'a> Context<'a> {
+    fn new(contract: &'a ParsedContract) -> Self {
+        let state_variables = contract
+            .units
+            .iter()
+            .map(|unit| {
+                let names = lineage(contract, unit)
+                    .into_iter()
+                    .flat_map(|base| base.state_variables.iter())
+                    .filter(|variable| !variable.is_constant && !variable.is_immutable)
+                    .map(|variable| variable.name.as_str())
+                    .collect();
+                (unit.name.as_str(), names)
+            })
+            .collect();
+        let contract_types = contract
+            .units
+            .iter()
+            .filter(|unit| unit.kind != "library")
+            .map(|unit| unit.name.as_str())
+            .collect();
+
+        Self {
+            contract,
+            source: contract.code(),
+            state_variables,
+            contract_types,
+        }
+    }
+
+    fn unit(&self, name: &str) -> Option<&'a ContractUnit> {
+        self.contract.units.iter().find(|unit| unit.name == name)
+    }
+
+    /// Whether a type names another contract, so calls on it leave this one
+    fn is_contract_type(&self, type_name: &str) -> bool {
+        let bytes = type_name.as_bytes();
+        self.contract_types.contains(type_name)
+            // Interfaces imported from elsewhere, e.g. IERC20
+            || (bytes.len() > 1 && bytes[0] == b'I' && bytes[1].is_ascii_uppercase())
+    }
+
+    /// Checks over whole units: locked ether, state shadowing and the pragma
+    fn unit_checks(&self) -> Vec<Vulnerability> {
+        let mut findings = Vec::new();
+        let payable = Regex::new(r"\b(?:receive|fallback)\s*\([^)]*\)\s*external\s+payable|\bconstructor\s*\([^)]*\)[^{]*\bpayable\b")
+            .expect("valid payable pattern");
+        let send = Regex::new(r"\.\s*(?:transfer|send)\s*\(|\bvalue\s*:|\bselfdestruct\s*\(|\bsuicide\s*\(|\bdelegatecall\b|\bcall\s*\.value\s*\(")
+            .expect("valid send pattern");
+
+        for unit in self.contract.units.iter().filter(|unit| unit.kind == "contract") {
+            let lineage = lineage(self.contract, unit);
+
+            // Bases outside this file might send the ether
+            if unit.inheritance.iter().all(|base| self.unit(base).is_some()) {
+                let spans: Vec<&str> = lineage.iter().filter_map(|base| unit_span(self.source, base)).collect();
+                let receives = unit.functions.iter().any(|function| function.state_mutability == "payable")
+                    || spans.iter().any(|span| payable.is_match(span));
+                let sends = spans.iter().any(|span| send.is_match(span));
+                if receives && !sends {
+                    findings.push(finding(
+                        "locked-ether",
+                        &self.contract.name,
+                        unit.line_number,
+                        "Medium",
+                        0.6,
+                        VulnerabilityCategory::DenialOfService,
+                        "CWE-400",
+                        format!("Contract Locking Ether: {}", unit.name),
+                        format!("{} can receive ether but has no function that sends it, so ether sent to it is locked forever.", unit.name),
+                        "Remove the payable attribute or add a function that withdraws the ether.",
+                    ));
+                }
+            }
+
+            for variable in &unit.state_variables {
+                if variable.name.starts_with("__") {
+                    continue;
+                }
+                let shadowed = lineage[1..]
+                    .iter()
+                    .find(|base| base.state_variables.iter().any(|base_variable| base_variable.name == variable.name));
+                if let Some(base) = shadowed {
+                    findings.push(finding(
+                        "shadowing-state",
+                        &self.contract.name,
+                        variable.line_number,
+                        "High",
+                        0.8,
+                        VulnerabilityCategory::CodeQuality,
+                        "CWE-710",
+                        format!("State Variable Shadowing: {}.{}", unit.name, variable.name),
+                        format!(
+                            "`{}` in {} shadows the state variable of the same name in {}. Code in {} keeps using its own copy, so the two silently diverge.",
+                            variable.name, unit.name, base.name, base.name
+                        ),
+                        "Remove the variable from the derived contract and use the inherited one.",
+                    ));
+                }
+            }
+        }
+
+        let floating = Regex::new(r"pragma\s+solidity\s+([^;]*[\^>~][^;]*);").expect("valid pragma pattern");
+        if let Some(captures) = floating.captures(self.source) {
+            let line = line_at(self.source, captures.get(0).unwrap().start());
+            findings.push(finding(
+                "solc-version",
+                &self.contract.name,
+                line,
+                "Info",
+                0.9,
+                VulnerabilityCategory::CodeQuality,
+                "CWE-1104",
+                "Floating Pragma".to_string(),
+                format!("`pragma solidity {}` allows compilation with compiler versions other than the tested one.", captures[1].trim()),
+                "Pin the compiler version, e.g. `pragma solidity 0.8.24;`.",
+            ));
+        }
+
+        findings
+    }
+}
+
+/// One function, with its unit and the file it is in
+struct FunctionScope<'a> {
+    context: &'a Context<'a>,
+    unit: &'a ContractUnit,
+    function: &'a UnitFunction,
+    body: &'a str,
+}
+
+impl<'a> FunctionScope<'a> {
+    fn line(&self, offset: usize) -> usize {
+        self.function.body_line_number + self.body[..offset].matches('\n').count()
+    }
+
+    fn label(&self) -> String {
+        format!("{}.{}", self.unit.name, self.function.name)
+    }
+
+    fn is_entry_point(&self) -> bool {
+        matches!(self.function.visibility.as_str(), "external" | "public")
+    }
+
+    fn is_protected(&self) -> bool {
+        !exploitability::caller_guards(self.function).is_empty()
+    }
+
+    fn state_variables(&self) -> &[&'a str] {
+        self.context
+            .state_variables
+            .get(self.unit.name.as_str())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Type of a parameter or state variable visible in the function
+    fn type_of(&self, name: &str) -> Option<&str> {
+        self.function
+            .parameter_names
+            .iter()
+            .position(|parameter| parameter == name)
+            .and_then(|index| self.function.parameter_types.get(index))
+            .map(String::as_str)
+            .or_else(|| {
+                lineage(self.context.contract, self.unit)
+                    .into_iter()
+                    .flat_map(|unit| unit.state_variables.iter())
+                    .find(|variable| variable.name == name)
+                    .map(|variable| variable.type_name.as_str())
+            })
+    }
+
+    fn is_parameter(&self, name: &str) -> bool {
+        self.function.parameter_names.iter().any(|parameter| parameter == name)
+    }
+
+    /// External calls in the body: offset and whether they send ether
+    fn external_calls(&self) -> Vec<(usize, bool)> {
+        let value_call = Regex::new(r"\.\s*call\s*\{[^}]*\bvalue\s*:").expect("valid value call pattern");
+        let low_level = Regex::new(r"\.\s*(?:call|delegatecall)\s*[({]").expect("valid low-level pattern");
+        let member_call = Regex::new(r"\b([A-Za-z_]\w*)\s*(?:\(\s*([\w.\[\]]+)\s*\))?\s*\.\s*([A-Za-z_]\w*)\s*\(").expect("valid member call pattern");
+
+        let mut calls: Vec<(usize, bool)> = value_call.find_iter(self.body).map(|m| (m.start(), true)).collect();
+        for m in low_level.find_iter(self.body) {
+            if !calls.iter().any(|(offset, _)| *offset == m.start()) {
+                calls.push((m.start(), false));
+            }
+        }
+        for captures in member_call.captures_iter(self.body) {
+            let receiver = &captures[1];
+            let is_cast = captures.get(2).is_some();
+            let external = if is_cast {
+                self.context.is_contract_type(receiver)
+            } else {
+                self.type_of(receiver).is_some_and(|type_name| self.context.is_contract_type(type_name))
+            };
+            if external {
+                calls.push((captures.get(0).unwrap().start(), false));
+            }
+        }
+
+        calls.sort();
+        calls
+    }
+
+    /// Offsets of writes to state variables
+    fn state_writes(&self, body: &str) -> Vec<usize> {
+        let names = self.state_variables();
+        if names.is_empty() {
+            return Vec::new();
+        }
+        let alternatives = names.iter().map(|name| regex::escape(name)).collect::<Vec<_>>().join("|");
+        let write = Regex::new(&format!(
+            r"(?:\b(?:{0})\b(?:\s*\[[^\]]*\]|\s*\.\s*\w+)*\s*(?:[-+*/%|&^]?=[^=]|\+\+|--)|\bdelete\s+(?:{0})\b|\b(?:{0})\s*\.\s*(?:push|pop)\s*\(|(?:\+\+|--)\s*(?:{0})\b)",
+            alternatives
+        ))
+        .expect("valid state write pattern");
+        write.find_iter(body).map(|m| m.start()).collect()
+    }
+
+    /// reentrancy-eth and reentrancy-no-eth
+    fn reentrancy(&self, graph: &CallGraph) -> Option<Vulnerability> {
+        if matches!(self.function.state_mutability.as_str(), "view" | "pure")
+            || self.function.modifiers.iter().any(|modifier| REENTRANCY_GUARDS.contains(&modifier.as_str()))
+        {
+            return None;
+        }
+
+        let calls = self.external_calls();
+        let (first_call, _) = *calls.first()?;
+
+        // Writes after the call, directly or in internal functions called after it
+        let mut written_after = self.state_writes(self.body).into_iter().any(|offset| offset > first_call);
+        if !written_after {
+            let call_pattern = Regex::new(r"\b([A-Za-z_]\w*)\s*\(").expect("valid call pattern");
+            written_after = call_pattern
+                .captures_iter(&self.body[first_call..])
+                .filter_map(|captures| {
+                    graph.nodes.iter().find(|node| {
+                        node.unit == self.unit.name
+                            && node.function.name == captures[1]
+                            && !matches!(node.function.visibility.as_str(), "external")
+                    })
+                })
+                .any(|node| !self.state_writes(node.function.body.as_deref().unwrap_or_default()).is_empty());
+        }
+        if !written_after {
+            return None;
+        }
+
+        let sends_ether = calls.iter().any(|(_, value)| *value);
+        let (check, severity, title) = if sends_ether {
+            ("reentrancy-eth", "High", "Reentrancy (Ether)")
+        } else {
+            ("reentrancy-no-eth", "Medium", "Reentrancy")
+        };
+        Some(finding(
+            check,
+            &self.context.contract.name,
+            self.line(first_call),
+            severity,
+            0.6,
+            VulnerabilityCategory::Reentrancy,
+            "CWE-841",
+            format!("{}: {}", title, self.label()),
+            format!(
+                "{} writes state after an external call{}. The callee can re-enter {} before the state is updated.",
+                self.label(),
+                if sends_ether { " that sends ether" } else { "" },
+                self.function.name
+            ),
+            "Update state before making external calls (checks-effects-interactions) or add a reentrancy guard.",
+        ))
+    }
+
+    /// arbitrary-send-eth, controlled-delegatecall and suicidal
+    fn arbitrary_send(&self) -> Vec<Vulnerability> {
+        let mut findings = Vec::new();
+        if !self.is_entry_point() || self.is_protected() {
+            return findings;
+        }
+
+        let send = Regex::new(
+            r"(?:payable\s*\(\s*(\w+)\s*\)|\b(\w+))\s*\.\s*(?:transfer|send)\s*\([^,()]*\)|(?:payable\s*\(\s*(\w+)\s*\)|\b(\w+))\s*\.\s*call\s*\{[^}]*\bvalue\s*:",
+        )
+        .expect("valid send pattern");
+        for captures in send.captures_iter(self.body) {
+            let destination = (1..=4).find_map(|group| captures.get(group)).map(|m| m.as_str()).unwrap_or_default();
+            if self.is_parameter(destination) {
+                findings.push(finding(
+                    "arbitrary-send-eth",
+                    &self.context.contract.name,
+                    self.line(captures.get(0).unwrap().start()),
+                    "High",
+                    0.7,
+                    VulnerabilityCategory::AccessControl,
+                    "CWE-284",
+                    format!("Arbitrary Ether Send: {}", self.label()),
+                    format!(
+                        "{} sends ether to `{}`, which any caller chooses, and has no access control.",
+                        self.label(),
+                        destination
+                    ),
+                    "Restrict who can call the function, or only send ether to msg.sender or a stored address.",
+                ));
+                break;
+            }
+        }
+
+        let delegatecall = Regex::new(r"\b(\w+)\s*\.\s*delegatecall\s*\(").expect("valid delegatecall pattern");
+        if let Some(captures) = delegatecall.captures_iter(self.body).find(|captures| self.is_parameter(&captures[1])) {
+            findings.push(finding(
+                "controlled-delegatecall",
+                &self.context.contract.name,
+                self.line(captures.get(0).unwrap().start()),
+                "High",
+                0.7,
+                VulnerabilityCategory::LowLevelCalls,
+                "CWE-829",
+                format!("Controlled Delegatecall: {}", self.label()),
+                format!(
+                    "{} delegatecalls `{}`, which any caller chooses. The target runs with this contract's storage and balance.",
+                    self.label(),
+                    &captures[1]
+                ),
+                "Only delegatecall trusted, fixed implementations, and restrict who can call the function.",
+            ));
+        }
+
+        let destruct = Regex::new(r"\b(?:selfdestruct|suicide)\s*\(").expect("valid selfdestruct pattern");
+        if let Some(m) = destruct.find(self.body) {
+            findings.push(finding(
+                "suicidal",
+                &self.context.contract.name,
+                self.line(m.start()),
+                "High",
+                0.8,
+                VulnerabilityCategory::AccessControl,
+                "CWE-284",
+                format!("Unprotected Selfdestruct: {}", self.label()),
+                format!("Anyone can call {} and destroy the contract.", self.label()),
+                "Restrict the function to a trusted role, or remove selfdestruct.",
+            ));
+        }
+
+        findings
+    }
+
+    /// unchecked-lowlevel, unchecked-send and unchecked-transfer
+    fn unchecked_results(&self) -> Vec<Vulnerability> {
+        let call = Regex::new(r"\.\s*(call|delegatecall|staticcall|send|transfer|transferFrom)\s*[({]").expect("valid call pattern");
+        let bool_capture = Regex::new(r"\(\s*bool\s+(\w+)|\bbool\s+(\w+)\s*=").expect("valid capture pattern");
+
+        let mut findings = Vec::new();
+        for captures in call.captures_iter(self.body) {
+            let method = &captures[1];
+            let start = captures.get(0).unwrap().start();
+            let arguments = match self.call_arguments(captures.get(0).unwrap().end() - 1) {
+                Some(arguments) => arguments,
+                None => continue,
+            };
+
+            let check = match method {
+                "call" | "delegatecall" | "staticcall" => "unchecked-lowlevel",
+                "send" => "unchecked-send",
+                // One-argument transfer is ether and reverts on failure
+                "transfer" if arguments == 2 => "unchecked-transfer",
+                "transferFrom" if arguments == 3 => "unchecked-transfer",
+                _ => continue,
+            };
+
+            let statement_start = self.body[..start].rfind([';', '{', '}']).map_or(0, |i| i + 1);
+            let statement = self.body[statement_start..start].trim_start();
+            let checked = if let Some(capture) = bool_capture.captures(statement) {
+                let name = capture.get(1).or_else(|| capture.get(2)).unwrap().as_str();
+                let rest = &self.body[start..];
+                let rest = &rest[rest.find(';').map_or(rest.len(), |i| i + 1)..];
+                Regex::new(&format!(r"\b{}\b", regex::escape(name))).expect("valid name pattern").is_match(rest)
+            } else {
+                statement.contains('=')
+                    || ["require", "assert", "if", "return", "!", "("].iter().any(|prefix| statement.starts_with(prefix))
+                    || statement.contains("require(")
+            };
+            if checked {
+                continue;
+            }
+
+            let (severity, description, recommendation) = match check {
+                "unchecked-transfer" => (
+                    "High",
+                    "ignores the boolean returned by an ERC20 transfer. Tokens that return false instead of reverting make a failed transfer look successful.",
+                    "Use SafeERC20's safeTransfer/safeTransferFrom or check the returned value.",
+                ),
+                "unchecked-send" => (
+                    "Medium",
+                    "ignores the result of send, so a failed ether transfer goes unnoticed.",
+                    "Check the result of send, or use call with a checked result.",
+                ),
+                _ => (
+                    "Medium",
+                    "ignores the success flag of a low-level call, so a failed call goes unnoticed.",
+                    "Check the returned success flag and revert or handle the failure.",
+                ),
+            };
+            findings.push(finding(
+                check,
+                &self.context.contract.name,
+                self.line(start),
+                severity,
+                0.7,
+                VulnerabilityCategory::UnhandledExceptions,
+                "CWE-252",
+                format!("Unchecked {} Result: {}", method, self.label()),
+                format!("{} {}", self.label(), description),
+                recommendation,
+            ));
+        }
+
+        findings
+    }
+
+    /// Number of arguments of the call whose argument list opens at `open`, skipping `{value: ..}` options
+    fn call_arguments(&self, open: usize) -> Option<usize> {
+        let mut open = open;
+        if self.body[open..].starts_with('{') {
+            let close = matching_brace(self.body, open)?;
+            open = close + 1 + self.body[close + 1..].find('(')?;
+        }
+        let close = matching_paren(self.body, open)?;
+        let inner = self.body[open + 1..close].trim();
+        if inner.is_empty() {
+            return Some(0);
+        }
+
+        let mut depth = 0;
+        let mut arguments = 1;
+        for c in inner.chars() {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                ',' if depth == 0 => arguments += 1,
+                _ => {}
+            }
+        }
+        Some(arguments)
+    }
+
+    /// Single-statement patterns: storage pointers, tx.origin, randomness, arithmetic and comparisons
+    fn statement_patterns(&self) -> Vec<Vulnerability> {
+        let mut findings = Vec::new();
+        let file = &self.context.contract.name;
+
+        let storage_pointer = Regex::new(r"\b[A-Za-z_][\w.]*(?:\[\])?\s+storage\s+(\w+)\s*;").expect("valid storage pattern");
+        for captures in storage_pointer.captures_iter(self.body) {
+            findings.push(finding(
+                "uninitialized-storage",
+                file,
+                self.line(captures.get(0).unwrap().start()),
+                "High",
+                0.8,
+                VulnerabilityCategory::InputValidation,
+                "CWE-824",
+                format!("Uninitialized Storage Pointer: {}", &captures[1]),
+                format!(
+                    "`{}` in {} is a storage pointer declared without a value. Older compilers point it at slot 0, so writing through it overwrites the first state variables.",
+                    &captures[1],
+                    self.label()
+                ),
+                "Initialize the pointer, or declare the variable in memory.",
+            ));
+        }
+
+        let tx_origin = Regex::new(r"tx\.origin\s*[!=]=|[!=]=\s*tx\.origin").expect("valid tx.origin pattern");
+        if let Some(m) = tx_origin.find(self.body) {
+            findings.push(finding(
+                "tx-origin",
+                file,
+                self.line(m.start()),
+                "Medium",
+                0.8,
+                VulnerabilityCategory::AccessControl,
+                "CWE-477",
+                format!("tx.origin Authorization: {}", self.label()),
+                format!(
+                    "{} authorizes callers with tx.origin. A contract the owner interacts with can call it on the owner's behalf.",
+                    self.label()
+                ),
+                "Use msg.sender for authorization.",
+            ));
+        }
+
+        let mut prng_lines = HashSet::new();
+        let block_data = Regex::new(r"block\.timestamp|block\.difficulty|block\.prevrandao|blockhash\s*\(|\bnow\b").expect("valid block data pattern");
+        for statement in statements(self.body) {
+            let text = &self.body[statement.clone()];
+            if let Some(m) = block_data.find(text) {
+                if text.contains('%') {
+                    let line = self.line(statement.start + m.start());
+                    prng_lines.insert(line);
+                    findings.push(finding(
+                        "weak-prng",
+                        file,
+                        line,
+                        "High",
+                        0.7,
+                        VulnerabilityCategory::Cryptography,
+                        "CWE-330",
+                        format!("Weak Randomness: {}", self.label()),
+                        format!(
+                            "{} derives a random value from block data, which block producers can influence and anyone can predict.",
+                            self.label()
+                        ),
+                        "Use a verifiable randomness source such as Chainlink VRF or a commit-reveal scheme.",
+                    ));
+                }
+            }
+        }
+
+        let timestamp = Regex::new(r"block\.timestamp\s*(?:[<>]=?|[!=]=)|(?:[<>]=?|[!=]=)\s*block\.timestamp").expect("valid timestamp pattern");
+        if let Some(m) = timestamp.find(self.body) {
+            let line = self.line(m.start());
+            if !prng_lines.contains(&line) {
+                findings.push(finding(
+                    "timestamp",
+                    file,
+                    line,
+                    "Low",
+                    0.6,
+                    VulnerabilityCategory::TimestampDependence,
+                    "CWE-829",
+                    format!("Block Timestamp Comparison: {}", self.label()),
+                    format!("{} compares against block.timestamp, which block producers can shift by a few seconds.", self.label()),
+                    "Make sure a few seconds of drift cannot change the outcome.",
+                ));
+            }
+        }
+
+        let divide_first = Regex::new(r"(?:\b[\w.\[\]]+|\))\s*/\s*[\w.\[\]()]+\s*\*").expect("valid division pattern");
+        if let Some(m) = divide_first.find(self.body) {
+            findings.push(finding(
+                "divide-before-multiply",
+                file,
+                self.line(m.start()),
+                "Medium",
+                0.6,
+                VulnerabilityCategory::IntegerOverflow,
+                "CWE-1339",
+                format!("Division Before Multiplication: {}", self.label()),
+                format!("{} divides before multiplying. Integer division truncates, and the multiplication amplifies the lost precision.", self.label()),
+                "Multiply before dividing.",
+            ));
+        }
+
+        let strict_balance = Regex::new(r"(?:\.balance|balanceOf\s*\([^)]*\))\s*[!=]=|[!=]=\s*[\w.()]*(?:\.balance\b|balanceOf\s*\()").expect("valid equality pattern");
+        if let Some(m) = strict_balance.find(self.body) {
+            findings.push(finding(
+                "incorrect-equality",
+                file,
+                self.line(m.start()),
+                "Medium",
+                0.7,
+                VulnerabilityCategory::DenialOfService,
+                "CWE-697",
+                format!("Strict Balance Equality: {}", self.label()),
+                format!(
+                    "{} compares a balance with == or !=. Anyone can send ether or tokens to change the balance and break the condition.",
+                    self.label()
+                ),
+                "Compare balances with >= or <=, or track deposits in a state variable.",
+            ));
+        }
+
+        let boolean_equal = Regex::new(r"[!=]=\s*(?:true|false)\b|\b(?:true|false)\s*[!=]=").expect("valid boolean pattern");
+        if let Some(m) = boolean_equal.find(self.body) {
+            findings.push(finding(
+                "boolean-equal",
+                file,
+                self.line(m.start()),
+                "Info",
+                0.9,
+                VulnerabilityCategory::CodeQuality,
+                "CWE-1164",
+                format!("Comparison With Boolean Constant: {}", self.label()),
+                format!("{} compares a value with true or false.", self.label()),
+                "Use the boolean expression directly.",
+            ));
+        }
+
+        let assembly = Regex::new(r"\bassembly\s*(?:\(\s*\x22[^\x22]*\x22\s*\)\s*)?\{").expect("valid assembly pattern");
+        if let Some(m) = assembly.find(self.body) {
+            findings.push(finding(
+                "assembly",
+                file,
+                self.line(m.start()),
+                "Info",
+                0.9,
+                VulnerabilityCategory::LowLevelCalls,
+                "CWE-695",
+                format!("Inline Assembly: {}", self.label()),
+                format!("{} uses inline assembly, which bypasses the compiler's safety checks.", self.label()),
+                "Keep assembly minimal and document what it relies on.",
+            ));
+        }
+
+        let low_level = Regex::new(r"\.\s*(?:call|delegatecall|staticcall)\s*[({]").expect("valid low-level pattern");
+        if let Some(m) = low_level.find(self.body) {
+            findings.push(finding(
+                "low-level-calls",
+                file,
+                self.line(m.start()),
+                "Info",
+                0.9,
+                VulnerabilityCategory::LowLevelCalls,
+                "CWE-695",
+                format!("Low-Level Call: {}", self.label()),
+                format!("{} makes a low-level call, which skips the existence check and type safety of a contract call.", self.label()),
+                "Prefer contract calls through an interface where possible.",
+            ));
+        }
+
+        findings
+    }
+
+    /// calls-loop and msg-value-loop
+    fn loops(&self) -> Vec<Vulnerability> {
+        let loop_pattern = Regex::new(r"\b(?:for|while)\s*\(").expect("valid loop pattern");
+        let calls = self.external_calls();
+        let mut findings = Vec::new();
+        let mut reported: HashSet<&str> = HashSet::new();
+
+        for m in loop_pattern.find_iter(self.body) {
+            let header_close = match matching_paren(self.body, m.end() - 1) {
+                Some(close) => close,
+                None => continue,
+            };
+            let open = header_close + 1 + (self.body.len() - header_close - 1 - self.body[header_close + 1..].trim_start().len());
+            let end = if self.body[open..].starts_with('{') {
+                matching_brace(self.body, open).unwrap_or(self.body.len())
+            } else {
+                open + self.body[open..].find(';').unwrap_or(self.body.len() - open)
+            };
+
+            if !reported.contains("msg-value-loop") && self.body[m.start()..end].contains("msg.value") {
+                reported.insert("msg-value-loop");
+                findings.push(finding(
+                    "msg-value-loop",
+                    &self.context.contract.name,
+                    self.line(m.start()),
+                    "High",
+                    0.7,
+                    VulnerabilityCategory::InputValidation,
+                    "CWE-837",
+                    format!("msg.value in Loop: {}", self.label()),
+                    format!("{} reads msg.value inside a loop, so the same ether is counted once per iteration.", self.label()),
+                    "Track the ether used across iterations and check the total against msg.value once.",
+                ));
+            }
+
+            if let Some((offset, _)) = calls.iter().find(|(offset, _)| *offset > m.start() && *offset < end) {
+                if reported.insert("calls-loop") {
+                    findings.push(finding(
+                        "calls-loop",
+                        &self.context.contract.name,
+                        self.line(*offset),
+                        "Low",
+                        0.6,
+                        VulnerabilityCategory::DenialOfService,
+                        "CWE-400",
+                        format!("External Call in Loop: {}", self.label()),
+                        format!("{} makes an external call inside a loop. One reverting callee blocks the whole loop.", self.label()),
+                        "Favor pull over push payments, or let failed calls be skipped and retried.",
+                    ));
+                }
+            }
+        }
+
+        findings
+    }
+
+    /// shadowing-local and shadowing-builtin
+    fn shadowing(&self) -> Vec<Vulnerability> {
+        let local = Regex::new(
+            r"\b(?:u?int\d*|address(?:\s+payable)?|bool|bytes\d*|string|[A-Z]\w*)(?:\[\d*\])*(?:\s+(?:memory|storage|calldata))?\s+([a-z_]\w*)\s*[=;]",
+        )
+        .expect("valid local pattern");
+        let functions: HashSet<&str> = self.unit.functions.iter().map(|function| function.name.as_str()).collect();
+        let all_state: Vec<&str> = lineage(self.context.contract, self.unit)
+            .into_iter()
+            .flat_map(|unit| unit.state_variables.iter())
+            .map(|variable| variable.name.as_str())
+            .collect();
+
+        let mut declared: Vec<(String, usize)> = self
+            .function
+            .parameter_names
+            .iter()
+            .filter(|name| !name.is_empty())
+            .map(|name| (name.clone(), self.function.line_number))
+            .collect();
+        declared.extend(
+            local
+                .captures_iter(self.body)
+                .map(|captures| (captures[1].to_string(), self.line(captures.get(0).unwrap().start()))),
+        );
+        if BUILTINS.contains(&self.function.name.as_str()) {
+            declared.push((self.function.name.clone(), self.function.line_number));
+        }
+
+        let mut findings = Vec::new();
+        let mut seen = HashSet::new();
+        for (name, line) in declared {
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+            if BUILTINS.contains(&name.as_str()) {
+                findings.push(finding(
+                    "shadowing-builtin",
+                    &self.context.contract.name,
+                    line,
+                    "Low",
+                    0.8,
+                    VulnerabilityCategory::CodeQuality,
+                    "CWE-710",
+                    format!("Built-in Symbol Shadowing: {} in {}", name, self.label()),
+                    format!("`{}` in {} shadows the built-in symbol of the same name.", name, self.label()),
+                    "Rename the declaration.",
+                ));
+            } else if name != self.function.name && (all_state.contains(&name.as_str()) || functions.contains(name.as_str())) {
+                let what = if all_state.contains(&name.as_str()) { "state variable" } else { "function" };
+                findings.push(finding(
+                    "shadowing-local",
+                    &self.context.contract.name,
+                    line,
+                    "Low",
+                    0.8,
+                    VulnerabilityCategory::CodeQuality,
+                    "CWE-710",
+                    format!("Local Variable Shadowing: {} in {}", name, self.label()),
+                    format!("`{}` in {} shadows the {} of the same name.", name, self.label(), what),
+                    "Rename the local variable, e.g. with a leading or trailing underscore.",
+                ));
+            }
+        }
+
+        findings
+    }
+}
+
+/// A unit followed by its bases declared in the same file, nearest first
+pub fn lineage<'a>(contract: &'a ParsedContract, unit: &'a ContractUnit) -> Vec<&'a ContractUnit> {
+    let mut lineage = vec![unit];
+    let mut index = 0;
+    while index < lineage.len() {
+        let current = lineage[index];
+        for base in &current.inheritance {
+            if let Some(base_unit) = contract.units.iter().find(|candidate| &candidate.name == base) {
+                if !lineage.iter().any(|known| known.name == base_unit.name) {
+                    lineage.push(base_unit);
+                }
+            }
+        }
+        index += 1;
+    }
+    lineage
+}
+
+/// Source text of a unit, from its header to its closing brace
+pub fn unit_span<'s>(source: &'s str, unit: &ContractUnit) -> Option<&'s str> {
+    let start: usize = source.split_inclusive('\n').take(unit.line_number - 1).map(str::len).sum();
+    let open = start + source[start..].find('{')?;
+    let close = matching_brace(source, open)?;
+    Some(&source[start..=close])
+}
+
+/// Byte ranges of the top-level statements and conditions of a body
+fn statements(body: &str) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for (index, c) in body.char_indices() {
+        if matches!(c, ';' | '{' | '}') {
+            if !body[start..index].trim().is_empty() {
+                ranges.push(start..index);
+            }
+            start = index + 1;
+        }
+    }
+    ranges
+}
+
+fn line_at(source: &str, offset: usize) -> usize {
+    source[..offset].matches('\n').count() + 1
+}
+
+#[allow(clippy::too_many_arguments)]
+fn finding(
+    check: &str,
+    file: &str,
+    line_number: usize,
+    severity: &str,
+    confidence: f64,
+    category: VulnerabilityCategory,
+    cwe_id: &str,
+    title: String,
+    description: String,
+    recommendation: &str,
+) -> Vulnerability {
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title,
+        description,
+        severity: severity.to_string(),
+        category,
+        file_path: file.to_string(),
+        line_number: Some(line_number),
+        code_snippet: None,
+        recommendation: Some(recommendation.to_string()),
+        references: vec![format!("https://github.com/crytic/slither/wiki/Detector-Documentation#{}", check)],
+        cwe_id: Some(cwe_id.to_string()),
+        tool: TOOL_NAME.to_string(),
+        confidence,
+        provenance: None,
+        severity_overrides: Vec::new(),
+        cvss: None,
+        detected_by: Vec::new(),
+    }
+}
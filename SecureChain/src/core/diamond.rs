@@ -0,0 +1,821 @@
+//! Diamond (EIP-2535) aware analysis
+//!
+//! A diamond is analysed as one system rather than as isolated facets: the
+//! facets together form its combined ABI, and they share the diamond's
+//! storage through the AppStorage or DiamondStorage patterns. This module
+//! reconstructs both from source and, for deployed diamonds, from the loupe
+//! functions and the `DiamondCut` event history, and checks the composed
+//! system for routing, storage and upgrade problems.
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use crate::core::fetcher::{ContractFetcher, ContractInfo};
+use crate::core::parser::{ContractUnit, ParsedContract};
+use crate::core::rpc::RpcClient;
+use crate::core::selectors::{self, ExternalFunction, SelectorRoute};
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+use crate::status;
+use crate::utils::abi::{encode_hex, keccak256, read_address, read_usize, word};
+
+const TOOL_NAME: &str = "Diamond Analysis";
+
+/// Loupe function returning every facet with its selectors
+const FACETS_SIGNATURE: &str = "facets()";
+
+/// Event emitted for every diamond cut
+const DIAMOND_CUT_EVENT: &str = "DiamondCut((address,uint8,bytes4[])[],address,bytes)";
+
+/// Upgrade function of the standard diamond
+const DIAMOND_CUT_SIGNATURE: &str = "diamondCut((address,uint8,bytes4[])[],address,bytes)";
+
+/// Loupe functions required by EIP-2535
+const LOUPE_SIGNATURES: &[&str] = &[
+    "facets()",
+    "facetFunctionSelectors(address)",
+    "facetAddresses()",
+    "facetAddress(bytes4)",
+];
+
+/// Marks of an access check inside a `diamondCut` implementation
+const ACCESS_CHECKS: &[&str] = &["enforceIsContractOwner", "msg.sender", "_msgSender", "Owner", "Role", "_authorize"];
+
+/// A facet of the diamond
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiamondFacet {
+    /// Contract name, when the facet's source is available
+    pub name: Option<String>,
+    /// Deployed address, for on-chain diamonds
+    pub address: Option<String>,
+    pub selectors: Vec<String>,
+}
+
+impl DiamondFacet {
+    /// Name and/or address, for tables
+    pub fn label(&self) -> String {
+        match (&self.name, &self.address) {
+            (Some(name), Some(address)) => format!("{} ({})", name, address),
+            (Some(name), None) => name.clone(),
+            (None, Some(address)) => address.clone(),
+            (None, None) => "unknown".to_string(),
+        }
+    }
+}
+
+/// What a facet cut does with its selectors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FacetCutAction {
+    Add,
+    Replace,
+    Remove,
+}
+
+impl fmt::Display for FacetCutAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FacetCutAction::Add => write!(f, "Add"),
+            FacetCutAction::Replace => write!(f, "Replace"),
+            FacetCutAction::Remove => write!(f, "Remove"),
+        }
+    }
+}
+
+/// One entry of a `DiamondCut` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetCut {
+    pub facet_address: String,
+    pub action: FacetCutAction,
+    pub selectors: Vec<String>,
+}
+
+/// A `DiamondCut` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiamondCut {
+    pub block_number: u64,
+    pub transaction_hash: String,
+    pub cuts: Vec<FacetCut>,
+    /// Contract delegatecalled to initialise the cut, zero if none
+    pub init_address: String,
+}
+
+/// State of a deployed diamond as read from chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiamondDeployment {
+    pub address: String,
+    pub facets: Vec<DiamondFacet>,
+    pub cut_history: Vec<DiamondCut>,
+}
+
+/// A storage struct shared by the facets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageLayout {
+    pub name: String,
+    /// `AppStorage` or `DiamondStorage`
+    pub pattern: String,
+    /// Storage position: slot 0 for AppStorage, the hashed seed for DiamondStorage
+    pub position: String,
+    /// Field declarations in order
+    pub fields: Vec<String>,
+    /// Contract or library the layout was found in
+    pub declared_in: String,
+}
+
+/// The reconstructed diamond: facets, combined ABI, shared storage and cut history
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiamondSystem {
+    pub address: Option<String>,
+    pub facets: Vec<DiamondFacet>,
+    /// Combined ABI as a selector to facet routing table
+    pub routes: Vec<SelectorRoute>,
+    pub storage: Vec<StorageLayout>,
+    pub cut_history: Vec<DiamondCut>,
+}
+
+/// Result of diamond analysis
+#[derive(Debug, Clone, Default)]
+pub struct DiamondAnalysis {
+    pub system: DiamondSystem,
+    pub findings: Vec<Vulnerability>,
+}
+
+/// Read the facets and cut history of a deployed diamond
+pub fn load_deployment(rpc: &RpcClient, address: &str) -> Result<DiamondDeployment> {
+    let data = rpc.call(address, &keccak256(FACETS_SIGNATURE.as_bytes())[..4])?;
+    let facets = decode_facets(&data)
+        .ok_or_else(|| anyhow!("{} returned no valid facets() result; is it an EIP-2535 diamond?", address))?;
+
+    // Providers often cap log queries; the loupe alone is enough to analyse the diamond
+    let cut_history = match rpc.get_logs(address, &keccak256(DIAMOND_CUT_EVENT.as_bytes())) {
+        Ok(logs) => logs
+            .iter()
+            .filter_map(|log| {
+                let (cuts, init_address) = decode_diamond_cut(&log.data)?;
+                Some(DiamondCut {
+                    block_number: log.block_number,
+                    transaction_hash: log.transaction_hash.clone(),
+                    cuts,
+                    init_address,
+                })
+            })
+            .collect(),
+        Err(e) => {
+            log::warn!("Could not read the DiamondCut history of {}: {}", address, e);
+            Vec::new()
+        }
+    };
+
+    Ok(DiamondDeployment {
+        address: address.to_string(),
+        facets,
+        cut_history,
+    })
+}
+
+/// Fetch the verified source of every facet and name the facets after their contracts
+pub async fn fetch_facet_sources(
+    fetcher: &ContractFetcher,
+    deployment: &mut DiamondDeployment,
+    network: &str,
+    api_key: Option<&str>,
+    rate_limit: u32,
+) -> Vec<ContractInfo> {
+    let delay = std::time::Duration::from_millis(1000 / rate_limit.max(1) as u64);
+    let mut contracts = Vec::new();
+
+    for facet in &mut deployment.facets {
+        let address = match &facet.address {
+            Some(address) if !address.eq_ignore_ascii_case(&deployment.address) => address.clone(),
+            _ => continue,
+        };
+
+        tokio::time::sleep(delay).await;
+        match fetcher.fetch_contracts(network, &address, api_key).await {
+            Ok(fetched) if !fetched.is_empty() => {
+                status!("   📦 Facet {} at {}", fetched[0].name, address);
+                facet.name = Some(fetched[0].name.clone());
+                contracts.extend(fetched);
+            }
+            Ok(_) => log::warn!("Facet {} has no verified source", address),
+            Err(e) => log::warn!("Could not fetch facet {}: {}", address, e),
+        }
+    }
+
+    contracts
+}
+
+/// Analyse the contracts of a run as one diamond, optionally against its deployed state
+pub fn analyze_diamond(contracts: &[ParsedContract], deployment: Option<&DiamondDeployment>) -> Result<DiamondAnalysis> {
+    let externals = selectors::external_functions(contracts)?;
+    let mut analysis = DiamondAnalysis::default();
+
+    let facets = match deployment {
+        Some(deployment) => deployment.facets.clone(),
+        None => local_facets(contracts, &externals)?,
+    };
+    if facets.is_empty() {
+        return Err(anyhow!("No diamond facets found"));
+    }
+
+    let units: HashMap<&str, (&ContractUnit, &str)> = contracts
+        .iter()
+        .flat_map(|contract| contract.units.iter().map(move |unit| (unit.name.as_str(), (unit, contract.name.as_str()))))
+        .collect();
+
+    build_routes(&facets, &externals, deployment, &mut analysis);
+    check_standard_functions(&facets, &externals, deployment, &mut analysis);
+    if let Some(deployment) = deployment {
+        check_cut_history(deployment, &mut analysis);
+    }
+
+    let storage = storage_layouts(contracts)?;
+    check_storage_layouts(&storage, &mut analysis);
+    analysis.system.storage = storage;
+
+    for facet in &facets {
+        if let Some((unit, file)) = facet.name.as_deref().and_then(|name| units.get(name)) {
+            check_facet_code(&units, unit, file, &mut analysis);
+        }
+    }
+    check_diamond_cut_access(&units, &mut analysis);
+
+    analysis.system.address = deployment.map(|deployment| deployment.address.clone());
+    analysis.system.cut_history = deployment.map(|deployment| deployment.cut_history.clone()).unwrap_or_default();
+    analysis.system.facets = facets;
+
+    Ok(analysis)
+}
+
+/// Facets found in source, with the selectors each one exposes
+fn local_facets(contracts: &[ParsedContract], externals: &HashMap<String, Vec<ExternalFunction>>) -> Result<Vec<DiamondFacet>> {
+    Ok(selectors::diamond_facet_names(contracts)?
+        .into_iter()
+        .map(|name| {
+            let mut facet_selectors: Vec<String> = externals
+                .get(&name)
+                .map(|functions| functions.iter().map(|f| f.selector.clone()).collect())
+                .unwrap_or_default();
+            facet_selectors.sort();
+
+            DiamondFacet {
+                name: Some(name),
+                address: None,
+                selectors: facet_selectors,
+            }
+        })
+        .collect())
+}
+
+/// Combined ABI of the diamond, and for deployed diamonds the gaps between chain and source
+fn build_routes(
+    facets: &[DiamondFacet],
+    externals: &HashMap<String, Vec<ExternalFunction>>,
+    deployment: Option<&DiamondDeployment>,
+    analysis: &mut DiamondAnalysis,
+) {
+    let location = deployment.map(|deployment| deployment.address.as_str()).unwrap_or("diamond");
+
+    for facet in facets {
+        let functions: HashMap<&str, &ExternalFunction> = facet
+            .name
+            .as_ref()
+            .and_then(|name| externals.get(name))
+            .map(|functions| functions.iter().map(|f| (f.selector.as_str(), f)).collect())
+            .unwrap_or_default();
+
+        let mut unresolved = Vec::new();
+        for selector in &facet.selectors {
+            let signature = match functions.get(selector.as_str()) {
+                Some(function) => function.signature.clone(),
+                None => {
+                    unresolved.push(selector.as_str());
+                    "unknown".to_string()
+                }
+            };
+            analysis.system.routes.push(SelectorRoute {
+                selector: selector.clone(),
+                signature,
+                facet: facet.label(),
+            });
+        }
+
+        if deployment.is_none() {
+            continue;
+        }
+
+        match &facet.name {
+            None => analysis.findings.push(diamond_finding(
+                location,
+                None,
+                format!("Facet {} Has No Verified Source", facet.label()),
+                format!(
+                    "The diamond routes {} selectors to {}, whose source is not verified. Their behaviour, storage use and access control cannot be reviewed.",
+                    facet.selectors.len(),
+                    facet.label()
+                ),
+                "Medium",
+                VulnerabilityCategory::Other,
+                "Verify the facet's source on the explorer, or remove the facet if it is not meant to be live.",
+            )),
+            Some(name) if !unresolved.is_empty() => analysis.findings.push(diamond_finding(
+                location,
+                None,
+                format!("Selectors Routed to {} Missing From Its Source", name),
+                format!(
+                    "The loupe routes {} to {}, but its verified source exposes no function with these selectors. The deployed facet is not the reviewed code, or the routing is stale.",
+                    unresolved.join(", "),
+                    facet.label()
+                ),
+                "Medium",
+                VulnerabilityCategory::Other,
+                "Compare the deployed bytecode with the verified source and remove stale selectors with a Remove cut.",
+            )),
+            Some(_) => {}
+        }
+
+        // Functions in source the diamond never routes are dead code from the diamond's view
+        let routed: HashSet<&str> = facet.selectors.iter().map(|s| s.as_str()).collect();
+        let mut unrouted: Vec<&str> = functions
+            .values()
+            .filter(|f| !routed.contains(f.selector.as_str()))
+            .map(|f| f.signature.as_str())
+            .collect();
+        unrouted.sort();
+        if let (Some(name), false) = (&facet.name, unrouted.is_empty()) {
+            analysis.findings.push(diamond_finding(
+                location,
+                None,
+                format!("Facet {} Has Functions the Diamond Does Not Route", name),
+                format!(
+                    "{} exposes {} which are not registered in the diamond. Calls to them through the diamond revert.",
+                    name,
+                    unrouted.join(", ")
+                ),
+                "Info",
+                VulnerabilityCategory::CodeQuality,
+                "Add the missing selectors with a diamond cut, or remove the functions if they are not meant to be reachable.",
+            ));
+        }
+    }
+
+    analysis.system.routes.sort_by(|a, b| a.selector.cmp(&b.selector));
+}
+
+/// The diamond should expose `diamondCut` and the loupe functions
+fn check_standard_functions(
+    facets: &[DiamondFacet],
+    externals: &HashMap<String, Vec<ExternalFunction>>,
+    deployment: Option<&DiamondDeployment>,
+    analysis: &mut DiamondAnalysis,
+) {
+    let mut available: HashSet<String> = facets.iter().flat_map(|facet| facet.selectors.iter().cloned()).collect();
+    // In source, the diamond contract itself may implement standard functions directly
+    if deployment.is_none() {
+        available.extend(externals.values().flatten().map(|f| f.selector.clone()));
+    }
+    let location = deployment.map(|deployment| deployment.address.as_str()).unwrap_or("diamond");
+
+    let missing_loupe: Vec<&str> = LOUPE_SIGNATURES
+        .iter()
+        .copied()
+        .filter(|signature| !available.contains(&selectors::selector(signature)))
+        .collect();
+    if !missing_loupe.is_empty() {
+        analysis.findings.push(diamond_finding(
+            location,
+            None,
+            "Missing Diamond Loupe Functions".to_string(),
+            format!(
+                "The diamond does not expose {}. EIP-2535 requires the loupe so that users and tools can see which code a diamond runs.",
+                missing_loupe.join(", ")
+            ),
+            "Low",
+            VulnerabilityCategory::CodeQuality,
+            "Add the DiamondLoupeFacet from the reference implementation.",
+        ));
+    }
+
+    if !available.contains(&selectors::selector(DIAMOND_CUT_SIGNATURE)) {
+        analysis.findings.push(diamond_finding(
+            location,
+            None,
+            "Diamond Has No diamondCut Function".to_string(),
+            "No facet exposes the standard `diamondCut`. The diamond is either immutable or upgraded through a non-standard function that tooling cannot follow.".to_string(),
+            "Info",
+            VulnerabilityCategory::Other,
+            "Confirm the upgrade path is intended and document it.",
+        ));
+    }
+}
+
+/// Replay the cut history and compare it with what the loupe reports
+fn check_cut_history(deployment: &DiamondDeployment, analysis: &mut DiamondAnalysis) {
+    if deployment.cut_history.is_empty() {
+        return;
+    }
+
+    let mut replayed: HashMap<String, String> = HashMap::new();
+    for cut in deployment.cut_history.iter().flat_map(|event| &event.cuts) {
+        for selector in &cut.selectors {
+            match cut.action {
+                FacetCutAction::Add | FacetCutAction::Replace => {
+                    replayed.insert(selector.clone(), cut.facet_address.to_lowercase());
+                }
+                FacetCutAction::Remove => {
+                    replayed.remove(selector);
+                }
+            }
+        }
+    }
+
+    let loupe: HashMap<String, String> = deployment
+        .facets
+        .iter()
+        .flat_map(|facet| {
+            let address = facet.address.clone().unwrap_or_default().to_lowercase();
+            facet.selectors.iter().map(move |selector| (selector.clone(), address.clone()))
+        })
+        .collect();
+
+    let mut mismatched: Vec<&str> = replayed
+        .keys()
+        .chain(loupe.keys())
+        .filter(|selector| replayed.get(*selector) != loupe.get(*selector))
+        .map(|selector| selector.as_str())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    mismatched.sort();
+
+    if !mismatched.is_empty() {
+        analysis.findings.push(diamond_finding(
+            &deployment.address,
+            None,
+            "Loupe Disagrees With DiamondCut History".to_string(),
+            format!(
+                "Replaying the {} DiamondCut events gives a different facet than the loupe reports for {}. Routing was changed without a DiamondCut event, or the loupe is wrong.",
+                deployment.cut_history.len(),
+                mismatched.join(", ")
+            ),
+            "Medium",
+            VulnerabilityCategory::Other,
+            "Emit DiamondCut for every routing change and make the loupe read the same storage diamondCut writes.",
+        ));
+    }
+}
+
+/// AppStorage and DiamondStorage structs declared across the run
+fn storage_layouts(contracts: &[ParsedContract]) -> Result<Vec<StorageLayout>> {
+    let struct_pattern = Regex::new(r"\bstruct\s+(\w+)\s*\{([^}]*)\}")?;
+    let position_pattern = Regex::new(
+        r#"\bbytes32\s+(?:(?:internal|private|public|constant)\s+)+(\w+)\s*=\s*keccak256\s*\(\s*(?:abi\.encodePacked\s*\(\s*)?"([^"]*)""#,
+    )?;
+    let literal_pattern = Regex::new(r#"keccak256\s*\(\s*(?:abi\.encodePacked\s*\(\s*)?"([^"]*)""#)?;
+
+    let mut layouts: Vec<StorageLayout> = Vec::new();
+    for contract in contracts {
+        let source = contract.code();
+
+        let structs: HashMap<&str, Vec<String>> = struct_pattern
+            .captures_iter(source)
+            .map(|captures| {
+                let fields = captures
+                    .get(2)
+                    .map_or("", |m| m.as_str())
+                    .split(';')
+                    .map(|field| field.split_whitespace().collect::<Vec<_>>().join(" "))
+                    .filter(|field| !field.is_empty())
+                    .collect();
+                (captures.get(1).map_or("", |m| m.as_str()), fields)
+            })
+            .collect();
+
+        let positions: HashMap<&str, &str> = position_pattern
+            .captures_iter(source)
+            .filter_map(|captures| Some((captures.get(1)?.as_str(), captures.get(2)?.as_str())))
+            .collect();
+
+        if let Some(fields) = structs.get("AppStorage") {
+            layouts.push(StorageLayout {
+                name: "AppStorage".to_string(),
+                pattern: "AppStorage".to_string(),
+                position: "slot 0".to_string(),
+                fields: fields.clone(),
+                declared_in: contract.name.clone(),
+            });
+        }
+
+        // DiamondStorage accessors point a storage struct at a hashed slot in assembly
+        for unit in &contract.units {
+            for function in &unit.functions {
+                let body = match &function.body {
+                    Some(body) if body.contains(".slot") && body.contains(":=") => body,
+                    _ => continue,
+                };
+                let struct_name = match function.return_types.first() {
+                    Some(name) if structs.contains_key(name.as_str()) => name,
+                    _ => continue,
+                };
+
+                let seed = literal_pattern
+                    .captures(body)
+                    .and_then(|captures| captures.get(1))
+                    .map(|m| m.as_str())
+                    .or_else(|| {
+                        positions
+                            .iter()
+                            .find(|(constant, _)| contains_word(body, constant))
+                            .map(|(_, seed)| *seed)
+                    });
+
+                if let Some(seed) = seed {
+                    let position = format!("keccak256(\"{}\")", seed);
+                    if layouts.iter().any(|l| l.name == *struct_name && l.position == position && l.declared_in == unit.name) {
+                        continue;
+                    }
+                    layouts.push(StorageLayout {
+                        name: struct_name.clone(),
+                        pattern: "DiamondStorage".to_string(),
+                        position,
+                        fields: structs[struct_name.as_str()].clone(),
+                        declared_in: unit.name.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(layouts)
+}
+
+/// Layouts that disagree with each other corrupt the shared storage
+fn check_storage_layouts(layouts: &[StorageLayout], analysis: &mut DiamondAnalysis) {
+    // AppStorage always lives at slot 0, every copy must match
+    let app_storage: Vec<&StorageLayout> = layouts.iter().filter(|l| l.pattern == "AppStorage").collect();
+    if let Some(first) = app_storage.first() {
+        if let Some(other) = app_storage.iter().find(|l| l.fields != first.fields) {
+            analysis.findings.push(diamond_finding(
+                &other.declared_in,
+                None,
+                "Inconsistent AppStorage Layout".to_string(),
+                format!(
+                    "AppStorage in {} declares ({}) while {} declares ({}). Facets compiled against different layouts read and write each other's fields.",
+                    first.declared_in,
+                    first.fields.join("; "),
+                    other.declared_in,
+                    other.fields.join("; ")
+                ),
+                "High",
+                VulnerabilityCategory::Other,
+                "Declare AppStorage once in a shared file and only append new fields at the end.",
+            ));
+        }
+    }
+
+    let mut by_position: BTreeMap<&str, Vec<&StorageLayout>> = BTreeMap::new();
+    for layout in layouts.iter().filter(|l| l.pattern == "DiamondStorage") {
+        by_position.entry(layout.position.as_str()).or_default().push(layout);
+    }
+
+    for (position, users) in by_position {
+        let first = users[0];
+        if let Some(other) = users.iter().find(|l| l.name != first.name || l.fields != first.fields) {
+            analysis.findings.push(diamond_finding(
+                &other.declared_in,
+                None,
+                format!("Diamond Storage Position Collision at {}", position),
+                format!(
+                    "{} ({}) and {} ({}) both place their storage at {} with different layouts. Writes through one overwrite the other's fields.",
+                    first.name, first.declared_in, other.name, other.declared_in, position
+                ),
+                "Critical",
+                VulnerabilityCategory::Other,
+                "Give every DiamondStorage struct its own unique position string.",
+            ));
+        }
+    }
+}
+
+/// Storage and code in a facet that endangers the whole diamond
+fn check_facet_code(units: &HashMap<&str, (&ContractUnit, &str)>, facet: &ContractUnit, file: &str, analysis: &mut DiamondAnalysis) {
+    // The facet and the contracts it inherits from all run in the diamond's storage
+    let mut chain = vec![facet];
+    let mut seen: HashSet<&str> = HashSet::from([facet.name.as_str()]);
+    let mut index = 0;
+    while index < chain.len() {
+        for base in &chain[index].inheritance {
+            if let Some((unit, _)) = units.get(base.as_str()) {
+                if unit.kind != "interface" && seen.insert(unit.name.as_str()) {
+                    chain.push(unit);
+                }
+            }
+        }
+        index += 1;
+    }
+
+    let own_storage: Vec<_> = chain
+        .iter()
+        .flat_map(|unit| &unit.state_variables)
+        .filter(|variable| !variable.is_constant && !variable.is_immutable && variable.type_name != "AppStorage")
+        .collect();
+    if let Some(first) = own_storage.first() {
+        let names: Vec<&str> = own_storage.iter().map(|v| v.name.as_str()).collect();
+        analysis.findings.push(diamond_finding(
+            file,
+            Some(first.line_number),
+            format!("Facet {} Declares Its Own State Variables", facet.name),
+            format!(
+                "{} declares {} in regular storage. Facets run in the diamond's storage, so these slots overlap AppStorage and the variables of other facets.",
+                facet.name,
+                names.join(", ")
+            ),
+            "High",
+            VulnerabilityCategory::Other,
+            "Move the variables into AppStorage or a DiamondStorage struct with its own position.",
+        ));
+    }
+
+    for function in chain.iter().flat_map(|unit| &unit.functions) {
+        let body = match &function.body {
+            Some(body) => body,
+            None => continue,
+        };
+
+        if contains_word(body, "selfdestruct") {
+            analysis.findings.push(diamond_finding(
+                file,
+                Some(function.line_number),
+                format!("selfdestruct in Facet {}", facet.name),
+                format!(
+                    "`{}` in {} calls selfdestruct. Executed through the diamond's delegatecall it destroys the diamond itself, not the facet.",
+                    function.name, facet.name
+                ),
+                "High",
+                VulnerabilityCategory::AccessControl,
+                "Remove selfdestruct from facet code.",
+            ));
+        }
+
+        if body.contains("delegatecall") && !matches!(function.name.as_str(), "diamondCut" | "initializeDiamondCut") {
+            analysis.findings.push(diamond_finding(
+                file,
+                Some(function.line_number),
+                format!("delegatecall in Facet {}", facet.name),
+                format!(
+                    "`{}` in {} performs a delegatecall. The target runs with the diamond's storage and balance, outside the facets reviewed here.",
+                    function.name, facet.name
+                ),
+                "Medium",
+                VulnerabilityCategory::LowLevelCalls,
+                "Restrict delegatecall targets to trusted, immutable contracts, or route the logic through a facet.",
+            ));
+        }
+    }
+}
+
+/// `diamondCut` replaces any code in the diamond, so it must be access controlled
+fn check_diamond_cut_access(units: &HashMap<&str, (&ContractUnit, &str)>, analysis: &mut DiamondAnalysis) {
+    let mut names: Vec<&&str> = units.keys().collect();
+    names.sort();
+
+    for name in names {
+        let (unit, file) = units[*name];
+        if unit.kind != "contract" {
+            continue;
+        }
+
+        for function in unit.functions.iter().filter(|f| f.name == "diamondCut") {
+            let protected = !function.modifiers.is_empty()
+                || function
+                    .body
+                    .as_deref()
+                    .is_none_or(|body| ACCESS_CHECKS.iter().any(|check| body.contains(check)));
+            if protected {
+                continue;
+            }
+
+            analysis.findings.push(diamond_finding(
+                file,
+                Some(function.line_number),
+                format!("Unprotected diamondCut in {}", unit.name),
+                format!(
+                    "`diamondCut` in {} has no owner or role check. Anyone can add a facet and take over the diamond's storage and funds.",
+                    unit.name
+                ),
+                "Critical",
+                VulnerabilityCategory::AccessControl,
+                "Call LibDiamond.enforceIsContractOwner() or an equivalent role check at the start of diamondCut.",
+            ));
+        }
+    }
+}
+
+/// Decode the result of `facets()`: `(address, bytes4[])[]`
+fn decode_facets(data: &[u8]) -> Option<Vec<DiamondFacet>> {
+    let array = read_usize(data, 0)?;
+    let facets = decode_facet_tuples(data, array, false)?
+        .into_iter()
+        .map(|(address, _, selectors)| DiamondFacet {
+            name: None,
+            address: Some(address),
+            selectors,
+        })
+        .collect();
+
+    Some(facets)
+}
+
+/// Decode `DiamondCut` event data: `((address,uint8,bytes4[])[], address, bytes)`
+fn decode_diamond_cut(data: &[u8]) -> Option<(Vec<FacetCut>, String)> {
+    let array = read_usize(data, 0)?;
+    let init_address = read_address(data, 32)?;
+
+    let cuts = decode_facet_tuples(data, array, true)?
+        .into_iter()
+        .map(|(facet_address, action, selectors)| {
+            let action = match action? {
+                0 => FacetCutAction::Add,
+                1 => FacetCutAction::Replace,
+                2 => FacetCutAction::Remove,
+                _ => return None,
+            };
+            Some(FacetCut {
+                facet_address,
+                action,
+                selectors,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some((cuts, init_address))
+}
+
+/// Facet address, cut action if encoded, and selectors of one decoded tuple
+type FacetTuple = (String, Option<usize>, Vec<String>);
+
+/// Decode a dynamic array of `(address, [uint8,] bytes4[])` tuples starting at `array`
+fn decode_facet_tuples(data: &[u8], array: usize, with_action: bool) -> Option<Vec<FacetTuple>> {
+    let length = read_usize(data, array)?;
+    let heads = array.checked_add(32)?;
+
+    let mut tuples = Vec::new();
+    for i in 0..length {
+        let tuple = heads.checked_add(read_usize(data, heads.checked_add(i.checked_mul(32)?)?)?)?;
+        let address = read_address(data, tuple)?;
+        let (action, selectors_head) = if with_action {
+            (Some(read_usize(data, tuple + 32)?), tuple + 64)
+        } else {
+            (None, tuple + 32)
+        };
+
+        let selectors_start = tuple.checked_add(read_usize(data, selectors_head)?)?;
+        let count = read_usize(data, selectors_start)?;
+        let selectors = (0..count)
+            .map(|j| word(data, selectors_start + 32 + j * 32).map(|w| format!("0x{}", encode_hex(&w[..4]))))
+            .collect::<Option<Vec<_>>>()?;
+
+        tuples.push((address, action, selectors));
+    }
+
+    Some(tuples)
+}
+
+fn contains_word(text: &str, word: &str) -> bool {
+    text.match_indices(word).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + word.len()..].chars().next();
+        !before.is_some_and(|c| c.is_alphanumeric() || c == '_') && !after.is_some_and(|c| c.is_alphanumeric() || c == '_')
+    })
+}
+
+fn diamond_finding(
+    file: &str,
+    line_number: Option<usize>,
+    title: String,
+    description: String,
+    severity: &str,
+    category: VulnerabilityCategory,
+    recommendation: &str,
+) -> Vulnerability {
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title,
+        description,
+        severity: severity.to_string(),
+        category,
+        file_path: file.to_string(),
+        line_number,
+        code_snippet: None,
+        recommendation: Some(recommendation.to_string()),
+        references: vec!["https://eips.ethereum.org/EIPS/eip-2535".to_string()],
+        cwe_id: None,
+        tool: TOOL_NAME.to_string(),
+        confidence: 0.75,
+        provenance: None,
+        severity_overrides: Vec::new(),
+        cvss: None,
+        detected_by: Vec::new(),
+    }
+}
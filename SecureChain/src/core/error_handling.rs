@@ -0,0 +1,382 @@
+//! Error handling quality
+//!
+//! Requires without a message, revert strings over 32 bytes and string
+//! reasons where the compiler has custom errors are review comments written
+//! on nearly every audit. Each is reported once per contract with the lines
+//! it applies to, so a codebase with a hundred bare requires reads as one
+//! comment rather than a hundred. The run also gets an error handling score:
+//! how many of its checks tell the caller why they failed, and how cheaply.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::core::detectors;
+use crate::core::parser::{matching_paren, split_top_level, ParsedContract};
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+
+pub const TOOL_NAME: &str = "Error Handling";
+
+/// Revert strings longer than this take a second word of bytecode and revert data
+const MAX_REASON_BYTES: usize = 32;
+
+/// Lines listed in a finding before the rest are only counted
+const LISTED_LINES: usize = 10;
+
+/// Error handling of the checks of a run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ErrorHandlingQuality {
+    /// `require` statements
+    pub requires: usize,
+    /// `revert` statements
+    pub reverts: usize,
+    /// Requires and reverts that give no reason
+    pub without_reason: usize,
+    /// Reasons given as strings
+    pub string_reasons: usize,
+    /// String reasons over 32 bytes
+    pub long_reasons: usize,
+    /// Reasons given as custom errors
+    pub custom_errors: usize,
+    /// String reasons in files whose version pragma allows custom errors
+    pub convertible: usize,
+    /// 0 to 100: a check scores 1 with a custom error or, where custom errors
+    /// are unavailable, a string of at most 32 bytes, 0.5 with any other
+    /// string and 0 without a reason
+    pub score: f64,
+}
+
+/// Why a check reverts, as the caller sees it
+enum Reason {
+    Missing,
+    /// String literal of this many bytes
+    Text(usize),
+    CustomError,
+    /// String built at runtime
+    Expression,
+}
+
+/// A `require` or `revert` statement
+struct Check {
+    line: usize,
+    is_require: bool,
+    reason: Reason,
+}
+
+/// Report missing messages, long revert strings and string reasons custom errors could replace, per contract
+pub fn check_errors(contract: &ParsedContract) -> Vec<Vulnerability> {
+    let support = custom_error_support(&contract.pragma_directives);
+    let mut findings = Vec::new();
+
+    for unit in contract.units.iter().filter(|unit| unit.kind != "interface") {
+        let checks = match detectors::unit_span(contract.code(), unit) {
+            Some(span) => find_checks(contract.code(), span),
+            None => continue,
+        };
+
+        let missing = lines_where(&checks, |reason| matches!(reason, Reason::Missing));
+        if let Some(&first) = missing.first() {
+            findings.push(finding(
+                &contract.name,
+                first,
+                "Low",
+                VulnerabilityCategory::CodeQuality,
+                Some("CWE-703"),
+                format!("Missing Error Message in {}", unit.name),
+                format!(
+                    "{} require or revert statement{} in {} revert{} without a reason ({}), so callers, front ends and monitoring only see a bare revert and cannot tell which check failed.",
+                    missing.len(),
+                    plural(missing.len()),
+                    unit.name,
+                    if missing.len() == 1 { "s" } else { "" },
+                    line_list(&missing)
+                ),
+                if support.is_some() {
+                    "Give each check a custom error, e.g. `if (amount == 0) revert ZeroAmount();`."
+                } else {
+                    "Give each check a short message, e.g. `require(amount > 0, \"zero amount\");`."
+                },
+                "https://docs.soliditylang.org/en/latest/control-structures.html#error-handling-assert-require-revert-and-exceptions",
+            ));
+        }
+
+        let long: Vec<(usize, usize)> = checks
+            .iter()
+            .filter_map(|check| match check.reason {
+                Reason::Text(bytes) if bytes > MAX_REASON_BYTES => Some((check.line, bytes)),
+                _ => None,
+            })
+            .collect();
+        if let Some(&(first, _)) = long.first() {
+            let listed: Vec<String> = long
+                .iter()
+                .take(LISTED_LINES)
+                .map(|(line, bytes)| format!("line {}: {} bytes", line, bytes))
+                .collect();
+            let more = long.len().saturating_sub(LISTED_LINES);
+            findings.push(finding(
+                &contract.name,
+                first,
+                "Info",
+                VulnerabilityCategory::GasOptimization,
+                None,
+                format!("Long Revert String in {}", unit.name),
+                format!(
+                    "{} revert string{} in {} {} longer than {} bytes ({}{}), so each takes an extra word of bytecode at deployment and of revert data when it fails.",
+                    long.len(),
+                    plural(long.len()),
+                    unit.name,
+                    if long.len() == 1 { "is" } else { "are" },
+                    MAX_REASON_BYTES,
+                    listed.join(", "),
+                    if more > 0 { format!(" and {} more", more) } else { String::new() }
+                ),
+                "Shorten the messages to 32 bytes or less, or replace them with custom errors.",
+                "https://github.com/protofire/solhint/blob/develop/docs/rules/best-practices/reason-string.md",
+            ));
+        }
+
+        let strings = lines_where(&checks, |reason| matches!(reason, Reason::Text(_) | Reason::Expression));
+        if let (Some(lowest_has_them), Some(&first)) = (support, strings.first()) {
+            let pragma = if lowest_has_them {
+                String::new()
+            } else {
+                " Raise the version pragma to at least 0.8.4 first, since it still allows older compilers.".to_string()
+            };
+            findings.push(finding(
+                &contract.name,
+                first,
+                "Info",
+                VulnerabilityCategory::GasOptimization,
+                None,
+                format!("Revert Strings Instead of Custom Errors in {}", unit.name),
+                format!(
+                    "{} reverts with {} string message{} ({}) although its version pragma allows Solidity 0.8.4 or later, whose custom errors cost less bytecode and revert data, can carry the values that made the check fail and are decoded by name from the ABI.{}",
+                    unit.name,
+                    strings.len(),
+                    plural(strings.len()),
+                    line_list(&strings),
+                    pragma
+                ),
+                "Declare custom errors and revert with them, e.g. `error InsufficientBalance(uint256 available, uint256 required);` and `if (balance < amount) revert InsufficientBalance(balance, amount);`.",
+                "https://soliditylang.org/blog/2021/04/21/custom-errors/",
+            ));
+        }
+    }
+    findings
+}
+
+/// Error handling score over the Solidity sources of a run, `None` when they have no checks
+pub fn assess(contracts: &[ParsedContract]) -> Option<ErrorHandlingQuality> {
+    let mut quality = ErrorHandlingQuality::default();
+    let mut points = 0.0;
+    for contract in contracts.iter().filter(|contract| !contract.is_vyper()) {
+        let support = custom_error_support(&contract.pragma_directives);
+        for check in find_checks(contract.code(), contract.code()) {
+            match check.reason {
+                Reason::Missing => quality.without_reason += 1,
+                Reason::CustomError => {
+                    quality.custom_errors += 1;
+                    points += 1.0;
+                }
+                Reason::Text(_) | Reason::Expression => {
+                    quality.string_reasons += 1;
+                    let long = matches!(check.reason, Reason::Text(bytes) if bytes > MAX_REASON_BYTES);
+                    quality.long_reasons += long as usize;
+                    quality.convertible += support.is_some() as usize;
+                    points += if long || support.is_some() { 0.5 } else { 1.0 };
+                }
+            }
+            if check.is_require {
+                quality.requires += 1;
+            } else {
+                quality.reverts += 1;
+            }
+        }
+    }
+
+    let total = quality.requires + quality.reverts;
+    if total == 0 {
+        return None;
+    }
+    quality.score = points * 100.0 / total as f64;
+    Some(quality)
+}
+
+/// Requires and reverts in `span`, a slice of `code`, which line numbers count from
+fn find_checks(code: &str, span: &str) -> Vec<Check> {
+    let statement = Regex::new(r"\b(require|revert)\b\s*(\(|[A-Za-z_$])").expect("valid check pattern");
+    // Error names are capitalized, functions building a message are not: `Errors.Unauthorized()`, `Strings.toString(id)`
+    let custom_error = Regex::new(r"^(?:[A-Za-z_$][\w$]*\s*\.\s*)*[A-Z][\w$]*\s*\(").expect("valid custom error pattern");
+    let base = span.as_ptr() as usize - code.as_ptr() as usize;
+
+    let mut checks = Vec::new();
+    let mut line = code[..base].matches('\n').count() + 1;
+    let mut counted = 0;
+    // End of the last check's arguments, whose message may itself say "revert"
+    let mut arguments_end = 0;
+    for captures in statement.captures_iter(span) {
+        let whole = captures.get(0).expect("whole match");
+        if whole.start() < arguments_end {
+            continue;
+        }
+        line += span[counted..whole.start()].matches('\n').count();
+        counted = whole.start();
+        let is_require = &captures[1] == "require";
+        // `revert InsufficientBalance(...)`
+        if &captures[2] != "(" {
+            if !is_require {
+                checks.push(Check { line, is_require, reason: Reason::CustomError });
+            }
+            continue;
+        }
+
+        let open = whole.end() - 1;
+        let close = match matching_paren(span, open) {
+            Some(close) => close,
+            None => continue,
+        };
+        arguments_end = close;
+        let arguments = split_top_level(&span[open + 1..close], ',');
+        let message = if is_require { arguments.get(1) } else { arguments.first() };
+        let reason = match message.map(|message| message.trim()) {
+            None | Some("") => Reason::Missing,
+            Some(message) => match string_bytes(message) {
+                Some(bytes) => Reason::Text(bytes),
+                None if custom_error.is_match(message) => Reason::CustomError,
+                None => Reason::Expression,
+            },
+        };
+        checks.push(Check { line, is_require, reason });
+    }
+    checks
+}
+
+/// Bytes of a string literal, escapes counted as the byte they stand for; `None` for other expressions
+fn string_bytes(expression: &str) -> Option<usize> {
+    let literal = expression.strip_prefix("unicode").unwrap_or(expression);
+    let quote = literal.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let content = literal[1..].strip_suffix(quote)?;
+    let mut bytes = 0;
+    let mut chars = content.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('x') => {
+                    chars.nth(1);
+                    bytes += 1;
+                }
+                Some('u') => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    bytes += u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32).map_or(1, char::len_utf8);
+                }
+                _ => bytes += 1,
+            }
+        } else {
+            bytes += c.len_utf8();
+        }
+    }
+    Some(bytes)
+}
+
+/// Whether the version pragma allows a compiler with custom errors (0.8.4 on),
+/// and if so whether the lowest version it allows has them
+fn custom_error_support(pragmas: &[String]) -> Option<bool> {
+    const CUSTOM_ERRORS: (u32, u32, u32) = (0, 8, 4);
+    let version = Regex::new(r"(\^|~|>=|>|<=|<|=)?\s*(\d+)\.(\d+)(?:\.(\d+))?").expect("valid version pattern");
+    let spec = pragmas.iter().find_map(|pragma| pragma.trim().strip_prefix("solidity"))?;
+    let comparators: Vec<(String, (u32, u32, u32))> = version
+        .captures_iter(spec)
+        .filter_map(|captures| {
+            let number = |index: usize| captures.get(index).map_or(Some(0), |part| part.as_str().parse().ok());
+            let operator = captures.get(1).map_or("", |operator| operator.as_str()).to_string();
+            Some((operator, (number(2)?, number(3)?, number(4)?)))
+        })
+        .collect();
+
+    let lowest = comparators
+        .iter()
+        .filter(|(operator, _)| !operator.starts_with('<'))
+        .map(|(_, version)| *version)
+        .min()?;
+    // The range reaches custom errors when 0.8.4, or one of its own lower bounds above that, satisfies it
+    let candidates = std::iter::once(CUSTOM_ERRORS).chain(
+        comparators
+            .iter()
+            .filter(|(_, version)| *version >= CUSTOM_ERRORS)
+            .map(|(operator, version)| if operator == ">" { (version.0, version.1, version.2 + 1) } else { *version }),
+    );
+    let allowed = candidates
+        .into_iter()
+        .any(|candidate| comparators.iter().all(|(operator, bound)| satisfies(candidate, operator, *bound)));
+    allowed.then_some(lowest >= CUSTOM_ERRORS)
+}
+
+fn satisfies(version: (u32, u32, u32), operator: &str, bound: (u32, u32, u32)) -> bool {
+    match operator {
+        // `^0.8.0` allows the 0.8 releases, `^1.2.0` the 1.x ones
+        "^" if bound.0 == 0 => version >= bound && version.0 == 0 && version.1 == bound.1,
+        "^" => version >= bound && version.0 == bound.0,
+        "~" => version >= bound && version.0 == bound.0 && version.1 == bound.1,
+        ">=" => version >= bound,
+        ">" => version > bound,
+        "<=" => version <= bound,
+        "<" => version < bound,
+        _ => version == bound,
+    }
+}
+
+/// Lines of the checks whose reason `filter` accepts
+fn lines_where(checks: &[Check], filter: impl Fn(&Reason) -> bool) -> Vec<usize> {
+    checks.iter().filter(|check| filter(&check.reason)).map(|check| check.line).collect()
+}
+
+/// Line numbers, the first few listed and the rest counted
+fn line_list(lines: &[usize]) -> String {
+    let listed: Vec<String> = lines.iter().take(LISTED_LINES).map(|line| line.to_string()).collect();
+    let prefix = if lines.len() == 1 { "line" } else { "lines" };
+    match lines.len().saturating_sub(LISTED_LINES) {
+        0 => format!("{} {}", prefix, listed.join(", ")),
+        more => format!("{} {} and {} more", prefix, listed.join(", "), more),
+    }
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn finding(
+    file: &str,
+    line_number: usize,
+    severity: &str,
+    category: VulnerabilityCategory,
+    cwe_id: Option<&str>,
+    title: String,
+    description: String,
+    recommendation: &str,
+    reference: &str,
+) -> Vulnerability {
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title,
+        description,
+        severity: severity.to_string(),
+        category,
+        file_path: file.to_string(),
+        line_number: Some(line_number),
+        code_snippet: None,
+        recommendation: Some(recommendation.to_string()),
+        references: vec![reference.to_string()],
+        cwe_id: cwe_id.map(str::to_string),
+        tool: TOOL_NAME.to_string(),
+        confidence: 0.9,
+        provenance: None,
+        severity_overrides: Vec::new(),
+        cvss: None,
+        detected_by: Vec::new(),
+    }
+}
@@ -0,0 +1,202 @@
+//! Exploitability assessment of findings
+//!
+//! Bug bounty programs care less about what a bug is than about who can
+//! trigger it. For every finding this module locates the enclosing function,
+//! walks the contract's internal call graph back to its external entry
+//! points and records whether an arbitrary EOA can reach it, whether a
+//! privileged role is required, and whether exploitation depends on market
+//! conditions such as oracle prices or transaction ordering.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::core::callgraph::CallGraph;
+use crate::core::parser::UnitFunction;
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+
+/// Modifier name prefixes that restrict callers
+const PRIVILEGED_MODIFIERS: &[&str] = &["only", "auth", "requiresAuth", "restricted", "ownerOnly", "adminOnly", "whenCallerIs"];
+
+/// Code that depends on prices, liquidity or ordering an attacker does not fully control
+const MARKET_MARKERS: &[(&str, &str)] = &[
+    ("latestRoundData", "oracle price"),
+    ("latestAnswer", "oracle price"),
+    ("getReserves", "AMM reserves"),
+    ("slot0", "AMM spot price"),
+    ("getAmountOut", "AMM quote"),
+    ("flashLoan", "flash loan liquidity"),
+    ("block.timestamp", "block timing"),
+    ("block.number", "block timing"),
+];
+
+/// Finding wording that implies market conditions
+const MARKET_WORDS: &[(&str, &str)] = &[
+    ("front-run", "transaction ordering"),
+    ("frontrun", "transaction ordering"),
+    ("sandwich", "transaction ordering"),
+    ("mev", "transaction ordering"),
+    ("flash loan", "flash loan liquidity"),
+    ("oracle", "oracle price"),
+    ("price manipulation", "price manipulation"),
+];
+
+/// Bounty-relevant facts about a finding
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Exploitability {
+    /// Function containing the finding, as `Contract.function`
+    pub function: Option<String>,
+    /// Whether an arbitrary externally owned account can trigger it
+    pub reachable_by_eoa: bool,
+    /// Unprivileged external functions through which it is reached
+    pub entry_points: Vec<String>,
+    /// Whether every path to it requires a privileged caller
+    pub requires_privileged_role: bool,
+    /// Roles guarding the paths, e.g. modifier names
+    pub privileged_roles: Vec<String>,
+    /// Whether exploitation depends on prices, liquidity or ordering
+    pub requires_market_conditions: bool,
+    pub market_conditions: Vec<String>,
+}
+
+impl Exploitability {
+    /// One-line summary for reports
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.reachable_by_eoa {
+            if self.entry_points.is_empty() {
+                parts.push("Reachable by any EOA".to_string());
+            } else {
+                parts.push(format!("Reachable by any EOA via {}", self.entry_points.join(", ")));
+            }
+        } else if self.requires_privileged_role {
+            parts.push(format!("Requires privileged role ({})", self.privileged_roles.join(", ")));
+        } else {
+            parts.push("No external entry point found".to_string());
+        }
+        if self.requires_market_conditions {
+            parts.push(format!("depends on {}", self.market_conditions.join(", ")));
+        }
+        parts.join("; ")
+    }
+}
+
+/// Assess every finding, keyed by vulnerability id
+pub fn assess(graph: &CallGraph, vulnerabilities: &[Vulnerability]) -> HashMap<String, Exploitability> {
+    let role_pattern = Regex::new(r"(?:hasRole|_checkRole|onlyRole)\s*\(\s*([A-Za-z_]\w*)").expect("valid role pattern");
+    let guards: Vec<Vec<String>> = graph
+        .nodes
+        .iter()
+        .map(|node| privileged_by(node.function, node.function.body.as_deref().unwrap_or_default(), &role_pattern))
+        .collect();
+
+    vulnerabilities
+        .iter()
+        .map(|vuln| (vuln.id.clone(), assess_finding(vuln, graph, &guards)))
+        .collect()
+}
+
+fn assess_finding(vuln: &Vulnerability, graph: &CallGraph, guards: &[Vec<String>]) -> Exploitability {
+    let mut exploitability = Exploitability::default();
+
+    let enclosing = vuln.line_number.and_then(|line| graph.enclosing(&vuln.file_path, line));
+
+    // Without a location, judge the file by its unprivileged entry points
+    let starts: Vec<usize> = match enclosing {
+        Some(index) => vec![index],
+        None => (0..graph.nodes.len())
+            .filter(|i| graph.nodes[*i].file == vuln.file_path && graph.is_entry_point(*i))
+            .collect(),
+    };
+
+    if let Some(index) = enclosing {
+        exploitability.function = Some(format!("{}.{}", graph.nodes[index].unit, graph.nodes[index].function.name));
+    }
+
+    // Walk callers back from the finding; privileged functions end a path
+    let mut seen: HashSet<usize> = starts.iter().copied().collect();
+    let mut queue: VecDeque<usize> = starts.into_iter().collect();
+    let mut roles: Vec<String> = Vec::new();
+    while let Some(index) = queue.pop_front() {
+        if !guards[index].is_empty() {
+            for role in &guards[index] {
+                if !roles.contains(role) {
+                    roles.push(role.clone());
+                }
+            }
+            continue;
+        }
+
+        if graph.is_entry_point(index) {
+            exploitability.reachable_by_eoa = true;
+            let entry = format!("`{}`", graph.nodes[index].function.name);
+            if !exploitability.entry_points.contains(&entry) {
+                exploitability.entry_points.push(entry);
+            }
+        }
+
+        for &caller in graph.callers(index) {
+            if seen.insert(caller) {
+                queue.push_back(caller);
+            }
+        }
+    }
+
+    exploitability.requires_privileged_role = !exploitability.reachable_by_eoa && !roles.is_empty();
+    exploitability.privileged_roles = roles;
+
+    // Market conditions from the code around the finding and from its wording
+    let body = enclosing
+        .and_then(|index| graph.nodes[index].function.body.as_deref())
+        .unwrap_or_default();
+    let text = format!("{} {}", vuln.title, vuln.description).to_lowercase();
+    let mut conditions: Vec<&str> = MARKET_MARKERS
+        .iter()
+        .filter(|(marker, _)| body.contains(marker))
+        .map(|(_, condition)| *condition)
+        .chain(MARKET_WORDS.iter().filter(|(word, _)| text.contains(word)).map(|(_, condition)| *condition))
+        .collect();
+    if matches!(vuln.category, VulnerabilityCategory::TimestampDependence | VulnerabilityCategory::RaceCondition) {
+        conditions.push("transaction ordering");
+    }
+    conditions.sort();
+    conditions.dedup();
+
+    exploitability.requires_market_conditions = !conditions.is_empty();
+    exploitability.market_conditions = conditions.into_iter().map(str::to_string).collect();
+
+    exploitability
+}
+
+/// Modifiers or inline checks that restrict who may call `function`
+pub fn caller_guards(function: &UnitFunction) -> Vec<String> {
+    let role_pattern = Regex::new(r"(?:hasRole|_checkRole|onlyRole)\s*\(\s*([A-Za-z_]\w*)").expect("valid role pattern");
+    privileged_by(function, function.body.as_deref().unwrap_or_default(), &role_pattern)
+}
+
+/// Modifiers or inline checks that restrict who may call a function
+fn privileged_by(function: &UnitFunction, body: &str, role_pattern: &Regex) -> Vec<String> {
+    let mut guards: Vec<String> = function
+        .modifiers
+        .iter()
+        .filter(|modifier| PRIVILEGED_MODIFIERS.iter().any(|prefix| modifier.starts_with(prefix)))
+        .cloned()
+        .collect();
+
+    guards.extend(role_pattern.captures_iter(body).map(|captures| captures[1].to_string()));
+
+    let sender_check = ["msg.sender ==", "== msg.sender", "msg.sender !=", "!= msg.sender"]
+        .iter()
+        .any(|check| body.contains(check));
+    if sender_check {
+        guards.push("msg.sender check".to_string());
+    }
+    for call in ["_checkOwner", "enforceIsContractOwner", "_onlyOwner", "requireAuth"] {
+        if body.contains(call) {
+            guards.push(call.to_string());
+        }
+    }
+
+    guards.dedup();
+    guards
+}
@@ -2,13 +2,48 @@
 //! 
 //! This module handles fetching smart contracts from various sources
 //! including blockchain explorers, GitHub repositories, and local files.
+//!
+//! Bulk fetches stream one address at a time. Each fetched source is written
+//! to disk as soon as it arrives and recorded in a manifest next to it, so a
+//! fetch that fails or is interrupted midway keeps what it got and can be
+//! resumed from the manifest instead of starting over.
+//!
+//! Contracts verified with several files come back from Etherscan as solc
+//! standard JSON input, or as a map of files. Their sources are written out
+//! at the paths the compiler saw, with the remappings and compiler settings
+//! of the verification as `foundry.toml`, `hardhat.config.js`,
+//! `remappings.txt` and the `standard-input.json` itself, so tools run on
+//! the fetched directory compile it as it was deployed.
+//!
+//! A proxy is followed to its implementation, whose sources are written
+//! below the proxy's so that analysing the address covers both.
+//!
+//! Each network tries its `sources` in turn until one has the verified
+//! source: the Etherscan-compatible explorer, Sourcify (full or partial
+//! match) and Blockscout, so chains without an Etherscan clone can still be
+//! fetched. Further chains are configured under `networks.chains`.
 
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
-
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[cfg(feature = "explorers")]
+use crate::core::proxy;
+#[cfg(feature = "explorers")]
+use crate::core::rpc::RpcClient;
+#[cfg(feature = "explorers")]
+use crate::status;
+use crate::utils::artifact;
 use crate::utils::config::Config;
+use crate::utils::error::ToolError;
+
+/// Manifest of a bulk fetch, in its output directory
+pub const MANIFEST_FILE: &str = "fetch-manifest.json";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContractInfo {
@@ -20,8 +55,28 @@ pub struct ContractInfo {
     pub network: String,
     pub verified: bool,
     pub metadata: HashMap<String, String>,
+    /// Every file of a contract verified with several, `source_code` being the one declaring it
+    #[serde(default)]
+    pub project: Option<VerifiedProject>,
+}
+
+/// Sources and compiler settings of a contract verified as a multi-file project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifiedProject {
+    /// Content of each file, by its path in the project
+    pub sources: BTreeMap<String, String>,
+    /// File declaring the verified contract
+    pub main_file: String,
+    /// `prefix=target` remappings the sources were compiled with
+    pub remappings: Vec<String>,
+    /// Settings of the standard JSON input, remappings included
+    pub settings: Value,
 }
 
+/// Files besides the sources that describe a fetched project
+const PROJECT_FILES: &[&str] = &["foundry.toml", "hardhat.config.js", "remappings.txt", "standard-input.json"];
+
+#[cfg(feature = "explorers")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EtherscanResponse {
     pub status: String,
@@ -29,6 +84,7 @@ pub struct EtherscanResponse {
     pub result: Vec<EtherscanContract>,
 }
 
+#[cfg(feature = "explorers")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EtherscanContract {
     #[serde(rename = "SourceCode")]
@@ -59,8 +115,358 @@ pub struct EtherscanContract {
     pub swarm_source: String,
 }
 
+/// Outcome of fetching one address of a bulk fetch
+pub struct Fetched {
+    pub address: String,
+    pub result: Result<Vec<ContractInfo>>,
+}
+
+/// How fetching an address went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FetchStatus {
+    Fetched,
+    /// The explorer answered but has no verified source for the address
+    Unverified,
+    Failed,
+}
+
+/// One address of a bulk fetch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchItem {
+    pub address: String,
+    pub status: FetchStatus,
+    /// Source files written for the address, relative to the output directory
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Contracts fetched for the address, its implementation's included
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contracts: Vec<DeployedContract>,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Kind of the error a failed fetch ended with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<ToolError>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// A fetched contract and the address it is deployed at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployedContract {
+    pub name: String,
+    pub address: String,
+}
+
+impl DeployedContract {
+    /// Each of `contracts` fetched for `address`, implementations at their own address
+    pub fn of(address: &str, contracts: &[ContractInfo]) -> Vec<Self> {
+        contracts
+            .iter()
+            .map(|contract| Self {
+                name: contract.name.clone(),
+                address: if contract.address.is_empty() { address.to_string() } else { contract.address.clone() },
+            })
+            .collect()
+    }
+}
+
+/// What a bulk fetch has done so far, saved after every address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchManifest {
+    pub network: String,
+    pub items: Vec<FetchItem>,
+}
+
+impl FetchManifest {
+    pub fn new(network: &str) -> Self {
+        Self { network: network.to_string(), items: Vec::new() }
+    }
+
+    /// Manifest in `dir`, if a previous fetch left one
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        artifact::read_json(&path, "fetch manifest").map(Some)
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        artifact::write_atomic(dir.join(MANIFEST_FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Whether a resumed fetch can skip `address`; failed addresses are tried again
+    pub fn is_done(&self, address: &str) -> bool {
+        self.items
+            .iter()
+            .any(|item| item.address.eq_ignore_ascii_case(address) && item.status != FetchStatus::Failed)
+    }
+
+    /// Record the outcome of an address, replacing an earlier one
+    pub fn record(&mut self, item: FetchItem) {
+        self.items.retain(|existing| !existing.address.eq_ignore_ascii_case(&item.address));
+        self.items.push(item);
+    }
+}
+
+/// Directory below `dir` that the sources of `address` are written to
+pub fn source_dir(dir: &Path, address: &str) -> PathBuf {
+    dir.join(file_name(address))
+}
+
+/// Write the sources fetched for `address` below `dir`, returning their paths relative to it
+pub fn persist_sources(dir: &Path, address: &str, contracts: &[ContractInfo]) -> Result<Vec<String>> {
+    let address_dir = source_dir(dir, address);
+    std::fs::create_dir_all(&address_dir)?;
+
+    let mut files = Vec::new();
+    for contract in contracts {
+        // The implementation behind a proxy goes below the proxy, so both are analysed together
+        let contract_dir = if contract.address.is_empty() || contract.address.eq_ignore_ascii_case(address) {
+            address_dir.clone()
+        } else {
+            source_dir(&address_dir, &contract.address)
+        };
+        std::fs::create_dir_all(&contract_dir)?;
+        if let Some(project) = &contract.project {
+            for path in write_project(&contract_dir, project, contract)? {
+                files.push(path.strip_prefix(dir).unwrap_or(&path).to_string_lossy().into_owned());
+            }
+            continue;
+        }
+        let name = file_name(&contract.name);
+        let name = if name.ends_with(".sol") { name } else { format!("{}.sol", name) };
+        let path = contract_dir.join(name);
+        artifact::write_atomic(&path, &contract.source_code)?;
+        files.push(path.strip_prefix(dir).unwrap_or(&path).to_string_lossy().into_owned());
+    }
+    Ok(files)
+}
+
+/// Write the files of `project` below `dir` at their paths, with the build configuration of
+/// Foundry and Hardhat and the standard JSON input it was verified with, returning what was written
+pub fn write_project(dir: &Path, project: &VerifiedProject, contract: &ContractInfo) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for (source, content) in &project.sources {
+        let path = dir.join(source);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        artifact::write_atomic(&path, content)?;
+        written.push(path);
+    }
+
+    let version = solc_version(&contract.compiler_version);
+    let optimizer = project.settings.pointer("/optimizer/enabled").and_then(Value::as_bool).unwrap_or(contract.optimization);
+    let runs = project.settings.pointer("/optimizer/runs").and_then(Value::as_u64).unwrap_or(200);
+    let mut profile = format!(
+        "# Build settings of the verified contract {} at {} on {}\n[profile.default]\nsrc = \".\"\nout = \"out\"\nlibs = []\n",
+        contract.name, contract.address, contract.network
+    );
+    if let Some(version) = &version {
+        profile.push_str(&format!("solc_version = \"{}\"\n", version));
+    }
+    profile.push_str(&format!("optimizer = {}\noptimizer_runs = {}\n", optimizer, runs));
+    if let Some(evm_version) = project.settings.get("evmVersion").and_then(Value::as_str) {
+        profile.push_str(&format!("evm_version = \"{}\"\n", evm_version));
+    }
+    if project.settings.get("viaIR").and_then(Value::as_bool) == Some(true) {
+        profile.push_str("via_ir = true\n");
+    }
+    if let Some(hash) = project.settings.pointer("/metadata/bytecodeHash").and_then(Value::as_str) {
+        profile.push_str(&format!("bytecode_hash = \"{}\"\n", hash));
+    }
+    let libraries: Vec<String> = project
+        .settings
+        .get("libraries")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .flat_map(|(file, libraries)| {
+            libraries
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter_map(move |(name, address)| Some(format!("{}:{}:{}", file, name, address.as_str()?)))
+        })
+        .collect();
+    profile.push_str(&format!("remappings = {}\nlibraries = {}\n", json!(project.remappings), json!(libraries)));
+
+    let hardhat = format!(
+        "// Build settings of the verified contract {} at {} on {}\nmodule.exports = {};\n",
+        contract.name,
+        contract.address,
+        contract.network,
+        serde_json::to_string_pretty(&json!({
+            "solidity": { "version": version.as_deref().unwrap_or("unknown"), "settings": project.settings },
+            "paths": { "sources": "." },
+        }))?
+    );
+    let input = json!({
+        "language": "Solidity",
+        "sources": project.sources.iter().map(|(path, content)| (path.clone(), json!({ "content": content }))).collect::<serde_json::Map<_, _>>(),
+        "settings": project.settings,
+    });
+
+    let remappings = project.remappings.iter().map(|remapping| format!("{}\n", remapping)).collect::<String>();
+    for (name, content) in PROJECT_FILES.iter().zip([profile, hardhat, remappings, serde_json::to_string_pretty(&input)?]) {
+        // A source of the project may have one of these names
+        if project.sources.contains_key(*name) || (name == &"remappings.txt" && content.is_empty()) {
+            continue;
+        }
+        let path = dir.join(name);
+        artifact::write_atomic(&path, content)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Every source file of a fetched contract, for telling what it is
+#[cfg(feature = "explorers")]
+fn all_sources(contract: &ContractInfo) -> String {
+    match &contract.project {
+        Some(project) => project.sources.values().map(String::as_str).collect::<Vec<_>>().join("\n"),
+        None => contract.source_code.clone(),
+    }
+}
+
+/// Solc release of an explorer's compiler version, `v0.8.19+commit.7dd6d404` being `0.8.19`
+fn solc_version(compiler_version: &str) -> Option<String> {
+    let version = compiler_version.trim().trim_start_matches('v');
+    let version = version.split('+').next().unwrap_or(version);
+    let mut parts = version.split('.');
+    let valid = parts.clone().count() == 3 && parts.all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    valid.then(|| version.to_string())
+}
+
+/// Sources and settings of a verified source that holds several files, `None` for a single flat file
+///
+/// Etherscan returns standard JSON input wrapped in a second pair of braces,
+/// and older multi-file verifications as a bare map of file to content.
+#[cfg(feature = "explorers")]
+fn parse_verified_project(contract: &EtherscanContract) -> Result<Option<VerifiedProject>> {
+    let source = contract.source_code.trim();
+    let json_text = match source.strip_prefix("{{").and_then(|inner| inner.strip_suffix("}}")) {
+        Some(inner) => format!("{{{}}}", inner),
+        None if source.starts_with('{') => source.to_string(),
+        None => return Ok(None),
+    };
+    let input: Value = serde_json::from_str(&json_text).map_err(|e| anyhow!("Unreadable multi-file source of {}: {}", contract.contract_name, e))?;
+
+    // Standard JSON input has `sources` and `settings`; the bare map is the sources alone
+    let (files, mut settings) = match input.get("sources").and_then(Value::as_object) {
+        Some(files) => (files.clone(), input.get("settings").cloned().unwrap_or_else(|| json!({}))),
+        None => match input.as_object() {
+            Some(files) => (files.clone(), json!({})),
+            None => return Err(anyhow!("Unreadable multi-file source of {}", contract.contract_name)),
+        },
+    };
+    if settings.get("optimizer").is_none() {
+        settings["optimizer"] = json!({ "enabled": contract.optimization_used == "1", "runs": contract.runs.parse::<u64>().unwrap_or(200) });
+    }
+    if settings.get("evmVersion").is_none() && !matches!(contract.evm_version.to_lowercase().as_str(), "" | "default") {
+        settings["evmVersion"] = json!(contract.evm_version.to_lowercase());
+    }
+    verified_project(&contract.contract_name, &files, settings).map(Some)
+}
+
+/// Project of `files`, each `{ "content": ... }` by source unit name, compiled with `settings`
+///
+/// The main file is the one declaring `name`, else the one named after it.
+#[cfg(feature = "explorers")]
+fn verified_project(name: &str, files: &serde_json::Map<String, Value>, mut settings: Value) -> Result<VerifiedProject> {
+    let mut sources = BTreeMap::new();
+    for (name, file) in files {
+        let content = match file.get("content").and_then(Value::as_str) {
+            Some(content) => content,
+            None => continue,
+        };
+        let path = project_path(name).ok_or_else(|| anyhow!("Verified source {} has an unsafe path", name))?;
+        sources.insert(path, content.to_string());
+    }
+    if sources.is_empty() {
+        return Err(anyhow!("Verified source of {} has no files", name));
+    }
+
+    // Targets are source paths too, and moved along with them
+    let remappings: Vec<String> = settings
+        .get("remappings")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .filter_map(|remapping| {
+            let (prefix, target) = remapping.split_once('=')?;
+            let target = project_path(target).filter(|target| !target.is_empty())?;
+            Some(format!("{}={}{}", prefix, target, if remapping.ends_with('/') { "/" } else { "" }))
+        })
+        .collect();
+    settings["remappings"] = json!(remappings);
+
+    let declaration = regex::Regex::new(&format!(r"\b(?:abstract\s+)?(?:contract|library)\s+{}\b", regex::escape(name)))?;
+    let main_file = sources
+        .iter()
+        .find(|(_, content)| declaration.is_match(content))
+        .or_else(|| sources.iter().find(|(path, _)| Path::new(path).file_stem().is_some_and(|stem| *stem == *name)))
+        .or_else(|| sources.iter().next())
+        .map(|(path, _)| path.clone())
+        .expect("at least one source");
+    Ok(VerifiedProject { sources, main_file, remappings, settings })
+}
+
+/// JSON of a verified contract, `None` when the service does not know it
+#[cfg(feature = "explorers")]
+fn get_verified(url: &str, timeout: u64) -> Result<Option<Value>> {
+    match ureq::get(url).timeout(Duration::from_secs(timeout)).set("User-Agent", "SecureChain").call() {
+        Ok(response) => Ok(Some(response.into_json()?)),
+        Err(ureq::Error::Status(404, _)) => Ok(None),
+        Err(ureq::Error::Status(status, response)) => Err(ToolError::NetworkError {
+            message: format!("{} answered {}: {}", url, status, response.into_string().unwrap_or_default()),
+        }
+        .into()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Relative path of a source unit name in the project, `None` when it would leave it
+///
+/// Truffle names sources `project:/contracts/A.sol` and some verifications
+/// keep absolute paths, which are made relative to the project.
+#[cfg(feature = "explorers")]
+fn project_path(name: &str) -> Option<String> {
+    let name = name.trim().trim_start_matches("project:").replace('\\', "/");
+    let mut parts = Vec::new();
+    for part in name.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => return None,
+            part => parts.push(
+                part.chars()
+                    .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '@' | '+') { c } else { '_' })
+                    .collect::<String>(),
+            ),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+/// `name` with anything that is not safe in a file name replaced
+fn file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    match name.trim_start_matches('.') {
+        "" => "unnamed".to_string(),
+        name => name.to_string(),
+    }
+}
+
 pub struct ContractFetcher {
     config: Config,
+    /// JSON-RPC endpoint for reading proxy slots, over the network's `rpc_url`
+    rpc_url: Option<String>,
 }
 
 impl ContractFetcher {
@@ -68,9 +474,16 @@ impl ContractFetcher {
     pub fn new(config: Config) -> Self {
         Self {
             config,
+            rpc_url: None,
         }
     }
 
+    /// Read proxy slots from `rpc_url` instead of the network's configured endpoint
+    pub fn with_rpc_url(mut self, rpc_url: Option<String>) -> Self {
+        self.rpc_url = rpc_url;
+        self
+    }
+
     /// Fetch contracts from various sources
     pub async fn fetch_contracts(
         &self,
@@ -79,26 +492,176 @@ impl ContractFetcher {
         api_key: Option<&str>,
     ) -> Result<Vec<ContractInfo>> {
         match source {
-            "etherscan" | "ethereum" | "polygon" | "bsc" => {
-                self.fetch_from_etherscan(address, api_key.unwrap_or("")).await
-            },
+            #[cfg(feature = "explorers")]
+            "etherscan" => self.fetch_through_proxy(address, "ethereum", api_key).await,
+            #[cfg(feature = "explorers")]
             "github" => self.fetch_from_github(address).await,
             "local" => self.fetch_from_local(address).await,
+            #[cfg(feature = "explorers")]
+            network if self.config.networks.evm_network(network).is_some() => {
+                self.fetch_through_proxy(address, network, api_key).await
+            },
+            #[cfg(not(feature = "explorers"))]
+            source if source == "github" || self.config.networks.evm_network(source).is_some() => {
+                let _ = api_key;
+                Err(anyhow!("Fetching from {} needs a build with the `explorers` feature", source))
+            },
             _ => Err(anyhow!("Unsupported source: {}", source)),
         }
     }
 
-    /// Fetch contract from Etherscan
-    async fn fetch_from_etherscan(&self, address: &str, network: &str) -> Result<Vec<ContractInfo>> {
-        let api_key = std::env::var("ETHERSCAN_API_KEY").unwrap_or_else(|_| "YourApiKeyToken".to_string());
+    /// Fetch `addresses` one after another, yielding each outcome as soon as it is known
+    ///
+    /// Fetches are spaced to stay under `rate_limit` requests per second.
+    /// Dropping the stream cancels the addresses not fetched yet.
+    pub fn fetch_each<'a>(
+        &'a self,
+        source: &'a str,
+        addresses: Vec<String>,
+        api_key: Option<&'a str>,
+        rate_limit: u32,
+    ) -> impl Stream<Item = Fetched> + 'a {
+        let delay = Duration::from_millis(1000 / rate_limit.max(1) as u64);
+        futures::stream::iter(addresses.into_iter().enumerate()).then(move |(index, address)| async move {
+            if index > 0 {
+                tokio::time::sleep(delay).await;
+            }
+            let result = self.fetch_contracts(source, &address, api_key).await;
+            Fetched { address, result }
+        })
+    }
 
-        let base_url = match network {
-            "ethereum" => "https://api.etherscan.io/api",
-            "polygon" => "https://api.polygonscan.com/api",
-            "arbitrum" => "https://api.arbiscan.io/api",
-            "optimism" => "https://api-optimistic.etherscan.io/api",
-            "bsc" => "https://api.bscscan.com/api",
-            _ => return Err(anyhow!("Unsupported network: {}", network)),
+    /// Fetch a contract from the network's sources and, when it is a proxy, the implementation it delegates to
+    ///
+    /// The implementation's contracts carry their own address and the proxy's
+    /// under `implementation_of`; the proxy's record its kind and implementation.
+    /// An implementation that cannot be fetched leaves the proxy alone, with a warning.
+    #[cfg(feature = "explorers")]
+    async fn fetch_through_proxy(&self, address: &str, network: &str, api_key: Option<&str>) -> Result<Vec<ContractInfo>> {
+        let mut contracts = self.fetch_verified(address, network, api_key).await?;
+        let (kind, implementation) = match self.implementation_of(&contracts, address, network) {
+            Some(found) => found,
+            None => return Ok(contracts),
+        };
+        let described = kind.map_or_else(|| "a proxy".to_string(), |kind| format!("a {} proxy", kind));
+        status!("🪞 {} is {}; fetching its implementation {}", address, described, implementation);
+
+        let implementations = match self.fetch_verified(&implementation, network, api_key).await {
+            Ok(implementations) if !implementations.is_empty() => implementations,
+            Ok(_) => {
+                status!("⚠️  Implementation {} has no verified source; only the proxy of {} is analysed", implementation, address);
+                Vec::new()
+            }
+            Err(e) => {
+                status!("⚠️  Could not fetch implementation {}: {}; only the proxy of {} is analysed", implementation, e, address);
+                Vec::new()
+            }
+        };
+        let kind = match kind {
+            Some(proxy::ProxyKind::Eip1967) if implementations.iter().any(|contract| proxy::is_uups(&all_sources(contract))) => {
+                Some(proxy::ProxyKind::Uups)
+            }
+            kind => kind,
+        };
+
+        for contract in &mut contracts {
+            if let Some(kind) = kind {
+                contract.metadata.insert("proxy_kind".to_string(), kind.to_string());
+            }
+            contract.metadata.insert("implementation".to_string(), implementation.clone());
+        }
+        for mut contract in implementations {
+            contract.metadata.insert("implementation_of".to_string(), address.to_string());
+            contracts.push(contract);
+        }
+        Ok(contracts)
+    }
+
+    /// Kind and implementation address of a proxy, from the explorer or else its EIP-1967 slots
+    #[cfg(feature = "explorers")]
+    fn implementation_of(&self, contracts: &[ContractInfo], address: &str, network: &str) -> Option<(Option<proxy::ProxyKind>, String)> {
+        let contract = contracts.first()?;
+        let kind = proxy::proxy_kind(&all_sources(contract));
+        let listed = contract
+            .metadata
+            .get("implementation")
+            .filter(|implementation| implementation.starts_with("0x") && !implementation.eq_ignore_ascii_case(address));
+        if let Some(implementation) = listed {
+            return Some((kind, implementation.clone()));
+        }
+
+        let kind = kind?;
+        let network_config = self.config.networks.evm_network(network)?;
+        let rpc = RpcClient::new(self.rpc_url.as_deref().unwrap_or(&network_config.rpc_url), network_config.timeout);
+        match proxy::read_implementation(&rpc, address, kind) {
+            Ok(Some(implementation)) => Some((Some(kind), implementation)),
+            Ok(None) => {
+                status!("⚠️  {} looks like a {} proxy but its implementation slot is empty", address, kind);
+                None
+            }
+            Err(e) => {
+                status!("⚠️  {} looks like a {} proxy, but its implementation could not be read: {}", address, kind, e);
+                None
+            }
+        }
+    }
+
+    /// Verified source of `address` from the first of the network's sources that has it
+    ///
+    /// Sources without an endpoint for the network are skipped. No contracts
+    /// means a source answered and none had the source; an error means none answered.
+    #[cfg(feature = "explorers")]
+    async fn fetch_verified(&self, address: &str, network: &str, api_key: Option<&str>) -> Result<Vec<ContractInfo>> {
+        let network_config = self
+            .config
+            .networks
+            .evm_network(network)
+            .ok_or_else(|| ToolError::ConfigError { message: format!("Unsupported network: {}", network) })?;
+
+        let mut answered = false;
+        let mut last_error = None;
+        for source in &network_config.sources {
+            let fetched = match source.as_str() {
+                "etherscan" if !network_config.explorer_url.is_empty() => self.fetch_from_etherscan(address, network, api_key).await,
+                "sourcify" if network_config.chain_id.is_some() && !network_config.sourcify_url.is_empty() => {
+                    self.fetch_from_sourcify(address, network).await
+                }
+                "blockscout" if !network_config.blockscout_url.is_empty() => self.fetch_from_blockscout(address, network).await,
+                "etherscan" | "sourcify" | "blockscout" => continue,
+                other => {
+                    return Err(ToolError::ConfigError {
+                        message: format!("Unknown fetch source {} for {} (use etherscan, sourcify or blockscout)", other, network),
+                    }
+                    .into())
+                }
+            };
+            match fetched {
+                Ok(contracts) if !contracts.is_empty() => return Ok(contracts),
+                Ok(_) => answered = true,
+                Err(e) => {
+                    status!("⚠️  {} could not fetch {}: {}", source, address, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        match last_error {
+            Some(e) if !answered => Err(e),
+            _ if !answered => Err(ToolError::ConfigError { message: format!("{} has no fetch source configured", network) }.into()),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Fetch contract from the network's Etherscan-compatible explorer
+    #[cfg(feature = "explorers")]
+    async fn fetch_from_etherscan(&self, address: &str, network: &str, api_key: Option<&str>) -> Result<Vec<ContractInfo>> {
+        let api_key = match api_key {
+            Some(key) => key.to_string(),
+            None => std::env::var("ETHERSCAN_API_KEY").unwrap_or_else(|_| "YourApiKeyToken".to_string()),
+        };
+
+        let base_url = match self.config.networks.evm_network(network) {
+            Some(network_config) if !network_config.explorer_url.is_empty() => network_config.explorer_url.clone(),
+            _ => return Err(ToolError::ConfigError { message: format!("Unsupported network: {}", network) }.into()),
         };
 
         let url = format!(
@@ -106,7 +669,7 @@ impl ContractFetcher {
             base_url, address, api_key
         );
 
-        println!("Fetching contract from: {}", url);
+        status!("Fetching contract from: {}", url);
 
         let response = ureq::get(&url)
             .query("module", "contract")
@@ -119,7 +682,7 @@ impl ContractFetcher {
         let etherscan_response: EtherscanResponse = serde_json::from_str(&body)?;
 
         if etherscan_response.status != "1" {
-            return Err(anyhow!("Etherscan API error: {}", etherscan_response.message));
+            return Err(ToolError::NetworkError { message: format!("Etherscan API error: {}", etherscan_response.message) }.into());
         }
 
         let mut contracts = Vec::new();
@@ -127,6 +690,14 @@ impl ContractFetcher {
             if contract.source_code.is_empty() {
                 continue;
             }
+            let project = parse_verified_project(&contract)?;
+            let source_code = match &project {
+                Some(project) => project.sources[&project.main_file].clone(),
+                None => contract.source_code,
+            };
+            if let Some(project) = &project {
+                status!("📂 {} verified with {} files, main file {}", contract.contract_name, project.sources.len(), project.main_file);
+            }
 
             let mut metadata = HashMap::new();
             metadata.insert("abi".to_string(), contract.abi);
@@ -136,23 +707,174 @@ impl ContractFetcher {
             metadata.insert("license_type".to_string(), contract.license_type);
             metadata.insert("proxy".to_string(), contract.proxy);
             metadata.insert("implementation".to_string(), contract.implementation);
+            metadata.insert("fetched_from".to_string(), "etherscan".to_string());
 
             contracts.push(ContractInfo {
                 name: contract.contract_name,
                 address: address.to_string(),
-                source_code: contract.source_code,
+                source_code,
                 compiler_version: contract.compiler_version,
                 optimization: contract.optimization_used == "1",
                 network: network.to_string(),
                 verified: true,
                 metadata,
+                project,
             });
         }
 
         Ok(contracts)
     }
 
+    /// Fetch contract from Sourcify, taking full and partial matches alike
+    ///
+    /// A partial match compiles to the deployed bytecode but with a different
+    /// metadata hash, so its comments or file layout may differ from what was
+    /// deployed; `sourcify_match` records which one it was.
+    #[cfg(feature = "explorers")]
+    async fn fetch_from_sourcify(&self, address: &str, network: &str) -> Result<Vec<ContractInfo>> {
+        let network_config = self
+            .config
+            .networks
+            .evm_network(network)
+            .ok_or_else(|| ToolError::ConfigError { message: format!("Unsupported network: {}", network) })?;
+        let chain_id = network_config
+            .chain_id
+            .ok_or_else(|| ToolError::ConfigError { message: format!("No chain id configured for {}", network) })?;
+        let url = format!(
+            "{}/v2/contract/{}/{}?fields=sources,compilation,abi,proxyResolution",
+            network_config.sourcify_url.trim_end_matches('/'),
+            chain_id,
+            address
+        );
+        status!("Fetching contract from: {}", url);
+
+        let contract = match get_verified(&url, network_config.timeout)? {
+            Some(contract) => contract,
+            None => return Ok(Vec::new()),
+        };
+        let matched = match contract["match"].as_str() {
+            Some("exact_match") => "full",
+            Some("match") => "partial",
+            _ => return Ok(Vec::new()),
+        };
+
+        let compilation = &contract["compilation"];
+        let name = compilation["name"].as_str().unwrap_or("Contract").to_string();
+        let files = contract["sources"].as_object().cloned().unwrap_or_default();
+        let settings = compilation["compilerSettings"].clone();
+        let mut project = verified_project(&name, &files, if settings.is_object() { settings } else { json!({}) })?;
+        // The fully qualified name says which file declares the contract
+        let declared_in = compilation["fullyQualifiedName"].as_str().and_then(|fqn| fqn.rsplit_once(':')).and_then(|(file, _)| project_path(file));
+        if let Some(file) = declared_in.filter(|file| project.sources.contains_key(file)) {
+            project.main_file = file;
+        }
+        status!("📂 {} is a {} match on Sourcify with {} files, main file {}", name, matched, project.sources.len(), project.main_file);
+
+        let mut metadata = HashMap::new();
+        metadata.insert("abi".to_string(), contract["abi"].to_string());
+        metadata.insert("evm_version".to_string(), project.settings["evmVersion"].as_str().unwrap_or_default().to_string());
+        metadata.insert("sourcify_match".to_string(), matched.to_string());
+        metadata.insert("fetched_from".to_string(), "sourcify".to_string());
+        let proxy = &contract["proxyResolution"];
+        if proxy["isProxy"].as_bool() == Some(true) {
+            metadata.insert("proxy".to_string(), "1".to_string());
+            if let Some(implementation) = proxy["implementations"][0]["address"].as_str() {
+                metadata.insert("implementation".to_string(), implementation.to_string());
+            }
+        }
+
+        Ok(vec![ContractInfo {
+            name,
+            address: address.to_string(),
+            source_code: project.sources[&project.main_file].clone(),
+            compiler_version: compilation["compilerVersion"].as_str().unwrap_or("unknown").to_string(),
+            optimization: project.settings["optimizer"]["enabled"].as_bool().unwrap_or(false),
+            network: network.to_string(),
+            verified: true,
+            metadata,
+            project: (project.sources.len() > 1).then_some(project),
+        }])
+    }
+
+    /// Fetch contract from the network's Blockscout instance
+    #[cfg(feature = "explorers")]
+    async fn fetch_from_blockscout(&self, address: &str, network: &str) -> Result<Vec<ContractInfo>> {
+        let network_config = self
+            .config
+            .networks
+            .evm_network(network)
+            .ok_or_else(|| ToolError::ConfigError { message: format!("Unsupported network: {}", network) })?;
+        let url = format!("{}/api/v2/smart-contracts/{}", network_config.blockscout_url.trim_end_matches('/'), address);
+        status!("Fetching contract from: {}", url);
+
+        let contract = match get_verified(&url, network_config.timeout)? {
+            Some(contract) => contract,
+            None => return Ok(Vec::new()),
+        };
+        let source_code = contract["source_code"].as_str().unwrap_or_default();
+        let verified = contract["is_verified"].as_bool() == Some(true) || contract["is_partially_verified"].as_bool() == Some(true);
+        if !verified || source_code.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let name = contract["name"].as_str().unwrap_or("Contract").to_string();
+        let main_path = contract["file_path"].as_str().filter(|path| !path.is_empty()).map_or_else(|| format!("{}.sol", name), str::to_string);
+        let mut files = serde_json::Map::new();
+        files.insert(main_path.clone(), json!({ "content": source_code }));
+        for source in contract["additional_sources"].as_array().into_iter().flatten() {
+            if let (Some(path), Some(content)) = (source["file_path"].as_str(), source["source_code"].as_str()) {
+                files.insert(path.to_string(), json!({ "content": content }));
+            }
+        }
+
+        let mut settings = match &contract["compiler_settings"] {
+            settings if settings.is_object() => settings.clone(),
+            _ => json!({}),
+        };
+        let optimization = contract["optimization_enabled"].as_bool().unwrap_or(false);
+        if settings.get("optimizer").is_none() {
+            settings["optimizer"] = json!({ "enabled": optimization, "runs": contract["optimization_runs"].as_u64().unwrap_or(200) });
+        }
+        let evm_version = contract["evm_version"].as_str().unwrap_or_default().to_lowercase();
+        if settings.get("evmVersion").is_none() && !matches!(evm_version.as_str(), "" | "default") {
+            settings["evmVersion"] = json!(evm_version);
+        }
+        let mut project = verified_project(&name, &files, settings)?;
+        if let Some(main_file) = project_path(&main_path).filter(|path| project.sources.contains_key(path)) {
+            project.main_file = main_file;
+        }
+        if project.sources.len() > 1 {
+            status!("📂 {} verified on Blockscout with {} files, main file {}", name, project.sources.len(), project.main_file);
+        }
+
+        let mut metadata = HashMap::new();
+        metadata.insert("abi".to_string(), contract["abi"].to_string());
+        metadata.insert("constructor_arguments".to_string(), contract["constructor_args"].as_str().unwrap_or_default().to_string());
+        metadata.insert("evm_version".to_string(), evm_version);
+        metadata.insert("license_type".to_string(), contract["license_type"].as_str().unwrap_or_default().to_string());
+        metadata.insert("fetched_from".to_string(), "blockscout".to_string());
+        let implementation = contract["implementations"][0]["address"]
+            .as_str()
+            .or_else(|| contract["minimal_proxy_address_hash"].as_str());
+        if let Some(implementation) = implementation {
+            metadata.insert("implementation".to_string(), implementation.to_string());
+        }
+
+        Ok(vec![ContractInfo {
+            name,
+            address: address.to_string(),
+            source_code: project.sources[&project.main_file].clone(),
+            compiler_version: contract["compiler_version"].as_str().unwrap_or("unknown").to_string(),
+            optimization,
+            network: network.to_string(),
+            verified: true,
+            metadata,
+            project: (project.sources.len() > 1).then_some(project),
+        }])
+    }
+
     /// Fetch contracts from GitHub
+    #[cfg(feature = "explorers")]
     async fn fetch_from_github(&self, query: &str) -> Result<Vec<ContractInfo>> {
         let github_token = std::env::var("GITHUB_TOKEN").ok();
 
@@ -193,6 +915,7 @@ impl ContractFetcher {
                                         network: "github".to_string(),
                                         verified: false,
                                         metadata: HashMap::new(),
+                                        project: None,
                                     });
                                 }
                             }
@@ -230,20 +953,26 @@ impl ContractFetcher {
                 network: "local".to_string(),
                 verified: false,
                 metadata: HashMap::new(),
+                project: None,
             });
         } else if path.is_dir() {
-            // Directory - recursively find .sol files
+            // Directory - recursively find Solidity and Vyper files
             let walker = walkdir::WalkDir::new(path);
             for entry in walker.into_iter().filter_map(|e| e.ok()) {
                 let entry_path = entry.path();
                 if entry_path.is_file() {
                     if let Some(extension) = entry_path.extension() {
-                        if extension == "sol" {
+                        if extension == "sol" || extension == "vy" {
                             let source_code = std::fs::read_to_string(entry_path)?;
                             let name = entry_path.file_name()
                                 .and_then(|n| n.to_str())
                                 .unwrap_or("unknown")
                                 .to_string();
+                            let relative_path = entry_path
+                                .strip_prefix(path)
+                                .unwrap_or(entry_path)
+                                .to_string_lossy()
+                                .to_string();
 
                             contracts.push(ContractInfo {
                                 name,
@@ -253,7 +982,8 @@ impl ContractFetcher {
                                 optimization: false,
                                 network: "local".to_string(),
                                 verified: false,
-                                metadata: HashMap::new(),
+                                metadata: HashMap::from([("path".to_string(), relative_path)]),
+                                project: None,
                             });
                         }
                     }
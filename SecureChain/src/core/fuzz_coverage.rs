@@ -0,0 +1,287 @@
+//! Echidna and forge coverage
+//!
+//! Echidna writes the sequences that grew coverage to `coverage/` in its
+//! corpus directory, and an annotated copy of the source where executed
+//! lines are marked with `*`. Both are read back after a campaign: line
+//! coverage of the contract's functions, the corpus size, the distinct
+//! function-to-function transitions the sequences explore, and how often
+//! each function was called. Functions the campaign never reached are
+//! residual risk, since no property was checked after them.
+//!
+//! forge measures the same through `forge coverage --report lcov`: the
+//! Foundry fuzzer collects it for its generated suite, and `analyze` reads
+//! the `lcov.info` a Foundry project left at its root for the contracts
+//! Echidna did not fuzz. Only lines forge instrumented count there, so
+//! declarations and comments never lower the percentage.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::core::parser::ParsedContract;
+
+/// File name forge writes an lcov report to
+pub const LCOV_REPORT: &str = "lcov.info";
+
+/// How far up from the input a `foundry.toml` is looked for
+const MAX_PROJECT_DEPTH: usize = 4;
+
+/// Coverage of one fuzzing campaign or test suite run on a contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzCoverage {
+    pub contract: String,
+    /// What measured it: "echidna", or "forge" for lcov reports
+    #[serde(default = "default_tool")]
+    pub tool: String,
+    /// Lines of the contract's functions Echidna executed
+    pub lines_covered: u32,
+    /// Lines of code in the contract's functions
+    pub total_lines: u32,
+    pub coverage_percentage: f64,
+    pub uncovered_lines: Vec<u32>,
+    /// Call sequences kept in the corpus, none for forge
+    pub corpus_size: usize,
+    /// Distinct pairs of functions called one after the other in the corpus
+    pub unique_transitions: usize,
+    pub functions: Vec<FunctionHits>,
+}
+
+/// How much of one function the campaign exercised
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionHits {
+    pub name: String,
+    pub line: usize,
+    /// Calls to the function in the corpus sequences, internal functions only being reached through others;
+    /// for forge, every call the tests made
+    pub calls: usize,
+    pub lines_covered: u32,
+}
+
+impl FunctionHits {
+    pub fn reached(&self) -> bool {
+        self.calls > 0 || self.lines_covered > 0
+    }
+}
+
+impl FuzzCoverage {
+    /// Functions the campaign never called or executed a line of
+    pub fn unreached(&self) -> impl Iterator<Item = &FunctionHits> {
+        self.functions.iter().filter(|function| !function.reached())
+    }
+}
+
+fn default_tool() -> String {
+    "echidna".to_string()
+}
+
+/// Line coverage over every measured contract, `None` when nothing measured any
+pub fn line_coverage(coverages: &[FuzzCoverage]) -> Option<f64> {
+    if coverages.is_empty() {
+        return None;
+    }
+    let covered: u32 = coverages.iter().map(|coverage| coverage.lines_covered).sum();
+    let total: u32 = coverages.iter().map(|coverage| coverage.total_lines).sum();
+    Some(percentage(covered, total))
+}
+
+/// Coverage of `contract` from the corpus directory of a finished campaign, `None` when Echidna left nothing there
+pub fn collect(corpus_dir: &Path, contract: &ParsedContract) -> Option<FuzzCoverage> {
+    let sequences = read_sequences(&corpus_dir.join("coverage"));
+    let covered = read_covered_lines(corpus_dir);
+    if sequences.is_empty() && covered.is_none() {
+        return None;
+    }
+    let covered = covered.unwrap_or_default();
+
+    let mut calls: HashMap<String, usize> = HashMap::new();
+    let mut transitions = HashSet::new();
+    for sequence in &sequences {
+        for name in sequence {
+            *calls.entry(name.clone()).or_default() += 1;
+        }
+        for pair in sequence.windows(2) {
+            transitions.insert((pair[0].as_str(), pair[1].as_str()));
+        }
+    }
+
+    let mut coverage = measure(contract, "echidna", &covered, None, &calls);
+    coverage.corpus_size = sequences.len();
+    coverage.unique_transitions = transitions.len();
+    Some(coverage)
+}
+
+/// Hits per line and function of one source file of an lcov report
+#[derive(Debug, Clone, Default)]
+pub struct LcovFile {
+    /// `SF:` path, relative to the project root for forge
+    pub path: String,
+    lines: HashMap<u32, u64>,
+    functions: HashMap<String, u64>,
+}
+
+/// Source files of an lcov report, as `forge coverage --report lcov` writes it
+pub fn parse_lcov(report: &str) -> Vec<LcovFile> {
+    let mut files = Vec::new();
+    let mut file: Option<LcovFile> = None;
+    for line in report.lines().map(str::trim) {
+        let (key, value) = line.split_once(':').unwrap_or((line, ""));
+        match (key, file.as_mut()) {
+            ("SF", _) => file = Some(LcovFile { path: value.to_string(), ..LcovFile::default() }),
+            ("DA", Some(file)) => {
+                let mut fields = value.split(',');
+                if let (Some(Ok(line)), Some(Ok(hits))) = (fields.next().map(str::parse::<u32>), fields.next().map(str::parse::<u64>)) {
+                    *file.lines.entry(line).or_default() += hits;
+                }
+            }
+            // `FNDA:hits,Contract.function`, overloads adding up
+            ("FNDA", Some(file)) => {
+                if let Some((Ok(hits), name)) = value.split_once(',').map(|(hits, name)| (hits.parse::<u64>(), name)) {
+                    let name = name.rsplit('.').next().unwrap_or(name);
+                    *file.functions.entry(name.to_string()).or_default() += hits;
+                }
+            }
+            ("end_of_record", _) => files.extend(file.take()),
+            _ => {}
+        }
+    }
+    files.extend(file);
+    files
+}
+
+/// Coverage of `contract` from its file of an lcov report
+pub fn from_lcov(file: &LcovFile, contract: &ParsedContract) -> FuzzCoverage {
+    let covered: HashSet<u32> = file.lines.iter().filter(|(_, hits)| **hits > 0).map(|(line, _)| *line).collect();
+    let instrumented: HashSet<u32> = file.lines.keys().copied().collect();
+    let calls: HashMap<String, usize> = file.functions.iter().map(|(name, hits)| (name.clone(), *hits as usize)).collect();
+    measure(contract, "forge", &covered, Some(&instrumented), &calls)
+}
+
+/// The `lcov.info` of the nearest Foundry project holding `input_path`, with the project root
+pub fn project_lcov(input_path: &Path) -> Option<(PathBuf, PathBuf)> {
+    let start = if input_path.is_dir() { Some(input_path) } else { input_path.parent() };
+    let root = start
+        .into_iter()
+        .flat_map(Path::ancestors)
+        .take(MAX_PROJECT_DEPTH)
+        .find(|dir| dir.join("foundry.toml").is_file())?;
+    let report = root.join(LCOV_REPORT);
+    report.is_file().then(|| (root.to_path_buf(), report))
+}
+
+/// Coverage of `contract`'s functions given the lines executed and the calls made to each function.
+/// Lines count when `instrumented` holds them, or, without it, when they hold code.
+fn measure(contract: &ParsedContract, tool: &str, covered: &HashSet<u32>, instrumented: Option<&HashSet<u32>>, calls: &HashMap<String, usize>) -> FuzzCoverage {
+    let lines: Vec<&str> = contract.source_code.lines().collect();
+    let mut code_lines = BTreeSet::new();
+    let mut functions = Vec::new();
+    for function in contract.functions.iter().filter(|function| !function.is_constructor) {
+        let span = function_lines(function.line_number, function.body.matches('\n').count());
+        let body_lines: Vec<u32> = span
+            .filter(|line| match instrumented {
+                Some(instrumented) => instrumented.contains(line),
+                None => lines.get(*line as usize - 1).is_some_and(|text| is_code(text)),
+            })
+            .collect();
+        code_lines.extend(body_lines.iter().copied());
+        functions.push(FunctionHits {
+            name: function.name.clone(),
+            line: function.line_number,
+            calls: calls.get(function.name.as_str()).copied().unwrap_or_default(),
+            lines_covered: body_lines.iter().filter(|line| covered.contains(line)).count() as u32,
+        });
+    }
+
+    let total_lines = code_lines.len() as u32;
+    let uncovered_lines: Vec<u32> = code_lines.iter().copied().filter(|line| !covered.contains(line)).collect();
+    let lines_covered = total_lines - uncovered_lines.len() as u32;
+    FuzzCoverage {
+        contract: contract.name.clone(),
+        tool: tool.to_string(),
+        lines_covered,
+        total_lines,
+        coverage_percentage: percentage(lines_covered, total_lines),
+        uncovered_lines,
+        corpus_size: 0,
+        unique_transitions: 0,
+        functions,
+    }
+}
+
+fn percentage(covered: u32, total: u32) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        covered as f64 * 100.0 / total as f64
+    }
+}
+
+/// Lines from a function's declaration to the end of its body
+fn function_lines(line_number: usize, body_newlines: usize) -> impl Iterator<Item = u32> {
+    let start = line_number.max(1) as u32;
+    start..=start + body_newlines as u32
+}
+
+/// Whether a source line holds code rather than blank space, a comment or a lone brace
+fn is_code(line: &str) -> bool {
+    let line = line.trim();
+    !(line.is_empty() || line == "{" || line == "}" || line.starts_with("//") || line.starts_with("/*") || line.starts_with('*'))
+}
+
+/// Function names of each corpus sequence, in call order
+fn read_sequences(dir: &Path) -> Vec<Vec<String>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+        .filter_map(|content| serde_json::from_str::<Vec<Value>>(&content).ok())
+        .map(|transactions| {
+            transactions
+                .iter()
+                .filter(|tx| tx.pointer("/call/tag").and_then(Value::as_str) == Some("SolCall"))
+                .filter_map(|tx| tx.pointer("/call/contents/0").and_then(Value::as_str))
+                .map(str::to_string)
+                .collect()
+        })
+        .collect()
+}
+
+/// Executed line numbers from the newest `covered.*.txt` source annotation
+///
+/// Lines read `12 | *r | code` in current Echidna and `*r | code` in older
+/// releases, where the line number is counted instead.
+fn read_covered_lines(corpus_dir: &Path) -> Option<HashSet<u32>> {
+    let newest = std::fs::read_dir(corpus_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            name.starts_with("covered.") && name.ends_with(".txt")
+        })
+        .max()?;
+    let content = std::fs::read_to_string(newest).ok()?;
+
+    let mut covered = HashSet::new();
+    let mut counted = 0u32;
+    for line in content.lines() {
+        let parts: Vec<&str> = line.splitn(3, '|').collect();
+        let (number, markers) = match parts.as_slice() {
+            [number, markers, _] if number.trim().parse::<u32>().is_ok() => (number.trim().parse().ok(), *markers),
+            [markers, _] | [markers, _, _] => (None, *markers),
+            // A file name heading the next source
+            _ => {
+                counted = 0;
+                continue;
+            }
+        };
+        counted += 1;
+        if markers.contains('*') {
+            covered.insert(number.unwrap_or(counted));
+        }
+    }
+    Some(covered)
+}
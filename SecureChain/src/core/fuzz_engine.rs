@@ -2,16 +2,49 @@
 //! 
 //! This module provides fuzzing capabilities to discover runtime
 //! vulnerabilities through automated input generation and testing.
+//! Inputs come from Echidna or, with `tools.fuzzer = "foundry"`, from
+//! forge fuzz and invariant tests generated for the contract.
+//!
+//! For Echidna, a harness contract inheriting the target is generated with
+//! `echidna_` properties derived from its state: balances within the total
+//! supply, a total supply only the constructor sets staying constant, and an
+//! owner that the fuzzing accounts, none of them the deployer, cannot
+//! change. A violated property is reported at the function of the failing
+//! call sequence that writes the variable it checks, else at the variable.
 
 use anyhow::Result;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use tokio::process::Command;
+use std::collections::HashMap;
 use std::time::Duration;
 
-use crate::core::parser::ParsedContract;
+use crate::core::fuzz_coverage::{self, FuzzCoverage};
+use crate::core::parser::{ContractUnit, ParsedContract, UnitFunction, UnitStateVariable};
+use crate::core::selectors;
 use crate::report::vulnerability::Vulnerability;
+use crate::status;
 use crate::utils::config::Config;
+use crate::utils::budget;
+use crate::utils::error::ToolError;
+use crate::utils::platform;
+use crate::utils::retention;
+
+/// Selector of `Panic(uint256)`, which Solidity reverts with on failed asserts and checked arithmetic
+const PANIC_SELECTOR: &str = "4e487b71";
+
+/// Generated Echidna harness contract, and the file it is written to
+const HARNESS_NAME: &str = "SecureChainProperties";
+
+/// Transactions Echidna sends in a campaign
+const ECHIDNA_TEST_LIMIT: u32 = 10000;
+
+/// Account deploying the harness, and so owning the target
+const ECHIDNA_DEPLOYER: &str = "0x30000";
+
+/// Accounts Echidna sends transactions from, none of them the deployer
+const ECHIDNA_SENDERS: &[&str] = &["0x10000", "0x20000"];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FuzzingConfig {
@@ -28,16 +61,30 @@ pub struct PropertyTest {
     pub description: String,
     pub test_function: String,
     pub expected_behavior: String,
+    /// State variables the property checks
+    #[serde(default)]
+    pub variables: Vec<String>,
+    /// Line of the first of them in the target's source
+    #[serde(default)]
+    pub line_number: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FuzzingResults {
     pub contract_name: String,
+    /// Backend that produced the results
+    #[serde(default = "default_fuzzer")]
+    pub fuzzer: String,
     pub test_cases_run: u32,
     pub failures: Vec<FuzzingFailure>,
-    pub coverage_report: CoverageReport,
+    /// Coverage Echidna measured over its corpus; Foundry campaigns report none
+    #[serde(default)]
+    pub coverage_report: Option<FuzzCoverage>,
     pub property_results: Vec<PropertyResult>,
     pub duration: Duration,
+    /// Why the fuzzer did not complete, in which case there are no failures or coverage from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ToolError>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,14 +95,9 @@ pub struct FuzzingFailure {
     pub input_data: String,
     pub gas_used: Option<u64>,
     pub stack_trace: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CoverageReport {
-    pub lines_covered: u32,
-    pub total_lines: u32,
-    pub coverage_percentage: f64,
-    pub uncovered_lines: Vec<u32>,
+    /// Line of the target's source the failure is reported at
+    #[serde(default)]
+    pub line_number: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +108,10 @@ pub struct PropertyResult {
     pub iterations: u32,
 }
 
+fn default_fuzzer() -> String {
+    "echidna".to_string()
+}
+
 pub struct FuzzEngine {
     config: Config,
     fuzzing_config: FuzzingConfig,
@@ -90,145 +136,140 @@ impl FuzzEngine {
 
     /// Run fuzzing tests on a contract
     pub async fn fuzz_contract(&self, contract: &ParsedContract) -> Result<FuzzingResults> {
-        println!("🎲 Starting fuzzing tests for contract: {}", contract.name);
+        status!("🎲 Starting fuzzing tests for contract: {}", contract.name);
 
         let start_time = std::time::Instant::now();
 
-        // Generate property tests from contract analysis
-        let property_tests = self.generate_property_tests(contract)?;
+        // Properties derived from the contract's state, checked by Echidna through a generated harness
+        let harness = self.generate_property_tests(contract)?;
 
-        // Run Echidna fuzzing
-        let echidna_results = self.run_echidna_fuzzing(contract).await?;
-
-        // Run custom property tests
-        let property_results = self.run_property_tests(contract, &property_tests).await?;
-
-        // Generate coverage report
-        let coverage_report = self.generate_coverage_report(contract)?;
+        let campaign = if self.fuzzer() == "foundry" {
+            self.run_foundry_fuzzing(contract).await.map(|(failures, count, coverage)| (failures, count, coverage, Vec::new()))
+        } else {
+            self.run_echidna_fuzzing(contract, harness.as_ref()).await.map(|(failures, coverage, property_results)| {
+                let count = failures.len() as u32;
+                (failures, count, coverage, property_results)
+            })
+        };
+        // A fuzzer that fails leaves no failures, coverage or property results, with the failure recorded
+        let (failures, test_cases_run, coverage_report, property_results, error) = match campaign {
+            Ok((failures, count, coverage, property_results)) => (failures, count, coverage, property_results, None),
+            Err(e) => {
+                let tool = if self.fuzzer() == "foundry" { "Foundry" } else { "Echidna" };
+                log::warn!("{} failed: {}", tool, e);
+                (Vec::new(), 0, None, Vec::new(), Some(ToolError::classify(tool, &e)))
+            }
+        };
 
         let duration = start_time.elapsed();
 
         Ok(FuzzingResults {
             contract_name: contract.name.clone(),
-            test_cases_run: echidna_results.len() as u32,
-            failures: echidna_results,
+            fuzzer: self.fuzzer().to_string(),
+            test_cases_run,
+            failures,
             coverage_report,
             property_results,
             duration,
+            error,
         })
     }
 
-    /// Generate property tests from contract analysis
-    fn generate_property_tests(&self, contract: &ParsedContract) -> Result<Vec<PropertyTest>> {
-        let mut property_tests = Vec::new();
-
-        // Generate basic property tests for common patterns
-        for function in &contract.functions {
-            // Test for reentrancy protection
-            if function.visibility == "external" || function.visibility == "public" {
-                if function.body.contains("call") || function.body.contains("transfer") {
-                    property_tests.push(PropertyTest {
-                        name: format!("reentrancy_protection_{}", function.name),
-                        description: "Ensure function is protected against reentrancy attacks".to_string(),
-                        test_function: format!("test_reentrancy_{}", function.name),
-                        expected_behavior: "Function should not be vulnerable to reentrancy".to_string(),
-                    });
-                }
-            }
-
-            // Test for access control
-            if function.modifiers.iter().any(|m| m.contains("onlyOwner") || m.contains("onlyAdmin")) {
-                property_tests.push(PropertyTest {
-                    name: format!("access_control_{}", function.name),
-                    description: "Ensure function properly enforces access control".to_string(),
-                    test_function: format!("test_access_control_{}", function.name),
-                    expected_behavior: "Function should reject unauthorized callers".to_string(),
-                });
-            }
-
-            // Test for integer overflow/underflow
-            if function.body.contains("SafeMath") || function.body.contains("unchecked") {
-                property_tests.push(PropertyTest {
-                    name: format!("integer_safety_{}", function.name),
-                    description: "Ensure function handles integer operations safely".to_string(),
-                    test_function: format!("test_integer_safety_{}", function.name),
-                    expected_behavior: "Function should handle integer operations without overflow/underflow".to_string(),
-                });
-            }
+    /// Configured backend, Echidna unless Foundry is chosen
+    fn fuzzer(&self) -> &str {
+        match self.config.tools.fuzzer.as_str() {
+            "foundry" => "foundry",
+            _ => "echidna",
         }
+    }
 
-        // Generate invariant tests for state variables
-        for state_var in &contract.state_variables {
-            if state_var.type_name.contains("uint") || state_var.type_name.contains("int") {
-                property_tests.push(PropertyTest {
-                    name: format!("invariant_{}", state_var.name),
-                    description: format!("Ensure {} maintains valid state", state_var.name),
-                    test_function: format!("test_invariant_{}", state_var.name),
-                    expected_behavior: "State variable should maintain valid values".to_string(),
-                });
+    /// Echidna harness with the properties derived from the state of the first contract of `contract`
+    /// that deploys without arguments and has any, `None` when none has
+    fn generate_property_tests(&self, contract: &ParsedContract) -> Result<Option<EchidnaHarness>> {
+        for unit in contract.units.iter().filter(|unit| unit.kind == "contract") {
+            if unit.constructor.as_ref().is_some_and(|constructor| !constructor.parameter_types.is_empty()) {
+                continue;
+            }
+            let harness = EchidnaHarness::generate(contract, unit)?;
+            if !harness.properties.is_empty() {
+                return Ok(Some(harness));
             }
         }
-
-        Ok(property_tests)
+        Ok(None)
     }
 
-    /// Run Echidna fuzzing
-    async fn run_echidna_fuzzing(&self, contract: &ParsedContract) -> Result<Vec<FuzzingFailure>> {
-        println!("  🔍 Running Echidna fuzzing...");
+    /// Run Echidna fuzzing, on the harness of generated properties when there is one
+    async fn run_echidna_fuzzing(
+        &self,
+        contract: &ParsedContract,
+        harness: Option<&EchidnaHarness>,
+    ) -> Result<(Vec<FuzzingFailure>, Option<FuzzCoverage>, Vec<PropertyResult>)> {
+        status!("  🔍 Running Echidna fuzzing...");
 
         // Create temporary contract file
-        let temp_dir = tempfile::tempdir()?;
-        let contract_path = temp_dir.path().join(format!("{}.sol", contract.name));
+        let temp_dir = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).tempdir()?;
+        let contract_path = temp_dir.path().join(source_file_name(&contract.name));
+        let corpus_dir = temp_dir.path().join("corpus");
 
         // Generate Echidna configuration
-        let echidna_config = self.generate_echidna_config(contract)?;
+        let echidna_config = self.generate_echidna_config(contract, &corpus_dir, harness.is_some())?;
         let config_path = temp_dir.path().join("echidna.yaml");
 
-        std::fs::write(&contract_path, &contract.source_code)?;
+        std::fs::write(&contract_path, contract.source_code.as_bytes())?;
         std::fs::write(&config_path, &echidna_config)?;
 
         // Run Echidna
-        let output = Command::new("echidna-test")
-            .arg(&contract_path)
-            .arg("--config")
-            .arg(&config_path)
-            .arg("--format")
-            .arg("json")
-            .output()
-            .await;
-
-        match output {
-            Ok(cmd_output) => {
-                if cmd_output.status.success() {
-                    let stdout = String::from_utf8_lossy(&cmd_output.stdout);
-                    self.parse_echidna_output(&stdout)
-                } else {
-                    let stderr = String::from_utf8_lossy(&cmd_output.stderr);
-                    log::warn!("Echidna failed: {}", stderr);
-                    Ok(Vec::new())
-                }
+        let mut command = platform::async_command("echidna-test");
+        match harness {
+            Some(harness) => {
+                status!("  🧪 {} properties of {}: {}", harness.properties.len(), harness.target, harness.property_names().join(", "));
+                let harness_path = temp_dir.path().join(format!("{}.sol", HARNESS_NAME));
+                std::fs::write(&harness_path, &harness.source)?;
+                command.arg(&harness_path).arg("--contract").arg(HARNESS_NAME);
             }
-            Err(e) => {
-                log::warn!("Failed to run Echidna: {}. Make sure it's installed.", e);
-                Ok(Vec::new())
+            None => {
+                command.arg(&contract_path);
             }
         }
+        command.arg("--config").arg(&config_path).arg("--format").arg("json");
+        let output = budget::run(&mut command, None).await?;
+        // Echidna also exits with an error when a property fails, after reporting it
+        if !output.status.success() && output.stdout.is_empty() {
+            return Err(ToolError::crashed(output.status, &output.stderr).into());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut failures = self.parse_echidna_output(&stdout)?;
+        let property_results = match harness {
+            Some(harness) => harness.results(&mut failures),
+            None => Vec::new(),
+        };
+        Ok((failures, fuzz_coverage::collect(&corpus_dir, contract), property_results))
     }
 
     /// Generate Echidna configuration
-    fn generate_echidna_config(&self, contract: &ParsedContract) -> Result<String> {
+    fn generate_echidna_config(&self, contract: &ParsedContract, corpus_dir: &std::path::Path, harness: bool) -> Result<String> {
         let mut config = String::new();
 
-        config.push_str("testLimit: 10000\n");
+        config.push_str(&format!("testLimit: {}\n", ECHIDNA_TEST_LIMIT));
         config.push_str("shrinkLimit: 5000\n");
         config.push_str("seqLen: 100\n");
-        config.push_str("contractAddr: \"0x00a329c0648769A73afAc7F9381E08FB43dBEA72\"\n");
-        config.push_str("deployer: \"0x00a329c0648769A73afAc7F9381E08FB43dBEA72\"\n");
-        config.push_str("sender: [\"0x00a329c0648769A73afAc7F9381E08FB43dBEA72\"]\n");
-        config.push_str("psender: \"0x00a329c0648769A73afAc7F9381E08FB43dBEA72\"\n");
+        if harness {
+            // The harness checks what accounts other than the owner can do, so none of them deploys it
+            let senders: Vec<String> = ECHIDNA_SENDERS.iter().map(|sender| format!("{:?}", sender)).collect();
+            config.push_str(&format!("deployer: {:?}\n", ECHIDNA_DEPLOYER));
+            config.push_str(&format!("sender: [{}]\n", senders.join(", ")));
+            config.push_str(&format!("psender: {:?}\n", ECHIDNA_SENDERS[0]));
+        } else {
+            config.push_str("contractAddr: \"0x00a329c0648769A73afAc7F9381E08FB43dBEA72\"\n");
+            config.push_str("deployer: \"0x00a329c0648769A73afAc7F9381E08FB43dBEA72\"\n");
+            config.push_str("sender: [\"0x00a329c0648769A73afAc7F9381E08FB43dBEA72\"]\n");
+            config.push_str("psender: \"0x00a329c0648769A73afAc7F9381E08FB43dBEA72\"\n");
+        }
         config.push_str("prefix: \"echidna_\"\n");
         config.push_str("codeSize: 0x6000\n");
-        config.push_str("corpus: \"corpus\"\n");
+        // Coverage is read back from the corpus, so it must land where the campaign can find it
+        config.push_str(&format!("corpusDir: {:?}\n", corpus_dir.to_string_lossy()));
         config.push_str("coverage: true\n");
         config.push_str("checkAsserts: true\n");
 
@@ -250,9 +291,43 @@ impl FuzzEngine {
     }
 
     /// Parse Echidna output
+    ///
+    /// Echidna 2 prints one JSON document with every test; older releases print a line per property.
     fn parse_echidna_output(&self, output: &str) -> Result<Vec<FuzzingFailure>> {
         let mut failures = Vec::new();
 
+        if let Some(tests) = output
+            .find('{')
+            .and_then(|start| serde_json::from_str::<Value>(output[start..].trim()).ok())
+            .and_then(|campaign| campaign.get("tests").and_then(Value::as_array).cloned())
+        {
+            for test in tests.iter().filter(|test| test.get("status").and_then(Value::as_str) == Some("solved")) {
+                let name = test.get("name").and_then(Value::as_str).unwrap_or("unknown");
+                let sequence: Vec<String> = test
+                    .get("transactions")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|transaction| transaction.get("function").and_then(Value::as_str).map(str::to_string))
+                    .collect();
+                let property = test.get("type").and_then(Value::as_str) != Some("assertion");
+                failures.push(FuzzingFailure {
+                    test_case: name.to_string(),
+                    failure_type: if property { "Property violation" } else { "Assertion failure" }.to_string(),
+                    error_message: match test.get("error").and_then(Value::as_str) {
+                        Some(error) => error.to_string(),
+                        None if property => format!("{} returned false after {} calls", name, sequence.len()),
+                        None => format!("An assertion in {} failed", name),
+                    },
+                    input_data: if sequence.is_empty() { "N/A".to_string() } else { sequence.join("\n") },
+                    gas_used: None,
+                    stack_trace: None,
+                    line_number: None,
+                });
+            }
+            return Ok(failures);
+        }
+
         // Parse JSON output from Echidna
         for line in output.lines() {
             if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(line) {
@@ -278,6 +353,7 @@ impl FuzzEngine {
                                     stack_trace: json_value.get("stack_trace")
                                         .and_then(|v| v.as_str())
                                         .map(|s| s.to_string()),
+                                    line_number: None,
                                 };
                                 failures.push(failure);
                             }
@@ -290,49 +366,89 @@ impl FuzzEngine {
         Ok(failures)
     }
 
-    /// Run custom property tests
-    async fn run_property_tests(
-        &self,
-        _contract: &ParsedContract,
-        property_tests: &[PropertyTest],
-    ) -> Result<Vec<PropertyResult>> {
-        let mut results = Vec::new();
-
-        for property in property_tests {
-            println!("  🧪 Testing property: {}", property.name);
-
-            // For now, create mock results
-            // In a real implementation, this would execute the property tests
-            let result = PropertyResult {
-                property_name: property.name.clone(),
-                passed: true, // This would be determined by actual test execution
-                counterexample: None,
-                iterations: 1000,
-            };
+    /// Run generated forge fuzz and invariant tests, returning the failures and the inputs tried
+    async fn run_foundry_fuzzing(&self, contract: &ParsedContract) -> Result<(Vec<FuzzingFailure>, u32, Option<FuzzCoverage>)> {
+        status!("  🔍 Running Foundry fuzzing...");
 
-            results.push(result);
+        let suite = FoundrySuite::generate(contract)?;
+        if suite.targets.is_empty() {
+            status!("  ⏭️  No contract in {} with functions to fuzz deploys without constructor arguments, skipping", contract.name);
+            return Ok((Vec::new(), 0, None));
         }
 
-        Ok(results)
-    }
+        let foundry = &self.config.tools.foundry;
+        let temp_dir = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).tempdir()?;
+        std::fs::create_dir_all(temp_dir.path().join("src"))?;
+        std::fs::create_dir_all(temp_dir.path().join("test"))?;
+        std::fs::write(temp_dir.path().join("src").join(source_file_name(&contract.name)), contract.source_code.as_bytes())?;
+        std::fs::write(temp_dir.path().join("test").join("SecureChainFuzz.t.sol"), &suite.source)?;
+        std::fs::write(
+            temp_dir.path().join("foundry.toml"),
+            format!(
+                "[profile.default]\nsrc = \"src\"\ntest = \"test\"\nlibs = []\n\n[invariant]\nruns = {}\ndepth = {}\nfail_on_revert = false\n",
+                foundry.invariant_runs, foundry.invariant_depth
+            ),
+        )?;
 
-    /// Generate coverage report
-    fn generate_coverage_report(&self, contract: &ParsedContract) -> Result<CoverageReport> {
-        let total_lines = contract.source_code.lines().count() as u32;
-        let lines_covered = (total_lines as f64 * 0.75) as u32; // Mock 75% coverage
-        let coverage_percentage = (lines_covered as f64 / total_lines as f64) * 100.0;
+        let mut command = platform::async_command(&foundry.executable);
+        command
+            .arg("test")
+            .arg("--root")
+            .arg(temp_dir.path())
+            .arg("--fuzz-runs")
+            .arg(foundry.fuzz_runs.to_string())
+            .arg("--json");
+        let output = budget::run(&mut command, Some(Duration::from_secs(foundry.timeout))).await?;
 
-        let mut uncovered_lines = Vec::new();
-        for i in (lines_covered + 1)..=total_lines {
-            uncovered_lines.push(i);
-        }
+        // forge exits non-zero when a test fails, so whatever it printed is parsed
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (failures, count) = match suite.parse_output(&stdout) {
+            Some(parsed) => parsed,
+            None if output.status.success() => return Err(ToolError::ParseError { message: "unreadable forge test output".to_string() }.into()),
+            None => return Err(ToolError::crashed(output.status, &output.stderr).into()),
+        };
+        let coverage = if foundry.coverage { self.forge_coverage(contract, temp_dir.path()).await } else { None };
+        Ok((failures, count, coverage))
+    }
 
-        Ok(CoverageReport {
-            lines_covered,
-            total_lines,
-            coverage_percentage,
-            uncovered_lines,
-        })
+    /// Line coverage of the contract under the generated suite, from `forge coverage` in its project
+    async fn forge_coverage(&self, contract: &ParsedContract, project: &std::path::Path) -> Option<FuzzCoverage> {
+        let foundry = &self.config.tools.foundry;
+        let report = project.join(fuzz_coverage::LCOV_REPORT);
+        let mut command = platform::async_command(&foundry.executable);
+        command
+            .arg("coverage")
+            .arg("--root")
+            .arg(project)
+            .arg("--fuzz-runs")
+            .arg(foundry.fuzz_runs.to_string())
+            .arg("--report")
+            .arg("lcov")
+            .arg("--report-file")
+            .arg(&report);
+        // Coverage is a by-product of the campaign: without it the failures still stand
+        if let Err(e) = budget::run(&mut command, Some(Duration::from_secs(foundry.timeout))).await {
+            log::warn!("forge coverage failed: {}", e);
+            return None;
+        }
+        let lcov = match std::fs::read_to_string(&report) {
+            Ok(lcov) => lcov,
+            Err(_) => {
+                status!("  ⚠️  forge coverage wrote no report, so {} has no coverage", contract.name);
+                return None;
+            }
+        };
+        let source = format!("src/{}", source_file_name(&contract.name));
+        let files = fuzz_coverage::parse_lcov(&lcov);
+        let file = files.iter().find(|file| file.path.trim_start_matches("./") == source)?;
+        let coverage = fuzz_coverage::from_lcov(file, contract);
+        status!(
+            "  📈 forge coverage: {:.1}% of {} lines, {} function(s) never exercised",
+            coverage.coverage_percentage,
+            coverage.total_lines,
+            coverage.unreached().count()
+        );
+        Some(coverage)
     }
 
     /// Convert fuzzing results to vulnerabilities
@@ -354,26 +470,37 @@ impl FuzzEngine {
                 severity: severity.to_string(),
                 category: crate::report::vulnerability::VulnerabilityCategory::Fuzzing,
                 file_path: results.contract_name.clone(),
-                line_number: None,
+                line_number: failure.line_number,
                 code_snippet: Some(failure.input_data.clone()),
                 recommendation: Some("Review the failing test case and fix the underlying issue".to_string()),
-                references: vec!["Echidna Fuzzing".to_string()],
+                references: vec![match results.fuzzer.as_str() {
+                    "foundry" => "Foundry Fuzzing".to_string(),
+                    _ => "Echidna Fuzzing".to_string(),
+                }],
                 cwe_id: None,
                 tool: "FuzzEngine".to_string(),
                 confidence: 0.8,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             };
 
             vulnerabilities.push(vulnerability);
         }
 
-        // Add coverage-related recommendations
-        if results.coverage_report.coverage_percentage < 80.0 {
+        // Add coverage-related recommendations, only when the campaign measured it
+        let low_coverage = results
+            .coverage_report
+            .as_ref()
+            .filter(|coverage| coverage.coverage_percentage < self.fuzzing_config.coverage_threshold);
+        if let Some(coverage) = low_coverage {
             let coverage_issue = Vulnerability {
                 id: uuid::Uuid::new_v4().to_string(),
                 title: "Low Test Coverage".to_string(),
                 description: format!(
-                    "Test coverage is {}%, which is below the recommended 80% threshold",
-                    results.coverage_report.coverage_percentage
+                    "Fuzzing covered {:.1}% of the contract's function lines, below the {}% threshold",
+                    coverage.coverage_percentage, self.fuzzing_config.coverage_threshold
                 ),
                 severity: "Info".to_string(),
                 category: crate::report::vulnerability::VulnerabilityCategory::CodeQuality,
@@ -385,6 +512,10 @@ impl FuzzEngine {
                 cwe_id: None,
                 tool: "FuzzEngine".to_string(),
                 confidence: 1.0,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             };
 
             vulnerabilities.push(coverage_issue);
@@ -398,4 +529,525 @@ impl Default for FuzzEngine {
     fn default() -> Self {
         Self::new(crate::utils::config::Config::default())
     }
-}
\ No newline at end of file
+}
+
+/// Generated Echidna harness: a contract inheriting the target, with `echidna_` properties of its state
+struct EchidnaHarness {
+    /// Contract the harness inherits
+    target: String,
+    source: String,
+    properties: Vec<PropertyTest>,
+    /// Functions of the target by name, with their line
+    functions: HashMap<String, usize>,
+    /// Functions writing each state variable, or calling one that does
+    writers: HashMap<String, Vec<String>>,
+}
+
+/// How the harness reads a piece of the target's state
+struct StateAccess<'a> {
+    /// Expression reading it, `{}` standing for the account where it takes one
+    expression: String,
+    /// Variable holding it, when the source declares it
+    variable: Option<&'a UnitStateVariable>,
+}
+
+impl StateAccess<'_> {
+    fn of(&self, account: &str) -> String {
+        self.expression.replace("{}", account)
+    }
+}
+
+impl EchidnaHarness {
+    /// Properties of `unit`'s token supply, balances and owner, as far as its state has them
+    fn generate(contract: &ParsedContract, unit: &ContractUnit) -> Result<Self> {
+        let lineage = lineage(contract, unit);
+        let variables: Vec<&UnitStateVariable> = lineage.iter().flat_map(|unit| &unit.state_variables).collect();
+        let functions: Vec<&UnitFunction> = lineage.iter().flat_map(|unit| &unit.functions).collect();
+
+        let mut writers: HashMap<String, Vec<String>> = HashMap::new();
+        for variable in &variables {
+            let direct = writes(&functions, &variable.name)?;
+            let mut touching = direct.clone();
+            for writer in &direct {
+                let call = Regex::new(&format!(r"\b{}\s*\(", regex::escape(writer)))?;
+                for function in &functions {
+                    if !touching.contains(&function.name) && function.body.as_deref().is_some_and(|body| call.is_match(body)) {
+                        touching.push(function.name.clone());
+                    }
+                }
+            }
+            writers.insert(variable.name.clone(), touching);
+        }
+
+        let uint = |type_name: &str| type_name.starts_with("uint");
+        let supply = state_access(&variables, &functions, &["totalSupply", "_totalSupply"], uint, ("totalSupply", &[], "uint"));
+        let balances = state_access(
+            &variables,
+            &functions,
+            &["balances", "_balances", "balanceOf"],
+            |type_name| type_name.starts_with("mapping(address=>uint"),
+            ("balanceOf", &["address"], "uint"),
+        );
+        let cap = state_access(&variables, &functions, &["cap", "_cap", "maxSupply", "_maxSupply", "MAX_SUPPLY"], uint, ("cap", &[], "uint"));
+        let owner = state_access(
+            &variables,
+            &functions,
+            &["owner", "_owner", "admin", "_admin"],
+            |type_name| type_name == "address" || type_name == "addresspayable",
+            ("owner", &[], "address"),
+        );
+
+        let accounts: Vec<String> = ECHIDNA_SENDERS
+            .iter()
+            .chain(std::iter::once(&ECHIDNA_DEPLOYER))
+            .map(|account| format!("address(uint160({}))", account))
+            .chain(std::iter::once("address(this)".to_string()))
+            .collect();
+        let mut harness = Self { target: unit.name.clone(), source: String::new(), properties: Vec::new(), functions: HashMap::new(), writers };
+        for function in &functions {
+            harness.functions.entry(function.name.clone()).or_insert(function.line_number);
+        }
+        let supply_fixed = supply
+            .as_ref()
+            .and_then(|supply| supply.variable)
+            .is_some_and(|variable| harness.writers.get(&variable.name).is_none_or(|writers| writers.is_empty()));
+        let (mut fields, mut initializers, mut body) = (String::new(), String::new(), String::new());
+        let mut property = |name: &str, description: String, expected: &str, condition: String, variables: &[Option<&UnitStateVariable>]| {
+            let variables: Vec<&UnitStateVariable> = variables.iter().flatten().copied().collect();
+            body.push_str(&format!(
+                "\n    function {}() public view returns (bool) {{\n        return {};\n    }}\n",
+                name, condition
+            ));
+            harness.properties.push(PropertyTest {
+                name: name.to_string(),
+                description,
+                test_function: name.to_string(),
+                expected_behavior: expected.to_string(),
+                variables: variables.iter().map(|variable| variable.name.clone()).collect(),
+                line_number: variables.first().map(|variable| variable.line_number),
+            });
+        };
+
+        if let (Some(supply), Some(balances)) = (&supply, &balances) {
+            let each: Vec<String> = accounts.iter().map(|account| format!("{} <= {}", balances.of(account), supply.of(""))).collect();
+            let sum: Vec<String> = accounts.iter().map(|account| balances.of(account)).collect();
+            property(
+                "echidna_balances_within_total_supply",
+                format!("No balance of {}, nor their sum, exceeds the total supply", unit.name),
+                "Balances add up to at most the total supply",
+                format!("{} && {} <= {}", each.join(" && "), sum.join(" + "), supply.of("")),
+                &[balances.variable, supply.variable],
+            );
+        }
+        // A supply only the constructor sets must never change
+        if let Some(supply) = supply.as_ref().filter(|_| supply_fixed) {
+            fields.push_str("    uint256 internal secureChainInitialSupply;\n");
+            initializers.push_str(&format!("        secureChainInitialSupply = {};\n", supply.of("")));
+            property(
+                "echidna_total_supply_constant",
+                format!("The total supply of {} changes although only its constructor sets it", unit.name),
+                "The total supply stays what the constructor made it",
+                format!("{} == secureChainInitialSupply", supply.of("")),
+                &[supply.variable],
+            );
+        }
+        if let (Some(supply), Some(cap)) = (&supply, &cap) {
+            property(
+                "echidna_total_supply_within_cap",
+                format!("The total supply of {} exceeds its cap", unit.name),
+                "The total supply stays at or below the cap",
+                format!("{} <= {}", supply.of(""), cap.of("")),
+                &[supply.variable, cap.variable],
+            );
+        }
+        // Only the deployer owns the target, and it sends nothing during the campaign
+        if let Some(owner) = owner.as_ref().filter(|owner| !owner.variable.is_some_and(|variable| variable.is_constant || variable.is_immutable)) {
+            fields.push_str("    address internal secureChainInitialOwner;\n");
+            initializers.push_str(&format!("        secureChainInitialOwner = {};\n", owner.of("")));
+            property(
+                "echidna_owner_unchanged",
+                format!("An account other than the owner of {} changed its owner", unit.name),
+                "Only the owner can change the owner",
+                format!("{} == secureChainInitialOwner", owner.of("")),
+                &[owner.variable],
+            );
+        }
+
+        let mut pragmas: Vec<String> = contract.pragma_directives.iter().map(|pragma| format!("pragma {};\n", pragma.trim())).collect();
+        if !contract.pragma_directives.iter().any(|pragma| pragma.trim_start().starts_with("solidity")) {
+            pragmas.insert(0, "pragma solidity >=0.6.2;\n".to_string());
+        }
+        // Constructors needed a visibility before 0.7
+        let legacy = contract
+            .pragma_directives
+            .iter()
+            .any(|pragma| pragma.trim_start().starts_with("solidity") && ["0.4.", "0.5.", "0.6."].iter().any(|version| pragma.contains(version)));
+        let constructor = if initializers.is_empty() {
+            String::new()
+        } else {
+            format!("\n    constructor(){} {{\n{}    }}\n", if legacy { " public" } else { "" }, initializers)
+        };
+        harness.source = format!(
+            "// SPDX-License-Identifier: UNLICENSED\n// Generated by SecureChain: Echidna properties of {target}\n{pragmas}\nimport \"./{file}\";\n\ncontract {name} is {target} {{\n{fields}{constructor}{body}}}\n",
+            target = unit.name,
+            pragmas = pragmas.concat(),
+            file = source_file_name(&contract.name),
+            name = HARNESS_NAME,
+            fields = fields,
+            constructor = constructor,
+            body = body,
+        );
+        Ok(harness)
+    }
+
+    fn property_names(&self) -> Vec<&str> {
+        self.properties.iter().map(|property| property.name.as_str()).collect()
+    }
+
+    /// Result of each property, placing the failures among `failures` at the target's source
+    fn results(&self, failures: &mut [FuzzingFailure]) -> Vec<PropertyResult> {
+        self.properties
+            .iter()
+            .map(|property| {
+                let failure = failures.iter_mut().find(|failure| failure.test_case == property.test_function);
+                let counterexample = failure.map(|failure| {
+                    failure.line_number = self.locate(property, &failure.input_data);
+                    failure.error_message = format!("{}. Expected: {}", property.description, property.expected_behavior);
+                    failure.input_data.clone()
+                });
+                PropertyResult {
+                    property_name: property.name.clone(),
+                    passed: counterexample.is_none(),
+                    counterexample,
+                    iterations: ECHIDNA_TEST_LIMIT,
+                }
+            })
+            .collect()
+    }
+
+    /// Line of the last call of `sequence` writing a variable `property` checks, else of its last
+    /// call into the target, else of the variable
+    fn locate(&self, property: &PropertyTest, sequence: &str) -> Option<usize> {
+        let called: Vec<&str> = sequence.lines().rev().map(|call| call.split('(').next().unwrap_or(call).trim()).collect();
+        let writes_checked = |name: &str| {
+            property
+                .variables
+                .iter()
+                .any(|variable| self.writers.get(variable).is_some_and(|writers| writers.iter().any(|writer| writer == name)))
+        };
+        called
+            .iter()
+            .find(|name| writes_checked(name))
+            .or_else(|| called.iter().find(|name| self.functions.contains_key(**name)))
+            .and_then(|name| self.functions.get(*name).copied())
+            .or(property.line_number)
+    }
+}
+
+/// `unit` and the units of the same source it inherits from, nearest first
+fn lineage<'a>(contract: &'a ParsedContract, unit: &'a ContractUnit) -> Vec<&'a ContractUnit> {
+    let mut lineage = vec![unit];
+    let mut index = 0;
+    while index < lineage.len() {
+        for base in &lineage[index].inheritance {
+            if let Some(found) = contract.units.iter().find(|candidate| candidate.name == *base) {
+                if !lineage.iter().any(|known| known.name == found.name) {
+                    lineage.push(found);
+                }
+            }
+        }
+        index += 1;
+    }
+    lineage
+}
+
+/// How a harness reads the state variable named one of `names` with a matching type, directly
+/// when it is not private, else through the `(name, parameter types, return type)` getter
+fn state_access<'a>(
+    variables: &[&'a UnitStateVariable],
+    functions: &[&UnitFunction],
+    names: &[&str],
+    type_matches: impl Fn(&str) -> bool,
+    getter: (&str, &[&str], &str),
+) -> Option<StateAccess<'a>> {
+    let (getter, parameters, returns) = getter;
+    let variable = variables
+        .iter()
+        .copied()
+        .find(|variable| names.contains(&variable.name.as_str()) && type_matches(&compact(&variable.type_name)));
+    let takes_account = !parameters.is_empty();
+    if let Some(variable) = variable.filter(|variable| variable.visibility != "private") {
+        let expression = if takes_account { format!("{}[{{}}]", variable.name) } else { variable.name.clone() };
+        return Some(StateAccess { expression, variable: Some(variable) });
+    }
+
+    let function = functions.iter().find(|function| {
+        function.name == getter
+            && function.body.is_some()
+            && matches!(function.visibility.as_str(), "public" | "external")
+            && function.parameter_types.iter().map(|type_name| compact(type_name)).eq(parameters.iter().map(|type_name| type_name.to_string()))
+            && function.return_types.first().is_some_and(|type_name| compact(type_name).starts_with(returns))
+    })?;
+    // External functions are only callable through the contract's own address
+    let call = if function.visibility == "external" { format!("this.{}", getter) } else { getter.to_string() };
+    let expression = if takes_account { format!("{}({{}})", call) } else { format!("{}()", call) };
+    Some(StateAccess { expression, variable })
+}
+
+/// Names of the `functions` assigning to `variable`, an element of it or incrementing it
+fn writes(functions: &[&UnitFunction], variable: &str) -> Result<Vec<String>> {
+    let name = regex::escape(variable);
+    let assignment = Regex::new(&format!(
+        r"\b{name}\s*(?:\[[^\]]*\]\s*)*(?:\+\+|--|[-+*/|&^]?=(?:[^=]|$))|(?:\+\+|--)\s*{name}\b",
+        name = name
+    ))?;
+    Ok(functions
+        .iter()
+        .filter(|function| function.body.as_deref().is_some_and(|body| assignment.is_match(body)))
+        .map(|function| function.name.clone())
+        .collect())
+}
+
+/// Type name without whitespace, `mapping(address => uint256)` being `mapping(address=>uint256)`
+fn compact(type_name: &str) -> String {
+    type_name.split_whitespace().collect()
+}
+
+/// Generated forge test file, with the target function behind each test
+struct FoundrySuite {
+    source: String,
+    /// Contracts deployed in `setUp`
+    targets: Vec<String>,
+    /// Test function name to the target function it calls, or the property it checks
+    tests: HashMap<String, String>,
+}
+
+impl FoundrySuite {
+    /// Fuzz tests for every external function of the contracts in `contract` that deploy without arguments,
+    /// and invariant tests for their `echidna_` properties
+    ///
+    /// Reverts are expected and pass; a test fails when the call panics, i.e. on
+    /// a failed assert, overflow, division by zero or out-of-bounds access, or
+    /// when a property returns false.
+    fn generate(contract: &ParsedContract) -> Result<Self> {
+        let functions = selectors::external_functions(std::slice::from_ref(contract))?;
+        let mut suite = Self { source: String::new(), targets: Vec::new(), tests: HashMap::new() };
+        let mut body = String::new();
+
+        for unit in contract.units.iter().filter(|unit| unit.kind == "contract") {
+            if unit.constructor.as_ref().is_some_and(|constructor| !constructor.parameter_types.is_empty()) {
+                continue;
+            }
+            let target = format!("target{}", suite.targets.len());
+            let tests = suite.tests.len();
+
+            for function in functions.get(&unit.name).into_iter().flatten().filter(|function| !function.is_getter) {
+                let (name, types) = match function.signature.split_once('(') {
+                    Some((name, types)) => (name, types.trim_end_matches(')')),
+                    None => continue,
+                };
+                let types: Vec<&str> = if types.is_empty() { Vec::new() } else { types.split(',').collect() };
+                // Structs and function pointers have no fuzzable parameter form here
+                let declarations: Option<Vec<String>> = types
+                    .iter()
+                    .enumerate()
+                    .map(|(index, type_name)| parameter_declaration(type_name).map(|declaration| format!("{} a{}", declaration, index)))
+                    .collect();
+                let declarations = match declarations {
+                    Some(declarations) => declarations,
+                    None => continue,
+                };
+                if name.starts_with("echidna_") && types.is_empty() {
+                    let test = suite.unique_name(format!("invariant_{}_{}", unit.name, name));
+                    body.push_str(&format!(
+                        "\n    function {test}() public view {{\n        (bool ok, bytes memory data) = address({target}).staticcall(abi.encodeWithSignature(\"{signature}\"));\n        require(ok && data.length >= 32 && abi.decode(data, (bool)), \"property returned false\");\n    }}\n",
+                        test = test,
+                        target = target,
+                        signature = function.signature,
+                    ));
+                    suite.tests.insert(test, format!("{}.{}", unit.name, function.signature));
+                    continue;
+                }
+
+                let arguments: Vec<String> = (0..types.len()).map(|index| format!(", a{}", index)).collect();
+                let test = suite.unique_name(format!("testFuzz_{}_{}", unit.name, name));
+                body.push_str(&format!(
+                    "\n    function {test}({declarations}) public {{\n        (bool ok, bytes memory data) = address({target}).call(abi.encodeWithSignature(\"{signature}\"{arguments}));\n        _checkPanic(ok, data);\n    }}\n",
+                    test = test,
+                    declarations = declarations.join(", "),
+                    target = target,
+                    signature = function.signature,
+                    arguments = arguments.concat(),
+                ));
+                suite.tests.insert(test, format!("{}.{}", unit.name, function.signature));
+            }
+            if suite.tests.len() > tests {
+                suite.targets.push(unit.name.clone());
+            }
+        }
+
+        let declarations: String = suite
+            .targets
+            .iter()
+            .enumerate()
+            .map(|(index, name)| format!("    {} internal target{};\n", name, index))
+            .collect();
+        let deployments: String = suite
+            .targets
+            .iter()
+            .enumerate()
+            .map(|(index, name)| format!("        target{} = new {}();\n", index, name))
+            .collect();
+        suite.source = format!(
+            "// SPDX-License-Identifier: UNLICENSED\n// Generated by SecureChain\npragma solidity >=0.6.2;\n\nimport \"../src/{file}\";\n\ncontract SecureChainFuzz {{\n{declarations}\n    function setUp() public {{\n{deployments}    }}\n{body}\n    function _checkPanic(bool ok, bytes memory data) internal pure {{\n        if (ok || data.length != 36) {{\n            return;\n        }}\n        bytes4 selector;\n        uint256 code;\n        assembly {{\n            selector := mload(add(data, 32))\n            code := mload(add(data, 36))\n        }}\n        if (selector == 0x{panic}) {{\n            bytes memory digits = \"0123456789abcdef\";\n            revert(string(abi.encodePacked(\"panic 0x\", digits[(code >> 4) & 15], digits[code & 15])));\n        }}\n    }}\n}}\n",
+            file = source_file_name(&contract.name),
+            declarations = declarations,
+            deployments = deployments,
+            body = body,
+            panic = PANIC_SELECTOR,
+        );
+        Ok(suite)
+    }
+
+    /// `name`, numbered when an overload already took it
+    fn unique_name(&self, name: String) -> String {
+        let name: String = name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' }).collect();
+        if !self.tests.contains_key(&name) {
+            return name;
+        }
+        (2..).map(|index| format!("{}_{}", name, index)).find(|numbered| !self.tests.contains_key(numbered)).unwrap_or(name)
+    }
+
+    /// Failures and number of inputs tried from `forge test --json` output, `None` when there is none
+    fn parse_output(&self, output: &str) -> Option<(Vec<FuzzingFailure>, u32)> {
+        // Compiler warnings may precede the JSON document
+        let start = output.find('{')?;
+        let suites: HashMap<String, Value> = serde_json::from_str(output[start..].trim()).ok()?;
+
+        let mut failures = Vec::new();
+        let mut inputs = 0u64;
+        for suite in suites.values() {
+            let results = match suite.get("test_results").and_then(Value::as_object) {
+                Some(results) => results,
+                None => continue,
+            };
+            for (test, result) in results {
+                let kind = result.get("kind");
+                inputs += kind
+                    .and_then(|kind| kind.pointer("/Fuzz/runs").or_else(|| kind.pointer("/Invariant/calls")))
+                    .and_then(Value::as_u64)
+                    .unwrap_or(1);
+                if result.get("status").and_then(Value::as_str) != Some("Failure") {
+                    continue;
+                }
+                failures.push(self.failure(test, result));
+            }
+        }
+        Some((failures, inputs.min(u32::MAX as u64) as u32))
+    }
+
+    /// Failure record of the failed test `test`
+    fn failure(&self, test: &str, result: &Value) -> FuzzingFailure {
+        let name = test.split('(').next().unwrap_or(test);
+        let target = self.tests.get(name).cloned().unwrap_or_else(|| name.to_string());
+        let reason = result.get("reason").and_then(Value::as_str).unwrap_or("").trim();
+
+        let (failure_type, error_message) = if name.starts_with("invariant_") {
+            ("Property violation", format!("{} returned false after a sequence of calls", target))
+        } else if let Some(code) = reason.strip_prefix("panic 0x") {
+            // Solidity raises Panic for assert-style errors
+            ("Assertion failure", format!("{} panicked: {} (0x{})", target, panic_description(code), code))
+        } else if reason.is_empty() {
+            ("Revert", format!("{} failed", target))
+        } else {
+            ("Revert", format!("{} failed: {}", target, reason))
+        };
+
+        let gas_used = result.get("kind").and_then(|kind| {
+            kind.pointer("/Fuzz/mean_gas")
+                .or_else(|| kind.pointer("/Unit/gas"))
+                .and_then(Value::as_u64)
+        });
+        let logs: Vec<&str> = result
+            .get("decoded_logs")
+            .and_then(Value::as_array)
+            .map(|logs| logs.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        FuzzingFailure {
+            test_case: target.clone(),
+            failure_type: failure_type.to_string(),
+            error_message,
+            input_data: result
+                .get("counterexample")
+                .filter(|counterexample| !counterexample.is_null())
+                .map(|counterexample| counterexample_text(&target, counterexample))
+                .unwrap_or_else(|| "N/A".to_string()),
+            gas_used,
+            stack_trace: if logs.is_empty() { None } else { Some(logs.join("\n")) },
+            line_number: None,
+        }
+    }
+}
+
+/// File name the contract's source is written to in the generated project
+fn source_file_name(name: &str) -> String {
+    let file = std::path::Path::new(name).file_name().map(|file| file.to_string_lossy().to_string()).unwrap_or_default();
+    if file.ends_with(".sol") {
+        file
+    } else {
+        format!("{}.sol", if file.is_empty() { "Target" } else { file.as_str() })
+    }
+}
+
+/// Parameter declaration for a canonical ABI type, `None` for types fuzz tests cannot take
+fn parameter_declaration(type_name: &str) -> Option<String> {
+    if type_name.contains('(') || type_name.starts_with("function") {
+        return None;
+    }
+    if type_name.contains('[') || type_name == "string" || type_name == "bytes" {
+        Some(format!("{} memory", type_name))
+    } else {
+        Some(type_name.to_string())
+    }
+}
+
+/// Inputs of a forge counterexample, a single call or the sequence that broke an invariant
+fn counterexample_text(target: &str, counterexample: &Value) -> String {
+    let call = |call: &Value| -> String {
+        let arguments = call.get("args").and_then(Value::as_str);
+        let calldata = call.get("calldata").and_then(Value::as_str).unwrap_or("");
+        match (call.get("signature").and_then(Value::as_str), arguments) {
+            (Some(signature), Some(arguments)) if signature.starts_with("testFuzz_") => {
+                format!("{}({})", target.split('(').next().unwrap_or(target), arguments)
+            }
+            (Some(signature), Some(arguments)) => {
+                format!("{}({})", signature.split('(').next().unwrap_or(signature), arguments)
+            }
+            _ => calldata.to_string(),
+        }
+    };
+    if let Some(single) = counterexample.get("Single") {
+        call(single)
+    } else if let Some(sequence) = counterexample.get("Sequence").and_then(Value::as_array) {
+        sequence.iter().map(call).collect::<Vec<_>>().join("\n")
+    } else {
+        call(counterexample)
+    }
+}
+
+/// What Solidity panics with `code` (hex) for
+fn panic_description(code: &str) -> &'static str {
+    match code {
+        "01" => "assertion failed",
+        "11" => "arithmetic overflow or underflow",
+        "12" => "division or modulo by zero",
+        "21" => "invalid enum value",
+        "22" => "corrupt storage byte array",
+        "31" => "pop on an empty array",
+        "32" => "array index out of bounds",
+        "41" => "out of memory",
+        "51" => "call to an uninitialized function",
+        _ => "panic",
+    }
+}
@@ -0,0 +1,591 @@
+//! Gas optimization analysis
+//!
+//! Storage is what makes most contracts expensive to use: reading a slot
+//! costs 100 to 2100 gas where reading memory or the stack costs 3. These
+//! checks look for what gas reviews point out on nearly every audit: state
+//! read or written on every iteration of a loop, the same state variable
+//! read several times in one function, structs whose field order leaves
+//! storage slots half empty, external functions copying read-only array and
+//! struct arguments to memory, and state variables that never change after
+//! deployment and could be constants or immutables. They work on source
+//! text, so they suggest rather than prove, and every finding is
+//! informational. The run also gets a gas optimization score: the share of
+//! functions, structs and state variables with nothing to point out.
+
+use regex::Regex;
+use std::collections::HashSet;
+use std::ops::Range;
+
+use crate::core::detectors;
+use crate::core::parser::{matching_brace, matching_paren, split_top_level, ContractUnit, ParsedContract, UnitFunction, UnitStateVariable};
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+
+pub const TOOL_NAME: &str = "Gas Analysis";
+
+/// Names listed in a finding before the rest are only counted
+const LISTED_NAMES: usize = 10;
+
+/// Bytes in a storage slot
+const SLOT_BYTES: usize = 32;
+
+/// Report storage access in loops, repeated storage reads, unpacked structs,
+/// memory parameters that could be calldata and state variables that could
+/// be constant or immutable
+pub fn check_gas(contract: &ParsedContract) -> Vec<Vulnerability> {
+    review(contract).findings
+}
+
+/// Gas optimization score of the Solidity sources of a run, 0 to 100: the
+/// share of functions, structs and mutable state variables without a finding
+pub fn score(contracts: &[ParsedContract]) -> f64 {
+    let (inspected, flagged) = contracts
+        .iter()
+        .map(review)
+        .fold((0, 0), |(inspected, flagged), review| (inspected + review.inspected, flagged + review.flagged));
+    if inspected == 0 {
+        return 100.0;
+    }
+    (inspected - flagged) as f64 * 100.0 / inspected as f64
+}
+
+/// Findings of one source file, and how many of its functions, structs and
+/// state variables were looked at and how many have a finding
+struct Review {
+    findings: Vec<Vulnerability>,
+    inspected: usize,
+    flagged: usize,
+}
+
+fn review(contract: &ParsedContract) -> Review {
+    let mut review = Review { findings: Vec::new(), inspected: 0, flagged: 0 };
+    if contract.is_vyper() {
+        return review;
+    }
+    let types = Types::new(contract);
+
+    for unit in contract.units.iter().filter(|unit| unit.kind != "interface") {
+        let state: Vec<&UnitStateVariable> = detectors::lineage(contract, unit)
+            .into_iter()
+            .flat_map(|base| base.state_variables.iter())
+            .filter(|variable| !variable.is_constant && !variable.is_immutable)
+            .collect();
+
+        for function in &unit.functions {
+            let body = match function.body.as_deref() {
+                Some(body) => body,
+                None => continue,
+            };
+            let scope = FunctionScope { contract, unit, function, body, state: &state, types: &types };
+            let findings: Vec<Vulnerability> =
+                [scope.storage_in_loops(), scope.repeated_reads(), scope.calldata_parameters()].into_iter().flatten().collect();
+            review.inspected += 1;
+            review.flagged += !findings.is_empty() as usize;
+            review.findings.extend(findings);
+        }
+
+        let (inspected, candidates) = constant_candidates(contract, unit, &types);
+        review.inspected += inspected;
+        if let Some((count, finding)) = candidates {
+            review.flagged += count;
+            review.findings.push(finding);
+        }
+    }
+
+    for definition in &types.structs {
+        review.inspected += 1;
+        if let Some(finding) = unpacked_struct(contract, definition, &types) {
+            review.flagged += 1;
+            review.findings.push(finding);
+        }
+    }
+    review
+}
+
+/// Struct, enum and contract names of a source file, for sizing types
+struct Types {
+    structs: Vec<StructDefinition>,
+    enums: HashSet<String>,
+    contracts: HashSet<String>,
+}
+
+struct StructDefinition {
+    name: String,
+    line: usize,
+    /// Field types in declaration order
+    fields: Vec<String>,
+}
+
+impl Types {
+    fn new(contract: &ParsedContract) -> Self {
+        let code = contract.code();
+        let struct_pattern = Regex::new(r"\bstruct\s+(\w+)\s*\{").expect("valid struct pattern");
+        let structs = struct_pattern
+            .captures_iter(code)
+            .filter_map(|captures| {
+                let whole = captures.get(0)?;
+                let open = whole.end() - 1;
+                let close = matching_brace(code, open)?;
+                let fields = code[open + 1..close]
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|field| !field.is_empty())
+                    .map(|field| field.rsplit_once(char::is_whitespace).map_or(field, |(type_name, _)| type_name.trim()))
+                    .map(|type_name| type_name.split_whitespace().collect::<Vec<_>>().join(" "))
+                    .collect();
+                Some(StructDefinition { name: captures[1].to_string(), line: line_at(code, whole.start()), fields })
+            })
+            .collect();
+        let enum_pattern = Regex::new(r"\benum\s+(\w+)").expect("valid enum pattern");
+        Self {
+            structs,
+            enums: enum_pattern.captures_iter(code).map(|captures| captures[1].to_string()).collect(),
+            contracts: contract.units.iter().map(|unit| unit.name.clone()).collect(),
+        }
+    }
+
+    /// Bytes a value of `type_name` takes in storage, `None` for types that
+    /// take whole slots: mappings, arrays, strings, bytes and structs
+    fn size(&self, type_name: &str) -> Option<usize> {
+        let type_name = type_name.trim();
+        if !self.is_value_type(type_name) {
+            return None;
+        }
+        let bits = |prefix: &str| type_name.strip_prefix(prefix).and_then(|bits| bits.parse::<usize>().ok());
+        let bytes = type_name.as_bytes();
+        match type_name {
+            "bool" => Some(1),
+            "address" | "address payable" => Some(20),
+            "uint" | "int" => Some(SLOT_BYTES),
+            _ => match bits("uint").or_else(|| bits("int")) {
+                Some(bits) => Some(bits / 8),
+                None if bits("bytes").is_some() => bits("bytes"),
+                None if self.enums.contains(type_name) => Some(1),
+                // Contracts and interfaces are addresses, including interfaces declared elsewhere, e.g. IERC20
+                None if self.contracts.contains(type_name) || (bytes.len() > 1 && bytes[0] == b'I' && bytes[1].is_ascii_uppercase()) => {
+                    Some(20)
+                }
+                None => None,
+            },
+        }
+    }
+
+    /// Whether reading a variable of `type_name` reads a single slot
+    fn is_value_type(&self, type_name: &str) -> bool {
+        !type_name.starts_with("mapping")
+            && !type_name.contains('[')
+            && !matches!(type_name, "string" | "bytes")
+            && !self.structs.iter().any(|definition| definition.name == type_name)
+    }
+}
+
+/// A function body under analysis
+struct FunctionScope<'a> {
+    contract: &'a ParsedContract,
+    unit: &'a ContractUnit,
+    function: &'a UnitFunction,
+    body: &'a str,
+    /// Mutable state variables of the unit and its bases
+    state: &'a [&'a UnitStateVariable],
+    types: &'a Types,
+}
+
+impl FunctionScope<'_> {
+    fn line(&self, offset: usize) -> usize {
+        self.function.body_line_number + self.body[..offset].matches('\n').count()
+    }
+
+    fn label(&self) -> String {
+        format!("{}.{}", self.unit.name, self.function.name)
+    }
+
+    /// State variables of the unit the function does not shadow with a parameter
+    fn state(&self) -> impl Iterator<Item = &UnitStateVariable> + '_ {
+        self.state.iter().copied().filter(|variable| !self.function.parameter_names.contains(&variable.name))
+    }
+
+    /// State read or written on every iteration of a loop
+    fn storage_in_loops(&self) -> Option<Vulnerability> {
+        let loops = loops(self.body);
+        if loops.is_empty() {
+            return None;
+        }
+
+        let mut accessed = Vec::new();
+        let mut first = None;
+        for variable in self.state() {
+            // Each key of a mapping is its own slot, so there is nothing to cache
+            if variable.type_name.starts_with("mapping") {
+                continue;
+            }
+            let array = variable.type_name.contains('[');
+            let hit = loops
+                .iter()
+                .flat_map(|region| occurrences(&self.body[region.clone()], &variable.name).into_iter().map(move |at| region.start + at))
+                // Elements of an array differ from one iteration to the next, its length does not
+                .find(|at| !array || self.body[at + variable.name.len()..].trim_start().starts_with(".length"));
+            if let Some(at) = hit {
+                accessed.push(if array { format!("`{}.length`", variable.name) } else { format!("`{}`", variable.name) });
+                first = Some(first.map_or(at, |first: usize| first.min(at)));
+            }
+        }
+
+        let first = first?;
+        Some(finding(
+            &self.contract.name,
+            self.line(first),
+            0.7,
+            Some("CWE-1050"),
+            format!("Storage Access in Loop: {}", self.label()),
+            format!(
+                "{} reads or writes {} on every iteration of a loop. Each access is a storage operation of at least 100 gas where a local variable costs 3, so the cost grows with the number of iterations.",
+                self.label(),
+                name_list(&accessed)
+            ),
+            "Copy the value into a local variable before the loop, use the local inside it, and write the result back to storage once after the loop.",
+        ))
+    }
+
+    /// The same state variable read more than once outside loops, and never written
+    fn repeated_reads(&self) -> Option<Vulnerability> {
+        let loops = loops(self.body);
+        let mut repeated = Vec::new();
+        let mut first = None;
+        for variable in self.state().filter(|variable| self.types.is_value_type(&variable.type_name)) {
+            let found = occurrences(self.body, &variable.name);
+            if found.iter().any(|at| is_write(self.body, *at, &variable.name)) {
+                continue;
+            }
+            let reads: Vec<usize> = found.into_iter().filter(|at| !loops.iter().any(|region| region.contains(at))).collect();
+            if reads.len() > 1 {
+                repeated.push(format!("`{}` {} times", variable.name, reads.len()));
+                first = Some(first.map_or(reads[0], |first: usize| first.min(reads[0])));
+            }
+        }
+
+        let first = first?;
+        Some(finding(
+            &self.contract.name,
+            self.line(first),
+            0.7,
+            None,
+            format!("Repeated Storage Reads: {}", self.label()),
+            format!(
+                "{} reads {} from storage. Every read after the first is a warm storage read of 100 gas, where a local variable costs 3.",
+                self.label(),
+                name_list(&repeated)
+            ),
+            "Read each variable once into a local variable and use the local for the rest of the function.",
+        ))
+    }
+
+    /// Parameters of an external function copied to memory although the function never modifies them
+    fn calldata_parameters(&self) -> Option<Vulnerability> {
+        if self.function.visibility != "external" || self.body.contains("assembly") {
+            return None;
+        }
+        let unmodified: Vec<String> = split_top_level(self.parameters()?, ',')
+            .iter()
+            .filter_map(|parameter| {
+                let words: Vec<&str> = parameter.split_whitespace().collect();
+                match words.as_slice() {
+                    [_, .., "memory", name] => Some(name.to_string()),
+                    _ => None,
+                }
+            })
+            .filter(|name| !occurrences(self.body, name).iter().any(|at| is_write(self.body, *at, name)))
+            .map(|name| format!("`{}`", name))
+            .collect();
+        if unmodified.is_empty() {
+            return None;
+        }
+
+        Some(finding(
+            &self.contract.name,
+            self.function.line_number,
+            0.8,
+            None,
+            format!("Memory Parameter Could Be Calldata: {}", self.label()),
+            format!(
+                "{} takes {} as memory but never modifies {}, so each call pays for copying the argument from calldata to memory.",
+                self.label(),
+                name_list(&unmodified),
+                if unmodified.len() == 1 { "it" } else { "them" }
+            ),
+            "Declare read-only array, string, bytes and struct parameters of external functions as calldata.",
+        ))
+    }
+
+    /// Parameter list of the function's header
+    fn parameters(&self) -> Option<&str> {
+        let code = self.contract.code();
+        let start = self.contract.offset_of(self.function.body.as_ref()?)?;
+        let header = &code[..start];
+        let at = header.rfind(&format!("function {}", self.function.name))?;
+        let open = at + header[at..].find('(')?;
+        let close = matching_paren(code, open)?;
+        Some(&code[open + 1..close])
+    }
+}
+
+/// State variables of `unit` that no function changes: how many were looked
+/// at, and how many could be constant or immutable with the finding that lists them
+fn constant_candidates(contract: &ParsedContract, unit: &ContractUnit, types: &Types) -> (usize, Option<(usize, Vulnerability)>) {
+    // Any unit of the file may write an internal variable it inherits
+    let functions: Vec<&str> = contract
+        .units
+        .iter()
+        .flat_map(|unit| unit.functions.iter())
+        .filter_map(|function| function.body.as_deref())
+        .chain(contract.modifiers.iter().map(|modifier| &*modifier.body))
+        .collect();
+    let constructors: Vec<&str> =
+        contract.units.iter().filter_map(|unit| unit.constructor.as_ref()).filter_map(|constructor| constructor.body.as_deref()).collect();
+    let written = |bodies: &[&str], name: &str| bodies.iter().any(|body| occurrences(body, name).iter().any(|at| is_write(body, *at, name)));
+
+    let mut inspected = 0;
+    let mut candidates = Vec::new();
+    let mut first = None;
+    for variable in unit.state_variables.iter().filter(|variable| !variable.is_constant && !variable.is_immutable) {
+        if !types.is_value_type(&variable.type_name) {
+            continue;
+        }
+        inspected += 1;
+        if written(&functions, &variable.name) {
+            continue;
+        }
+        let initial_value = contract
+            .state_variables
+            .iter()
+            .find(|declared| declared.name == variable.name && declared.line_number == variable.line_number)
+            .and_then(|declared| declared.initial_value.as_deref());
+        let kind = match (initial_value, written(&constructors, &variable.name)) {
+            // Values only known at deployment can still be immutable
+            (Some(value), false)
+                if ["msg.", "block.", "tx.", "this", "gasleft"].iter().any(|runtime| value.contains(runtime)) =>
+            {
+                "immutable"
+            }
+            (Some(_), false) => "constant",
+            (None, true) => "immutable",
+            // Never set, or set twice, which an immutable cannot be
+            _ => continue,
+        };
+        candidates.push(format!("`{}` could be {}", variable.name, kind));
+        first = Some(first.map_or(variable.line_number, |first: usize| first.min(variable.line_number)));
+    }
+
+    let first = match first {
+        Some(first) => first,
+        None => return (inspected, None),
+    };
+    let finding = finding(
+        &contract.name,
+        first,
+        0.7,
+        None,
+        format!("Constant or Immutable Candidates in {}", unit.name),
+        format!(
+            "{} state variable{} of {} never change{} after deployment: {}. Every read is a storage read of up to 2100 gas, where constants and immutables are part of the bytecode and cost 3.",
+            candidates.len(),
+            if candidates.len() == 1 { "" } else { "s" },
+            unit.name,
+            if candidates.len() == 1 { "s" } else { "" },
+            name_list(&candidates)
+        ),
+        "Declare variables fixed at compile time as constant and variables set once in the constructor as immutable.",
+    );
+    (inspected, Some((candidates.len(), finding)))
+}
+
+/// A struct whose fields would fit in fewer storage slots in another order
+fn unpacked_struct(contract: &ParsedContract, definition: &StructDefinition, types: &Types) -> Option<Vulnerability> {
+    let sizes: Vec<Option<usize>> = definition.fields.iter().map(|field| types.size(field)).collect();
+    let declared = declared_slots(&sizes);
+    let packed = packed_slots(&sizes);
+    if packed >= declared {
+        return None;
+    }
+
+    Some(finding(
+        &contract.name,
+        definition.line,
+        0.8,
+        None,
+        format!("Unpacked Struct: {}", definition.name),
+        format!(
+            "{} takes {} storage slots in the order its fields are declared, and {} with small fields placed next to each other. Every slot saved is 20000 gas less when a new {} is stored and 2100 less when one is read cold.",
+            definition.name, declared, packed, definition.name
+        ),
+        "Order the fields so that those smaller than 32 bytes (addresses, bools, small integers) are adjacent and fill whole slots together.",
+    ))
+}
+
+/// Slots of fields of these sizes in the given order
+fn declared_slots(sizes: &[Option<usize>]) -> usize {
+    let mut slots = 0;
+    let mut used = 0;
+    for size in sizes {
+        match size {
+            Some(size) if used + size <= SLOT_BYTES => used += size,
+            Some(size) => {
+                slots += 1;
+                used = *size;
+            }
+            None => {
+                slots += (used > 0) as usize + 1;
+                used = 0;
+            }
+        }
+    }
+    slots + (used > 0) as usize
+}
+
+/// Slots of fields of these sizes placed largest first, each in the first slot it fits
+fn packed_slots(sizes: &[Option<usize>]) -> usize {
+    let mut packable: Vec<usize> = sizes.iter().flatten().copied().collect();
+    packable.sort_unstable_by(|a, b| b.cmp(a));
+    let mut slots: Vec<usize> = Vec::new();
+    for size in packable {
+        match slots.iter_mut().find(|used| **used + size <= SLOT_BYTES) {
+            Some(used) => *used += size,
+            None => slots.push(size),
+        }
+    }
+    slots.len() + sizes.iter().filter(|size| size.is_none()).count()
+}
+
+/// Byte ranges of the loops of a body that run on every iteration: the
+/// condition, increment and body, without a for loop's initializer; nested
+/// loops are part of the outer one
+fn loops(body: &str) -> Vec<Range<usize>> {
+    let pattern = Regex::new(r"\b(?:for|while)\s*\(").expect("valid loop pattern");
+    let mut regions: Vec<Range<usize>> = Vec::new();
+    for m in pattern.find_iter(body) {
+        if regions.iter().any(|region| region.contains(&m.start())) {
+            continue;
+        }
+        let close = match matching_paren(body, m.end() - 1) {
+            Some(close) => close,
+            None => continue,
+        };
+        let start = match body[m.start()..].starts_with("for") {
+            true => body[m.end()..close].find(';').map_or(m.start(), |semicolon| m.end() + semicolon),
+            false => m.start(),
+        };
+        let rest = &body[close + 1..];
+        let open = close + 1 + (rest.len() - rest.trim_start().len());
+        let end = if body[open..].starts_with('{') {
+            matching_brace(body, open).unwrap_or(body.len())
+        } else {
+            open + body[open..].find(';').unwrap_or(body.len() - open)
+        };
+        regions.push(start..end);
+    }
+    regions
+}
+
+/// Offsets of `name` in `text` as an identifier of its own, not a member of something else
+fn occurrences(text: &str, name: &str) -> Vec<usize> {
+    let identifier = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    text.match_indices(name)
+        .map(|(at, _)| at)
+        .filter(|at| {
+            let before = text[..*at].chars().next_back();
+            let after = text[at + name.len()..].chars().next();
+            !before.is_some_and(|c| identifier(c) || c == '.') && !after.is_some_and(identifier)
+        })
+        .collect()
+}
+
+/// Whether the occurrence of `name` at `at` is assigned, incremented, deleted,
+/// pushed to or popped, directly or through indexes and members
+fn is_write(text: &str, at: usize, name: &str) -> bool {
+    let before = text[..at].trim_end();
+    if before.ends_with("++") || before.ends_with("--") || before.ends_with("delete") {
+        return true;
+    }
+    let mut rest = text[at + name.len()..].trim_start();
+    loop {
+        if rest.starts_with('[') {
+            match closing_bracket(rest) {
+                Some(close) => rest = rest[close + 1..].trim_start(),
+                None => return false,
+            }
+        } else if let Some(member) = rest.strip_prefix('.') {
+            let member = member.trim_start();
+            let length = member.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(member.len());
+            if matches!(&member[..length], "push" | "pop") {
+                return true;
+            }
+            rest = member[length..].trim_start();
+        } else {
+            break;
+        }
+    }
+    rest.starts_with("++")
+        || rest.starts_with("--")
+        || (["=", "+=", "-=", "*=", "/=", "%=", "|=", "&=", "^=", "<<=", ">>="].iter().any(|op| rest.starts_with(op))
+            && !rest.starts_with("==")
+            && !rest.starts_with("=>"))
+}
+
+/// Offset of the `]` closing the `[` `text` starts with
+fn closing_bracket(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (index, c) in text.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn line_at(source: &str, offset: usize) -> usize {
+    source[..offset].matches('\n').count() + 1
+}
+
+/// Names, the first few listed and the rest counted
+fn name_list(names: &[String]) -> String {
+    let listed = names.iter().take(LISTED_NAMES).cloned().collect::<Vec<_>>().join(", ");
+    match names.len().saturating_sub(LISTED_NAMES) {
+        0 => listed,
+        more => format!("{} and {} more", listed, more),
+    }
+}
+
+fn finding(
+    file: &str,
+    line_number: usize,
+    confidence: f64,
+    cwe_id: Option<&str>,
+    title: String,
+    description: String,
+    recommendation: &str,
+) -> Vulnerability {
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title,
+        description,
+        severity: "Info".to_string(),
+        category: VulnerabilityCategory::GasOptimization,
+        file_path: file.to_string(),
+        line_number: Some(line_number),
+        code_snippet: None,
+        recommendation: Some(recommendation.to_string()),
+        references: vec!["https://docs.soliditylang.org/en/latest/internals/layout_in_storage.html".to_string()],
+        cwe_id: cwe_id.map(str::to_string),
+        tool: TOOL_NAME.to_string(),
+        confidence,
+        provenance: None,
+        severity_overrides: Vec::new(),
+        cvss: None,
+        detected_by: Vec::new(),
+    }
+}
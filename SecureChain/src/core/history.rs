@@ -0,0 +1,351 @@
+//! Run history and ETA estimation
+//!
+//! A deep audit of a large project can take hours where a standard scan of
+//! the same code takes minutes, and nothing told the user which of the two
+//! they had started. Every analysis and audit now appends its size, the
+//! external tools it could use and how long each stage took to a history
+//! file in `general.data_dir`. A later run with the same target,
+//! depth and options predicts each stage from the time per line of code of
+//! the recent comparable runs, shows the ETA with a range when it starts and
+//! updates it as stages complete. The file holds sizes and durations only,
+//! no paths, contract names or findings.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::status;
+use crate::utils::artifact;
+use crate::utils::cache;
+use crate::utils::config::Config;
+use crate::utils::platform;
+
+/// Version of the file layout, bumped on incompatible changes
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Oldest runs are dropped beyond this many
+const MAX_RUNS: usize = 500;
+
+/// Comparable runs a prediction is based on, most recent first
+const SAMPLE_RUNS: usize = 20;
+
+/// Comparable runs with the same tool set needed before runs with other tool sets are left out
+const MIN_SAME_TOOLS: usize = 3;
+
+/// Least time between two ETA updates
+const UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// External tools whose presence changes how long a run takes
+const TIMED_TOOLS: &[&str] = &["slither", "myth", "echidna", "solhint", "semgrep", "solc"];
+
+/// Every recorded run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunHistory {
+    pub schema_version: u32,
+    pub runs: Vec<RunRecord>,
+}
+
+/// One finished analysis or audit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub recorded_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub profile: RunProfile,
+    pub lines_of_code: usize,
+    pub stages: BTreeMap<String, StageRecord>,
+    pub total_ms: u64,
+}
+
+/// Time spent in one stage, and the lines it covered
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct StageRecord {
+    pub duration_ms: u64,
+    /// Lines of code the stage ran on, without those reused from the cache
+    pub lines: usize,
+}
+
+/// What makes two runs comparable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunProfile {
+    /// "analyze" or "audit"
+    pub command: String,
+    pub target: String,
+    pub depth: String,
+    pub ai: bool,
+    pub fuzz: bool,
+    /// External tools that were installed
+    pub tools: Vec<String>,
+}
+
+impl RunProfile {
+    pub fn new(command: &str, target: &str, depth: &str, ai: bool, fuzz: bool) -> Self {
+        Self {
+            command: command.to_string(),
+            target: target.to_string(),
+            depth: depth.to_string(),
+            ai,
+            fuzz,
+            tools: TIMED_TOOLS
+                .iter()
+                .filter(|tool| platform::is_tool_available(tool))
+                .map(|tool| tool.to_string())
+                .collect(),
+        }
+    }
+
+    fn matches(&self, other: &RunProfile) -> bool {
+        self.command == other.command
+            && self.target == other.target
+            && self.depth == other.depth
+            && self.ai == other.ai
+            && self.fuzz == other.fuzz
+    }
+}
+
+/// Predicted remaining time, with the range earlier runs spread over
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Estimate {
+    pub expected: Duration,
+    pub low: Duration,
+    pub high: Duration,
+}
+
+impl std::fmt::Display for Estimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({} – {})", format_duration(self.expected), format_duration(self.low), format_duration(self.high))
+    }
+}
+
+/// Times the stages of one run, keeps its ETA current and records it when done
+pub struct StageTimer {
+    /// History file the run is recorded in
+    path: PathBuf,
+    profile: RunProfile,
+    lines_of_code: usize,
+    started: Instant,
+    /// End of the last finished stage
+    mark: Instant,
+    last_update: Instant,
+    stages: BTreeMap<String, StageRecord>,
+    /// Lines of code each stage has covered, including reused ones
+    covered: BTreeMap<String, usize>,
+    /// Milliseconds per line of each stage in comparable runs, sorted
+    rates: BTreeMap<String, Vec<f64>>,
+}
+
+impl StageTimer {
+    /// Start timing a run over `lines_of_code` lines and show its ETA
+    pub fn start(config: &Config, profile: RunProfile, lines_of_code: usize) -> Self {
+        let path = history_path(config);
+        let runs = read(&path).ok().flatten().map(|history| history.runs).unwrap_or_default();
+        let comparable: Vec<&RunRecord> = runs.iter().rev().filter(|run| run.profile.matches(&profile)).collect();
+        let same_tools: Vec<&RunRecord> = comparable.iter().copied().filter(|run| run.profile.tools == profile.tools).collect();
+        let samples = if same_tools.len() >= MIN_SAME_TOOLS { same_tools } else { comparable };
+
+        let mut rates: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        for run in samples.iter().take(SAMPLE_RUNS) {
+            for (stage, record) in &run.stages {
+                if record.lines > 0 {
+                    rates.entry(stage.clone()).or_default().push(record.duration_ms as f64 / record.lines as f64);
+                }
+            }
+        }
+        for stage_rates in rates.values_mut() {
+            stage_rates.sort_by(|a, b| a.total_cmp(b));
+        }
+
+        let now = Instant::now();
+        let timer = Self {
+            path,
+            profile,
+            lines_of_code,
+            started: now,
+            mark: now,
+            last_update: now,
+            stages: BTreeMap::new(),
+            covered: BTreeMap::new(),
+            rates,
+        };
+        match timer.remaining() {
+            Some(estimate) => {
+                let samples = samples.len().min(SAMPLE_RUNS);
+                status!(
+                    "⏱️  Estimated {} time: {}, from {} earlier run{}",
+                    timer.noun(),
+                    estimate,
+                    samples,
+                    if samples == 1 { "" } else { "s" }
+                )
+            }
+            None => status!("⏱️  No earlier comparable runs, {} ETAs are shown from the next one on", timer.noun()),
+        }
+        timer
+    }
+
+    fn noun(&self) -> &'static str {
+        if self.profile.command == "audit" {
+            "audit"
+        } else {
+            "analysis"
+        }
+    }
+
+    /// Close `stage` after it ran on `lines` lines of code, and show the updated ETA now and then
+    pub fn finish_stage(&mut self, stage: &str, lines: usize) {
+        let now = Instant::now();
+        self.record_stage(stage, now.duration_since(self.mark), lines);
+        self.mark = now;
+        self.show_remaining(now);
+    }
+
+    /// Close the per-file stages of a file that was analysed alongside others
+    ///
+    /// The wall time since the previous file finished is shared among the
+    /// stages in proportion to how long each took on this file, so rates
+    /// stay comparable with runs that analysed one file at a time.
+    pub fn finish_concurrent(&mut self, stages: &[(&str, Duration)], lines: usize) {
+        let now = Instant::now();
+        let wall = now.duration_since(self.mark);
+        let total: Duration = stages.iter().map(|(_, took)| *took).sum();
+        for (stage, took) in stages {
+            let share = if total.is_zero() { 1.0 / stages.len() as f64 } else { took.as_secs_f64() / total.as_secs_f64() };
+            self.record_stage(stage, wall.mul_f64(share), lines);
+        }
+        self.mark = now;
+        self.show_remaining(now);
+    }
+
+    fn record_stage(&mut self, stage: &str, duration: Duration, lines: usize) {
+        let record = self.stages.entry(stage.to_string()).or_default();
+        record.duration_ms += duration.as_millis() as u64;
+        record.lines += lines;
+        *self.covered.entry(stage.to_string()).or_default() += lines;
+    }
+
+    /// Show the updated ETA now and then
+    fn show_remaining(&mut self, now: Instant) {
+        if now.duration_since(self.last_update) >= UPDATE_INTERVAL {
+            if let Some(estimate) = self.remaining().filter(|estimate| !estimate.high.is_zero()) {
+                status!("   ⏱️  About {} left", estimate);
+            }
+            self.last_update = now;
+        }
+    }
+
+    /// Count lines reused from the cache as covered by the per-file stages, taking no time
+    pub fn skip(&mut self, stages: &[&str], lines: usize) {
+        for stage in stages {
+            *self.covered.entry(stage.to_string()).or_default() += lines;
+        }
+        self.mark = Instant::now();
+    }
+
+    /// Time left for the stages earlier runs went through, `None` without earlier runs
+    pub fn remaining(&self) -> Option<Estimate> {
+        if self.rates.is_empty() {
+            return None;
+        }
+        let mut estimate = Estimate::default();
+        for stage in self.rates.keys() {
+            let stage_estimate = self.stage_remaining(stage).unwrap_or_default();
+            estimate.expected += stage_estimate.expected;
+            estimate.low += stage_estimate.low;
+            estimate.high += stage_estimate.high;
+        }
+        Some(estimate)
+    }
+
+    /// Time left for one stage, `None` when no earlier run went through it
+    pub fn stage_remaining(&self, stage: &str) -> Option<Estimate> {
+        let rates = self.rates.get(stage)?;
+        let covered = self.covered.get(stage).copied().unwrap_or_default();
+        let lines = self.lines_of_code.saturating_sub(covered) as f64;
+        Some(Estimate {
+            expected: Duration::from_millis((percentile(rates, 0.5) * lines) as u64),
+            low: Duration::from_millis((percentile(rates, 0.1) * lines) as u64),
+            high: Duration::from_millis((percentile(rates, 0.9) * lines) as u64),
+        })
+    }
+
+    /// Add the finished run to the history
+    pub fn finish(self) {
+        let record = RunRecord {
+            recorded_at: Utc::now(),
+            profile: self.profile,
+            lines_of_code: self.lines_of_code,
+            stages: self.stages,
+            total_ms: self.started.elapsed().as_millis() as u64,
+        };
+        if let Err(e) = append(&self.path, record) {
+            log::warn!("Could not record run history: {}", e);
+        }
+    }
+}
+
+/// Location of the history file, in `general.data_dir`
+pub fn history_path(config: &Config) -> PathBuf {
+    cache::expand_home(&config.general.data_dir).join("history.json")
+}
+
+/// Runs recorded so far, `None` before the first one
+pub fn load(config: &Config) -> Result<Option<RunHistory>> {
+    read(&history_path(config))
+}
+
+fn read(path: &Path) -> Result<Option<RunHistory>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(artifact::read_json(path, "run history file")?))
+}
+
+/// Drop the runs recorded before `cutoff`, or only count them with `dry_run`
+pub fn prune(config: &Config, cutoff: DateTime<Utc>, dry_run: bool) -> Result<usize> {
+    let path = history_path(config);
+    let mut history = match read(&path)? {
+        Some(history) => history,
+        None => return Ok(0),
+    };
+    let before = history.runs.len();
+    history.runs.retain(|run| run.recorded_at >= cutoff);
+    let dropped = before - history.runs.len();
+    if dropped > 0 && !dry_run {
+        artifact::write_atomic(&path, serde_json::to_string_pretty(&history)?)?;
+    }
+    Ok(dropped)
+}
+
+fn append(path: &Path, record: RunRecord) -> Result<()> {
+    // An unreadable history only costs the estimates, so it is started over
+    let mut history = read(path).ok().flatten().unwrap_or(RunHistory { schema_version: SCHEMA_VERSION, runs: Vec::new() });
+    history.schema_version = SCHEMA_VERSION;
+    history.runs.push(record);
+    if history.runs.len() > MAX_RUNS {
+        let excess = history.runs.len() - MAX_RUNS;
+        history.runs.drain(..excess);
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    artifact::write_atomic(path, serde_json::to_string_pretty(&history)?)?;
+    Ok(())
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], fraction: f64) -> f64 {
+    let rank = ((sorted.len() as f64 * fraction).ceil() as usize).clamp(1, sorted.len());
+    sorted[rank - 1]
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m {:02}s", seconds / 60, seconds % 60),
+        _ => format!("{}h {:02}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
@@ -0,0 +1,302 @@
+//! Git pre-commit hook support
+//!
+//! This module installs a pre-commit hook and implements the fast
+//! changed-file scan it runs. Only staged contract files are scanned, only
+//! the plugins' built-in detectors and, on Solidity, the native detector pack
+//! are used, and findings are cached per git blob so unchanged files cost
+//! nothing on the next commit. The cache key also holds the SecureChain
+//! version and the detectors enabled for the file's platform, so an upgrade
+//! or a detector change scans again.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::core::detector_catalog;
+use crate::core::detectors;
+use crate::core::fetcher::ContractInfo;
+use crate::core::parser::ContractParser;
+use crate::plugins::PluginManager;
+use crate::report::vulnerability::{Severity, Vulnerability};
+use crate::utils::artifact;
+use crate::utils::cache;
+use crate::utils::config::Config;
+
+/// Marker line identifying hooks written by SecureChain
+const HOOK_MARKER: &str = "# securechain pre-commit hook";
+
+/// Cache entries are dropped once the cache grows past this size
+const MAX_CACHE_ENTRIES: usize = 1000;
+
+/// Result of scanning the staged changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookScanReport {
+    pub files_scanned: usize,
+    pub cache_hits: usize,
+    pub fail_on: String,
+    pub findings: Vec<Vulnerability>,
+    pub blocking: Vec<Vulnerability>,
+    pub duration_ms: u128,
+}
+
+impl HookScanReport {
+    /// Whether the commit should be rejected
+    pub fn is_blocked(&self) -> bool {
+        !self.blocking.is_empty()
+    }
+}
+
+/// A staged file eligible for scanning
+struct StagedFile {
+    path: String,
+    blob: String,
+    platform: &'static str,
+}
+
+/// Scanner for staged changes, used by the pre-commit hook
+pub struct HookScanner {
+    plugin_manager: PluginManager,
+    contract_parser: ContractParser,
+    cache_path: PathBuf,
+}
+
+impl HookScanner {
+    /// Create a scanner for the current git repository
+    pub fn new() -> Result<Self> {
+        let git_dir = PathBuf::from(git(&["rev-parse", "--git-dir"])?.trim());
+
+        Ok(Self {
+            plugin_manager: PluginManager::new(),
+            contract_parser: ContractParser::new()?,
+            cache_path: git_dir.join("securechain").join("hook-cache.json"),
+        })
+    }
+
+    /// Scan staged contract files and collect findings at or above `fail_on`
+    pub fn scan_staged(&self, fail_on: &str, config: &Config) -> Result<HookScanReport> {
+        let start_time = std::time::Instant::now();
+        let threshold = parse_threshold(fail_on)?;
+
+        let mut cache = self.load_cache();
+        let mut used = HashSet::new();
+        let mut findings = Vec::new();
+        let mut files_scanned = 0;
+        let mut cache_hits = 0;
+
+        for file in staged_files()? {
+            files_scanned += 1;
+            let key = cache_key(&file, config);
+            used.insert(key.clone());
+
+            if let Some(cached) = cache.get(&key) {
+                cache_hits += 1;
+                findings.extend(cached.iter().cloned().map(|mut vuln| {
+                    vuln.file_path = file.path.clone();
+                    vuln
+                }));
+                continue;
+            }
+
+            let vulnerabilities = self.scan_blob(&file)?;
+            cache.insert(key, vulnerabilities.clone());
+            findings.extend(vulnerabilities);
+        }
+
+        if cache.len() > MAX_CACHE_ENTRIES {
+            cache.retain(|key, _| used.contains(key));
+        }
+        if let Err(e) = self.save_cache(&cache) {
+            log::debug!("Failed to write hook cache: {}", e);
+        }
+        // Filtered after caching, so re-enabling a detector needs no cache reset
+        detector_catalog::retain_enabled(&mut findings, config);
+
+        let blocking = findings
+            .iter()
+            .filter(|vuln| vuln.severity_priority() >= threshold.priority())
+            .cloned()
+            .collect();
+
+        Ok(HookScanReport {
+            files_scanned,
+            cache_hits,
+            fail_on: threshold.to_string(),
+            findings,
+            blocking,
+            duration_ms: start_time.elapsed().as_millis(),
+        })
+    }
+
+    /// Run the built-in detectors on the staged content of a single file
+    fn scan_blob(&self, file: &StagedFile) -> Result<Vec<Vulnerability>> {
+        let source_code = git(&["cat-file", "blob", &file.blob])?;
+
+        // Rust sources are only contracts when they use ink!
+        if file.platform == "ink" && !source_code.contains("#[ink") {
+            return Ok(Vec::new());
+        }
+
+        let contract = ContractInfo {
+            name: file.path.clone(),
+            address: "".to_string(),
+            source_code,
+            compiler_version: "unknown".to_string(),
+            optimization: false,
+            network: "local".to_string(),
+            verified: false,
+            metadata: HashMap::new(),
+            project: None,
+        };
+
+        let parsed_contract = self.contract_parser.parse_contract(&contract)?;
+        let mut vulnerabilities = self.plugin_manager.internal_checks(&parsed_contract, file.platform)?;
+        if file.platform == "evm" && !parsed_contract.is_vyper() {
+            vulnerabilities.extend(detectors::run_detectors(&parsed_contract));
+        }
+        for vuln in &mut vulnerabilities {
+            vuln.file_path = file.path.clone();
+        }
+
+        Ok(vulnerabilities)
+    }
+
+    fn load_cache(&self) -> HashMap<String, Vec<Vulnerability>> {
+        std::fs::read_to_string(&self.cache_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_cache(&self, cache: &HashMap<String, Vec<Vulnerability>>) -> Result<()> {
+        if let Some(parent) = self.cache_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        artifact::write_atomic(&self.cache_path, serde_json::to_string(cache)?)?;
+        Ok(())
+    }
+}
+
+/// Cache key of a staged file's findings: its blob, under this version and the detectors enabled for it
+fn cache_key(file: &StagedFile, config: &Config) -> String {
+    let mut parts = vec![env!("CARGO_PKG_VERSION"), file.platform, file.blob.as_str()];
+    if file.platform == "evm" {
+        parts.push(detectors::TOOL_NAME);
+    }
+    parts.extend(
+        detector_catalog::for_target(file.platform)
+            .filter(|detector| detector.enabled(config))
+            .map(|detector| detector.id),
+    );
+    cache::key(&parts)
+}
+
+/// Install the pre-commit hook into the current repository
+pub fn install_hook(fail_on: &str, force: bool) -> Result<PathBuf> {
+    let threshold = parse_threshold(fail_on)?;
+    let hooks_dir = PathBuf::from(git(&["rev-parse", "--git-path", "hooks"])?.trim());
+    let hook_path = hooks_dir.join("pre-commit");
+
+    if hook_path.exists() && !force && !is_securechain_hook(&hook_path) {
+        return Err(anyhow!(
+            "A pre-commit hook already exists at {}. Use --force to overwrite it",
+            hook_path.display()
+        ));
+    }
+
+    let executable = std::env::current_exe()
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "securechain".to_string());
+
+    let script = format!(
+        "#!/bin/sh\n{}\n# Bypass once with: git commit --no-verify\nexec \"{}\" hook run --fail-on {}\n",
+        HOOK_MARKER, executable, threshold
+    );
+
+    std::fs::create_dir_all(&hooks_dir)?;
+    artifact::write_atomic(&hook_path, script)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(hook_path)
+}
+
+/// Remove the pre-commit hook if it was installed by SecureChain
+pub fn uninstall_hook() -> Result<Option<PathBuf>> {
+    let hooks_dir = PathBuf::from(git(&["rev-parse", "--git-path", "hooks"])?.trim());
+    let hook_path = hooks_dir.join("pre-commit");
+
+    if !is_securechain_hook(&hook_path) {
+        return Ok(None);
+    }
+
+    std::fs::remove_file(&hook_path)?;
+    Ok(Some(hook_path))
+}
+
+fn is_securechain_hook(path: &Path) -> bool {
+    std::fs::read_to_string(path)
+        .map(|content| content.contains(HOOK_MARKER))
+        .unwrap_or(false)
+}
+
+pub fn parse_threshold(fail_on: &str) -> Result<Severity> {
+    fail_on
+        .parse::<Severity>()
+        .map_err(|e| anyhow!("{} (expected critical, high, medium, low or info)", e))
+}
+
+/// List staged, added or modified contract files with their blob ids
+fn staged_files() -> Result<Vec<StagedFile>> {
+    let output = git(&["diff", "--cached", "--raw", "-z", "--no-renames", "--diff-filter=ACM"])?;
+    let mut files = Vec::new();
+
+    // Records look like ":100644 100644 <old> <new> M\0<path>\0"
+    let mut fields = output.split('\0');
+    while let (Some(meta), Some(path)) = (fields.next(), fields.next()) {
+        let parts: Vec<&str> = meta.trim_start_matches(':').split_whitespace().collect();
+        if parts.len() < 4 || parts[1] == "160000" {
+            continue;
+        }
+
+        if let Some(platform) = platform_for_path(path) {
+            files.push(StagedFile {
+                path: path.to_string(),
+                blob: parts[3].to_string(),
+                platform,
+            });
+        }
+    }
+
+    Ok(files)
+}
+
+/// Platform whose built-in detectors read a file, by its extension
+pub fn platform_for_path(path: &str) -> Option<&'static str> {
+    match Path::new(path).extension()?.to_str()? {
+        "sol" | "vy" => Some("evm"),
+        "move" => Some("move"),
+        "cairo" => Some("cairo"),
+        "rs" => Some("ink"),
+        _ => None,
+    }
+}
+
+fn git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git").args(args).output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
@@ -0,0 +1,661 @@
+//! Import of other audit firms' findings
+//!
+//! Clients with more than one audit vendor kept each vendor's findings in a
+//! spreadsheet of their own, apart from ours. Findings exported as CSV, JSON
+//! or SARIF are read into analysis results as findings of their vendor, so
+//! reports, triage and fix verification cover them with ours; verify-fixes
+//! lists them for manual review, since no tool of ours can re-check them. A
+//! finding the results already have, in the same file with a similar title
+//! or on a nearby line in the same category, is not added again: the one
+//! there keeps the earlier sighting and a reference to the vendor's. Files
+//! are compared relative to the project root, whichever directory the vendor
+//! named them from. Importing the same file twice adds nothing.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::core::analyzer::AnalysisResults;
+use crate::core::manifest;
+use crate::report::cvss::Cvss;
+use crate::report::vulnerability::{Provenance, Vulnerability, VulnerabilityCategory};
+use crate::utils::artifact;
+
+/// Lines apart two findings of the same category can be and still be the same issue
+const LINE_TOLERANCE: usize = 3;
+
+/// Share of title words two findings in the same file need in common to be the same issue
+const TITLE_SIMILARITY: f64 = 0.5;
+
+const TITLE: &[&str] = &["title", "name", "issue", "finding", "summary"];
+const REFERENCE: &[&str] = &["id", "findingid", "ref", "reference", "identifier"];
+const SEVERITY: &[&str] = &["severity", "risk", "risklevel", "impact", "level"];
+const CVSS: &[&str] = &["cvss", "cvssvector", "cvssv3", "cvss31", "vector"];
+const DESCRIPTION: &[&str] = &["description", "details", "detail", "body"];
+const FILE: &[&str] = &["file", "filepath", "path", "contract", "source", "location", "affected"];
+const LINE: &[&str] = &["line", "linenumber", "lines", "startline"];
+const RECOMMENDATION: &[&str] = &["recommendation", "remediation", "mitigation", "fix"];
+const STATUS: &[&str] = &["status", "resolution", "state"];
+const DATE: &[&str] = &["date", "reported", "reportedat", "foundat", "created"];
+
+/// Layout of an imported findings file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Csv,
+    Json,
+    Sarif,
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+            Format::Sarif => "sarif",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            "sarif" => Ok(Format::Sarif),
+            other => Err(anyhow!("Unknown import format: {} (expected csv, json or sarif)", other)),
+        }
+    }
+}
+
+/// A finding as its vendor reported it
+#[derive(Debug, Clone, Default)]
+pub struct ExternalFinding {
+    /// The vendor's id for the finding, such as `ACME-07`
+    pub reference: Option<String>,
+    pub title: String,
+    pub description: String,
+    pub severity: &'static str,
+    /// CVSS vector the vendor scored the finding with
+    pub cvss: Option<Cvss>,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub recommendation: Option<String>,
+    /// Where the vendor left it: acknowledged, fixed and the like
+    pub status: Option<String>,
+    pub reported_at: Option<DateTime<Utc>>,
+}
+
+impl ExternalFinding {
+    /// The vendor's id, or its title when the vendor gave none
+    pub fn label(&self) -> &str {
+        self.reference.as_deref().unwrap_or(&self.title)
+    }
+}
+
+/// One import into a results file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRecord {
+    pub vendor: String,
+    /// File the findings were read from
+    pub source: String,
+    pub format: Format,
+    pub imported_at: DateTime<Utc>,
+    /// Ids of the findings added to the results
+    pub added: Vec<String>,
+    /// Vendor findings the results already had
+    pub duplicates: Vec<Duplicate>,
+    /// Vendor findings an earlier import of the same vendor already added
+    pub already_imported: usize,
+}
+
+/// A vendor finding matched to one already in the results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Duplicate {
+    /// The vendor's id, or its title when the vendor gave none
+    pub reference: String,
+    /// Id of the finding in the results it duplicates
+    pub finding_id: String,
+}
+
+/// Findings of `path`, in `format` or the one its extension and content suggest
+pub fn read(path: &Path, format: Option<Format>) -> Result<(Format, Vec<ExternalFinding>)> {
+    let content = artifact::read_text(path, "findings file")?;
+    let content = content.trim_start_matches('\u{feff}');
+    let format = match format {
+        Some(format) => format,
+        None => detect(path, content)?,
+    };
+
+    let findings = match format {
+        Format::Csv => csv_records(content).iter().filter_map(from_fields).collect(),
+        Format::Json => {
+            let value: Value = serde_json::from_str(content).map_err(|e| artifact::invalid(path, "findings file", &e))?;
+            json_records(&value)
+                .ok_or_else(|| anyhow!("{} holds no list of findings", path.display()))?
+                .iter()
+                .filter_map(from_fields)
+                .collect()
+        }
+        Format::Sarif => {
+            let value: Value = serde_json::from_str(content).map_err(|e| artifact::invalid(path, "SARIF log", &e))?;
+            sarif_findings(&value)
+        }
+    };
+    Ok((format, findings))
+}
+
+/// Add the findings of `vendor` to `results`, other than those the results already have
+///
+/// The caller rescores the results afterwards.
+pub fn merge(results: &mut AnalysisResults, vendor: &str, source: &Path, format: Format, findings: &[ExternalFinding]) -> ImportRecord {
+    let imported_at = Utc::now();
+    let import_id = uuid::Uuid::new_v4().to_string();
+    let mut record = ImportRecord {
+        vendor: vendor.to_string(),
+        source: source.display().to_string(),
+        format,
+        imported_at,
+        added: Vec::new(),
+        duplicates: Vec::new(),
+        already_imported: 0,
+    };
+
+    let root = results.manifest.as_ref().map(|manifest| manifest::project_dir(Path::new(&manifest.input_path)).to_path_buf());
+    // File of each finding in the results relative to the project root, as the vendor's are compared
+    let mut paths: Vec<String> = {
+        let files = results.finding_files();
+        results
+            .vulnerabilities
+            .iter()
+            .map(|vuln| {
+                let file = match &results.manifest {
+                    Some(manifest) => manifest.source_path(&vuln.file_path, files.get(vuln.id.as_str()).copied()),
+                    None => vuln.file_path.as_str(),
+                };
+                project_relative(file, root.as_deref())
+            })
+            .collect()
+    };
+
+    // Each finding in the results absorbs at most one vendor finding, as baseline findings pair one to one
+    let mut matched: HashSet<usize> = HashSet::new();
+    for external in findings {
+        let vuln = to_vulnerability(vendor, external, &results.contract_name, root.as_deref(), &import_id, imported_at);
+        let imported = results.vulnerabilities.iter().zip(&paths).any(|(existing, path)| {
+            existing.tool == vuln.tool
+                && existing.title == vuln.title
+                && same_path(path, &vuln.file_path)
+                && same_place(existing, &vuln)
+        });
+        if imported {
+            record.already_imported += 1;
+            continue;
+        }
+
+        let duplicate = results
+            .vulnerabilities
+            .iter()
+            .zip(&paths)
+            .enumerate()
+            .position(|(index, (existing, path))| !matched.contains(&index) && same_issue(external, &vuln, existing, path));
+        match duplicate {
+            Some(index) => {
+                matched.insert(index);
+                let existing = &mut results.vulnerabilities[index];
+                existing.merge_provenance(&vuln);
+                existing.references.push(format!("Also reported by {} as {}", vendor, external.label()));
+                record.duplicates.push(Duplicate { reference: external.label().to_string(), finding_id: existing.id.clone() });
+            }
+            None => {
+                record.added.push(vuln.id.clone());
+                paths.push(vuln.file_path.clone());
+                results.vulnerabilities.push(vuln);
+            }
+        }
+    }
+    record
+}
+
+/// The vendor's finding as one of the results, attributed to the vendor
+fn to_vulnerability(
+    vendor: &str,
+    external: &ExternalFinding,
+    contract_name: &str,
+    root: Option<&Path>,
+    import_id: &str,
+    imported_at: DateTime<Utc>,
+) -> Vulnerability {
+    let text = format!("{} {}", external.title, external.description);
+    let mut description = if external.description.is_empty() { external.title.clone() } else { external.description.clone() };
+    if let Some(status) = &external.status {
+        description.push_str(&format!("\n\nStatus reported by {}: {}", vendor, status));
+    }
+    let cwe_id = Regex::new(r"(?i)\bCWE-(\d+)")
+        .ok()
+        .and_then(|cwe| cwe.captures(&text))
+        .map(|captures| format!("CWE-{}", &captures[1]));
+
+    let mut references = Vec::new();
+    if let Some(reference) = &external.reference {
+        references.push(format!("{} {}", vendor, reference));
+    }
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: external.title.clone(),
+        description,
+        severity: external.severity.to_string(),
+        category: category_of(&text),
+        file_path: external.file.as_deref().map_or_else(|| contract_name.to_string(), |file| project_relative(file, root)),
+        line_number: external.line,
+        code_snippet: None,
+        recommendation: external.recommendation.clone(),
+        references,
+        cwe_id,
+        tool: vendor.to_string(),
+        // Found and written up by an auditor, not inferred by a tool
+        confidence: 1.0,
+        provenance: Some(Provenance {
+            detected_at: external.reported_at.unwrap_or(imported_at),
+            tool_version: None,
+            run_id: format!("import-{}", import_id),
+            git_commit: None,
+        }),
+        severity_overrides: Vec::new(),
+        cvss: external.cvss.clone(),
+        detected_by: Vec::new(),
+    }
+}
+
+/// Whether `existing`, whose file is `path`, covers the vendor finding: same file, and a similar title or a nearby line in the same category
+fn same_issue(external: &ExternalFinding, vuln: &Vulnerability, existing: &Vulnerability, path: &str) -> bool {
+    let same_file = external.file.is_none() || same_path(path, &vuln.file_path);
+    if !same_file {
+        return false;
+    }
+    let nearby = match (vuln.line_number, existing.line_number) {
+        (Some(theirs), Some(ours)) => theirs.abs_diff(ours) <= LINE_TOLERANCE,
+        _ => false,
+    };
+    title_similarity(&vuln.title, &existing.title) >= TITLE_SIMILARITY
+        || (nearby && vuln.category == existing.category && vuln.category != VulnerabilityCategory::Other)
+}
+
+/// Whether a vendor finding imported earlier is `vuln`: on the same line, or with the same message when either has none
+fn same_place(existing: &Vulnerability, vuln: &Vulnerability) -> bool {
+    match (existing.line_number, vuln.line_number) {
+        (Some(ours), Some(theirs)) => ours == theirs,
+        _ => existing.line_number == vuln.line_number && existing.description == vuln.description,
+    }
+}
+
+/// `path` relative to the project at `root`, with `/` separators and without a URI scheme or line suffix
+///
+/// Vendors name files relative to wherever they checked the code out, SARIF
+/// as URIs that may be absolute, and the manifest relative to the directory
+/// SecureChain ran in.
+fn project_relative(path: &str, root: Option<&Path>) -> String {
+    let path = path.trim();
+    let path = path.strip_prefix("file://").unwrap_or(path);
+    let path = path.split('#').next().unwrap_or(path);
+    let path = match path.rsplit_once(':') {
+        Some((file, line)) if !line.is_empty() && line.chars().all(|c| c.is_ascii_digit() || c == '-') => file,
+        _ => path,
+    };
+    let path = path.replace('\\', "/");
+    let mut relative = path.trim_start_matches("./");
+    let roots = root.map(|root| [Some(root.to_path_buf()), root.canonicalize().ok()]).unwrap_or_default();
+    for root in roots.iter().flatten() {
+        let root = root.to_string_lossy().replace('\\', "/");
+        let root = root.trim_start_matches("./").trim_end_matches('/');
+        if root.is_empty() || root == "." {
+            continue;
+        }
+        if let Some(rest) = relative.strip_prefix(root).and_then(|rest| rest.strip_prefix('/')) {
+            relative = rest;
+            break;
+        }
+    }
+    relative.to_string()
+}
+
+/// Whether two project-relative paths name the same file
+///
+/// One side may name the file from further up the tree than the other, and
+/// some tools' findings name the contract rather than its file, which only
+/// its stem can be compared with.
+fn same_path(a: &str, b: &str) -> bool {
+    let (a_path, b_path) = (Path::new(a), Path::new(b));
+    if a_path.extension().is_none() || b_path.extension().is_none() {
+        return file_stem(a) == file_stem(b);
+    }
+    a_path.ends_with(b_path) || b_path.ends_with(a_path)
+}
+
+/// File name without directories, extension or line suffix, in lower case
+///
+/// Our findings name their contract or its file, depending on the tool, and
+/// vendors name files relative to wherever they checked the code out.
+fn file_stem(path: &str) -> String {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    let name = name.split(['#', ':']).next().unwrap_or(name);
+    let name = name.split_once('.').map_or(name, |(stem, _)| stem);
+    name.trim().to_lowercase()
+}
+
+/// Share of the words of two titles they have in common
+fn title_similarity(a: &str, b: &str) -> f64 {
+    let words = |title: &str| -> HashSet<String> {
+        title
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| word.len() > 2 && !matches!(*word, "the" | "and" | "for" | "with" | "from" | "can" | "may"))
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// Category a finding's title and description suggest
+fn category_of(text: &str) -> VulnerabilityCategory {
+    let text = text.to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|word| text.contains(word));
+    if has(&["reentran", "re-entran"]) {
+        VulnerabilityCategory::Reentrancy
+    } else if has(&["access control", "unauthori", "onlyowner", "privilege", "permission"]) {
+        VulnerabilityCategory::AccessControl
+    } else if has(&["overflow", "underflow"]) {
+        VulnerabilityCategory::IntegerOverflow
+    } else if has(&["return value", "unchecked call", "unhandled", "silently fail"]) {
+        VulnerabilityCategory::UnhandledExceptions
+    } else if has(&["timestamp", "block.number"]) {
+        VulnerabilityCategory::TimestampDependence
+    } else if has(&["delegatecall", "low-level", "low level"]) {
+        VulnerabilityCategory::LowLevelCalls
+    } else if has(&["denial of service", "dos ", "unbounded loop", "gas limit"]) {
+        VulnerabilityCategory::DenialOfService
+    } else if has(&["front-run", "frontrun", "front run", "sandwich", "race condition"]) {
+        VulnerabilityCategory::RaceCondition
+    } else if has(&["signature", "ecrecover", "randomness", "replay"]) {
+        VulnerabilityCategory::Cryptography
+    } else if has(&["zero address", "validation", "sanity check"]) {
+        VulnerabilityCategory::InputValidation
+    } else if has(&["dead code", "unused", "unreachable"]) {
+        VulnerabilityCategory::DeadCode
+    } else if has(&["gas"]) {
+        VulnerabilityCategory::GasOptimization
+    } else {
+        VulnerabilityCategory::Other
+    }
+}
+
+/// Severity as findings spell it, from the words, levels and scores vendors use
+fn normalize_severity(text: &str) -> &'static str {
+    let text = text.trim().to_lowercase();
+    if let Ok(score) = text.parse::<f64>() {
+        // CVSS, as SARIF's security-severity uses it
+        return match score {
+            s if s >= 9.0 => "Critical",
+            s if s >= 7.0 => "High",
+            s if s >= 4.0 => "Medium",
+            s if s > 0.0 => "Low",
+            _ => "Info",
+        };
+    }
+    if text.starts_with("crit") {
+        "Critical"
+    } else if text.starts_with("high") || text == "error" || text == "major" {
+        "High"
+    } else if text.starts_with("med") || text == "moderate" || text == "warning" {
+        "Medium"
+    } else if text.starts_with("low") || text == "minor" || text == "note" {
+        "Low"
+    } else {
+        "Info"
+    }
+}
+
+/// A vendor's CVSS vector, if it is one we can score
+fn parse_cvss(vector: &str) -> Option<Cvss> {
+    match Cvss::parse(vector) {
+        Ok(cvss) => Some(cvss),
+        Err(e) => {
+            log::warn!("Ignoring the CVSS vector of an imported finding: {}", e);
+            None
+        }
+    }
+}
+
+/// SARIF for an explicit `.sarif` or a JSON file with SARIF runs, otherwise by extension
+fn detect(path: &Path, content: &str) -> Result<Format> {
+    let name = path.file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if name.ends_with(".sarif") || name.ends_with(".sarif.json") {
+        Ok(Format::Sarif)
+    } else if name.ends_with(".csv") {
+        Ok(Format::Csv)
+    } else if name.ends_with(".json") {
+        let sarif = serde_json::from_str::<Value>(content).is_ok_and(|value| value.get("runs").is_some_and(Value::is_array));
+        Ok(if sarif { Format::Sarif } else { Format::Json })
+    } else {
+        Err(anyhow!("Cannot tell the format of {}; pass --format (csv, json or sarif)", path.display()))
+    }
+}
+
+/// A finding from its fields, keyed by normalized column or property name
+fn from_fields(fields: &HashMap<String, String>) -> Option<ExternalFinding> {
+    let get = |aliases: &[&str]| aliases.iter().find_map(|alias| fields.get(*alias).filter(|value| !value.trim().is_empty())).map(|value| value.trim().to_string());
+
+    let description = get(DESCRIPTION).unwrap_or_default();
+    let title = match get(TITLE).or_else(|| description.lines().next().map(str::to_string)) {
+        Some(title) if !title.is_empty() => title,
+        _ => {
+            log::warn!("Skipping an imported finding without a title or description");
+            return None;
+        }
+    };
+    let (file, location_line) = match get(FILE) {
+        Some(location) => {
+            let (file, line) = split_location(&location);
+            (Some(file), line)
+        }
+        None => (None, None),
+    };
+    // Some vendors give the vector, others only its score
+    let cvss_field = get(CVSS);
+    let cvss = cvss_field.as_deref().filter(|field| field.to_uppercase().starts_with("CVSS:")).and_then(parse_cvss);
+    // Without a severity, the score rates the finding
+    let severity = get(SEVERITY).or_else(|| cvss.as_ref().map(|cvss| cvss.score.to_string())).or(cvss_field);
+    Some(ExternalFinding {
+        reference: get(REFERENCE),
+        title,
+        description,
+        severity: normalize_severity(&severity.unwrap_or_default()),
+        cvss,
+        file,
+        line: get(LINE).and_then(|line| first_number(&line)).or(location_line),
+        recommendation: get(RECOMMENDATION),
+        status: get(STATUS),
+        reported_at: get(DATE).and_then(|date| parse_date(&date)),
+    })
+}
+
+/// File and line of locations such as `src/Vault.sol:42`, `Vault.sol#L42-L50` or `Vault.sol L42`
+fn split_location(location: &str) -> (String, Option<usize>) {
+    let pattern = Regex::new(r"^(.*?)(?:[:#]|\s+)L?(\d+)\b.*$").expect("valid location pattern");
+    match pattern.captures(location.trim()) {
+        Some(captures) if !captures[1].trim().is_empty() => (captures[1].trim().to_string(), captures[2].parse().ok()),
+        _ => (location.trim().to_string(), None),
+    }
+}
+
+fn first_number(text: &str) -> Option<usize> {
+    let digits: String = text.chars().skip_while(|c| !c.is_ascii_digit()).take_while(char::is_ascii_digit).collect();
+    digits.parse().ok()
+}
+
+fn parse_date(text: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Some(date.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0).map(|date| date.and_utc())
+}
+
+/// Column or property name without case, spaces, dashes or underscores
+fn normalize_key(key: &str) -> String {
+    key.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase()
+}
+
+/// Rows of a CSV export after its header, keyed by normalized column name
+fn csv_records(content: &str) -> Vec<HashMap<String, String>> {
+    let mut rows = csv_rows(content).into_iter();
+    let header: Vec<String> = match rows.next() {
+        Some(header) => header.iter().map(|name| normalize_key(name)).collect(),
+        None => return Vec::new(),
+    };
+    rows.map(|row| header.iter().cloned().zip(row).collect()).collect()
+}
+
+/// Fields of each non-blank line, with quoted fields spanning commas, quotes and line breaks
+fn csv_rows(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => quoted = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            '\r' => {}
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|row| row.iter().any(|field| !field.trim().is_empty()));
+    rows
+}
+
+/// Findings of a JSON export: a list, or the list under `findings`, `issues`, `vulnerabilities` or `results`
+fn json_records(value: &Value) -> Option<Vec<HashMap<String, String>>> {
+    let list = match value {
+        Value::Array(list) => list,
+        Value::Object(object) => ["findings", "issues", "vulnerabilities", "results"]
+            .iter()
+            .find_map(|key| object.get(*key).and_then(Value::as_array))?,
+        _ => return None,
+    };
+    Some(list.iter().filter_map(Value::as_object).map(json_fields).collect())
+}
+
+/// Scalar properties of a finding, and those of objects one level down such as `location`
+fn json_fields(object: &serde_json::Map<String, Value>) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    for (key, value) in object {
+        match value {
+            Value::Object(nested) => {
+                for (nested_key, nested_value) in nested {
+                    if let Some(text) = scalar(nested_value) {
+                        fields.entry(normalize_key(nested_key)).or_insert(text);
+                    }
+                }
+            }
+            _ => {
+                if let Some(text) = scalar(value) {
+                    fields.insert(normalize_key(key), text);
+                }
+            }
+        }
+    }
+    fields
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().filter_map(|item| item.as_str().map(str::to_string)).collect();
+            (!items.is_empty()).then(|| items.join(", "))
+        }
+        _ => None,
+    }
+}
+
+/// Results of every run of a SARIF log, described by their rules
+fn sarif_findings(log: &Value) -> Vec<ExternalFinding> {
+    let mut findings = Vec::new();
+    for run in log.get("runs").and_then(Value::as_array).into_iter().flatten() {
+        let rules: Vec<&Value> = run.pointer("/tool/driver/rules").and_then(Value::as_array).map(|rules| rules.iter().collect()).unwrap_or_default();
+        for result in run.get("results").and_then(Value::as_array).into_iter().flatten() {
+            let rule_id = result.get("ruleId").and_then(Value::as_str);
+            let rule = result
+                .get("ruleIndex")
+                .and_then(Value::as_u64)
+                .and_then(|index| rules.get(index as usize).copied())
+                .or_else(|| rules.iter().copied().find(|rule| rule.get("id").and_then(Value::as_str) == rule_id));
+            let text = |value: Option<&Value>, pointer: &str| value.and_then(|value| value.pointer(pointer)).and_then(Value::as_str).map(str::to_string);
+
+            let message = text(Some(result), "/message/text").unwrap_or_default();
+            let title = match text(rule, "/shortDescription/text").or_else(|| text(rule, "/name")).or(rule_id.map(str::to_string)) {
+                Some(title) => title,
+                None => message.lines().next().unwrap_or_default().to_string(),
+            };
+            if title.is_empty() {
+                log::warn!("Skipping a SARIF result without a rule or message");
+                continue;
+            }
+
+            // Our own SARIF keeps the severity and vector; other tools give a CVSS score on the rule, or only a level
+            let cvss = text(Some(result), "/properties/cvss").and_then(|vector| parse_cvss(&vector));
+            let severity = text(Some(result), "/properties/severity")
+                .or_else(|| cvss.as_ref().map(|cvss| cvss.score.to_string()))
+                .or_else(|| text(rule, "/properties/security-severity"))
+                .or_else(|| text(Some(result), "/level"))
+                .or_else(|| text(rule, "/defaultConfiguration/level"))
+                .unwrap_or_else(|| "warning".to_string());
+            let location = result.pointer("/locations/0/physicalLocation");
+            findings.push(ExternalFinding {
+                reference: text(Some(result), "/guid").or_else(|| rule_id.map(str::to_string)),
+                title,
+                description: message,
+                severity: normalize_severity(&severity),
+                cvss,
+                file: text(location, "/artifactLocation/uri"),
+                line: location.and_then(|location| location.pointer("/region/startLine")).and_then(Value::as_u64).map(|line| line as usize),
+                recommendation: text(rule, "/help/text"),
+                status: text(Some(result), "/baselineState"),
+                reported_at: None,
+            });
+        }
+    }
+    findings
+}
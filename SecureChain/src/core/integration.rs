@@ -0,0 +1,320 @@
+//! Integrator risk profile
+//!
+//! Protocols building on an audited contract need to know what it can do to
+//! them, not only what is wrong with it: who can pause it, upgrade it or
+//! change its fees, which calls can revert for reasons outside their control,
+//! and where it hands control to third-party code mid-call. This module
+//! derives those trust assumptions for every deployable contract from its
+//! external functions and the internal functions they reach.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::core::callgraph::CallGraph;
+use crate::core::detectors::REENTRANCY_GUARDS;
+use crate::core::exploitability::{self, Exploitability};
+use crate::core::parser::{matching_paren, ContractUnit, ParsedContract, UnitFunction};
+
+/// Administrative capabilities: function name pattern, and calls that grant the capability under any name
+const CAPABILITIES: &[(&str, &str, Option<&str>)] = &[
+    ("Pause", r"(?i)^(?:un)?pause", Some(r"\b_(?:un)?pause\s*\(")),
+    ("Upgrade", r"(?i)^(?:upgrade|setImplementation|diamondCut)", Some(r"\b_upgradeTo\w*\s*\(")),
+    ("Change fees", r"(?i)^(?:set|update|change)\w*fee", None),
+    ("Transfer control", r"^(?:transferOwnership|renounceOwnership|grantRole|revokeRole|setAdmin|changeAdmin|acceptOwnership)$", None),
+    ("Mint", r"(?i)^mint", Some(r"\b_mint\s*\(")),
+    ("Move funds", r"(?i)^(?:withdraw|sweep|rescue|recover|emergency)", None),
+    ("Block accounts", r"(?i)blacklist|blocklist|freeze|sanction", None),
+    ("Change oracle", r"(?i)^(?:set|update|change)\w*(?:oracle|feed|price)", None),
+    ("Change parameters", r"^(?:set|update|change)[A-Z]", None),
+];
+
+/// Capabilities that only count as administrative when a role is required
+const GATED_ONLY: &[&str] = &["Change parameters", "Move funds", "Mint"];
+
+/// Checks that make a call revert for reasons the caller does not control, matched
+/// against modifiers and `require`/`if` conditions, or against every call when flagged
+const REVERT_CONDITIONS: &[(&str, &str, bool)] = &[
+    (r"\bwhenNotPaused\b|_requireNotPaused\s*\(|!\s*paused\b", "while paused", false),
+    (r"\bwhenPaused\b|_requirePaused\s*\(", "unless paused", false),
+    (r"(?i)\b(?:deadline|expir\w*)\b", "after a deadline", false),
+    (r"(?i)\b(?:amountOutMin|minAmount\w*|minOut\w*|slippage)\b", "when slippage exceeds the caller's limit", false),
+    (r"(?i)\b(?:blacklist\w*|blocklist\w*|isBlocked|frozen|sanction\w*)\b", "for blocked accounts", false),
+    (r"(?i)\b(?:cap|maxSupply|max[A-Z]\w*|\w*Limit)\b", "when a cap or limit is reached", false),
+    (r"(?i)\b(?:cooldown\w*|lockedUntil|unlockTime|lockPeriod)\b", "during a lock or cooldown period", false),
+    (r"\btransferFrom\s*\(", "without sufficient allowance or balance", true),
+    (r"(?:latestRoundData|latestAnswer)\s*\(", "when the oracle is stale or down", true),
+];
+
+/// What an integrator has to trust about one deployable contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractSurface {
+    pub name: String,
+    pub file: String,
+    pub controls: Vec<AdminControl>,
+    pub functions: Vec<ExternalFunction>,
+}
+
+/// A function that changes how the contract behaves for everyone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminControl {
+    /// What it can do, e.g. `Pause` or `Upgrade`
+    pub capability: String,
+    pub function: String,
+    /// Roles allowed to call it, empty when anyone can
+    pub callable_by: Vec<String>,
+}
+
+/// One external or public function, as integrators call it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalFunction {
+    /// `name(types)`
+    pub signature: String,
+    pub state_mutability: String,
+    /// Roles allowed to call it, empty when anyone can
+    pub callable_by: Vec<String>,
+    /// Conditions beyond the arguments that make it revert
+    pub reverts: Vec<String>,
+    /// Calls that hand control to code outside this contract
+    pub callbacks: Vec<String>,
+    pub reentrancy_guarded: bool,
+    /// Findings inside the function or the internal functions it reaches
+    pub findings: usize,
+}
+
+impl ExternalFunction {
+    fn is_view(&self) -> bool {
+        matches!(self.state_mutability.as_str(), "view" | "pure")
+    }
+
+    /// Whether callees can re-enter while the function is still running
+    pub fn reentrant_callbacks(&self) -> bool {
+        !self.callbacks.is_empty() && !self.reentrancy_guarded && !self.is_view()
+    }
+}
+
+/// Trust assumptions of every contract of the run that is not a base of another
+pub fn profile(contracts: &[ParsedContract], exploitability: &HashMap<String, Exploitability>) -> Vec<ContractSurface> {
+    let graph = CallGraph::build(contracts);
+    let units: Vec<(&ParsedContract, &ContractUnit)> = contracts
+        .iter()
+        .flat_map(|contract| contract.units.iter().map(move |unit| (contract, unit)))
+        .collect();
+
+    let capabilities: Vec<(&str, Regex, Option<Regex>)> = CAPABILITIES
+        .iter()
+        .map(|(capability, name, call)| {
+            (
+                *capability,
+                Regex::new(name).expect("valid capability pattern"),
+                call.map(|call| Regex::new(call).expect("valid capability pattern")),
+            )
+        })
+        .collect();
+    let revert_conditions: Vec<(Regex, &str, bool)> = REVERT_CONDITIONS
+        .iter()
+        .map(|(pattern, condition, in_calls)| (Regex::new(pattern).expect("valid revert pattern"), *condition, *in_calls))
+        .collect();
+
+    let mut findings_by_function: HashMap<&str, usize> = HashMap::new();
+    for assessment in exploitability.values() {
+        if let Some(function) = assessment.function.as_deref() {
+            *findings_by_function.entry(function).or_default() += 1;
+        }
+    }
+
+    let deployable = units.iter().filter(|(_, unit)| {
+        unit.kind == "contract" && !units.iter().any(|(_, other)| other.inheritance.contains(&unit.name))
+    });
+
+    let mut surfaces = Vec::new();
+    for (contract, unit) in deployable {
+        let mut surface = ContractSurface {
+            name: unit.name.clone(),
+            file: contract.name.clone(),
+            controls: Vec::new(),
+            functions: Vec::new(),
+        };
+
+        for (base, function) in inherited_functions(&units, unit) {
+            if !matches!(function.visibility.as_str(), "external" | "public") {
+                continue;
+            }
+            let index = graph
+                .nodes
+                .iter()
+                .position(|node| node.unit == base.name && std::ptr::eq(node.function, function));
+            let reached: Vec<&UnitFunction> = index
+                .map(|index| {
+                    graph
+                        .callees_of(index)
+                        .into_iter()
+                        .map(|callee| graph.nodes[callee].function)
+                        .filter(|callee| matches!(callee.visibility.as_str(), "internal" | "private"))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let mut callable_by = exploitability::caller_guards(function);
+            for callee in &reached {
+                for guard in exploitability::caller_guards(callee) {
+                    if !callable_by.contains(&guard) {
+                        callable_by.push(guard);
+                    }
+                }
+            }
+
+            let code: String = std::iter::once(function)
+                .chain(reached.iter().copied())
+                .filter_map(|f| f.body.as_deref())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let checks = format!("{}\n{}", function.modifiers.join(" "), checks(&code));
+
+            let callbacks = callbacks(&code, contract, unit);
+            let mut reverts: Vec<String> = revert_conditions
+                .iter()
+                .filter(|(pattern, _, in_calls)| pattern.is_match(if *in_calls { &code } else { &checks }))
+                .map(|(_, condition, _)| condition.to_string())
+                .collect();
+            if !callbacks.is_empty() {
+                reverts.push("when a called contract reverts".to_string());
+            }
+
+            let signature = format!("{}({})", function.name, function.parameter_types.join(","));
+            if !matches!(function.state_mutability.as_str(), "view" | "pure") {
+                let capability = capabilities.iter().find(|(_, name, call)| {
+                    name.is_match(&function.name) || call.as_ref().is_some_and(|call| call.is_match(&code))
+                });
+                // Ungated setters, withdrawals and mints are user actions or findings, not controls
+                let control = capability.filter(|(capability, _, _)| !callable_by.is_empty() || !GATED_ONLY.contains(capability));
+                if let Some((capability, _, _)) = control {
+                    surface.controls.push(AdminControl {
+                        capability: capability.to_string(),
+                        function: signature.clone(),
+                        callable_by: callable_by.clone(),
+                    });
+                }
+            }
+
+            let label = format!("{}.{}", base.name, function.name);
+            surface.functions.push(ExternalFunction {
+                signature,
+                state_mutability: function.state_mutability.clone(),
+                callable_by,
+                reverts,
+                callbacks,
+                reentrancy_guarded: function.modifiers.iter().any(|modifier| REENTRANCY_GUARDS.contains(&modifier.as_str())),
+                findings: findings_by_function.get(label.as_str()).copied().unwrap_or_default(),
+            });
+        }
+
+        if !surface.functions.is_empty() {
+            surfaces.push(surface);
+        }
+    }
+
+    surfaces
+}
+
+/// Functions of a unit and of its bases in the run, the most derived declaration first
+fn inherited_functions<'a>(
+    units: &[(&'a ParsedContract, &'a ContractUnit)],
+    unit: &'a ContractUnit,
+) -> Vec<(&'a ContractUnit, &'a UnitFunction)> {
+    let mut lineage = vec![unit];
+    let mut index = 0;
+    while index < lineage.len() {
+        for base in &lineage[index].inheritance {
+            if let Some((_, base_unit)) = units.iter().find(|(_, candidate)| &candidate.name == base) {
+                if !lineage.iter().any(|known| known.name == base_unit.name) {
+                    lineage.push(base_unit);
+                }
+            }
+        }
+        index += 1;
+    }
+
+    let mut functions: Vec<(&ContractUnit, &UnitFunction)> = Vec::new();
+    for declaring in lineage {
+        for function in &declaring.functions {
+            let overridden = functions
+                .iter()
+                .any(|(_, known)| known.name == function.name && known.parameter_types == function.parameter_types);
+            if !overridden {
+                functions.push((declaring, function));
+            }
+        }
+    }
+    functions
+}
+
+/// Conditions of `require`, `assert` and `if`, and `_require`/`_check` helper calls
+fn checks(code: &str) -> String {
+    let condition = Regex::new(r"\b(?:require|assert|if)\s*\(|\b_(?:require|check)\w*\s*\(").expect("valid condition pattern");
+    condition
+        .find_iter(code)
+        .filter_map(|m| {
+            let close = matching_paren(code, m.end() - 1)?;
+            Some(&code[m.start()..=close])
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Calls in `code` that run code outside the contract before the function returns
+fn callbacks(code: &str, contract: &ParsedContract, unit: &ContractUnit) -> Vec<String> {
+    let mut callbacks: Vec<String> = Vec::new();
+    let mut add = |callback: &str| {
+        if !callbacks.iter().any(|known| known == callback) {
+            callbacks.push(callback.to_string());
+        }
+    };
+
+    if Regex::new(r"\.\s*call\s*[{(]").expect("valid call pattern").is_match(code) {
+        add("low-level call");
+    }
+    if Regex::new(r"\.\s*delegatecall\s*\(").expect("valid delegatecall pattern").is_match(code) {
+        add("delegatecall");
+    }
+    if Regex::new(r"\b_safeMint\s*\(|\b_safeTransfer\s*\(|\bsafeTransferFrom\s*\([^;]*,[^;]*,[^;]*,|\bonERC(?:721|1155)Received\b")
+        .expect("valid receiver hook pattern")
+        .is_match(code)
+    {
+        add("token receiver hook");
+    }
+    if Regex::new(r"\b(?:onFlashLoan|executeOperation|\w+Callback)\s*\(").expect("valid callback pattern").is_match(code) {
+        add("flash loan or swap callback");
+    }
+
+    // Calls on contract-typed variables and casts
+    let state_types: HashMap<&str, &str> = unit
+        .state_variables
+        .iter()
+        .map(|variable| (variable.name.as_str(), variable.type_name.as_str()))
+        .collect();
+    let is_contract_type = |type_name: &str| {
+        let bytes = type_name.as_bytes();
+        contract.units.iter().any(|u| u.name == type_name && u.kind != "library")
+            || (bytes.len() > 1 && bytes[0] == b'I' && bytes[1].is_ascii_uppercase())
+    };
+    let member_call = Regex::new(r"\b([A-Za-z_]\w*)\s*(\(\s*[\w.\[\]]+\s*\))?\s*\.\s*([A-Za-z_]\w*)\s*\(").expect("valid member call pattern");
+    for captures in member_call.captures_iter(code) {
+        let receiver = &captures[1];
+        let external = if captures.get(2).is_some() {
+            is_contract_type(receiver)
+        } else {
+            state_types.get(receiver).is_some_and(|type_name| is_contract_type(type_name))
+        };
+        if !external {
+            continue;
+        }
+        if matches!(&captures[3], "transfer" | "transferFrom" | "safeTransfer" | "safeTransferFrom") {
+            add("token transfer (hooks on ERC777-style tokens)");
+        } else {
+            add("external contract call");
+        }
+    }
+
+    callbacks
+}
@@ -0,0 +1,313 @@
+//! Custom error, revert string and event inventory
+//!
+//! Integrators decode failed calls by the selectors of custom errors and by
+//! revert strings, and monitoring teams build alerting on events. This
+//! module lists every custom error, revert string and event of a run with
+//! the external functions that can raise or emit it, directly, through a
+//! modifier or through the internal functions they call, and the findings
+//! in any of those functions, so reports show which failure and log
+//! surfaces a finding affects.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::core::callgraph::CallGraph;
+use crate::core::exploitability::Exploitability;
+use crate::core::parser::{matching_paren, split_top_level, ParsedContract};
+use crate::core::selectors;
+
+/// Every error and event surface of a run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Inventory {
+    pub errors: Vec<Surface>,
+    pub revert_strings: Vec<Surface>,
+    pub events: Vec<Surface>,
+}
+
+/// One custom error, revert string or event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Surface {
+    /// Canonical signature, or the message of a revert string
+    pub signature: String,
+    /// Error selector or event topic; `None` for revert strings and errors declared outside the run
+    pub selector: Option<String>,
+    /// `Unit (file:line)` of the declaration, when it is in the run
+    pub declared_in: Option<String>,
+    /// External and public functions that can raise or emit it, as `Contract.function`
+    pub raised_by: Vec<String>,
+    /// Findings in those functions or in the code raising it, by vulnerability id
+    pub findings: Vec<String>,
+}
+
+impl Inventory {
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.revert_strings.is_empty() && self.events.is_empty()
+    }
+
+    /// Surfaces a finding affects, as reports name them: `error X(..)`, `event Y(..)` or `"message"`
+    pub fn surfaces_of(&self, finding_id: &str) -> Vec<String> {
+        let affected = |surfaces: &[Surface]| -> Vec<String> {
+            surfaces
+                .iter()
+                .filter(|surface| surface.findings.iter().any(|id| id == finding_id))
+                .map(|surface| surface.signature.clone())
+                .collect()
+        };
+        let mut names: Vec<String> = affected(&self.errors).into_iter().map(|signature| format!("error {}", signature)).collect();
+        names.extend(affected(&self.revert_strings).into_iter().map(|message| format!("\"{}\"", message)));
+        names.extend(affected(&self.events).into_iter().map(|signature| format!("event {}", signature)));
+        names
+    }
+}
+
+/// Name of a surface, with the entry points raising it and the findings on their paths
+type RaisedBy = (String, Vec<String>, Vec<String>);
+
+/// What one function raises and emits in its own code
+#[derive(Default)]
+struct Raised {
+    errors: Vec<String>,
+    revert_strings: Vec<String>,
+    events: Vec<String>,
+}
+
+impl Raised {
+    fn scan(code: &str, patterns: &Patterns) -> Self {
+        let mut raised = Raised::default();
+        for captures in patterns.emit.captures_iter(code) {
+            push(&mut raised.events, &captures[1]);
+        }
+        for captures in patterns.revert_error.captures_iter(code) {
+            push(&mut raised.errors, &captures[1]);
+        }
+        for captures in patterns.revert_string.captures_iter(code) {
+            push(&mut raised.revert_strings, &captures[1]);
+        }
+        // `require(condition, "message")` and, since 0.8.26, `require(condition, Error(...))`
+        for found in patterns.require.find_iter(code) {
+            let close = match matching_paren(code, found.end() - 1) {
+                Some(close) => close,
+                None => continue,
+            };
+            let arguments = split_top_level(&code[found.end()..close], ',');
+            let reason = match arguments.get(1) {
+                Some(reason) => reason.trim(),
+                None => continue,
+            };
+            if let Some(captures) = patterns.string.captures(reason) {
+                push(&mut raised.revert_strings, &captures[1]);
+            } else if let Some(captures) = patterns.error_call.captures(reason) {
+                push(&mut raised.errors, &captures[1]);
+            }
+        }
+        raised
+    }
+
+    fn extend(&mut self, other: &Raised) {
+        for (into, from) in [
+            (&mut self.errors, &other.errors),
+            (&mut self.revert_strings, &other.revert_strings),
+            (&mut self.events, &other.events),
+        ] {
+            for item in from {
+                push(into, item);
+            }
+        }
+    }
+}
+
+struct Patterns {
+    emit: Regex,
+    revert_error: Regex,
+    revert_string: Regex,
+    require: Regex,
+    string: Regex,
+    error_call: Regex,
+}
+
+impl Patterns {
+    fn new() -> Self {
+        Self {
+            emit: Regex::new(r"\bemit\s+(?:[A-Za-z_]\w*\.)?([A-Za-z_]\w*)\s*\(").expect("valid emit pattern"),
+            revert_error: Regex::new(r"\brevert\s+(?:[A-Za-z_]\w*\.)?([A-Za-z_]\w*)\s*\(").expect("valid revert pattern"),
+            revert_string: Regex::new(r#"\brevert\s*\(\s*"((?:[^"\\]|\\.)*)"\s*\)"#).expect("valid revert pattern"),
+            require: Regex::new(r"\brequire\s*\(").expect("valid require pattern"),
+            string: Regex::new(r#"^"((?:[^"\\]|\\.)*)"$"#).expect("valid string pattern"),
+            error_call: Regex::new(r"^(?:[A-Za-z_]\w*\.)?([A-Za-z_]\w*)\s*\(").expect("valid error pattern"),
+        }
+    }
+}
+
+/// Inventory of the custom errors, revert strings and events of `contracts`
+///
+/// `exploitability` locates findings in functions, as `Contract.function`.
+pub fn build(contracts: &[ParsedContract], exploitability: &HashMap<String, Exploitability>) -> Inventory {
+    let patterns = Patterns::new();
+    let graph = CallGraph::build(contracts);
+
+    let modifiers: HashMap<&str, Raised> = contracts
+        .iter()
+        .flat_map(|contract| contract.modifiers.iter())
+        .map(|modifier| (modifier.name.as_str(), Raised::scan(&modifier.body, &patterns)))
+        .collect();
+    let own: Vec<Raised> = graph
+        .nodes
+        .iter()
+        .map(|node| {
+            let mut raised = Raised::scan(node.function.body.as_deref().unwrap_or_default(), &patterns);
+            for modifier in &node.function.modifiers {
+                if let Some(by_modifier) = modifiers.get(modifier.as_str()) {
+                    raised.extend(by_modifier);
+                }
+            }
+            raised
+        })
+        .collect();
+
+    let mut findings_by_function: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (id, assessment) in exploitability {
+        if let Some(function) = assessment.function.as_deref() {
+            findings_by_function.entry(function).or_default().push(id);
+        }
+    }
+
+    // Surfaces by kind and name, in the order they are first seen
+    let mut raised_by: [Vec<RaisedBy>; 3] = Default::default();
+    for (index, node) in graph.nodes.iter().enumerate() {
+        if !matches!(node.function.visibility.as_str(), "external" | "public") {
+            continue;
+        }
+        let entry = format!("{}.{}", node.unit, node.function.name);
+        for function in std::iter::once(index).chain(graph.callees_of(index)) {
+            let label = format!("{}.{}", graph.nodes[function].unit, graph.nodes[function].function.name);
+            let findings = findings_by_function.get(label.as_str()).cloned().unwrap_or_default();
+            let raised = &own[function];
+            for (kind, names) in [&raised.errors, &raised.revert_strings, &raised.events].into_iter().enumerate() {
+                for name in names {
+                    let position = match raised_by[kind].iter().position(|(known, _, _)| known == name) {
+                        Some(position) => position,
+                        None => {
+                            raised_by[kind].push((name.clone(), Vec::new(), Vec::new()));
+                            raised_by[kind].len() - 1
+                        }
+                    };
+                    let (_, functions, ids) = &mut raised_by[kind][position];
+                    push(functions, &entry);
+                    for id in findings.iter().chain(findings_by_function.get(entry.as_str()).into_iter().flatten()) {
+                        push(ids, id);
+                    }
+                }
+            }
+        }
+    }
+    let [errors_raised, strings_raised, events_raised] = raised_by;
+
+    let declarations = declared_errors(contracts);
+    let signatures = selectors::canonical_signatures(
+        contracts,
+        &declarations.iter().map(|declaration| (declaration.name.clone(), declaration.types.clone())).collect::<Vec<_>>(),
+    )
+    .unwrap_or_default();
+    let mut errors: Vec<Surface> = declarations
+        .iter()
+        .zip(signatures)
+        .filter(|(declaration, _)| declaration.first)
+        .map(|(declaration, signature)| {
+            let selector = Some(selectors::selector(&signature));
+            surface(signature, selector, Some(declaration.location.clone()), &declaration.name, &errors_raised)
+        })
+        .collect();
+    // Errors of libraries and dependencies outside the run are still raised by name
+    for (name, _, _) in &errors_raised {
+        if !declarations.iter().any(|declaration| &declaration.name == name) {
+            errors.push(surface(format!("{}(...)", name), None, None, name, &errors_raised));
+        }
+    }
+
+    let revert_strings = strings_raised
+        .iter()
+        .map(|(message, _, _)| surface(message.clone(), None, None, message, &strings_raised))
+        .collect();
+
+    let topics = selectors::event_signatures(contracts).unwrap_or_default();
+    let mut events: Vec<Surface> = Vec::new();
+    for contract in contracts {
+        let signatures = topics.get(&contract.name).cloned().unwrap_or_default();
+        for (event, (signature, topic)) in contract.events.iter().zip(signatures) {
+            if events.iter().any(|known| known.signature == signature) {
+                continue;
+            }
+            let location = format!("{}:{}", contract.name, event.line_number);
+            let topic = Some(topic).filter(|_| !event.anonymous);
+            events.push(surface(signature, topic, Some(location), &event.name, &events_raised));
+        }
+    }
+
+    Inventory { errors, revert_strings, events }
+}
+
+/// A custom error declaration
+struct ErrorDeclaration {
+    name: String,
+    types: Vec<String>,
+    location: String,
+    /// Whether it is the first declaration of the name in the run
+    first: bool,
+}
+
+/// Custom errors declared in `contracts`, at file level or in a unit
+fn declared_errors(contracts: &[ParsedContract]) -> Vec<ErrorDeclaration> {
+    let pattern = Regex::new(r"\berror\s+([A-Za-z_]\w*)\s*\(").expect("valid error declaration pattern");
+    let mut declarations: Vec<ErrorDeclaration> = Vec::new();
+    for contract in contracts.iter().filter(|contract| !contract.is_vyper()) {
+        let code = contract.code();
+        for captures in pattern.captures_iter(code) {
+            let whole = captures.get(0).expect("match has a whole group");
+            let close = match matching_paren(code, whole.end() - 1) {
+                Some(close) => close,
+                None => continue,
+            };
+            // A declaration, not a call of a function named `error`
+            if !code[close + 1..].trim_start().starts_with(';') {
+                continue;
+            }
+            let types = split_top_level(&code[whole.end()..close], ',')
+                .iter()
+                .filter_map(|parameter| parameter.split_whitespace().next().map(str::to_string))
+                .collect();
+            let line = code[..whole.start()].matches('\n').count() + 1;
+            let unit = contract.units.iter().rfind(|unit| unit.line_number <= line);
+            let depth = code[..whole.start()].matches('{').count().saturating_sub(code[..whole.start()].matches('}').count());
+            let location = match unit.filter(|_| depth > 0) {
+                Some(unit) => format!("{} ({}:{})", unit.name, contract.name, line),
+                None => format!("{}:{}", contract.name, line),
+            };
+            let name = captures[1].to_string();
+            let first = !declarations.iter().any(|declaration| declaration.name == name);
+            declarations.push(ErrorDeclaration { name, types, location, first });
+        }
+    }
+    declarations
+}
+
+fn surface(
+    signature: String,
+    selector: Option<String>,
+    declared_in: Option<String>,
+    name: &str,
+    raised: &[RaisedBy],
+) -> Surface {
+    let (raised_by, findings) = raised
+        .iter()
+        .find(|(known, _, _)| known == name)
+        .map(|(_, functions, findings)| (functions.clone(), findings.clone()))
+        .unwrap_or_default();
+    Surface { signature, selector, declared_in, raised_by, findings }
+}
+
+fn push(items: &mut Vec<String>, item: &str) {
+    if !items.iter().any(|known| known == item) {
+        items.push(item.to_string());
+    }
+}
@@ -0,0 +1,243 @@
+//! Analysis job queue
+//!
+//! A shared daemon runs analyses for several users at once. Workers are
+//! limited overall and per resource class, so a deep audit, which can run
+//! Mythril and Echidna for an hour, never takes the workers quick scans
+//! need. Waiting jobs start by priority, then in arrival order; a job whose
+//! class is at its limit lets the next eligible job overtake it. Once the
+//! queue is full, new jobs are rejected straight away instead of waiting
+//! without bound.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::Notify;
+
+use crate::utils::config::Config;
+
+/// Status code of a rejected job, as an HTTP server would answer
+pub const QUEUE_FULL_CODE: u16 = 429;
+
+/// How heavy a job is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceClass {
+    /// `quick` and `standard` analyses: static tools only
+    Quick,
+    /// `deep` analyses, which add symbolic execution and fuzzing
+    Deep,
+}
+
+impl ResourceClass {
+    pub fn for_depth(depth: &str) -> Self {
+        if depth == "deep" {
+            ResourceClass::Deep
+        } else {
+            ResourceClass::Quick
+        }
+    }
+}
+
+/// Order in which waiting jobs start
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl std::str::FromStr for Priority {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        match value.to_lowercase().as_str() {
+            "low" => Ok(Priority::Low),
+            "normal" => Ok(Priority::Normal),
+            "high" => Ok(Priority::High),
+            _ => Err(anyhow::anyhow!("Invalid priority: {} (expected low, normal or high)", value)),
+        }
+    }
+}
+
+/// Worker and queue limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueLimits {
+    pub workers: usize,
+    pub deep_workers: usize,
+    pub max_queued: usize,
+}
+
+impl QueueLimits {
+    pub fn from_config(config: &Config) -> Self {
+        let workers = config.general.max_concurrent_tasks.max(1);
+        Self {
+            workers,
+            deep_workers: config.general.max_deep_tasks.clamp(1, workers),
+            max_queued: config.general.max_queued_jobs,
+        }
+    }
+}
+
+/// Current load of the queue
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueueStatus {
+    pub running: usize,
+    pub running_deep: usize,
+    pub queued: usize,
+}
+
+/// The queue was full when the job arrived
+#[derive(Debug)]
+pub struct QueueFull {
+    pub queued: usize,
+}
+
+impl std::fmt::Display for QueueFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} jobs are already waiting", self.queued)
+    }
+}
+
+impl std::error::Error for QueueFull {}
+
+#[derive(Debug, Clone, Copy)]
+struct Waiting {
+    ticket: u64,
+    class: ResourceClass,
+    priority: Priority,
+}
+
+#[derive(Default)]
+struct State {
+    running: usize,
+    running_deep: usize,
+    /// In arrival order
+    waiting: Vec<Waiting>,
+    next_ticket: u64,
+}
+
+impl State {
+    fn can_start(&self, limits: &QueueLimits, class: ResourceClass) -> bool {
+        self.running < limits.workers && (class == ResourceClass::Quick || self.running_deep < limits.deep_workers)
+    }
+
+    /// The waiting job to start next, if any can start now
+    fn next(&self, limits: &QueueLimits) -> Option<u64> {
+        self.waiting
+            .iter()
+            .filter(|job| self.can_start(limits, job.class))
+            // Highest priority first; among equals the earliest, which max_by_key would not give
+            .fold(None, |best: Option<&Waiting>, job| match best {
+                Some(best) if best.priority >= job.priority => Some(best),
+                _ => Some(job),
+            })
+            .map(|job| job.ticket)
+    }
+}
+
+/// Bounded, prioritised admission of analysis jobs
+pub struct JobQueue {
+    limits: QueueLimits,
+    state: Mutex<State>,
+    changed: Notify,
+}
+
+impl JobQueue {
+    pub fn new(limits: QueueLimits) -> Arc<Self> {
+        Arc::new(Self {
+            limits,
+            state: Mutex::new(State::default()),
+            changed: Notify::new(),
+        })
+    }
+
+    pub fn limits(&self) -> QueueLimits {
+        self.limits
+    }
+
+    pub fn status(&self) -> QueueStatus {
+        let state = self.lock();
+        QueueStatus {
+            running: state.running,
+            running_deep: state.running_deep,
+            queued: state.waiting.len(),
+        }
+    }
+
+    /// Wait for a worker; the job runs while the returned permit is alive
+    pub async fn acquire(self: &Arc<Self>, class: ResourceClass, priority: Priority) -> Result<JobPermit, QueueFull> {
+        let ticket = {
+            let mut state = self.lock();
+            if state.waiting.len() >= self.limits.max_queued {
+                return Err(QueueFull { queued: state.waiting.len() });
+            }
+            let ticket = state.next_ticket;
+            state.next_ticket += 1;
+            state.waiting.push(Waiting { ticket, class, priority });
+            ticket
+        };
+        // Leaves the queue if the client goes away while waiting
+        let mut waiting = WaitingGuard { queue: self, ticket: Some(ticket) };
+
+        loop {
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+
+            {
+                let mut state = self.lock();
+                if state.next(&self.limits) == Some(ticket) {
+                    state.waiting.retain(|job| job.ticket != ticket);
+                    state.running += 1;
+                    if class == ResourceClass::Deep {
+                        state.running_deep += 1;
+                    }
+                    waiting.ticket = None;
+                    drop(state);
+                    // Another job may be able to start alongside this one
+                    self.changed.notify_waiters();
+                    return Ok(JobPermit { queue: Arc::clone(self), class });
+                }
+            }
+            changed.await;
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A running job's worker, released on drop
+pub struct JobPermit {
+    queue: Arc<JobQueue>,
+    class: ResourceClass,
+}
+
+impl Drop for JobPermit {
+    fn drop(&mut self) {
+        {
+            let mut state = self.queue.lock();
+            state.running -= 1;
+            if self.class == ResourceClass::Deep {
+                state.running_deep -= 1;
+            }
+        }
+        self.queue.changed.notify_waiters();
+    }
+}
+
+struct WaitingGuard<'a> {
+    queue: &'a JobQueue,
+    ticket: Option<u64>,
+}
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(ticket) = self.ticket {
+            self.queue.lock().waiting.retain(|job| job.ticket != ticket);
+            self.queue.changed.notify_waiters();
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Vulnerability knowledge base for AI prompts
+//!
+//! A model asked for vulnerabilities invents plausible ones as readily as it
+//! finds real ones. With `ai.knowledge.enabled`, each analysis prompt also
+//! carries the write-ups closest to the contract's code, drawn from a
+//! bundled corpus of SWC entries and past exploits. Findings can then be
+//! grounded in real precedent, or checked against it. The corpus and the
+//! code are embedded with `ai.knowledge.model` on `ai.knowledge.backend`,
+//! and entries are ranked by cosine similarity. The corpus embeddings are
+//! cached below `general.cache_dir`, keyed by backend, model and corpus, so
+//! they are only computed once.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::core::ai_provider;
+use crate::utils::artifact;
+use crate::utils::cache;
+use crate::utils::config::{AiConfig, Config};
+
+/// Corpus bundled into the binary
+const CORPUS: &str = include_str!("../../corpus/knowledge.json");
+
+/// Characters of code embedded for retrieval; embedding models take a few thousand tokens
+const QUERY_CHARS: usize = 16_000;
+
+/// One write-up of the corpus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub id: String,
+    pub title: String,
+    /// `swc` for a registry entry, `exploit` for an incident
+    pub kind: String,
+    pub swc: Option<String>,
+    pub cwe: Option<String>,
+    /// A `VulnerabilityCategory` name
+    pub category: Option<String>,
+    pub text: String,
+    pub references: Vec<String>,
+}
+
+impl Entry {
+    /// Text embedded for the entry
+    fn document(&self) -> String {
+        format!("{}\n{}", self.title, self.text)
+    }
+}
+
+/// Entries of the bundled corpus
+pub fn entries() -> Result<Vec<Entry>> {
+    serde_json::from_str(CORPUS).context("The bundled knowledge corpus is invalid")
+}
+
+/// The corpus with an embedding per entry
+pub struct KnowledgeBase {
+    entries: Vec<Entry>,
+    vectors: Vec<Vec<f32>>,
+}
+
+impl KnowledgeBase {
+    /// Corpus embeddings from the cache, or computed and cached now
+    pub async fn load(config: &Config) -> Result<Self> {
+        let entries = entries()?;
+        let path = cache_path(config);
+        if let Some(vectors) = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Vec<Vec<f32>>>(&content).ok())
+            .filter(|vectors| vectors.len() == entries.len())
+        {
+            return Ok(Self { entries, vectors });
+        }
+
+        let documents: Vec<String> = entries.iter().map(Entry::document).collect();
+        let vectors = embed(&config.ai, &documents).await.context("Could not embed the knowledge corpus")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        if let Err(e) = artifact::write_atomic(&path, serde_json::to_string(&vectors)?) {
+            log::warn!("Could not cache knowledge embeddings in {}: {}", path.display(), e);
+        }
+        Ok(Self { entries, vectors })
+    }
+
+    /// The `count` entries closest to `code`, closest first
+    pub async fn retrieve(&self, config: &AiConfig, code: &str, count: usize) -> Result<Vec<&Entry>> {
+        let query = embed(config, &[query(code).to_string()]).await?.remove(0);
+        let mut ranked: Vec<(f32, &Entry)> =
+            self.vectors.iter().zip(&self.entries).map(|(vector, entry)| (cosine(&query, vector), entry)).collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        Ok(ranked.into_iter().take(count).map(|(_, entry)| entry).collect())
+    }
+}
+
+/// Part of `code` embedded for retrieval
+pub fn query(code: &str) -> &str {
+    match code.char_indices().nth(QUERY_CHARS) {
+        Some((end, _)) => &code[..end],
+        None => code,
+    }
+}
+
+/// Prompt section presenting `entries` as precedent
+pub fn prompt_section(entries: &[&Entry]) -> String {
+    let mut section = String::from(
+        "Known vulnerabilities and past exploits resembling this code, for reference. \
+         Report an issue only if the code below actually has it, and name the precedent in its description when one applies.\n",
+    );
+    for entry in entries {
+        let ids: Vec<&str> = [entry.swc.as_deref(), entry.cwe.as_deref()].into_iter().flatten().collect();
+        section.push_str(&format!("\n- {}", entry.title));
+        if !ids.is_empty() {
+            section.push_str(&format!(" ({})", ids.join(", ")));
+        }
+        section.push_str(&format!(": {}", entry.text));
+    }
+    section
+}
+
+/// Embedding of each of `inputs` on the knowledge backend
+async fn embed(config: &AiConfig, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+    let knowledge = &config.knowledge;
+    let provider = ai_provider::provider(config, &knowledge.backend)
+        .ok_or_else(|| anyhow!("Unknown knowledge backend: {}", knowledge.backend))?;
+    let key = ai_provider::api_key(provider.as_ref()).map_err(|e| anyhow!(e))?;
+    let request = provider
+        .embedding_request(key.as_deref(), &knowledge.model, inputs)
+        .ok_or_else(|| anyhow!("The {} backend has no embeddings API", provider.name()))?;
+    let client = reqwest::Client::builder().user_agent("SecureChain").build()?;
+    let answer: serde_json::Value = request.build(&client).send().await?.error_for_status()?.json().await?;
+    let vectors = provider
+        .embeddings(&answer)
+        .ok_or_else(|| anyhow!("{} answered without embeddings", provider.name()))?;
+    if vectors.len() != inputs.len() {
+        bail!("{} returned {} embeddings for {} inputs", provider.name(), vectors.len(), inputs.len());
+    }
+    Ok(vectors)
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norms = a.iter().map(|x| x * x).sum::<f32>().sqrt() * b.iter().map(|y| y * y).sum::<f32>().sqrt();
+    match norms {
+        norms if norms > 0.0 => dot / norms,
+        _ => 0.0,
+    }
+}
+
+fn cache_path(config: &Config) -> PathBuf {
+    let knowledge = &config.ai.knowledge;
+    cache::expand_home(&config.general.cache_dir)
+        .join("knowledge")
+        .join(format!("{}.json", cache::key(&[&knowledge.backend, &knowledge.model, CORPUS])))
+}
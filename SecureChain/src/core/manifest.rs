@@ -0,0 +1,365 @@
+//! Run manifest
+//!
+//! The manifest records what is needed to reproduce an analysis run: the
+//! exact invocation, SecureChain and tool versions, the command each tool
+//! was run with, the effective configuration, the commit of the audited code
+//! and the environment. It is captured when the analysis starts and the
+//! report's reproduction appendix is generated from it.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Stdio;
+use tokio::process::Command;
+
+use crate::core::capabilities::Capabilities;
+use crate::core::parser;
+use crate::report::vulnerability::{Provenance, Vulnerability};
+use crate::utils::abi::{encode_hex, keccak256};
+use crate::utils::config::Config;
+use crate::utils::platform;
+
+/// Placeholder for the audited file in tool command templates
+pub const FILE_PLACEHOLDER: &str = "{file}";
+
+/// Configuration keys whose values are never written to a manifest
+const SECRET_KEYS: &[&str] = &["api_key", "apikey", "secret", "password", "private_key"];
+
+/// An external tool as it was invoked during the run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolInvocation {
+    pub tool: String,
+    /// First line of `--version`, if the tool is installed
+    pub version: Option<String>,
+    /// Command line with `{file}` in place of the audited file
+    pub command: String,
+    /// Shell command preparing files the tool command expects
+    pub setup: Option<String>,
+}
+
+impl ToolInvocation {
+    /// Command line for a concrete file
+    pub fn command_for(&self, file: &str) -> String {
+        self.command.replace(FILE_PLACEHOLDER, &shell_quote(file))
+    }
+}
+
+/// An audited source file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceFile {
+    /// Name findings refer to in `file_path`
+    pub name: String,
+    pub path: String,
+    pub keccak256: String,
+}
+
+/// Everything needed to reproduce an analysis run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunManifest {
+    /// Identifies the run in the provenance of its findings
+    #[serde(default)]
+    pub run_id: String,
+    pub securechain_version: String,
+    /// Command line SecureChain was started with
+    pub invocation: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub input_path: String,
+    pub target: String,
+    pub depth: String,
+    /// Commit of the repository containing the input, if any
+    pub git_commit: Option<String>,
+    /// Whether the input had uncommitted changes
+    pub git_dirty: bool,
+    pub os: String,
+    pub arch: String,
+    pub tools: Vec<ToolInvocation>,
+    /// Effective configuration with secrets removed
+    pub config: serde_json::Value,
+    pub sources: Vec<SourceFile>,
+}
+
+impl RunManifest {
+    /// Capture the manifest for a run about to analyse `input_path`
+    pub async fn capture(config: &Config, input_path: &Path, target: &str, depth: &str) -> Self {
+        let started_at = chrono::Utc::now();
+        let (git_commit, git_dirty) = git_state(input_path).await;
+
+        let sources = source_files(input_path);
+        let vyper = sources.iter().any(|source| parser::is_vyper_file(&source.name));
+        let mut tools = Vec::new();
+        for mut invocation in tool_invocations(config, input_path, target, depth, vyper) {
+            let executable = invocation.command.split_whitespace().next().unwrap_or_default().to_string();
+            invocation.version = tool_version(&executable).await;
+            tools.push(invocation);
+        }
+
+        let mut config_value = serde_json::to_value(config).unwrap_or_default();
+        redact_secrets(&mut config_value);
+
+        let mut manifest = Self {
+            run_id: uuid::Uuid::new_v4().to_string(),
+            securechain_version: env!("CARGO_PKG_VERSION").to_string(),
+            invocation: std::env::args().map(|arg| shell_quote(&arg)).collect::<Vec<_>>().join(" "),
+            started_at,
+            input_path: input_path.display().to_string(),
+            target: target.to_string(),
+            depth: depth.to_string(),
+            git_commit,
+            git_dirty,
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            tools,
+            config: config_value,
+            sources,
+        };
+        // Detectors are only known to be missing once the version is
+        let capabilities = Capabilities::from_manifest(&manifest);
+        if let Some(slither) = manifest.tools.iter_mut().find(|invocation| invocation.tool == "Slither") {
+            slither.command = slither_command(config, input_path, &capabilities);
+        }
+        manifest
+    }
+
+    /// Invocation record of a tool, by the name findings carry in `tool`
+    pub fn tool(&self, name: &str) -> Option<&ToolInvocation> {
+        self.tools.iter().find(|invocation| invocation.tool == name)
+    }
+
+    /// Give findings produced by this run, and not seen before, this run's provenance
+    pub fn stamp(&self, findings: &mut [Vulnerability]) {
+        let detected_at = chrono::Utc::now();
+        for finding in findings.iter_mut().filter(|finding| finding.provenance.is_none()) {
+            // Tools outside the manifest are SecureChain's own detectors
+            let tool_version = match self.tool(&finding.tool) {
+                Some(invocation) => invocation.version.clone(),
+                None => Some(self.securechain_version.clone()),
+            };
+            finding.provenance = Some(Provenance {
+                detected_at,
+                tool_version,
+                run_id: self.run_id.clone(),
+                git_commit: self.git_commit.clone(),
+            });
+        }
+    }
+
+    /// Path of the source file a finding's `file_path` refers to
+    ///
+    /// `file` is the finding's file relative to the input, when the results
+    /// attribute it to a contract; without it the first source of that name wins.
+    pub fn source_path<'a>(&'a self, name: &'a str, file: Option<&str>) -> &'a str {
+        file.and_then(|file| self.sources.iter().find(|source| Path::new(&source.path).ends_with(file)))
+            .or_else(|| self.sources.iter().find(|source| source.name == name))
+            .map(|source| source.path.as_str())
+            .unwrap_or(name)
+    }
+}
+
+/// Commands the analysis engine runs for this target and depth
+fn tool_invocations(config: &Config, input_path: &Path, target: &str, depth: &str, vyper: bool) -> Vec<ToolInvocation> {
+    let mut tools = Vec::new();
+    if target != "evm" {
+        return tools;
+    }
+
+    tools.push(invocation("Slither", &slither_command(config, input_path, &Capabilities::default()), None));
+    if depth == "deep" {
+        let mythril = &config.tools.mythril;
+        tools.push(invocation(
+            "Mythril",
+            &format!(
+                "{} {} {{file}} --max-depth {} --solver-timeout {} --execution-timeout {}",
+                mythril.executable,
+                mythril.args.join(" "),
+                mythril.max_depth,
+                mythril.solver_timeout,
+                (mythril.timeout * 4 / 5).max(1)
+            ),
+            None,
+        ));
+        tools.push(invocation("Echidna", "echidna-test {file} --format json", None));
+    }
+
+    let solhint = &config.tools.solhint;
+    if solhint.enabled {
+        let (config_path, setup) = match &solhint.config_file {
+            Some(path) => (path.display().to_string(), None),
+            None => {
+                let rules = serde_json::json!({ "extends": solhint.rule_packs }).to_string();
+                (".solhint.json".to_string(), Some(format!("echo {} > .solhint.json", shell_quote(&rules))))
+            }
+        };
+        tools.push(invocation(
+            "Solhint",
+            &format!("{} --formatter json --config {} {{file}}", solhint.executable, shell_quote(&config_path)),
+            setup,
+        ));
+    }
+
+    let semgrep = &config.tools.semgrep;
+    if semgrep.enabled {
+        let packs: String = semgrep
+            .rule_packs
+            .iter()
+            .map(|pack| format!(" --config {}", shell_quote(pack)))
+            .collect();
+        tools.push(invocation(
+            "Semgrep",
+            &format!("{} scan --json --quiet --metrics=off{} {{file}}", semgrep.executable, packs),
+            None,
+        ));
+    }
+
+    let solc = &config.tools.solc;
+    if solc.enabled {
+        // Without output flags solc only compiles and prints the same diagnostics
+        tools.push(invocation("Solc", &format!("{} {{file}}", solc.executable), None));
+    }
+
+    let vyper_config = &config.tools.vyper;
+    if vyper && vyper_config.enabled {
+        tools.push(invocation("Vyper", &format!("{} -f abi {{file}}", vyper_config.executable), None));
+    }
+
+    tools
+}
+
+/// Slither command line with the configured detectors the installed release has
+/// and the configured options; a framework compiles the project at `input_path`
+fn slither_command(config: &Config, input_path: &Path, capabilities: &Capabilities) -> String {
+    let slither = &config.tools.slither;
+    let include = capabilities.slither_detectors(&slither.include_detectors);
+    let exclude = capabilities.slither_detectors(&slither.exclude_detectors);
+    let mut command = vec![slither.executable.clone()];
+    match slither.compiles_project() {
+        true => {
+            command.extend([shell_quote(&project_dir(input_path).to_string_lossy()), "--compile-force-framework".to_string(), shell_quote(&slither.framework)]);
+        }
+        false => command.push(FILE_PLACEHOLDER.to_string()),
+    }
+    command.extend(slither.arguments(&include, &exclude).iter().map(|arg| shell_quote(arg)));
+    command.join(" ")
+}
+
+/// Directory of the project at `input_path`: the input itself, or the directory of an input file
+pub fn project_dir(input_path: &Path) -> &Path {
+    match input_path.parent() {
+        _ if input_path.is_dir() => input_path,
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+fn invocation(tool: &str, command: &str, setup: Option<String>) -> ToolInvocation {
+    ToolInvocation {
+        tool: tool.to_string(),
+        version: None,
+        command: command.to_string(),
+        setup,
+    }
+}
+
+/// First output line of `<executable> --version`, or `None` if it cannot be run
+async fn tool_version(executable: &str) -> Option<String> {
+    let output = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        platform::async_command(executable)
+            .arg("--version")
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+
+    let text = if output.stdout.is_empty() { output.stderr } else { output.stdout };
+    String::from_utf8_lossy(&text)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// HEAD commit and dirty state of the repository containing `path`
+async fn git_state(path: &Path) -> (Option<String>, bool) {
+    let directory = if path.is_dir() { path } else { path.parent().unwrap_or(Path::new(".")) };
+    let directory = if directory.as_os_str().is_empty() { Path::new(".") } else { directory };
+
+    let git = |args: &[&str]| {
+        let mut command = Command::new("git");
+        command.arg("-C").arg(directory).args(args).stdin(Stdio::null());
+        command
+    };
+
+    let commit = match git(&["rev-parse", "HEAD"]).output().await {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        _ => return (None, false),
+    };
+
+    let pathspec = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let dirty = git(&["status", "--porcelain", "--"])
+        .arg(&pathspec)
+        .output()
+        .await
+        .map(|output| !output.stdout.is_empty())
+        .unwrap_or(false);
+
+    (Some(commit), dirty)
+}
+
+/// Source files the analysis reads, with content hashes
+fn source_files(input_path: &Path) -> Vec<SourceFile> {
+    let paths: Vec<std::path::PathBuf> = if input_path.is_file() {
+        vec![input_path.to_path_buf()]
+    } else {
+        walkdir::WalkDir::new(input_path)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.into_path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "sol" || ext == "vy"))
+            .collect()
+    };
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let content = std::fs::read(&path).ok()?;
+            Some(SourceFile {
+                name: path.file_name()?.to_str()?.to_string(),
+                path: path.display().to_string(),
+                keccak256: format!("0x{}", encode_hex(&keccak256(&content))),
+            })
+        })
+        .collect()
+}
+
+/// Blank out configuration values that may hold credentials
+fn redact_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, entry) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if SECRET_KEYS.iter().any(|secret| key.contains(secret)) && !entry.is_null() {
+                    *entry = serde_json::Value::String("<redacted>".to_string());
+                } else {
+                    redact_secrets(entry);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
+}
+
+/// Quote an argument for POSIX shells when it needs quoting
+pub fn shell_quote(arg: &str) -> String {
+    let safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,{}".contains(c));
+    if safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
@@ -0,0 +1,267 @@
+//! Project metadata for report front matter
+//!
+//! An audit usually covers several contracts, each with its own compiler
+//! pragma, license and dependencies. This module collects them per contract,
+//! together with deployment addresses where the run knows them, so reports
+//! can describe what was audited instead of naming only the first file.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::core::fetcher::ContractInfo;
+use crate::core::parser::{matching_brace, ParsedContract};
+
+/// What a run audited
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectMetadata {
+    /// Name to head reports with
+    pub title: String,
+    pub contracts: Vec<ContractMetadata>,
+    /// Distinct compiler versions, exact where known and pragma ranges otherwise
+    pub compiler_versions: Vec<String>,
+    pub licenses: Vec<String>,
+    pub dependencies: Vec<Dependency>,
+}
+
+/// One contract, interface or library of the run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractMetadata {
+    pub name: String,
+    /// `contract`, `abstract contract`, `interface` or `library`
+    pub kind: String,
+    pub file: String,
+    /// Exact compiler version for verified sources, else the `pragma solidity` range
+    pub solc_version: Option<String>,
+    pub license: Option<String>,
+    /// Deployed address, for fetched contracts
+    pub address: Option<String>,
+    /// Network of the address, for contracts of a protocol fetched across several
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    /// Non-blank lines of code, excluding comments
+    pub lines_of_code: usize,
+}
+
+/// A third-party package the sources import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dependency {
+    pub name: String,
+    pub version: Option<String>,
+    /// Where the version was found
+    pub source: Option<String>,
+}
+
+impl ProjectMetadata {
+    /// Record the deployment of a fetched contract
+    pub fn set_deployment(&mut self, contract: &ContractInfo) {
+        let compiler_version = Some(contract.compiler_version.clone()).filter(|v| !v.is_empty() && v != "unknown");
+
+        for entry in self.contracts.iter_mut().filter(|entry| entry.name == contract.name) {
+            if !contract.address.is_empty() {
+                entry.address = Some(contract.address.clone());
+            }
+            if compiler_version.is_some() {
+                entry.solc_version = compiler_version.clone();
+            }
+        }
+        self.compiler_versions = distinct(self.contracts.iter().filter_map(|entry| entry.solc_version.clone()));
+    }
+
+    /// Record the address a contract is deployed at
+    pub fn set_address(&mut self, name: &str, address: &str) {
+        for entry in self.contracts.iter_mut().filter(|entry| entry.name == name) {
+            entry.address = Some(address.to_string());
+        }
+    }
+
+    /// Record the network and address a contract is deployed at
+    pub fn set_network_address(&mut self, name: &str, address: &str, network: &str) {
+        for entry in self.contracts.iter_mut().filter(|entry| entry.name == name) {
+            entry.address = Some(address.to_string());
+            entry.network = Some(network.to_string());
+        }
+    }
+
+    /// Contracts and abstract contracts, the units that hold code
+    pub fn deployable(&self) -> impl Iterator<Item = &ContractMetadata> {
+        self.contracts
+            .iter()
+            .filter(|entry| entry.kind.ends_with("contract"))
+    }
+
+    /// Total lines of code of the run
+    pub fn lines_of_code(&self) -> usize {
+        self.contracts.iter().map(|entry| entry.lines_of_code).sum()
+    }
+}
+
+/// Collect metadata for the parsed contracts of a run at `input_path`
+pub fn extract(input_path: &Path, contracts: &[ParsedContract]) -> ProjectMetadata {
+    let mut entries = Vec::new();
+    for contract in contracts {
+        let source = contract.code();
+        let solc_version = Some(contract.compiler_version.clone())
+            .filter(|v| !v.is_empty() && v != "unknown")
+            .or_else(|| pragma_version(&contract.pragma_directives));
+
+        for unit in &contract.units {
+            entries.push(ContractMetadata {
+                name: unit.name.clone(),
+                kind: unit.kind.clone(),
+                file: contract.name.clone(),
+                solc_version: solc_version.clone(),
+                license: contract.license.clone(),
+                address: None,
+                network: None,
+                lines_of_code: match contract.is_vyper() {
+                    // A Vyper file is its contract
+                    true => source.lines().filter(|line| !line.trim().is_empty()).count(),
+                    false => unit_lines_of_code(source, unit.line_number),
+                },
+            });
+        }
+    }
+
+    let mut metadata = ProjectMetadata {
+        title: String::new(),
+        compiler_versions: distinct(entries.iter().filter_map(|entry| entry.solc_version.clone())),
+        licenses: distinct(entries.iter().filter_map(|entry| entry.license.clone())),
+        dependencies: dependencies(input_path, contracts),
+        contracts: entries,
+    };
+    metadata.title = title(&metadata, contracts);
+    metadata
+}
+
+/// Up to three contract names, or the file name when nothing deployable was parsed
+fn title(metadata: &ProjectMetadata, contracts: &[ParsedContract]) -> String {
+    let names: Vec<&str> = metadata.deployable().map(|entry| entry.name.as_str()).collect();
+    match names.len() {
+        0 => contracts.first().map(|contract| contract.name.clone()).unwrap_or_default(),
+        1..=3 => names.join(", "),
+        n => format!("{} and {} more contracts", names[..2].join(", "), n - 2),
+    }
+}
+
+fn pragma_version(pragmas: &[String]) -> Option<String> {
+    pragmas
+        .iter()
+        .find_map(|pragma| pragma.trim().strip_prefix("solidity"))
+        .map(|version| version.trim().to_string())
+}
+
+/// Lines of the unit declared at `line_number`, from its header to its closing brace
+fn unit_lines_of_code(source: &str, line_number: usize) -> usize {
+    let start: usize = source
+        .split_inclusive('\n')
+        .take(line_number.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    let end = source[start..]
+        .find('{')
+        .and_then(|open| matching_brace(source, start + open))
+        .map_or(source.len(), |close| close + 1);
+
+    source[start..end].lines().filter(|line| !line.trim().is_empty()).count()
+}
+
+/// Packages imported by the sources, with versions from the project's manifests
+fn dependencies(input_path: &Path, contracts: &[ParsedContract]) -> Vec<Dependency> {
+    let import_pattern = Regex::new(r#"\bimport\s+[^;]*?["']([^"']+)["']"#).expect("valid import pattern");
+    // Flattened and verified sources keep the header of each OpenZeppelin file
+    let openzeppelin_pattern =
+        Regex::new(r"OpenZeppelin Contracts(?: \(last updated)? v(\d+\.\d+\.\d+)").expect("valid version pattern");
+
+    let mut packages: BTreeMap<String, Option<(String, String)>> = BTreeMap::new();
+    for contract in contracts {
+        for captures in import_pattern.captures_iter(&contract.source_code) {
+            if let Some(package) = package_name(&captures[1]) {
+                packages.entry(package).or_default();
+            }
+        }
+        if let Some(captures) = openzeppelin_pattern.captures(&contract.source_code) {
+            packages
+                .entry("@openzeppelin/contracts".to_string())
+                .or_insert_with(|| Some((captures[1].to_string(), "source header".to_string())));
+        }
+    }
+
+    let root = project_root(input_path);
+    packages
+        .into_iter()
+        .map(|(name, known)| {
+            let found = known.or_else(|| root.as_deref().and_then(|root| installed_version(root, &name)));
+            Dependency {
+                name,
+                version: found.as_ref().map(|(version, _)| version.clone()),
+                source: found.map(|(_, source)| source),
+            }
+        })
+        .collect()
+}
+
+/// Package an import path refers to, `None` for relative imports
+fn package_name(import: &str) -> Option<String> {
+    if import.starts_with('.') || import.starts_with('/') {
+        return None;
+    }
+    let mut segments = import.split('/');
+    let first = segments.next()?;
+    if first.starts_with('@') {
+        Some(format!("{}/{}", first, segments.next()?))
+    } else if first == "lib" {
+        segments.next().map(str::to_string)
+    } else if segments.next().is_some() {
+        Some(first.to_string())
+    } else {
+        None
+    }
+}
+
+/// Nearest directory at or above the input holding a package manifest or a Foundry project
+fn project_root(input_path: &Path) -> Option<std::path::PathBuf> {
+    let start = if input_path.is_file() { input_path.parent()? } else { input_path };
+    let start = start.canonicalize().ok()?;
+    start
+        .ancestors()
+        .find(|dir| ["package.json", "foundry.toml", "node_modules", "lib"].iter().any(|marker| dir.join(marker).exists()))
+        .map(Path::to_path_buf)
+}
+
+/// Installed version of a package, else the range declared in package.json
+fn installed_version(root: &Path, package: &str) -> Option<(String, String)> {
+    let read_version = |path: &Path| -> Option<String> {
+        let manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+        manifest["version"].as_str().map(str::to_string)
+    };
+
+    let installed = [
+        Path::new("node_modules").join(package).join("package.json"),
+        Path::new("lib").join(package.trim_start_matches('@').replace('/', "-")).join("package.json"),
+        Path::new("lib").join(package).join("package.json"),
+    ];
+    for relative in &installed {
+        if let Some(version) = read_version(&root.join(relative)) {
+            return Some((version, relative.display().to_string()));
+        }
+    }
+
+    let manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(root.join("package.json")).ok()?).ok()?;
+    ["dependencies", "devDependencies"].iter().find_map(|section| {
+        manifest[section][package]
+            .as_str()
+            .map(|range| (range.to_string(), "package.json".to_string()))
+    })
+}
+
+fn distinct(values: impl Iterator<Item = String>) -> Vec<String> {
+    let mut distinct: Vec<String> = Vec::new();
+    for value in values {
+        if !distinct.contains(&value) {
+            distinct.push(value);
+        }
+    }
+    distinct
+}
@@ -3,8 +3,74 @@
 //! This module contains the main analysis engine and supporting components
 //! for smart contract security auditing.
 
+pub mod ai_log;
+pub mod ai_provider;
+pub mod ai_usage;
 pub mod analyzer;
+pub mod annotate;
+pub mod architecture;
+#[cfg(feature = "execution")]
+pub mod arithmetic;
+pub mod baseline;
+pub mod bytecode;
+pub mod callgraph;
+pub mod capabilities;
+pub mod ci;
+#[cfg(feature = "ai")]
+pub mod clustering;
+pub mod community;
+pub mod completeness;
+pub mod daemon;
+pub mod dataflow;
+pub mod dead_code;
+pub mod dedup;
+pub mod deployment;
+pub mod detector_catalog;
+pub mod detectors;
+pub mod diamond;
+pub mod error_handling;
+pub mod exploitability;
 pub mod fetcher;
+pub mod fuzz_coverage;
+pub mod gas;
+pub mod history;
+pub mod import;
+pub mod integration;
+pub mod inventory;
+pub mod jobs;
+pub mod manifest;
+pub mod metadata;
+pub mod notify;
 pub mod parser;
+pub mod poc_verify;
+pub mod project_graph;
+pub mod protocol;
+pub mod proxy;
+pub mod remediation;
+pub mod risk;
+pub mod rpc;
+pub mod selectors;
+pub mod selftest;
+pub mod server;
+pub mod simulation;
+pub mod snapshot;
+pub mod solc_ast;
+pub mod suppression;
+pub mod test_review;
+pub mod toolchain;
+pub mod tree_shaking;
+pub mod triage;
+pub mod validation;
+pub mod verification;
+pub mod vyper;
+#[cfg(feature = "ai")]
 pub mod ai_assist;
+#[cfg(feature = "ai")]
+pub mod ai_chunks;
+#[cfg(feature = "ai")]
+pub mod ai_health;
+#[cfg(feature = "ai")]
+pub mod knowledge;
+#[cfg(feature = "fuzzing")]
 pub mod fuzz_engine;
+pub mod hook;
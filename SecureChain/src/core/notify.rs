@@ -0,0 +1,67 @@
+//! Notifications
+//!
+//! Some outcomes need someone's attention before the next report is read:
+//! an accepted risk whose acceptance expired, or a scheduled scan of
+//! `securechain serve` that failed. Each is posted as JSON to every URL in
+//! `notifications.webhooks`. The `text` field makes the payload a Slack or
+//! Mattermost incoming webhook message, and `event` and `details` carry the
+//! same for other receivers. A webhook that fails is logged; it never fails
+//! the run that raised the notification.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::utils::config::NotificationsConfig;
+
+/// One outcome to tell the webhooks about
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    /// What happened, such as `acceptance_expired`
+    pub event: String,
+    /// One-line summary, what chat webhooks show
+    pub text: String,
+    pub details: Value,
+}
+
+impl Notification {
+    pub fn new(event: &str, text: impl Into<String>, details: Value) -> Self {
+        Self { event: event.to_string(), text: text.into(), details }
+    }
+}
+
+/// Post `notification` to every configured webhook; how many took it
+#[cfg(feature = "explorers")]
+pub fn send(config: &NotificationsConfig, notification: &Notification) -> usize {
+    let body = match serde_json::to_value(notification) {
+        Ok(body) => body,
+        Err(e) => {
+            log::warn!("Notification {} not sent: {}", notification.event, e);
+            return 0;
+        }
+    };
+    config
+        .webhooks
+        .iter()
+        .filter(|url| {
+            let response = ureq::post(url)
+                .timeout(std::time::Duration::from_secs(config.timeout))
+                .set("User-Agent", "SecureChain")
+                .send_json(body.clone());
+            match response {
+                Ok(_) => true,
+                Err(e) => {
+                    log::warn!("Webhook {} refused notification {}: {}", url, notification.event, e);
+                    false
+                }
+            }
+        })
+        .count()
+}
+
+#[cfg(not(feature = "explorers"))]
+pub fn send(config: &NotificationsConfig, notification: &Notification) -> usize {
+    if !config.webhooks.is_empty() {
+        log::warn!("Notification {} not sent: webhooks need a build with the `explorers` feature", notification.event);
+    }
+    0
+}
@@ -2,18 +2,29 @@
 //! 
 //! This module handles parsing of smart contract source code
 //! and extraction of relevant metadata for security analysis.
+//!
+//! Solidity is split into units by braces. Vyper files are one contract
+//! each, laid out by indentation: decorated `def` blocks become the unit's
+//! functions, `name: type` declarations its state variables, and `event`
+//! and `interface` blocks the events and the interface units.
 
 use anyhow::Result;
 use regex::Regex;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Deref, Range};
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
 
 use crate::core::fetcher::ContractInfo;
+use crate::core::solc_ast;
+use crate::utils::config::SolcConfig;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsedContract {
     pub name: String,
-    pub source_code: String,
+    pub source_code: Arc<str>,
     pub functions: Vec<FunctionInfo>,
     pub state_variables: Vec<StateVariable>,
     pub modifiers: Vec<ModifierInfo>,
@@ -24,6 +35,193 @@ pub struct ParsedContract {
     pub pragma_directives: Vec<String>,
     pub license: Option<String>,
     pub metadata: HashMap<String, String>,
+    /// Contracts, interfaces and libraries declared in the source
+    #[serde(default)]
+    pub units: Vec<ContractUnit>,
+    /// "solidity" or "vyper"
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Source with comments blanked out, shared with the unit function bodies
+    #[serde(skip)]
+    code: OnceLock<Arc<str>>,
+}
+
+fn default_language() -> String {
+    "solidity".to_string()
+}
+
+impl ParsedContract {
+    /// Source with comments replaced by spaces, computed once and shared
+    pub fn code(&self) -> &str {
+        self.code.get_or_init(|| match self.is_vyper() {
+            true => strip_vyper_comments(&self.source_code).into(),
+            false => strip_comments(&self.source_code).into(),
+        })
+    }
+
+    /// Byte offset in the source of a span of `code()`, such as a unit function's body
+    pub fn offset_of(&self, span: &SourceSpan) -> Option<usize> {
+        span.offset_in(self.code.get()?)
+    }
+
+    pub fn is_vyper(&self) -> bool {
+        self.language == "vyper"
+    }
+
+    /// Path of the source relative to the input directory, or its name for single files
+    pub fn path(&self) -> &str {
+        self.metadata.get("path").unwrap_or(&self.name)
+    }
+
+    /// Path an import of this source refers to, relative to the input
+    /// directory; `None` for imports that are not relative (`./`, `../`),
+    /// which resolve through remappings or the include path
+    pub fn resolve_import(&self, import: &str) -> Option<String> {
+        if !import.starts_with("./") && !import.starts_with("../") {
+            return None;
+        }
+        let path = self.path().replace('\\', "/");
+        let mut parts: Vec<&str> = path.split('/').collect();
+        parts.pop();
+        for part in import.split('/') {
+            match part {
+                "" | "." => {}
+                ".." => {
+                    parts.pop()?;
+                }
+                _ => parts.push(part),
+            }
+        }
+        Some(parts.join("/"))
+    }
+}
+
+/// Whether a file name is a Vyper source, by its extension
+pub fn is_vyper_file(name: &str) -> bool {
+    name.ends_with(".vy") || name.ends_with(".vyi")
+}
+
+/// A range of a contract's source, sharing the buffer instead of copying it
+///
+/// Bodies of every function in a large flattened file would otherwise hold
+/// the file several times over, and again for each clone of the contract.
+#[derive(Clone)]
+pub struct SourceSpan {
+    source: Arc<str>,
+    range: Range<usize>,
+}
+
+impl SourceSpan {
+    pub fn new(source: &Arc<str>, range: Range<usize>) -> Self {
+        Self { source: Arc::clone(source), range }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source[self.range.clone()]
+    }
+
+    /// Byte offset of the span in `source`, if it was cut from that buffer
+    pub fn offset_in(&self, source: &Arc<str>) -> Option<usize> {
+        Arc::ptr_eq(&self.source, source).then_some(self.range.start)
+    }
+}
+
+impl Deref for SourceSpan {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SourceSpan {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for SourceSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for SourceSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// Spans are written as their text; read back, each owns its own buffer
+impl Serialize for SourceSpan {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for SourceSpan {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let range = 0..text.len();
+        Ok(Self { source: text.into(), range })
+    }
+}
+
+/// A single contract, interface or library declaration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractUnit {
+    pub name: String,
+    /// "contract", "abstract contract", "interface" or "library"
+    pub kind: String,
+    pub inheritance: Vec<String>,
+    pub functions: Vec<UnitFunction>,
+    /// Constructor, kept apart from the functions since it has no selector
+    #[serde(default)]
+    pub constructor: Option<UnitFunction>,
+    pub state_variables: Vec<UnitStateVariable>,
+    pub line_number: usize,
+}
+
+/// A function declared inside a contract unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnitFunction {
+    pub name: String,
+    /// Parameter types as written, without data locations or names
+    pub parameter_types: Vec<String>,
+    /// Parameter names, empty for unnamed parameters
+    #[serde(default)]
+    pub parameter_names: Vec<String>,
+    pub return_types: Vec<String>,
+    pub visibility: String,
+    pub state_mutability: String,
+    pub is_virtual: bool,
+    pub is_override: bool,
+    pub modifiers: Vec<String>,
+    pub line_number: usize,
+    /// Function body without the surrounding braces (None if unimplemented)
+    pub body: Option<SourceSpan>,
+    /// Line of the body's opening brace
+    #[serde(default)]
+    pub body_line_number: usize,
+}
+
+/// A state variable declared at the top level of a contract unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnitStateVariable {
+    pub name: String,
+    pub type_name: String,
+    pub visibility: String,
+    pub is_constant: bool,
+    pub is_immutable: bool,
+    pub line_number: usize,
+}
+
+/// Struct and enum definitions found anywhere in a source file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserTypes {
+    /// Struct name to field types
+    pub structs: HashMap<String, Vec<String>>,
+    pub enums: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,7 +233,7 @@ pub struct FunctionInfo {
     pub return_parameters: Vec<Parameter>,
     pub modifiers: Vec<String>,
     pub line_number: usize,
-    pub body: String,
+    pub body: SourceSpan,
     pub is_constructor: bool,
     pub is_fallback: bool,
     pub is_receive: bool,
@@ -56,7 +254,7 @@ pub struct StateVariable {
 pub struct ModifierInfo {
     pub name: String,
     pub parameters: Vec<Parameter>,
-    pub body: String,
+    pub body: SourceSpan,
     pub line_number: usize,
 }
 
@@ -85,6 +283,8 @@ pub struct ContractParser {
     pragma_pattern: Regex,
     license_pattern: Regex,
     inheritance_pattern: Regex,
+    /// solc to take Solidity functions, modifiers and inheritance from, when set
+    solc: Option<SolcConfig>,
 }
 
 impl ContractParser {
@@ -107,7 +307,8 @@ impl ContractParser {
         )?;
         
         let import_pattern = Regex::new(
-            r#"import\s+(?:"([^"]+)"|'([^']+)')"#
+            // `import "x";`, `import "x" as X;`, `import * as X from "x";` and `import {A, B as C} from "x";`
+            r#"import\s+(?:[^;"']*?\bfrom\s+)?(?:"([^"]+)"|'([^']+)')"#
         )?;
         
         let pragma_pattern = Regex::new(
@@ -131,26 +332,51 @@ impl ContractParser {
             pragma_pattern,
             license_pattern,
             inheritance_pattern,
+            solc: None,
         })
     }
 
+    /// Take Solidity functions, modifiers and inheritance from solc's AST when
+    /// `tools.solc.ast` is on, keeping the regular expressions for sources solc cannot parse
+    pub fn with_solc(mut self, solc: &SolcConfig) -> Self {
+        self.solc = Some(solc.clone()).filter(|solc| solc.ast);
+        self
+    }
+
     /// Parse a contract from ContractInfo
     pub fn parse_contract(&self, contract_info: &ContractInfo) -> Result<ParsedContract> {
-        let source_code = &contract_info.source_code;
-        
+        // One buffer for the source and one for its comment-free form; everything else points into them
+        let source_code: Arc<str> = contract_info.source_code.as_str().into();
+        if is_vyper_file(&contract_info.name) {
+            return self.parse_vyper(contract_info, source_code);
+        }
+        let code: Arc<str> = strip_comments(&source_code).into();
+
         // Extract basic information
-        let functions = self.extract_functions(source_code)?;
-        let state_variables = self.extract_state_variables(source_code)?;
-        let modifiers = self.extract_modifiers(source_code)?;
-        let events = self.extract_events(source_code)?;
-        let imports = self.extract_imports(source_code)?;
-        let pragma_directives = self.extract_pragma_directives(source_code)?;
-        let license = self.extract_license(source_code)?;
-        let inheritance = self.extract_inheritance(source_code)?;
+        let ast = self.solc.as_ref().map(|solc| solc_ast::parse(&contract_info.name, &source_code, solc));
+        let (functions, modifiers, inheritance) = match ast {
+            Some(Ok(declarations)) => (declarations.functions, declarations.modifiers, declarations.inheritance),
+            ast => {
+                if let Some(Err(e)) = ast {
+                    log::debug!("Parsing {} without solc's AST: {}", contract_info.name, e);
+                }
+                (
+                    self.extract_functions(&source_code)?,
+                    self.extract_modifiers(&source_code)?,
+                    self.extract_inheritance(&source_code)?,
+                )
+            }
+        };
+        let state_variables = self.extract_state_variables(&source_code)?;
+        let events = self.extract_events(&source_code)?;
+        let imports = self.extract_imports(&source_code)?;
+        let pragma_directives = self.extract_pragma_directives(&source_code)?;
+        let license = self.extract_license(&source_code)?;
+        let units = self.units_of(&code)?;
 
         Ok(ParsedContract {
             name: contract_info.name.clone(),
-            source_code: source_code.clone(),
+            source_code,
             functions,
             state_variables,
             modifiers,
@@ -161,15 +387,229 @@ impl ContractParser {
             pragma_directives,
             license,
             metadata: contract_info.metadata.clone(),
+            units,
+            language: default_language(),
+            code: OnceLock::from(code),
+        })
+    }
+
+    /// Parse a Vyper source, which declares one contract named after its file
+    fn parse_vyper(&self, contract_info: &ContractInfo, source_code: Arc<str>) -> Result<ParsedContract> {
+        let code: Arc<str> = strip_vyper_comments(&source_code).into();
+        let lines = LineIndex::new(&code);
+        let pragma_pattern = Regex::new(r"(?m)^\s*#\s*(?:@version|pragma\s+version)\s+(\S.*?)\s*$")?;
+        let license_pattern = Regex::new(r"(?m)^\s*#\s*(?:SPDX-License-Identifier:|@license)\s*(\S.*?)\s*$")?;
+        let function_pattern = Regex::new(r"^def\s+(\w+)\s*\(")?;
+        let state_pattern = Regex::new(r"^(\w+)\s*:\s*(.+?)\s*(?:=\s*(.+))?$")?;
+
+        let file_name = Path::new(&contract_info.name).file_stem().and_then(|stem| stem.to_str()).unwrap_or(&contract_info.name);
+        let mut unit = ContractUnit {
+            name: file_name.to_string(),
+            kind: "contract".to_string(),
+            inheritance: Vec::new(),
+            functions: Vec::new(),
+            constructor: None,
+            state_variables: Vec::new(),
+            line_number: 1,
+        };
+        let mut interfaces = Vec::new();
+        let mut functions = Vec::new();
+        let mut state_variables = Vec::new();
+        let mut events = Vec::new();
+        let mut imports = Vec::new();
+        let mut decorators: Vec<&str> = Vec::new();
+
+        let mut offset = 0;
+        while offset < code.len() {
+            let line_end = code[offset..].find('\n').map_or(code.len(), |i| offset + i);
+            let line = &code[offset..line_end];
+            if line.trim().is_empty() || line.starts_with(char::is_whitespace) {
+                offset = line_end + 1;
+                continue;
+            }
+
+            // Statements run over several lines while brackets are open; indented lines below belong to them
+            let header_end = statement_end(&code, offset);
+            let header = code[offset..header_end].trim();
+            let block_start = (header_end + 1).min(code.len());
+            let block_end = indented_block_end(&code, block_start);
+            let line_number = lines.line(offset);
+            let next = block_end.max(header_end + 1);
+
+            if let Some(decorator) = header.strip_prefix('@') {
+                decorators.push(decorator);
+                offset = next;
+                continue;
+            }
+
+            if let Some(captures) = function_pattern.captures(header) {
+                let body_end = block_start + code[block_start..block_end].trim_end().len();
+                let body = (body_end > block_start).then(|| SourceSpan::new(&code, block_start..body_end));
+                if let Some(function) =
+                    vyper_function(&captures[1], header, &decorators, body, line_number, lines.line(block_start))
+                {
+                    functions.push(FunctionInfo {
+                        name: function.name.clone(),
+                        visibility: function.visibility.clone(),
+                        state_mutability: function.state_mutability.clone(),
+                        parameters: function
+                            .parameter_names
+                            .iter()
+                            .zip(&function.parameter_types)
+                            .map(|(name, type_name)| Parameter { name: name.clone(), type_name: type_name.clone(), indexed: false })
+                            .collect(),
+                        return_parameters: function
+                            .return_types
+                            .iter()
+                            .map(|type_name| Parameter { name: String::new(), type_name: type_name.clone(), indexed: false })
+                            .collect(),
+                        modifiers: function.modifiers.clone(),
+                        line_number,
+                        body: function.body.clone().unwrap_or_else(|| SourceSpan::new(&code, block_start..block_start)),
+                        is_constructor: function.name == "__init__",
+                        is_fallback: function.name == "__default__",
+                        is_receive: false,
+                    });
+                    if function.name == "__init__" {
+                        unit.constructor = Some(function);
+                    } else {
+                        unit.functions.push(function);
+                    }
+                }
+                decorators.clear();
+                offset = next;
+                continue;
+            }
+            decorators.clear();
+
+            let block = &code[block_start..block_end];
+            let mut words = header.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some("event"), Some(name)) => events.push(EventInfo {
+                    name: name.trim_end_matches(':').to_string(),
+                    parameters: block
+                        .lines()
+                        .filter_map(|field| field.split_once(':'))
+                        .map(|(name, type_name)| {
+                            let type_name = type_name.trim();
+                            let indexed = type_name.starts_with("indexed(");
+                            let type_name = unwrap_type(type_name, "indexed").unwrap_or(type_name);
+                            Parameter { name: name.trim().to_string(), type_name: type_name.to_string(), indexed }
+                        })
+                        .collect(),
+                    anonymous: false,
+                    line_number,
+                }),
+                (Some("interface"), Some(name)) => {
+                    let name = name.trim_end_matches(':').to_string();
+                    let mut interface = ContractUnit {
+                        name,
+                        kind: "interface".to_string(),
+                        inheritance: Vec::new(),
+                        functions: Vec::new(),
+                        constructor: None,
+                        state_variables: Vec::new(),
+                        line_number,
+                    };
+                    // Interface functions are one line each, ending in their mutability: `def f() -> uint256: view`
+                    let mut member_offset = block_start;
+                    for member in block.split_inclusive('\n') {
+                        let trimmed = member.trim();
+                        if let Some(captures) = function_pattern.captures(trimmed) {
+                            let mutability = trimmed.rsplit_once(':').map_or("", |(_, mutability)| mutability.trim());
+                            if let Some(function) = vyper_function(
+                                &captures[1],
+                                trimmed,
+                                &["external", mutability],
+                                None,
+                                lines.line(member_offset),
+                                lines.line(member_offset),
+                            ) {
+                                interface.functions.push(function);
+                            }
+                        }
+                        member_offset += member.len();
+                    }
+                    interfaces.push(interface);
+                }
+                (Some("struct" | "enum" | "flag"), _) => {}
+                (Some("implements:"), Some(name)) => unit.inheritance.push(name.to_string()),
+                (Some("from" | "import"), _) => imports.push(header.to_string()),
+                _ => {
+                    if let Some(captures) = state_pattern.captures(&header.split_whitespace().collect::<Vec<_>>().join(" ")) {
+                        let mut type_name = captures[2].to_string();
+                        let mut visibility = "internal";
+                        let (mut is_constant, mut is_immutable) = (false, false);
+                        // `public(constant(uint256))` wraps the type once per attribute
+                        loop {
+                            if let Some(inner) = unwrap_type(&type_name, "public") {
+                                visibility = "public";
+                                type_name = inner.to_string();
+                            } else if let Some(inner) = unwrap_type(&type_name, "constant") {
+                                is_constant = true;
+                                type_name = inner.to_string();
+                            } else if let Some(inner) = unwrap_type(&type_name, "immutable") {
+                                is_immutable = true;
+                                type_name = inner.to_string();
+                            } else if let Some(inner) = unwrap_type(&type_name, "transient") {
+                                type_name = inner.to_string();
+                            } else {
+                                break;
+                            }
+                        }
+
+                        state_variables.push(StateVariable {
+                            name: captures[1].to_string(),
+                            type_name: type_name.clone(),
+                            visibility: visibility.to_string(),
+                            is_constant,
+                            is_immutable,
+                            initial_value: captures.get(3).map(|m| m.as_str().to_string()),
+                            line_number,
+                        });
+                        unit.state_variables.push(UnitStateVariable {
+                            name: captures[1].to_string(),
+                            type_name,
+                            visibility: visibility.to_string(),
+                            is_constant,
+                            is_immutable,
+                            line_number,
+                        });
+                    }
+                }
+            }
+            offset = next;
+        }
+
+        let mut units = interfaces;
+        units.push(unit);
+        Ok(ParsedContract {
+            name: contract_info.name.clone(),
+            functions,
+            state_variables,
+            modifiers: Vec::new(),
+            events,
+            imports,
+            inheritance: units.last().map(|unit| unit.inheritance.clone()).unwrap_or_default(),
+            compiler_version: contract_info.compiler_version.clone(),
+            pragma_directives: pragma_pattern
+                .captures_iter(&source_code)
+                .map(|captures| format!("version {}", &captures[1]))
+                .collect(),
+            license: license_pattern.captures(&source_code).map(|captures| captures[1].to_string()),
+            metadata: contract_info.metadata.clone(),
+            units,
+            language: "vyper".to_string(),
+            source_code,
+            code: OnceLock::from(code),
         })
     }
 
     /// Extract function information from source code
-    fn extract_functions(&self, source_code: &str) -> Result<Vec<FunctionInfo>> {
+    fn extract_functions(&self, source_code: &Arc<str>) -> Result<Vec<FunctionInfo>> {
         let mut functions = Vec::new();
-        let lines: Vec<&str> = source_code.lines().collect();
 
-        for (line_num, line) in lines.iter().enumerate() {
+        for (line_num, (offset, line)) in lines_with_offsets(source_code).enumerate() {
             if let Some(captures) = self.function_pattern.captures(line) {
                 let name = captures.get(1).map(|m| m.as_str()).unwrap_or("").to_string();
                 let params_str = captures.get(2).map(|m| m.as_str()).unwrap_or("");
@@ -181,7 +621,7 @@ impl ContractParser {
                 let return_parameters = self.parse_parameters(returns_str)?;
 
                 // Extract function body (simplified)
-                let body = self.extract_function_body(source_code, line_num)?;
+                let body = self.extract_function_body(source_code, offset)?;
 
                 functions.push(FunctionInfo {
                     name: name.clone(),
@@ -231,18 +671,17 @@ impl ContractParser {
     }
 
     /// Extract modifiers from source code
-    fn extract_modifiers(&self, source_code: &str) -> Result<Vec<ModifierInfo>> {
+    fn extract_modifiers(&self, source_code: &Arc<str>) -> Result<Vec<ModifierInfo>> {
         let mut modifiers = Vec::new();
-        let lines: Vec<&str> = source_code.lines().collect();
 
-        for (line_num, line) in lines.iter().enumerate() {
+        for (line_num, (offset, line)) in lines_with_offsets(source_code).enumerate() {
             if let Some(captures) = self.modifier_pattern.captures(line) {
                 let name = captures.get(1).map(|m| m.as_str()).unwrap_or("").to_string();
                 let params_str = captures.get(2).map(|m| m.as_str()).unwrap_or("");
                 let parameters = self.parse_parameters(params_str)?;
 
                 // Extract modifier body (simplified)
-                let body = self.extract_modifier_body(source_code, line_num)?;
+                let body = self.extract_modifier_body(source_code, offset)?;
 
                 modifiers.push(ModifierInfo {
                     name,
@@ -351,11 +790,16 @@ impl ContractParser {
                 continue;
             }
 
-            let parts: Vec<&str> = param.split_whitespace().collect();
-            if parts.len() >= 2 {
-                let type_name = parts[0].to_string();
-                let name = parts[1].to_string();
-                let indexed = param.contains("indexed");
+            let words: Vec<&str> = param.split_whitespace().collect();
+            let indexed = words.contains(&"indexed");
+            // Unnamed parameters still count for signatures; `indexed`, `payable` and data locations are not names
+            let parts: Vec<&str> = words
+                .into_iter()
+                .filter(|word| !matches!(*word, "indexed" | "payable" | "memory" | "calldata" | "storage"))
+                .collect();
+            if let Some(type_name) = parts.first() {
+                let type_name = type_name.to_string();
+                let name = if parts.len() >= 2 { parts[parts.len() - 1].to_string() } else { String::new() };
 
                 parameters.push(Parameter {
                     name,
@@ -369,39 +813,572 @@ impl ContractParser {
     }
 
     /// Extract function body (simplified implementation)
-    fn extract_function_body(&self, source_code: &str, start_line: usize) -> Result<String> {
-        let lines: Vec<&str> = source_code.lines().collect();
-        let mut body = String::new();
-        let mut brace_count = 0;
-        let mut started = false;
+    ///
+    /// The body spans whole lines, from the one with the opening brace to the one where braces balance.
+    fn extract_function_body(&self, source_code: &Arc<str>, start: usize) -> Result<SourceSpan> {
+        let mut brace_count = 0i64;
+        let mut body_start = None;
+        let mut line_start = start;
 
-        for (_i, line) in lines.iter().enumerate().skip(start_line) {
-            for ch in line.chars() {
-                if ch == '{' {
+        for (i, c) in source_code.bytes().enumerate().skip(start) {
+            match c {
+                b'{' => {
                     brace_count += 1;
-                    started = true;
-                } else if ch == '}' {
-                    brace_count -= 1;
+                    body_start.get_or_insert(line_start);
                 }
+                b'}' => brace_count -= 1,
+                b'\n' => {
+                    if let (Some(body_start), 0) = (body_start, brace_count) {
+                        return Ok(SourceSpan::new(source_code, body_start..i + 1));
+                    }
+                    line_start = i + 1;
+                }
+                _ => {}
             }
+        }
+
+        let body_start = body_start.unwrap_or(source_code.len());
+        Ok(SourceSpan::new(source_code, body_start..source_code.len()))
+    }
+
+    /// Extract modifier body (simplified implementation)
+    fn extract_modifier_body(&self, source_code: &Arc<str>, start: usize) -> Result<SourceSpan> {
+        // Similar to extract_function_body but for modifiers
+        self.extract_function_body(source_code, start)
+    }
+
+    /// Split the source into its contract, interface and library declarations
+    pub fn extract_units(&self, source_code: &str) -> Result<Vec<ContractUnit>> {
+        self.units_of(&strip_comments(source_code).into())
+    }
 
-            if started {
-                body.push_str(line);
-                body.push('\n');
+    /// Units of a source whose comments are already stripped, with bodies pointing into it
+    fn units_of(&self, source: &Arc<str>) -> Result<Vec<ContractUnit>> {
+        let lines = LineIndex::new(source);
+        let unit_pattern = Regex::new(
+            r"\b(abstract\s+contract|contract|interface|library)\s+(\w+)\s*(?:is\s+([^{]+))?\{"
+        )?;
+
+        let mut units = Vec::new();
+        for captures in unit_pattern.captures_iter(source) {
+            let header = captures.get(0).unwrap();
+            let open = header.end() - 1;
+            let close = match matching_brace(source, open) {
+                Some(close) => close,
+                None => continue,
+            };
+
+            let kind = captures[1].split_whitespace().collect::<Vec<_>>().join(" ");
+            let inheritance = captures
+                .get(3)
+                .map(|m| split_top_level(m.as_str(), ','))
+                .unwrap_or_default()
+                .into_iter()
+                // Drop base constructor arguments, e.g. `ERC20("Token", "TKN")`
+                .map(|base| base.split('(').next().unwrap_or("").trim().to_string())
+                .filter(|base| !base.is_empty())
+                .collect();
+
+            let body_start = open + 1;
+            let (constructors, functions): (Vec<_>, Vec<_>) = self
+                .extract_unit_functions(source, &lines, body_start, close)?
+                .into_iter()
+                .partition(|function| function.name == "constructor");
+            units.push(ContractUnit {
+                name: captures[2].to_string(),
+                kind,
+                inheritance,
+                functions,
+                constructor: constructors.into_iter().next(),
+                state_variables: self.extract_unit_state_variables(source, &lines, body_start, close)?,
+                line_number: lines.line(header.start()),
+            });
+        }
+
+        Ok(units)
+    }
+
+    /// Extract struct and enum definitions from the source
+    pub fn extract_user_types(&self, source_code: &str) -> Result<UserTypes> {
+        let source = strip_comments(source_code);
+        let struct_pattern = Regex::new(r"\bstruct\s+(\w+)\s*\{([^}]*)\}")?;
+        let enum_pattern = Regex::new(r"\benum\s+(\w+)\s*\{")?;
+
+        let mut user_types = UserTypes::default();
+        for captures in struct_pattern.captures_iter(&source) {
+            let fields = captures[2]
+                .split(';')
+                .filter_map(parameter_type)
+                .collect();
+            user_types.structs.insert(captures[1].to_string(), fields);
+        }
+        for captures in enum_pattern.captures_iter(&source) {
+            user_types.enums.push(captures[1].to_string());
+        }
+
+        Ok(user_types)
+    }
+
+    /// Extract the functions declared directly in a unit body, and its constructor under that name
+    fn extract_unit_functions(&self, source: &Arc<str>, lines: &LineIndex, start: usize, end: usize) -> Result<Vec<UnitFunction>> {
+        let function_pattern = Regex::new(r"\b(?:function\s+(\w+)|(constructor))\s*\(")?;
+        let returns_pattern = Regex::new(r"\breturns\s*\(")?;
+        let body = &source[start..end];
+        let depths = brace_depths(body);
+
+        let mut functions = Vec::new();
+        for captures in function_pattern.captures_iter(body) {
+            let name_match = captures.get(1).or_else(|| captures.get(2)).unwrap();
+            if depths[name_match.start()] != 0 {
+                continue;
             }
 
-            if started && brace_count == 0 {
-                break;
+            let params_open = start + captures.get(0).unwrap().end() - 1;
+            let params_close = match matching_paren(source, params_open) {
+                Some(close) => close,
+                None => continue,
+            };
+
+            // Header runs until the body or the terminating semicolon
+            let header_end = source[params_close..end]
+                .find(['{', ';'])
+                .map(|i| params_close + i)
+                .unwrap_or(end);
+            let mut attributes = &source[params_close + 1..header_end];
+
+            let mut return_types = Vec::new();
+            if let Some(returns) = returns_pattern.find(attributes) {
+                let returns_open = params_close + 1 + returns.end() - 1;
+                if let Some(returns_close) = matching_paren(source, returns_open) {
+                    return_types = split_top_level(&source[returns_open + 1..returns_close], ',')
+                        .iter()
+                        .filter_map(|param| parameter_type(param))
+                        .collect();
+                }
+                attributes = &attributes[..returns.start()];
             }
+
+            let words: Vec<&str> = attributes
+                .split(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == ',')
+                .filter(|word| !word.is_empty())
+                .collect();
+
+            let visibility = words
+                .iter()
+                .find(|word| matches!(**word, "external" | "public" | "internal" | "private"))
+                .map(|word| word.to_string())
+                .unwrap_or_else(|| "public".to_string());
+            let state_mutability = words
+                .iter()
+                .find(|word| matches!(**word, "view" | "pure" | "payable" | "constant"))
+                .map(|word| word.to_string())
+                .unwrap_or_default();
+            let modifiers = words
+                .iter()
+                .filter(|word| {
+                    !matches!(**word, "external" | "public" | "internal" | "private" | "view" | "pure"
+                        | "payable" | "constant" | "virtual" | "override")
+                        && word.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+                })
+                .map(|word| word.to_string())
+                .collect();
+
+            let function_body = if source[header_end..].starts_with('{') {
+                matching_brace(source, header_end).map(|close| SourceSpan::new(source, header_end + 1..close))
+            } else {
+                None
+            };
+
+            let parameters = split_top_level(&source[params_open + 1..params_close], ',');
+            functions.push(UnitFunction {
+                name: name_match.as_str().to_string(),
+                parameter_types: parameters.iter().filter_map(|param| parameter_type(param)).collect(),
+                parameter_names: parameters
+                    .iter()
+                    .filter(|param| !param.trim().is_empty())
+                    .map(|param| parameter_name(param))
+                    .collect(),
+                return_types,
+                visibility,
+                state_mutability,
+                is_virtual: words.contains(&"virtual"),
+                is_override: words.contains(&"override"),
+                modifiers,
+                line_number: lines.line(start + name_match.start()),
+                body: function_body,
+                body_line_number: lines.line(header_end),
+            });
         }
 
-        Ok(body)
+        Ok(functions)
     }
 
-    /// Extract modifier body (simplified implementation)
-    fn extract_modifier_body(&self, source_code: &str, start_line: usize) -> Result<String> {
-        // Similar to extract_function_body but for modifiers
-        self.extract_function_body(source_code, start_line)
+    /// Extract the state variables declared at the top level of a unit body
+    fn extract_unit_state_variables(&self, source: &str, lines: &LineIndex, start: usize, end: usize) -> Result<Vec<UnitStateVariable>> {
+        let declaration_pattern = Regex::new(
+            r"^\s*(mapping\s*\(.*\)|[A-Za-z_][\w.]*(?:\s*\[[^\]]*\])*)((?:\s+(?:public|private|internal|constant|immutable|override|transient))*)\s+(\w+)\s*(?:=[\s\S]*)?$"
+        )?;
+        let keywords = ["function", "modifier", "event", "error", "struct", "enum", "using", "constructor",
+            "fallback", "receive", "return", "emit", "type"];
+
+        let body = &source[start..end];
+        let depths = brace_depths(body);
+
+        // Top-level statements are the `;`-terminated runs at depth zero
+        let mut state_variables = Vec::new();
+        let mut statement_start = 0;
+        for (i, c) in body.char_indices() {
+            if depths[i] != 0 {
+                statement_start = i + 1;
+                continue;
+            }
+            if c == '}' {
+                statement_start = i + 1;
+            }
+            if c != ';' {
+                continue;
+            }
+
+            let statement = &body[statement_start..i];
+            let offset = statement_start;
+            statement_start = i + 1;
+
+            let first_word = statement.split_whitespace().next().unwrap_or("");
+            if keywords.contains(&first_word) {
+                continue;
+            }
+
+            if let Some(captures) = declaration_pattern.captures(statement) {
+                let attributes = captures.get(2).map(|m| m.as_str()).unwrap_or("");
+                let visibility = ["public", "private", "internal"]
+                    .iter()
+                    .find(|v| attributes.split_whitespace().any(|word| word == **v))
+                    .unwrap_or(&"internal")
+                    .to_string();
+                let leading = statement.len() - statement.trim_start().len();
+
+                state_variables.push(UnitStateVariable {
+                    name: captures[3].to_string(),
+                    type_name: captures[1].split_whitespace().collect::<Vec<_>>().join(" "),
+                    visibility,
+                    is_constant: attributes.contains("constant"),
+                    is_immutable: attributes.contains("immutable"),
+                    line_number: lines.line(start + offset + leading),
+                });
+            }
+        }
+
+        Ok(state_variables)
+    }
+}
+
+/// Replace comments with spaces, keeping byte offsets and line numbers intact
+pub fn strip_comments(source: &str) -> String {
+    let bytes = source.as_bytes();
+    let mut out = bytes.to_vec();
+    let mut i = 0;
+    let mut in_string: Option<u8> = None;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        if let Some(quote) = in_string {
+            if c == b'\\' {
+                i += 2;
+                continue;
+            }
+            if c == quote {
+                in_string = None;
+            }
+            i += 1;
+        } else if c == b'"' || c == b'\'' {
+            in_string = Some(c);
+            i += 1;
+        } else if c == b'/' && bytes.get(i + 1) == Some(&b'/') {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                out[i] = b' ';
+                i += 1;
+            }
+        } else if c == b'/' && bytes.get(i + 1) == Some(&b'*') {
+            while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                if bytes[i] != b'\n' {
+                    out[i] = b' ';
+                }
+                i += 1;
+            }
+            for byte in &mut out[i..(i + 2).min(bytes.len())] {
+                *byte = b' ';
+            }
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+
+    // Only ASCII bytes were replaced, so multi-byte characters outside comments stay valid
+    String::from_utf8(out).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).to_string())
+}
+
+/// Replace Vyper `#` comments with spaces, keeping byte offsets and line numbers intact
+pub fn strip_vyper_comments(source: &str) -> String {
+    let bytes = source.as_bytes();
+    let mut out = bytes.to_vec();
+    let mut i = 0;
+    let mut in_string: Option<u8> = None;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        if let Some(quote) = in_string {
+            if c == b'\\' {
+                i += 2;
+                continue;
+            }
+            if c == quote {
+                in_string = None;
+            }
+            i += 1;
+        } else if c == b'"' || c == b'\'' {
+            in_string = Some(c);
+            i += 1;
+        } else if c == b'#' {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                out[i] = b' ';
+                i += 1;
+            }
+        } else {
+            i += 1;
+        }
+    }
+
+    String::from_utf8(out).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).to_string())
+}
+
+/// End of the Vyper statement starting at `start`: the first newline outside brackets
+fn statement_end(code: &str, start: usize) -> usize {
+    let mut depth = 0i32;
+    for (i, c) in code.bytes().enumerate().skip(start) {
+        match c {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth -= 1,
+            b'\n' if depth <= 0 => return i,
+            _ => {}
+        }
+    }
+    code.len()
+}
+
+/// End of the indented lines from `start`, at the next line that starts in the first column
+fn indented_block_end(code: &str, start: usize) -> usize {
+    let mut offset = start;
+    for line in code[start.min(code.len())..].split_inclusive('\n') {
+        if !line.trim().is_empty() && !line.starts_with(char::is_whitespace) {
+            return offset;
+        }
+        offset += line.len();
+    }
+    code.len()
+}
+
+/// `inner` of a type written as `wrapper(inner)`
+fn unwrap_type<'a>(type_name: &'a str, wrapper: &str) -> Option<&'a str> {
+    type_name.strip_prefix(wrapper)?.trim_start().strip_prefix('(')?.strip_suffix(')').map(str::trim)
+}
+
+/// A Vyper function from its `def` header and decorators
+fn vyper_function(
+    name: &str,
+    header: &str,
+    decorators: &[&str],
+    body: Option<SourceSpan>,
+    line_number: usize,
+    body_line_number: usize,
+) -> Option<UnitFunction> {
+    let open = header.find('(')?;
+    let close = matching_paren(header, open)?;
+    let parameters = split_top_level(&header[open + 1..close], ',');
+
+    // `-> uint256:` or `-> (uint256, bool):`, up to the colon ending the header
+    let rest = header[close + 1..].trim();
+    let return_types = match rest.strip_prefix("->") {
+        Some(returns) => {
+            let returns = returns.trim().split(':').next().unwrap_or("").trim();
+            match returns.strip_prefix('(').and_then(|inner| inner.strip_suffix(')')) {
+                Some(inner) => split_top_level(inner, ','),
+                None => vec![returns.to_string()],
+            }
+        }
+        None => Vec::new(),
+    };
+
+    // Decorators may take arguments, e.g. `@nonreentrant("lock")` before Vyper 0.4
+    let names: Vec<&str> = decorators.iter().map(|decorator| decorator.split('(').next().unwrap_or("").trim()).collect();
+    let visibility = if names.contains(&"external") { "external" } else { "internal" };
+    let state_mutability = names.iter().find(|name| matches!(**name, "view" | "pure" | "payable")).copied().unwrap_or_default();
+
+    Some(UnitFunction {
+        name: name.to_string(),
+        parameter_types: parameters
+            .iter()
+            .filter_map(|param| param.split_once(':'))
+            .map(|(_, type_name)| type_name.split('=').next().unwrap_or("").trim().to_string())
+            .collect(),
+        parameter_names: parameters
+            .iter()
+            .filter_map(|param| param.split_once(':'))
+            .map(|(name, _)| name.trim().to_string())
+            .collect(),
+        return_types,
+        visibility: visibility.to_string(),
+        state_mutability: state_mutability.to_string(),
+        is_virtual: false,
+        is_override: false,
+        modifiers: names
+            .iter()
+            .filter(|name| !matches!(**name, "external" | "internal" | "view" | "pure" | "payable" | "nonpayable" | "deploy"))
+            .map(|name| name.to_string())
+            .collect(),
+        line_number,
+        body,
+        body_line_number,
+    })
+}
+
+/// Find the `}` matching the `{` at `open`
+pub fn matching_brace(source: &str, open: usize) -> Option<usize> {
+    matching_delimiter(source, open, b'{', b'}')
+}
+
+/// Find the `)` matching the `(` at `open`
+pub fn matching_paren(source: &str, open: usize) -> Option<usize> {
+    matching_delimiter(source, open, b'(', b')')
+}
+
+fn matching_delimiter(source: &str, open: usize, opening: u8, closing: u8) -> Option<usize> {
+    let bytes = source.as_bytes();
+    let mut depth = 0usize;
+    let mut in_string: Option<u8> = None;
+    let mut i = open;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        if let Some(quote) = in_string {
+            if c == b'\\' {
+                i += 1;
+            } else if c == quote {
+                in_string = None;
+            }
+        } else if c == b'"' || c == b'\'' {
+            in_string = Some(c);
+        } else if c == opening {
+            depth += 1;
+        } else if c == closing {
+            depth = depth.checked_sub(1)?;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Brace depth at every byte offset of `body` (the braces themselves count as outer)
+fn brace_depths(body: &str) -> Vec<usize> {
+    let mut depths = Vec::with_capacity(body.len());
+    let mut depth = 0usize;
+    for c in body.bytes() {
+        if c == b'}' {
+            depth = depth.saturating_sub(1);
+        }
+        depths.push(depth);
+        if c == b'{' {
+            depth += 1;
+        }
+    }
+    depths
+}
+
+/// Split on `separator` outside of parentheses and brackets
+pub fn split_top_level(text: &str, separator: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+
+    for c in text.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            _ => {}
+        }
+        if c == separator && depth == 0 {
+            parts.push(current.trim().to_string());
+            current.clear();
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_string());
+    }
+
+    parts
+}
+
+/// Type of a parameter or field declaration, without data location or name
+fn parameter_type(declaration: &str) -> Option<String> {
+    let declaration = declaration.trim();
+    if declaration.is_empty() {
+        return None;
+    }
+
+    // Mapping types contain spaces, keep them whole
+    if declaration.starts_with("mapping") {
+        let close = matching_paren(declaration, declaration.find('(')?)?;
+        return Some(declaration[..=close].to_string());
+    }
+
+    let mut words = declaration.split_whitespace();
+    let mut type_name = words.next()?.to_string();
+    // `address payable` is just `address` in signatures
+    let rest: Vec<&str> = words.collect();
+    if type_name == "address" && rest.first() == Some(&"payable") {
+        type_name = "address".to_string();
+    }
+
+    Some(type_name)
+}
+
+/// Name of a parameter declaration, empty when unnamed
+fn parameter_name(declaration: &str) -> String {
+    let words: Vec<&str> = declaration.split_whitespace().collect();
+    match words.last() {
+        Some(last) if words.len() > 1 && !matches!(*last, "memory" | "storage" | "calldata" | "payable") && !last.ends_with(')') => {
+            last.to_string()
+        }
+        _ => String::new(),
+    }
+}
+
+/// Lines of `source` with the byte offset each starts at, without line terminators
+fn lines_with_offsets(source: &str) -> impl Iterator<Item = (usize, &str)> {
+    source.split_inclusive('\n').scan(0, |offset, line| {
+        let start = *offset;
+        *offset += line.len();
+        Some((start, line.trim_end_matches(['\n', '\r'])))
+    })
+}
+
+/// Start offsets of every line, so line numbers in large files don't rescan the source from the top
+struct LineIndex(Vec<usize>);
+
+impl LineIndex {
+    fn new(source: &str) -> Self {
+        Self(std::iter::once(0).chain(source.match_indices('\n').map(|(i, _)| i + 1)).collect())
+    }
+
+    /// 1-based line of a byte offset
+    fn line(&self, offset: usize) -> usize {
+        self.0.partition_point(|start| *start <= offset)
     }
 }
 
@@ -0,0 +1,202 @@
+//! PoC verification
+//!
+//! A generated PoC is a claim until it runs. Each Solidity PoC is compiled
+//! with forge in a scratch Foundry project and its `test_exploit` run, either
+//! against a local deployment in forge's own EVM or, with a fork URL, against
+//! a fork of a live chain or an Anvil node. A PoC is confirmed only when the
+//! test passes and asserts something: a test that merely reaches its end
+//! shows the calls went through, not that the exploit worked. PoCs edited by
+//! hand, such as the `exploits/` written by `scan` with their assertions
+//! completed, are verified in place of freshly generated ones.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::report::vulnerability::Vulnerability;
+use crate::utils::budget;
+use crate::utils::cache;
+use crate::utils::config::FoundryConfig;
+use crate::utils::platform;
+use crate::utils::retention;
+
+/// Name of the test every Solidity PoC defines
+const TEST_NAME: &str = "test_exploit";
+
+/// Outcome of running one PoC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PocStatus {
+    /// The test passed and its assertions held
+    #[serde(rename = "confirmed")]
+    Confirmed,
+    #[serde(rename = "failed to compile")]
+    CompileFailed,
+    /// The test failed, reverted or asserted nothing
+    #[serde(rename = "did not exploit")]
+    NotExploited,
+}
+
+impl std::fmt::Display for PocStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            PocStatus::Confirmed => "confirmed",
+            PocStatus::CompileFailed => "failed to compile",
+            PocStatus::NotExploited => "did not exploit",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Result of verifying the PoC of one finding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PocVerification {
+    pub status: PocStatus,
+    /// Compiler error or failure reason
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Whether it ran against a fork rather than a local deployment
+    pub fork: bool,
+    pub verified_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Compiles and runs Solidity PoCs with forge
+pub struct PocVerifier<'a> {
+    foundry: &'a FoundryConfig,
+    fork_url: Option<String>,
+}
+
+impl<'a> PocVerifier<'a> {
+    pub fn new(foundry: &'a FoundryConfig, fork_url: Option<String>) -> Self {
+        Self { foundry, fork_url }
+    }
+
+    /// Compile `code`, the PoC of `vulnerability`, and run its exploit test
+    pub async fn verify(&self, vulnerability: &Vulnerability, code: &str) -> Result<PocVerification> {
+        if !code.contains(&format!("function {}(", TEST_NAME)) {
+            let detail = format!("the PoC has no {} test; it is a template to complete by hand", TEST_NAME);
+            return Ok(self.outcome(PocStatus::NotExploited, Some(detail)));
+        }
+        let audited = std::fs::canonicalize(&vulnerability.file_path).ok();
+        let forge_std = self.forge_std(audited.as_deref()).ok_or_else(|| {
+            anyhow!("forge-std not found next to {}; set tools.foundry.forge_std to a checkout of it", vulnerability.file_path)
+        })?;
+
+        let temp_dir = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).tempdir()?;
+        std::fs::create_dir_all(temp_dir.path().join("test"))?;
+        let mut allow_paths = vec![forge_std.clone()];
+        let code = match &audited {
+            // A PoC importing the audited file names it as the finding does, relative to where the analysis ran
+            Some(path) => {
+                allow_paths.extend(path.parent().map(Path::to_path_buf));
+                code.replace(&format!("import \"{}\";", vulnerability.file_path), &format!("import \"{}\";", path.display()))
+            }
+            None => code.to_string(),
+        };
+        std::fs::write(temp_dir.path().join("test").join("Exploit.t.sol"), code.as_bytes())?;
+        std::fs::write(temp_dir.path().join("foundry.toml"), foundry_toml(&forge_std, &allow_paths))?;
+
+        let timeout = Some(Duration::from_secs(self.foundry.timeout));
+        let mut build = platform::async_command(&self.foundry.executable);
+        build.arg("build").arg("--root").arg(temp_dir.path());
+        let output = budget::run(&mut build, timeout).await?;
+        if !output.status.success() {
+            return Ok(self.outcome(PocStatus::CompileFailed, compiler_error(&output)));
+        }
+
+        let mut test = platform::async_command(&self.foundry.executable);
+        test.arg("test").arg("--root").arg(temp_dir.path()).arg("--match-test").arg(TEST_NAME);
+        if let Some(url) = &self.fork_url {
+            test.arg("--fork-url").arg(url);
+        }
+        let output = budget::run(&mut test, timeout).await?;
+        let outcome = match output.status.success() {
+            true if asserts(&code) => self.outcome(PocStatus::Confirmed, None),
+            true => self.outcome(
+                PocStatus::NotExploited,
+                Some(format!("{} passed without asserting anything; assert the state the exploit changes", TEST_NAME)),
+            ),
+            false => self.outcome(PocStatus::NotExploited, failure_reason(&output)),
+        };
+        Ok(outcome)
+    }
+
+    fn outcome(&self, status: PocStatus, detail: Option<String>) -> PocVerification {
+        PocVerification { status, detail, fork: self.fork_url.is_some(), verified_at: chrono::Utc::now() }
+    }
+
+    /// forge-std from the configuration, else from the Foundry project holding the audited file
+    fn forge_std(&self, audited: Option<&Path>) -> Option<PathBuf> {
+        if !self.foundry.forge_std.is_empty() {
+            return Some(cache::expand_home(Path::new(&self.foundry.forge_std))).filter(|path| path.join("src").join("Test.sol").is_file());
+        }
+        audited?
+            .ancestors()
+            .map(|dir| dir.join("lib").join("forge-std"))
+            .find(|path| path.join("src").join("Test.sol").is_file())
+    }
+}
+
+/// PoC of `vulnerability` among the files of `dir`, such as the `exploits/` of a scan edited by hand
+pub fn edited_poc(dir: &Path, vulnerability: &Vulnerability) -> Result<Option<String>> {
+    let header = format!("PoC Exploit for: {}\n * Severity: {}\n", vulnerability.title, vulnerability.severity);
+    let location = match vulnerability.line_number {
+        Some(line) => format!("Location: {}:{},", vulnerability.file_path, line),
+        None => format!("Location: {},", vulnerability.file_path),
+    };
+    let mut candidates = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("sol") {
+            continue;
+        }
+        let code = std::fs::read_to_string(&path)?;
+        if code.contains(&header) {
+            candidates.push(code);
+        }
+    }
+    // Findings sharing a title are told apart by the location generated PoCs give
+    let located = candidates.iter().position(|code| code.contains(&location));
+    Ok(match located {
+        Some(index) => Some(candidates.swap_remove(index)),
+        None => candidates.into_iter().next(),
+    })
+}
+
+fn foundry_toml(forge_std: &Path, allow_paths: &[PathBuf]) -> String {
+    let allow_paths: Vec<String> = allow_paths.iter().map(|path| format!("{:?}", path.display().to_string())).collect();
+    format!(
+        "[profile.default]\nsrc = \"test\"\ntest = \"test\"\nlibs = []\nremappings = [{:?}]\nallow_paths = [{}]\n",
+        format!("forge-std/={}/src/", forge_std.display()),
+        allow_paths.join(", ")
+    )
+}
+
+/// Whether the exploit test checks its outcome
+fn asserts(code: &str) -> bool {
+    let test = code.split(&format!("function {}(", TEST_NAME)).nth(1).unwrap_or_default();
+    test.lines()
+        .map(str::trim_start)
+        .filter(|line| !line.starts_with("//"))
+        .any(|line| line.starts_with("assert") || line.starts_with("vm.expect") || line.starts_with("require("))
+}
+
+/// First compiler error forge printed
+fn compiler_error(output: &std::process::Output) -> Option<String> {
+    let printed = format!("{}\n{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    let lines: Vec<&str> = printed.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    lines
+        .iter()
+        .find(|line| line.starts_with("Error (") || line.starts_with("ParserError") || line.starts_with("TypeError"))
+        .or_else(|| lines.last())
+        .map(|line| line.to_string())
+}
+
+/// Reason forge gave for the failing test, as in `[FAIL: reason] test_exploit()`
+fn failure_reason(output: &std::process::Output) -> Option<String> {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.lines().map(str::trim).find(|line| line.starts_with("[FAIL")) {
+        Some(line) => Some(line.to_string()),
+        None => compiler_error(output),
+    }
+}
@@ -0,0 +1,243 @@
+//! Import and call graph of a project
+//!
+//! Cross-contract checks need to know which files pull in which, and which
+//! functions of one contract reach functions of another. The import graph
+//! links every source to the sources it imports, resolved against the run
+//! when they are part of it. The call graph links functions by the calls in
+//! their bodies: internal and library calls as the function-level call
+//! graph resolves them, by name across the run, and external calls through
+//! state variables, parameters and casts typed as a contract or interface
+//! of the run.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::core::callgraph::CallGraph;
+use crate::core::parser::ParsedContract;
+
+/// Imports and calls of the contracts in a run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectGraph {
+    /// Source paths, relative to the input directory
+    pub files: Vec<String>,
+    pub imports: Vec<ImportEdge>,
+    pub calls: Vec<CallEdge>,
+}
+
+/// One source importing another
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportEdge {
+    pub from: String,
+    /// Path of the imported source in the run, or the import as written when it is not part of it
+    pub to: String,
+    pub resolved: bool,
+}
+
+/// One function calling another, both as `Unit.function`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: CallKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CallKind {
+    /// A function of the same contract or of one it inherits
+    Internal,
+    /// A library function
+    Library,
+    /// A message call to another contract
+    External,
+}
+
+impl ProjectGraph {
+    /// Graph of the imports and calls of `contracts`
+    pub fn build(contracts: &[ParsedContract]) -> Self {
+        let mut graph = ProjectGraph {
+            files: contracts.iter().map(|contract| contract.path().replace('\\', "/")).collect(),
+            ..Default::default()
+        };
+
+        for (contract, from) in contracts.iter().zip(graph.files.clone()) {
+            for import in &contract.imports {
+                // Relative imports resolve against the importing file, others against the
+                // input directory or, for remapped and package imports, by their trailing path
+                let target = contract.resolve_import(import).unwrap_or_else(|| import.clone());
+                let edge = match graph.files.iter().find(|file| **file == target || file.ends_with(&format!("/{}", target))) {
+                    Some(file) => ImportEdge { from: from.clone(), to: file.clone(), resolved: true },
+                    None => ImportEdge { from: from.clone(), to: import.clone(), resolved: false },
+                };
+                if !graph.imports.contains(&edge) {
+                    graph.imports.push(edge);
+                }
+            }
+        }
+
+        let calls = CallGraph::build(contracts);
+        let label = |index: usize| format!("{}.{}", calls.nodes[index].unit, calls.nodes[index].function.name);
+        for (callee, node) in calls.nodes.iter().enumerate() {
+            for &caller in calls.callers(callee) {
+                let kind = match node.unit_kind {
+                    "library" if calls.nodes[caller].unit != node.unit => CallKind::Library,
+                    _ => CallKind::Internal,
+                };
+                graph.add_call(label(caller), label(callee), kind);
+            }
+        }
+
+        let units: HashMap<&str, &str> = contracts
+            .iter()
+            .flat_map(|contract| contract.units.iter())
+            .map(|unit| (unit.name.as_str(), unit.kind.as_str()))
+            .collect();
+        let member_call = Regex::new(r"\b([A-Za-z_]\w*)\s*\.\s*([A-Za-z_]\w*)\s*[({]").expect("valid member call pattern");
+        let cast_call = Regex::new(r"\b([A-Za-z_]\w*)\s*\(\s*[^()]*(?:\([^()]*\)[^()]*)*\)\s*\.\s*([A-Za-z_]\w*)\s*[({]")
+            .expect("valid cast call pattern");
+        for unit in contracts.iter().flat_map(|contract| contract.units.iter()).filter(|unit| unit.kind != "interface") {
+            // Declared types of call receivers: state variables, then each function's parameters
+            let state: HashMap<&str, &str> = unit
+                .state_variables
+                .iter()
+                .map(|variable| (variable.name.as_str(), variable.type_name.as_str()))
+                .collect();
+            for function in &unit.functions {
+                let body = match function.body.as_deref() {
+                    Some(body) => body,
+                    None => continue,
+                };
+                let mut typed = state.clone();
+                for (type_name, name) in function.parameter_types.iter().zip(&function.parameter_names) {
+                    typed.insert(name.as_str(), type_name.as_str());
+                }
+                let from = format!("{}.{}", unit.name, function.name);
+
+                let targets = member_call
+                    .captures_iter(body)
+                    .filter_map(|captures| typed.get(&captures[1]).map(|type_name| (type_name.to_string(), captures[2].to_string())))
+                    .chain(cast_call.captures_iter(body).map(|captures| (captures[1].to_string(), captures[2].to_string())));
+                for (target, member) in targets {
+                    if matches!(units.get(target.as_str()), Some(kind) if *kind != "library") {
+                        graph.add_call(from.clone(), format!("{}.{}", target, member), CallKind::External);
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    fn add_call(&mut self, from: String, to: String, kind: CallKind) {
+        let edge = CallEdge { from, to, kind };
+        if !self.calls.contains(&edge) {
+            self.calls.push(edge);
+        }
+    }
+
+    /// Graphviz digraph of the imports, unresolved ones dashed
+    pub fn imports_to_dot(&self) -> String {
+        let mut diagram = String::from("digraph imports {\n    rankdir=LR;\n    node [shape=box, fontname=\"Helvetica\"];\n");
+        for file in &self.files {
+            diagram.push_str(&format!("    \"{}\";\n", file));
+        }
+        for edge in &self.imports {
+            if !edge.resolved {
+                diagram.push_str(&format!("    \"{}\" [style=dashed];\n", edge.to));
+            }
+            diagram.push_str(&format!("    \"{}\" -> \"{}\";\n", edge.from, edge.to));
+        }
+        diagram.push_str("}\n");
+        diagram
+    }
+
+    /// Graphviz digraph of the calls, one cluster per contract
+    pub fn calls_to_dot(&self) -> String {
+        let mut diagram = String::from("digraph calls {\n    rankdir=LR;\n    node [shape=box, fontname=\"Helvetica\"];\n");
+        for (unit, functions) in self.functions_by_unit() {
+            diagram.push_str(&format!("    subgraph \"cluster_{}\" {{\n        label=\"{}\";\n", unit, unit));
+            for function in functions {
+                diagram.push_str(&format!("        \"{}\" [label=\"{}\"];\n", function, &function[unit.len() + 1..]));
+            }
+            diagram.push_str("    }\n");
+        }
+        for edge in &self.calls {
+            let style = match edge.kind {
+                CallKind::Internal => "",
+                CallKind::Library => " [style=dotted]",
+                CallKind::External => " [color=red, label=\"external\"]",
+            };
+            diagram.push_str(&format!("    \"{}\" -> \"{}\"{};\n", edge.from, edge.to, style));
+        }
+        diagram.push_str("}\n");
+        diagram
+    }
+
+    /// Mermaid flowchart of the imports
+    pub fn imports_to_mermaid(&self) -> String {
+        let ids: HashMap<&str, usize> = self
+            .files
+            .iter()
+            .chain(self.imports.iter().map(|edge| &edge.to))
+            .fold(HashMap::new(), |mut ids, name| {
+                let next = ids.len();
+                ids.entry(name.as_str()).or_insert(next);
+                ids
+            });
+        let mut diagram = String::from("flowchart LR\n");
+        let mut declared: Vec<usize> = Vec::new();
+        for name in self.files.iter().chain(self.imports.iter().map(|edge| &edge.to)) {
+            let id = ids[name.as_str()];
+            if !declared.contains(&id) {
+                declared.push(id);
+                diagram.push_str(&format!("    f{}[\"{}\"]\n", id, name));
+            }
+        }
+        for edge in &self.imports {
+            let arrow = if edge.resolved { "-->" } else { "-.->" };
+            diagram.push_str(&format!("    f{} {} f{}\n", ids[edge.from.as_str()], arrow, ids[edge.to.as_str()]));
+        }
+        diagram
+    }
+
+    /// Mermaid flowchart of the calls, one subgraph per contract
+    pub fn calls_to_mermaid(&self) -> String {
+        let id = |function: &str| function.replace('.', "_");
+        let mut diagram = String::from("flowchart LR\n");
+        for (unit, functions) in self.functions_by_unit() {
+            diagram.push_str(&format!("    subgraph {}\n", unit));
+            for function in functions {
+                diagram.push_str(&format!("        {}[\"{}\"]\n", id(function), &function[unit.len() + 1..]));
+            }
+            diagram.push_str("    end\n");
+        }
+        for edge in &self.calls {
+            let arrow = match edge.kind {
+                CallKind::Internal => "-->",
+                CallKind::Library => "-.->",
+                CallKind::External => "==>",
+            };
+            diagram.push_str(&format!("    {} {} {}\n", id(&edge.from), arrow, id(&edge.to)));
+        }
+        diagram
+    }
+
+    /// Functions of the call graph grouped by contract, in the order they are first seen
+    fn functions_by_unit(&self) -> Vec<(&str, Vec<&str>)> {
+        let mut units: Vec<(&str, Vec<&str>)> = Vec::new();
+        for function in self.calls.iter().flat_map(|edge| [edge.from.as_str(), edge.to.as_str()]) {
+            let unit = function.split('.').next().unwrap_or(function);
+            let position = match units.iter().position(|(known, _)| *known == unit) {
+                Some(position) => position,
+                None => {
+                    units.push((unit, Vec::new()));
+                    units.len() - 1
+                }
+            };
+            if !units[position].1.contains(&function) {
+                units[position].1.push(function);
+            }
+        }
+        units
+    }
+}
@@ -0,0 +1,235 @@
+//! Whole-protocol fetches
+//!
+//! A protocol is rarely one address: a governor, its timelock, the treasury
+//! and the implementations behind their proxies, often spread over several
+//! chains. `fetch --manifest protocol.toml` fetches every named address the
+//! manifest lists, each from its own network, into `<network>/<address>/`
+//! below the output directory, with each network's fetch manifest beside its
+//! sources so an interrupted fetch resumes. `protocol.json` then records
+//! what each name turned out to be; `analyze` given that directory audits
+//! the whole protocol in one run and reports each contract at its address.
+//!
+//! The manifest is TOML:
+//!
+//! ```toml
+//! name = "Example DAO"
+//! network = "ethereum"    # for contracts that name none
+//!
+//! [[contracts]]
+//! name = "Timelock"
+//! address = "0x..."
+//!
+//! [[contracts]]
+//! name = "Bridge"
+//! address = "0x..."
+//! network = "arbitrum"
+//! ```
+//!
+//! or a JSON address registry of names by network, as deployment scripts
+//! commonly write: `{ "ethereum": { "Timelock": "0x..." } }`.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::core::fetcher::{self, DeployedContract, FetchManifest, FetchStatus};
+use crate::core::metadata::ProjectMetadata;
+use crate::utils::artifact;
+
+/// Project manifest a protocol fetch leaves in its output directory
+pub const PROJECT_FILE: &str = "protocol.json";
+
+/// Named addresses of a protocol to fetch
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProtocolManifest {
+    #[serde(default)]
+    pub name: String,
+    /// Network of the contracts that name none
+    #[serde(default = "default_network")]
+    pub network: String,
+    #[serde(default)]
+    pub contracts: Vec<ProtocolEntry>,
+}
+
+/// One named address of a protocol manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolEntry {
+    pub name: String,
+    pub address: String,
+    #[serde(default)]
+    pub network: Option<String>,
+}
+
+fn default_network() -> String {
+    "ethereum".to_string()
+}
+
+impl ProtocolManifest {
+    /// Manifest at `path`, TOML or, for a `.json` file, an address registry
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = artifact::read_text(path, "protocol manifest")?;
+        let manifest = if path.extension().is_some_and(|ext| ext == "json") {
+            let registry: Value = serde_json::from_str(&content).map_err(|e| anyhow!("Invalid address registry {}: {}", path.display(), e))?;
+            Self::from_registry(&registry)?
+        } else {
+            toml::from_str(&content).map_err(|e| anyhow!("Invalid protocol manifest {}: {}", path.display(), e))?
+        };
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Manifest of a registry of `{ network: { name: address } }`, with an optional top-level `name`
+    fn from_registry(registry: &Value) -> Result<Self> {
+        let networks = registry.as_object().ok_or_else(|| anyhow!("An address registry is an object of networks"))?;
+        let mut contracts = Vec::new();
+        for (network, addresses) in networks.iter().filter(|(key, _)| *key != "name") {
+            let addresses = addresses
+                .as_object()
+                .ok_or_else(|| anyhow!("Network {} of the address registry is not an object of names", network))?;
+            for (name, address) in addresses {
+                let address = address.as_str().ok_or_else(|| anyhow!("Address of {} on {} is not a string", name, network))?;
+                contracts.push(ProtocolEntry { name: name.clone(), address: address.to_string(), network: Some(network.clone()) });
+            }
+        }
+        Ok(Self {
+            name: registry["name"].as_str().unwrap_or_default().to_string(),
+            network: default_network(),
+            contracts,
+        })
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.contracts.is_empty() {
+            return Err(anyhow!("The protocol manifest lists no contracts"));
+        }
+        let mut seen = std::collections::HashSet::new();
+        for entry in &self.contracts {
+            let network = self.network_of(entry);
+            if !is_address(&entry.address) {
+                return Err(anyhow!("{} on {} has an invalid address: {}", entry.name, network, entry.address));
+            }
+            if !seen.insert((network.to_string(), entry.name.clone())) {
+                return Err(anyhow!("{} is listed twice on {}", entry.name, network));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn network_of<'a>(&'a self, entry: &'a ProtocolEntry) -> &'a str {
+        entry.network.as_deref().unwrap_or(&self.network)
+    }
+
+    /// Networks the contracts are on, in the order the manifest first names them
+    pub fn networks(&self) -> Vec<String> {
+        let mut networks: Vec<String> = Vec::new();
+        for entry in &self.contracts {
+            let network = self.network_of(entry);
+            if !networks.iter().any(|known| known == network) {
+                networks.push(network.to_string());
+            }
+        }
+        networks
+    }
+
+    /// Addresses to fetch from `network`
+    pub fn addresses_on(&self, network: &str) -> Vec<String> {
+        self.contracts
+            .iter()
+            .filter(|entry| self.network_of(entry) == network)
+            .map(|entry| entry.address.clone())
+            .collect()
+    }
+}
+
+/// What a protocol fetch brought in, read by `analyze`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolProject {
+    pub name: String,
+    pub contracts: Vec<ProtocolContract>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// One named address of a fetched protocol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolContract {
+    /// Name the manifest gives it
+    pub name: String,
+    pub network: String,
+    pub address: String,
+    pub status: FetchStatus,
+    /// Directory of its sources, relative to the project
+    pub dir: String,
+    /// Contracts fetched for the address, its implementation's included
+    #[serde(default)]
+    pub contracts: Vec<DeployedContract>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ProtocolProject {
+    /// Project of `manifest` from the fetch manifests of its networks
+    pub fn build(manifest: &ProtocolManifest, fetches: &HashMap<String, FetchManifest>) -> Self {
+        let contracts = manifest
+            .contracts
+            .iter()
+            .map(|entry| {
+                let network = manifest.network_of(entry);
+                let item = fetches
+                    .get(network)
+                    .and_then(|fetch| fetch.items.iter().find(|item| item.address.eq_ignore_ascii_case(&entry.address)));
+                let dir = fetcher::source_dir(Path::new(network), &entry.address);
+                ProtocolContract {
+                    name: entry.name.clone(),
+                    network: network.to_string(),
+                    address: entry.address.clone(),
+                    // An address the fetch never reached, interrupted before it, counts as failed
+                    status: item.map_or(FetchStatus::Failed, |item| item.status),
+                    dir: dir.to_string_lossy().into_owned(),
+                    contracts: item.map(|item| item.contracts.clone()).unwrap_or_default(),
+                    error: match item {
+                        Some(item) => item.error.clone(),
+                        None => Some("not fetched".to_string()),
+                    },
+                }
+            })
+            .collect();
+        Self { name: manifest.name.clone(), contracts, fetched_at: Utc::now() }
+    }
+
+    /// Project a protocol fetch left in `dir`, if it is one
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(PROJECT_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        artifact::read_json(&path, "protocol project").map(Some)
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        artifact::write_atomic(dir.join(PROJECT_FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn with_status(&self, status: FetchStatus) -> impl Iterator<Item = &ProtocolContract> {
+        self.contracts.iter().filter(move |contract| contract.status == status)
+    }
+
+    /// Head the report with the protocol and give each fetched contract its network and address
+    pub fn annotate(&self, metadata: &mut ProjectMetadata) {
+        if !self.name.is_empty() {
+            metadata.title = self.name.clone();
+        }
+        for contract in self.with_status(FetchStatus::Fetched) {
+            for deployed in &contract.contracts {
+                metadata.set_network_address(&deployed.name, &deployed.address, &contract.network);
+            }
+        }
+    }
+}
+
+fn is_address(address: &str) -> bool {
+    address.len() == 42 && address.starts_with("0x") && address[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
@@ -0,0 +1,371 @@
+//! Upgradeable proxies
+//!
+//! Most deployed protocols sit behind an EIP-1967 proxy: a Transparent proxy
+//! that its admin upgrades, a UUPS proxy whose implementation carries the
+//! upgrade function, or a beacon proxy that asks a beacon for the
+//! implementation. Fetching such an address used to give only the proxy shim,
+//! and the code holding the logic went unaudited. Fetching now recognises the
+//! proxy, finds its implementation from the explorer or the EIP-1967 slots
+//! and fetches it too. A run with proxy or upgradeable code is checked for
+//! what breaks upgradeable systems: implementations anyone can initialize,
+//! proxies whose own state variables overlap the implementation's storage,
+//! and upgrade functions without access control.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::core::parser::{ContractUnit, ParsedContract, UnitFunction};
+use crate::core::rpc::RpcClient;
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+use crate::utils::abi::{keccak256, read_address};
+
+pub const TOOL_NAME: &str = "Proxy Analysis";
+
+/// `bytes32(uint256(keccak256("eip1967.proxy.implementation")) - 1)`
+pub const IMPLEMENTATION_SLOT: &str = "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+
+/// `bytes32(uint256(keccak256("eip1967.proxy.beacon")) - 1)`
+pub const BEACON_SLOT: &str = "0xa3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50";
+
+/// Checks in a function body that restrict who gets past them
+const ACCESS_CHECKS: &[&str] = &["msg.sender", "_msgSender", "Owner", "Role", "_authorize", "_checkAdmin", "ifAdmin"];
+
+/// Modifiers of upgrade functions that say where they run from, not who calls them
+const CONTEXT_MODIFIERS: &[&str] = &["onlyProxy", "notDelegated"];
+
+/// Public functions that point a proxy at new code
+const UPGRADE_FUNCTIONS: &[&str] = &["upgradeTo", "upgradeToAndCall", "upgradeAndCall", "upgrade", "setImplementation", "changeImplementation"];
+
+/// How a proxy finds its implementation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProxyKind {
+    /// Upgraded by its admin through the proxy itself
+    Transparent,
+    /// Upgraded through the implementation's `upgradeTo`
+    Uups,
+    /// Reads the implementation from a beacon contract
+    Beacon,
+    /// Keeps the implementation in the EIP-1967 slot, upgrade path unknown
+    Eip1967,
+}
+
+impl std::fmt::Display for ProxyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            ProxyKind::Transparent => "Transparent",
+            ProxyKind::Uups => "UUPS",
+            ProxyKind::Beacon => "beacon",
+            ProxyKind::Eip1967 => "EIP-1967",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Proxy pattern of verified sources that delegate from their fallback, `None` for other code
+///
+/// UUPS proxies are plain EIP-1967 proxies; only their implementation tells them apart.
+pub fn proxy_kind(source: &str) -> Option<ProxyKind> {
+    if !(source.contains("fallback") && source.contains("delegatecall")) {
+        return None;
+    }
+    if source.contains("TransparentUpgradeableProxy") {
+        Some(ProxyKind::Transparent)
+    } else if source.contains("BeaconProxy") || source.contains("eip1967.proxy.beacon") {
+        Some(ProxyKind::Beacon)
+    } else if source.contains("ERC1967Proxy")
+        || source.contains("eip1967.proxy.implementation")
+        || source.to_lowercase().contains(&IMPLEMENTATION_SLOT[2..])
+    {
+        Some(ProxyKind::Eip1967)
+    } else {
+        None
+    }
+}
+
+/// Whether an implementation's sources carry the UUPS upgrade function
+pub fn is_uups(source: &str) -> bool {
+    source.contains("UUPSUpgradeable") || source.contains("proxiableUUID")
+}
+
+/// Implementation a deployed proxy delegates to, from its EIP-1967 slots; `None` when the slot is empty
+pub fn read_implementation(rpc: &RpcClient, proxy: &str, kind: ProxyKind) -> Result<Option<String>> {
+    if kind != ProxyKind::Beacon {
+        return Ok(slot_address(&rpc.storage_at(proxy, IMPLEMENTATION_SLOT)?));
+    }
+    let beacon = match slot_address(&rpc.storage_at(proxy, BEACON_SLOT)?) {
+        Some(beacon) => beacon,
+        None => return Ok(None),
+    };
+    let selector = &keccak256(b"implementation()")[..4];
+    Ok(read_address(&rpc.call(&beacon, selector)?, 0).filter(|address| !is_zero_address(address)))
+}
+
+/// Address held in a storage word, which nodes may return without its leading zeros
+fn slot_address(value: &[u8]) -> Option<String> {
+    let mut word = vec![0u8; 32usize.saturating_sub(value.len())];
+    word.extend_from_slice(value);
+    read_address(&word, word.len() - 32).filter(|address| !is_zero_address(address))
+}
+
+fn is_zero_address(address: &str) -> bool {
+    address.trim_start_matches("0x").chars().all(|c| c == '0')
+}
+
+/// Proxy checks over every contract of a run
+///
+/// Implementations are checked wherever they appear, proxies for storage of
+/// their own, and upgrade functions in any contract that has one.
+pub fn check_proxies(contracts: &[ParsedContract]) -> Vec<Vulnerability> {
+    let mut units: HashMap<&str, (&ContractUnit, &str)> = HashMap::new();
+    for contract in contracts {
+        for unit in &contract.units {
+            units.entry(unit.name.as_str()).or_insert((unit, contract.name.as_str()));
+        }
+    }
+    let mut names: Vec<&str> = units.keys().copied().collect();
+    names.sort_unstable();
+
+    let mut findings = Vec::new();
+    let mut proxies = Vec::new();
+    let mut implementations = Vec::new();
+    for name in names {
+        let (unit, file) = units[name];
+        if unit.kind == "interface" || unit.kind == "library" {
+            continue;
+        }
+        let chain = lineage(&units, unit);
+        check_upgrade_access(unit, file, &chain, &mut findings);
+        if unit.kind != "contract" {
+            continue;
+        }
+        if is_proxy(&chain) {
+            proxies.push((unit, file, chain));
+        } else if is_upgradeable(&chain) {
+            check_initialization(unit, file, &chain, &mut findings);
+            implementations.push((unit, chain));
+        }
+    }
+
+    for (proxy, file, chain) in &proxies {
+        check_proxy_storage(proxy, file, chain, &implementations, &mut findings);
+    }
+    findings
+}
+
+/// The unit and the units of the run it inherits from, nearest first
+fn lineage<'a>(units: &HashMap<&str, (&'a ContractUnit, &str)>, unit: &'a ContractUnit) -> Vec<&'a ContractUnit> {
+    let mut chain = vec![unit];
+    let mut seen: HashSet<&str> = HashSet::from([unit.name.as_str()]);
+    let mut index = 0;
+    while index < chain.len() {
+        for base in &chain[index].inheritance {
+            if let Some(&(base, _)) = units.get(base.as_str()) {
+                if base.kind != "interface" && seen.insert(base.name.as_str()) {
+                    chain.push(base);
+                }
+            }
+        }
+        index += 1;
+    }
+    chain
+}
+
+/// Names the unit inherits, including bases whose source is not in the run
+fn inherits<'a>(chain: &'a [&'a ContractUnit]) -> impl Iterator<Item = &'a str> {
+    chain.iter().flat_map(|unit| std::iter::once(unit.name.as_str()).chain(unit.inheritance.iter().map(String::as_str)))
+}
+
+fn functions<'a>(chain: &'a [&'a ContractUnit]) -> impl Iterator<Item = &'a UnitFunction> {
+    chain.iter().flat_map(|unit| &unit.functions)
+}
+
+/// A proxy derives from a `*Proxy` base, or forwards calls with `_delegate` or a delegating fallback
+fn is_proxy(chain: &[&ContractUnit]) -> bool {
+    inherits(chain).any(|name| name.ends_with("Proxy"))
+        || functions(chain).any(|function| {
+            function.name == "_delegate"
+                || (function.name == "fallback" && function.body.as_deref().is_some_and(|body| body.contains("delegatecall")))
+        })
+}
+
+/// Upgradeable code initializes in a function, since a proxy never runs its constructor
+fn is_upgradeable(chain: &[&ContractUnit]) -> bool {
+    inherits(chain).any(|name| name == "Initializable" || name.ends_with("Upgradeable")) || functions(chain).any(is_initializer)
+}
+
+fn is_initializer(function: &UnitFunction) -> bool {
+    function.modifiers.iter().any(|modifier| modifier == "initializer" || modifier == "reinitializer")
+}
+
+fn is_uups_unit(chain: &[&ContractUnit]) -> bool {
+    inherits(chain).any(|name| name == "UUPSUpgradeable") || functions(chain).any(|function| function.name == "_authorizeUpgrade")
+}
+
+/// An implementation whose constructor leaves its initializers open can be initialized by anyone
+fn check_initialization(unit: &ContractUnit, file: &str, chain: &[&ContractUnit], findings: &mut Vec<Vulnerability>) {
+    let initializer = match unit.functions.iter().find(|function| is_initializer(function)) {
+        Some(initializer) => initializer,
+        None => return,
+    };
+    let locked = unit.constructor.as_ref().is_some_and(|constructor| {
+        is_initializer(constructor) || constructor.body.as_deref().is_some_and(|body| body.contains("_disableInitializers"))
+    });
+    if locked {
+        return;
+    }
+
+    let uups = is_uups_unit(chain);
+    let impact = if uups {
+        "Whoever initializes it becomes its owner and can call upgradeToAndCall on the implementation itself, delegating to code that selfdestructs it or otherwise bricks every proxy pointing at it."
+    } else {
+        "Whoever initializes it takes every role the initializer grants on the implementation, and can act with them wherever the implementation's own state is trusted."
+    };
+    findings.push(finding(
+        file,
+        Some(initializer.line_number),
+        if uups { "High" } else { "Medium" },
+        VulnerabilityCategory::AccessControl,
+        Some("CWE-665"),
+        format!("Uninitialized Implementation {}", unit.name),
+        format!(
+            "{} is initialized through `{}`, but its constructor does not disable initializers. Proxies initialize their own storage; the implementation contract keeps an open initializer. {}",
+            unit.name, initializer.name, impact
+        ),
+        "Add a constructor that calls `_disableInitializers()` (OpenZeppelin 4.6 and later), or mark the constructor `initializer`.",
+        "https://docs.openzeppelin.com/upgrades-plugins/writing-upgradeable#initializing_the_implementation_contract",
+    ));
+}
+
+/// State variables of a proxy take the same slots as the implementation's first variables
+fn check_proxy_storage(
+    proxy: &ContractUnit,
+    file: &str,
+    chain: &[&ContractUnit],
+    implementations: &[(&ContractUnit, Vec<&ContractUnit>)],
+    findings: &mut Vec<Vulnerability>,
+) {
+    let variables: Vec<_> = chain
+        .iter()
+        .rev()
+        .flat_map(|unit| &unit.state_variables)
+        .filter(|variable| !variable.is_constant && !variable.is_immutable)
+        .collect();
+    let first = match variables.first() {
+        Some(first) => first,
+        None => return,
+    };
+    let names: Vec<&str> = variables.iter().map(|variable| variable.name.as_str()).collect();
+
+    // Slot 0 of each implementation of the run, whose first variable the proxy's first one overwrites
+    let overlaps: Vec<String> = implementations
+        .iter()
+        .filter_map(|(implementation, implementation_chain)| {
+            let variable = implementation_chain
+                .iter()
+                .rev()
+                .flat_map(|unit| &unit.state_variables)
+                .find(|variable| !variable.is_constant && !variable.is_immutable)?;
+            Some(format!("`{}` of {}", variable.name, implementation.name))
+        })
+        .collect();
+    let overlap = if overlaps.is_empty() {
+        String::new()
+    } else {
+        format!(" In this run `{}` shares slot 0 with {}.", first.name, overlaps.join(", "))
+    };
+
+    findings.push(finding(
+        file,
+        Some(first.line_number),
+        "High",
+        VulnerabilityCategory::Other,
+        None,
+        format!("Proxy {} Declares State Variables", proxy.name),
+        format!(
+            "{} declares {} in regular storage, starting at slot 0. The implementation runs in the proxy's storage and lays out its own variables from slot 0 too, so writes through either overwrite the other's.{}",
+            proxy.name,
+            names.join(", "),
+            overlap
+        ),
+        "Keep proxy state in EIP-1967 slots or another unstructured storage position, and declare no state variables in the proxy.",
+        "https://eips.ethereum.org/EIPS/eip-1967",
+    ));
+}
+
+/// Upgrade functions and UUPS `_authorizeUpgrade` overrides without an owner or role check
+fn check_upgrade_access(unit: &ContractUnit, file: &str, chain: &[&ContractUnit], findings: &mut Vec<Vulnerability>) {
+    for function in &unit.functions {
+        let body = match &function.body {
+            Some(body) => body,
+            None => continue,
+        };
+        let restricted = function.modifiers.iter().any(|modifier| !CONTEXT_MODIFIERS.contains(&modifier.as_str()))
+            || ACCESS_CHECKS.iter().any(|check| body.contains(check));
+        if restricted {
+            continue;
+        }
+
+        let authorizer = function.name == "_authorizeUpgrade";
+        let public = matches!(function.visibility.as_str(), "public" | "external");
+        if !(authorizer || public && UPGRADE_FUNCTIONS.contains(&function.name.as_str())) {
+            continue;
+        }
+        let description = if authorizer {
+            format!(
+                "`_authorizeUpgrade` in {} neither has a modifier nor checks the caller, so UUPS `upgradeToAndCall` lets anyone point every proxy of {} at their own code.",
+                unit.name, unit.name
+            )
+        } else {
+            let through = if is_proxy(chain) { "the proxy" } else { "the proxies using it" };
+            format!(
+                "`{}` in {} changes the implementation without checking the caller. Anyone can replace the code of {} and take its storage and funds.",
+                function.name, unit.name, through
+            )
+        };
+        findings.push(finding(
+            file,
+            Some(function.line_number),
+            "Critical",
+            VulnerabilityCategory::AccessControl,
+            Some("CWE-284"),
+            format!("Unprotected Upgrade Function {} in {}", function.name, unit.name),
+            description,
+            "Restrict upgrades to the owner, an admin role or a timelock, e.g. with `onlyOwner` on `_authorizeUpgrade`.",
+            "https://docs.openzeppelin.com/contracts/5.x/api/proxy#UUPSUpgradeable",
+        ));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn finding(
+    file: &str,
+    line_number: Option<usize>,
+    severity: &str,
+    category: VulnerabilityCategory,
+    cwe_id: Option<&str>,
+    title: String,
+    description: String,
+    recommendation: &str,
+    reference: &str,
+) -> Vulnerability {
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title,
+        description,
+        severity: severity.to_string(),
+        category,
+        file_path: file.to_string(),
+        line_number,
+        code_snippet: None,
+        recommendation: Some(recommendation.to_string()),
+        references: vec![reference.to_string()],
+        cwe_id: cwe_id.map(str::to_string),
+        tool: TOOL_NAME.to_string(),
+        confidence: 0.8,
+        provenance: None,
+        severity_overrides: Vec::new(),
+        cvss: None,
+        detected_by: Vec::new(),
+    }
+}
@@ -0,0 +1,186 @@
+//! Remediation verification
+//!
+//! After a client reports fixes, each prior finding is checked against the
+//! new code by re-running only the tool that produced it. A finding is fixed
+//! when its tool ran and no longer reports it, not fixed when it still does,
+//! and regressed when it now appears more often or at a higher severity.
+//! Findings from tools that cannot be re-run stay unverified for manual review.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::core::analyzer::AnalysisResults;
+use crate::core::completeness::Completeness;
+use crate::core::snapshot::Drift;
+use crate::report::generator::ComprehensiveReport;
+use crate::report::vulnerability::Vulnerability;
+use crate::utils::artifact;
+
+/// Tools whose findings can be verified by re-running them on the fixed code
+pub const RERUNNABLE_TOOLS: &[&str] = &[
+    "Slither",
+    "Native Detectors",
+    "Parameter Validation",
+    "Error Handling",
+    "Gas Analysis",
+    "Mythril",
+    "Echidna",
+    "Solhint",
+    "Semgrep",
+    "Solc",
+    "Arithmetic Execution",
+    "Selector Analysis",
+    "Dead Code Analysis",
+    "Proxy Analysis",
+];
+
+/// Outcome of re-checking one prior finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixStatus {
+    Fixed,
+    NotFixed,
+    Regressed,
+    /// Its tool could not be re-run, so the fix needs manual review
+    Unverified,
+}
+
+impl std::fmt::Display for FixStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            FixStatus::Fixed => "Fixed",
+            FixStatus::NotFixed => "Not Fixed",
+            FixStatus::Regressed => "Regressed",
+            FixStatus::Unverified => "Unverified",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// One prior finding and what the re-run found
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingVerification {
+    pub finding: Vulnerability,
+    pub status: FixStatus,
+    /// Matching findings in the fixed code
+    pub current: Vec<Vulnerability>,
+    pub note: String,
+}
+
+/// Result of verifying fixes for a set of prior findings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemediationResults {
+    pub project: String,
+    pub verified_at: chrono::DateTime<chrono::Utc>,
+    pub verifications: Vec<FindingVerification>,
+    /// Findings of the re-run tools that the prior results did not contain
+    pub new_findings: Vec<Vulnerability>,
+    pub tools_rerun: Vec<String>,
+    pub completeness: Completeness,
+    /// Differences from the behavior snapshot of the original code, when one was given
+    #[serde(default)]
+    pub behavior_drift: Vec<Drift>,
+}
+
+impl RemediationResults {
+    pub fn count(&self, status: FixStatus) -> usize {
+        self.verifications.iter().filter(|verification| verification.status == status).count()
+    }
+}
+
+/// Prior findings from `analyze --json` output or a JSON report
+pub fn load_findings(path: &Path) -> Result<(String, Vec<Vulnerability>)> {
+    let content = artifact::read_text(path, "findings file")?;
+    if let Ok(results) = serde_json::from_str::<AnalysisResults>(&content) {
+        let project = results
+            .metadata
+            .as_ref()
+            .map_or_else(|| results.contract_name.clone(), |metadata| metadata.title.clone());
+        return Ok((project, results.vulnerabilities));
+    }
+    if let Ok(report) = serde_json::from_str::<ComprehensiveReport>(&content) {
+        return Ok((report.metadata.project.title, report.vulnerability_analysis.vulnerabilities));
+    }
+    // Either parse is as good as the other for telling a truncated file apart
+    match serde_json::from_str::<serde_json::Value>(&content) {
+        Err(e) => Err(artifact::invalid(path, "findings file", &e)),
+        Ok(_) => Err(anyhow!("{} is neither analysis results nor a JSON report", path.display())),
+    }
+}
+
+/// Compare prior findings with the findings of re-running their tools
+///
+/// Findings whose tool did not complete on their file are unverified.
+pub fn verify(
+    prior: &[Vulnerability],
+    current: &[Vulnerability],
+    completeness: &Completeness,
+) -> (Vec<FindingVerification>, Vec<Vulnerability>) {
+    let verifications = prior
+        .iter()
+        .map(|finding| {
+            // Still reported means seen before: keep when and with what it was first found,
+            // and the severity an auditor gave it
+            let matches: Vec<Vulnerability> = current
+                .iter()
+                .filter(|c| same_issue(finding, c))
+                .map(|c| {
+                    let mut c = c.clone();
+                    c.merge_provenance(finding);
+                    c.merge_severity_overrides(finding);
+                    c
+                })
+                .collect();
+            let prior_count = prior.iter().filter(|p| same_issue(finding, p)).count();
+
+            let (status, note) = if !completeness.completed_on(&finding.tool, &finding.file_path) {
+                let reason = if RERUNNABLE_TOOLS.contains(&finding.tool.as_str()) {
+                    format!("{} did not complete on the fixed code", finding.tool)
+                } else {
+                    format!("{} findings cannot be re-checked automatically", finding.tool)
+                };
+                (FixStatus::Unverified, reason)
+            } else if matches.is_empty() {
+                (FixStatus::Fixed, format!("{} no longer reports it", finding.tool))
+            } else if let Some(worse) = matches.iter().find(|m| m.severity_priority() > finding.severity_priority()) {
+                (
+                    FixStatus::Regressed,
+                    format!("Severity rose from {} to {}", finding.severity, worse.severity),
+                )
+            } else if matches.len() > prior_count {
+                (
+                    FixStatus::Regressed,
+                    format!("Now reported {} times, previously {}", matches.len(), prior_count),
+                )
+            } else {
+                let lines: Vec<String> = matches
+                    .iter()
+                    .filter_map(|m| m.line_number.map(|line| line.to_string()))
+                    .collect();
+                let at = if lines.is_empty() { String::new() } else { format!(" at line {}", lines.join(", ")) };
+                (FixStatus::NotFixed, format!("Still reported{}", at))
+            };
+
+            FindingVerification {
+                finding: finding.clone(),
+                status,
+                current: matches,
+                note,
+            }
+        })
+        .collect();
+
+    let new_findings = current
+        .iter()
+        .filter(|c| !prior.iter().any(|p| same_issue(p, c)))
+        .cloned()
+        .collect();
+
+    (verifications, new_findings)
+}
+
+/// Same tool, file and title; lines move when code around a fix changes
+fn same_issue(a: &Vulnerability, b: &Vulnerability) -> bool {
+    a.tool == b.tool && a.file_path == b.file_path && a.title == b.title
+}
@@ -0,0 +1,118 @@
+//! Per-function risk scoring
+//!
+//! Expensive analyses such as symbolic execution cannot cover every function
+//! of a large contract within their budget. Scoring each external function by
+//! the dangerous operations it performs, directly or through the internal
+//! functions it calls, and by the guards it lacks lets them spend the budget
+//! on the functions most likely to hold a bug.
+
+use anyhow::Result;
+
+use crate::core::callgraph::CallGraph;
+use crate::core::parser::{ParsedContract, UnitFunction};
+use crate::core::selectors;
+
+/// Operations that raise a function's risk, with their weight
+const RISK_MARKERS: &[(&str, u32, &str)] = &[
+    ("delegatecall", 5, "delegatecall"),
+    ("selfdestruct", 5, "selfdestruct"),
+    (".call{value", 4, "sends ether"),
+    (".transfer(", 3, "sends ether or tokens"),
+    (".send(", 3, "sends ether"),
+    (".call(", 3, "low-level call"),
+    ("transferFrom(", 2, "moves tokens"),
+    ("assembly", 2, "inline assembly"),
+    ("tx.origin", 1, "tx.origin"),
+    ("block.timestamp", 1, "block timestamp"),
+    ("for (", 1, "loop"),
+    ("while (", 1, "loop"),
+];
+
+/// Risk of one external function of a deployable contract
+#[derive(Debug, Clone)]
+pub struct FunctionRisk {
+    pub contract: String,
+    pub signature: String,
+    pub selector: String,
+    pub score: u32,
+    pub reasons: Vec<String>,
+}
+
+/// Score the state-changing external functions of every deployable contract, riskiest first
+pub fn score_functions(contract: &ParsedContract) -> Result<Vec<FunctionRisk>> {
+    let contracts = std::slice::from_ref(contract);
+    let externals = selectors::external_functions(contracts)?;
+    let graph = CallGraph::build(contracts);
+
+    let mut risks: Vec<FunctionRisk> = Vec::new();
+    for (name, functions) in externals {
+        for external in functions.iter().filter(|external| !external.is_getter) {
+            let function_name = external.signature.split('(').next().unwrap_or_default();
+            let index = graph.nodes.iter().position(|node| {
+                node.function.name == function_name && node.function.line_number == external.line_number
+            });
+            let index = match index {
+                Some(index) if !is_read_only(graph.nodes[index].function) => index,
+                _ => continue,
+            };
+
+            let (score, reasons) = score_function(&graph, index);
+            risks.push(FunctionRisk {
+                contract: name.clone(),
+                signature: external.signature.clone(),
+                selector: external.selector.clone(),
+                score,
+                reasons,
+            });
+        }
+    }
+
+    risks.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.signature.cmp(&b.signature)));
+    Ok(risks)
+}
+
+fn score_function(graph: &CallGraph, index: usize) -> (u32, Vec<String>) {
+    let function: &UnitFunction = graph.nodes[index].function;
+    let body = function.body.as_deref().unwrap_or_default();
+
+    // Internal calls count as part of the function, external ones are entry points of their own
+    let reached: String = graph
+        .callees_of(index)
+        .into_iter()
+        .filter(|callee| !graph.is_entry_point(*callee))
+        .filter_map(|callee| graph.nodes[callee].function.body.as_deref())
+        .fold(body.to_string(), |mut reached, callee_body| {
+            reached.push_str(callee_body);
+            reached
+        });
+
+    let mut score = 1;
+    let mut reasons = Vec::new();
+
+    for (marker, weight, reason) in RISK_MARKERS {
+        if reached.contains(marker) && !reasons.iter().any(|existing| existing == reason) {
+            score += weight;
+            reasons.push(reason.to_string());
+        }
+    }
+
+    if function.state_mutability == "payable" {
+        score += 3;
+        reasons.push("payable".to_string());
+    }
+
+    let guarded = function.modifiers.iter().any(|modifier| modifier.starts_with("only"))
+        || ["msg.sender ==", "== msg.sender", "hasRole(", "_checkOwner", "_checkRole"]
+            .iter()
+            .any(|check| body.contains(check));
+    if !guarded {
+        score += 2;
+        reasons.push("callable by anyone".to_string());
+    }
+
+    (score, reasons)
+}
+
+fn is_read_only(function: &UnitFunction) -> bool {
+    matches!(function.state_mutability.as_str(), "view" | "pure" | "constant")
+}
@@ -0,0 +1,118 @@
+//! Minimal Ethereum JSON-RPC client
+//!
+//! Only the calls needed to inspect deployed contracts are implemented:
+//! `eth_call` and `eth_getStorageAt` against the latest block, and `eth_getLogs`.
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::utils::abi::{decode_hex, encode_hex};
+
+/// A log entry returned by `eth_getLogs`
+#[derive(Debug, Clone)]
+pub struct RpcLog {
+    pub block_number: u64,
+    pub transaction_hash: String,
+    pub data: Vec<u8>,
+}
+
+/// JSON-RPC client for a single endpoint
+pub struct RpcClient {
+    url: String,
+    #[cfg_attr(not(feature = "explorers"), allow(dead_code))]
+    timeout: Duration,
+}
+
+impl RpcClient {
+    /// Create a client for `url`
+    pub fn new(url: &str, timeout_secs: u64) -> Self {
+        Self {
+            url: url.to_string(),
+            timeout: Duration::from_secs(timeout_secs),
+        }
+    }
+
+    /// Send a JSON-RPC request and return its `result`
+    pub fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let response = self.post(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        }))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(anyhow!(
+                "{} failed: {}",
+                method,
+                error["message"].as_str().unwrap_or("unknown RPC error")
+            ));
+        }
+
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| anyhow!("{} returned no result", method))
+    }
+
+    /// POST one JSON-RPC body and decode the response
+    #[cfg(feature = "explorers")]
+    fn post(&self, body: Value) -> Result<Value> {
+        Ok(ureq::post(&self.url).timeout(self.timeout).send_json(body)?.into_json()?)
+    }
+
+    #[cfg(not(feature = "explorers"))]
+    fn post(&self, _body: Value) -> Result<Value> {
+        Err(anyhow!("JSON-RPC calls to {} need a build with the `explorers` feature", self.url))
+    }
+
+    /// Execute a read-only call against the latest block
+    pub fn call(&self, to: &str, data: &[u8]) -> Result<Vec<u8>> {
+        let result = self.request(
+            "eth_call",
+            json!([{ "to": to, "data": format!("0x{}", encode_hex(data)) }, "latest"]),
+        )?;
+
+        decode_hex(result.as_str().unwrap_or_default()).map_err(|e| anyhow!("Invalid eth_call result: {}", e))
+    }
+
+    /// Read a storage slot of `address` at the latest block
+    pub fn storage_at(&self, address: &str, slot: &str) -> Result<Vec<u8>> {
+        let result = self.request("eth_getStorageAt", json!([address, slot, "latest"]))?;
+
+        decode_hex(result.as_str().unwrap_or_default()).map_err(|e| anyhow!("Invalid eth_getStorageAt result: {}", e))
+    }
+
+    /// Logs emitted by `address` with the given first topic, over the whole chain
+    pub fn get_logs(&self, address: &str, topic0: &[u8]) -> Result<Vec<RpcLog>> {
+        let result = self.request(
+            "eth_getLogs",
+            json!([{
+                "address": address,
+                "topics": [format!("0x{}", encode_hex(topic0))],
+                "fromBlock": "earliest",
+                "toBlock": "latest",
+            }]),
+        )?;
+
+        let entries = result.as_array().ok_or_else(|| anyhow!("eth_getLogs returned no list"))?;
+        let mut logs = Vec::new();
+        for entry in entries {
+            let block_number = entry["blockNumber"]
+                .as_str()
+                .and_then(|block| u64::from_str_radix(block.trim_start_matches("0x"), 16).ok())
+                .unwrap_or_default();
+
+            logs.push(RpcLog {
+                block_number,
+                transaction_hash: entry["transactionHash"].as_str().unwrap_or_default().to_string(),
+                data: decode_hex(entry["data"].as_str().unwrap_or_default())
+                    .map_err(|e| anyhow!("Invalid log data: {}", e))?,
+            });
+        }
+
+        logs.sort_by_key(|log| log.block_number);
+        Ok(logs)
+    }
+}
@@ -0,0 +1,685 @@
+//! Function selector analysis for Solidity contracts
+//!
+//! This module computes the 4-byte selectors of every externally callable
+//! function (including public state variable getters) and checks them across
+//! all contracts of an analysis run: selector collisions, proxy/implementation
+//! clashes, selectors routed to several diamond facets, shadowed functions
+//! and state variables in inheritance chains, and implementations that
+//! change the semantics of the interface they implement.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::core::parser::{ContractParser, ContractUnit, ParsedContract, UnitFunction, UserTypes};
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+use crate::utils::abi::{encode_hex, keccak256};
+
+const TOOL_NAME: &str = "Selector Analysis";
+
+/// Well-known ERC20 functions callers expect to return `bool`
+const ERC20_BOOL_FUNCTIONS: &[&str] = &[
+    "transfer(address,uint256)",
+    "transferFrom(address,address,uint256)",
+    "approve(address,uint256)",
+];
+
+/// An externally callable entry point of a contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalFunction {
+    pub contract: String,
+    pub file: String,
+    pub signature: String,
+    pub selector: String,
+    pub line_number: usize,
+    /// Whether this is the implicit getter of a public state variable
+    pub is_getter: bool,
+    /// Whether calling it cannot change state: view and pure functions, and getters
+    #[serde(default)]
+    pub is_view: bool,
+}
+
+/// One row of a diamond's selector routing table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectorRoute {
+    pub selector: String,
+    pub signature: String,
+    pub facet: String,
+}
+
+/// Findings and routing information from selector analysis
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SelectorAnalysis {
+    pub findings: Vec<Vulnerability>,
+    /// Facet routing table, only filled for diamond-based systems
+    pub routes: Vec<SelectorRoute>,
+}
+
+/// Compute the 4-byte selector of a canonical signature, e.g. `transfer(address,uint256)`
+pub fn selector(signature: &str) -> String {
+    format!("0x{}", encode_hex(&keccak256(signature.as_bytes())[..4]))
+}
+
+/// A contract unit together with the file it was declared in
+struct UnitRef<'a> {
+    unit: &'a ContractUnit,
+    file: &'a str,
+}
+
+/// Resolves names and types across every unit of an analysis run
+struct SelectorContext<'a> {
+    units: HashMap<&'a str, UnitRef<'a>>,
+    user_types: UserTypes,
+}
+
+impl<'a> SelectorContext<'a> {
+    fn new(contracts: &'a [ParsedContract]) -> Result<Self> {
+        let parser = ContractParser::new()?;
+        let mut units = HashMap::new();
+        let mut user_types = UserTypes::default();
+
+        for contract in contracts {
+            for unit in &contract.units {
+                units.insert(unit.name.as_str(), UnitRef { unit, file: &contract.name });
+            }
+
+            let types = parser.extract_user_types(&contract.source_code)?;
+            user_types.structs.extend(types.structs);
+            user_types.enums.extend(types.enums);
+        }
+
+        Ok(Self { units, user_types })
+    }
+
+    /// Canonical ABI type of a Solidity type name
+    fn canonical_type(&self, type_name: &str, depth: usize) -> String {
+        // Peel array suffixes, canonicalise the base, then re-append them
+        if let Some(open) = type_name.find('[') {
+            let (base, arrays) = type_name.split_at(open);
+            let arrays: String = arrays.chars().filter(|c| !c.is_whitespace()).collect();
+            return format!("{}{}", self.canonical_type(base.trim(), depth), arrays);
+        }
+
+        let base = type_name.rsplit('.').next().unwrap_or(type_name);
+        match base {
+            "uint" => "uint256".to_string(),
+            "int" => "int256".to_string(),
+            "byte" => "bytes1".to_string(),
+            "fixed" => "fixed128x18".to_string(),
+            "ufixed" => "ufixed128x18".to_string(),
+            "address" | "bool" | "string" | "bytes" | "function" => base.to_string(),
+            _ if is_sized_elementary(base) => base.to_string(),
+            _ => {
+                if let Some(fields) = self.user_types.structs.get(base) {
+                    if depth < 8 {
+                        let fields: Vec<String> = fields.iter().map(|f| self.canonical_type(f, depth + 1)).collect();
+                        return format!("({})", fields.join(","));
+                    }
+                }
+                if self.user_types.enums.iter().any(|e| e == base) {
+                    return "uint8".to_string();
+                }
+                // Contracts, interfaces and unresolved imports are addresses in the ABI
+                "address".to_string()
+            }
+        }
+    }
+
+    fn signature(&self, name: &str, parameter_types: &[String]) -> String {
+        let types: Vec<String> = parameter_types.iter().map(|t| self.canonical_type(t, 0)).collect();
+        format!("{}({})", name, types.join(","))
+    }
+
+    /// External functions and getters declared directly in a unit
+    fn own_externals(&self, unit_ref: &UnitRef) -> Vec<ExternalFunction> {
+        let unit = unit_ref.unit;
+        let mut externals = Vec::new();
+
+        for function in &unit.functions {
+            if !matches!(function.visibility.as_str(), "external" | "public") {
+                continue;
+            }
+            let signature = self.signature(&function.name, &function.parameter_types);
+            externals.push(ExternalFunction {
+                contract: unit.name.clone(),
+                file: unit_ref.file.to_string(),
+                selector: selector(&signature),
+                signature,
+                line_number: function.line_number,
+                is_getter: false,
+                is_view: matches!(function.state_mutability.as_str(), "view" | "pure" | "constant"),
+            });
+        }
+
+        for variable in &unit.state_variables {
+            if variable.visibility != "public" {
+                continue;
+            }
+            let signature = self.signature(&variable.name, &getter_parameters(&variable.type_name));
+            externals.push(ExternalFunction {
+                contract: unit.name.clone(),
+                file: unit_ref.file.to_string(),
+                selector: selector(&signature),
+                signature,
+                line_number: variable.line_number,
+                is_getter: true,
+                is_view: true,
+            });
+        }
+
+        externals
+    }
+
+    /// Base units of `name`, nearest first, as far as they are part of this run
+    fn linearized_bases(&self, name: &str) -> Vec<&'a ContractUnit> {
+        let mut bases = Vec::new();
+        let mut seen = HashSet::new();
+        let mut queue: Vec<&str> = vec![name];
+
+        while let Some(current) = queue.pop() {
+            if let Some(unit_ref) = self.units.get(current) {
+                for base in unit_ref.unit.inheritance.iter().rev() {
+                    if let Some(base_ref) = self.units.get(base.as_str()) {
+                        if seen.insert(base_ref.unit.name.as_str()) {
+                            bases.push(base_ref.unit);
+                            queue.insert(0, base_ref.unit.name.as_str());
+                        }
+                    }
+                }
+            }
+        }
+
+        bases
+    }
+
+    /// All external functions reachable on a deployed unit, own and inherited
+    fn effective_externals(&self, unit_ref: &UnitRef) -> Vec<ExternalFunction> {
+        let mut externals = self.own_externals(unit_ref);
+        let mut selectors: HashSet<String> = externals.iter().map(|f| f.selector.clone()).collect();
+
+        for base in self.linearized_bases(&unit_ref.unit.name) {
+            if let Some(base_ref) = self.units.get(base.name.as_str()) {
+                for external in self.own_externals(base_ref) {
+                    if selectors.insert(external.selector.clone()) {
+                        externals.push(external);
+                    }
+                }
+            }
+        }
+
+        externals
+    }
+}
+
+/// Run selector analysis over all contracts of a run
+pub fn analyze_selectors(contracts: &[ParsedContract]) -> Result<SelectorAnalysis> {
+    let context = SelectorContext::new(contracts)?;
+    let mut analysis = SelectorAnalysis::default();
+
+    let mut deployable: Vec<&UnitRef> = context
+        .units
+        .values()
+        .filter(|unit_ref| unit_ref.unit.kind == "contract")
+        .collect();
+    deployable.sort_by(|a, b| a.unit.name.cmp(&b.unit.name));
+
+    check_collisions(&context, &mut analysis);
+    check_proxy_clashes(&context, &deployable, &mut analysis);
+
+    let facets = diamond_facets(contracts, &deployable);
+    if !facets.is_empty() {
+        check_facet_routing(&context, &facets, &mut analysis);
+    }
+
+    for unit_ref in &deployable {
+        check_shadowing(&context, unit_ref, &mut analysis);
+        check_interface_semantics(&context, unit_ref, &mut analysis);
+    }
+
+    Ok(analysis)
+}
+
+/// Effective external functions of every deployable contract, by contract name
+pub fn external_functions(contracts: &[ParsedContract]) -> Result<HashMap<String, Vec<ExternalFunction>>> {
+    let context = SelectorContext::new(contracts)?;
+
+    Ok(context
+        .units
+        .values()
+        .filter(|unit_ref| unit_ref.unit.kind == "contract")
+        .map(|unit_ref| (unit_ref.unit.name.clone(), context.effective_externals(unit_ref)))
+        .collect())
+}
+
+/// Canonical signature and topic of each event, by the file it is declared in
+pub fn event_signatures(contracts: &[ParsedContract]) -> Result<HashMap<String, Vec<(String, String)>>> {
+    let context = SelectorContext::new(contracts)?;
+
+    Ok(contracts
+        .iter()
+        .map(|contract| {
+            let events = contract
+                .events
+                .iter()
+                .map(|event| {
+                    let types: Vec<String> = event.parameters.iter().map(|parameter| parameter.type_name.clone()).collect();
+                    let signature = context.signature(&event.name, &types);
+                    let topic = format!("0x{}", encode_hex(&keccak256(signature.as_bytes())));
+                    (signature, topic)
+                })
+                .collect();
+            (contract.name.clone(), events)
+        })
+        .collect())
+}
+
+/// Canonical signatures of declarations given by name and parameter types as
+/// written, with the run's structs and enums resolved
+pub fn canonical_signatures(contracts: &[ParsedContract], declarations: &[(String, Vec<String>)]) -> Result<Vec<String>> {
+    let context = SelectorContext::new(contracts)?;
+    Ok(declarations.iter().map(|(name, types)| context.signature(name, types)).collect())
+}
+
+/// Names of the facets of a diamond-based system, or nothing if no diamond is present
+pub fn diamond_facet_names(contracts: &[ParsedContract]) -> Result<Vec<String>> {
+    let context = SelectorContext::new(contracts)?;
+    let mut deployable: Vec<&UnitRef> = context
+        .units
+        .values()
+        .filter(|unit_ref| unit_ref.unit.kind == "contract")
+        .collect();
+    deployable.sort_by(|a, b| a.unit.name.cmp(&b.unit.name));
+
+    Ok(diamond_facets(contracts, &deployable)
+        .into_iter()
+        .map(|unit_ref| unit_ref.unit.name.clone())
+        .collect())
+}
+
+/// Distinct signatures that hash to the same selector
+fn check_collisions(context: &SelectorContext, analysis: &mut SelectorAnalysis) {
+    let mut by_selector: HashMap<String, Vec<ExternalFunction>> = HashMap::new();
+    for unit_ref in context.units.values() {
+        for external in context.own_externals(unit_ref) {
+            by_selector.entry(external.selector.clone()).or_default().push(external);
+        }
+    }
+
+    let mut selectors: Vec<&String> = by_selector.keys().collect();
+    selectors.sort();
+
+    for selector in selectors {
+        let functions = &by_selector[selector];
+        let signatures: HashSet<&str> = functions.iter().map(|f| f.signature.as_str()).collect();
+        if signatures.len() < 2 {
+            continue;
+        }
+
+        let mut names: Vec<&str> = signatures.into_iter().collect();
+        names.sort();
+        let first = &functions[0];
+
+        analysis.findings.push(selector_finding(
+            first,
+            format!("Function Selector Collision on {}", selector),
+            format!(
+                "The signatures {} all hash to selector {}. Behind a proxy or diamond only one of them can be routed; calls intended for the other silently reach the wrong function.",
+                names.join(", "),
+                selector
+            ),
+            "High",
+            VulnerabilityCategory::Other,
+            "Rename one of the functions so that the selectors no longer collide.",
+        ));
+    }
+}
+
+/// Proxy functions that shadow implementation functions with the same selector
+fn check_proxy_clashes(context: &SelectorContext, deployable: &[&UnitRef], analysis: &mut SelectorAnalysis) {
+    let proxies: Vec<&&UnitRef> = deployable.iter().filter(|unit_ref| is_proxy(unit_ref.unit)).collect();
+
+    for proxy in proxies {
+        let proxy_selectors: HashMap<String, ExternalFunction> = context
+            .effective_externals(proxy)
+            .into_iter()
+            .map(|f| (f.selector.clone(), f))
+            .collect();
+
+        for implementation in deployable {
+            if implementation.unit.name == proxy.unit.name || is_proxy(implementation.unit) {
+                continue;
+            }
+
+            for external in context.effective_externals(implementation) {
+                if let Some(proxy_function) = proxy_selectors.get(&external.selector) {
+                    analysis.findings.push(selector_finding(
+                        &external,
+                        format!("Proxy Selector Clash: {}", external.signature),
+                        format!(
+                            "`{}` in {} has selector {}, which {} also exposes as `{}`. Calls through the proxy never reach the implementation function.",
+                            external.signature, external.contract, external.selector, proxy_function.contract, proxy_function.signature
+                        ),
+                        "High",
+                        VulnerabilityCategory::Other,
+                        "Use the transparent or UUPS proxy pattern so that the proxy exposes no selectors of its own, or rename the clashing function.",
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Selectors implemented by more than one facet, plus the routing table
+fn check_facet_routing(context: &SelectorContext, facets: &[&UnitRef], analysis: &mut SelectorAnalysis) {
+    let mut by_selector: HashMap<String, Vec<ExternalFunction>> = HashMap::new();
+    for facet in facets {
+        for external in context.effective_externals(facet) {
+            by_selector.entry(external.selector.clone()).or_default().push(external);
+        }
+    }
+
+    let mut selectors: Vec<&String> = by_selector.keys().collect();
+    selectors.sort();
+
+    for selector in selectors {
+        let functions = &by_selector[selector];
+        for function in functions {
+            analysis.routes.push(SelectorRoute {
+                selector: selector.clone(),
+                signature: function.signature.clone(),
+                facet: function.contract.clone(),
+            });
+        }
+
+        if functions.len() > 1 {
+            let owners: Vec<&str> = functions.iter().map(|f| f.contract.as_str()).collect();
+            analysis.findings.push(selector_finding(
+                &functions[0],
+                format!("Selector {} Implemented by Multiple Facets", selector),
+                format!(
+                    "`{}` is exposed by {}. A diamond routes each selector to exactly one facet, so adding both facets either reverts or silently replaces one implementation.",
+                    functions[0].signature,
+                    owners.join(", ")
+                ),
+                "High",
+                VulnerabilityCategory::Other,
+                "Keep every selector in a single facet and move shared logic into internal library functions.",
+            ));
+        }
+    }
+}
+
+/// Functions and state variables redeclared in a child contract
+fn check_shadowing(context: &SelectorContext, unit_ref: &UnitRef, analysis: &mut SelectorAnalysis) {
+    let unit = unit_ref.unit;
+    let bases = context.linearized_bases(&unit.name);
+
+    for function in &unit.functions {
+        if function.is_override {
+            continue;
+        }
+        let signature = context.signature(&function.name, &function.parameter_types);
+
+        let shadowed = bases.iter().find(|base| {
+            base.kind != "interface"
+                && base
+                    .functions
+                    .iter()
+                    .any(|f| f.body.is_some() && context.signature(&f.name, &f.parameter_types) == signature)
+        });
+
+        if let Some(base) = shadowed {
+            analysis.findings.push(unit_finding(
+                unit_ref,
+                function.line_number,
+                format!("Shadowed Function {}", signature),
+                format!(
+                    "`{}` in {} redefines the implemented function from {} without `override`. Older compilers accept this silently, so code relying on the base behaviour is bypassed.",
+                    signature, unit.name, base.name
+                ),
+                "Medium",
+                VulnerabilityCategory::CodeQuality,
+                "Mark the base function `virtual` and the redefinition `override`, and confirm the override is intended.",
+            ));
+        }
+    }
+
+    for variable in &unit.state_variables {
+        let shadowed = bases
+            .iter()
+            .find(|base| base.state_variables.iter().any(|v| v.name == variable.name));
+
+        if let Some(base) = shadowed {
+            analysis.findings.push(unit_finding(
+                unit_ref,
+                variable.line_number,
+                format!("Shadowed State Variable {}", variable.name),
+                format!(
+                    "{} declares `{}`, which is already declared in {}. The two live in different storage slots, so functions from {} read and write a different value.",
+                    unit.name, variable.name, base.name, base.name
+                ),
+                "Medium",
+                VulnerabilityCategory::CodeQuality,
+                "Remove the redeclaration and use the inherited variable.",
+            ));
+        }
+    }
+}
+
+/// Implementations that differ from the interfaces they implement
+fn check_interface_semantics(context: &SelectorContext, unit_ref: &UnitRef, analysis: &mut SelectorAnalysis) {
+    let unit = unit_ref.unit;
+    let bases = context.linearized_bases(&unit.name);
+
+    // Implementations visible on this contract, nearest declaration first
+    let mut implementations: HashMap<String, &UnitFunction> = HashMap::new();
+    for candidate in std::iter::once(unit).chain(bases.iter().copied().filter(|b| b.kind != "interface")) {
+        for function in &candidate.functions {
+            implementations
+                .entry(context.signature(&function.name, &function.parameter_types))
+                .or_insert(function);
+        }
+    }
+
+    for interface in bases.iter().filter(|b| b.kind == "interface") {
+        for declared in &interface.functions {
+            let signature = context.signature(&declared.name, &declared.parameter_types);
+            let implementation = match implementations.get(&signature) {
+                Some(implementation) => implementation,
+                None => continue,
+            };
+
+            let declared_read_only = matches!(declared.state_mutability.as_str(), "view" | "pure");
+            let implemented_read_only = matches!(implementation.state_mutability.as_str(), "view" | "pure");
+            if declared_read_only && !implemented_read_only {
+                analysis.findings.push(unit_finding(
+                    unit_ref,
+                    implementation.line_number,
+                    format!("State-Changing Implementation of {}", signature),
+                    format!(
+                        "{} declares `{}` as {}, but {} implements it as state-changing. Integrators calling it through the interface assume it has no side effects.",
+                        interface.name, signature, declared.state_mutability, unit.name
+                    ),
+                    "Medium",
+                    VulnerabilityCategory::Other,
+                    "Keep the implementation read-only or update the interface so callers know it changes state.",
+                ));
+            }
+
+            let declared_returns: Vec<String> = declared.return_types.iter().map(|t| context.canonical_type(t, 0)).collect();
+            let implemented_returns: Vec<String> = implementation.return_types.iter().map(|t| context.canonical_type(t, 0)).collect();
+            if declared_returns != implemented_returns {
+                analysis.findings.push(unit_finding(
+                    unit_ref,
+                    implementation.line_number,
+                    format!("Return Type Mismatch in {}", signature),
+                    format!(
+                        "{} declares `{}` returning ({}), but {} returns ({}). Callers decoding the interface's return data will revert or misread it.",
+                        interface.name,
+                        signature,
+                        declared_returns.join(", "),
+                        unit.name,
+                        implemented_returns.join(", ")
+                    ),
+                    "Medium",
+                    VulnerabilityCategory::Other,
+                    "Match the return types declared by the interface.",
+                ));
+            }
+        }
+    }
+
+    // ERC20 functions that do not return bool break SafeERC20-less integrators
+    for (signature, implementation) in &implementations {
+        if ERC20_BOOL_FUNCTIONS.contains(&signature.as_str())
+            && matches!(implementation.visibility.as_str(), "external" | "public")
+            && implementation.return_types.iter().map(|t| context.canonical_type(t, 0)).collect::<Vec<_>>() != ["bool"]
+        {
+            analysis.findings.push(unit_finding(
+                unit_ref,
+                implementation.line_number,
+                format!("Non-Standard ERC20 {}", signature),
+                format!(
+                    "{} exposes `{}` without returning `bool`. Contracts calling it through the ERC20 interface revert when decoding the missing return value.",
+                    unit.name, signature
+                ),
+                "Medium",
+                VulnerabilityCategory::Other,
+                "Return `bool` as required by ERC20.",
+            ));
+        }
+    }
+}
+
+/// Facets of a diamond-based system, or nothing if no diamond is present
+fn diamond_facets<'a, 'b>(contracts: &[ParsedContract], deployable: &'b [&'a UnitRef<'a>]) -> Vec<&'b UnitRef<'a>> {
+    let is_diamond = contracts.iter().any(|contract| {
+        contract.source_code.contains("diamondCut") || contract.units.iter().any(|unit| unit.name.ends_with("Facet"))
+    });
+    if !is_diamond {
+        return Vec::new();
+    }
+
+    let named_facets: Vec<&UnitRef> = deployable
+        .iter()
+        .copied()
+        .filter(|unit_ref| unit_ref.unit.name.ends_with("Facet"))
+        .collect();
+    if !named_facets.is_empty() {
+        return named_facets;
+    }
+
+    deployable
+        .iter()
+        .copied()
+        .filter(|unit_ref| !unit_ref.unit.name.contains("Diamond"))
+        .collect()
+}
+
+/// A proxy forwards unknown selectors with delegatecall from its fallback
+fn is_proxy(unit: &ContractUnit) -> bool {
+    unit.functions.iter().any(|f| {
+        f.name == "fallback" && f.body.as_deref().is_some_and(|body| body.contains("delegatecall"))
+    }) || unit.name.ends_with("Proxy")
+}
+
+/// Getter parameters of a public state variable: mapping keys and array indices
+fn getter_parameters(type_name: &str) -> Vec<String> {
+    let mut parameters = Vec::new();
+    let mut current = type_name.trim().to_string();
+
+    loop {
+        if let Some(rest) = current.strip_prefix("mapping") {
+            let inner = rest.trim().trim_start_matches('(').trim_end_matches(')');
+            match inner.split_once("=>") {
+                Some((key, value)) => {
+                    // Named mapping keys (`mapping(address user => ...)`) keep only the type
+                    let key = key.split_whitespace().next().unwrap_or("").to_string();
+                    parameters.push(key);
+                    current = value.trim().to_string();
+                }
+                None => break,
+            }
+        } else if current.ends_with(']') {
+            parameters.push("uint256".to_string());
+            let open = current.rfind('[').unwrap_or(0);
+            current = current[..open].trim().to_string();
+        } else {
+            break;
+        }
+    }
+
+    parameters
+}
+
+fn is_sized_elementary(type_name: &str) -> bool {
+    let size = if let Some(size) = type_name.strip_prefix("uint") {
+        size
+    } else if let Some(size) = type_name.strip_prefix("int") {
+        size
+    } else if let Some(size) = type_name.strip_prefix("bytes") {
+        size
+    } else {
+        return type_name.starts_with("fixed") || type_name.starts_with("ufixed");
+    };
+    !size.is_empty() && size.chars().all(|c| c.is_ascii_digit())
+}
+
+fn selector_finding(
+    function: &ExternalFunction,
+    title: String,
+    description: String,
+    severity: &str,
+    category: VulnerabilityCategory,
+    recommendation: &str,
+) -> Vulnerability {
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title,
+        description,
+        severity: severity.to_string(),
+        category,
+        file_path: function.file.clone(),
+        line_number: Some(function.line_number),
+        code_snippet: Some(format!("{} // {}", function.signature, function.selector)),
+        recommendation: Some(recommendation.to_string()),
+        references: vec!["https://eips.ethereum.org/EIPS/eip-2535".to_string()],
+        cwe_id: None,
+        tool: TOOL_NAME.to_string(),
+        confidence: 0.8,
+        provenance: None,
+        severity_overrides: Vec::new(),
+        cvss: None,
+        detected_by: Vec::new(),
+    }
+}
+
+fn unit_finding(
+    unit_ref: &UnitRef,
+    line_number: usize,
+    title: String,
+    description: String,
+    severity: &str,
+    category: VulnerabilityCategory,
+    recommendation: &str,
+) -> Vulnerability {
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title,
+        description,
+        severity: severity.to_string(),
+        category,
+        file_path: unit_ref.file.to_string(),
+        line_number: Some(line_number),
+        code_snippet: None,
+        recommendation: Some(recommendation.to_string()),
+        references: Vec::new(),
+        cwe_id: None,
+        tool: TOOL_NAME.to_string(),
+        confidence: 0.7,
+        provenance: None,
+        severity_overrides: Vec::new(),
+        cvss: None,
+        detected_by: Vec::new(),
+    }
+}
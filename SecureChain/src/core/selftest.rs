@@ -0,0 +1,168 @@
+//! Detection self-test
+//!
+//! Runs the analyzer over a corpus of known-vulnerable contracts bundled into
+//! the binary: minimal versions of the SWC registry test cases and of the
+//! not-so-smart-contracts examples. Each case names the weakness it contains,
+//! and the run reports which were found, per category. A missing or broken
+//! tool shows up as a lower rate, so this doubles as a check of the install.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::core::analyzer::AnalysisEngine;
+use crate::core::completeness::Completeness;
+use crate::plugins::PluginManager;
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+use crate::utils::config::Config;
+use crate::utils::retention;
+
+/// A corpus contract and the weakness it contains
+pub struct Case {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub file: &'static str,
+    pub source: &'static str,
+    pub category: VulnerabilityCategory,
+    /// Title fragments a detection must have; any finding of `category` counts when empty
+    keywords: &'static [&'static str],
+}
+
+impl Case {
+    fn detects(&self, vuln: &Vulnerability) -> bool {
+        if vuln.file_path != self.file {
+            return false;
+        }
+        if self.keywords.is_empty() {
+            return vuln.category == self.category;
+        }
+        let title = vuln.title.to_lowercase();
+        self.keywords.iter().any(|keyword| title.contains(keyword))
+    }
+}
+
+macro_rules! case {
+    ($id:expr, $title:expr, $dir:literal, $file:literal, $category:ident, [$($keyword:expr),*]) => {
+        Case {
+            id: $id,
+            title: $title,
+            file: $file,
+            source: include_str!(concat!("../../corpus/", $dir, "/", $file)),
+            category: VulnerabilityCategory::$category,
+            keywords: &[$($keyword),*],
+        }
+    };
+}
+
+pub const CORPUS: &[Case] = &[
+    case!("SWC-101", "Integer Overflow and Underflow", "swc", "SWC-101_integer_overflow.sol", IntegerOverflow, []),
+    case!("SWC-103", "Floating Pragma", "swc", "SWC-103_floating_pragma.sol", CodeQuality, ["pragma"]),
+    case!("SWC-104", "Unchecked Call Return Value", "swc", "SWC-104_unchecked_call_return.sol", UnhandledExceptions, []),
+    case!("SWC-105", "Unprotected Ether Withdrawal", "swc", "SWC-105_unprotected_ether_withdrawal.sol", AccessControl, []),
+    case!("SWC-106", "Unprotected SELFDESTRUCT", "swc", "SWC-106_unprotected_selfdestruct.sol", AccessControl, ["selfdestruct", "suicid"]),
+    case!("SWC-107", "Reentrancy", "swc", "SWC-107_reentrancy.sol", Reentrancy, []),
+    case!("SWC-109", "Uninitialized Storage Pointer", "swc", "SWC-109_uninitialized_storage_pointer.sol", CodeQuality, ["uninitialized"]),
+    case!("SWC-112", "Delegatecall to Untrusted Callee", "swc", "SWC-112_delegatecall_untrusted_callee.sol", LowLevelCalls, ["delegatecall"]),
+    case!("SWC-113", "DoS with Failed Call", "swc", "SWC-113_dos_failed_call.sol", DenialOfService, []),
+    case!("SWC-115", "Authorization through tx.origin", "swc", "SWC-115_tx_origin.sol", AccessControl, ["tx.origin"]),
+    case!("SWC-116", "Block values as a proxy for time", "swc", "SWC-116_block_timestamp.sol", TimestampDependence, []),
+    case!("SWC-119", "Shadowing State Variables", "swc", "SWC-119_shadowing_state_variables.sol", CodeQuality, ["shadow"]),
+    case!("SWC-120", "Weak Sources of Randomness", "swc", "SWC-120_weak_randomness.sol", Cryptography, ["random", "prng"]),
+    case!("SWC-132", "Unexpected Ether balance", "swc", "SWC-132_unexpected_ether_balance.sol", DenialOfService, ["equality"]),
+    case!("NSSC", "Unprotected function", "not-so-smart-contracts", "unprotected_function.sol", AccessControl, []),
+    case!("NSSC", "Wrong constructor name", "not-so-smart-contracts", "wrong_constructor_name.sol", AccessControl, ["constructor"]),
+    case!("NSSC", "Race condition", "not-so-smart-contracts", "race_condition.sol", RaceCondition, []),
+    case!("NSSC", "Reentrancy", "not-so-smart-contracts", "reentrancy_dao.sol", Reentrancy, []),
+];
+
+/// Outcome of one corpus contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseResult {
+    pub id: String,
+    pub title: String,
+    pub file: String,
+    pub category: VulnerabilityCategory,
+    pub detected: bool,
+    /// Titles of the findings that detected it
+    pub findings: Vec<String>,
+}
+
+/// Detection rate of one category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryRate {
+    pub category: VulnerabilityCategory,
+    pub detected: usize,
+    pub total: usize,
+}
+
+impl CategoryRate {
+    pub fn rate(&self) -> f64 {
+        self.detected as f64 / self.total as f64 * 100.0
+    }
+}
+
+/// Outcome of a self-test run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelftestReport {
+    pub depth: String,
+    pub cases: Vec<CaseResult>,
+    pub categories: Vec<CategoryRate>,
+    /// Share of cases detected (0-100)
+    pub detection_rate: f64,
+    /// Which tools ran on the corpus, to explain misses
+    pub completeness: Option<Completeness>,
+}
+
+/// Analyse the bundled corpus at `depth` and score what was found
+pub async fn run(config: &Config, depth: &str) -> Result<SelftestReport> {
+    let corpus_dir = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).tempdir()?;
+    for case in CORPUS {
+        std::fs::write(corpus_dir.path().join(case.file), case.source)?;
+    }
+
+    let engine = AnalysisEngine::new(config.clone(), PluginManager::new());
+    let results = engine.analyze_contracts(corpus_dir.path(), "evm", depth, false).await?;
+
+    let cases: Vec<CaseResult> = CORPUS
+        .iter()
+        .map(|case| {
+            let findings: Vec<String> = results
+                .vulnerabilities
+                .iter()
+                .filter(|vuln| case.detects(vuln))
+                .map(|vuln| vuln.title.clone())
+                .collect();
+            CaseResult {
+                id: case.id.to_string(),
+                title: case.title.to_string(),
+                file: case.file.to_string(),
+                category: case.category.clone(),
+                detected: !findings.is_empty(),
+                findings,
+            }
+        })
+        .collect();
+
+    let mut categories: Vec<CategoryRate> = Vec::new();
+    for case in &cases {
+        let index = match categories.iter().position(|rate| rate.category == case.category) {
+            Some(index) => index,
+            None => {
+                categories.push(CategoryRate { category: case.category.clone(), detected: 0, total: 0 });
+                categories.len() - 1
+            }
+        };
+        categories[index].total += 1;
+        if case.detected {
+            categories[index].detected += 1;
+        }
+    }
+
+    let detected = cases.iter().filter(|case| case.detected).count();
+    Ok(SelftestReport {
+        depth: depth.to_string(),
+        detection_rate: detected as f64 / cases.len() as f64 * 100.0,
+        cases,
+        categories,
+        completeness: results.completeness,
+    })
+}
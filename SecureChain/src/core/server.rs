@@ -0,0 +1,1121 @@
+//! HTTP API server
+//!
+//! Dashboards and CI systems integrate through `securechain serve` instead
+//! of shelling out to the CLI. `POST /analyze` and `POST /probe` answer 202
+//! with a job id straight away; the job waits in the same queue as the
+//! daemon's, so a full queue answers 429. Clients then poll
+//! `GET /results/{id}`, which answers 202 with the job's status until it has
+//! finished and 200 with its results after. Sources are given either as a
+//! path the server can read or inline, as file contents keyed by relative
+//! path, for clients on other machines. Every request must carry the
+//! server's token as a bearer token, since a job can read any path the
+//! server can.
+//!
+//! Business units sharing one server are set up as tenants under
+//! `server.tenants`. Each request then carries its tenant's key instead, and
+//! sees and counts only that tenant's jobs. A tenant's finished jobs, its
+//! analysis cache, its AI spend, run history, usage metrics and AI upload log
+//! are kept in a directory of its own below `general.output_dir/tenants`, where finished jobs stay retrievable after
+//! a restart. A tenant may be limited in jobs a minute and in estimated AI
+//! spend a calendar month; an AI job runs capped at what is left of the
+//! month's budget.
+//!
+//! Recurring scans are registered with `POST /schedules`: a cron expression
+//! and a scan as `POST /analyze` takes it, usually of a git repository or a
+//! deployed address fetched afresh for each run. Each due run is queued as
+//! an ordinary job, and a schedule keeps the history of its runs. A run
+//! that fails is posted to the notification webhooks, the tenant's own when
+//! it has any. Schedules can be paused and resumed, and are kept with the
+//! tenant's storage so they survive a restart; runs due while the server was
+//! down or the schedule paused are not made up.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::Poll;
+use std::time::{Duration, Instant};
+use subtle::ConstantTimeEq;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::core::analyzer::AnalysisEngine;
+use crate::core::fetcher::{self, ContractFetcher};
+use crate::core::jobs::{JobQueue, Priority, QueueLimits, QueueStatus, ResourceClass, QUEUE_FULL_CODE};
+use crate::core::notify::{self, Notification};
+use crate::plugins::PluginManager;
+use crate::status;
+use crate::utils::artifact;
+use crate::utils::cache;
+use crate::utils::config::{Config, TenantConfig};
+use crate::utils::cron::Cron;
+use crate::utils::metrics;
+use crate::utils::retention;
+
+/// Token clients must send; a random one is generated and printed when unset
+pub const TOKEN_ENV: &str = "SECURECHAIN_API_TOKEN";
+
+/// Largest request body accepted, inline sources included
+const MAX_BODY: usize = 16 * 1024 * 1024;
+
+/// How long a client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Finished jobs kept for retrieval; the oldest are forgotten first
+const MAX_FINISHED_JOBS: usize = 1000;
+
+/// Tenant of a server without `server.tenants`, whose token is the server's
+const DEFAULT_TENANT: &str = "default";
+
+/// Directory below `general.output_dir` holding each tenant's storage
+const TENANTS_DIR: &str = "tenants";
+
+/// Window a tenant's rate limit counts submissions over
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Runs a schedule remembers; the oldest are forgotten first
+const MAX_SCHEDULE_RUNS: usize = 100;
+
+/// What a job runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    Analyze,
+    Probe,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// A submitted job, as `GET /results/{id}` returns it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub tenant: String,
+    pub kind: JobKind,
+    /// Schedule the job is a run of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    pub status: JobStatus,
+    pub submitted_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Analysis results, or the probes of a probe job
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+}
+
+impl Job {
+    fn is_finished(&self) -> bool {
+        matches!(self.status, JobStatus::Completed | JobStatus::Failed)
+    }
+}
+
+/// Body of `POST /analyze` and `POST /probe`, and the scan of a schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Submission {
+    /// File or directory on the server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    input: Option<PathBuf>,
+    /// Source files by relative path, used when no input is given
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    sources: HashMap<String, String>,
+    /// Git repository cloned when the job starts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    repo: Option<String>,
+    /// Deployed contract whose verified sources are fetched when the job starts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    /// Network `address` is deployed on
+    #[serde(default = "default_network")]
+    network: String,
+    #[serde(default = "default_target")]
+    target: String,
+    #[serde(default = "default_depth")]
+    depth: String,
+    #[serde(default)]
+    ai: bool,
+    /// Probe creativity: low, medium or high
+    #[serde(default = "default_creativity")]
+    creativity: String,
+    /// Include proof-of-concept code with each probe
+    #[serde(default)]
+    #[cfg_attr(not(feature = "ai"), allow(dead_code))]
+    poc: bool,
+    #[serde(default)]
+    priority: Priority,
+}
+
+fn default_network() -> String {
+    "ethereum".to_string()
+}
+
+fn default_target() -> String {
+    "evm".to_string()
+}
+
+fn default_depth() -> String {
+    "standard".to_string()
+}
+
+fn default_creativity() -> String {
+    "medium".to_string()
+}
+
+/// Body of `POST /schedules`
+#[derive(Debug, Deserialize)]
+struct ScheduleRequest {
+    cron: String,
+    #[serde(default)]
+    name: String,
+    #[serde(flatten)]
+    scan: Submission,
+}
+
+/// A recurring scan, as `GET /schedules/{id}` returns it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Schedule {
+    id: String,
+    #[serde(default)]
+    name: String,
+    cron: String,
+    #[serde(flatten)]
+    scan: Submission,
+    #[serde(default)]
+    paused: bool,
+    created_at: DateTime<Utc>,
+    /// Oldest first
+    #[serde(default)]
+    runs: Vec<ScheduledRun>,
+}
+
+/// One run of a schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ScheduledRun {
+    /// Job the run was queued as; none when it could not be queued
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    job: Option<String>,
+    started_at: DateTime<Utc>,
+    status: JobStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    finished_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// Findings of a completed run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    findings: Option<usize>,
+}
+
+impl Schedule {
+    fn label(&self) -> &str {
+        if self.name.is_empty() {
+            &self.id
+        } else {
+            &self.name
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.runs.last().is_some_and(|run| matches!(run.status, JobStatus::Queued | JobStatus::Running))
+    }
+
+    fn record(&mut self, run: ScheduledRun) {
+        self.runs.push(run);
+        if self.runs.len() > MAX_SCHEDULE_RUNS {
+            self.runs.drain(..self.runs.len() - MAX_SCHEDULE_RUNS);
+        }
+    }
+
+    /// The schedule with when it runs next, and its runs or only the last one
+    fn to_json(&self, with_runs: bool) -> Value {
+        let next_run = if self.paused { None } else { self.cron.parse::<Cron>().ok().and_then(|cron| cron.next_after(Utc::now())) };
+        let mut value = json!(self);
+        value["next_run"] = json!(next_run);
+        if !with_runs {
+            if let Some(schedule) = value.as_object_mut() {
+                schedule.remove("runs");
+            }
+            value["last_run"] = json!(self.runs.last());
+        }
+        value
+    }
+}
+
+/// A parsed HTTP request
+struct HttpRequest {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+/// State shared by the connections and jobs of a running server
+struct Server {
+    queue: Arc<JobQueue>,
+    jobs: Mutex<HashMap<String, Job>>,
+    tenants: Vec<Tenant>,
+    config: Config,
+}
+
+/// A client of the server, with its own key, storage and limits
+struct Tenant {
+    name: String,
+    key: String,
+    engine: AnalysisEngine,
+    /// The server's configuration, with the output and cache directories in the tenant's storage
+    config: Config,
+    /// Where finished jobs and the AI spend are kept; none without `server.tenants`
+    storage: Option<PathBuf>,
+    rate_limit: u32,
+    ai_budget: f64,
+    /// When the jobs of the last minute were submitted
+    submissions: Mutex<VecDeque<Instant>>,
+    /// Held while the AI spend is updated
+    spend: Mutex<()>,
+    schedules: Mutex<Vec<Schedule>>,
+    schedules_path: Option<PathBuf>,
+}
+
+/// Estimated AI spend of a tenant in a calendar month
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AiSpend {
+    /// `YYYY-MM`
+    month: String,
+    cost: f64,
+}
+
+/// Run the API server in the foreground until interrupted
+pub async fn serve(config: Config, host: &str, port: u16) -> Result<()> {
+    let listener = TcpListener::bind((host, port)).await?;
+    let mut generated = None;
+    let tenants = if config.server.tenants.is_empty() {
+        let token = match std::env::var(TOKEN_ENV) {
+            Ok(token) if !token.is_empty() => token,
+            _ => generated.insert(uuid::Uuid::new_v4().to_string()).clone(),
+        };
+        vec![Tenant::new(DEFAULT_TENANT, token, config.clone(), None, &TenantConfig::default())?]
+    } else {
+        tenants(&config)?
+    };
+
+    let limits = QueueLimits::from_config(&config);
+    let server = Arc::new(Server { queue: JobQueue::new(limits), jobs: Mutex::new(HashMap::new()), tenants, config });
+    status!("🌐 SecureChain API listening on http://{}", listener.local_addr()?);
+    status!(
+        "   {} workers ({} for deep audits), up to {} queued jobs",
+        limits.workers,
+        limits.deep_workers,
+        limits.max_queued
+    );
+    match generated {
+        Some(token) => status!("   Token: {} (set {} to choose it)", token, TOKEN_ENV),
+        None if server.config.server.tenants.is_empty() => {}
+        None => status!(
+            "   Tenants: {}",
+            server.tenants.iter().map(|tenant| tenant.name.as_str()).collect::<Vec<_>>().join(", ")
+        ),
+    }
+    status!("   POST /analyze, POST /probe, GET /results/{{id}}, GET /status with `Authorization: Bearer <token>`");
+    let schedules: usize = server.tenants.iter().map(|tenant| tenant.schedules().len()).sum();
+    status!("   POST /schedules, GET /schedules, POST /schedules/{{id}}/pause|resume; {} registered", schedules);
+
+    tokio::spawn(run_schedules(Arc::clone(&server)));
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    log::warn!("API server failed to accept a connection: {}", e);
+                    continue;
+                }
+            },
+            _ = tokio::signal::ctrl_c() => break,
+        };
+
+        let server = Arc::clone(&server);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &server).await {
+                log::warn!("API request failed: {}", e);
+            }
+        });
+    }
+
+    status!("🌐 SecureChain API stopped; unfinished jobs were abandoned");
+    Ok(())
+}
+
+async fn handle_connection(stream: TcpStream, server: &Arc<Server>) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let (code, body) = match tokio::time::timeout(READ_TIMEOUT, read_request(BufReader::new(reader))).await {
+        Err(_) => (408, error_body("request not received in time")),
+        Ok(Err(e)) => (400, error_body(&e.to_string())),
+        Ok(Ok(request)) => route(server, request).await,
+    };
+
+    let body = serde_json::to_string(&body)?;
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code,
+        reason(code),
+        body.len(),
+        body
+    );
+    writer.write_all(response.as_bytes()).await?;
+    writer.shutdown().await?;
+    Ok(())
+}
+
+async fn route(server: &Arc<Server>, request: HttpRequest) -> (u16, Value) {
+    // The key says which tenant is asking, and every query below is limited to its jobs
+    let tenant = request
+        .headers
+        .get("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .and_then(|token| server.tenants.iter().position(|tenant| same_key(&tenant.key, token.trim())));
+    let tenant = match tenant {
+        Some(tenant) => tenant,
+        None => return (401, error_body("missing or invalid bearer token")),
+    };
+
+    let path = request.path.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("POST", ["analyze"]) => submit(server, tenant, JobKind::Analyze, &request.body).await,
+        ("POST", ["probe"]) => submit(server, tenant, JobKind::Probe, &request.body).await,
+        ("GET", ["results", id]) => match server.job(&server.tenants[tenant], id) {
+            Some(job) if job.is_finished() => (200, json!(job)),
+            Some(job) => (202, json!(job)),
+            None => (404, error_body(&format!("no job {}", id))),
+        },
+        ("POST", ["schedules"]) => create_schedule(server, tenant, &request.body),
+        ("GET", ["schedules"]) => {
+            let schedules = server.tenants[tenant].schedules();
+            (200, json!({ "schedules": schedules.iter().map(|schedule| schedule.to_json(false)).collect::<Vec<_>>() }))
+        }
+        ("GET", ["schedules", id]) => match server.tenants[tenant].schedules().iter().find(|schedule| schedule.id == *id) {
+            Some(schedule) => (200, schedule.to_json(true)),
+            None => (404, error_body(&format!("no schedule {}", id))),
+        },
+        ("POST", ["schedules", id, action @ ("pause" | "resume")]) => {
+            let paused = *action == "pause";
+            match server.tenants[tenant].update_schedule(id, |schedule| schedule.paused = paused) {
+                Some(schedule) => (200, schedule.to_json(false)),
+                None => (404, error_body(&format!("no schedule {}", id))),
+            }
+        }
+        ("DELETE", ["schedules", id]) => {
+            let tenant = &server.tenants[tenant];
+            let mut schedules = tenant.schedules();
+            match schedules.iter().position(|schedule| schedule.id == *id) {
+                Some(index) => {
+                    let schedule = schedules.remove(index);
+                    tenant.save_schedules(&schedules);
+                    (200, schedule.to_json(false))
+                }
+                None => (404, error_body(&format!("no schedule {}", id))),
+            }
+        }
+        ("GET", ["status"]) => {
+            let tenant = &server.tenants[tenant];
+            let jobs = server.jobs();
+            let count = |status: JobStatus| {
+                jobs.values().filter(|job| job.tenant == tenant.name && job.status == status).count()
+            };
+            let queue: QueueStatus = server.queue.status();
+            let mut body = json!({
+                "tenant": tenant.name,
+                "queue": queue,
+                "limits": server.queue.limits(),
+                "jobs": {
+                    "queued": count(JobStatus::Queued),
+                    "running": count(JobStatus::Running),
+                    "completed": count(JobStatus::Completed),
+                    "failed": count(JobStatus::Failed),
+                },
+                "cached_contracts": tenant.engine.cached_contracts(),
+            });
+            if tenant.ai_budget > 0.0 {
+                body["ai_budget"] = json!({ "budget": tenant.ai_budget, "spent": tenant.ai_spent() });
+            }
+            (200, body)
+        }
+        (_, ["analyze"] | ["probe"] | ["results", _] | ["status"] | ["schedules"] | ["schedules", _] | ["schedules", _, "pause" | "resume"]) => {
+            (405, error_body(&format!("{} not allowed here", request.method)))
+        }
+        _ => (404, error_body(&format!("no endpoint {}", path))),
+    }
+}
+
+/// Queue a job and answer with its id, or why it was not accepted
+async fn submit(server: &Arc<Server>, tenant: usize, kind: JobKind, body: &[u8]) -> (u16, Value) {
+    let submission: Submission = match serde_json::from_slice(body) {
+        Ok(submission) => submission,
+        Err(e) => return (400, error_body(&format!("invalid request body: {}", e))),
+    };
+    if let Err(e) = validate(kind, &submission, &server.config) {
+        let code = if kind == JobKind::Probe && !cfg!(feature = "ai") { 501 } else { 400 };
+        return (code, error_body(&e.to_string()));
+    }
+    let owner = &server.tenants[tenant];
+    if let Some(input) = &submission.input {
+        if server.in_other_storage(owner, input) {
+            return (403, error_body(&format!("{} belongs to another tenant", input.display())));
+        }
+    }
+    if let Err(e) = owner.admit() {
+        return (429, error_body(&e.to_string()));
+    }
+    if kind == JobKind::Probe || submission.ai {
+        if let Some(remaining) = owner.ai_remaining().filter(|remaining| *remaining <= 0.0) {
+            return (429, error_body(&ai_budget_spent(owner, remaining)));
+        }
+    }
+    enqueue(server, tenant, kind, submission, None).await
+}
+
+/// Queue a validated job, as a run of `schedule` if given
+async fn enqueue(server: &Arc<Server>, tenant: usize, kind: JobKind, submission: Submission, schedule: Option<String>) -> (u16, Value) {
+    // Inline sources are written out now, so a bad file name fails the request rather than the job
+    let scratch = match stage_sources(&submission) {
+        Ok(scratch) => scratch,
+        Err(e) => return (400, error_body(&e.to_string())),
+    };
+
+    let class = match kind {
+        JobKind::Analyze => ResourceClass::for_depth(&submission.depth),
+        JobKind::Probe => ResourceClass::Quick,
+    };
+    let queue = Arc::clone(&server.queue);
+    let priority = submission.priority;
+    let mut acquire = Box::pin(async move { queue.acquire(class, priority).await });
+    // A full queue rejects the job at once, so the client hears about it before getting an id
+    let permit = match futures::poll!(acquire.as_mut()) {
+        Poll::Ready(Err(full)) => {
+            return (
+                QUEUE_FULL_CODE,
+                json!({ "error": format!("Server is busy: {}", full), "code": QUEUE_FULL_CODE }),
+            )
+        }
+        Poll::Ready(Ok(permit)) => Some(permit),
+        Poll::Pending => None,
+    };
+
+    let job = Job {
+        id: uuid::Uuid::new_v4().to_string(),
+        tenant: server.tenants[tenant].name.clone(),
+        kind,
+        schedule,
+        status: JobStatus::Queued,
+        submitted_at: Utc::now(),
+        started_at: None,
+        finished_at: None,
+        error: None,
+        result: None,
+    };
+    let id = job.id.clone();
+    server.insert(job.clone());
+    if let Some(schedule) = &job.schedule {
+        let run = ScheduledRun {
+            job: Some(id.clone()),
+            started_at: job.submitted_at,
+            status: JobStatus::Queued,
+            finished_at: None,
+            error: None,
+            findings: None,
+        };
+        server.tenants[tenant].update_schedule(schedule, |schedule| schedule.record(run));
+    }
+
+    let server = Arc::clone(server);
+    tokio::spawn(async move {
+        let _permit = match permit {
+            Some(permit) => permit,
+            None => match acquire.await {
+                Ok(permit) => permit,
+                Err(full) => {
+                    server.finish(&id, Err(anyhow!("Server is busy: {}", full)));
+                    return;
+                }
+            },
+        };
+        server.update(&id, |job| {
+            job.status = JobStatus::Running;
+            job.started_at = Some(Utc::now());
+        });
+        let fetched = match fetch_sources(&server.tenants[tenant].config, &submission).await {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                server.finish(&id, Err(e));
+                return;
+            }
+        };
+        let input = match scratch.as_ref().or(fetched.as_ref()) {
+            Some(dir) => dir.path().to_path_buf(),
+            None => submission.input.clone().unwrap_or_default(),
+        };
+        status!("📥 {} job {} on {}", if kind == JobKind::Analyze { "Analysis" } else { "Probe" }, id, input.display());
+
+        let started = std::time::Instant::now();
+        let outcome = run(&server.tenants[tenant], kind, &submission, &input).await;
+        if server.config.general.metrics_enabled {
+            let name = if kind == JobKind::Analyze { "serve analyze" } else { "serve probe" };
+            let path = metrics::metrics_path(&server.tenants[tenant].config);
+            if let Err(e) = metrics::record(&path, name, started.elapsed(), outcome.is_ok()) {
+                log::warn!("Could not update usage metrics: {}", e);
+            }
+        }
+        server.finish(&id, outcome);
+    });
+
+    (202, json!({ "id": job.id, "status": job.status, "results": format!("/results/{}", job.id) }))
+}
+
+fn validate(kind: JobKind, submission: &Submission, config: &Config) -> Result<()> {
+    let given = [
+        submission.input.is_some(),
+        !submission.sources.is_empty(),
+        submission.repo.is_some(),
+        submission.address.is_some(),
+    ];
+    match given.iter().filter(|given| **given).count() {
+        0 => return Err(anyhow!("give the input path, the sources, a repo or an address to analyse")),
+        1 => {}
+        _ => return Err(anyhow!("give only one of input, sources, repo and address")),
+    }
+    if let Some(input) = submission.input.as_ref().filter(|input| !input.exists()) {
+        return Err(anyhow!("{} does not exist on the server", input.display()));
+    }
+    // Local paths and options would let a client clone what it could not read as input
+    if let Some(repo) = &submission.repo {
+        if !["https://", "ssh://", "git@"].iter().any(|scheme| repo.starts_with(scheme)) {
+            return Err(anyhow!("Invalid repo: {} (expected an https://, ssh:// or git@ URL)", repo));
+        }
+    }
+    if submission.address.is_some() && config.networks.evm_network(&submission.network).is_none() {
+        return Err(anyhow!("Unknown network: {}", submission.network));
+    }
+    match kind {
+        JobKind::Analyze => {
+            if !["quick", "standard", "deep"].contains(&submission.depth.as_str()) {
+                return Err(anyhow!("Invalid depth: {} (expected quick, standard or deep)", submission.depth));
+            }
+            if !["evm", "solana", "move", "cairo", "ink"].contains(&submission.target.as_str()) {
+                return Err(anyhow!("Invalid target: {}", submission.target));
+            }
+        }
+        JobKind::Probe => {
+            if !cfg!(feature = "ai") {
+                return Err(anyhow!("Creative probes need a build with the `ai` feature"));
+            }
+            if !["low", "medium", "high"].contains(&submission.creativity.as_str()) {
+                return Err(anyhow!("Invalid creativity: {} (expected low, medium or high)", submission.creativity));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write inline sources to a scratch directory, kept until the job finishes
+fn stage_sources(submission: &Submission) -> Result<Option<tempfile::TempDir>> {
+    if submission.sources.is_empty() {
+        return Ok(None);
+    }
+    let dir = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).tempdir()?;
+    for (name, content) in &submission.sources {
+        let relative = Path::new(name);
+        if !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+            return Err(anyhow!("Invalid source name: {} (use a relative path without ..)", name));
+        }
+        let path = dir.path().join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        artifact::write_atomic(path, content)?;
+    }
+    Ok(Some(dir))
+}
+
+/// Clone the submission's repo, or fetch the verified sources of its address, into a scratch directory
+async fn fetch_sources(config: &Config, submission: &Submission) -> Result<Option<tempfile::TempDir>> {
+    if let Some(repo) = &submission.repo {
+        let dir = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).tempdir()?;
+        let output = tokio::process::Command::new("git")
+            .args(["clone", "--depth", "1", "--quiet", "--", repo])
+            .arg(dir.path())
+            .output()
+            .await
+            .map_err(|e| anyhow!("Could not run git to clone {}: {}", repo, e))?;
+        if !output.status.success() {
+            return Err(anyhow!("Could not clone {}: {}", repo, String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        return Ok(Some(dir));
+    }
+    if let Some(address) = &submission.address {
+        let contracts = ContractFetcher::new(config.clone()).fetch_contracts(&submission.network, address, None).await?;
+        if contracts.is_empty() {
+            return Err(anyhow!("{} has no verified source on {}", address, submission.network));
+        }
+        let dir = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).tempdir()?;
+        fetcher::persist_sources(dir.path(), address, &contracts)?;
+        return Ok(Some(dir));
+    }
+    Ok(None)
+}
+
+/// Register a recurring scan
+fn create_schedule(server: &Arc<Server>, tenant: usize, body: &[u8]) -> (u16, Value) {
+    let request: ScheduleRequest = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(e) => return (400, error_body(&format!("invalid request body: {}", e))),
+    };
+    let checked = request
+        .cron
+        .parse::<Cron>()
+        .map(|_| ())
+        .and_then(|()| validate(JobKind::Analyze, &request.scan, &server.config));
+    if let Err(e) = checked {
+        return (400, error_body(&e.to_string()));
+    }
+    let owner = &server.tenants[tenant];
+    if let Some(input) = &request.scan.input {
+        if server.in_other_storage(owner, input) {
+            return (403, error_body(&format!("{} belongs to another tenant", input.display())));
+        }
+    }
+
+    let schedule = Schedule {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: request.name,
+        cron: request.cron,
+        scan: request.scan,
+        paused: false,
+        created_at: Utc::now(),
+        runs: Vec::new(),
+    };
+    let mut schedules = owner.schedules();
+    schedules.push(schedule.clone());
+    owner.save_schedules(&schedules);
+    status!("🗓️  Schedule {} ({}) registered", schedule.label(), schedule.cron);
+    (201, schedule.to_json(false))
+}
+
+/// Queue the runs of every schedule as they fall due, at the start of each minute
+async fn run_schedules(server: Arc<Server>) {
+    loop {
+        let wait = 60 - Utc::now().timestamp().rem_euclid(60) as u64;
+        tokio::time::sleep(Duration::from_secs(wait)).await;
+        let now = Utc::now();
+
+        for (index, tenant) in server.tenants.iter().enumerate() {
+            let due: Vec<Schedule> = tenant
+                .schedules()
+                .iter()
+                .filter(|schedule| !schedule.paused)
+                .filter(|schedule| schedule.cron.parse::<Cron>().is_ok_and(|cron| cron.matches(&now)))
+                .cloned()
+                .collect();
+            for schedule in due {
+                // Runs do not pile up behind one that is still going
+                if schedule.is_running() {
+                    status!("🗓️  Schedule {} skipped: its previous run has not finished", schedule.label());
+                    continue;
+                }
+                let (code, body) = enqueue(&server, index, JobKind::Analyze, schedule.scan.clone(), Some(schedule.id.clone())).await;
+                if code != 202 {
+                    let run = ScheduledRun {
+                        job: None,
+                        started_at: now,
+                        status: JobStatus::Failed,
+                        finished_at: Some(now),
+                        error: body["error"].as_str().map(str::to_string),
+                        findings: None,
+                    };
+                    tenant.update_schedule(&schedule.id, |schedule| schedule.record(run.clone()));
+                    alert(tenant, &schedule, &run);
+                }
+            }
+        }
+    }
+}
+
+/// Tell the tenant's webhooks that a run of `schedule` failed
+fn alert(tenant: &Tenant, schedule: &Schedule, run: &ScheduledRun) {
+    let error = run.error.as_deref().unwrap_or("unknown error");
+    let notification = Notification::new(
+        "scheduled_scan_failed",
+        format!("⚠️ Scheduled scan {} failed: {}", schedule.label(), error),
+        json!({
+            "tenant": tenant.name,
+            "schedule": schedule.id,
+            "name": schedule.name,
+            "cron": schedule.cron,
+            "job": run.job,
+            "error": error,
+        }),
+    );
+    let config = tenant.config.notifications.clone();
+    tokio::task::spawn_blocking(move || notify::send(&config, &notification));
+}
+
+async fn run(tenant: &Tenant, kind: JobKind, submission: &Submission, input: &Path) -> Result<Value> {
+    // An AI job of a tenant with a budget gets an engine of its own, capped at what is left of it
+    let budgeted = match tenant.ai_remaining() {
+        Some(remaining) if kind == JobKind::Probe || submission.ai => {
+            if remaining <= 0.0 {
+                return Err(anyhow!(ai_budget_spent(tenant, remaining)));
+            }
+            let mut config = tenant.config.clone();
+            config.ai.max_cost = if config.ai.max_cost > 0.0 { config.ai.max_cost.min(remaining) } else { remaining };
+            Some(AnalysisEngine::new(config, PluginManager::new()))
+        }
+        _ => None,
+    };
+    let engine = budgeted.as_ref().unwrap_or(&tenant.engine);
+
+    let outcome = match kind {
+        JobKind::Analyze => engine
+            .analyze_contracts(input, &submission.target, &submission.depth, submission.ai)
+            .await
+            .and_then(|results| Ok(serde_json::to_value(results)?)),
+        #[cfg(feature = "ai")]
+        JobKind::Probe => engine
+            .generate_creative_probes(input, &submission.creativity, &tenant.config.ai.backend, submission.poc)
+            .await
+            .and_then(|probes| Ok(serde_json::to_value(probes)?)),
+        #[cfg(not(feature = "ai"))]
+        JobKind::Probe => Err(anyhow!("Creative probes need a build with the `ai` feature")),
+    };
+    // Failed jobs may have spent too
+    if let Some(usage) = budgeted.as_ref().and_then(AnalysisEngine::ai_usage) {
+        tenant.add_ai_spend(usage.cost);
+    }
+    outcome
+}
+
+fn ai_budget_spent(tenant: &Tenant, remaining: f64) -> String {
+    format!(
+        "AI budget of ${:.2} this month is spent (${:.2} left); jobs without AI can still run",
+        tenant.ai_budget,
+        remaining.max(0.0)
+    )
+}
+
+/// Whether `token` is `key`, in time that does not depend on where they differ
+fn same_key(key: &str, token: &str) -> bool {
+    bool::from(key.as_bytes().ct_eq(token.as_bytes()))
+}
+
+/// The tenants of `server.tenants`, with their storage created and schedules loaded
+fn tenants(config: &Config) -> Result<Vec<Tenant>> {
+    let root = cache::expand_home(&config.general.output_dir).join(TENANTS_DIR);
+    let mut tenants: Vec<Tenant> = Vec::new();
+    for (name, settings) in &config.server.tenants {
+        let key = std::env::var(&settings.key_env).ok().filter(|key| !key.is_empty()).ok_or_else(|| {
+            anyhow!("Tenant {} has no key: set server.tenants.{}.key_env to an environment variable holding it", name, name)
+        })?;
+        let prefix = if settings.storage_prefix.is_empty() { name } else { &settings.storage_prefix };
+        let mut components = Path::new(prefix).components();
+        if !matches!((components.next(), components.next()), (Some(Component::Normal(_)), None)) {
+            return Err(anyhow!("Invalid storage prefix of tenant {}: {} (use a single directory name)", name, prefix));
+        }
+        let storage = root.join(prefix);
+        if let Some(other) = tenants.iter().find(|tenant| tenant.key == key || tenant.storage.as_ref() == Some(&storage)) {
+            return Err(anyhow!("Tenants {} and {} share a key or a storage prefix", other.name, name));
+        }
+        std::fs::create_dir_all(storage.join("runs"))?;
+
+        let mut tenant_config = config.clone();
+        tenant_config.general.output_dir = storage.clone();
+        tenant_config.general.cache_dir = storage.join("cache");
+        tenant_config.general.data_dir = storage.clone();
+        if !settings.webhooks.is_empty() {
+            tenant_config.notifications.webhooks = settings.webhooks.clone();
+        }
+        tenants.push(Tenant::new(name, key, tenant_config, Some(storage), settings)?);
+    }
+    Ok(tenants)
+}
+
+impl Tenant {
+    fn new(name: &str, key: String, config: Config, storage: Option<PathBuf>, settings: &TenantConfig) -> Result<Self> {
+        let schedules_path = match &storage {
+            Some(storage) => Some(storage.join("schedules.json")),
+            None => dirs::home_dir().map(|home| home.join(".config/bugforgex/schedules.json")),
+        };
+        let schedules = match &schedules_path {
+            Some(path) if path.exists() => artifact::read_json(path, "schedules file")?,
+            _ => Vec::new(),
+        };
+        Ok(Self {
+            name: name.to_string(),
+            key,
+            engine: AnalysisEngine::new(config.clone(), PluginManager::new()).with_contract_cache(),
+            config,
+            storage,
+            rate_limit: settings.rate_limit,
+            ai_budget: settings.ai_budget,
+            submissions: Mutex::new(VecDeque::new()),
+            spend: Mutex::new(()),
+            schedules: Mutex::new(schedules),
+            schedules_path,
+        })
+    }
+
+    fn schedules(&self) -> MutexGuard<'_, Vec<Schedule>> {
+        self.schedules.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Change schedule `id` and keep it; the schedule after, if there is one
+    fn update_schedule(&self, id: &str, change: impl FnOnce(&mut Schedule)) -> Option<Schedule> {
+        let mut schedules = self.schedules();
+        let schedule = schedules.iter_mut().find(|schedule| schedule.id == id)?;
+        change(schedule);
+        let schedule = schedule.clone();
+        self.save_schedules(&schedules);
+        Some(schedule)
+    }
+
+    fn save_schedules(&self, schedules: &[Schedule]) {
+        let path = match &self.schedules_path {
+            Some(path) => path,
+            None => return,
+        };
+        let written = match path.parent() {
+            Some(dir) => std::fs::create_dir_all(dir).map_err(anyhow::Error::from).and_then(|()| write_json(path, &schedules)),
+            None => write_json(path, &schedules),
+        };
+        if let Err(e) = written {
+            log::warn!("Could not save the schedules of tenant {}: {}", self.name, e);
+        }
+    }
+
+    /// Count a submission against the rate limit, or say why it is refused
+    fn admit(&self) -> Result<()> {
+        if self.rate_limit == 0 {
+            return Ok(());
+        }
+        let mut submissions = self.submissions.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let now = Instant::now();
+        while submissions.front().is_some_and(|submitted| now.duration_since(*submitted) >= RATE_WINDOW) {
+            submissions.pop_front();
+        }
+        if submissions.len() >= self.rate_limit as usize {
+            return Err(anyhow!("Tenant {} is over its rate limit of {} jobs a minute", self.name, self.rate_limit));
+        }
+        submissions.push_back(now);
+        Ok(())
+    }
+
+    /// What is left of this month's AI budget, `None` when it is unlimited
+    fn ai_remaining(&self) -> Option<f64> {
+        Some(self.ai_budget).filter(|budget| *budget > 0.0).map(|budget| budget - self.ai_spent())
+    }
+
+    /// Estimated AI spend of this month
+    fn ai_spent(&self) -> f64 {
+        match self.spend_path().and_then(|path| artifact::read_json::<AiSpend>(&path, "AI spend file").ok()) {
+            Some(spend) if spend.month == Utc::now().format("%Y-%m").to_string() => spend.cost,
+            _ => 0.0,
+        }
+    }
+
+    fn add_ai_spend(&self, cost: f64) {
+        let path = match self.spend_path() {
+            Some(path) if cost > 0.0 => path,
+            _ => return,
+        };
+        let _guard = self.spend.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let spend = AiSpend { month: Utc::now().format("%Y-%m").to_string(), cost: self.ai_spent() + cost };
+        if let Err(e) = write_json(&path, &spend) {
+            log::warn!("Could not record the AI spend of tenant {}: {}", self.name, e);
+        }
+    }
+
+    fn spend_path(&self) -> Option<PathBuf> {
+        self.storage.as_ref().map(|storage| storage.join("ai_spend.json"))
+    }
+
+    /// Where a finished job of this tenant is kept, if it keeps them
+    fn job_path(&self, id: &str) -> Option<PathBuf> {
+        // Ids are uuids; anything else could point outside the storage
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+            return None;
+        }
+        self.storage.as_ref().map(|storage| storage.join("runs").join(format!("{}.json", id)))
+    }
+}
+
+impl Server {
+    /// Job `id` if it belongs to `tenant`, from memory or from the tenant's storage
+    fn job(&self, tenant: &Tenant, id: &str) -> Option<Job> {
+        if let Some(job) = self.jobs().get(id) {
+            return Some(job.clone()).filter(|job| job.tenant == tenant.name);
+        }
+        let path = tenant.job_path(id).filter(|path| path.exists())?;
+        artifact::read_json(&path, "job file").ok()
+    }
+
+    /// Whether `input` lies in the storage of a tenant other than `tenant`
+    fn in_other_storage(&self, tenant: &Tenant, input: &Path) -> bool {
+        let input = match input.canonicalize() {
+            Ok(input) => input,
+            Err(_) => return false,
+        };
+        self.tenants
+            .iter()
+            .filter(|other| other.name != tenant.name)
+            .filter_map(|other| other.storage.as_ref()?.canonicalize().ok())
+            .any(|storage| input.starts_with(storage))
+    }
+
+    fn jobs(&self) -> MutexGuard<'_, HashMap<String, Job>> {
+        self.jobs.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn insert(&self, job: Job) {
+        let mut jobs = self.jobs();
+        jobs.insert(job.id.clone(), job);
+        let mut finished: Vec<(DateTime<Utc>, String)> = jobs
+            .values()
+            .filter(|job| job.is_finished())
+            .map(|job| (job.finished_at.unwrap_or(job.submitted_at), job.id.clone()))
+            .collect();
+        if finished.len() > MAX_FINISHED_JOBS {
+            finished.sort();
+            for (_, id) in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+                jobs.remove(id);
+            }
+        }
+    }
+
+    fn update(&self, id: &str, change: impl FnOnce(&mut Job)) {
+        if let Some(job) = self.jobs().get_mut(id) {
+            change(job);
+        }
+    }
+
+    fn finish(&self, id: &str, outcome: Result<Value>) {
+        self.update(id, |job| {
+            job.finished_at = Some(Utc::now());
+            match outcome {
+                Ok(result) => {
+                    job.status = JobStatus::Completed;
+                    job.result = Some(result);
+                }
+                Err(e) => {
+                    job.status = JobStatus::Failed;
+                    job.error = Some(e.to_string());
+                }
+            }
+        });
+
+        // Tenants keep their finished jobs, so they can be retrieved after a restart
+        let job = match self.jobs().get(id) {
+            Some(job) => job.clone(),
+            None => return,
+        };
+        let tenant = match self.tenants.iter().find(|tenant| tenant.name == job.tenant) {
+            Some(tenant) => tenant,
+            None => return,
+        };
+        if let Some(path) = tenant.job_path(id) {
+            if let Err(e) = write_json(&path, &job) {
+                log::warn!("Could not keep job {} of tenant {}: {}", id, job.tenant, e);
+            }
+        }
+
+        if let Some(schedule) = &job.schedule {
+            let findings = job.result.as_ref().and_then(|result| result["vulnerabilities"].as_array()).map(Vec::len);
+            let run = ScheduledRun {
+                job: Some(job.id.clone()),
+                started_at: job.started_at.unwrap_or(job.submitted_at),
+                status: job.status,
+                finished_at: job.finished_at,
+                error: job.error.clone(),
+                findings,
+            };
+            let updated = tenant.update_schedule(schedule, |schedule| {
+                match schedule.runs.iter_mut().rev().find(|recorded| recorded.job.as_deref() == Some(id)) {
+                    Some(recorded) => *recorded = run.clone(),
+                    None => schedule.record(run.clone()),
+                }
+            });
+            if let Some(schedule) = updated.filter(|_| job.status == JobStatus::Failed) {
+                alert(tenant, &schedule, &run);
+            }
+        }
+    }
+}
+
+async fn read_request(mut reader: BufReader<tokio::net::tcp::OwnedReadHalf>) -> Result<HttpRequest> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await?;
+    let mut parts = line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Err(anyhow!("malformed request line")),
+    };
+
+    let mut headers = HashMap::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Err(anyhow!("connection closed inside the headers"));
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length: usize = match headers.get("content-length") {
+        Some(length) => length.parse().map_err(|_| anyhow!("invalid Content-Length"))?,
+        None => 0,
+    };
+    if length > MAX_BODY {
+        return Err(anyhow!("request body over {} bytes", MAX_BODY));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+    Ok(HttpRequest { method, path, headers, body })
+}
+
+fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+    artifact::write_atomic(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+fn error_body(message: &str) -> Value {
+    json!({ "error": message })
+}
+
+fn reason(code: u16) -> &'static str {
+    match code {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        429 => "Too Many Requests",
+        501 => "Not Implemented",
+        _ => "Internal Server Error",
+    }
+}
@@ -0,0 +1,356 @@
+//! Fork-based dynamic analysis on Anvil
+//!
+//! Some findings depend on state rather than code: who the owner actually
+//! is, what an oracle currently answers, whether an initializer was ever
+//! called. With `analyze --simulate`, an Anvil node is started, forking the
+//! chain at `--fork-url` (and `--fork-block`) when given, and each contract
+//! is either deployed on it or, with `--deployed-at`, taken at its live
+//! address. Transactions given with `--replay` are re-sent from their
+//! original senders first, so the checks see the state an incident left.
+//! Plugins then run their state-dependent checks through a
+//! [`SimulationContext`], and every contract's checks start from the same
+//! state, as it is snapshotted before and reverted after them.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::core::parser::ParsedContract;
+use crate::core::rpc::RpcClient;
+use crate::core::selectors;
+use crate::plugins::PluginManager;
+use crate::report::vulnerability::Vulnerability;
+use crate::utils::abi::{decode_hex, encode_hex, read_address};
+use crate::utils::config::{AnvilConfig, SolcConfig, ToolsConfig};
+use crate::utils::error::ToolError;
+use crate::utils::platform;
+use crate::utils::retention;
+
+/// Name of the stage in completeness records and on its findings
+pub const TOOL_NAME: &str = "Simulation";
+
+/// How often a starting node is polled until it answers
+const STARTUP_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Timeout of a single request to the local node (seconds)
+const RPC_TIMEOUT: u64 = 60;
+
+/// What `analyze --simulate` was asked to run against
+#[derive(Debug, Clone, Default)]
+pub struct SimulationSettings {
+    /// RPC endpoint to fork; a fresh local chain without one
+    pub fork_url: Option<String>,
+    /// Block to fork at; the latest without one
+    pub fork_block: Option<u64>,
+    /// Live address of each contract, by name; the empty name stands for any contract
+    pub deployed_at: HashMap<String, String>,
+    /// Transactions re-sent on the fork before any check runs
+    pub replay: Vec<String>,
+}
+
+impl SimulationSettings {
+    /// Settings from `--deployed-at` entries, each `Name=0x...` or a bare address for any contract
+    pub fn parse_deployed_at(entries: &[String]) -> Result<HashMap<String, String>> {
+        entries
+            .iter()
+            .map(|entry| {
+                let (name, address) = entry.split_once('=').unwrap_or(("", entry.as_str()));
+                if !is_address(address) {
+                    bail!("Invalid --deployed-at address: {}", entry);
+                }
+                Ok((name.trim().to_string(), address.to_string()))
+            })
+            .collect()
+    }
+
+    /// Live address of `contract`, if it is not to be deployed
+    pub fn address_of(&self, contract: &str) -> Option<&str> {
+        self.deployed_at.get(contract).or_else(|| self.deployed_at.get("")).map(String::as_str)
+    }
+}
+
+/// Outcome of a transaction sent on the node
+#[derive(Debug, Clone)]
+pub struct TxOutcome {
+    pub hash: String,
+    pub success: bool,
+    pub gas_used: u64,
+    /// Address of the contract it created, if any
+    pub contract_address: Option<String>,
+}
+
+/// A running Anvil node
+pub struct Simulation {
+    /// Killed when the simulation is dropped
+    _node: tokio::process::Child,
+    rpc: RpcClient,
+    forked: bool,
+    /// Unlocked, funded account transactions are sent from by default
+    deployer: String,
+}
+
+impl Simulation {
+    /// Start Anvil as `settings` ask, and replay their transactions
+    pub async fn start(anvil: &AnvilConfig, settings: &SimulationSettings) -> Result<Self> {
+        let port = free_port()?;
+        let mut command = platform::async_command(&anvil.executable);
+        command.arg("--port").arg(port.to_string()).arg("--silent");
+        if let Some(url) = &settings.fork_url {
+            command.arg("--fork-url").arg(url);
+            if let Some(block) = settings.fork_block {
+                command.arg("--fork-block-number").arg(block.to_string());
+            }
+        }
+        command.kill_on_drop(true).stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null());
+        let mut node = command.spawn().map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => anyhow::Error::new(ToolError::ToolNotFound { program: anvil.executable.clone() }),
+            _ => anyhow::Error::new(e),
+        })?;
+
+        let rpc = RpcClient::new(&format!("http://127.0.0.1:{}", port), RPC_TIMEOUT);
+        let started = Instant::now();
+        loop {
+            if let Some(status) = node.try_wait()? {
+                bail!("anvil exited during startup ({})", status);
+            }
+            if rpc.request("eth_chainId", json!([])).is_ok() {
+                break;
+            }
+            if started.elapsed() > Duration::from_secs(anvil.startup_timeout) {
+                bail!("anvil did not answer within {}s", anvil.startup_timeout);
+            }
+            tokio::time::sleep(STARTUP_POLL_INTERVAL).await;
+        }
+
+        let deployer = rpc
+            .request("eth_accounts", json!([]))?
+            .get(0)
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("anvil has no unlocked account"))?
+            .to_string();
+        let simulation = Self { _node: node, rpc, forked: settings.fork_url.is_some(), deployer };
+        for hash in &settings.replay {
+            let outcome = simulation.replay(hash).with_context(|| format!("Could not replay {}", hash))?;
+            if !outcome.success {
+                log::warn!("Replayed transaction {} reverted on the fork", hash);
+            }
+        }
+        Ok(simulation)
+    }
+
+    /// Whether the node forks a live chain
+    pub fn forked(&self) -> bool {
+        self.forked
+    }
+
+    /// Funded account that deploys contracts
+    pub fn deployer(&self) -> &str {
+        &self.deployer
+    }
+
+    /// Raw JSON-RPC access to the node, for calls without a helper here
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    /// Number of the latest block
+    pub fn block_number(&self) -> Result<u64> {
+        quantity(&self.rpc.request("eth_blockNumber", json!([]))?)
+    }
+
+    /// Timestamp of the latest block
+    pub fn timestamp(&self) -> Result<u64> {
+        let block = self.rpc.request("eth_getBlockByNumber", json!(["latest", false]))?;
+        quantity(&block["timestamp"])
+    }
+
+    /// Read-only call of `data` on `to`
+    pub fn call(&self, to: &str, data: &[u8]) -> Result<Vec<u8>> {
+        self.rpc.call(to, data)
+    }
+
+    /// Runtime code at `address`, empty for an account without code
+    pub fn code_at(&self, address: &str) -> Result<Vec<u8>> {
+        let code = self.rpc.request("eth_getCode", json!([address, "latest"]))?;
+        decode_hex(code.as_str().unwrap_or_default()).map_err(|e| anyhow!("Invalid eth_getCode result: {}", e))
+    }
+
+    /// Storage slot of `address`
+    pub fn storage_at(&self, address: &str, slot: &str) -> Result<Vec<u8>> {
+        self.rpc.storage_at(address, slot)
+    }
+
+    /// Let transactions be sent from `address` without its key
+    pub fn impersonate(&self, address: &str) -> Result<()> {
+        self.rpc.request("anvil_impersonateAccount", json!([address]))?;
+        Ok(())
+    }
+
+    /// Set the ether balance of `address`, in wei
+    pub fn set_balance(&self, address: &str, wei: u128) -> Result<()> {
+        self.rpc.request("anvil_setBalance", json!([address, format!("0x{:x}", wei)]))?;
+        Ok(())
+    }
+
+    /// Send a transaction from `from`, an unlocked or impersonated account; no `to` creates a contract
+    pub fn send(&self, from: &str, to: Option<&str>, data: &[u8], value: u128) -> Result<TxOutcome> {
+        let mut transaction = json!({ "from": from, "data": format!("0x{}", encode_hex(data)), "value": format!("0x{:x}", value) });
+        if let Some(to) = to {
+            transaction["to"] = json!(to);
+        }
+        let hash = self.rpc.request("eth_sendTransaction", json!([transaction]))?;
+        let hash = hash.as_str().ok_or_else(|| anyhow!("eth_sendTransaction returned no hash"))?.to_string();
+        // Anvil mines each transaction as it arrives, so its receipt is there at once
+        let receipt = self.rpc.request("eth_getTransactionReceipt", json!([hash]))?;
+        Ok(TxOutcome {
+            success: receipt["status"].as_str() == Some("0x1"),
+            gas_used: quantity(&receipt["gasUsed"]).unwrap_or_default(),
+            contract_address: receipt["contractAddress"].as_str().map(str::to_string),
+            hash,
+        })
+    }
+
+    /// Deploy `bytecode`, creation code with any constructor arguments appended, from the deployer
+    pub fn deploy(&self, bytecode: &[u8]) -> Result<String> {
+        let outcome = self.send(&self.deployer, None, bytecode, 0)?;
+        match (outcome.success, outcome.contract_address) {
+            (true, Some(address)) => Ok(address),
+            _ => bail!("deployment reverted (transaction {})", outcome.hash),
+        }
+    }
+
+    /// Re-send a transaction of the forked chain from its original sender
+    pub fn replay(&self, hash: &str) -> Result<TxOutcome> {
+        let original = self.rpc.request("eth_getTransactionByHash", json!([hash]))?;
+        let from = original["from"].as_str().ok_or_else(|| anyhow!("transaction {} not found", hash))?;
+        let data = decode_hex(original["input"].as_str().unwrap_or_default()).map_err(|e| anyhow!("Invalid transaction input: {}", e))?;
+        let value = original["value"].as_str().and_then(|value| u128::from_str_radix(value.trim_start_matches("0x"), 16).ok()).unwrap_or_default();
+        self.impersonate(from)?;
+        self.set_balance(from, value.saturating_add(100 * WEI_PER_ETHER))?;
+        self.send(from, original["to"].as_str(), &data, value)
+    }
+
+    /// Record the node's state, for [`revert`](Self::revert) to return to
+    pub fn snapshot(&self) -> Result<String> {
+        let id = self.rpc.request("evm_snapshot", json!([]))?;
+        id.as_str().map(str::to_string).ok_or_else(|| anyhow!("evm_snapshot returned no id"))
+    }
+
+    pub fn revert(&self, snapshot: &str) -> Result<()> {
+        self.rpc.request("evm_revert", json!([snapshot]))?;
+        Ok(())
+    }
+}
+
+const WEI_PER_ETHER: u128 = 1_000_000_000_000_000_000;
+
+/// The node of a run, started for the first contract and shared by the rest
+pub struct SimulationStage {
+    settings: SimulationSettings,
+    /// Locked for a whole contract, so checks of contracts analysed in parallel do not see each other's state
+    node: tokio::sync::Mutex<Option<std::result::Result<Simulation, String>>>,
+}
+
+impl SimulationStage {
+    pub fn new(settings: SimulationSettings) -> Self {
+        Self { settings, node: tokio::sync::Mutex::new(None) }
+    }
+
+    /// Deploy or locate `contract` on the node and run the dynamic checks of `target`'s plugin on it
+    pub async fn run(&self, tools: &ToolsConfig, plugins: &PluginManager, target: &str, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        let mut node = self.node.lock().await;
+        if node.is_none() {
+            // A node that failed to start is not retried for every contract
+            *node = Some(Simulation::start(&tools.anvil, &self.settings).await.map_err(|e| format!("{:#}", e)));
+        }
+        let simulation = match node.as_ref() {
+            Some(Ok(simulation)) => simulation,
+            Some(Err(e)) => bail!("{}", e),
+            None => bail!("the simulation node was not started"),
+        };
+
+        let snapshot = simulation.snapshot()?;
+        let checked: Result<Vec<Vulnerability>> = async {
+            let (address, deployed) = match self.settings.address_of(&contract.name) {
+                Some(address) => (address.to_string(), false),
+                None => (simulation.deploy(&creation_code(&tools.solc, contract).await?)?, true),
+            };
+            let context = SimulationContext { simulation, contract, address, deployed };
+            plugins.dynamic_checks(&context, target)
+        }
+        .await;
+        simulation.revert(&snapshot)?;
+        checked
+    }
+}
+
+/// One contract on the node, as plugins see it during their dynamic checks
+pub struct SimulationContext<'a> {
+    pub simulation: &'a Simulation,
+    pub contract: &'a ParsedContract,
+    /// Address of the contract on the node
+    pub address: String,
+    /// Whether it was deployed for the run rather than found at a live address
+    pub deployed: bool,
+}
+
+impl SimulationContext<'_> {
+    /// Return data of calling `signature`, such as `owner()`, on the contract without arguments
+    pub fn call_getter(&self, signature: &str) -> Result<Vec<u8>> {
+        let selector = decode_hex(&selectors::selector(signature)).map_err(|e| anyhow!("Invalid selector: {}", e))?;
+        self.simulation.call(&self.address, &selector)
+    }
+
+    /// Address returned by the getter `signature`, if it answers with one
+    pub fn address_getter(&self, signature: &str) -> Option<String> {
+        self.call_getter(signature).ok().and_then(|data| read_address(&data, 0))
+    }
+
+    /// Where the finding of a dynamic check points to
+    pub fn location(&self) -> String {
+        match self.deployed {
+            true => self.contract.name.clone(),
+            false => format!("{} at {}", self.contract.name, self.address),
+        }
+    }
+}
+
+/// Creation code of `contract` compiled with solc, when it deploys without constructor arguments
+pub async fn creation_code(solc: &SolcConfig, contract: &ParsedContract) -> Result<Vec<u8>> {
+    if contract.units.iter().any(|unit| unit.name == contract.name && unit.constructor.as_ref().is_some_and(|c| !c.parameter_types.is_empty())) {
+        bail!("{} takes constructor arguments; pass its live address with --deployed-at", contract.name);
+    }
+    let input = json!({
+        "language": "Solidity",
+        "sources": { contract.name.as_str(): { "content": contract.source_code } },
+        "settings": { "outputSelection": { "*": { "*": ["evm.bytecode.object"] } } },
+    });
+    let input_file = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).suffix(".json").tempfile()?;
+    std::fs::write(input_file.path(), input.to_string())?;
+    let mut command = platform::async_command(&solc.executable);
+    command.arg("--standard-json").stdin(std::fs::File::open(input_file.path())?);
+    let output = crate::utils::budget::run(&mut command, Some(Duration::from_secs(solc.timeout))).await?;
+
+    let compiled: Value = serde_json::from_slice(&output.stdout).map_err(|_| ToolError::crashed(output.status, &output.stderr))?;
+    let bytecode = compiled
+        .pointer(&format!("/contracts/{}/{}/evm/bytecode/object", contract.name, contract.name))
+        .and_then(Value::as_str)
+        .filter(|object| !object.is_empty())
+        .ok_or_else(|| anyhow!("solc produced no bytecode for {}", contract.name))?;
+    decode_hex(bytecode).map_err(|e| anyhow!("Invalid bytecode: {}", e))
+}
+
+/// A port no one listens on, for the node
+fn free_port() -> Result<u16> {
+    Ok(std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+fn quantity(value: &Value) -> Result<u64> {
+    let text = value.as_str().ok_or_else(|| anyhow!("Expected a hex quantity, got {}", value))?;
+    u64::from_str_radix(text.trim_start_matches("0x"), 16).map_err(|e| anyhow!("Invalid quantity {}: {}", text, e))
+}
+
+fn is_address(text: &str) -> bool {
+    text.len() == 42 && text.starts_with("0x") && text[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
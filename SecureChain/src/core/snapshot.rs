@@ -0,0 +1,442 @@
+//! Behavior snapshots
+//!
+//! A refactor or an upgrade that is meant to change nothing observable can
+//! still drop a function, rename an event, shift a storage slot or change
+//! what a getter returns, and none of that shows up as a finding. A snapshot
+//! records what callers and the chain see of each deployable contract: the
+//! selectors of its external functions, the events of its source file, its
+//! storage layout and, given a deployment, the outputs of its view functions
+//! for canonical inputs. Checking the code against a recorded snapshot lists
+//! every difference, additions apart from removals and changes.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use crate::core::parser::{ContractParser, ContractUnit, ParsedContract, UserTypes};
+use crate::core::rpc::RpcClient;
+use crate::core::selectors;
+use crate::utils::abi::{decode_hex, encode_hex};
+
+/// Version of the file layout, bumped on incompatible changes
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Golden file `snapshot record` writes and `snapshot check` reads when none is given
+pub const DEFAULT_FILE: &str = "securechain-snapshot.json";
+
+/// Observable behavior of the deployable contracts of a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub schema_version: u32,
+    pub recorded_at: DateTime<Utc>,
+    pub securechain_version: String,
+    /// Deployment the view outputs were read from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deployment: Option<String>,
+    pub contracts: BTreeMap<String, ContractSnapshot>,
+}
+
+/// Observable behavior of one deployable contract
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContractSnapshot {
+    /// Selector of each external function and getter, own and inherited, by canonical signature
+    pub functions: BTreeMap<String, String>,
+    /// Topic of each event declared in the contract's source file, by canonical signature
+    pub events: BTreeMap<String, String>,
+    /// State variables in storage order, inherited ones first
+    pub storage: Vec<StorageSlot>,
+    /// Return data of view functions on the deployment, by call
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub views: BTreeMap<String, String>,
+}
+
+/// Where a state variable lives
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageSlot {
+    pub name: String,
+    pub type_name: String,
+    pub slot: u64,
+    /// Byte offset within the slot, for packed variables
+    pub offset: u32,
+    pub declared_in: String,
+}
+
+/// Kind of difference from the snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl fmt::Display for DriftKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DriftKind::Added => write!(f, "added"),
+            DriftKind::Removed => write!(f, "removed"),
+            DriftKind::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+/// One difference between a snapshot and the current code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Drift {
+    pub contract: String,
+    /// "contract", "function", "event", "storage" or "view"
+    pub aspect: String,
+    pub kind: DriftKind,
+    pub detail: String,
+}
+
+impl Drift {
+    fn new(contract: &str, aspect: &str, kind: DriftKind, detail: String) -> Self {
+        Self { contract: contract.to_string(), aspect: aspect.to_string(), kind, detail }
+    }
+
+    /// Whether existing callers, storage or outputs are affected, not only extended
+    pub fn is_breaking(&self) -> bool {
+        self.kind != DriftKind::Added
+    }
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}: {}", self.contract, self.aspect, self.kind, self.detail)
+    }
+}
+
+impl Snapshot {
+    /// Selectors, events and storage layout of every deployable contract in `contracts`
+    pub fn record(contracts: &[ParsedContract]) -> Result<Self> {
+        let functions = selectors::external_functions(contracts)?;
+        let events = selectors::event_signatures(contracts)?;
+        let layout = LayoutContext::new(contracts)?;
+
+        let mut snapshot = Self {
+            schema_version: SCHEMA_VERSION,
+            recorded_at: Utc::now(),
+            securechain_version: env!("CARGO_PKG_VERSION").to_string(),
+            deployment: None,
+            contracts: BTreeMap::new(),
+        };
+        for contract in contracts.iter().filter(|contract| !is_test_file(&contract.name)) {
+            for unit in contract.units.iter().filter(|unit| unit.kind == "contract") {
+                let entry = ContractSnapshot {
+                    functions: functions
+                        .get(&unit.name)
+                        .into_iter()
+                        .flatten()
+                        .map(|function| (function.signature.clone(), function.selector.clone()))
+                        .collect(),
+                    events: events.get(&contract.name).into_iter().flatten().cloned().collect(),
+                    storage: layout.storage(&unit.name),
+                    views: BTreeMap::new(),
+                };
+                snapshot.contracts.insert(unit.name.clone(), entry);
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Read the view functions of `contract` deployed at `address` with canonical inputs
+    pub fn read_views(&mut self, contracts: &[ParsedContract], contract: &str, address: &str, rpc: &RpcClient) -> Result<usize> {
+        let functions = selectors::external_functions(contracts)?;
+        let entry = self
+            .contracts
+            .get_mut(contract)
+            .ok_or_else(|| anyhow!("No deployable contract {} in the input", contract))?;
+
+        let mut skipped = 0;
+        for function in functions.get(contract).into_iter().flatten().filter(|function| function.is_view) {
+            let inputs = match canonical_inputs(&function.signature) {
+                Some(inputs) => inputs,
+                None => {
+                    skipped += 1;
+                    continue;
+                }
+            };
+            let selector = decode_hex(&function.selector)?;
+            for (label, arguments) in inputs {
+                let mut data = selector.clone();
+                data.extend(arguments);
+                let output = match rpc.call(address, &data) {
+                    Ok(output) => format!("0x{}", encode_hex(&output)),
+                    Err(e) => format!("error: {}", e),
+                };
+                entry.views.insert(format!("{}{}", function.signature, label), output);
+            }
+        }
+        self.deployment = Some(address.to_string());
+        Ok(skipped)
+    }
+
+    /// Differences of `current` from this snapshot
+    pub fn compare(&self, current: &Snapshot) -> Vec<Drift> {
+        let mut drift = Vec::new();
+        for (name, golden) in &self.contracts {
+            let now = match current.contracts.get(name) {
+                Some(now) => now,
+                None => {
+                    drift.push(Drift::new(name, "contract", DriftKind::Removed, format!("{} is no longer in the input", name)));
+                    continue;
+                }
+            };
+            compare_maps(name, "function", "selector", &golden.functions, &now.functions, &mut drift);
+            compare_maps(name, "event", "topic", &golden.events, &now.events, &mut drift);
+            compare_storage(name, &golden.storage, &now.storage, &mut drift);
+            // Outputs only compare when both sides read a deployment
+            if !golden.views.is_empty() && !now.views.is_empty() {
+                compare_maps(name, "view", "output", &golden.views, &now.views, &mut drift);
+            }
+        }
+        for name in current.contracts.keys().filter(|name| !self.contracts.contains_key(*name)) {
+            drift.push(Drift::new(name, "contract", DriftKind::Added, format!("{} is new", name)));
+        }
+        drift
+    }
+
+    /// Whether view outputs were recorded that `current` did not read
+    pub fn views_unchecked(&self, current: &Snapshot) -> bool {
+        self.contracts.values().any(|contract| !contract.views.is_empty())
+            && current.contracts.values().all(|contract| contract.views.is_empty())
+    }
+}
+
+/// Foundry tests and scripts are not part of what a project deploys
+fn is_test_file(name: &str) -> bool {
+    name.ends_with(".t.sol") || name.ends_with(".s.sol")
+}
+
+fn compare_maps(
+    contract: &str,
+    aspect: &str,
+    value: &str,
+    golden: &BTreeMap<String, String>,
+    now: &BTreeMap<String, String>,
+    drift: &mut Vec<Drift>,
+) {
+    for (key, expected) in golden {
+        match now.get(key) {
+            None => drift.push(Drift::new(contract, aspect, DriftKind::Removed, format!("`{}`", key))),
+            Some(actual) if actual != expected => drift.push(Drift::new(
+                contract,
+                aspect,
+                DriftKind::Changed,
+                format!("`{}` {} was {}, now {}", key, value, expected, actual),
+            )),
+            Some(_) => {}
+        }
+    }
+    for key in now.keys().filter(|key| !golden.contains_key(*key)) {
+        drift.push(Drift::new(contract, aspect, DriftKind::Added, format!("`{}`", key)));
+    }
+}
+
+fn compare_storage(contract: &str, golden: &[StorageSlot], now: &[StorageSlot], drift: &mut Vec<Drift>) {
+    let position = |slot: &StorageSlot| format!("slot {} offset {}", slot.slot, slot.offset);
+    for expected in golden {
+        match now.iter().find(|actual| actual.name == expected.name && actual.declared_in == expected.declared_in) {
+            None => drift.push(Drift::new(
+                contract,
+                "storage",
+                DriftKind::Removed,
+                format!("`{}` ({}, {})", expected.name, expected.type_name, position(expected)),
+            )),
+            Some(actual) if actual.type_name != expected.type_name || actual.slot != expected.slot || actual.offset != expected.offset => {
+                drift.push(Drift::new(
+                    contract,
+                    "storage",
+                    DriftKind::Changed,
+                    format!(
+                        "`{}` was {} at {}, now {} at {}",
+                        expected.name,
+                        expected.type_name,
+                        position(expected),
+                        actual.type_name,
+                        position(actual)
+                    ),
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+    for actual in now {
+        if !golden.iter().any(|expected| expected.name == actual.name && expected.declared_in == actual.declared_in) {
+            drift.push(Drift::new(
+                contract,
+                "storage",
+                DriftKind::Added,
+                format!("`{}` ({}, {})", actual.name, actual.type_name, position(actual)),
+            ));
+        }
+    }
+}
+
+/// Argument sets a view function is called with, labelled, when all its parameters are static elementary types
+fn canonical_inputs(signature: &str) -> Option<Vec<(String, Vec<u8>)>> {
+    let open = signature.find('(')?;
+    let parameters = &signature[open + 1..signature.len() - 1];
+    if parameters.is_empty() {
+        return Some(vec![(String::new(), Vec::new())]);
+    }
+
+    let types: Vec<&str> = parameters.split(',').collect();
+    let mut zero = Vec::new();
+    let mut one = Vec::new();
+    for type_name in &types {
+        let mut word = [0u8; 32];
+        if let Some(size) = type_name.strip_prefix("bytes").filter(|size| !size.is_empty()) {
+            size.parse::<usize>().ok().filter(|size| (1..=32).contains(size))?;
+            // bytesN are left-aligned
+            word[0] = 1;
+        } else if type_name.starts_with("uint") || type_name.starts_with("int") || matches!(*type_name, "address" | "bool") {
+            word[31] = 1;
+        } else {
+            return None;
+        }
+        zero.extend([0u8; 32]);
+        one.extend(word);
+    }
+    Some(vec![(" with zeros".to_string(), zero), (" with ones".to_string(), one)])
+}
+
+/// Resolves storage sizes across every unit of a project
+struct LayoutContext<'a> {
+    units: HashMap<&'a str, &'a ContractUnit>,
+    user_types: UserTypes,
+}
+
+/// Storage a type takes: bytes for value types, which pack, or whole slots
+enum Size {
+    Bytes(u32),
+    Slots(u64),
+}
+
+impl<'a> LayoutContext<'a> {
+    fn new(contracts: &'a [ParsedContract]) -> Result<Self> {
+        let parser = ContractParser::new()?;
+        let mut units = HashMap::new();
+        let mut user_types = UserTypes::default();
+        for contract in contracts {
+            for unit in &contract.units {
+                units.insert(unit.name.as_str(), unit);
+            }
+            let types = parser.extract_user_types(&contract.source_code)?;
+            user_types.structs.extend(types.structs);
+            user_types.enums.extend(types.enums);
+        }
+        Ok(Self { units, user_types })
+    }
+
+    /// State variables of `name` with their slots, bases first in declaration order
+    fn storage(&self, name: &str) -> Vec<StorageSlot> {
+        let mut order = Vec::new();
+        self.linearize(name, &mut HashSet::new(), &mut order);
+
+        let mut layout = Vec::new();
+        let (mut slot, mut offset) = (0u64, 0u32);
+        for unit in order {
+            for variable in unit.state_variables.iter().filter(|variable| !variable.is_constant && !variable.is_immutable) {
+                let (at_slot, at_offset) = place(self.size(&variable.type_name, 0), &mut slot, &mut offset);
+                layout.push(StorageSlot {
+                    name: variable.name.clone(),
+                    type_name: variable.type_name.clone(),
+                    slot: at_slot,
+                    offset: at_offset,
+                    declared_in: unit.name.clone(),
+                });
+            }
+        }
+        layout
+    }
+
+    /// Units in storage order: each base before the contracts deriving from it, left to right
+    fn linearize(&self, name: &str, seen: &mut HashSet<String>, order: &mut Vec<&'a ContractUnit>) {
+        let unit = match self.units.get(name) {
+            Some(unit) if seen.insert(name.to_string()) => *unit,
+            _ => return,
+        };
+        for base in &unit.inheritance {
+            self.linearize(base, seen, order);
+        }
+        order.push(unit);
+    }
+
+    fn size(&self, type_name: &str, depth: usize) -> Size {
+        let type_name = type_name.trim();
+        if type_name.starts_with("mapping") || type_name.ends_with("[]") {
+            return Size::Slots(1);
+        }
+        if let Some(open) = type_name.rfind('[').filter(|_| type_name.ends_with(']')) {
+            // Unknown lengths (constants) count as one element
+            let length = type_name[open + 1..type_name.len() - 1].trim().parse::<u64>().unwrap_or(1);
+            return match self.size(&type_name[..open], depth) {
+                Size::Bytes(bytes) => Size::Slots(length.div_ceil(u64::from(32 / bytes))),
+                Size::Slots(slots) => Size::Slots(length * slots),
+            };
+        }
+
+        let base = type_name.split_whitespace().next().unwrap_or(type_name);
+        let base = base.rsplit('.').next().unwrap_or(base);
+        match base {
+            "bool" => Size::Bytes(1),
+            "address" => Size::Bytes(20),
+            "string" | "bytes" => Size::Slots(1),
+            "uint" | "int" => Size::Bytes(32),
+            _ => {
+                let bits = base.strip_prefix("uint").or_else(|| base.strip_prefix("int")).and_then(|bits| bits.parse::<u32>().ok())
+                    .filter(|bits| (8..=256).contains(bits) && bits % 8 == 0);
+                if let Some(bits) = bits {
+                    return Size::Bytes(bits / 8);
+                }
+                if let Some(bytes) = base.strip_prefix("bytes").and_then(|bytes| bytes.parse::<u32>().ok()).filter(|bytes| (1..=32).contains(bytes)) {
+                    return Size::Bytes(bytes);
+                }
+                if self.user_types.enums.iter().any(|name| name == base) {
+                    return Size::Bytes(1);
+                }
+                if let Some(fields) = self.user_types.structs.get(base).filter(|_| depth < 8) {
+                    let (mut slot, mut offset) = (0u64, 0u32);
+                    for field in fields {
+                        place(self.size(field, depth + 1), &mut slot, &mut offset);
+                    }
+                    return Size::Slots(slot + u64::from(offset > 0));
+                }
+                // Contracts and interfaces are addresses; anything unresolved takes a slot
+                if self.units.contains_key(base) {
+                    Size::Bytes(20)
+                } else {
+                    Size::Slots(1)
+                }
+            }
+        }
+    }
+}
+
+/// Position of the next variable of `size`, advancing the cursor past it
+fn place(size: Size, slot: &mut u64, offset: &mut u32) -> (u64, u32) {
+    match size {
+        Size::Bytes(bytes) => {
+            if *offset + bytes > 32 {
+                *slot += 1;
+                *offset = 0;
+            }
+            let position = (*slot, *offset);
+            *offset += bytes;
+            position
+        }
+        Size::Slots(slots) => {
+            if *offset > 0 {
+                *slot += 1;
+                *offset = 0;
+            }
+            let position = (*slot, 0);
+            *slot += slots;
+            position
+        }
+    }
+}
@@ -0,0 +1,230 @@
+//! Solidity declarations from solc's AST
+//!
+//! The parser's regular expressions read one line at a time, so they miss
+//! functions whose signature spans several lines and leave findings on the
+//! wrong lines. With `tools.solc.ast` on, functions, modifiers and
+//! inheritance come from the AST solc produces when it stops after parsing,
+//! which needs neither the compiler version the pragma asks for nor the
+//! imported files. Import directives are blanked out beforehand, keeping
+//! every byte offset, so the AST's source locations point into the
+//! original source.
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde_json::Value;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::core::parser::{strip_comments, FunctionInfo, ModifierInfo, Parameter, SourceSpan};
+use crate::utils::config::SolcConfig;
+use crate::utils::platform;
+use crate::utils::retention;
+
+/// What the parser takes from the AST of a source
+pub struct Declarations {
+    pub functions: Vec<FunctionInfo>,
+    pub modifiers: Vec<ModifierInfo>,
+    pub inheritance: Vec<String>,
+}
+
+/// Parse `source` with solc, failing when solc is unavailable or cannot parse it
+pub fn parse(name: &str, source: &Arc<str>, solc: &SolcConfig) -> Result<Declarations> {
+    let input = serde_json::json!({
+        "language": "Solidity",
+        "sources": { name: { "content": without_imports(source) } },
+        "settings": {
+            "stopAfter": "parsing",
+            "outputSelection": { "*": { "": ["ast"] } },
+        },
+    });
+    let output = run(solc, &input.to_string())?;
+
+    let errors: Vec<&str> = output["errors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|error| error["severity"] == "error")
+        .filter_map(|error| error["message"].as_str())
+        .collect();
+    if !errors.is_empty() {
+        return Err(anyhow!("{}", errors.join("; ")));
+    }
+    let ast = &output["sources"][name]["ast"];
+    if ast.is_null() {
+        return Err(anyhow!("solc returned no AST"));
+    }
+
+    let mut declarations = Declarations { functions: Vec::new(), modifiers: Vec::new(), inheritance: Vec::new() };
+    for node in nodes(ast) {
+        match node["nodeType"].as_str() {
+            // Free functions, declared outside any contract
+            Some("FunctionDefinition") => declarations.functions.extend(function(source, node)),
+            Some("ContractDefinition") => {
+                if node["contractKind"] == "contract" {
+                    declarations.inheritance.extend(
+                        node["baseContracts"]
+                            .as_array()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|base| type_name_of(&base["baseName"])),
+                    );
+                }
+                for member in nodes(node) {
+                    match member["nodeType"].as_str() {
+                        Some("FunctionDefinition") => declarations.functions.extend(function(source, member)),
+                        Some("ModifierDefinition") => declarations.modifiers.extend(modifier(source, member)),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(declarations)
+}
+
+/// Run solc's standard JSON interface, killing it past the configured timeout
+fn run(solc: &SolcConfig, input: &str) -> Result<Value> {
+    let input_file = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).suffix(".json").tempfile()?;
+    std::fs::write(input_file.path(), input)?;
+    let output_file = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).suffix(".json").tempfile()?;
+
+    let mut child = platform::command(&solc.executable)
+        .arg("--standard-json")
+        .stdin(std::fs::File::open(input_file.path())?)
+        .stdout(output_file.reopen()?)
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow!("could not run {}: {}", solc.executable, e))?;
+    let deadline = Instant::now() + Duration::from_secs(solc.timeout);
+    while child.try_wait()?.is_none() {
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow!("solc timed out after {}s", solc.timeout));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    let output = std::fs::read_to_string(output_file.path())?;
+    serde_json::from_str(&output).map_err(|e| anyhow!("unreadable solc output: {}", e))
+}
+
+/// Source with its import directives replaced by spaces
+fn without_imports(source: &str) -> String {
+    let pattern = Regex::new(r"(?m)^\s*import\b[^;]*;").expect("valid import pattern");
+    let mut bytes = source.as_bytes().to_vec();
+    for found in pattern.find_iter(&strip_comments(source)) {
+        for byte in &mut bytes[found.range()] {
+            if *byte != b'\n' {
+                *byte = b' ';
+            }
+        }
+    }
+    // Whole directives were replaced, so no multi-byte character is cut
+    String::from_utf8(bytes).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).to_string())
+}
+
+fn nodes(node: &Value) -> impl Iterator<Item = &Value> {
+    node["nodes"].as_array().into_iter().flatten()
+}
+
+fn function(source: &Arc<str>, node: &Value) -> Option<FunctionInfo> {
+    if node["implemented"] == false {
+        return None;
+    }
+    let (start, end) = location(node)?;
+    let kind = node["kind"].as_str().unwrap_or("function");
+    let name = match kind {
+        "constructor" | "fallback" | "receive" => kind.to_string(),
+        _ => node["name"].as_str()?.to_string(),
+    };
+    let visibility = match node["visibility"].as_str() {
+        Some(visibility @ ("external" | "public" | "internal" | "private")) => visibility.to_string(),
+        _ => "public".to_string(),
+    };
+    // The regex parser leaves non-payable functions without a mutability
+    let state_mutability = match node["stateMutability"].as_str() {
+        Some("nonpayable") | None => String::new(),
+        Some(mutability) => mutability.to_string(),
+    };
+    let modifiers = node["modifiers"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        // Base constructor arguments are written like modifiers
+        .filter(|invocation| invocation["kind"] != "baseConstructorSpecifier")
+        .filter_map(|invocation| type_name_of(&invocation["modifierName"]))
+        .collect();
+
+    Some(FunctionInfo {
+        name,
+        visibility,
+        state_mutability,
+        parameters: parameters(source, &node["parameters"]),
+        return_parameters: parameters(source, &node["returnParameters"]),
+        modifiers,
+        line_number: line_of(source, start),
+        body: lines_of(source, start, end),
+        is_constructor: kind == "constructor",
+        is_fallback: kind == "fallback",
+        is_receive: kind == "receive",
+    })
+}
+
+fn modifier(source: &Arc<str>, node: &Value) -> Option<ModifierInfo> {
+    if node["body"].is_null() {
+        return None;
+    }
+    let (start, end) = location(node)?;
+    Some(ModifierInfo {
+        name: node["name"].as_str()?.to_string(),
+        parameters: parameters(source, &node["parameters"]),
+        body: lines_of(source, start, end),
+        line_number: line_of(source, start),
+    })
+}
+
+fn parameters(source: &str, list: &Value) -> Vec<Parameter> {
+    list["parameters"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|declaration| {
+            let (start, end) = location(&declaration["typeName"])?;
+            Some(Parameter {
+                name: declaration["name"].as_str().unwrap_or_default().to_string(),
+                // The type as written, since types are not resolved when solc stops after parsing
+                type_name: source.get(start..end)?.split_whitespace().collect::<Vec<_>>().join(" "),
+                indexed: declaration["indexed"] == true,
+            })
+        })
+        .collect()
+}
+
+/// Name of a referenced contract or modifier, qualified names included
+fn type_name_of(node: &Value) -> Option<String> {
+    node["name"].as_str().or_else(|| node["pathNode"]["name"].as_str()).map(str::to_string)
+}
+
+/// Byte range of a node, from its `start:length:source` location
+fn location(node: &Value) -> Option<(usize, usize)> {
+    let mut parts = node["src"].as_str()?.split(':');
+    let start: usize = parts.next()?.parse().ok()?;
+    let length: usize = parts.next()?.parse().ok()?;
+    Some((start, start + length))
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source.as_bytes()[..offset.min(source.len())].iter().filter(|&&byte| byte == b'\n').count() + 1
+}
+
+/// Whole lines from the declaration's first to its last, as the regex parser
+/// spans bodies, so a line of the body is `line_number` plus its index
+fn lines_of(source: &Arc<str>, start: usize, end: usize) -> SourceSpan {
+    let start = source[..start.min(source.len())].rfind('\n').map_or(0, |newline| newline + 1);
+    let end = end.min(source.len());
+    let end = source[end..].find('\n').map_or(source.len(), |newline| end + newline + 1);
+    SourceSpan::new(source, start..end)
+}
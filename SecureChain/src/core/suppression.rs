@@ -0,0 +1,322 @@
+//! False-positive suppression file
+//!
+//! A `.securechain-ignore.toml` at the root of a project lists the findings
+//! its auditors have reviewed and accepted, so they stop coming back on
+//! every run:
+//!
+//! ```toml
+//! [[suppress]]
+//! detector = "tx-origin"
+//! path = "contracts/mocks/**"
+//! justification = "Test doubles, never deployed"
+//!
+//! [[suppress]]
+//! fingerprint = "3f1c..."
+//! justification = "Reentrancy guarded by the caller"
+//! owner = "treasury team"
+//! expires = "2025-06-30"
+//! ```
+//!
+//! A rule matches a finding when every field it gives does: `fingerprint` is
+//! the finding's fingerprint, `detector` a detector id or `tool/check` as
+//! `securechain detectors list` and SARIF name them, and `path` a glob over
+//! the file relative to the project root, optionally followed by `:line` or
+//! `:first-last`. Suppressed findings are not dropped silently: results keep
+//! them with the rule that matched, and reports list them in an appendix.
+//!
+//! A rule accepting a risk rather than a false positive can name its
+//! `owner` and an `expires = "YYYY-MM-DD"` date. Once that date has passed
+//! the rule stops suppressing: the finding is reported again, flagged at the
+//! top of the report with its owner and posted to `notifications.webhooks`,
+//! on every run until someone renews or removes the rule.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{NaiveDate, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::core::detector_catalog;
+use crate::core::notify::Notification;
+use crate::report::vulnerability::utils::fingerprints;
+use crate::report::vulnerability::Vulnerability;
+
+/// File the rules are read from, in the input's directory or the nearest one above it
+pub const FILE_NAME: &str = ".securechain-ignore.toml";
+
+/// A finding left out of the results, and why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuppressedFinding {
+    pub finding: Vulnerability,
+    /// The rule that matched, as written in the file
+    pub rule: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub justification: Option<String>,
+    /// Who accepted the risk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Last day the acceptance holds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<NaiveDate>,
+}
+
+/// A finding reported again because the rule accepting it has expired
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpiredAcceptance {
+    pub finding_id: String,
+    pub title: String,
+    /// The rule that matched, as written in the file
+    pub rule: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    pub expired: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub justification: Option<String>,
+}
+
+/// What the rules of a suppression file did to the findings of a run
+#[derive(Debug, Default)]
+pub struct Applied {
+    pub suppressed: Vec<SuppressedFinding>,
+    /// Findings kept because every rule matching them has expired
+    pub expired: Vec<ExpiredAcceptance>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IgnoreFile {
+    #[serde(default)]
+    suppress: Vec<RuleSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    fingerprint: Option<String>,
+    detector: Option<String>,
+    path: Option<String>,
+    justification: Option<String>,
+    owner: Option<String>,
+    expires: Option<String>,
+}
+
+struct Rule {
+    fingerprint: Option<String>,
+    detector: Option<Regex>,
+    path: Option<Regex>,
+    lines: Option<(usize, usize)>,
+    description: String,
+    justification: Option<String>,
+    owner: Option<String>,
+    expires: Option<NaiveDate>,
+}
+
+/// The rules of a project's suppression file
+pub struct Suppressions {
+    /// Directory of the file, which rule paths are relative to
+    root: PathBuf,
+    rules: Vec<Rule>,
+}
+
+impl Suppressions {
+    /// Rules of the suppression file nearest to `input_path`, if there is one
+    pub fn discover(input_path: &Path) -> Result<Option<Self>> {
+        let start = if input_path.is_file() { input_path.parent().unwrap_or(input_path) } else { input_path };
+        let start = match start.canonicalize() {
+            Ok(start) => start,
+            Err(_) => return Ok(None),
+        };
+        match start.ancestors().map(|dir| dir.join(FILE_NAME)).find(|path| path.is_file()) {
+            Some(path) => Self::load(&path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Rules of the suppression file at `path`
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let file: IgnoreFile = toml::from_str(&text).with_context(|| format!("Invalid suppression file {}", path.display()))?;
+        let rules = file
+            .suppress
+            .into_iter()
+            .enumerate()
+            .map(|(index, spec)| Rule::compile(spec).with_context(|| format!("{}: rule {}", path.display(), index + 1)))
+            .collect::<Result<_>>()?;
+        Ok(Self { root: path.parent().map(Path::to_path_buf).unwrap_or_default(), rules })
+    }
+
+    /// Take the findings a rule in force matches out of `vulnerabilities`
+    ///
+    /// `path_of` gives the path of a finding's source file as the run read
+    /// it; findings whose file is outside the project match by name alone.
+    /// Findings only expired rules match stay, and are returned as such.
+    /// Fingerprints are numbered with the findings of the run suppressed
+    /// `earlier`, as reports number them.
+    pub fn apply(
+        &self,
+        vulnerabilities: &mut Vec<Vulnerability>,
+        earlier: &[SuppressedFinding],
+        path_of: impl Fn(&Vulnerability) -> PathBuf,
+    ) -> Applied {
+        let today = Utc::now().date_naive();
+        let mut applied = Applied::default();
+        let fingerprints = fingerprints(vulnerabilities.iter().chain(earlier.iter().map(|suppressed| &suppressed.finding)));
+        let mut kept = Vec::with_capacity(vulnerabilities.len());
+        for (vuln, fingerprint) in vulnerabilities.drain(..).zip(fingerprints) {
+            let path = self.relative_path(&path_of(&vuln));
+            let (expired, in_force): (Vec<&Rule>, Vec<&Rule>) = self
+                .rules
+                .iter()
+                .filter(|rule| rule.matches(&vuln, &fingerprint, &path))
+                .partition(|rule| rule.expires.is_some_and(|expires| expires < today));
+            match (in_force.first(), expired.first()) {
+                (Some(rule), _) => applied.suppressed.push(SuppressedFinding {
+                    finding: vuln,
+                    rule: rule.description.clone(),
+                    justification: rule.justification.clone(),
+                    owner: rule.owner.clone(),
+                    expires: rule.expires,
+                }),
+                (None, Some(rule)) => {
+                    applied.expired.push(ExpiredAcceptance {
+                        finding_id: vuln.id.clone(),
+                        title: vuln.title.clone(),
+                        rule: rule.description.clone(),
+                        owner: rule.owner.clone(),
+                        expired: rule.expires.unwrap_or(today),
+                        justification: rule.justification.clone(),
+                    });
+                    kept.push(vuln);
+                }
+                (None, None) => kept.push(vuln),
+            }
+        }
+        *vulnerabilities = kept;
+        applied
+    }
+
+    fn relative_path(&self, path: &Path) -> String {
+        let absolute = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let relative = match absolute.strip_prefix(&self.root) {
+            Ok(relative) => relative,
+            Err(_) => Path::new(path.file_name().unwrap_or(path.as_os_str())),
+        };
+        relative.to_string_lossy().replace('\\', "/").trim_start_matches("./").to_string()
+    }
+}
+
+impl Rule {
+    fn compile(spec: RuleSpec) -> Result<Self> {
+        if spec.fingerprint.is_none() && spec.detector.is_none() && spec.path.is_none() {
+            return Err(anyhow!("a rule needs a fingerprint, detector or path"));
+        }
+        let mut description = Vec::new();
+        if let Some(fingerprint) = &spec.fingerprint {
+            description.push(format!("fingerprint {}", fingerprint));
+        }
+        if let Some(detector) = &spec.detector {
+            description.push(format!("detector {}", detector));
+        }
+        if let Some(path) = &spec.path {
+            description.push(format!("path {}", path));
+        }
+
+        let expires = spec
+            .expires
+            .as_deref()
+            .map(|date| NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").map_err(|_| anyhow!("invalid expiry date {} (YYYY-MM-DD)", date)))
+            .transpose()?;
+        let (path, lines) = match spec.path.as_deref().map(split_lines).transpose()? {
+            Some((glob, lines)) => (Some(glob_regex(glob, !glob.contains('/'))?), lines),
+            None => (None, None),
+        };
+        Ok(Self {
+            fingerprint: spec.fingerprint.map(|fingerprint| fingerprint.trim().trim_start_matches("0x").to_lowercase()),
+            detector: spec.detector.as_deref().map(|detector| glob_regex(detector, false)).transpose()?,
+            path,
+            lines,
+            description: description.join(", "),
+            justification: spec.justification,
+            owner: spec.owner,
+            expires,
+        })
+    }
+
+    fn matches(&self, vuln: &Vulnerability, fingerprint: &str, path: &str) -> bool {
+        if let Some(expected) = &self.fingerprint {
+            if fingerprint != expected {
+                return false;
+            }
+        }
+        if let Some(detector) = &self.detector {
+            if !detector.is_match(&detector_catalog::check_id(vuln)) {
+                return false;
+            }
+        }
+        if let Some(glob) = &self.path {
+            if !glob.is_match(path) {
+                return false;
+            }
+        }
+        match (self.lines, vuln.line_number) {
+            (Some((first, last)), Some(line)) => first <= line && line <= last,
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+}
+
+/// Glob and line range of a `path`: `glob`, `glob:line` or `glob:first-last`
+fn split_lines(path: &str) -> Result<(&str, Option<(usize, usize)>)> {
+    let (glob, range) = match path.rsplit_once(':') {
+        Some((glob, range)) if !range.is_empty() && range.chars().all(|c| c.is_ascii_digit() || c == '-') => (glob, range),
+        _ => return Ok((path, None)),
+    };
+    let parse = |line: &str| line.trim().parse::<usize>().map_err(|_| anyhow!("invalid line range {} in {}", range, path));
+    let lines = match range.split_once('-') {
+        Some((first, last)) => (parse(first)?, parse(last)?),
+        None => (parse(range)?, parse(range)?),
+    };
+    if lines.0 > lines.1 {
+        return Err(anyhow!("line range {} in {} ends before it starts", range, path));
+    }
+    Ok((glob, Some(lines)))
+}
+
+/// Regex for a glob: `*` within a path segment, `**` across segments, `?`
+/// one character; with `any_directory` it matches below any directory, as a
+/// file name without a `/` does
+fn glob_regex(glob: &str, any_directory: bool) -> Result<Regex> {
+    let glob = glob.trim().trim_start_matches("./");
+    let mut pattern = String::from(if any_directory { "^(?:.*/)?" } else { "^" });
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    pattern.push_str("(?:.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Ok(Regex::new(&pattern)?)
+}
+
+/// Notification of the accepted risks of `project` whose acceptance expired, for their owners to re-accept or fix
+pub fn expiry_notification(project: &str, expired: &[ExpiredAcceptance]) -> Notification {
+    let owners: BTreeSet<&str> = expired.iter().filter_map(|expired| expired.owner.as_deref()).collect();
+    let mut text = format!("{} accepted risk(s) in {} expired and are reported again", expired.len(), project);
+    if !owners.is_empty() {
+        text.push_str(&format!(" (owners: {})", owners.into_iter().collect::<Vec<_>>().join(", ")));
+    }
+    Notification::new("acceptance_expired", text, json!({ "project": project, "expired": expired }))
+}
@@ -0,0 +1,418 @@
+//! Foundry test suite review
+//!
+//! A passing test suite is only evidence if the tests could have failed.
+//! This module reads a project's Foundry tests for shortcuts that make them
+//! pass regardless of the code under test: `vm.assume` filters that leave the
+//! fuzzer a sliver of the input space, `vm.mockCall` on the contracts being
+//! tested, `deal` balances no real account holds, and tests that never
+//! assert anything. Each one is a blind spot, listed in the report so clients
+//! know which green tests to discount.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::core::fetcher::ContractInfo;
+use crate::core::parser::{matching_paren, ContractParser, ContractUnit, ParsedContract, UnitFunction};
+
+/// Balances above this many wei (a billion ether) exceed any real holder
+const UNREALISTIC_BALANCE: f64 = 1e27;
+
+/// `vm.assume` bounds below this keep fuzzed integers away from the overflow range
+const NARROW_UPPER_BOUND: f64 = 1e30;
+
+/// How far up from the input a `foundry.toml` is looked for
+const MAX_PROJECT_DEPTH: usize = 4;
+
+/// Kind of testing shortcut
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Shortcut {
+    /// `vm.assume` pins or narrows a fuzzed input instead of bounding it
+    NarrowAssume,
+    /// `vm.mockCall` replaces a function of a contract under test
+    MockedTarget,
+    /// `deal` gives an account more than any real account holds
+    UnrealisticDeal,
+    /// The test never asserts or expects anything
+    NoAssertions,
+}
+
+impl std::fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Shortcut::NarrowAssume => "Narrow vm.assume",
+            Shortcut::MockedTarget => "Mocked contract under test",
+            Shortcut::UnrealisticDeal => "Unrealistic deal",
+            Shortcut::NoAssertions => "No assertions",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// A test that can pass without showing the code is correct
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlindSpot {
+    pub file: String,
+    pub test_contract: String,
+    /// Test function, or `setUp` for shortcuts every test of the contract inherits
+    pub test: String,
+    pub line: usize,
+    pub shortcut: Shortcut,
+    pub detail: String,
+}
+
+/// Blind spots of a project's test suite
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TestReview {
+    pub files_reviewed: usize,
+    pub tests_reviewed: usize,
+    /// Tests with a blind spot of their own or in their contract's `setUp`
+    pub tests_affected: usize,
+    pub blind_spots: Vec<BlindSpot>,
+}
+
+/// Review the Foundry tests of the project `input_path` belongs to, `None` without tests
+///
+/// Tests are the files of the run that declare test contracts or, when the
+/// input holds none, those in the test directory of the nearest `foundry.toml`.
+pub fn review(input_path: &Path, contracts: &[ContractInfo], parsed: &[ParsedContract]) -> Option<TestReview> {
+    let parser = ContractParser::new().ok()?;
+    let is_test_file = |contract: &ParsedContract| contract.units.iter().any(is_test_unit);
+    let mut files: Vec<(String, String)> = contracts
+        .iter()
+        .zip(parsed)
+        .filter(|(_, parsed)| is_test_file(parsed))
+        .map(|(contract, _)| {
+            let path = contract.metadata.get("path").cloned().unwrap_or_else(|| contract.name.clone());
+            (path, contract.source_code.clone())
+        })
+        .collect();
+    if files.is_empty() {
+        files = project_test_files(input_path);
+    }
+    if files.is_empty() {
+        return None;
+    }
+
+    // Contracts under test are those of the run that are not tests themselves
+    let targets: HashSet<&str> = parsed
+        .iter()
+        .filter(|contract| !is_test_file(contract))
+        .flat_map(|contract| &contract.units)
+        .filter(|unit| unit.kind.ends_with("contract"))
+        .map(|unit| unit.name.as_str())
+        .collect();
+
+    let units: Vec<(String, ContractUnit)> = files
+        .iter()
+        .filter_map(|(path, source)| parser.extract_units(source).ok().map(|units| (path, units)))
+        .flat_map(|(path, units)| units.into_iter().map(move |unit| (path.clone(), unit)))
+        .collect();
+    let asserting = asserting_functions(units.iter().map(|(_, unit)| unit));
+
+    let checks = Checks::new(&targets);
+    let mut review = TestReview { files_reviewed: files.len(), ..TestReview::default() };
+    for (file, unit) in units.iter().filter(|(_, unit)| is_test_unit(unit)) {
+        let spots_before = review.blind_spots.len();
+        let tests_before = review.tests_reviewed;
+        for function in &unit.functions {
+            let body = match &function.body {
+                Some(body) => body,
+                None => continue,
+            };
+            let is_test = is_test_function(&function.name);
+            if is_test {
+                review.tests_reviewed += 1;
+            }
+            if !is_test && function.name != "setUp" {
+                continue;
+            }
+
+            let mut spot = |line: usize, shortcut: Shortcut, detail: String| {
+                review.blind_spots.push(BlindSpot {
+                    file: file.clone(),
+                    test_contract: unit.name.clone(),
+                    test: function.name.clone(),
+                    line,
+                    shortcut,
+                    detail,
+                });
+            };
+            let line_at = |offset: usize| function.body_line_number + body[..offset].matches('\n').count();
+
+            for (offset, detail) in checks.narrow_assumes(function, body) {
+                spot(line_at(offset), Shortcut::NarrowAssume, detail);
+            }
+            for (offset, detail) in checks.mocked_targets(unit, function, body) {
+                spot(line_at(offset), Shortcut::MockedTarget, detail);
+            }
+            for (offset, detail) in checks.unrealistic_deals(body) {
+                spot(line_at(offset), Shortcut::UnrealisticDeal, detail);
+            }
+            if is_test && !function.name.starts_with("testFail") && !checks.asserts(body, &asserting) {
+                spot(
+                    function.line_number,
+                    Shortcut::NoAssertions,
+                    "Never asserts, expects a revert, event or call, or calls a helper that does; it only shows the calls do not revert".to_string(),
+                );
+            }
+        }
+
+        let spots = &review.blind_spots[spots_before..];
+        review.tests_affected += if spots.iter().any(|spot| spot.test == "setUp") {
+            review.tests_reviewed - tests_before
+        } else {
+            spots.iter().map(|spot| &spot.test).collect::<HashSet<_>>().len()
+        };
+    }
+    Some(review)
+}
+
+/// Test files under the test directory of the nearest Foundry project
+fn project_test_files(input_path: &Path) -> Vec<(String, String)> {
+    let start = if input_path.is_dir() { Some(input_path) } else { input_path.parent() };
+    let root = match start
+        .into_iter()
+        .flat_map(Path::ancestors)
+        .take(MAX_PROJECT_DEPTH)
+        .find(|dir| dir.join("foundry.toml").is_file())
+    {
+        Some(root) => root,
+        None => return Vec::new(),
+    };
+
+    let test_dir = std::fs::read_to_string(root.join("foundry.toml"))
+        .ok()
+        .and_then(|content| content.parse::<toml::Value>().ok())
+        .and_then(|manifest| manifest.get("profile")?.get("default")?.get("test")?.as_str().map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from("test"));
+
+    walkdir::WalkDir::new(root.join(test_dir))
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "sol"))
+        .filter_map(|entry| {
+            let source = std::fs::read_to_string(entry.path()).ok()?;
+            let path = entry.path().strip_prefix(root).unwrap_or(entry.path()).to_string_lossy().to_string();
+            Some((path, source))
+        })
+        .collect()
+}
+
+fn is_test_unit(unit: &ContractUnit) -> bool {
+    unit.kind.ends_with("contract")
+        && (unit.inheritance.iter().any(|base| base == "Test" || base == "DSTest")
+            || unit.functions.iter().any(|function| is_test_function(&function.name)))
+}
+
+fn is_test_function(name: &str) -> bool {
+    name.starts_with("test") || name.starts_with("invariant")
+}
+
+/// Functions of test contracts that assert, directly or through other functions that do
+fn asserting_functions<'a>(units: impl Iterator<Item = &'a ContractUnit>) -> HashSet<String> {
+    let assertion = Regex::new(ASSERTION).expect("valid assertion pattern");
+    let call = Regex::new(r"\b([A-Za-z_]\w*)\s*\(").expect("valid call pattern");
+
+    let bodies: Vec<(&str, &str)> = units
+        .flat_map(|unit| &unit.functions)
+        .filter_map(|function| function.body.as_deref().map(|body| (function.name.as_str(), body)))
+        .collect();
+    let mut asserting: HashSet<String> = bodies
+        .iter()
+        .filter(|(_, body)| assertion.is_match(body))
+        .map(|(name, _)| name.to_string())
+        .collect();
+    loop {
+        let before = asserting.len();
+        for (name, body) in &bodies {
+            if !asserting.contains(*name) && call.captures_iter(body).any(|captures| asserting.contains(&captures[1])) {
+                asserting.insert(name.to_string());
+            }
+        }
+        if asserting.len() == before {
+            return asserting;
+        }
+    }
+}
+
+/// Calls that make a test fail when the code misbehaves
+const ASSERTION: &str = r"\b(?:assert\w*|require|fail)\s*\(|\bvm\.(?:expectRevert|expectEmit|expectCall|expectPartialRevert)\b|\brevert\b";
+
+/// Compiled patterns of the checks
+struct Checks<'a> {
+    targets: &'a HashSet<&'a str>,
+    assertion: Regex,
+    call: Regex,
+    assume: Regex,
+    comparison: Regex,
+    mock: Regex,
+    local: Regex,
+    deal: Regex,
+}
+
+impl<'a> Checks<'a> {
+    fn new(targets: &'a HashSet<&'a str>) -> Self {
+        Self {
+            targets,
+            assertion: Regex::new(ASSERTION).expect("valid assertion pattern"),
+            call: Regex::new(r"\b([A-Za-z_]\w*)\s*\(").expect("valid call pattern"),
+            assume: Regex::new(r"\bvm\.assume\s*\(").expect("valid assume pattern"),
+            comparison: Regex::new(r"^\s*\(?\s*([A-Za-z_]\w*)\s*(==|<=|>=|<|>)\s*([0-9][0-9_]*(?:e[0-9]+)?(?:\s+(?:wei|gwei|ether))?)\s*\)?\s*$")
+                .expect("valid comparison pattern"),
+            mock: Regex::new(r"\bvm\.mockCall(?:Revert)?\s*\(").expect("valid mock pattern"),
+            local: Regex::new(r"\b([A-Z]\w*)\s+(?:memory\s+|storage\s+)?([a-z_]\w*)\s*=").expect("valid declaration pattern"),
+            deal: Regex::new(r"\b(?:vm\.)?deal\s*\(").expect("valid deal pattern"),
+        }
+    }
+
+    /// Whether the test asserts, or calls a function that does
+    fn asserts(&self, body: &str, asserting: &HashSet<String>) -> bool {
+        self.assertion.is_match(body) || self.call.captures_iter(body).any(|captures| asserting.contains(&captures[1]))
+    }
+
+    /// `vm.assume` conditions that pin a fuzzed parameter or cap it far below its type's range
+    fn narrow_assumes(&self, function: &UnitFunction, body: &str) -> Vec<(usize, String)> {
+        let mut spots = Vec::new();
+        for found in self.assume.find_iter(body) {
+            let open = found.end() - 1;
+            let condition = match matching_paren(body, open) {
+                Some(close) => &body[open + 1..close],
+                None => continue,
+            };
+            for clause in condition.split("&&") {
+                let captures = match self.comparison.captures(clause) {
+                    Some(captures) => captures,
+                    None => continue,
+                };
+                let parameter = &captures[1];
+                let index = match function.parameter_names.iter().position(|name| name == parameter) {
+                    Some(index) => index,
+                    None => continue,
+                };
+                let type_name = function.parameter_types.get(index).map(String::as_str).unwrap_or("");
+                let value = match parse_amount(&captures[3]) {
+                    Some(value) => value,
+                    None => continue,
+                };
+                let detail = match &captures[2] {
+                    "==" => format!(
+                        "vm.assume({}) pins `{}` to one value, so the fuzz test checks a single input",
+                        clause.trim(),
+                        parameter
+                    ),
+                    "<" | "<=" if type_name.starts_with("uint") && value < NARROW_UPPER_BOUND => format!(
+                        "vm.assume({}) discards every larger `{}`, including the overflow range; use bound() and test large values explicitly",
+                        clause.trim(),
+                        parameter
+                    ),
+                    _ => continue,
+                };
+                spots.push((found.start(), detail));
+            }
+        }
+        spots
+    }
+
+    /// `vm.mockCall` on a contract of the run, which replaces the behaviour under test
+    fn mocked_targets(&self, unit: &ContractUnit, function: &UnitFunction, body: &str) -> Vec<(usize, String)> {
+        let mut types: HashMap<&str, &str> = unit
+            .state_variables
+            .iter()
+            .map(|variable| (variable.name.as_str(), variable.type_name.as_str()))
+            .collect();
+        for (type_name, name) in function.parameter_types.iter().zip(&function.parameter_names) {
+            types.insert(name.as_str(), type_name.as_str());
+        }
+        for captures in self.local.captures_iter(body) {
+            let (type_name, name) = (captures.get(1).unwrap().as_str(), captures.get(2).unwrap().as_str());
+            types.insert(name, type_name);
+        }
+
+        let mut spots = Vec::new();
+        for found in self.mock.find_iter(body) {
+            let open = found.end() - 1;
+            let arguments = match matching_paren(body, open) {
+                Some(close) => &body[open + 1..close],
+                None => continue,
+            };
+            let receiver = arguments.split(',').next().unwrap_or("").trim();
+            let receiver = receiver
+                .strip_prefix("address(")
+                .and_then(|inner| inner.strip_suffix(')'))
+                .unwrap_or(receiver)
+                .trim();
+            let target = types.get(receiver).copied().unwrap_or(receiver);
+            if self.targets.contains(target) {
+                spots.push((
+                    found.start(),
+                    format!(
+                        "Mocks a call to `{}`, a {} under test, so the test checks the mock instead of its code",
+                        receiver, target
+                    ),
+                ));
+            }
+        }
+        spots
+    }
+
+    /// `deal` and `vm.deal` with more than any real account holds
+    fn unrealistic_deals(&self, body: &str) -> Vec<(usize, String)> {
+        let mut spots = Vec::new();
+        for found in self.deal.find_iter(body) {
+            let open = found.end() - 1;
+            let arguments = match matching_paren(body, open) {
+                Some(close) => &body[open + 1..close],
+                None => continue,
+            };
+            // The amount is the second argument of deal(who, amount) and deal(token, who, amount)
+            let amount = match arguments.split(',').map(str::trim).collect::<Vec<_>>()[..] {
+                [_, amount] | [_, _, amount] | [_, _, amount, _] => amount,
+                _ => continue,
+            };
+            let unrealistic = match amount {
+                "type(uint256).max" | "type(uint128).max" | "type(uint96).max" => true,
+                _ => parse_amount(amount).is_some_and(|value| value > UNREALISTIC_BALANCE),
+            };
+            if unrealistic {
+                spots.push((
+                    found.start(),
+                    format!(
+                        "Deals {}, more than any real account holds; totals and caps overflow or saturate in ways production never reaches",
+                        amount
+                    ),
+                ));
+            }
+        }
+        spots
+    }
+}
+
+/// Value of a literal amount such as `1_000e18`, `5 ether` or `2 * 10**30`, in its smallest unit
+fn parse_amount(text: &str) -> Option<f64> {
+    let text = text.trim();
+    if let Some((left, right)) = text.split_once('*').filter(|_| !text.contains("**")) {
+        return Some(parse_amount(left)? * parse_amount(right)?);
+    }
+    if let Some((base, exponent)) = text.split_once("**") {
+        return Some(parse_amount(base)?.powf(parse_amount(exponent)?));
+    }
+    let (number, unit) = match text.split_once(char::is_whitespace) {
+        Some((number, unit)) => (number, unit.trim()),
+        None => (text, ""),
+    };
+    let scale = match unit {
+        "" | "wei" => 1.0,
+        "gwei" => 1e9,
+        "ether" => 1e18,
+        _ => return None,
+    };
+    let number = number.replace('_', "");
+    let value = match number.split_once('e') {
+        Some((mantissa, exponent)) => mantissa.parse::<f64>().ok()? * 10f64.powi(exponent.parse().ok()?),
+        None => number.parse::<f64>().ok()?,
+    };
+    Some(value * scale)
+}
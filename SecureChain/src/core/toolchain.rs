@@ -0,0 +1,403 @@
+//! Managed tool installs
+//!
+//! Findings depend on the versions of the tools behind them, and whatever
+//! happens to be on PATH differs from one machine to the next and drifts
+//! over time. `securechain install` puts pinned versions of Slither,
+//! Mythril, Echidna, solc and Foundry in a prefix of their own: the Python
+//! tools in a virtual environment per version, the others as release
+//! binaries. The executables of the active version of each tool are linked
+//! into the prefix's `bin` directory, which tool commands search before
+//! PATH and put first on the PATH of the tools they start, so Slither
+//! compiles with the managed solc too.
+//!
+//! Release downloads run with the user's privileges on every analysis, so
+//! each is checked against a SHA-256 before it is unpacked: the one listed
+//! for its version and platform, or one given with `--sha256`. A download
+//! that does not match is not installed. Versions other than the pinned
+//! ones, and releases without a known digest, only install with
+//! `--allow-unverified`.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use crate::utils::abi::encode_hex;
+use crate::utils::artifact;
+use crate::utils::platform;
+
+/// A tool `securechain install` manages
+pub struct Tool {
+    pub name: &'static str,
+    /// Version installed when none is asked for
+    pub pinned: &'static str,
+    /// Executables the tool provides, linked into the prefix's `bin` directory
+    pub executables: &'static [&'static str],
+    source: Source,
+}
+
+enum Source {
+    /// A Python package, installed with pip into a virtual environment
+    Python(&'static str),
+    /// A binary or archive of the project's GitHub release
+    Release,
+}
+
+pub const TOOLS: &[Tool] = &[
+    Tool { name: "slither", pinned: "0.10.0", executables: &["slither"], source: Source::Python("slither-analyzer") },
+    Tool { name: "mythril", pinned: "0.24.8", executables: &["myth"], source: Source::Python("mythril") },
+    Tool { name: "echidna", pinned: "2.2.3", executables: &["echidna"], source: Source::Release },
+    Tool { name: "solc", pinned: "0.8.24", executables: &["solc"], source: Source::Release },
+    Tool { name: "foundry", pinned: "1.0.0", executables: &["forge", "cast", "anvil"], source: Source::Release },
+];
+
+/// SHA-256 of a release asset, as its project publishes it
+struct ReleaseDigest {
+    tool: &'static str,
+    version: &'static str,
+    /// File name of the asset, as `release_url` names it for the platform
+    asset: &'static str,
+    sha256: &'static str,
+}
+
+/// Digests release downloads are verified against, by tool, version and platform asset
+///
+/// Entries are copied from each project's published checksums whenever a
+/// pin moves. A release without one installs only with `--sha256` or
+/// `--allow-unverified`.
+const DIGESTS: &[ReleaseDigest] = &[];
+
+/// How a download is checked before it is installed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Verification<'a> {
+    /// SHA-256 the release download must have, in place of the listed one
+    pub sha256: Option<&'a str>,
+    /// Install versions other than the pinned one, and releases without a known digest, unchecked
+    pub allow_unverified: bool,
+}
+
+/// An installed version of a tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedTool {
+    pub tool: String,
+    pub version: String,
+    /// Directory holding the tool's executables
+    pub bin: PathBuf,
+    pub installed_at: DateTime<Utc>,
+    /// Whether tool commands run this version
+    pub active: bool,
+}
+
+/// Directory managed tools are installed in
+pub fn prefix() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".local/share/bugforgex/tools"))
+}
+
+/// Directory with the executables of the active versions, if any tool is installed
+pub fn bin_dir() -> Option<PathBuf> {
+    prefix().map(|prefix| prefix.join("bin")).filter(|bin| bin.is_dir())
+}
+
+/// The managed tool named `name`
+pub fn tool(name: &str) -> Result<&'static Tool> {
+    TOOLS.iter().find(|tool| tool.name == name).ok_or_else(|| {
+        anyhow!("Unknown tool: {} ({})", name, TOOLS.iter().map(|tool| tool.name).collect::<Vec<_>>().join(", "))
+    })
+}
+
+/// Installed versions, oldest install first
+pub fn installed() -> Vec<ManagedTool> {
+    let path = match prefix() {
+        Some(prefix) => prefix.join("installed.json"),
+        None => return Vec::new(),
+    };
+    if !path.exists() {
+        return Vec::new();
+    }
+    match artifact::read_json(&path, "managed tool list") {
+        Ok(tools) => tools,
+        Err(e) => {
+            log::warn!("Ignoring managed tools: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Install `version` of `tool`, the pinned one by default, and make it the
+/// active version; returns the install and whether it was already there
+pub async fn install(tool: &Tool, version: Option<&str>, verification: Verification<'_>) -> Result<(ManagedTool, bool)> {
+    let version = version.unwrap_or(tool.pinned).trim_start_matches('v');
+    if version.is_empty() || !version.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-') {
+        return Err(anyhow!("Invalid version: {}", version));
+    }
+    match (&tool.source, verification.sha256) {
+        (Source::Python(_), Some(_)) => {
+            return Err(anyhow!("{} is installed with pip, which --sha256 does not apply to", tool.name));
+        }
+        (Source::Python(_), None) if version != tool.pinned && !verification.allow_unverified => {
+            return Err(anyhow!(
+                "{} {} is not the pinned version {}; pass --allow-unverified to install it anyway",
+                tool.name,
+                version,
+                tool.pinned
+            ));
+        }
+        _ => {}
+    }
+    let prefix = prefix().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+    let mut managed = installed();
+
+    let existing = managed.iter().position(|installed| installed.tool == tool.name && installed.version == version);
+    let present = existing.is_some_and(|index| tool.executables.iter().all(|name| executable_in(&managed[index].bin, name).is_some()));
+    let index = match (existing, present) {
+        (Some(index), true) => index,
+        _ => {
+            let dir = prefix.join(tool.name).join(version);
+            if dir.exists() {
+                std::fs::remove_dir_all(&dir)?;
+            }
+            std::fs::create_dir_all(&dir)?;
+            let bin = match fetch(tool, version, &dir, verification).await {
+                Ok(bin) => bin,
+                Err(e) => {
+                    let _ = std::fs::remove_dir_all(&dir);
+                    return Err(e);
+                }
+            };
+            if let Some(index) = existing {
+                managed.remove(index);
+            }
+            managed.push(ManagedTool { tool: tool.name.to_string(), version: version.to_string(), bin, installed_at: Utc::now(), active: false });
+            managed.len() - 1
+        }
+    };
+    for (position, installed) in managed.iter_mut().enumerate() {
+        if installed.tool == tool.name {
+            installed.active = position == index;
+        }
+    }
+
+    save(&prefix, &managed)?;
+    Ok((managed[index].clone(), present))
+}
+
+/// Remove `version` of `tool`, or every version when none is given; another
+/// installed version, the latest, becomes active in place of a removed active one
+pub fn uninstall(tool: &Tool, version: Option<&str>) -> Result<Vec<ManagedTool>> {
+    let prefix = prefix().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+    let version = version.map(|version| version.trim_start_matches('v'));
+    let (removed, mut kept): (Vec<ManagedTool>, Vec<ManagedTool>) = installed()
+        .into_iter()
+        .partition(|installed| installed.tool == tool.name && version.is_none_or(|version| installed.version == version));
+    for installed in &removed {
+        let dir = prefix.join(tool.name).join(&installed.version);
+        if dir.exists() {
+            std::fs::remove_dir_all(dir)?;
+        }
+    }
+    if !kept.iter().any(|installed| installed.tool == tool.name && installed.active) {
+        if let Some(latest) = kept.iter_mut().filter(|installed| installed.tool == tool.name).last() {
+            latest.active = true;
+        }
+    }
+
+    save(&prefix, &kept)?;
+    Ok(removed)
+}
+
+/// Write the list of installs and link the executables of the active versions
+fn save(prefix: &Path, managed: &[ManagedTool]) -> Result<()> {
+    let bin = prefix.join("bin");
+    std::fs::create_dir_all(&bin)?;
+    for tool in TOOLS {
+        let active = managed.iter().find(|installed| installed.tool == tool.name && installed.active);
+        for name in tool.executables {
+            for link in [bin.join(name), bin.join(format!("{}.exe", name))] {
+                if link.symlink_metadata().is_ok() {
+                    std::fs::remove_file(&link)?;
+                }
+            }
+            if let Some(target) = active.and_then(|installed| executable_in(&installed.bin, name)) {
+                link_executable(&target, &bin.join(target.file_name().unwrap_or_default()))?;
+            }
+        }
+    }
+    artifact::write_atomic(prefix.join("installed.json"), serde_json::to_string_pretty(managed)?)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn link_executable(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// Symbolic links need privileges on Windows, so executables are copied
+#[cfg(not(unix))]
+fn link_executable(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::fs::copy(target, link).map(|_| ())
+}
+
+/// The executable `name` in `dir`, with the platform's extension
+fn executable_in(dir: &Path, name: &str) -> Option<PathBuf> {
+    [dir.join(name), dir.join(format!("{}.exe", name))].into_iter().find(|path| path.is_file())
+}
+
+/// Install `version` of `tool` in `dir`, returning the directory of its executables
+async fn fetch(tool: &Tool, version: &str, dir: &Path, verification: Verification<'_>) -> Result<PathBuf> {
+    let bin = match tool.source {
+        Source::Python(package) => {
+            let venv = dir.join("venv");
+            let mut python = match cfg!(windows) {
+                true => {
+                    let mut python = platform::async_command("py");
+                    python.arg("-3");
+                    python
+                }
+                false => platform::async_command("python3"),
+            };
+            run(python.args(["-m", "venv"]).arg(&venv), "create a virtual environment").await?;
+            let bin = venv.join(if cfg!(windows) { "Scripts" } else { "bin" });
+            let pip = format!("{}=={}", package, version);
+            run(
+                tokio::process::Command::new(bin.join("python")).args(["-m", "pip", "install", "--quiet", &pip]),
+                &format!("install {}", pip),
+            )
+            .await?;
+            bin
+        }
+        Source::Release => {
+            let bin = dir.join("bin");
+            std::fs::create_dir_all(&bin)?;
+            let url = release_url(tool.name, version)?;
+            let asset = url.rsplit('/').next().unwrap_or("release");
+            let expected = expected_digest(tool, version, asset, verification)?;
+            let archive = url.ends_with(".tar.gz") || url.ends_with(".zip");
+            let download = match archive {
+                true => dir.join(asset),
+                false => bin.join(if cfg!(windows) { format!("{}.exe", tool.name) } else { tool.name.to_string() }),
+            };
+            run(platform::async_command("curl").args(["-fsSL", "-o"]).arg(&download).arg(&url), &format!("download {}", url)).await?;
+            if let Some(expected) = expected {
+                let actual = sha256_of(&download)?;
+                if actual != expected {
+                    std::fs::remove_file(&download)?;
+                    return Err(anyhow!("{} does not match its checksum (expected sha256 {}, got {}); not installed", url, expected, actual));
+                }
+            }
+            if archive {
+                run(platform::async_command("tar").arg("-xf").arg(&download).arg("-C").arg(&bin), &format!("unpack {}", url)).await?;
+                std::fs::remove_file(&download)?;
+            }
+            #[cfg(unix)]
+            for name in tool.executables {
+                use std::os::unix::fs::PermissionsExt;
+                if let Some(path) = executable_in(&bin, name) {
+                    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+                }
+            }
+            bin
+        }
+    };
+
+    for name in tool.executables {
+        if executable_in(&bin, name).is_none() {
+            return Err(anyhow!("{} {} installed without its {} executable", tool.name, version, name));
+        }
+    }
+    Ok(bin)
+}
+
+/// SHA-256 the release asset must have, `None` when installing it unchecked was asked for
+fn expected_digest(tool: &Tool, version: &str, asset: &str, verification: Verification<'_>) -> Result<Option<String>> {
+    if let Some(sha256) = verification.sha256 {
+        let sha256 = sha256.trim().trim_start_matches("0x").to_lowercase();
+        if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow!("Invalid SHA-256: {} (expected 64 hex digits)", sha256));
+        }
+        return Ok(Some(sha256));
+    }
+    let listed = DIGESTS
+        .iter()
+        .find(|digest| digest.tool == tool.name && digest.version == version && digest.asset == asset)
+        .map(|digest| digest.sha256.to_string());
+    match listed {
+        Some(sha256) => Ok(Some(sha256)),
+        None if verification.allow_unverified => Ok(None),
+        None if version == tool.pinned => Err(anyhow!(
+            "No checksum is listed for {} {} ({}); pass --sha256 with the digest the project publishes, or --allow-unverified",
+            tool.name,
+            version,
+            asset
+        )),
+        None => Err(anyhow!(
+            "{} {} is not the pinned version {}; pass --sha256 with the digest of {} the project publishes, or --allow-unverified",
+            tool.name,
+            version,
+            tool.pinned,
+            asset
+        )),
+    }
+}
+
+/// SHA-256 of the file at `path`, in lower-case hex
+fn sha256_of(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok(encode_hex(&hasher.finalize()))
+}
+
+/// Download URL of a release of `tool` for this platform
+fn release_url(tool: &str, version: &str) -> Result<String> {
+    let (os, arch) = (std::env::consts::OS, std::env::consts::ARCH);
+    let unsupported = || anyhow!("No {} release for {} on {}", tool, os, arch);
+    let url = match tool {
+        "solc" => {
+            let asset = match (os, arch) {
+                ("linux", "x86_64") => "solc-static-linux",
+                ("macos", _) => "solc-macos",
+                ("windows", "x86_64") => "solc-windows.exe",
+                _ => return Err(unsupported()),
+            };
+            format!("https://github.com/ethereum/solidity/releases/download/v{}/{}", version, asset)
+        }
+        "echidna" => {
+            let platform = match (os, arch) {
+                ("linux", "x86_64") => "x86_64-linux",
+                ("macos", "x86_64") => "x86_64-macos",
+                ("macos", "aarch64") => "aarch64-macos",
+                ("windows", "x86_64") => "x86_64-windows",
+                _ => return Err(unsupported()),
+            };
+            format!("https://github.com/crytic/echidna/releases/download/v{0}/echidna-{0}-{1}.tar.gz", version, platform)
+        }
+        "foundry" => {
+            let platform = match os {
+                "linux" => "linux",
+                "macos" => "darwin",
+                "windows" => "win32",
+                _ => return Err(unsupported()),
+            };
+            let arch = match arch {
+                "x86_64" => "amd64",
+                "aarch64" => "arm64",
+                _ => return Err(unsupported()),
+            };
+            let extension = if os == "windows" { "zip" } else { "tar.gz" };
+            format!("https://github.com/foundry-rs/foundry/releases/download/v{0}/foundry_v{0}_{1}_{2}.{3}", version, platform, arch, extension)
+        }
+        _ => return Err(unsupported()),
+    };
+    Ok(url)
+}
+
+/// Run an install step, failing with the last line it wrote to stderr
+async fn run(command: &mut tokio::process::Command, what: &str) -> Result<()> {
+    let output = command.output().await.map_err(|e| anyhow!("Could not {}: {}", what, e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reason = stderr.lines().map(str::trim).rfind(|line| !line.is_empty()).unwrap_or("no output");
+    Err(anyhow!("Could not {}: {}", what, reason))
+}
@@ -0,0 +1,179 @@
+//! Analysis of only the code some entrypoints reach
+//!
+//! `--entrypoints Vault.deposit,Vault.withdraw` focuses a run on one feature
+//! of a large codebase. The functions the entrypoints reach through calls
+//! and the modifiers they apply are kept; every other function's body is
+//! blanked before any tool, fuzzer or AI prompt sees the source, and files
+//! with nothing reached are left out. Blanking keeps every line, so findings
+//! point at the original code, and keeps the source compiling. Findings
+//! inside a function that was left out are dropped from the results.
+
+use anyhow::{anyhow, Result};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use crate::core::callgraph::CallGraph;
+use crate::core::fetcher::ContractInfo;
+use crate::core::parser::{ContractParser, ParsedContract};
+use crate::core::selectors;
+use crate::report::vulnerability::Vulnerability;
+
+/// The part of a run's code reachable from its entrypoints
+pub struct Subgraph {
+    /// Entrypoints as given, `Contract.function`
+    pub entrypoints: Vec<String>,
+    /// Functions reached, of `functions` in the whole run
+    pub reached: usize,
+    pub functions: usize,
+    /// Files with a reached function
+    files: HashSet<String>,
+    /// Byte ranges of the bodies left out, by file
+    bodies: HashMap<String, Vec<Range<usize>>>,
+    /// First and last line of each function left out, by file
+    pruned: HashMap<String, Vec<(usize, usize)>>,
+    /// Reached external functions as Echidna names them, by the file of their contract
+    fuzz_targets: HashMap<String, Vec<String>>,
+}
+
+impl Subgraph {
+    /// Resolve `entrypoints` in `contracts` and walk the call graph from them
+    pub fn resolve(parser: &ContractParser, contracts: &[ContractInfo], entrypoints: &[String]) -> Result<Self> {
+        let parsed: Vec<ParsedContract> = contracts.iter().map(|contract| parser.parse_contract(contract)).collect::<Result<_>>()?;
+        let graph = CallGraph::build(&parsed);
+
+        let mut roots = Vec::new();
+        for entrypoint in entrypoints {
+            roots.extend(entry_nodes(&graph, &parsed, entrypoint)?);
+        }
+        let reached = graph.reachable_from(&roots);
+
+        let mut files = HashSet::new();
+        let mut bodies: HashMap<String, Vec<Range<usize>>> = HashMap::new();
+        let mut pruned: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+        for (index, node) in graph.nodes.iter().enumerate() {
+            if reached.contains(&index) {
+                files.insert(node.file.to_string());
+                continue;
+            }
+            let body = match &node.function.body {
+                Some(body) => body,
+                None => continue,
+            };
+            let start = match parsed.iter().find(|contract| contract.name == node.file).and_then(|contract| contract.offset_of(body)) {
+                Some(start) => start,
+                None => continue,
+            };
+            bodies.entry(node.file.to_string()).or_default().push(start..start + body.len());
+            let last_line = node.function.body_line_number + body.matches('\n').count();
+            pruned.entry(node.file.to_string()).or_default().push((node.function.line_number, last_line));
+        }
+
+        let reached_lines: HashSet<(&str, usize)> =
+            reached.iter().map(|index| (graph.nodes[*index].file, graph.nodes[*index].function.line_number)).collect();
+        let declared_in: HashMap<&str, &str> = parsed
+            .iter()
+            .flat_map(|contract| contract.units.iter().map(move |unit| (unit.name.as_str(), contract.name.as_str())))
+            .collect();
+        let mut fuzz_targets: HashMap<String, Vec<String>> = HashMap::new();
+        for (contract, functions) in selectors::external_functions(&parsed)? {
+            let file = match declared_in.get(contract.as_str()) {
+                Some(file) => file.to_string(),
+                None => continue,
+            };
+            let targets = functions
+                .iter()
+                .filter(|function| !function.is_getter && reached_lines.contains(&(function.file.as_str(), function.line_number)))
+                .map(|function| format!("{}.{}", contract, function.signature));
+            fuzz_targets.entry(file).or_default().extend(targets);
+        }
+
+        Ok(Self {
+            entrypoints: entrypoints.to_vec(),
+            reached: reached.len(),
+            functions: graph.nodes.len(),
+            files,
+            bodies,
+            pruned,
+            fuzz_targets,
+        })
+    }
+
+    /// The contracts with a reached function, the bodies of the functions
+    /// left out blanked
+    pub fn shake(&self, contracts: Vec<ContractInfo>) -> Vec<ContractInfo> {
+        contracts
+            .into_iter()
+            .filter(|contract| self.files.contains(&contract.name))
+            .map(|mut contract| {
+                if let Some(ranges) = self.bodies.get(&contract.name) {
+                    let mut ranges = ranges.clone();
+                    // Back to front, so earlier offsets still hold
+                    ranges.sort_by_key(|range| std::cmp::Reverse(range.start));
+                    for range in ranges {
+                        let blank: String = contract.source_code[range.clone()]
+                            .chars()
+                            .map(|c| if c == '\n' { '\n' } else { ' ' })
+                            .collect();
+                        contract.source_code.replace_range(range, &blank);
+                    }
+                }
+                contract
+            })
+            .collect()
+    }
+
+    /// Drop findings inside functions left out
+    pub fn retain(&self, vulnerabilities: &mut Vec<Vulnerability>) {
+        vulnerabilities.retain(|vuln| {
+            let (spans, line) = match (self.pruned.get(&vuln.file_path), vuln.line_number) {
+                (Some(spans), Some(line)) => (spans, line),
+                _ => return true,
+            };
+            !spans.iter().any(|(first, last)| *first <= line && line <= *last)
+        });
+    }
+
+    /// Reached external functions of the contracts in `file`, as Echidna's
+    /// `filterFunctions` takes them
+    pub fn fuzz_targets(&self, file: &str) -> &[String] {
+        self.fuzz_targets.get(file).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+/// Graph nodes of an entrypoint `Contract.function`: every overload the
+/// contract declares, or else the nearest base declaring one
+fn entry_nodes(graph: &CallGraph, contracts: &[ParsedContract], entrypoint: &str) -> Result<Vec<usize>> {
+    let (contract, function) = match entrypoint.trim().split_once('.') {
+        Some((contract, function)) if !contract.is_empty() && !function.is_empty() => (contract, function),
+        _ => return Err(anyhow!("Invalid entrypoint {} (expected Contract.function)", entrypoint)),
+    };
+    let units: HashMap<&str, &Vec<String>> = contracts
+        .iter()
+        .flat_map(|contract| contract.units.iter().map(|unit| (unit.name.as_str(), &unit.inheritance)))
+        .collect();
+    if !units.contains_key(contract) {
+        return Err(anyhow!("Entrypoint {}: no contract named {} in the input", entrypoint, contract));
+    }
+
+    // Nearest first: the contract, then its bases level by level
+    let mut lineage = vec![contract];
+    let mut index = 0;
+    while index < lineage.len() {
+        for base in units.get(lineage[index]).into_iter().flat_map(|bases| bases.iter()) {
+            if units.contains_key(base.as_str()) && !lineage.contains(&base.as_str()) {
+                lineage.push(base.as_str());
+            }
+        }
+        index += 1;
+    }
+
+    for unit in lineage {
+        let nodes: Vec<usize> = (0..graph.nodes.len())
+            .filter(|index| graph.nodes[*index].unit == unit && graph.nodes[*index].function.name == function)
+            .collect();
+        if !nodes.is_empty() {
+            return Ok(nodes);
+        }
+    }
+    Err(anyhow!("Entrypoint {}: {} and its bases declare no function {}", entrypoint, contract, function))
+}
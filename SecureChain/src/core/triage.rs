@@ -0,0 +1,143 @@
+//! Severity overrides
+//!
+//! Auditors regularly disagree with the severity a tool assigns: a
+//! reentrancy nobody can reach, or a low-severity check that guards the
+//! whole treasury. The QA process needs every such change justified and
+//! attributable, which hand-editing the results JSON gave no trace of. An
+//! override is recorded on the finding itself, so reports show the adjusted
+//! severity next to the original one, and appended to a log next to the user
+//! configuration that outlives the results file it was made in.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+
+use crate::report::vulnerability::{SeverityOverride, Vulnerability};
+use crate::utils::artifact;
+
+/// Version of the log layout, bumped on incompatible changes
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Severities a finding can be set to, as findings spell them
+pub const SEVERITIES: &[&str] = &["Critical", "High", "Medium", "Low", "Info"];
+
+/// An override as given on the command line; what is missing is asked for
+#[derive(Debug, Clone, Default)]
+pub struct Change {
+    pub finding: Option<String>,
+    pub severity: Option<String>,
+    pub justification: Option<String>,
+    pub author: Option<String>,
+}
+
+/// Every override made on this machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverrideLog {
+    pub schema_version: u32,
+    pub overrides: Vec<OverrideRecord>,
+}
+
+/// One override, with the finding and results file it was made on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverrideRecord {
+    pub finding_id: String,
+    pub title: String,
+    pub file_path: String,
+    pub tool: String,
+    pub results_file: String,
+    #[serde(flatten)]
+    pub change: SeverityOverride,
+}
+
+impl OverrideRecord {
+    pub fn new(finding: &Vulnerability, results_file: &Path, change: &SeverityOverride) -> Self {
+        Self {
+            finding_id: finding.id.clone(),
+            title: finding.title.clone(),
+            file_path: finding.file_path.clone(),
+            tool: finding.tool.clone(),
+            results_file: results_file.canonicalize().unwrap_or_else(|_| results_file.to_path_buf()).display().to_string(),
+            change: change.clone(),
+        }
+    }
+}
+
+/// Severity as findings spell it, from any case or `informational`
+pub fn parse_severity(text: &str) -> Result<&'static str> {
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("informational") {
+        return Ok("Info");
+    }
+    SEVERITIES
+        .iter()
+        .find(|severity| severity.eq_ignore_ascii_case(text))
+        .copied()
+        .ok_or_else(|| anyhow!("Unknown severity: {} (expected {})", text, SEVERITIES.join(", ").to_lowercase()))
+}
+
+/// Index of the finding `selector` names: its number as listed, its id or the start of its id
+pub fn find_finding(vulnerabilities: &[Vulnerability], selector: &str) -> Result<usize> {
+    let selector = selector.trim();
+    if let Ok(number) = selector.parse::<usize>() {
+        if (1..=vulnerabilities.len()).contains(&number) {
+            return Ok(number - 1);
+        }
+    }
+    if let Some(index) = vulnerabilities.iter().position(|v| v.id == selector) {
+        return Ok(index);
+    }
+
+    let matching: Vec<usize> = vulnerabilities
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| !selector.is_empty() && v.id.starts_with(selector))
+        .map(|(index, _)| index)
+        .collect();
+    match matching.as_slice() {
+        [index] => Ok(*index),
+        [] => Err(anyhow!("No finding {} in the results ({} findings)", selector, vulnerabilities.len())),
+        _ => Err(anyhow!("{} findings have ids starting with {}, give more of the id", matching.len(), selector)),
+    }
+}
+
+/// Author of an override when none is given: git's `user.name`, then `$USER`
+pub async fn default_author() -> Option<String> {
+    let git_name = match Command::new("git").args(["config", "user.name"]).stdin(Stdio::null()).output().await {
+        Ok(output) if output.status.success() => Some(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+        _ => None,
+    };
+    git_name
+        .or_else(|| std::env::var("USER").ok())
+        .filter(|name| !name.trim().is_empty())
+}
+
+/// Location of the override log, next to the user configuration
+pub fn log_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".config/bugforgex/severity_overrides.json"))
+}
+
+/// Overrides recorded so far, `None` before the first one
+pub fn load() -> Result<Option<OverrideLog>> {
+    let path = match log_path() {
+        Some(path) if path.exists() => path,
+        _ => return Ok(None),
+    };
+    Ok(Some(artifact::read_json(&path, "severity override log")?))
+}
+
+/// Add an override to the log
+pub fn append(record: OverrideRecord) -> Result<PathBuf> {
+    let path = log_path().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+    // Unlike the run history, an unreadable audit trail is not started over
+    let mut log = load()?.unwrap_or(OverrideLog { schema_version: SCHEMA_VERSION, overrides: Vec::new() });
+    log.schema_version = SCHEMA_VERSION;
+    log.overrides.push(record);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    artifact::write_atomic(&path, serde_json::to_string_pretty(&log)?)?;
+    Ok(path)
+}
@@ -0,0 +1,233 @@
+//! Parameter validation checks
+//!
+//! Constructors and setters are where a contract takes the values it trusts
+//! for the rest of its life, and a zero address, a fee above 100% or two
+//! arrays of different lengths passed there usually cannot be undone. These
+//! checks follow each parameter of a constructor or state-changing entry
+//! point with the data flow module, so a check made in a modifier, a helper
+//! or on a local copy counts, and only values that actually reach state or
+//! receive ether are reported.
+
+use regex::Regex;
+
+use crate::core::dataflow::{self, ParameterFlow};
+use crate::core::parser::{ContractUnit, ParsedContract, UnitFunction};
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+
+pub const TOOL_NAME: &str = "Parameter Validation";
+
+/// Words of parameter names that hold a fee, ratio or other bounded amount
+const BOUNDED_WORDS: &[&str] = &[
+    "fee", "ratio", "rate", "bps", "basis", "percent", "percentage", "factor", "multiplier", "slippage", "tax", "royalty",
+    "discount", "penalty", "commission",
+];
+
+/// Check the parameters of every constructor and setter in a source file.
+/// Slither reports missing zero checks itself, so `zero_checks` is only set
+/// when it did not run.
+pub fn check_parameters(contract: &ParsedContract, zero_checks: bool) -> Vec<Vulnerability> {
+    let mut findings = Vec::new();
+    for unit in contract.units.iter().filter(|unit| unit.kind.ends_with("contract")) {
+        let setters = unit.functions.iter().filter(|function| {
+            matches!(function.visibility.as_str(), "external" | "public")
+                && !matches!(function.state_mutability.as_str(), "view" | "pure" | "constant")
+        });
+        for function in unit.constructor.iter().chain(setters).filter(|function| function.body.is_some()) {
+            let scope = Scope { contract, unit, function };
+            let flows: Vec<(&str, &str, ParameterFlow)> = function
+                .parameter_names
+                .iter()
+                .zip(&function.parameter_types)
+                .filter(|(name, _)| !name.is_empty())
+                .map(|(name, type_name)| (name.as_str(), type_name.as_str(), dataflow::trace(contract, unit, function, name)))
+                .collect();
+
+            if zero_checks {
+                findings.extend(scope.zero_checks(&flows));
+            }
+            findings.extend(scope.bounds_checks(&flows));
+            findings.extend(scope.length_check(&flows));
+        }
+    }
+    findings
+}
+
+/// A constructor or setter, with its unit and the file it is in
+struct Scope<'a> {
+    contract: &'a ParsedContract,
+    unit: &'a ContractUnit,
+    function: &'a UnitFunction,
+}
+
+impl Scope<'_> {
+    fn label(&self) -> String {
+        format!("{}.{}", self.unit.name, self.function.name)
+    }
+
+    /// missing-zero-check: addresses stored or paid without a zero test
+    fn zero_checks(&self, flows: &[(&str, &str, ParameterFlow)]) -> Vec<Vulnerability> {
+        let zero_test = Regex::new(r"\baddress\s*\(\s*0(?:x0+)?\s*\)|\.\s*code\s*\.\s*length\b|\bisContract\s*\(")
+            .expect("valid zero test pattern");
+
+        flows
+            .iter()
+            .filter(|(_, type_name, flow)| {
+                *type_name == "address" && flow.is_used() && !flow.checks.iter().any(|check| zero_test.is_match(check))
+            })
+            .map(|(name, _, flow)| {
+                let destination = match flow.stores.first() {
+                    Some((variable, _)) => format!("is stored in `{}`", variable),
+                    None => "receives ether".to_string(),
+                };
+                finding(
+                    "missing-zero-check",
+                    &self.contract.name,
+                    flow.first_use().unwrap_or(self.function.line_number),
+                    "Low",
+                    0.7,
+                    "CWE-20",
+                    format!("Missing Zero-Address Check: {}({})", self.label(), name),
+                    format!(
+                        "`{}` {} in {} without being checked against the zero address, so a default or mistyped argument leaves the contract pointing at an address nobody controls.",
+                        name, destination, self.label()
+                    ),
+                    "Revert when the address is zero, e.g. `if (account == address(0)) revert ZeroAddress();`.",
+                )
+            })
+            .collect()
+    }
+
+    /// missing-bounds-check: fees and ratios stored without a range test
+    fn bounds_checks(&self, flows: &[(&str, &str, ParameterFlow)]) -> Vec<Vulnerability> {
+        // `<<` and `>>` are shifts, not comparisons
+        let comparison = Regex::new(r"(?:^|[^<>=])[<>]=?(?:[^<>=]|$)").expect("valid comparison pattern");
+
+        flows
+            .iter()
+            .filter(|(name, type_name, flow)| {
+                (type_name.starts_with("uint") || type_name.starts_with("int"))
+                    && is_bounded(name)
+                    && !flow.stores.is_empty()
+                    && !flow.checks.iter().any(|check| comparison.is_match(check))
+            })
+            .map(|(name, _, flow)| {
+                finding(
+                    "missing-bounds-check",
+                    &self.contract.name,
+                    flow.first_use().unwrap_or(self.function.line_number),
+                    "Medium",
+                    0.6,
+                    "CWE-1284",
+                    format!("Missing Bounds Check: {}({})", self.label(), name),
+                    format!(
+                        "`{}` is stored in `{}` by {} without an upper or lower bound, so a single call can set a fee or ratio above 100% or to a value that breaks the arithmetic using it.",
+                        name, flow.stores[0].0, self.label()
+                    ),
+                    "Check the value against a documented maximum before storing it, e.g. `if (fee > MAX_FEE_BPS) revert FeeTooHigh();`.",
+                )
+            })
+            .collect()
+    }
+
+    /// array-length-mismatch: parallel arrays indexed without comparing lengths
+    fn length_check(&self, flows: &[(&str, &str, ParameterFlow)]) -> Option<Vulnerability> {
+        let arrays: Vec<&(&str, &str, ParameterFlow)> =
+            flows.iter().filter(|(_, type_name, flow)| type_name.ends_with("[]") && flow.indexed).collect();
+        if arrays.len() < 2 {
+            return None;
+        }
+
+        let compared = |a: &str, b: &str| {
+            let length = |name: &str| format!(r"\b{}\s*\.\s*length\b", regex::escape(name));
+            let equality = Regex::new(&format!(r"{0}\s*[!=]=\s*{1}|{1}\s*[!=]=\s*{0}", length(a), length(b)))
+                .expect("valid length comparison pattern");
+            arrays.iter().any(|(_, _, flow)| flow.checks.iter().any(|check| equality.is_match(check)))
+        };
+        let unchecked: Vec<&str> = arrays
+            .iter()
+            .map(|(name, _, _)| *name)
+            .filter(|name| arrays.iter().any(|(other, _, _)| other != name && !compared(name, other)))
+            .collect();
+        if unchecked.len() < 2 {
+            return None;
+        }
+
+        Some(finding(
+            "array-length-mismatch",
+            &self.contract.name,
+            self.function.line_number,
+            "Low",
+            0.7,
+            "CWE-130",
+            format!("Array Length Mismatch: {}({})", self.label(), unchecked.join(", ")),
+            format!(
+                "{} indexes `{}` side by side without checking that their lengths are equal, so mismatched arguments either revert halfway or silently ignore the extra entries.",
+                self.label(),
+                unchecked.join("`, `")
+            ),
+            "Revert unless the arrays have the same length, e.g. `if (accounts.length != amounts.length) revert LengthMismatch();`.",
+        ))
+    }
+}
+
+/// Whether a parameter name marks a fee, ratio or similar bounded amount
+fn is_bounded(name: &str) -> bool {
+    // Split `_newFeeBps` into `new`, `fee` and `bps`
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in name.chars() {
+        let boundary = c == '_' || c.is_ascii_digit() || (c.is_uppercase() && !word.chars().last().is_some_and(char::is_uppercase));
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word).to_lowercase());
+        }
+        if c.is_alphabetic() {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        words.push(word.to_lowercase());
+    }
+
+    words.iter().any(|word| {
+        BOUNDED_WORDS.iter().any(|bounded| word == bounded || word.strip_suffix('s') == Some(bounded))
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn finding(
+    check: &str,
+    file: &str,
+    line_number: usize,
+    severity: &str,
+    confidence: f64,
+    cwe_id: &str,
+    title: String,
+    description: String,
+    recommendation: &str,
+) -> Vulnerability {
+    let cwe_number = cwe_id.trim_start_matches("CWE-");
+    let reference = if check == "missing-zero-check" {
+        format!("https://github.com/crytic/slither/wiki/Detector-Documentation#{}", check)
+    } else {
+        format!("https://cwe.mitre.org/data/definitions/{}.html", cwe_number)
+    };
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title,
+        description,
+        severity: severity.to_string(),
+        category: VulnerabilityCategory::InputValidation,
+        file_path: file.to_string(),
+        line_number: Some(line_number),
+        code_snippet: None,
+        recommendation: Some(recommendation.to_string()),
+        references: vec![reference],
+        cwe_id: Some(cwe_id.to_string()),
+        tool: TOOL_NAME.to_string(),
+        confidence,
+        provenance: None,
+        severity_overrides: Vec::new(),
+        cvss: None,
+        detected_by: Vec::new(),
+    }
+}
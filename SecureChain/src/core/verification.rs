@@ -0,0 +1,616 @@
+//! Source verification on block explorers
+//!
+//! Once the audit of local source is done, clients usually want the deployed
+//! contract verified. The audited sources, the compiler settings of the
+//! project and the exact compiler release are put together as solc standard
+//! JSON input and submitted to the Etherscan-family explorer of the network
+//! or to Sourcify, and the submission is polled until its outcome is known.
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::core::fetcher::ContractFetcher;
+use crate::core::metadata::{self, ProjectMetadata};
+use crate::core::parser::{strip_comments, ContractParser};
+use crate::utils::abi;
+use crate::utils::config::Config;
+use crate::utils::platform;
+
+/// Files marking a project root, most specific first
+const PROJECT_MANIFESTS: &[&str] = &["foundry.toml", "hardhat.config.ts", "hardhat.config.js", "truffle-config.js", "package.json"];
+
+/// Services `verify-onchain` can submit to
+pub const VERIFIERS: &[&str] = &["etherscan", "sourcify"];
+
+const SOURCIFY_URL: &str = "https://sourcify.dev/server";
+
+/// Solidity releases by version, for the commit hash explorers expect
+const SOLC_RELEASES_URL: &str = "https://binaries.soliditylang.org/bin/list.json";
+
+/// Etherscan asks for a few seconds between status checks
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Status checks before giving up, three minutes in all
+const MAX_POLLS: usize = 36;
+
+#[cfg_attr(not(feature = "explorers"), allow(dead_code))]
+const HTTP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Settings given on the command line, taking precedence over the project's
+#[derive(Debug, Default)]
+pub struct Overrides {
+    /// Contract to verify, when the sources define several
+    pub contract: Option<String>,
+    pub compiler: Option<String>,
+    pub optimizer_runs: Option<u32>,
+    pub evm_version: Option<String>,
+    /// ABI-encoded constructor arguments, hex
+    pub constructor_args: Option<String>,
+}
+
+/// Everything submitted for one contract
+#[derive(Debug, Clone, Serialize)]
+pub struct Submission {
+    /// `<source unit>:<contract>`, as explorers identify the contract to verify
+    pub contract: String,
+    /// Full compiler release, e.g. `0.8.24+commit.e11b9ed9`
+    pub compiler_version: String,
+    /// Where the compiler version and settings came from
+    pub settings_from: Vec<String>,
+    /// ABI-encoded constructor arguments, hex without `0x`
+    pub constructor_arguments: String,
+    pub source_files: usize,
+    /// solc standard JSON input holding the sources and settings
+    #[serde(skip)]
+    pub standard_json: Value,
+}
+
+/// Outcome of a verification request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    Verified,
+    /// Bytecode matches but the metadata hash differs, e.g. after comment changes
+    PartiallyVerified,
+    AlreadyVerified,
+    Failed,
+    /// No result before polling gave up
+    Pending,
+}
+
+impl std::fmt::Display for VerificationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            VerificationStatus::Verified => "verified",
+            VerificationStatus::PartiallyVerified => "partially verified",
+            VerificationStatus::AlreadyVerified => "already verified",
+            VerificationStatus::Failed => "failed",
+            VerificationStatus::Pending => "still pending",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationOutcome {
+    pub verifier: String,
+    pub status: VerificationStatus,
+    /// The verifier's own explanation
+    pub message: String,
+    /// Page showing the verified source
+    pub url: Option<String>,
+}
+
+/// Chain id of a network the explorers know
+pub fn chain_id(network: &str) -> Option<u64> {
+    match network {
+        "ethereum" | "etherscan" => Some(1),
+        "polygon" => Some(137),
+        "arbitrum" => Some(42161),
+        "optimism" => Some(10),
+        "bsc" => Some(56),
+        _ => None,
+    }
+}
+
+/// Put together the submission for a contract of the audited sources at `input`
+///
+/// The compiler version is taken from `--compiler`, else `foundry.toml`, else
+/// the analysis metadata when it is exact; optimizer and EVM settings come
+/// from the command line or `foundry.toml`.
+pub async fn prepare(
+    input: &Path,
+    analysis_metadata: Option<&ProjectMetadata>,
+    overrides: &Overrides,
+    config: &Config,
+) -> Result<Submission> {
+    let input_str = input.to_str().ok_or_else(|| anyhow!("Input path is not valid UTF-8: {}", input.display()))?;
+    let contracts = ContractFetcher::new(config.clone()).fetch_from_local(input_str).await?;
+    let parser = ContractParser::new()?;
+
+    // Deployable contracts, leaving out Foundry tests and scripts
+    let mut candidates: Vec<(String, PathBuf)> = Vec::new();
+    let mut parsed_contracts = Vec::new();
+    for contract in &contracts {
+        let parsed = parser.parse_contract(contract)?;
+        let file = match contract.metadata.get("path") {
+            Some(relative) => input.join(relative),
+            None => input.to_path_buf(),
+        };
+        if !contract.name.ends_with(".t.sol") && !contract.name.ends_with(".s.sol") {
+            for unit in parsed.units.iter().filter(|unit| unit.kind == "contract") {
+                candidates.push((unit.name.clone(), file.clone()));
+            }
+        }
+        parsed_contracts.push(parsed);
+    }
+
+    let names = || candidates.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(", ");
+    let (name, file) = match (&overrides.contract, candidates.len()) {
+        (_, 0) => return Err(anyhow!("No deployable contract found in {}", input.display())),
+        (Some(wanted), _) => candidates
+            .iter()
+            .find(|(name, _)| name == wanted)
+            .cloned()
+            .ok_or_else(|| anyhow!("No contract {} in {}; found: {}", wanted, input.display(), names()))?,
+        (None, 1) => candidates[0].clone(),
+        (None, _) => {
+            return Err(anyhow!(
+                "{} defines several contracts ({}); choose one with --contract",
+                input.display(),
+                names()
+            ))
+        }
+    };
+
+    let file = file.canonicalize()?;
+    let root = project_root(&file).ok_or_else(|| anyhow!("No project directory for {}", file.display()))?;
+    let entry = unit_name(file.strip_prefix(&root).unwrap_or(&file));
+    let foundry = foundry_profile(&root);
+
+    let remappings = project_remappings(&root, foundry.as_ref());
+    let sources = collect_sources(&root, &entry, &remappings)?;
+
+    // Compiler release, most specific source first
+    let mut settings_from = Vec::new();
+    let extracted;
+    let analysis_metadata = match analysis_metadata {
+        Some(analysis_metadata) => analysis_metadata,
+        None => {
+            extracted = metadata::extract(input, &parsed_contracts);
+            &extracted
+        }
+    };
+    let analysed_version = analysis_metadata
+        .contracts
+        .iter()
+        .find(|entry| entry.name == name)
+        .and_then(|entry| entry.solc_version.clone());
+    let foundry_version = foundry
+        .as_ref()
+        .and_then(|profile| profile.get("solc_version").or_else(|| profile.get("solc")))
+        .and_then(|value| value.as_str())
+        .and_then(exact_version);
+    let version = if let Some(version) = &overrides.compiler {
+        settings_from.push("command line".to_string());
+        exact_version(version).ok_or_else(|| anyhow!("--compiler needs an exact version such as 0.8.24, got: {}", version))?
+    } else if let Some(version) = foundry_version {
+        settings_from.push("foundry.toml".to_string());
+        version
+    } else {
+        match analysed_version.as_deref().map(|version| (version, exact_version(version))) {
+            Some((_, Some(version))) => {
+                settings_from.push("analysis metadata".to_string());
+                version
+            }
+            Some((range, None)) => {
+                return Err(anyhow!(
+                    "The compiler version of {} is only known as the range {}; pass --compiler with the exact version it was deployed with",
+                    name,
+                    range
+                ))
+            }
+            None => return Err(anyhow!("Unknown compiler version of {}; pass --compiler", name)),
+        }
+    };
+    let compiler_version = full_release(&version, &config.tools.solc.executable)?;
+
+    let settings = compiler_settings(foundry.as_ref(), overrides, &remappings, &mut settings_from);
+    let constructor_arguments = match &overrides.constructor_args {
+        Some(hex) => {
+            let bytes = abi::decode_hex(hex).map_err(|e| anyhow!("Invalid --constructor-args: {}", e))?;
+            abi::encode_hex(&bytes)
+        }
+        None => String::new(),
+    };
+
+    let source_files = sources.len();
+    let sources: serde_json::Map<String, Value> = sources
+        .into_iter()
+        .map(|(unit, content)| (unit, json!({ "content": content })))
+        .collect();
+
+    Ok(Submission {
+        contract: format!("{}:{}", entry, name),
+        compiler_version,
+        settings_from,
+        constructor_arguments,
+        source_files,
+        standard_json: json!({ "language": "Solidity", "sources": sources, "settings": settings }),
+    })
+}
+
+/// Submit to the Etherscan-family explorer at `explorer_url` and wait for its verdict
+pub async fn submit_to_etherscan(
+    submission: &Submission,
+    address: &str,
+    explorer_url: &str,
+    chain_id: Option<u64>,
+    api_key: &str,
+) -> Result<VerificationOutcome> {
+    // The multi-chain API needs the chain id, the per-network ones ignore it
+    let chain_id = chain_id.map(|id| id.to_string()).unwrap_or_default();
+    let mut query = vec![("apikey", api_key)];
+    if !chain_id.is_empty() {
+        query.push(("chainid", chain_id.as_str()));
+    }
+
+    let standard_json = submission.standard_json.to_string();
+    let compiler_version = format!("v{}", submission.compiler_version);
+    let response = post_form(
+        explorer_url,
+        &query,
+        &[
+            ("module", "contract"),
+            ("action", "verifysourcecode"),
+            ("contractaddress", address),
+            ("sourceCode", &standard_json),
+            ("codeformat", "solidity-standard-json-input"),
+            ("contractname", &submission.contract),
+            ("compilerversion", &compiler_version),
+            // Etherscan's spelling
+            ("constructorArguements", &submission.constructor_arguments),
+        ],
+    )?;
+
+    let result = response["result"].as_str().unwrap_or_default().to_string();
+    let url = explorer_site(explorer_url).map(|site| format!("{}/address/{}#code", site, address));
+    let outcome = |status, message: String| VerificationOutcome {
+        verifier: "etherscan".to_string(),
+        status,
+        message,
+        url: url.clone(),
+    };
+    if response["status"].as_str() != Some("1") {
+        if result.to_lowercase().contains("already verified") {
+            return Ok(outcome(VerificationStatus::AlreadyVerified, result));
+        }
+        return Err(anyhow!(
+            "Explorer rejected the submission: {}",
+            if result.is_empty() { response["message"].as_str().unwrap_or("no reason given") } else { &result }
+        ));
+    }
+
+    let guid = result;
+    for _ in 0..MAX_POLLS {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let mut status_query = query.clone();
+        status_query.extend([("module", "contract"), ("action", "checkverifystatus"), ("guid", guid.as_str())]);
+        let status = get_json(explorer_url, &status_query)?;
+        let message = status["result"].as_str().unwrap_or_default().to_string();
+        let lowered = message.to_lowercase();
+        if lowered.starts_with("pass") {
+            return Ok(outcome(VerificationStatus::Verified, message));
+        } else if lowered.contains("already verified") {
+            return Ok(outcome(VerificationStatus::AlreadyVerified, message));
+        } else if !lowered.contains("pending") && !lowered.contains("in progress") {
+            return Ok(outcome(VerificationStatus::Failed, message));
+        }
+    }
+    Ok(outcome(
+        VerificationStatus::Pending,
+        format!("No verdict yet; the explorer tracks the submission as {}", guid),
+    ))
+}
+
+/// Submit to Sourcify and wait for its verdict
+pub async fn submit_to_sourcify(submission: &Submission, address: &str, chain_id: u64) -> Result<VerificationOutcome> {
+    let response = post_json(
+        &format!("{}/v2/verify/{}/{}", SOURCIFY_URL, chain_id, address),
+        json!({
+            "stdJsonInput": submission.standard_json,
+            "compilerVersion": submission.compiler_version,
+            "contractIdentifier": submission.contract,
+        }),
+    )?;
+
+    let url = Some(format!("https://repo.sourcify.dev/{}/{}", chain_id, address));
+    let outcome = |status, message: String| VerificationOutcome {
+        verifier: "sourcify".to_string(),
+        status,
+        message,
+        url: url.clone(),
+    };
+    let verification_id = match response["verificationId"].as_str() {
+        Some(id) => id.to_string(),
+        None if response["customCode"].as_str() == Some("already_verified") => {
+            let message = response["message"].as_str().unwrap_or("Contract is already verified").to_string();
+            return Ok(outcome(VerificationStatus::AlreadyVerified, message));
+        }
+        None => {
+            return Err(anyhow!(
+                "Sourcify rejected the submission: {}",
+                response["message"].as_str().unwrap_or("no reason given")
+            ))
+        }
+    };
+
+    for _ in 0..MAX_POLLS {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let job = get_json(&format!("{}/v2/verify/{}", SOURCIFY_URL, verification_id), &[])?;
+        if job["isJobCompleted"].as_bool() != Some(true) {
+            continue;
+        }
+        if let Some(error) = job.get("error").filter(|error| !error.is_null()) {
+            let message = error["message"].as_str().unwrap_or("verification failed").to_string();
+            let status = match error["customCode"].as_str() {
+                Some("already_verified") => VerificationStatus::AlreadyVerified,
+                _ => VerificationStatus::Failed,
+            };
+            return Ok(outcome(status, message));
+        }
+        return Ok(match job["contract"]["match"].as_str() {
+            Some("exact_match") => outcome(VerificationStatus::Verified, "Exact match".to_string()),
+            Some("match") => outcome(
+                VerificationStatus::PartiallyVerified,
+                "Bytecode matches but the metadata hash differs".to_string(),
+            ),
+            _ => outcome(VerificationStatus::Failed, "Compiled bytecode does not match the deployed code".to_string()),
+        });
+    }
+    Ok(outcome(
+        VerificationStatus::Pending,
+        format!("No verdict yet; Sourcify tracks the job as {}", verification_id),
+    ))
+}
+
+/// Exact `x.y.z` version, with its commit if given, out of a version, `v`-prefixed version or `=x.y.z` pragma
+fn exact_version(version: &str) -> Option<String> {
+    let version = version.trim().trim_start_matches('=').trim().trim_start_matches('v');
+    let pattern = Regex::new(r"^\d+\.\d+\.\d+(\+commit\.[0-9a-f]{8})?$").expect("valid version pattern");
+    pattern.is_match(version).then(|| version.to_string())
+}
+
+/// Release with its commit hash, e.g. `0.8.24+commit.e11b9ed9`, from the local solc or the release list
+fn full_release(version: &str, solc: &str) -> Result<String> {
+    if version.contains("+commit.") {
+        return Ok(version.to_string());
+    }
+
+    let release_pattern = Regex::new(r"(\d+\.\d+\.\d+\+commit\.[0-9a-f]{8})").expect("valid release pattern");
+    let local = platform::command(solc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| release_pattern.captures(&String::from_utf8_lossy(&output.stdout)).map(|c| c[1].to_string()));
+    if let Some(release) = local.filter(|release| release.split('+').next() == Some(version)) {
+        return Ok(release);
+    }
+
+    let releases = get_json(SOLC_RELEASES_URL, &[])
+        .map_err(|e| anyhow!("Could not look up the commit of solc {}: {}; pass the full release to --compiler", version, e))?;
+    releases["releases"][version]
+        .as_str()
+        .and_then(|file| release_pattern.captures(file).map(|c| c[1].to_string()))
+        .ok_or_else(|| anyhow!("solc {} is not a published release", version))
+}
+
+/// Directory source unit names are relative to: the nearest project manifest, else the file's directory
+fn project_root(file: &Path) -> Option<PathBuf> {
+    let directory = file.parent()?;
+    PROJECT_MANIFESTS
+        .iter()
+        .find_map(|manifest| directory.ancestors().find(|dir| dir.join(manifest).is_file()))
+        .unwrap_or(directory)
+        .to_path_buf()
+        .into()
+}
+
+/// `[profile.default]` of the project's foundry.toml
+fn foundry_profile(root: &Path) -> Option<toml::Value> {
+    let manifest: toml::Value = std::fs::read_to_string(root.join("foundry.toml")).ok()?.parse().ok()?;
+    manifest.get("profile")?.get("default").cloned()
+}
+
+/// Remappings of foundry.toml and remappings.txt, as `prefix=target` lines
+fn project_remappings(root: &Path, foundry: Option<&toml::Value>) -> Vec<String> {
+    let mut remappings: Vec<String> = foundry
+        .and_then(|profile| profile.get("remappings")?.as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|value| value.as_str().map(str::to_string))
+        .collect();
+    if let Ok(content) = std::fs::read_to_string(root.join("remappings.txt")) {
+        for line in content.lines().map(str::trim).filter(|line| line.contains('=')) {
+            if !remappings.iter().any(|known| known == line) {
+                remappings.push(line.to_string());
+            }
+        }
+    }
+    remappings
+}
+
+/// Optimizer, EVM version and remappings, with the output explorers compile for
+fn compiler_settings(foundry: Option<&toml::Value>, overrides: &Overrides, remappings: &[String], settings_from: &mut Vec<String>) -> Value {
+    let foundry_bool = |key: &str| foundry.and_then(|profile| profile.get(key)?.as_bool());
+    let foundry_str = |key: &str| foundry.and_then(|profile| Some(profile.get(key)?.as_str()?.to_string()));
+
+    let (optimizer, runs) = match overrides.optimizer_runs {
+        Some(runs) => (true, runs),
+        None => (
+            foundry_bool("optimizer").unwrap_or(false),
+            foundry
+                .and_then(|profile| profile.get("optimizer_runs")?.as_integer())
+                .map_or(200, |runs| runs as u32),
+        ),
+    };
+    let evm_version = overrides.evm_version.clone().or_else(|| foundry_str("evm_version"));
+    if (overrides.optimizer_runs.is_some() || overrides.evm_version.is_some()) && !settings_from.iter().any(|from| from == "command line") {
+        settings_from.push("command line".to_string());
+    }
+    if foundry.is_some() && !settings_from.iter().any(|from| from == "foundry.toml") {
+        settings_from.push("foundry.toml".to_string());
+    }
+
+    let mut settings = json!({
+        "optimizer": { "enabled": optimizer, "runs": runs },
+        "remappings": remappings,
+        "outputSelection": { "*": { "*": ["abi", "evm.bytecode", "evm.deployedBytecode", "metadata"] } },
+    });
+    if let Some(evm_version) = evm_version {
+        settings["evmVersion"] = json!(evm_version);
+    }
+    if foundry_bool("via_ir") == Some(true) {
+        settings["viaIR"] = json!(true);
+    }
+    settings
+}
+
+/// Sources reachable from `entry` by imports, by source unit name
+///
+/// Unit names are paths relative to the project root, as Foundry and
+/// Hardhat name them; package imports that no remapping covers are read
+/// from `node_modules`.
+fn collect_sources(root: &Path, entry: &str, remappings: &[String]) -> Result<BTreeMap<String, String>> {
+    let import_pattern = Regex::new(r#"\bimport\s+[^;]*?["']([^"']+)["']"#).expect("valid import pattern");
+    let remappings: Vec<(&str, &str)> = remappings
+        .iter()
+        .filter_map(|remapping| remapping.split_once('='))
+        // Remappings limited to a context only apply to some importers
+        .filter(|(prefix, _)| !prefix.contains(':'))
+        .collect();
+
+    let mut sources = BTreeMap::new();
+    let mut queue = VecDeque::from([(entry.to_string(), None::<String>)]);
+    while let Some((unit, importer)) = queue.pop_front() {
+        if sources.contains_key(&unit) {
+            continue;
+        }
+        let content = [root.join(&unit), root.join("node_modules").join(&unit)]
+            .iter()
+            .find_map(|path| std::fs::read_to_string(path).ok())
+            .ok_or_else(|| match &importer {
+                Some(importer) => anyhow!("Cannot find {} imported by {} under {}", unit, importer, root.display()),
+                None => anyhow!("Cannot read {}", root.join(&unit).display()),
+            })?;
+
+        for captures in import_pattern.captures_iter(&strip_comments(&content)) {
+            let import = &captures[1];
+            let imported = if import.starts_with("./") || import.starts_with("../") {
+                let directory = unit.rsplit_once('/').map_or("", |(directory, _)| directory);
+                format!("{}/{}", directory, import)
+            } else {
+                // The longest matching prefix wins, as in solc
+                remappings
+                    .iter()
+                    .filter(|(prefix, _)| import.starts_with(prefix))
+                    .max_by_key(|(prefix, _)| prefix.len())
+                    .map_or_else(|| import.to_string(), |(prefix, target)| format!("{}{}", target, &import[prefix.len()..]))
+            };
+            queue.push_back((normalize(&imported), Some(unit.clone())));
+        }
+        sources.insert(unit, content);
+    }
+    Ok(sources)
+}
+
+/// Source unit name of a path relative to the project root
+fn unit_name(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Resolve `.` and `..` segments of a source unit name
+fn normalize(unit: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in unit.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." if segments.last().is_some_and(|last| *last != "..") => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Website of an explorer API, e.g. `https://polygonscan.com` for `https://api.polygonscan.com/api`
+fn explorer_site(explorer_url: &str) -> Option<String> {
+    let (scheme, rest) = explorer_url.split_once("://")?;
+    let host = rest.split('/').next()?;
+    let host = host.strip_prefix("api.").or_else(|| host.strip_prefix("api-")).unwrap_or(host);
+    Some(format!("{}://{}", scheme, host))
+}
+
+#[cfg(feature = "explorers")]
+fn get_json(url: &str, query: &[(&str, &str)]) -> Result<Value> {
+    let mut request = ureq::get(url).timeout(HTTP_TIMEOUT);
+    for (name, value) in query {
+        request = request.query(name, value);
+    }
+    Ok(request.call().map_err(|e| request_error(url, e))?.into_json()?)
+}
+
+#[cfg(feature = "explorers")]
+fn post_form(url: &str, query: &[(&str, &str)], fields: &[(&str, &str)]) -> Result<Value> {
+    let mut request = ureq::post(url).timeout(HTTP_TIMEOUT);
+    for (name, value) in query {
+        request = request.query(name, value);
+    }
+    Ok(request.send_form(fields).map_err(|e| request_error(url, e))?.into_json()?)
+}
+
+/// POST a JSON body, returning the body of error responses too since it carries the reason
+#[cfg(feature = "explorers")]
+fn post_json(url: &str, body: Value) -> Result<Value> {
+    match ureq::post(url).timeout(HTTP_TIMEOUT).send_json(body) {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => Ok(response.into_json()?),
+        Err(e) => Err(request_error(url, e)),
+    }
+}
+
+/// Error naming the endpoint without its query, which holds the API key
+#[cfg(feature = "explorers")]
+fn request_error(url: &str, error: ureq::Error) -> anyhow::Error {
+    match error {
+        ureq::Error::Status(code, _) => anyhow!("{} answered with HTTP {}", url, code),
+        ureq::Error::Transport(transport) => anyhow!(
+            "Could not reach {}: {}",
+            url,
+            transport.message().map_or_else(|| transport.kind().to_string(), str::to_string)
+        ),
+    }
+}
+
+#[cfg(not(feature = "explorers"))]
+fn get_json(url: &str, _query: &[(&str, &str)]) -> Result<Value> {
+    Err(anyhow!("Requests to {} need a build with the `explorers` feature", url))
+}
+
+#[cfg(not(feature = "explorers"))]
+fn post_form(url: &str, _query: &[(&str, &str)], _fields: &[(&str, &str)]) -> Result<Value> {
+    Err(anyhow!("Requests to {} need a build with the `explorers` feature", url))
+}
+
+#[cfg(not(feature = "explorers"))]
+fn post_json(url: &str, _body: Value) -> Result<Value> {
+    Err(anyhow!("Requests to {} need a build with the `explorers` feature", url))
+}
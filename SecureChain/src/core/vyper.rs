@@ -0,0 +1,303 @@
+//! Vyper checks
+//!
+//! The native detectors read Solidity syntax, so on Vyper sources they see
+//! neither modifiers nor braces. These checks cover what matters most in
+//! Vyper code instead: the compiler releases whose `@nonreentrant` locks did
+//! not work, calls that may fail silently, state written after an external
+//! call without a lock, and the arithmetic builtins that skip overflow
+//! checks. They work on the parsed functions of a `.vy` file and run on
+//! every EVM analysis of one, whether Slither is installed or not.
+
+use regex::Regex;
+
+use crate::core::parser::{ParsedContract, UnitFunction};
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+
+pub const TOOL_NAME: &str = "Vyper Checks";
+
+/// Releases whose `@nonreentrant` locks did not protect across functions (GHSA-5824-cm3x-3c38)
+const BROKEN_LOCK_RELEASES: &[(u32, u32, u32)] = &[(0, 2, 15), (0, 2, 16), (0, 3, 0)];
+
+const LOCK_ADVISORY: &str = "https://github.com/vyperlang/vyper/security/advisories/GHSA-5824-cm3x-3c38";
+
+/// Run every Vyper check on one source file
+pub fn run_checks(contract: &ParsedContract) -> Vec<Vulnerability> {
+    let mut findings = Vec::new();
+    findings.extend(pragma_checks(contract));
+
+    let units = contract.units.iter().filter(|unit| unit.kind == "contract");
+    for function in units.flat_map(|unit| unit.constructor.iter().chain(&unit.functions)) {
+        if let Some(body) = function.body.as_deref() {
+            let scope = Scope { contract, function, body };
+            findings.extend(scope.unchecked_raw_call());
+            findings.extend(scope.reentrancy());
+            findings.extend(scope.patterns());
+        }
+    }
+    findings
+}
+
+/// Version pragma findings, and the broken reentrancy lock of the releases it allows
+fn pragma_checks(contract: &ParsedContract) -> Vec<Vulnerability> {
+    let mut findings = Vec::new();
+    let pragma = contract.pragma_directives.iter().find_map(|pragma| pragma.strip_prefix("version ")).map(str::trim);
+    // Explorers name the exact compiler, e.g. `vyper:0.2.15`
+    let compiler = contract.compiler_version.trim_start_matches("vyper:").trim_start_matches('v');
+    let compiler = parse_version(compiler).map(|_| compiler);
+
+    match pragma {
+        Some(pragma) if pragma.starts_with(['^', '>', '~']) => findings.push(finding(
+            &contract.name,
+            1,
+            "Info",
+            0.9,
+            VulnerabilityCategory::CodeQuality,
+            "CWE-1103",
+            format!("Floating Vyper Version: {}", pragma),
+            format!("The version pragma `{}` lets the contract compile with releases it was not tested with.", pragma),
+            "Pin the exact compiler release, e.g. `#pragma version 0.3.10`.",
+            "https://docs.vyperlang.org/en/stable/structure-of-a-contract.html#version-pragma",
+        )),
+        Some(_) => {}
+        None if compiler.is_none() => findings.push(finding(
+            &contract.name,
+            1,
+            "Low",
+            0.8,
+            VulnerabilityCategory::CodeQuality,
+            "CWE-1103",
+            "Missing Vyper Version Pragma".to_string(),
+            "The source does not say which compiler release it is written for, so any installed release compiles it.".to_string(),
+            "Add a version pragma pinning the tested release, e.g. `#pragma version 0.3.10`.",
+            "https://docs.vyperlang.org/en/stable/structure-of-a-contract.html#version-pragma",
+        )),
+        None => {}
+    }
+
+    let locked: Vec<&UnitFunction> = contract
+        .units
+        .iter()
+        .flat_map(|unit| &unit.functions)
+        .filter(|function| function.modifiers.iter().any(|modifier| modifier == "nonreentrant"))
+        .collect();
+    let affected = compiler.or(pragma).map(affected_releases).unwrap_or_default();
+    if let (Some(function), false) = (locked.first(), affected.is_empty()) {
+        findings.push(finding(
+            &contract.name,
+            function.line_number,
+            "Critical",
+            if compiler.is_some() { 0.95 } else { 0.8 },
+            VulnerabilityCategory::Reentrancy,
+            "CWE-667",
+            format!("Broken Vyper Reentrancy Lock: {}", affected.join(", ")),
+            format!(
+                "{} functions rely on `@nonreentrant`, and the contract can be compiled with Vyper {}, whose reentrancy locks did not protect across functions. Contracts compiled with these releases were drained through cross-function reentrancy in July 2023.",
+                locked.len(),
+                affected.join(", ")
+            ),
+            "Compile with Vyper 0.3.1 or later and pin it in the version pragma; redeploy contracts compiled with an affected release.",
+            LOCK_ADVISORY,
+        ));
+    }
+
+    findings
+}
+
+/// Releases with the broken reentrancy lock that a version pragma or compiler version allows
+fn affected_releases(spec: &str) -> Vec<String> {
+    let spec = spec.trim();
+    let (operator, version) = match spec.find(|c: char| c.is_ascii_digit()) {
+        Some(index) => (spec[..index].trim(), &spec[index..]),
+        None => return Vec::new(),
+    };
+    let version = match parse_version(version.split_whitespace().next().unwrap_or("")) {
+        Some(version) => version,
+        None => return Vec::new(),
+    };
+
+    BROKEN_LOCK_RELEASES
+        .iter()
+        .filter(|release| match operator {
+            "" | "==" => **release == version,
+            // `^0.2.15` stays below 0.3.0, `~=0.2.15` below 0.3.0 as well
+            "^" | "~=" => release.0 == version.0 && release.1 == version.1 && **release >= version,
+            ">=" => **release >= version,
+            ">" => **release > version,
+            _ => false,
+        })
+        .map(|(major, minor, patch)| format!("{}.{}.{}", major, minor, patch))
+        .collect()
+}
+
+fn parse_version(text: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = text.trim().split(['.', '+', '-']).map(|part| part.parse::<u32>());
+    Some((parts.next()?.ok()?, parts.next()?.ok()?, parts.next().and_then(Result::ok).unwrap_or(0)))
+}
+
+/// One function, with the file it is in
+struct Scope<'a> {
+    contract: &'a ParsedContract,
+    function: &'a UnitFunction,
+    body: &'a str,
+}
+
+impl Scope<'_> {
+    fn line(&self, offset: usize) -> usize {
+        self.function.body_line_number + self.body[..offset].matches('\n').count()
+    }
+
+    fn is_external(&self) -> bool {
+        self.function.visibility == "external"
+    }
+
+    fn is_locked(&self) -> bool {
+        self.function.modifiers.iter().any(|modifier| modifier == "nonreentrant")
+    }
+
+    /// vyper-unchecked-raw-call: `raw_call` told not to revert, with its result dropped
+    fn unchecked_raw_call(&self) -> Option<Vulnerability> {
+        let call = Regex::new(r"(?m)^\s*raw_call\s*\([^\n]*revert_on_failure\s*=\s*False").expect("valid raw_call pattern");
+        let m = call.find(self.body)?;
+        Some(finding(
+            &self.contract.name,
+            self.line(m.start() + (m.as_str().len() - m.as_str().trim_start().len())),
+            "Medium",
+            0.85,
+            VulnerabilityCategory::UnhandledExceptions,
+            "CWE-252",
+            format!("Unchecked raw_call: {}", self.function.name),
+            format!(
+                "{} calls `raw_call` with `revert_on_failure=False` and ignores whether the call succeeded, so a failed transfer or call goes unnoticed.",
+                self.function.name
+            ),
+            "Keep the success flag and assert it, e.g. `success: bool = raw_call(to, b\"\", value=amount, revert_on_failure=False)` followed by `assert success`.",
+            "https://docs.vyperlang.org/en/stable/built-in-functions.html#raw_call",
+        ))
+    }
+
+    /// vyper-reentrancy: storage written after an external call in an unlocked external function
+    fn reentrancy(&self) -> Option<Vulnerability> {
+        if !self.is_external() || self.is_locked() {
+            return None;
+        }
+        let call = Regex::new(r"\b(?:raw_call|send|extcall)\b|\b[A-Z]\w*\s*\([^()]*\)\s*\.\s*\w+\s*\(").expect("valid call pattern");
+        let write = Regex::new(r"(?m)^\s*self\.\w+(?:\[[^\n]*?\])*(?:\.\w+)*\s*(?:[-+*/%]|\|\||&&)?=[^=]").expect("valid write pattern");
+
+        let first_call = call.find(self.body)?;
+        let written = write.find_at(self.body, first_call.end())?;
+        let variable = written.as_str().trim().trim_start_matches("self.");
+        let variable = variable.split(|c: char| !(c.is_alphanumeric() || c == '_')).next().unwrap_or(variable);
+        Some(finding(
+            &self.contract.name,
+            self.line(first_call.start()),
+            "High",
+            0.7,
+            VulnerabilityCategory::Reentrancy,
+            "CWE-841",
+            format!("Vyper Reentrancy: {}", self.function.name),
+            format!(
+                "{} makes an external call and writes `self.{}` afterwards without `@nonreentrant`, so the callee can re-enter while the state is stale.",
+                self.function.name, variable
+            ),
+            "Update storage before the external call, or add a reentrancy lock (`@nonreentrant` on Vyper 0.4, `@nonreentrant(\"lock\")` before).",
+            "https://docs.vyperlang.org/en/stable/control-structures.html#nonreentrant",
+        ))
+    }
+
+    /// vyper-tx-origin, vyper-unprotected-selfdestruct and vyper-unsafe-math
+    fn patterns(&self) -> Vec<Vulnerability> {
+        let mut findings = Vec::new();
+
+        if let Some(offset) = self.body.find("tx.origin") {
+            findings.push(finding(
+                &self.contract.name,
+                self.line(offset),
+                "High",
+                0.8,
+                VulnerabilityCategory::AccessControl,
+                "CWE-477",
+                format!("Vyper tx.origin Authorization: {}", self.function.name),
+                format!(
+                    "{} reads `tx.origin`, which is the account that started the transaction, so a contract the owner interacts with can act as the owner.",
+                    self.function.name
+                ),
+                "Authorize with `msg.sender` instead of `tx.origin`.",
+                "https://docs.vyperlang.org/en/stable/constants-and-vars.html#environment-variables",
+            ));
+        }
+
+        let destruct = Regex::new(r"\bselfdestruct\s*\(").expect("valid selfdestruct pattern");
+        if let Some(m) = destruct.find(self.body) {
+            if self.is_external() && !self.body.contains("msg.sender") {
+                findings.push(finding(
+                    &self.contract.name,
+                    self.line(m.start()),
+                    "High",
+                    0.8,
+                    VulnerabilityCategory::AccessControl,
+                    "CWE-284",
+                    format!("Unprotected Vyper selfdestruct: {}", self.function.name),
+                    format!("{} is external and calls `selfdestruct` without checking the caller.", self.function.name),
+                    "Assert the caller is authorized first, e.g. `assert msg.sender == self.owner`.",
+                    "https://docs.vyperlang.org/en/stable/built-in-functions.html#selfdestruct",
+                ));
+            }
+        }
+
+        let unsafe_math = Regex::new(r"\bunsafe_(?:add|sub|mul|div)\s*\(").expect("valid unsafe math pattern");
+        if let Some(m) = unsafe_math.find(self.body) {
+            findings.push(finding(
+                &self.contract.name,
+                self.line(m.start()),
+                "Low",
+                0.6,
+                VulnerabilityCategory::IntegerOverflow,
+                "CWE-190",
+                format!("Unchecked Vyper Arithmetic: {}", self.function.name),
+                format!(
+                    "{} uses `{}`, which wraps around instead of reverting on overflow.",
+                    self.function.name,
+                    m.as_str().trim_end_matches(|c: char| c == '(' || c.is_whitespace())
+                ),
+                "Use the checked operators unless the bounds are proven, and document why wrapping cannot happen.",
+                "https://docs.vyperlang.org/en/stable/built-in-functions.html#unsafe_add",
+            ));
+        }
+
+        findings
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn finding(
+    file: &str,
+    line_number: usize,
+    severity: &str,
+    confidence: f64,
+    category: VulnerabilityCategory,
+    cwe_id: &str,
+    title: String,
+    description: String,
+    recommendation: &str,
+    reference: &str,
+) -> Vulnerability {
+    Vulnerability {
+        id: uuid::Uuid::new_v4().to_string(),
+        title,
+        description,
+        severity: severity.to_string(),
+        category,
+        file_path: file.to_string(),
+        line_number: Some(line_number),
+        code_snippet: None,
+        recommendation: Some(recommendation.to_string()),
+        references: vec![reference.to_string()],
+        cwe_id: Some(cwe_id.to_string()),
+        tool: TOOL_NAME.to_string(),
+        confidence,
+        provenance: None,
+        severity_overrides: Vec::new(),
+        cvss: None,
+        detected_by: Vec::new(),
+    }
+}
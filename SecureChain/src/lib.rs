@@ -0,0 +1,15 @@
+//! SecureChain - Universal Web3 Smart Contract Security Auditor
+//!
+//! The analysis engine, parsers, report generators and tool runners behind
+//! the `securechain` binary, for embedding the auditor in other programs.
+//! With the `test-support` feature, [`testing`] adds fixture contracts and
+//! mock tools to test those pipelines without the real toolchain.
+
+pub mod cli;
+pub mod core;
+pub mod plugins;
+pub mod report;
+pub mod utils;
+
+#[cfg(feature = "test-support")]
+pub mod testing;
@@ -8,44 +8,76 @@ use anyhow::{anyhow, Result};
 use colored::*;
 use clap::Parser;
 
-mod cli;
-mod core;
-mod plugins;
-mod report;
-mod utils;
-
-use cli::commands::{execute_command, Cli};
-use utils::config::Config;
+use securechain::cli::commands::{execute_command, Cli};
+use securechain::utils::config::Config;
+use securechain::{status, utils};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
+    // Parse CLI arguments
+    let cli = Cli::parse();
+    utils::output::set_json_mode(cli.json);
+
+    // Initialize logging (always on stderr so stdout stays machine-readable)
     env_logger::Builder::from_default_env()
         .filter_level(log::LevelFilter::Info)
+        .target(env_logger::Target::Stderr)
         .init();
 
     // Display banner
-    display_banner();
+    if !utils::output::is_json_mode() && !cli.is_lightweight() {
+        display_banner();
+    }
 
     // Check if this is first run and setup if needed
-    if !is_setup_complete() {
-        println!("🔧 First-time setup detected. Running automatic setup...");
+    if !cli.is_lightweight() && !is_setup_complete() {
+        status!("🔧 First-time setup detected. Running automatic setup...");
         run_auto_setup().await?;
     }
 
-    // Parse CLI arguments
-    let cli = Cli::parse();
-
     // Load configuration
-    let config = Config::load().unwrap_or_else(|_| Config::default());
+    let mut config = Config::load().unwrap_or_else(|_| Config::default());
+
+    // Keep caches and leftovers of interrupted runs within the retention policy
+    if !cli.is_lightweight() {
+        utils::retention::enforce_on_startup(&config);
+    }
+
+        // Resource budgets from the command line win over the configured ones
+    let budget_flags = [cli.budget.max_disk, cli.budget.max_memory, cli.budget.max_cpu];
+    config.budget.max_disk_mb = cli.budget.max_disk.unwrap_or(config.budget.max_disk_mb);
+    config.budget.max_memory_mb = cli.budget.max_memory.unwrap_or(config.budget.max_memory_mb);
+    config.budget.max_cpu_secs = cli.budget.max_cpu.unwrap_or(config.budget.max_cpu_secs);
+    utils::budget::init(&config.budget, budget_flags.iter().any(Option::is_some))?;
 
     // Execute the command
-    match execute_command(cli, config).await {
+    let lightweight = cli.is_lightweight();
+    let command_name = cli.command_name();
+    let metrics_enabled = config.general.metrics_enabled;
+    let metrics_path = utils::metrics::metrics_path(&config);
+    let started = std::time::Instant::now();
+    let outcome = execute_command(cli, config).await;
+    utils::budget::finish();
+
+    // Viewing the metrics is not usage worth recording
+    if metrics_enabled && command_name != "stats" {
+        if let Err(e) = utils::metrics::record(&metrics_path, command_name, started.elapsed(), outcome.is_ok()) {
+            log::warn!("Could not update usage metrics: {}", e);
+        }
+    }
+
+    match outcome {
         Ok(_) => {
-            println!("\n{} Operation completed successfully!", "✅".green());
+            if !lightweight {
+                status!("\n{} Operation completed successfully!", "✅".green());
+            }
         }
         Err(e) => {
-            eprintln!("\n{} Error: {}", "❌".red(), e);
+            if utils::output::is_json_mode() {
+                let _ = utils::output::emit_json(&serde_json::json!({ "error": e.to_string() }));
+            } else {
+                eprintln!("\n{} Error: {}", "❌".red(), e);
+            }
             std::process::exit(1);
         }
     }
@@ -90,52 +122,42 @@ fn is_setup_complete() -> bool {
     // Check for required tools
     let tools = ["slither", "myth", "echidna-test", "forge"];
 
-    for tool in &tools {
-        if std::process::Command::new(tool)
-            .arg("--version")
-            .output()
-            .is_err()
-        {
-            return false;
-        }
-    }
-
-    true
+    tools.iter().all(|tool| utils::platform::is_tool_available(tool))
 }
 
 /// Check if this is first run and setup if needed
 async fn run_auto_setup() -> Result<()> {
-    println!("🔧 Setting up SecureChain with all required tools...");
+    status!("🔧 Setting up SecureChain with all required tools...");
 
     // Find the setup script in the SecureChain directory
     let current_dir = std::env::current_dir()?;
+    let script_name = utils::platform::setup_script_name();
     let setup_script = if current_dir.file_name().and_then(|n| n.to_str()) == Some("SecureChain") {
-        current_dir.join("setup.sh")
+        current_dir.join(script_name)
     } else {
-        current_dir.join("SecureChain").join("setup.sh")
+        current_dir.join("SecureChain").join(script_name)
     };
 
     if !setup_script.exists() {
-        println!("⚠️  Setup script not found. Creating minimal setup...");
+        status!("⚠️  Setup script not found. Creating minimal setup...");
         create_minimal_setup().await?;
         return Ok(());
     }
 
-    let output = tokio::process::Command::new("bash")
-        .arg(&setup_script)
+    let output = tokio::process::Command::from(utils::platform::script_command(&setup_script))
         .current_dir(setup_script.parent().unwrap())
         .output()
         .await?;
 
     if !output.status.success() {
         let error = String::from_utf8_lossy(&output.stderr);
-        println!("⚠️  Full setup failed: {}", error);
-        println!("🔄 Running minimal setup instead...");
+        status!("⚠️  Full setup failed: {}", error);
+        status!("🔄 Running minimal setup instead...");
         create_minimal_setup().await?;
         return Ok(());
     }
 
-    println!("✅ Setup completed successfully!");
+    status!("✅ Setup completed successfully!");
 
     // Create setup marker
     let config_dir = dirs::config_dir()
@@ -150,7 +172,7 @@ async fn run_auto_setup() -> Result<()> {
 
 /// Create minimal setup when full setup fails
 async fn create_minimal_setup() -> Result<()> {
-    println!("📦 Creating minimal SecureChain setup...");
+    status!("📦 Creating minimal SecureChain setup...");
 
     // Create config directory
     let config_dir = dirs::config_dir()
@@ -184,10 +206,10 @@ verbose = false
     std::fs::write(config_dir.join("config.toml"), default_config)?;
     std::fs::write(config_dir.join(".setup_complete"), "minimal")?;
 
-    println!("✅ Minimal setup completed!");
-    println!("💡 For full functionality, install tools manually:");
-    println!("   - pip install slither-analyzer mythril");
-    println!("   - npm install -g solhint");
+    status!("✅ Minimal setup completed!");
+    status!("💡 For full functionality, install tools manually:");
+    status!("   - pip install slither-analyzer mythril");
+    status!("   - npm install -g solhint");
 
     Ok(())
 }
\ No newline at end of file
@@ -4,6 +4,7 @@
 //! used on StarkNet and other Cairo-based blockchains.
 
 use anyhow::Result;
+use async_trait::async_trait;
 use std::process::Command;
 
 use crate::core::parser::ParsedContract;
@@ -83,6 +84,10 @@ impl CairoPlugin {
                 cwe_id: Some("CWE-190".to_string()),
                 tool: "Cairo Plugin".to_string(),
                 confidence: 0.6,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -102,6 +107,10 @@ impl CairoPlugin {
                 cwe_id: None,
                 tool: "Cairo Plugin".to_string(),
                 confidence: 0.5,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -130,6 +139,10 @@ impl CairoPlugin {
                     cwe_id: Some("CWE-665".to_string()),
                     tool: "Cairo Plugin".to_string(),
                     confidence: 0.7,
+                    provenance: None,
+                    severity_overrides: Vec::new(),
+                    cvss: None,
+                    detected_by: Vec::new(),
                 });
             }
         }
@@ -150,6 +163,10 @@ impl CairoPlugin {
                 cwe_id: None,
                 tool: "Cairo Plugin".to_string(),
                 confidence: 0.4,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -176,6 +193,10 @@ impl CairoPlugin {
                 cwe_id: Some("CWE-862".to_string()),
                 tool: "Cairo Plugin".to_string(),
                 confidence: 0.8,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -195,6 +216,10 @@ impl CairoPlugin {
                 cwe_id: Some("CWE-362".to_string()),
                 tool: "Cairo Plugin".to_string(),
                 confidence: 0.7,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -224,6 +249,10 @@ impl CairoPlugin {
                     cwe_id: None,
                     tool: "Cairo Plugin".to_string(),
                     confidence: 0.5,
+                    provenance: None,
+                    severity_overrides: Vec::new(),
+                    cvss: None,
+                    detected_by: Vec::new(),
                 });
             }
         }
@@ -244,6 +273,10 @@ impl CairoPlugin {
                 cwe_id: Some("CWE-20".to_string()),
                 tool: "Cairo Plugin".to_string(),
                 confidence: 0.6,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -270,6 +303,10 @@ impl CairoPlugin {
                 cwe_id: None,
                 tool: "Cairo Plugin".to_string(),
                 confidence: 0.3,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -289,6 +326,10 @@ impl CairoPlugin {
                 cwe_id: None,
                 tool: "Cairo Plugin".to_string(),
                 confidence: 0.2,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -296,6 +337,7 @@ impl CairoPlugin {
     }
 }
 
+#[async_trait]
 impl BlockchainPlugin for CairoPlugin {
     fn name(&self) -> &'static str {
         "Cairo"
@@ -305,14 +347,9 @@ impl BlockchainPlugin for CairoPlugin {
         vec!["cairo"]
     }
 
-    fn analyze_contract(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
-        let mut vulnerabilities = Vec::new();
-
-        // Run Cairo-specific analysis
-        vulnerabilities.extend(self.run_cairo_analysis(contract)?);
-        vulnerabilities.extend(self.check_cairo_best_practices(contract)?);
-
-        Ok(vulnerabilities)
+    async fn analyze_contract(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        // No external tool is integrated yet, so the pattern checks are the whole analysis
+        self.internal_checks(contract)
     }
 
     fn validate_contract(&self, contract: &ParsedContract) -> Result<bool> {
@@ -332,6 +369,16 @@ impl BlockchainPlugin for CairoPlugin {
     fn get_analysis_tools(&self) -> Vec<&'static str> {
         self.tools.clone()
     }
+
+    fn internal_checks(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        let mut vulnerabilities = Vec::new();
+
+        // Run Cairo-specific analysis
+        vulnerabilities.extend(self.run_cairo_analysis(contract)?);
+        vulnerabilities.extend(self.check_cairo_best_practices(contract)?);
+
+        Ok(vulnerabilities)
+    }
 }
 
 impl Default for CairoPlugin {
@@ -1,32 +1,50 @@
 //! EVM (Ethereum Virtual Machine) plugin for smart contract analysis
 //! 
-//! This plugin provides analysis capabilities for Solidity smart contracts
-//! running on EVM-compatible blockchains like Ethereum, Polygon, Arbitrum, etc.
+//! This plugin provides analysis capabilities for Solidity and Vyper smart
+//! contracts running on EVM-compatible blockchains like Ethereum, Polygon,
+//! Arbitrum, etc. Vyper sources get the Vyper checks instead of the Solidity
+//! pattern checks.
 
 use anyhow::Result;
-use std::process::Command;
-use tokio::process::Command as AsyncCommand;
+use async_trait::async_trait;
 
+use crate::core::bytecode::{self, BytecodeAnalysis};
+use crate::core::gas;
 use crate::core::parser::ParsedContract;
+use crate::core::selectors;
+use crate::core::simulation::{self, SimulationContext};
+use crate::core::vyper;
 use crate::plugins::BlockchainPlugin;
 use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+use crate::utils::abi::decode_hex;
+use crate::utils::budget;
+use crate::utils::config::SlitherConfig;
+use crate::utils::platform;
+use crate::utils::retention;
 
 /// EVM plugin for analyzing Solidity smart contracts
 pub struct EVMPlugin {
     tools: Vec<&'static str>,
+    slither: SlitherConfig,
 }
 
 impl EVMPlugin {
     /// Create a new EVM plugin
     pub fn new() -> Self {
+        Self::with_slither(SlitherConfig::default())
+    }
+
+    /// Create an EVM plugin running Slither with `slither`'s executable, detectors and options
+    pub fn with_slither(slither: SlitherConfig) -> Self {
         Self {
-            tools: vec!["slither", "mythril", "echidna", "foundry", "solhint"],
+            tools: vec!["slither", "mythril", "echidna", "foundry", "solhint", "vyper"],
+            slither,
         }
     }
 
     /// Check if Slither is available
     pub fn is_slither_available(&self) -> bool {
-        Command::new("slither")
+        platform::command("slither")
             .arg("--version")
             .output()
             .map(|output| output.status.success())
@@ -35,7 +53,7 @@ impl EVMPlugin {
 
     /// Check if Mythril is available
     pub fn is_mythril_available(&self) -> bool {
-        Command::new("myth")
+        platform::command("myth")
             .arg("version")
             .output()
             .map(|output| output.status.success())
@@ -44,7 +62,7 @@ impl EVMPlugin {
 
     /// Check if Echidna is available
     pub fn is_echidna_available(&self) -> bool {
-        Command::new("echidna-test")
+        platform::command("echidna-test")
             .arg("--version")
             .output()
             .map(|output| output.status.success())
@@ -55,22 +73,32 @@ impl EVMPlugin {
     async fn run_slither_analysis(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
         let mut vulnerabilities = Vec::new();
 
-        if !self.is_slither_available() {
+        // Probe without blocking the runtime's worker thread
+        let available = platform::async_command(&self.slither.executable)
+            .arg("--version")
+            .output()
+            .await
+            .map(|output| output.status.success())
+            .unwrap_or(false);
+        if !available {
             log::warn!("Slither not available, skipping static analysis");
             return Ok(vulnerabilities);
         }
 
-        // Create temporary file for analysis
-        let temp_file = tempfile::NamedTempFile::new()?;
-        std::fs::write(temp_file.path(), &contract.source_code)?;
+        // Create temporary file for analysis; crytic-compile picks the compiler by extension
+        let suffix = if contract.is_vyper() { ".vy" } else { ".sol" };
+        let temp_file = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).suffix(suffix).tempfile()?;
+        std::fs::write(temp_file.path(), contract.source_code.as_bytes())?;
 
-        // Run Slither with JSON output
-        let output = AsyncCommand::new("slither")
+        // Run Slither with JSON output; a plugin sees one file, so a configured framework has no project to compile
+        let mut command = platform::async_command(&self.slither.executable);
+        command
             .arg(temp_file.path())
-            .arg("--json")
-            .arg("-")
-            .output()
-            .await?;
+            .args(self.slither.arguments(&self.slither.include_detectors, &self.slither.exclude_detectors));
+        if contract.is_vyper() {
+            command.arg("--compile-force-framework").arg("vyper");
+        }
+        let output = budget::run(&mut command, Some(std::time::Duration::from_secs(self.slither.timeout))).await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -170,6 +198,10 @@ impl EVMPlugin {
             cwe_id: self.get_cwe_id(check),
             tool: "Slither".to_string(),
             confidence: self.map_confidence(confidence),
+            provenance: None,
+            severity_overrides: Vec::new(),
+            cvss: None,
+            detected_by: Vec::new(),
         })
     }
 
@@ -267,6 +299,9 @@ impl EVMPlugin {
 
     /// Run basic syntax and semantic checks
     fn run_basic_checks(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        if contract.is_vyper() {
+            return Ok(vyper::run_checks(contract));
+        }
         let mut vulnerabilities = Vec::new();
 
         // Check for common anti-patterns
@@ -285,6 +320,10 @@ impl EVMPlugin {
                 cwe_id: Some("CWE-477".to_string()),
                 tool: "EVM Plugin".to_string(),
                 confidence: 0.9,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -304,6 +343,10 @@ impl EVMPlugin {
                 cwe_id: None,
                 tool: "EVM Plugin".to_string(),
                 confidence: 0.7,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -323,6 +366,10 @@ impl EVMPlugin {
                 cwe_id: Some("CWE-252".to_string()),
                 tool: "EVM Plugin".to_string(),
                 confidence: 0.8,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -343,14 +390,327 @@ impl EVMPlugin {
                     cwe_id: Some("CWE-400".to_string()),
                     tool: "EVM Plugin".to_string(),
                     confidence: 0.6,
+                    provenance: None,
+                    severity_overrides: Vec::new(),
+                    cvss: None,
+                    detected_by: Vec::new(),
                 });
             }
         }
 
+        vulnerabilities.extend(gas::check_gas(contract));
+
         Ok(vulnerabilities)
     }
 }
 
+/// An oracle answer this much older than the latest block is stale (seconds)
+const ORACLE_STALENESS: u64 = 24 * 3600;
+
+impl EVMPlugin {
+    /// Who the owner of a live contract actually is: nobody, or a single key
+    fn check_owner(&self, context: &SimulationContext) -> Result<Vec<Vulnerability>> {
+        // A contract deployed for the run is owned by the simulation's deployer, which says nothing
+        if context.deployed {
+            return Ok(Vec::new());
+        }
+        let owner = match context.address_getter("owner()") {
+            Some(owner) => owner,
+            None => return Ok(Vec::new()),
+        };
+        let privileged: Vec<&str> = context
+            .contract
+            .functions
+            .iter()
+            .filter(|function| function.modifiers.iter().any(|modifier| modifier.starts_with("onlyOwner")))
+            .map(|function| function.name.as_str())
+            .collect();
+        if privileged.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let finding = if owner.trim_start_matches("0x").chars().all(|c| c == '0') {
+            Some((
+                "Ownership renounced".to_string(),
+                format!(
+                    "{} has no owner at block {}, so its owner-only functions ({}) can never be called again.",
+                    context.location(),
+                    context.simulation.block_number()?,
+                    privileged.join(", ")
+                ),
+                "Info",
+                "Make sure nothing the protocol needs, such as parameter updates or emergency pauses, is owner-only.",
+            ))
+        } else if context.simulation.code_at(&owner)?.is_empty() {
+            Some((
+                "Owner is a single externally owned account".to_string(),
+                format!(
+                    "The owner of {} is {}, an account without code, so one private key controls {}.",
+                    context.location(),
+                    owner,
+                    privileged.join(", ")
+                ),
+                "Medium",
+                "Transfer ownership to a multisig or a timelock so no single key can act alone.",
+            ))
+        } else {
+            None
+        };
+
+        Ok(finding
+            .into_iter()
+            .map(|(title, description, severity, recommendation)| Vulnerability {
+                id: uuid::Uuid::new_v4().to_string(),
+                title,
+                description,
+                severity: severity.to_string(),
+                category: VulnerabilityCategory::AccessControl,
+                file_path: context.contract.name.clone(),
+                line_number: None,
+                code_snippet: None,
+                recommendation: Some(recommendation.to_string()),
+                references: vec!["Observed on a simulation node".to_string()],
+                cwe_id: Some("CWE-284".to_string()),
+                tool: simulation::TOOL_NAME.to_string(),
+                confidence: 0.95,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
+            })
+            .collect())
+    }
+
+    /// What the contract's Chainlink-style feeds answer now: a price of zero or below, or a stale one
+    fn check_oracles(&self, context: &SimulationContext) -> Result<Vec<Vulnerability>> {
+        let mut vulnerabilities = Vec::new();
+        let feeds = context
+            .contract
+            .state_variables
+            .iter()
+            .filter(|variable| variable.visibility == "public" && variable.type_name.contains("Aggregator"));
+        for feed in feeds {
+            let address = match context.address_getter(&format!("{}()", feed.name)) {
+                Some(address) => address,
+                None => continue,
+            };
+            let selector = decode_hex(&selectors::selector("latestRoundData()")).unwrap_or_default();
+            // (roundId, answer, startedAt, updatedAt, answeredInRound)
+            let round = match context.simulation.call(&address, &selector) {
+                Ok(round) if round.len() >= 160 => round,
+                _ => continue,
+            };
+            let negative = round[32] & 0x80 != 0;
+            let zero = round[32..64].iter().all(|byte| *byte == 0);
+            let updated_at = u64::from_be_bytes(round[120..128].try_into().unwrap_or_default());
+            let age = context.simulation.timestamp()?.saturating_sub(updated_at);
+
+            let problem = if negative || zero {
+                Some(("answers a price of zero or below", "High"))
+            } else if age > ORACLE_STALENESS {
+                Some(("answers a stale price", "Medium"))
+            } else {
+                None
+            };
+            if let Some((problem, severity)) = problem {
+                vulnerabilities.push(Vulnerability {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    title: format!("Oracle {} {}", feed.name, problem),
+                    description: format!(
+                        "The feed {} of {} at {} {} at block {}: its last update was {} hours before the block.",
+                        feed.name,
+                        context.location(),
+                        address,
+                        problem,
+                        context.simulation.block_number()?,
+                        age / 3600
+                    ),
+                    severity: severity.to_string(),
+                    category: VulnerabilityCategory::InputValidation,
+                    file_path: context.contract.name.clone(),
+                    line_number: Some(feed.line_number),
+                    code_snippet: None,
+                    recommendation: Some(
+                        "Check that the answer is positive and that updatedAt is within the feed's heartbeat before using a price.".to_string(),
+                    ),
+                    references: vec!["https://docs.chain.link/data-feeds#check-the-timestamp-of-the-latest-answer".to_string()],
+                    cwe_id: Some("CWE-20".to_string()),
+                    tool: simulation::TOOL_NAME.to_string(),
+                    confidence: 0.9,
+                    provenance: None,
+                    severity_overrides: Vec::new(),
+                    cvss: None,
+                    detected_by: Vec::new(),
+                });
+            }
+        }
+        Ok(vulnerabilities)
+    }
+}
+
+/// Functions whose callers decide over funds, code or users; bytecode cannot show who that is
+const PRIVILEGED_FUNCTIONS: &[&str] = &[
+    "mint", "burn", "burnFrom", "upgradeTo", "upgradeToAndCall", "pause", "unpause", "setOwner", "changeOwner",
+    "changeAdmin", "blacklist", "addBlacklist", "setFee", "setFees", "setMaxTxAmount", "withdraw", "withdrawAll",
+    "emergencyWithdraw", "rescueTokens", "sweep", "execute", "setPrice", "setOracle", "diamondCut", "kill", "destroy",
+];
+
+impl EVMPlugin {
+    /// Findings on an unverified contract: the dangerous opcodes its code contains and the privileged functions it exposes
+    fn check_bytecode(&self, analysis: &BytecodeAnalysis) -> Vec<Vulnerability> {
+        let mut findings = Vec::new();
+        let located = |opcode: &str| {
+            let offsets: Vec<String> = analysis.offsets(opcode).iter().take(5).map(|offset| format!("{:#06x}", offset)).collect();
+            format!("{} at {}", opcode, offsets.join(", "))
+        };
+
+        let selfdestruct = !analysis.offsets("SELFDESTRUCT").is_empty();
+        let create2 = !analysis.offsets("CREATE2").is_empty();
+        if selfdestruct {
+            findings.push((
+                "Contract can self-destruct".to_string(),
+                format!(
+                    "The code of {} contains SELFDESTRUCT. Whoever reaches it can send away the contract's Ether, and on chains before Cancun delete its code.",
+                    analysis.address
+                ),
+                "High",
+                VulnerabilityCategory::AccessControl,
+                Some(located("SELFDESTRUCT")),
+                "Verify the source to show who can reach SELFDESTRUCT, or treat the contract as removable by its operator.",
+                "CWE-284",
+                vec!["https://swcregistry.io/docs/SWC-106"],
+            ));
+        }
+        if selfdestruct && create2 {
+            findings.push((
+                "Metamorphic contract pattern".to_string(),
+                format!(
+                    "{} uses both CREATE2 and SELFDESTRUCT, the pattern of metamorphic contracts: code it deploys can be destroyed and redeployed to the same address with different behaviour.",
+                    analysis.address
+                ),
+                "High",
+                VulnerabilityCategory::AccessControl,
+                Some(located("CREATE2")),
+                "Do not rely on the code at addresses this contract deploys staying the same; check their code hash before each interaction.",
+                "CWE-913",
+                vec!["https://a16zcrypto.com/posts/article/metamorphic-smart-contract-detector-tool/"],
+            ));
+        } else if create2 {
+            findings.push((
+                "Contract deploys with CREATE2".to_string(),
+                format!("{} deploys contracts to precomputed addresses with CREATE2.", analysis.address),
+                "Info",
+                VulnerabilityCategory::CodeQuality,
+                Some(located("CREATE2")),
+                "Check what is deployed and whether anyone can front-run or predict the deployment.",
+                "CWE-913",
+                Vec::new(),
+            ));
+        }
+
+        match &analysis.minimal_proxy {
+            Some(implementation) => findings.push((
+                "Minimal proxy".to_string(),
+                format!(
+                    "{} is an EIP-1167 minimal proxy: every call runs the code of {} on this contract's storage.",
+                    analysis.address, implementation
+                ),
+                "Info",
+                VulnerabilityCategory::LowLevelCalls,
+                None,
+                "Audit the implementation, which holds all of this contract's logic.",
+                "CWE-829",
+                vec!["https://eips.ethereum.org/EIPS/eip-1167"],
+            )),
+            None if !analysis.offsets("DELEGATECALL").is_empty() => findings.push((
+                "Delegatecall to other code".to_string(),
+                format!(
+                    "{} runs other contracts' code on its own storage with DELEGATECALL, as proxies and libraries do. If the target can be chosen or upgraded by someone, so can everything the contract does.",
+                    analysis.address
+                ),
+                "Medium",
+                VulnerabilityCategory::LowLevelCalls,
+                Some(located("DELEGATECALL")),
+                "Find out where the delegatecall target comes from and who can change it.",
+                "CWE-829",
+                vec!["https://swcregistry.io/docs/SWC-112"],
+            )),
+            None => {}
+        }
+
+        if !analysis.offsets("CALLCODE").is_empty() {
+            findings.push((
+                "Deprecated CALLCODE".to_string(),
+                format!("{} uses CALLCODE, which runs other code on its storage with a misleading msg.sender.", analysis.address),
+                "Medium",
+                VulnerabilityCategory::LowLevelCalls,
+                Some(located("CALLCODE")),
+                "Treat CALLCODE like DELEGATECALL and find out where its target comes from.",
+                "CWE-477",
+                vec!["https://swcregistry.io/docs/SWC-112"],
+            ));
+        }
+        if !analysis.offsets("ORIGIN").is_empty() {
+            findings.push((
+                "tx.origin used".to_string(),
+                format!(
+                    "{} reads tx.origin. If it authorizes callers with it, a contract the owner interacts with can act in the owner's name.",
+                    analysis.address
+                ),
+                "Medium",
+                VulnerabilityCategory::AccessControl,
+                Some(located("ORIGIN")),
+                "Verify the source to see whether tx.origin is used for authorization.",
+                "CWE-477",
+                vec!["https://swcregistry.io/docs/SWC-115"],
+            ));
+        }
+
+        let privileged = analysis.functions_named(PRIVILEGED_FUNCTIONS);
+        if !privileged.is_empty() {
+            findings.push((
+                "Privileged functions in an unverified contract".to_string(),
+                format!(
+                    "{} exposes {}. Without source, who may call them cannot be checked.",
+                    analysis.address,
+                    privileged.join(", ")
+                ),
+                "Info",
+                VulnerabilityCategory::AccessControl,
+                None,
+                "Ask the operator to verify the source, or trace the access checks of these functions in the bytecode.",
+                "CWE-284",
+                Vec::new(),
+            ));
+        }
+
+        findings
+            .into_iter()
+            .map(|(title, description, severity, category, code_snippet, recommendation, cwe, references)| Vulnerability {
+                id: uuid::Uuid::new_v4().to_string(),
+                title,
+                description,
+                severity: severity.to_string(),
+                category,
+                file_path: analysis.address.clone(),
+                line_number: None,
+                code_snippet,
+                recommendation: Some(recommendation.to_string()),
+                references: references.into_iter().map(str::to_string).collect(),
+                cwe_id: Some(cwe.to_string()),
+                tool: bytecode::TOOL_NAME.to_string(),
+                // An opcode in the code says nothing of who can reach it
+                confidence: 0.6,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
 impl BlockchainPlugin for EVMPlugin {
     fn name(&self) -> &'static str {
         "EVM"
@@ -360,18 +720,16 @@ impl BlockchainPlugin for EVMPlugin {
         vec!["solidity", "vyper"]
     }
 
-    fn analyze_contract(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+    async fn analyze_contract(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
         let mut vulnerabilities = Vec::new();
 
         // Run basic checks
         vulnerabilities.extend(self.run_basic_checks(contract)?);
 
-        // Run Slither analysis if available
-        if self.is_slither_available() {
-            match tokio::runtime::Runtime::new()?.block_on(self.run_slither_analysis(contract)) {
-                Ok(slither_vulns) => vulnerabilities.extend(slither_vulns),
-                Err(e) => log::warn!("Slither analysis failed: {}", e),
-            }
+        // Run Slither analysis if available, on the caller's runtime
+        match self.run_slither_analysis(contract).await {
+            Ok(slither_vulns) => vulnerabilities.extend(slither_vulns),
+            Err(e) => log::warn!("Slither analysis failed: {}", e),
         }
 
         Ok(vulnerabilities)
@@ -383,8 +741,8 @@ impl BlockchainPlugin for EVMPlugin {
             return Ok(false);
         }
 
-        // Check for valid Solidity syntax (basic check)
-        if !contract.source_code.contains("pragma solidity") && !contract.source_code.contains("contract") {
+        // Check for valid Solidity syntax (basic check); Vyper files are whole contracts
+        if !contract.is_vyper() && !contract.source_code.contains("pragma solidity") && !contract.source_code.contains("contract") {
             return Ok(false);
         }
 
@@ -399,6 +757,21 @@ impl BlockchainPlugin for EVMPlugin {
     fn get_analysis_tools(&self) -> Vec<&'static str> {
         self.tools.clone()
     }
+
+    fn internal_checks(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        self.run_basic_checks(contract)
+    }
+
+    fn dynamic_checks(&self, context: &SimulationContext) -> Result<Vec<Vulnerability>> {
+        let mut vulnerabilities = Vec::new();
+        vulnerabilities.extend(self.check_owner(context)?);
+        vulnerabilities.extend(self.check_oracles(context)?);
+        Ok(vulnerabilities)
+    }
+
+    fn bytecode_checks(&self, analysis: &BytecodeAnalysis) -> Result<Vec<Vulnerability>> {
+        Ok(self.check_bytecode(analysis))
+    }
 }
 
 impl Default for EVMPlugin {
@@ -4,9 +4,11 @@
 //! used on Polkadot and Substrate-based blockchains.
 
 use anyhow::Result;
+use async_trait::async_trait;
 use std::process::Command;
 
 use crate::core::parser::ParsedContract;
+use crate::plugins::rust_analysis::{self, RustDialect};
 use crate::plugins::BlockchainPlugin;
 use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
 
@@ -75,6 +77,10 @@ impl InkPlugin {
                 cwe_id: None,
                 tool: "Ink Plugin".to_string(),
                 confidence: 0.9,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -94,6 +100,10 @@ impl InkPlugin {
                 cwe_id: None,
                 tool: "Ink Plugin".to_string(),
                 confidence: 0.9,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -113,6 +123,10 @@ impl InkPlugin {
                 cwe_id: None,
                 tool: "Ink Plugin".to_string(),
                 confidence: 0.7,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -139,6 +153,10 @@ impl InkPlugin {
                 cwe_id: None,
                 tool: "Ink Plugin".to_string(),
                 confidence: 0.6,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -158,6 +176,10 @@ impl InkPlugin {
                 cwe_id: None,
                 tool: "Ink Plugin".to_string(),
                 confidence: 0.8,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -184,6 +206,10 @@ impl InkPlugin {
                 cwe_id: Some("CWE-862".to_string()),
                 tool: "Ink Plugin".to_string(),
                 confidence: 0.7,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -203,6 +229,10 @@ impl InkPlugin {
                 cwe_id: None,
                 tool: "Ink Plugin".to_string(),
                 confidence: 0.6,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -222,6 +252,10 @@ impl InkPlugin {
                 cwe_id: None,
                 tool: "Ink Plugin".to_string(),
                 confidence: 0.5,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -250,6 +284,10 @@ impl InkPlugin {
                     cwe_id: None,
                     tool: "Ink Plugin".to_string(),
                     confidence: 0.4,
+                    provenance: None,
+                    severity_overrides: Vec::new(),
+                    cvss: None,
+                    detected_by: Vec::new(),
                 });
             }
         }
@@ -270,6 +308,10 @@ impl InkPlugin {
                 cwe_id: None,
                 tool: "Ink Plugin".to_string(),
                 confidence: 0.3,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -296,6 +338,10 @@ impl InkPlugin {
                 cwe_id: None,
                 tool: "Ink Plugin".to_string(),
                 confidence: 0.3,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -315,34 +361,43 @@ impl InkPlugin {
                 cwe_id: None,
                 tool: "Ink Plugin".to_string(),
                 confidence: 0.5,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
         // Check for overflow protection
-        if contract.source_code.contains("u8") || contract.source_code.contains("u32") || contract.source_code.contains("u64") {
-            if !contract.source_code.contains("checked_add") && !contract.source_code.contains("saturating_add") {
-                vulnerabilities.push(Vulnerability {
-                    id: uuid::Uuid::new_v4().to_string(),
-                    title: "Potential Integer Overflow".to_string(),
-                    description: "Consider using checked arithmetic operations.".to_string(),
-                    severity: "Medium".to_string(),
-                    category: VulnerabilityCategory::IntegerOverflow,
-                    file_path: contract.name.clone(),
-                    line_number: None,
-                    code_snippet: None,
-                    recommendation: Some("Use checked_add, saturating_add, or similar safe arithmetic operations.".to_string()),
-                    references: vec!["https://doc.rust-lang.org/std/primitive.u32.html#method.checked_add".to_string()],
-                    cwe_id: Some("CWE-190".to_string()),
-                    tool: "Ink Plugin".to_string(),
-                    confidence: 0.6,
-                });
-            }
+        if (contract.source_code.contains("u8") || contract.source_code.contains("u32") || contract.source_code.contains("u64"))
+            && !contract.source_code.contains("checked_add") && !contract.source_code.contains("saturating_add")
+        {
+            vulnerabilities.push(Vulnerability {
+                id: uuid::Uuid::new_v4().to_string(),
+                title: "Potential Integer Overflow".to_string(),
+                description: "Consider using checked arithmetic operations.".to_string(),
+                severity: "Medium".to_string(),
+                category: VulnerabilityCategory::IntegerOverflow,
+                file_path: contract.name.clone(),
+                line_number: None,
+                code_snippet: None,
+                recommendation: Some("Use checked_add, saturating_add, or similar safe arithmetic operations.".to_string()),
+                references: vec!["https://doc.rust-lang.org/std/primitive.u32.html#method.checked_add".to_string()],
+                cwe_id: Some("CWE-190".to_string()),
+                tool: "Ink Plugin".to_string(),
+                confidence: 0.6,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
+            });
         }
 
         Ok(vulnerabilities)
     }
 }
 
+#[async_trait]
 impl BlockchainPlugin for InkPlugin {
     fn name(&self) -> &'static str {
         "Ink!"
@@ -352,14 +407,9 @@ impl BlockchainPlugin for InkPlugin {
         vec!["ink", "rust"]
     }
 
-    fn analyze_contract(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
-        let mut vulnerabilities = Vec::new();
-
-        // Run Ink!-specific analysis
-        vulnerabilities.extend(self.run_ink_analysis(contract)?);
-        vulnerabilities.extend(self.check_ink_best_practices(contract)?);
-
-        Ok(vulnerabilities)
+    async fn analyze_contract(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        // No external tool is integrated yet, so the pattern checks are the whole analysis
+        self.internal_checks(contract)
     }
 
     fn validate_contract(&self, contract: &ParsedContract) -> Result<bool> {
@@ -379,6 +429,17 @@ impl BlockchainPlugin for InkPlugin {
     fn get_analysis_tools(&self) -> Vec<&'static str> {
         self.tools.clone()
     }
+
+    fn internal_checks(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        let mut vulnerabilities = Vec::new();
+
+        // Run Ink!-specific analysis
+        vulnerabilities.extend(self.run_ink_analysis(contract)?);
+        vulnerabilities.extend(self.check_ink_best_practices(contract)?);
+        vulnerabilities.extend(rust_analysis::analyze(contract, RustDialect::Ink, "Ink Plugin"));
+
+        Ok(vulnerabilities)
+    }
 }
 
 impl Default for InkPlugin {
@@ -2,26 +2,52 @@
 //! 
 //! This module provides a pluggable architecture for supporting
 //! different blockchain platforms and smart contract languages.
+//!
+//! Plugins run on the analysis engine's tokio runtime: `analyze_contract` is
+//! async and awaits external tools, so a plugin never starts a runtime of its
+//! own. Pattern checks that need no tools stay synchronous in `internal_checks`
+//! for callers outside the runtime, like the pre-commit hook.
 
 pub mod evm;
 pub mod move_lang;
 pub mod cairo;
 pub mod ink;
+pub mod rust_analysis;
+pub mod solana;
 
 use anyhow::Result;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::core::bytecode::BytecodeAnalysis;
 use crate::core::parser::ParsedContract;
+use crate::core::simulation::SimulationContext;
 use crate::report::vulnerability::Vulnerability;
+use crate::utils::config::Config;
 
 /// Plugin trait for blockchain-specific analysis
-pub trait BlockchainPlugin {
+#[async_trait]
+pub trait BlockchainPlugin: Send + Sync {
     fn name(&self) -> &'static str;
     fn supported_languages(&self) -> Vec<&'static str>;
-    fn analyze_contract(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>>;
+    /// Run the built-in detectors and any available external tools
+    async fn analyze_contract(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>>;
     fn validate_contract(&self, contract: &ParsedContract) -> Result<bool>;
     fn get_analysis_tools(&self) -> Vec<&'static str>;
+
+    /// Run only the built-in detectors, without spawning external tools
+    fn internal_checks(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>>;
+
+    /// Run checks that depend on chain state against the contract on a simulation node
+    fn dynamic_checks(&self, _context: &SimulationContext) -> Result<Vec<Vulnerability>> {
+        Ok(Vec::new())
+    }
+
+    /// Run the checks possible on deployed bytecode alone, for contracts without verified source
+    fn bytecode_checks(&self, _analysis: &BytecodeAnalysis) -> Result<Vec<Vulnerability>> {
+        Ok(Vec::new())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,10 +75,16 @@ impl PluginManager {
         plugins.insert("move".to_string(), Box::new(move_lang::MovePlugin::new()));
         plugins.insert("cairo".to_string(), Box::new(cairo::CairoPlugin::new()));
         plugins.insert("ink".to_string(), Box::new(ink::InkPlugin::new()));
+        plugins.insert("solana".to_string(), Box::new(solana::SolanaPlugin::new()));
 
         Self { plugins }
     }
 
+    /// Apply the tool settings of `config` to the built-in plugins
+    pub fn configure(&mut self, config: &Config) {
+        self.plugins.insert("evm".to_string(), Box::new(evm::EVMPlugin::with_slither(config.tools.slither.clone())));
+    }
+
     /// Get available plugins
     pub fn get_available_plugins(&self) -> Vec<PluginInfo> {
         self.plugins
@@ -69,14 +101,14 @@ impl PluginManager {
     }
 
     /// Get plugin by name
-    pub fn get_plugin(&self, name: &str) -> Option<&Box<dyn BlockchainPlugin>> {
-        self.plugins.get(name)
+    pub fn get_plugin(&self, name: &str) -> Option<&dyn BlockchainPlugin> {
+        self.plugins.get(name).map(|plugin| plugin.as_ref())
     }
 
     /// Analyze contract using appropriate plugin
-    pub fn analyze_contract(&self, contract: &ParsedContract, target_platform: &str) -> Result<Vec<Vulnerability>> {
+    pub async fn analyze_contract(&self, contract: &ParsedContract, target_platform: &str) -> Result<Vec<Vulnerability>> {
         if let Some(plugin) = self.plugins.get(target_platform) {
-            plugin.analyze_contract(contract)
+            plugin.analyze_contract(contract).await
         } else {
             Err(anyhow::anyhow!("Plugin not found for platform: {}", target_platform))
         }
@@ -91,6 +123,33 @@ impl PluginManager {
         }
     }
 
+    /// Run the built-in detectors of the appropriate plugin
+    pub fn internal_checks(&self, contract: &ParsedContract, target_platform: &str) -> Result<Vec<Vulnerability>> {
+        if let Some(plugin) = self.plugins.get(target_platform) {
+            plugin.internal_checks(contract)
+        } else {
+            Err(anyhow::anyhow!("Plugin not found for platform: {}", target_platform))
+        }
+    }
+
+    /// Run the state-dependent checks of the appropriate plugin on a simulation node
+    pub fn dynamic_checks(&self, context: &SimulationContext, target_platform: &str) -> Result<Vec<Vulnerability>> {
+        if let Some(plugin) = self.plugins.get(target_platform) {
+            plugin.dynamic_checks(context)
+        } else {
+            Err(anyhow::anyhow!("Plugin not found for platform: {}", target_platform))
+        }
+    }
+
+    /// Run the bytecode checks of the appropriate plugin on an unverified contract
+    pub fn bytecode_checks(&self, analysis: &BytecodeAnalysis, target_platform: &str) -> Result<Vec<Vulnerability>> {
+        if let Some(plugin) = self.plugins.get(target_platform) {
+            plugin.bytecode_checks(analysis)
+        } else {
+            Err(anyhow::anyhow!("Plugin not found for platform: {}", target_platform))
+        }
+    }
+
     /// Check if a tool is available for a platform
     pub fn is_tool_available(&self, platform: &str, tool: &str) -> bool {
         if let Some(plugin) = self.plugins.get(platform) {
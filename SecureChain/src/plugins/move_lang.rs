@@ -4,6 +4,7 @@
 //! used on blockchains like Aptos, Sui, and Diem.
 
 use anyhow::Result;
+use async_trait::async_trait;
 use std::process::Command;
 
 use crate::core::parser::ParsedContract;
@@ -92,6 +93,10 @@ impl MovePlugin {
                 cwe_id: Some("CWE-476".to_string()),
                 tool: "Move Plugin".to_string(),
                 confidence: 0.8,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -111,6 +116,10 @@ impl MovePlugin {
                 cwe_id: None,
                 tool: "Move Plugin".to_string(),
                 confidence: 0.6,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -139,6 +148,10 @@ impl MovePlugin {
                     cwe_id: Some("CWE-863".to_string()),
                     tool: "Move Plugin".to_string(),
                     confidence: 0.7,
+                    provenance: None,
+                    severity_overrides: Vec::new(),
+                    cvss: None,
+                    detected_by: Vec::new(),
                 });
             }
         }
@@ -170,6 +183,10 @@ impl MovePlugin {
                         cwe_id: None,
                         tool: "Move Plugin".to_string(),
                         confidence: 0.5,
+                        provenance: None,
+                        severity_overrides: Vec::new(),
+                        cvss: None,
+                        detected_by: Vec::new(),
                     });
                 }
             }
@@ -198,6 +215,10 @@ impl MovePlugin {
                 cwe_id: Some("CWE-362".to_string()),
                 tool: "Move Plugin".to_string(),
                 confidence: 0.6,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -217,6 +238,10 @@ impl MovePlugin {
                 cwe_id: None,
                 tool: "Move Plugin".to_string(),
                 confidence: 0.9,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -243,6 +268,10 @@ impl MovePlugin {
                 cwe_id: None,
                 tool: "Move Plugin".to_string(),
                 confidence: 0.8,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -262,6 +291,10 @@ impl MovePlugin {
                 cwe_id: Some("CWE-732".to_string()),
                 tool: "Move Plugin".to_string(),
                 confidence: 0.4,
+                provenance: None,
+                severity_overrides: Vec::new(),
+                cvss: None,
+                detected_by: Vec::new(),
             });
         }
 
@@ -269,6 +302,7 @@ impl MovePlugin {
     }
 }
 
+#[async_trait]
 impl BlockchainPlugin for MovePlugin {
     fn name(&self) -> &'static str {
         "Move"
@@ -278,14 +312,9 @@ impl BlockchainPlugin for MovePlugin {
         vec!["move"]
     }
 
-    fn analyze_contract(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
-        let mut vulnerabilities = Vec::new();
-
-        // Run Move-specific analysis
-        vulnerabilities.extend(self.run_move_analysis(contract)?);
-        vulnerabilities.extend(self.check_move_best_practices(contract)?);
-
-        Ok(vulnerabilities)
+    async fn analyze_contract(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        // No external tool is integrated yet, so the pattern checks are the whole analysis
+        self.internal_checks(contract)
     }
 
     fn validate_contract(&self, contract: &ParsedContract) -> Result<bool> {
@@ -310,6 +339,16 @@ impl BlockchainPlugin for MovePlugin {
     fn get_analysis_tools(&self) -> Vec<&'static str> {
         self.tools.clone()
     }
+
+    fn internal_checks(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        let mut vulnerabilities = Vec::new();
+
+        // Run Move-specific analysis
+        vulnerabilities.extend(self.run_move_analysis(contract)?);
+        vulnerabilities.extend(self.check_move_best_practices(contract)?);
+
+        Ok(vulnerabilities)
+    }
 }
 
 impl Default for MovePlugin {
@@ -0,0 +1,355 @@
+//! Shared analysis of Rust contract sources
+//!
+//! Ink! contracts and Solana programs are plain Rust, so the same syntax-tree
+//! checks apply to both: `unsafe` code, arithmetic that wraps silently in
+//! release builds without `overflow-checks`, panics in entry points, which
+//! abort every call that reaches them, and storage vectors that grow without
+//! bound until the contract can no longer load its own state. A dialect only
+//! decides which functions are entry points and which structs are storage.
+
+use std::collections::HashSet;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Attribute, BinOp, Expr, Fields, ItemFn, ItemMod, ItemStruct, Lit, Meta, Token, Type};
+
+use crate::core::parser::ParsedContract;
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+
+/// Macros that always panic when reached
+const PANIC_MACROS: &[&str] = &["panic", "unreachable", "todo", "unimplemented"];
+
+/// Methods that panic on `None` or `Err`
+const PANIC_METHODS: &[&str] = &["unwrap", "expect"];
+
+/// The Rust contract flavour, deciding entry points and storage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RustDialect {
+    /// `#[ink(message)]`/`#[ink(constructor)]` entry points, `#[ink(storage)]` state
+    Ink,
+    /// Anchor `#[program]` instructions or a native `process_instruction`, `#[account]` state
+    Solana,
+}
+
+impl RustDialect {
+    fn is_storage(self, attrs: &[Attribute]) -> bool {
+        match self {
+            RustDialect::Ink => has_ink_attribute(attrs, "storage"),
+            RustDialect::Solana => attrs.iter().any(|attr| attr.path().is_ident("account")),
+        }
+    }
+
+    fn is_entry_point(self, function: &ItemFn, in_program: bool) -> bool {
+        match self {
+            RustDialect::Ink => has_ink_attribute(&function.attrs, "message") || has_ink_attribute(&function.attrs, "constructor"),
+            RustDialect::Solana => {
+                (in_program && matches!(function.vis, syn::Visibility::Public(_)))
+                    || function.sig.ident == "process_instruction"
+            }
+        }
+    }
+
+    fn entry_point_name(self) -> &'static str {
+        match self {
+            RustDialect::Ink => "message",
+            RustDialect::Solana => "instruction",
+        }
+    }
+}
+
+/// Run the Rust checks on one source file, reporting findings as `tool`
+pub fn analyze(contract: &ParsedContract, dialect: RustDialect, tool: &str) -> Vec<Vulnerability> {
+    let file = match syn::parse_file(&contract.source_code) {
+        Ok(file) => file,
+        Err(e) => {
+            log::debug!("{} is not valid Rust, skipping the Rust checks: {}", contract.name, e);
+            return Vec::new();
+        }
+    };
+
+    let mut storage = StorageVecs { dialect, fields: HashSet::new() };
+    storage.visit_file(&file);
+
+    let mut checker = Checker {
+        dialect,
+        storage_vecs: storage.fields,
+        in_program: false,
+        scope: None,
+        findings: Vec::new(),
+        contract,
+        tool,
+    };
+    checker.visit_file(&file);
+    checker.findings
+}
+
+/// Collects the `Vec` fields of storage structs
+struct StorageVecs {
+    dialect: RustDialect,
+    fields: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for StorageVecs {
+    fn visit_item_struct(&mut self, item: &'ast ItemStruct) {
+        if self.dialect.is_storage(&item.attrs) {
+            if let Fields::Named(fields) = &item.fields {
+                for field in &fields.named {
+                    if let (Some(name), true) = (&field.ident, is_vec(&field.ty)) {
+                        self.fields.insert(name.to_string());
+                    }
+                }
+            }
+        }
+        visit::visit_item_struct(self, item);
+    }
+}
+
+/// What was seen in the function being visited
+#[derive(Default)]
+struct FunctionScope {
+    name: String,
+    entry_point: bool,
+    /// First line and count of each pattern
+    panics: Option<(usize, usize)>,
+    arithmetic: Option<(usize, usize)>,
+    pushes: Option<(usize, String)>,
+    checks_length: bool,
+}
+
+struct Checker<'a> {
+    dialect: RustDialect,
+    storage_vecs: HashSet<String>,
+    in_program: bool,
+    scope: Option<FunctionScope>,
+    findings: Vec<Vulnerability>,
+    contract: &'a ParsedContract,
+    tool: &'a str,
+}
+
+impl Checker<'_> {
+    fn entry_scope(&mut self) -> Option<&mut FunctionScope> {
+        self.scope.as_mut().filter(|scope| scope.entry_point)
+    }
+
+    fn visit_function(&mut self, function: &ItemFn) {
+        let line = function.sig.ident.span().start().line;
+        if function.sig.unsafety.is_some() {
+            self.unsafe_finding(line, &format!("`unsafe fn {}`", function.sig.ident));
+        }
+
+        let outer = self.scope.replace(FunctionScope {
+            name: function.sig.ident.to_string(),
+            entry_point: self.dialect.is_entry_point(function, self.in_program),
+            ..FunctionScope::default()
+        });
+        visit::visit_item_fn(self, function);
+        if let Some(scope) = std::mem::replace(&mut self.scope, outer) {
+            self.report(scope);
+        }
+    }
+
+    fn report(&mut self, scope: FunctionScope) {
+        let entry = self.dialect.entry_point_name();
+        if let Some((line, count)) = scope.panics {
+            self.push(
+                line,
+                "High",
+                0.6,
+                VulnerabilityCategory::DenialOfService,
+                "CWE-248",
+                format!("Panic in {} `{}`", entry, scope.name),
+                format!(
+                    "`{}` can panic in {} place(s) (`unwrap`, `expect`, indexing or a panicking macro). A panic aborts the whole call, so an input or state that triggers it blocks the {} for everyone.",
+                    scope.name, count, entry
+                ),
+                "Return an error instead of panicking: use `?`, `get()` and `checked_*` and map failures to the contract's error type.",
+            );
+        }
+        if let Some((line, count)) = scope.arithmetic {
+            self.push(
+                line,
+                "Medium",
+                0.5,
+                VulnerabilityCategory::IntegerOverflow,
+                "CWE-190",
+                format!("Unchecked arithmetic in {} `{}`", entry, scope.name),
+                format!(
+                    "`{}` uses `+`, `-` or `*` on non-constant values {} time(s). Release builds wrap on overflow unless the profile sets `overflow-checks = true`.",
+                    scope.name, count
+                ),
+                "Use `checked_add`/`checked_sub`/`checked_mul` and handle `None`, or enable `overflow-checks` in the release profile.",
+            );
+        }
+        if let (Some((line, field)), false) = (scope.pushes, scope.checks_length) {
+            self.push(
+                line,
+                "Medium",
+                0.55,
+                VulnerabilityCategory::DenialOfService,
+                "CWE-770",
+                format!("Unbounded storage growth of `{}`", field),
+                format!(
+                    "`{}` pushes to the storage vector `{}` without checking its length. Storage vectors are loaded whole, so once it is large enough every call that reads it runs out of gas or weight.",
+                    scope.name, field
+                ),
+                "Cap the vector's length before pushing, or store the entries in a mapping keyed by index.",
+            );
+        }
+    }
+
+    fn unsafe_finding(&mut self, line: usize, what: &str) {
+        self.push(
+            line,
+            "Medium",
+            0.8,
+            VulnerabilityCategory::CodeQuality,
+            "CWE-242",
+            "Unsafe Rust code".to_string(),
+            format!("{} bypasses the compiler's memory safety checks.", what),
+            "Remove the unsafe code or document and test the invariants it relies on.",
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push(
+        &mut self,
+        line: usize,
+        severity: &str,
+        confidence: f64,
+        category: VulnerabilityCategory,
+        cwe_id: &str,
+        title: String,
+        description: String,
+        recommendation: &str,
+    ) {
+        self.findings.push(Vulnerability {
+            id: uuid::Uuid::new_v4().to_string(),
+            title,
+            description,
+            severity: severity.to_string(),
+            category,
+            file_path: self.contract.name.clone(),
+            line_number: Some(line),
+            code_snippet: self.contract.source_code.lines().nth(line.saturating_sub(1)).map(|code| code.trim().to_string()),
+            recommendation: Some(recommendation.to_string()),
+            references: Vec::new(),
+            cwe_id: Some(cwe_id.to_string()),
+            tool: self.tool.to_string(),
+            confidence,
+            provenance: None,
+            severity_overrides: Vec::new(),
+            cvss: None,
+            detected_by: Vec::new(),
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for Checker<'_> {
+    fn visit_item_mod(&mut self, item: &'ast ItemMod) {
+        let program = item.attrs.iter().any(|attr| attr.path().is_ident("program"));
+        let outer = self.in_program;
+        self.in_program = outer || program;
+        visit::visit_item_mod(self, item);
+        self.in_program = outer;
+    }
+
+    fn visit_item_fn(&mut self, function: &'ast ItemFn) {
+        self.visit_function(function);
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        // Methods are checked like free functions; ink! messages live in impl blocks
+        let function = ItemFn {
+            attrs: method.attrs.clone(),
+            vis: method.vis.clone(),
+            sig: method.sig.clone(),
+            block: Box::new(method.block.clone()),
+        };
+        self.visit_function(&function);
+    }
+
+    fn visit_stmt_macro(&mut self, stmt: &'ast syn::StmtMacro) {
+        if is_panic_macro(&stmt.mac) {
+            let line = stmt.span().start().line;
+            count(self.entry_scope().map(|scope| &mut scope.panics), line);
+        }
+        visit::visit_stmt_macro(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        let line = expr.span().start().line;
+        match expr {
+            Expr::Unsafe(_) => self.unsafe_finding(line, "An `unsafe` block"),
+            Expr::Macro(mac) if is_panic_macro(&mac.mac) => count(self.entry_scope().map(|scope| &mut scope.panics), line),
+            Expr::Index(_) => count(self.entry_scope().map(|scope| &mut scope.panics), line),
+            Expr::MethodCall(call) => {
+                let method = call.method.to_string();
+                if PANIC_METHODS.contains(&method.as_str()) {
+                    count(self.entry_scope().map(|scope| &mut scope.panics), line);
+                } else if method == "len" {
+                    if let Some(scope) = self.scope.as_mut() {
+                        scope.checks_length = true;
+                    }
+                } else if method == "push" {
+                    if let Expr::Field(field) = &*call.receiver {
+                        let name = match &field.member {
+                            syn::Member::Named(ident) => ident.to_string(),
+                            syn::Member::Unnamed(_) => String::new(),
+                        };
+                        if self.storage_vecs.contains(&name) {
+                            if let Some(scope) = self.scope.as_mut().filter(|scope| scope.pushes.is_none()) {
+                                scope.pushes = Some((line, name));
+                            }
+                        }
+                    }
+                }
+            }
+            Expr::Binary(binary) if is_wrapping_op(&binary.op) && !(is_literal(&binary.left) && is_literal(&binary.right)) => {
+                count(self.entry_scope().map(|scope| &mut scope.arithmetic), line);
+            }
+            _ => {}
+        }
+        visit::visit_expr(self, expr);
+    }
+}
+
+/// Record an occurrence: first line and running count
+fn count(slot: Option<&mut Option<(usize, usize)>>, line: usize) {
+    if let Some(slot) = slot {
+        let (first, n) = slot.unwrap_or((line, 0));
+        *slot = Some((first, n + 1));
+    }
+}
+
+fn is_panic_macro(mac: &syn::Macro) -> bool {
+    mac.path
+        .segments
+        .last()
+        .is_some_and(|segment| PANIC_MACROS.contains(&segment.ident.to_string().as_str()))
+}
+
+fn is_wrapping_op(op: &BinOp) -> bool {
+    matches!(
+        op,
+        BinOp::Add(_) | BinOp::Sub(_) | BinOp::Mul(_) | BinOp::AddAssign(_) | BinOp::SubAssign(_) | BinOp::MulAssign(_)
+    )
+}
+
+fn is_literal(expr: &Expr) -> bool {
+    matches!(expr, Expr::Lit(lit) if matches!(lit.lit, Lit::Int(_)))
+}
+
+fn is_vec(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.last().is_some_and(|segment| segment.ident == "Vec"),
+        _ => false,
+    }
+}
+
+/// Whether `attrs` contain `#[ink(<name>)]`
+fn has_ink_attribute(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().filter(|attr| attr.path().is_ident("ink")).any(|attr| {
+        attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            .is_ok_and(|metas| metas.iter().any(|meta| meta.path().is_ident(name)))
+    })
+}
@@ -0,0 +1,60 @@
+//! Solana plugin for smart contract analysis
+//!
+//! This plugin provides analysis capabilities for Solana programs written in
+//! Rust, both Anchor programs and native `process_instruction` entry points.
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::core::parser::ParsedContract;
+use crate::plugins::rust_analysis::{self, RustDialect};
+use crate::plugins::BlockchainPlugin;
+use crate::report::vulnerability::Vulnerability;
+
+/// Solana plugin for analyzing Rust programs
+pub struct SolanaPlugin {
+    tools: Vec<&'static str>,
+}
+
+impl SolanaPlugin {
+    /// Create a new Solana plugin
+    pub fn new() -> Self {
+        Self {
+            tools: vec!["cargo", "anchor", "solana"],
+        }
+    }
+}
+
+#[async_trait]
+impl BlockchainPlugin for SolanaPlugin {
+    fn name(&self) -> &'static str {
+        "Solana"
+    }
+
+    fn supported_languages(&self) -> Vec<&'static str> {
+        vec!["rust"]
+    }
+
+    async fn analyze_contract(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        // No external tool is integrated yet, so the Rust checks are the whole analysis
+        self.internal_checks(contract)
+    }
+
+    fn validate_contract(&self, contract: &ParsedContract) -> Result<bool> {
+        Ok(contract.source_code.contains("solana_program") || contract.source_code.contains("anchor_lang"))
+    }
+
+    fn get_analysis_tools(&self) -> Vec<&'static str> {
+        self.tools.clone()
+    }
+
+    fn internal_checks(&self, contract: &ParsedContract) -> Result<Vec<Vulnerability>> {
+        Ok(rust_analysis::analyze(contract, RustDialect::Solana, "Solana Plugin"))
+    }
+}
+
+impl Default for SolanaPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
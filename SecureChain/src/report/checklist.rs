@@ -0,0 +1,434 @@
+//! Audit checklist
+//!
+//! Turns a run into checklist items, each with a status: the requirements of
+//! a security standard (SCSVS built in, or one loaded from a file), the
+//! configured policy, and one item per finding category the standard does not
+//! cover. A requirement fails when findings match it, passes when the analysis
+//! checks for it and found nothing, is N/A when none of the analysed code is
+//! relevant to it, and is left for manual review when no tool can decide it.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+use crate::core::analyzer::AnalysisResults;
+use crate::core::completeness::ToolStatus;
+use crate::report::vulnerability::{Severity, Vulnerability, VulnerabilityCategory};
+use crate::utils::config::Config;
+
+/// Outcome of a checklist item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    Pass,
+    Fail,
+    #[serde(rename = "n/a")]
+    NotApplicable,
+    /// No tool can decide the item; an auditor has to
+    Manual,
+}
+
+impl fmt::Display for ItemStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemStatus::Pass => write!(f, "PASS"),
+            ItemStatus::Fail => write!(f, "FAIL"),
+            ItemStatus::NotApplicable => write!(f, "N/A"),
+            ItemStatus::Manual => write!(f, "MANUAL"),
+        }
+    }
+}
+
+impl ItemStatus {
+    fn icon(&self) -> &'static str {
+        match self {
+            ItemStatus::Pass => "✅ Pass",
+            ItemStatus::Fail => "❌ Fail",
+            ItemStatus::NotApplicable => "➖ N/A",
+            ItemStatus::Manual => "🔍 Manual",
+        }
+    }
+}
+
+/// A requirement of a security standard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Requirement {
+    pub id: String,
+    pub section: String,
+    pub requirement: String,
+    /// Findings in these categories fail the requirement
+    #[serde(default)]
+    pub categories: Vec<VulnerabilityCategory>,
+    /// Findings whose title contains one of these, ignoring case, fail it too
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Source snippets that make the requirement relevant; N/A when no analysed source has one
+    #[serde(default)]
+    pub applies_if: Vec<String>,
+    /// Tools one of which must have completed for a clean result to count as a pass
+    #[serde(default)]
+    pub tools: Vec<String>,
+}
+
+impl Requirement {
+    fn new(id: &str, section: &str, requirement: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            section: section.to_string(),
+            requirement: requirement.to_string(),
+            categories: Vec::new(),
+            keywords: Vec::new(),
+            applies_if: Vec::new(),
+            tools: Vec::new(),
+        }
+    }
+
+    fn categories(mut self, categories: &[VulnerabilityCategory]) -> Self {
+        self.categories = categories.to_vec();
+        self
+    }
+
+    fn keywords(mut self, keywords: &[&str]) -> Self {
+        self.keywords = keywords.iter().map(|keyword| keyword.to_string()).collect();
+        self
+    }
+
+    fn applies_if(mut self, patterns: &[&str]) -> Self {
+        self.applies_if = patterns.iter().map(|pattern| pattern.to_string()).collect();
+        self
+    }
+
+    fn tools(mut self, tools: &[&str]) -> Self {
+        self.tools = tools.iter().map(|tool| tool.to_string()).collect();
+        self
+    }
+
+    /// Whether this run could have found violations of the requirement
+    fn is_checked(&self, tools_completed: &[String]) -> bool {
+        (!self.categories.is_empty() || !self.keywords.is_empty())
+            && (self.tools.is_empty() || self.tools.iter().any(|tool| tools_completed.contains(tool)))
+    }
+
+    fn matches(&self, vuln: &Vulnerability) -> bool {
+        let title = vuln.title.to_lowercase();
+        self.categories.contains(&vuln.category)
+            || self.keywords.iter().any(|keyword| title.contains(&keyword.to_lowercase()))
+    }
+}
+
+/// A set of requirements to check a run against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Standard {
+    pub name: String,
+    pub requirements: Vec<Requirement>,
+}
+
+impl Standard {
+    /// `scsvs` for the built-in standard, otherwise the path of a TOML or JSON file
+    pub fn load(spec: &str) -> Result<Self> {
+        if spec.eq_ignore_ascii_case("scsvs") {
+            return Ok(Self::scsvs());
+        }
+
+        let path = Path::new(spec);
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Could not read checklist standard {}: {}", path.display(), e))?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&content).map_err(|e| anyhow!("Invalid checklist standard {}: {}", path.display(), e))
+        } else {
+            toml::from_str(&content).map_err(|e| anyhow!("Invalid checklist standard {}: {}", path.display(), e))
+        }
+    }
+
+    /// The Smart Contract Security Verification Standard, as far as findings can speak to it
+    fn scsvs() -> Self {
+        use VulnerabilityCategory::*;
+
+        let requirements = vec![
+            Requirement::new("V1.1", "Architecture", "Upgradeable contracts protect their initializers and keep a compatible storage layout")
+                .keywords(&["initializ", "storage collision", "storage layout", "storage slot"])
+                .applies_if(&["initialize", "upgradeTo", "delegatecall", "Proxy"]),
+            Requirement::new("V1.2", "Architecture", "Unreachable and unused code has been removed")
+                .categories(&[DeadCode]),
+            Requirement::new("V1.3", "Architecture", "A threat model documents trust assumptions and privileged roles"),
+            Requirement::new("V2.1", "Access Control", "Privileged functions can only be called by authorised accounts")
+                .categories(&[AccessControl])
+                .keywords(&["unprotected", "access control", "arbitrary-send"]),
+            Requirement::new("V2.2", "Access Control", "Authorisation does not rely on tx.origin")
+                .keywords(&["tx.origin", "tx-origin"])
+                .applies_if(&["tx.origin"]),
+            Requirement::new("V2.3", "Access Control", "selfdestruct is absent or restricted to authorised accounts")
+                .keywords(&["selfdestruct", "suicidal"])
+                .applies_if(&["selfdestruct"]),
+            Requirement::new("V3.1", "Blockchain Data", "No secrets are kept in contract storage, private or not"),
+            Requirement::new("V4.1", "Communications", "Return values of external and low-level calls are checked")
+                .categories(&[UnhandledExceptions, LowLevelCalls])
+                .keywords(&["unchecked", "return value"]),
+            Requirement::new("V4.2", "Communications", "delegatecall only reaches trusted code")
+                .keywords(&["delegatecall"])
+                .applies_if(&["delegatecall"]),
+            Requirement::new("V5.1", "Arithmetic", "Arithmetic cannot overflow or underflow")
+                .categories(&[IntegerOverflow])
+                .keywords(&["overflow", "underflow", "arithmetic"]),
+            Requirement::new("V5.2", "Arithmetic", "Divisions come after multiplications so precision is kept")
+                .keywords(&["divide-before-multiply", "division before multiplication", "precision"]),
+            Requirement::new("V6.1", "Malicious Input", "External input is validated before use")
+                .categories(&[InputValidation]),
+            Requirement::new("V7.1", "Gas Usage", "Loops are bounded so calls stay under the block gas limit")
+                .keywords(&["loop", "gas limit", "unbounded"]),
+            Requirement::new("V8.1", "Business Logic", "Business logic matches the specification"),
+            Requirement::new("V8.2", "Business Logic", "Outcomes do not depend on transaction ordering")
+                .categories(&[RaceCondition])
+                .keywords(&["front-run", "frontrun", "transaction order"]),
+            Requirement::new("V9.1", "Denial of Service", "No party can block operations for others")
+                .categories(&[DenialOfService]),
+            Requirement::new("V10.1", "Token", "Token transfers check return values or use SafeERC20")
+                .keywords(&["erc20", "unchecked transfer", "safetransfer"])
+                .applies_if(&["IERC20", "transferFrom("]),
+            Requirement::new("V11.1", "Code Clarity", "Code follows the style and quality guidelines")
+                .categories(&[CodeQuality, GasOptimization]),
+            Requirement::new("V12.1", "Test Coverage", "Invariants hold under fuzzing and symbolic execution")
+                .categories(&[Fuzzing, SymbolicExecution])
+                .tools(&["Echidna", "Mythril"]),
+            Requirement::new("V13.1", "Known Attacks", "State is updated before external calls, so reentrancy is impossible")
+                .categories(&[Reentrancy]),
+            Requirement::new("V13.2", "Known Attacks", "Randomness does not come from block data")
+                .categories(&[Cryptography])
+                .keywords(&["weak prng", "randomness", "blockhash"]),
+            Requirement::new("V13.3", "Known Attacks", "Logic does not depend on block.timestamp precision")
+                .categories(&[TimestampDependence]),
+            Requirement::new("V13.4", "Known Attacks", "Signatures cannot be replayed or malleated")
+                .keywords(&["signature", "ecrecover", "replay"])
+                .applies_if(&["ecrecover", "ECDSA"]),
+            Requirement::new("V14.1", "Decentralized Finance", "Prices do not come from spot reserves that can be manipulated")
+                .keywords(&["oracle", "price manipulation", "spot price"])
+                .applies_if(&["getReserves", "oracle", "Oracle", "price"]),
+            Requirement::new("V14.2", "Decentralized Finance", "Flash loans cannot be used to manipulate accounting")
+                .keywords(&["flash loan", "flashloan"])
+                .applies_if(&["flash", "Flash"]),
+        ];
+
+        Self {
+            name: "SCSVS".to_string(),
+            requirements,
+        }
+    }
+}
+
+/// One line of the checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub id: String,
+    pub section: String,
+    pub requirement: String,
+    pub status: ItemStatus,
+    /// Findings or tool runs behind the status
+    pub evidence: Vec<String>,
+}
+
+/// Checklist of a run against a standard and the configured policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checklist {
+    pub standard: String,
+    pub items: Vec<ChecklistItem>,
+}
+
+impl Checklist {
+    /// Checklist of a run against the configured standard and severity policy
+    pub fn for_results(results: &AnalysisResults, config: &Config) -> Result<Self> {
+        let standard = Standard::load(&config.reporting.checklist_standard)?;
+        Self::build(results, &standard, &config.analysis.hook_fail_on)
+    }
+
+    /// Checklist of a run against `standard`, failing the policy on findings at or above `fail_on`
+    pub fn build(results: &AnalysisResults, standard: &Standard, fail_on: &str) -> Result<Self> {
+        let fail_on: Severity = fail_on
+            .parse()
+            .map_err(|e| anyhow!("{} (expected critical, high, medium, low or info)", e))?;
+        let tools_completed = match &results.completeness {
+            Some(completeness) => completeness.tools_completed(),
+            None => results.analysis_summary.tools_used.clone(),
+        };
+        let mut items = Vec::new();
+
+        for requirement in &standard.requirements {
+            let matching: Vec<&Vulnerability> =
+                results.vulnerabilities.iter().filter(|vuln| requirement.matches(vuln)).collect();
+            let status = if !matching.is_empty() {
+                ItemStatus::Fail
+            } else if !is_relevant(requirement, results) {
+                ItemStatus::NotApplicable
+            } else if requirement.is_checked(&tools_completed) {
+                ItemStatus::Pass
+            } else {
+                ItemStatus::Manual
+            };
+            items.push(ChecklistItem {
+                id: requirement.id.clone(),
+                section: requirement.section.clone(),
+                requirement: requirement.requirement.clone(),
+                status,
+                evidence: matching.into_iter().map(evidence).collect(),
+            });
+        }
+
+        items.extend(policy_items(results, &fail_on));
+
+        // Findings no requirement speaks to still need an answer
+        let mut uncovered: Vec<(VulnerabilityCategory, Vec<String>)> = Vec::new();
+        for vuln in &results.vulnerabilities {
+            if standard.requirements.iter().any(|requirement| requirement.matches(vuln)) {
+                continue;
+            }
+            match uncovered.iter_mut().find(|(category, _)| *category == vuln.category) {
+                Some((_, found)) => found.push(evidence(vuln)),
+                None => uncovered.push((vuln.category.clone(), vec![evidence(vuln)])),
+            }
+        }
+        for (i, (category, found)) in uncovered.into_iter().enumerate() {
+            items.push(ChecklistItem {
+                id: format!("F{}", i + 1),
+                section: "Findings".to_string(),
+                requirement: format!("{} findings are resolved", category),
+                status: ItemStatus::Fail,
+                evidence: found,
+            });
+        }
+
+        Ok(Self {
+            standard: standard.name.clone(),
+            items,
+        })
+    }
+
+    pub fn count(&self, status: ItemStatus) -> usize {
+        self.items.iter().filter(|item| item.status == status).count()
+    }
+
+    /// Status counts and the item table, for embedding under a report heading
+    pub fn markdown_table(&self) -> String {
+        let mut table = format!(
+            "Standard: {} · {} pass · {} fail · {} n/a · {} manual\n\n",
+            self.standard,
+            self.count(ItemStatus::Pass),
+            self.count(ItemStatus::Fail),
+            self.count(ItemStatus::NotApplicable),
+            self.count(ItemStatus::Manual),
+        );
+        table.push_str("| ID | Section | Requirement | Status | Evidence |\n|----|---------|-------------|--------|----------|\n");
+        for item in &self.items {
+            table.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                item.id,
+                item.section,
+                item.requirement.replace('|', "\\|"),
+                item.status.icon(),
+                item.evidence.join("<br>").replace('|', "\\|"),
+            ));
+        }
+        table
+    }
+
+    /// The checklist as a standalone markdown document
+    pub fn to_markdown(&self, project: &str) -> String {
+        format!(
+            "# Audit Checklist\n\n**Project:** {}  \n**Generated:** {}\n\n{}",
+            project,
+            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+            self.markdown_table()
+        )
+    }
+
+    /// One row per item, evidence separated by semicolons
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("id,section,requirement,status,evidence\n");
+        for item in &self.items {
+            let row = [
+                item.id.as_str(),
+                item.section.as_str(),
+                item.requirement.as_str(),
+                &item.status.to_string(),
+                &item.evidence.join("; "),
+            ]
+            .map(csv_field)
+            .join(",");
+            csv.push_str(&row);
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Items of the configured policy: the severity gate and a complete tool run
+fn policy_items(results: &AnalysisResults, fail_on: &Severity) -> Vec<ChecklistItem> {
+    let blocking: Vec<String> = results
+        .vulnerabilities
+        .iter()
+        .filter(|vuln| vuln.severity_priority() >= fail_on.priority())
+        .map(evidence)
+        .collect();
+    let gate = ChecklistItem {
+        id: "P1".to_string(),
+        section: "Policy".to_string(),
+        requirement: format!("No findings of {} severity or above", fail_on),
+        status: if blocking.is_empty() { ItemStatus::Pass } else { ItemStatus::Fail },
+        evidence: blocking,
+    };
+
+    let (status, incomplete) = match &results.completeness {
+        Some(completeness) => {
+            let incomplete: Vec<String> = completeness
+                .tool_runs
+                .iter()
+                .filter(|run| matches!(run.status, ToolStatus::Failed | ToolStatus::Unavailable))
+                .map(|run| {
+                    let reason = run.reason.map(|reason| format!(": {}", reason)).unwrap_or_default();
+                    format!("{} on {}{}", run.tool, run.contract.as_deref().unwrap_or("all"), reason)
+                })
+                .collect();
+            let status = if incomplete.is_empty() { ItemStatus::Pass } else { ItemStatus::Fail };
+            (status, incomplete)
+        }
+        // Results from before completeness was recorded
+        None => (ItemStatus::Manual, Vec::new()),
+    };
+    let tools = ChecklistItem {
+        id: "P2".to_string(),
+        section: "Policy".to_string(),
+        requirement: "Every requested analysis tool completed".to_string(),
+        status,
+        evidence: incomplete,
+    };
+
+    vec![gate, tools]
+}
+
+/// Whether any analysed source contains what the requirement is about
+fn is_relevant(requirement: &Requirement, results: &AnalysisResults) -> bool {
+    // Without sources there is nothing to rule the requirement out with
+    if requirement.applies_if.is_empty() || results.sources.is_empty() {
+        return true;
+    }
+    results.sources.iter().any(|source| {
+        requirement
+            .applies_if
+            .iter()
+            .any(|pattern| source.content.contains(pattern.as_str()))
+    })
+}
+
+fn evidence(vuln: &Vulnerability) -> String {
+    match vuln.line_number {
+        Some(line) => format!("{} ({}:{})", vuln.title, vuln.file_path, line),
+        None => format!("{} ({})", vuln.title, vuln.file_path),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
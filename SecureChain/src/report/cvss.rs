@@ -0,0 +1,140 @@
+//! CVSS 3.1 base scores
+//!
+//! Findings carry a CVSS vector when the firm or tool that reported them gave
+//! one, and built-in findings get one from their severity, category and
+//! exploitability, so reports can be ordered and filtered by a number rather
+//! than by five buckets. Only the base metrics are scored; temporal and
+//! environmental metrics in a vector are kept but do not change the score.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::core::detector_catalog;
+use crate::core::exploitability::Exploitability;
+use crate::report::vulnerability::{Severity, Vulnerability, VulnerabilityCategory};
+
+/// A CVSS 3.x vector and its base score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cvss {
+    /// Vector string, e.g. `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:H/A:N`
+    pub vector: String,
+    /// Base score, 0.0 to 10.0
+    pub score: f64,
+}
+
+impl Cvss {
+    /// Parse a CVSS 3.0 or 3.1 vector and compute its base score
+    pub fn parse(vector: &str) -> Result<Self> {
+        let vector = vector.trim();
+        let metrics = match vector.strip_prefix("CVSS:3.1/").or_else(|| vector.strip_prefix("CVSS:3.0/")) {
+            Some(metrics) => metrics,
+            None => return Err(anyhow!("Not a CVSS 3 vector: {} (expected CVSS:3.1/AV:..)", vector)),
+        };
+        let metrics: HashMap<&str, &str> = metrics.split('/').filter_map(|metric| metric.split_once(':')).collect();
+        let value = |metric: &str, weights: &[(&str, f64)]| -> Result<f64> {
+            let given = metrics.get(metric).ok_or_else(|| anyhow!("CVSS vector {} has no {} metric", vector, metric))?;
+            weights
+                .iter()
+                .find(|(name, _)| name == given)
+                .map(|(_, weight)| *weight)
+                .ok_or_else(|| anyhow!("CVSS vector {} has an invalid value {}:{}", vector, metric, given))
+        };
+
+        let changed = value("S", &[("U", 0.0), ("C", 1.0)])? > 0.0;
+        let attack_vector = value("AV", &[("N", 0.85), ("A", 0.62), ("L", 0.55), ("P", 0.2)])?;
+        let complexity = value("AC", &[("L", 0.77), ("H", 0.44)])?;
+        let privileges = if changed {
+            value("PR", &[("N", 0.85), ("L", 0.68), ("H", 0.5)])?
+        } else {
+            value("PR", &[("N", 0.85), ("L", 0.62), ("H", 0.27)])?
+        };
+        let interaction = value("UI", &[("N", 0.85), ("R", 0.62)])?;
+        let impact_weights = [("H", 0.56), ("L", 0.22), ("N", 0.0)];
+        let confidentiality = value("C", &impact_weights)?;
+        let integrity = value("I", &impact_weights)?;
+        let availability = value("A", &impact_weights)?;
+
+        let base = 1.0 - (1.0 - confidentiality) * (1.0 - integrity) * (1.0 - availability);
+        let impact = if changed {
+            7.52 * (base - 0.029) - 3.25 * (base - 0.02).powi(15)
+        } else {
+            6.42 * base
+        };
+        let exploitability = 8.22 * attack_vector * complexity * privileges * interaction;
+        let score = if impact <= 0.0 {
+            0.0
+        } else if changed {
+            round_up((1.08 * (impact + exploitability)).min(10.0))
+        } else {
+            round_up((impact + exploitability).min(10.0))
+        };
+
+        Ok(Self { vector: vector.to_string(), score })
+    }
+
+    /// Qualitative rating of the score
+    pub fn severity(&self) -> Severity {
+        Severity::from_score(self.score)
+    }
+}
+
+/// Round up to one decimal as CVSS 3.1 specifies, without floating point drift
+fn round_up(value: f64) -> f64 {
+    let scaled = (value * 100_000.0).round() as i64;
+    if scaled % 10_000 == 0 {
+        scaled as f64 / 100_000.0
+    } else {
+        (scaled / 10_000 + 1) as f64 / 10.0
+    }
+}
+
+/// Give built-in findings without a vector one, from their severity,
+/// category and, where it was assessed, who can trigger them
+pub fn score_builtin(vulnerabilities: &mut [Vulnerability], exploitability: &HashMap<String, Exploitability>) {
+    for vuln in vulnerabilities.iter_mut() {
+        if vuln.cvss.is_some() || detector_catalog::detector_of(vuln).is_none() {
+            continue;
+        }
+        vuln.cvss = vuln
+            .severity_level()
+            .and_then(|severity| builtin_vector(&severity, &vuln.category, exploitability.get(&vuln.id)))
+            .and_then(|vector| Cvss::parse(&vector).ok());
+    }
+}
+
+/// Vector of a built-in finding
+///
+/// The severity sets how easily the issue is reached and how much it harms,
+/// landing each severity in its CVSS band; a privileged caller or dependence
+/// on market conditions lowers the score from there. Denial of service
+/// harms availability, everything else the integrity of the contract's state.
+fn builtin_vector(severity: &Severity, category: &VulnerabilityCategory, exploitability: Option<&Exploitability>) -> Option<String> {
+    let (mut complexity, mut privileges, interaction, scope, harm) = match severity {
+        Severity::Critical => ("L", "N", "N", "C", "H"),
+        Severity::High => ("L", "N", "N", "U", "H"),
+        Severity::Medium => ("H", "L", "N", "U", "H"),
+        Severity::Low => ("H", "H", "R", "U", "L"),
+        Severity::Info => return None,
+    };
+    if let Some(exploitability) = exploitability {
+        if exploitability.requires_privileged_role && !exploitability.reachable_by_eoa {
+            privileges = "H";
+        }
+        if exploitability.requires_market_conditions {
+            complexity = "H";
+        }
+    }
+
+    let availability_only = matches!(category, VulnerabilityCategory::DenialOfService | VulnerabilityCategory::GasOptimization);
+    // Critical findings lose funds and leave the contract unusable
+    let (integrity, availability) = match severity {
+        Severity::Critical => (harm, harm),
+        _ if availability_only => ("N", harm),
+        _ => (harm, "N"),
+    };
+    Some(format!(
+        "CVSS:3.1/AV:N/AC:{}/PR:{}/UI:{}/S:{}/C:N/I:{}/A:{}",
+        complexity, privileges, interaction, scope, integrity, availability
+    ))
+}
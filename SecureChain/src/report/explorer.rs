@@ -0,0 +1,256 @@
+//! Source explorer for HTML reports
+//!
+//! Clients often read a report without the audited repository at hand. The
+//! HTML report therefore bundles the analysed sources as a collapsible file
+//! tree with syntax highlighting and one anchor per line, and every finding
+//! links to the line it was reported on.
+//!
+//! Builds without the `reporting` feature only use `SourceFile`, which
+//! analysis results carry regardless of the report format.
+
+#![cfg_attr(not(feature = "reporting"), allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::report::vulnerability::Vulnerability;
+
+const KEYWORDS: &[&str] = &[
+    "pragma", "import", "from", "as", "contract", "interface", "library", "abstract", "is", "function",
+    "modifier", "event", "error", "struct", "enum", "mapping", "constructor", "fallback", "receive",
+    "returns", "return", "if", "else", "for", "while", "do", "break", "continue", "emit", "revert",
+    "require", "assert", "new", "delete", "try", "catch", "using", "unchecked", "assembly", "public",
+    "private", "internal", "external", "view", "pure", "payable", "constant", "immutable", "virtual",
+    "override", "memory", "storage", "calldata", "indexed", "anonymous", "true", "false", "this", "super",
+    "type", "let", "solidity",
+];
+
+const TYPES: &[&str] = &["address", "bool", "string", "bytes", "uint", "int", "byte", "fixed", "ufixed"];
+
+/// Stylesheet for the explorer
+pub const STYLE: &str = r#"
+        .explorer { display: flex; gap: 20px; align-items: flex-start; }
+        .file-tree { min-width: 220px; font-size: 14px; position: sticky; top: 10px; }
+        .file-tree ul { list-style: none; padding-left: 16px; margin: 0; }
+        .file-tree .count { color: #dc3545; font-size: 12px; }
+        .sources { flex: 1; min-width: 0; }
+        .source { border: 1px solid #ddd; border-radius: 5px; margin: 10px 0; }
+        .source summary { cursor: pointer; padding: 8px; background-color: #f8f9fa; font-weight: bold; }
+        .source table { border-collapse: collapse; width: 100%; font-family: monospace; font-size: 13px; }
+        .source td { padding: 0 8px; white-space: pre; vertical-align: top; }
+        .source td.ln { text-align: right; color: #999; user-select: none; width: 1%; }
+        .source td.ln a { color: inherit; text-decoration: none; }
+        .source tr.hit { background-color: #fff3cd; }
+        .source tr:target { background-color: #ffe08a; }
+        .tok-kw { color: #0033b3; font-weight: bold; }
+        .tok-type { color: #00627a; }
+        .tok-str { color: #067d17; }
+        .tok-num { color: #1750eb; }
+        .tok-com { color: #8c8c8c; font-style: italic; }
+"#;
+
+/// Opens the file holding the linked line, since anchors inside closed `<details>` do not scroll
+pub const SCRIPT: &str = r#"
+    <script>
+        function revealTarget() {
+            var target = document.getElementById(decodeURIComponent(location.hash.slice(1)));
+            if (!target) return;
+            for (var node = target; node; node = node.parentElement) {
+                if (node.tagName === 'DETAILS') node.open = true;
+            }
+            target.scrollIntoView({ block: 'center' });
+        }
+        window.addEventListener('hashchange', revealTarget);
+        window.addEventListener('load', revealTarget);
+    </script>
+"#;
+
+/// An analysed source file, as bundled into HTML reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceFile {
+    /// File name, as findings refer to it in `file_path`
+    pub name: String,
+    /// Path relative to the analysed directory, for the file tree
+    pub path: String,
+    pub content: String,
+}
+
+/// Anchor of a line of a bundled file
+pub fn line_anchor(sources: &[SourceFile], file_name: &str, line: usize) -> Option<String> {
+    let index = sources.iter().position(|source| source.name == file_name)?;
+    let lines = sources[index].content.lines().count();
+    (line >= 1 && line <= lines).then(|| format!("src-{}-L{}", index, line))
+}
+
+/// File tree and highlighted sources, with the lines findings point at marked
+pub fn render(sources: &[SourceFile], vulnerabilities: &[Vulnerability]) -> String {
+    let mut findings_by_line: BTreeMap<(&str, usize), Vec<&Vulnerability>> = BTreeMap::new();
+    for vuln in vulnerabilities {
+        if let Some(line) = vuln.line_number {
+            findings_by_line.entry((vuln.file_path.as_str(), line)).or_default().push(vuln);
+        }
+    }
+
+    let mut html = String::new();
+    html.push_str("<h2 id=\"source-explorer\">Source Explorer</h2>\n<div class=\"explorer\">\n");
+    html.push_str("<nav class=\"file-tree\">\n");
+    html.push_str(&render_tree(sources, vulnerabilities));
+    html.push_str("</nav>\n<div class=\"sources\">\n");
+
+    for (index, source) in sources.iter().enumerate() {
+        html.push_str(&format!(
+            "<details class=\"source\" id=\"src-{}\">\n<summary>{}</summary>\n<table>\n",
+            index,
+            escape_html(&source.path)
+        ));
+
+        let mut in_block_comment = false;
+        for (line_index, line) in source.content.lines().enumerate() {
+            let line_number = line_index + 1;
+            let anchor = format!("src-{}-L{}", index, line_number);
+            let (class, title) = match findings_by_line.get(&(source.name.as_str(), line_number)) {
+                Some(findings) => {
+                    let titles: Vec<String> = findings
+                        .iter()
+                        .map(|vuln| format!("[{}] {}", vuln.severity, vuln.title))
+                        .collect();
+                    (" class=\"hit\"", format!(" title=\"{}\"", escape_html(&titles.join("\n"))))
+                }
+                None => ("", String::new()),
+            };
+            html.push_str(&format!(
+                "<tr id=\"{anchor}\"{class}{title}><td class=\"ln\"><a href=\"#{anchor}\">{line_number}</a></td><td>{}</td></tr>\n",
+                highlight_line(line, &mut in_block_comment),
+            ));
+        }
+
+        html.push_str("</table>\n</details>\n");
+    }
+
+    html.push_str("</div>\n</div>\n");
+    html
+}
+
+/// Nested list of directories and files, each file with its finding count
+fn render_tree(sources: &[SourceFile], vulnerabilities: &[Vulnerability]) -> String {
+    #[derive(Default)]
+    struct Directory {
+        directories: BTreeMap<String, Directory>,
+        files: Vec<usize>,
+    }
+
+    let mut root = Directory::default();
+    for (index, source) in sources.iter().enumerate() {
+        let mut directory = &mut root;
+        let mut components: Vec<&str> = source.path.split(['/', '\\']).collect();
+        components.pop();
+        for component in components.into_iter().filter(|component| !component.is_empty()) {
+            directory = directory.directories.entry(component.to_string()).or_default();
+        }
+        directory.files.push(index);
+    }
+
+    fn render_directory(directory: &Directory, sources: &[SourceFile], vulnerabilities: &[Vulnerability], html: &mut String) {
+        html.push_str("<ul>\n");
+        for (name, child) in &directory.directories {
+            html.push_str(&format!("<li><details open><summary>{}/</summary>\n", escape_html(name)));
+            render_directory(child, sources, vulnerabilities, html);
+            html.push_str("</details></li>\n");
+        }
+        for &index in &directory.files {
+            let source = &sources[index];
+            let file_name = source.path.rsplit(['/', '\\']).next().unwrap_or(&source.path);
+            let count = vulnerabilities.iter().filter(|vuln| vuln.file_path == source.name).count();
+            let badge = if count > 0 { format!(" <span class=\"count\">({})</span>", count) } else { String::new() };
+            html.push_str(&format!("<li><a href=\"#src-{}\">{}</a>{}</li>\n", index, escape_html(file_name), badge));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    let mut html = String::new();
+    render_directory(&root, sources, vulnerabilities, &mut html);
+    html
+}
+
+/// Highlight one line of Solidity, carrying block comment state across lines
+fn highlight_line(line: &str, in_block_comment: &mut bool) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut html = String::new();
+    let mut i = 0;
+
+    let span = |class: &str, text: &str| format!("<span class=\"tok-{}\">{}</span>", class, escape_html(text));
+    let slice = |from: usize, to: usize| chars[from..to].iter().collect::<String>();
+
+    while i < chars.len() {
+        if *in_block_comment {
+            let start = i;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            if i < chars.len() {
+                i += 2;
+                *in_block_comment = false;
+            }
+            html.push_str(&span("com", &slice(start, i)));
+            continue;
+        }
+
+        let c = chars[i];
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            html.push_str(&span("com", &slice(i, chars.len())));
+            break;
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            *in_block_comment = true;
+            let start = i;
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            if i < chars.len() {
+                i += 2;
+                *in_block_comment = false;
+            }
+            html.push_str(&span("com", &slice(start, i)));
+        } else if c == '"' || c == '\'' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+            i = (i + 1).min(chars.len());
+            html.push_str(&span("str", &slice(start, i)));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                i += 1;
+            }
+            html.push_str(&span("num", &slice(start, i)));
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                i += 1;
+            }
+            let word = slice(start, i);
+            let base = word.trim_end_matches(|c: char| c.is_ascii_digit() || c == 'x');
+            if KEYWORDS.contains(&word.as_str()) {
+                html.push_str(&span("kw", &word));
+            } else if TYPES.contains(&base) {
+                html.push_str(&span("type", &word));
+            } else {
+                html.push_str(&escape_html(&word));
+            }
+        } else {
+            html.push_str(&escape_html(&c.to_string()));
+            i += 1;
+        }
+    }
+
+    html
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -6,11 +6,35 @@
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
 use std::path::Path;
 
-use crate::core::analyzer::{AnalysisResults, AnalysisMetrics};
+use crate::core::analyzer::{normalized_security_score, AnalysisMetrics, AnalysisResults, SCORE_UNIT_LINES};
+use crate::core::architecture::{Architecture, Module};
+use crate::core::baseline::BaselineComparison;
+use crate::core::capabilities::Capabilities;
+use crate::core::completeness::{Completeness, ToolStatus};
+use crate::core::fuzz_coverage;
+use crate::core::integration::ContractSurface;
+use crate::core::inventory::{Inventory, Surface};
+use crate::core::manifest::RunManifest;
+use crate::core::metadata::ProjectMetadata;
+use crate::core::poc_verify::{PocStatus, PocVerification};
+use crate::core::remediation::{FixStatus, RemediationResults};
+use crate::core::suppression::{self, ExpiredAcceptance};
+use crate::core::test_review::TestReview;
+use crate::report::checklist::Checklist;
+#[cfg(feature = "reporting")]
+use crate::report::explorer;
+use crate::report::explorer::SourceFile;
+use crate::report::hooks;
+use crate::report::i18n::{self, Catalog};
+use crate::report::naming;
+use crate::report::poc::{self, PocPlatform, PocPolicy};
+use crate::report::sarif;
 use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+use crate::utils::artifact;
 use crate::utils::config::Config;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +45,36 @@ pub struct ComprehensiveReport {
     pub recommendations: Vec<Recommendation>,
     pub technical_details: TechnicalDetails,
     pub appendices: Vec<Appendix>,
+    #[serde(default)]
+    pub completeness: Option<Completeness>,
+    #[serde(default)]
+    pub architecture: Option<Architecture>,
+    #[serde(default)]
+    pub test_review: Option<TestReview>,
+    /// File of each finding, by vulnerability id, where it says more than the finding's file name
+    #[serde(default)]
+    pub finding_files: HashMap<String, String>,
+    /// Id of each finding under `reporting.finding_ids`, by vulnerability id
+    #[serde(default)]
+    pub finding_numbers: HashMap<String, String>,
+    /// Fingerprint of each finding, as suppression rules and SARIF take it, by vulnerability id
+    #[serde(default)]
+    pub finding_fingerprints: HashMap<String, String>,
+    /// Custom errors, revert strings and events each finding affects, by vulnerability id
+    #[serde(default)]
+    pub finding_surfaces: HashMap<String, Vec<String>>,
+    /// Outcome of running each finding's PoC, by vulnerability id; only a confirmed one makes a finding verified
+    #[serde(default)]
+    pub poc_verification: HashMap<String, PocVerification>,
+    #[serde(default)]
+    pub baseline: Option<BaselineComparison>,
+    /// Findings whose risk acceptance expired, flagged until it is renewed
+    #[serde(default)]
+    pub expired_acceptances: Vec<ExpiredAcceptance>,
+    /// Analysed sources for the HTML explorer; JSON consumers have them in the analysis results
+    #[serde(default, skip_serializing)]
+    #[cfg_attr(not(feature = "reporting"), allow(dead_code))]
+    pub sources: Vec<SourceFile>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,9 +82,16 @@ pub struct ReportMetadata {
     pub report_id: String,
     pub generated_at: DateTime<Utc>,
     pub version: String,
-    pub contract_name: String,
+    /// What was audited: contracts, compiler versions, licenses, dependencies and addresses
+    pub project: ProjectMetadata,
     pub analysis_tools: Vec<String>,
+    /// Installed versions of the external tools, e.g. `Slither 0.10.0`
+    #[serde(default)]
+    pub tool_versions: Vec<String>,
     pub report_type: String,
+    /// Functions the analysis was restricted to; empty when all code was analysed
+    #[serde(default)]
+    pub entrypoints: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +103,9 @@ pub struct ExecutiveSummary {
     pub medium_risk_findings: usize,
     pub low_risk_findings: usize,
     pub security_score: f64,
+    /// Security score with the penalties spread over the code size
+    #[serde(default)]
+    pub normalized_security_score: f64,
     pub key_findings: Vec<String>,
     pub recommendations_summary: Vec<String>,
 }
@@ -77,7 +141,9 @@ pub struct TechnicalDetails {
 pub struct CoverageReport {
     pub lines_analyzed: usize,
     pub functions_analyzed: usize,
-    pub coverage_percentage: f64,
+    /// Line coverage fuzzing or the project's tests measured, `None` when nothing did
+    pub coverage_percentage: Option<f64>,
+    /// Functions no fuzzing call or test ever exercised, as `Contract.function`
     pub uncovered_areas: Vec<String>,
 }
 
@@ -129,12 +195,13 @@ This security audit was conducted using SecureChain's comprehensive analysis fra
 | **Medium Severity** | {} |
 | **Low Severity** | {} |
 | **Security Score** | {:.1}/100 |
+| **Normalized Security Score** | {:.1}/100 |
 | **Creative Probes** | {} |
 
 ## 🚨 Critical Issues Summary
 
 "#, 
-            results.contract_name,
+            project_metadata(results).title,
             chrono::Utc::now().format("%Y-%m-%d"),
             env!("CARGO_PKG_VERSION"),
             results.vulnerabilities.len(),
@@ -143,6 +210,7 @@ This security audit was conducted using SecureChain's comprehensive analysis fra
             results.analysis_summary.medium_count,
             results.analysis_summary.low_count,
             results.metrics.security_score,
+            normalized_score(results),
             probes.len()
         ));
         
@@ -233,20 +301,31 @@ This comprehensive security audit employed multiple analysis techniques:
 3. **AI Analysis:** Creative vulnerability discovery using large language models
 4. **Manual Review:** Expert analysis of complex logic and edge cases
 
-## 📋 Detailed Findings
-
 "#,
-            results.contract_name,
+            project_metadata(results).title,
             chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
             results.analysis_summary.analysis_duration,
             results.analysis_summary.tools_used.join(", ")
         ));
+        if let Some(architecture) = &results.architecture {
+            report.push_str("### Architecture Overview
+
+");
+            report.push_str(&self.architecture_overview(architecture));
+        }
+        report.push_str("## 📋 Detailed Findings
+
+");
         
         // Group and display vulnerabilities
         let mut by_severity = std::collections::HashMap::new();
         for vuln in &results.vulnerabilities {
             by_severity.entry(&vuln.severity).or_insert(Vec::new()).push(vuln);
         }
+        // Highest score first within each severity
+        for vulns in by_severity.values_mut() {
+            vulns.sort_by(|a, b| b.score().partial_cmp(&a.score()).unwrap_or(std::cmp::Ordering::Equal));
+        }
         
         for severity in &["Critical", "High", "Medium", "Low", "Info"] {
             if let Some(vulns) = by_severity.get(&severity.to_string()) {
@@ -261,7 +340,8 @@ This comprehensive security audit employed multiple analysis techniques:
 **File:** {}  
 **Line:** {}  
 **Tool:** {}  
-**Confidence:** {:.1}%
+**Confidence:** {:.1}%  
+**CVSS:** {}
 
 **Description:**
 {}
@@ -286,8 +366,9 @@ This vulnerability could potentially lead to [describe specific impact based on
                         vuln.category,
                         vuln.file_path,
                         vuln.line_number.unwrap_or(0),
-                        vuln.tool,
+                        vuln.tools().join(", "),
                         vuln.confidence * 100.0,
+                        vuln.cvss.as_ref().map_or("N/A".to_string(), |cvss| format!("{:.1} (`{}`)", cvss.score, cvss.vector)),
                         vuln.description,
                         vuln.recommendation.as_ref().unwrap_or(&"Review and fix this issue".to_string()),
                         vuln.references.join(", "),
@@ -300,6 +381,10 @@ This vulnerability could potentially lead to [describe specific impact based on
                 }
             }
         }
+        if let Some(review) = &results.test_review {
+            report.push_str("\n## 🧪 Test Blind Spots\n\n");
+            report.push_str(&self.test_blind_spots(review));
+        }
         
         // Creative probes section
         if !probes.is_empty() {
@@ -352,28 +437,12 @@ This vulnerability could potentially lead to [describe specific impact based on
 | Functions Analyzed | {} |
 | Complexity Score | {:.2} |
 | Security Score | {:.2}/100 |
-| Coverage Percentage | {:.1}% |
-
-## 🔧 Remediation Checklist
-
-### Immediate Actions Required
-- [ ] Review and fix all critical severity vulnerabilities
-- [ ] Implement proper access controls where missing
-- [ ] Add reentrancy guards to external functions
-- [ ] Validate all user inputs and external calls
-
-### Security Enhancements
-- [ ] Implement circuit breakers for emergency situations
-- [ ] Add comprehensive event logging
-- [ ] Use established security patterns (OpenZeppelin)
-- [ ] Implement proper error handling
-
-### Testing & Deployment
-- [ ] Write comprehensive unit tests
-- [ ] Perform integration testing
-- [ ] Deploy to testnet for additional validation
-- [ ] Set up monitoring and alerting
+| Normalized Security Score | {:.2}/100 |
+| Line Coverage | {} |
+{}
+## 🔧 Audit Checklist
 
+{}
 ## 📚 Additional Resources
 
 - [OpenZeppelin Security Guidelines](https://docs.openzeppelin.com/contracts/4.x/security)
@@ -388,7 +457,10 @@ This vulnerability could potentially lead to [describe specific impact based on
             results.metrics.functions_analyzed,
             results.metrics.complexity_score,
             results.metrics.security_score,
-            results.analysis_summary.coverage_percentage,
+            normalized_score(results),
+            coverage_text(results.analysis_summary.coverage_percentage),
+            unexercised_note(results),
+            Checklist::for_results(results, &self.config)?.markdown_table(),
             env!("CARGO_PKG_VERSION"),
             chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
         ));
@@ -412,9 +484,13 @@ This vulnerability could potentially lead to [describe specific impact based on
         // Format the report
         match format {
             "markdown" => self.generate_markdown_report(&report),
+            #[cfg(feature = "reporting")]
             "html" => self.generate_html_report(&report),
             "json" => self.generate_json_report(&report),
+            #[cfg(feature = "reporting")]
             "pdf" => self.generate_pdf_report(&report),
+            #[cfg(not(feature = "reporting"))]
+            "html" | "pdf" => Err(missing_reporting(format)),
             _ => Err(anyhow!("Unsupported report format: {}", format)),
         }
     }
@@ -425,24 +501,281 @@ This vulnerability could potentially lead to [describe specific impact based on
         self.generate_markdown_report(&report)
     }
 
+    /// Write a report in the specified format to `path`, replacing it whole,
+    /// then run the report hooks on it
+    ///
+    /// Markdown and HTML reports go to the file section by section as they
+    /// are rendered, so runs with thousands of findings are never held in
+    /// memory as one string.
+    pub fn write_report(&self, results: &AnalysisResults, format: &str, path: &Path) -> Result<()> {
+        artifact::write_atomic_with(path, |out| match format {
+            "json" | "sarif" => Ok(out.write_all(self.generate_report(results, format)?.as_bytes())?),
+            #[cfg(feature = "reporting")]
+            "html" => self.write_html_report(&self.create_comprehensive_report(results, true)?, out),
+            #[cfg(not(feature = "reporting"))]
+            "html" => Err(missing_reporting(format)),
+            _ => self.write_markdown_report(&self.create_comprehensive_report(results, true)?, out),
+        })?;
+
+        if !self.config.reporting.hooks.is_empty() {
+            match self.generate_report(results, "json") {
+                Ok(json) => hooks::run(&self.config.reporting, path, format, &json),
+                Err(e) => log::warn!("Report hooks skipped: {}", e),
+            }
+        }
+        Ok(())
+    }
+
     /// Generate a report in the specified format (for backward compatibility)
     pub fn generate_report(&self, results: &AnalysisResults, format: &str) -> Result<String> {
         match format {
             "markdown" => self.generate_markdown_report_from_results(results),
-            "json" => Ok(serde_json::to_string_pretty(results)?),
+            "json" => {
+                let mut json = serde_json::to_value(results)?;
+                json["finding_numbers"] = serde_json::to_value(self.finding_numbers(results))?;
+                Ok(serde_json::to_string_pretty(&json)?)
+            }
+            "sarif" => sarif::render(results, &self.finding_numbers(results)),
+            #[cfg(feature = "reporting")]
             "html" => {
                 let report = self.create_comprehensive_report(results, true)?;
                 self.generate_html_report(&report)
             },
+            #[cfg(not(feature = "reporting"))]
+            "html" => Err(missing_reporting(format)),
             _ => self.generate_markdown_report_from_results(results),
         }
     }
 
+    /// Generate a remediation verification report (markdown, json or html)
+    pub fn generate_remediation_report(&self, results: &RemediationResults, format: &str) -> Result<String> {
+        if format == "json" {
+            return Ok(serde_json::to_string_pretty(results)?);
+        }
+
+        let mut report = format!(
+            "# Remediation Verification: {}\n\n**Verified:** {}  \n**Tools Re-run:** {}  \n**Analysis Completeness:** {:.1}%\n\n",
+            results.project,
+            results.verified_at.format("%Y-%m-%d %H:%M UTC"),
+            if results.tools_rerun.is_empty() { "none".to_string() } else { results.tools_rerun.join(", ") },
+            results.completeness.score
+        );
+
+        report.push_str("## Summary\n\n| Status | Findings |\n|--------|----------|\n");
+        for status in [FixStatus::Fixed, FixStatus::NotFixed, FixStatus::Regressed, FixStatus::Unverified] {
+            report.push_str(&format!("| {} | {} |\n", status, results.count(status)));
+        }
+        report.push_str(&format!("| New findings | {} |\n\n", results.new_findings.len()));
+
+        report.push_str("## Prior Findings\n\n");
+        report.push_str("| # | Severity | Finding | Location | Status | Notes |\n");
+        report.push_str("|---|----------|---------|----------|--------|-------|\n");
+        let mut verifications: Vec<_> = results.verifications.iter().collect();
+        verifications.sort_by_key(|verification| std::cmp::Reverse(verification.finding.severity_priority()));
+        for (i, verification) in verifications.iter().enumerate() {
+            let finding = &verification.finding;
+            let location = match finding.line_number {
+                Some(line) => format!("{}:{}", finding.file_path, line),
+                None => finding.file_path.clone(),
+            };
+            let icon = match verification.status {
+                FixStatus::Fixed => "✅",
+                FixStatus::NotFixed => "❌",
+                FixStatus::Regressed => "🔺",
+                FixStatus::Unverified => "❔",
+            };
+            report.push_str(&format!(
+                "| {} | {} | {} | {} | {} {} | {} |\n",
+                i + 1,
+                finding.severity,
+                finding.title,
+                location,
+                icon,
+                verification.status,
+                verification.note
+            ));
+        }
+        report.push('\n');
+
+        if !results.new_findings.is_empty() {
+            report.push_str("## New Findings\n\n");
+            report.push_str("Reported by the re-run tools on the fixed code but not in the prior results. These may have been introduced by the fixes.\n\n");
+            let mut new_findings: Vec<_> = results.new_findings.iter().collect();
+            new_findings.sort_by_key(|finding| std::cmp::Reverse(finding.severity_priority()));
+            for finding in new_findings {
+                let location = match finding.line_number {
+                    Some(line) => format!("{}:{}", finding.file_path, line),
+                    None => finding.file_path.clone(),
+                };
+                report.push_str(&format!("- **[{}] {}** ({}, {})\n", finding.severity, finding.title, location, finding.tool));
+            }
+            report.push('\n');
+        }
+
+        if !results.behavior_drift.is_empty() {
+            report.push_str("## Behavior Drift\n\n");
+            report.push_str("Differences from the behavior snapshot of the original code. Fixes should not remove or change anything callers rely on.\n\n");
+            report.push_str("| Contract | Aspect | Change | Detail |\n|----------|--------|--------|--------|\n");
+            for drift in &results.behavior_drift {
+                report.push_str(&format!("| {} | {} | {} | {} |\n", drift.contract, drift.aspect, drift.kind, drift.detail));
+            }
+            report.push('\n');
+        }
+
+        if results.count(FixStatus::Unverified) > 0 {
+            report.push_str("> ⚠️ Unverified findings come from tools that could not be re-run on the fixed code and need manual review.\n");
+        }
+
+        if format == "html" {
+            return Ok(format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n    <title>Remediation Verification - {}</title>\n</head>\n<body>\n    <pre>{}</pre>\n</body>\n</html>",
+                results.project, report
+            ));
+        }
+        Ok(report)
+    }
+
+    /// Generate a report formatted for bug bounty submissions (Immunefi/HackerOne style)
+    ///
+    /// Each in-scope finding becomes a self-contained submission with target,
+    /// attacker requirements, impact, PoC and mitigation. Findings that need a
+    /// privileged role, informational findings and code quality issues are
+    /// listed separately since programs typically exclude them.
+    pub fn generate_bounty_report(&self, results: &AnalysisResults) -> Result<String> {
+        let mut in_scope = Vec::new();
+        let mut out_of_scope = Vec::new();
+
+        for vuln in &results.vulnerabilities {
+            let exploitability = results.exploitability.get(&vuln.id);
+            let reason = if vuln.severity == "Info" {
+                Some("Informational")
+            } else if matches!(
+                vuln.category,
+                VulnerabilityCategory::GasOptimization | VulnerabilityCategory::CodeQuality | VulnerabilityCategory::DeadCode
+            ) {
+                Some("Code quality / gas, no security impact")
+            } else if exploitability.is_some_and(|e| e.requires_privileged_role) {
+                Some("Requires a privileged role")
+            } else {
+                None
+            };
+
+            match reason {
+                Some(reason) => out_of_scope.push((vuln, reason)),
+                None => in_scope.push(vuln),
+            }
+        }
+        in_scope.sort_by_key(|vuln| std::cmp::Reverse(vuln.severity_priority()));
+
+        let mut report = format!(
+            "# Bug Bounty Submissions: {}\n\n**Generated:** {}  \n**In scope:** {}  \n**Out of scope:** {}\n\n",
+            project_metadata(results).title,
+            Utc::now().format("%Y-%m-%d %H:%M UTC"),
+            in_scope.len(),
+            out_of_scope.len()
+        );
+
+        let poc_policy = PocPolicy::from_config(&self.config);
+        let numbers = self.finding_numbers(results);
+        for vuln in &in_scope {
+            let exploitability = results.exploitability.get(&vuln.id);
+            let target = match vuln.line_number {
+                Some(line) => format!("{}:{}", vuln.file_path, line),
+                None => vuln.file_path.clone(),
+            };
+
+            let number = numbers.get(&vuln.id).map(String::as_str).unwrap_or_default();
+            report.push_str(&format!("## {} [{}] {}\n\n", number, vuln.severity, vuln.title));
+            report.push_str("| Field | Value |\n|-------|-------|\n");
+            report.push_str(&format!("| Target | `{}` |\n", target));
+            if let Some(function) = exploitability.and_then(|e| e.function.as_ref()) {
+                report.push_str(&format!("| Function | `{}` |\n", function));
+            }
+            report.push_str(&format!(
+                "| Weakness | {}{} |\n",
+                vuln.category,
+                vuln.cwe_id.as_ref().map(|cwe| format!(" ({})", cwe)).unwrap_or_default()
+            ));
+            report.push_str(&format!(
+                "| Attacker | {} |\n",
+                exploitability.map(|e| e.summary()).unwrap_or_else(|| "Not determined".to_string())
+            ));
+            report.push_str(&format!(
+                "| Preconditions | {} |\n",
+                match exploitability {
+                    Some(e) if e.requires_market_conditions => e.market_conditions.join(", "),
+                    Some(_) => "None".to_string(),
+                    None => "Not determined".to_string(),
+                }
+            ));
+            if let Some(cvss) = &vuln.cvss {
+                report.push_str(&format!("| CVSS | {:.1} (`{}`) |\n", cvss.score, cvss.vector));
+            }
+            if let Some(verification) = results.poc_verification.get(&vuln.id) {
+                report.push_str(&format!(
+                    "| PoC | {} |\n",
+                    match verification.status {
+                        PocStatus::Confirmed if verification.fork => "Verified, exploited on a fork".to_string(),
+                        PocStatus::Confirmed => "Verified, exploited on a local deployment".to_string(),
+                        status => format!("Not verified: {}", status),
+                    }
+                ));
+            }
+            report.push_str(&format!("| Detected by | {} (confidence {:.2}) |\n\n", vuln.tools().join(", "), vuln.confidence));
+
+            report.push_str(&format!("### Description\n\n{}\n\n", vuln.description));
+            report.push_str(&format!("### Impact\n\n{}\n\n", vuln.category.description()));
+            if let Some(code) = &vuln.code_snippet {
+                report.push_str(&format!("### Vulnerable Code\n\n```\n{}\n```\n\n", code));
+            }
+
+            // Every in-scope finding needs a PoC to be submitted, unless triage excluded it
+            if results.poc_flags.get(&vuln.id) != Some(&false) {
+                if poc_policy.no_exploit_code {
+                    report.push_str(&format!("### Steps to Reproduce\n\n{}\n", poc::steps(vuln)));
+                } else {
+                    let poc = poc::generate_poc(vuln);
+                    let language = match poc.platform {
+                        PocPlatform::Solidity => "solidity",
+                        PocPlatform::Cairo => "cairo",
+                        PocPlatform::Move => "move",
+                        PocPlatform::Ink => "rust",
+                    };
+                    report.push_str(&format!("### Proof of Concept\n\n```{}\n{}```\n\n", language, poc.code));
+                }
+            }
+
+            if let Some(recommendation) = &vuln.recommendation {
+                report.push_str(&format!("### Recommended Mitigation\n\n{}\n\n", recommendation));
+            }
+            if !vuln.references.is_empty() {
+                report.push_str("### References\n\n");
+                for reference in &vuln.references {
+                    report.push_str(&format!("- {}\n", reference));
+                }
+                report.push('\n');
+            }
+            report.push_str("---\n\n");
+        }
+
+        if !out_of_scope.is_empty() {
+            report.push_str("## Out of Scope\n\n| Finding | Severity | Reason |\n|---------|----------|--------|\n");
+            for (vuln, reason) in &out_of_scope {
+                report.push_str(&format!("| {} | {} | {} |\n", vuln.title.replace('|', "\\|"), vuln.severity, reason));
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Id of each finding under `reporting.finding_ids`, the same in every format
+    fn finding_numbers(&self, results: &AnalysisResults) -> HashMap<String, String> {
+        naming::finding_numbers(&results.vulnerabilities, &self.config.reporting.finding_ids)
+    }
+
     /// Load analysis results from file
     fn load_analysis_results(&self, path: &Path) -> Result<AnalysisResults> {
-        let content = std::fs::read_to_string(path)?;
-        let results: AnalysisResults = serde_json::from_str(&content)?;
-        Ok(results)
+        artifact::read_json(path, "analysis results file")
     }
 
     /// Create a comprehensive report from analysis results
@@ -450,7 +783,7 @@ This vulnerability could potentially lead to [describe specific impact based on
         let metadata = self.create_report_metadata(results)?;
         let vulnerability_analysis = self.create_vulnerability_analysis(&results.vulnerabilities)?;
         let recommendations = self.create_recommendations(&results.vulnerabilities, &results.recommendations)?;
-        let technical_details = self.create_technical_details(&results.metrics, results.analysis_summary.analysis_duration)?;
+        let technical_details = self.create_technical_details(results)?;
         let appendices = self.create_appendices(results)?;
 
         let executive_summary = if include_summary {
@@ -464,11 +797,31 @@ This vulnerability could potentially lead to [describe specific impact based on
                 medium_risk_findings: 0,
                 low_risk_findings: 0,
                 security_score: results.metrics.security_score,
+                normalized_security_score: normalized_score(results),
                 key_findings: Vec::new(),
                 recommendations_summary: Vec::new(),
             }
         };
 
+        let files = results.finding_files();
+        let finding_files = results
+            .vulnerabilities
+            .iter()
+            .filter_map(|vuln| {
+                let file = files.get(vuln.id.as_str()).filter(|file| **file != vuln.file_path)?;
+                Some((vuln.id.clone(), file.to_string()))
+            })
+            .collect();
+        let finding_surfaces = match &results.inventory {
+            Some(inventory) => results
+                .vulnerabilities
+                .iter()
+                .map(|vuln| (vuln.id.clone(), inventory.surfaces_of(&vuln.id)))
+                .filter(|(_, surfaces)| !surfaces.is_empty())
+                .collect(),
+            None => HashMap::new(),
+        };
+
         Ok(ComprehensiveReport {
             metadata,
             executive_summary,
@@ -476,6 +829,17 @@ This vulnerability could potentially lead to [describe specific impact based on
             recommendations,
             technical_details,
             appendices,
+            completeness: results.completeness.clone(),
+            architecture: results.architecture.clone(),
+            test_review: results.test_review.clone(),
+            finding_files,
+            finding_numbers: self.finding_numbers(results),
+            finding_fingerprints: results.fingerprints().into_iter().map(|(id, fingerprint)| (id.to_string(), fingerprint)).collect(),
+            finding_surfaces,
+            poc_verification: results.poc_verification.clone(),
+            baseline: results.baseline.clone(),
+            expired_acceptances: results.expired_acceptances.clone(),
+            sources: results.sources.clone(),
         })
     }
 
@@ -485,9 +849,11 @@ This vulnerability could potentially lead to [describe specific impact based on
             report_id: uuid::Uuid::new_v4().to_string(),
             generated_at: Utc::now(),
             version: env!("CARGO_PKG_VERSION").to_string(),
-            contract_name: results.contract_name.clone(),
+            project: project_metadata(results),
             analysis_tools: results.analysis_summary.tools_used.clone(),
+            tool_versions: results.manifest.as_ref().map(|manifest| Capabilities::from_manifest(manifest).summary()).unwrap_or_default(),
             report_type: "Security Audit Report".to_string(),
+            entrypoints: results.entrypoints.clone(),
         })
     }
 
@@ -517,6 +883,7 @@ This vulnerability could potentially lead to [describe specific impact based on
             medium_risk_findings: *medium_risk_findings,
             low_risk_findings: *low_risk_findings,
             security_score: results.metrics.security_score,
+            normalized_security_score: normalized_score(results),
             key_findings,
             recommendations_summary,
         })
@@ -639,12 +1006,13 @@ This vulnerability could potentially lead to [describe specific impact based on
     }
 
     /// Create technical details
-    fn create_technical_details(&self, metrics: &AnalysisMetrics, duration: f64) -> Result<TechnicalDetails> {
+    fn create_technical_details(&self, results: &AnalysisResults) -> Result<TechnicalDetails> {
+        let metrics = &results.metrics;
         let coverage_report = CoverageReport {
             lines_analyzed: metrics.lines_of_code,
             functions_analyzed: metrics.functions_analyzed,
-            coverage_percentage: 85.0, // Mock value
-            uncovered_areas: vec!["External library interactions".to_string()],
+            coverage_percentage: fuzz_coverage::line_coverage(&results.fuzz_coverage),
+            uncovered_areas: unexercised_functions(results),
         };
 
         let mut tool_configurations = HashMap::new();
@@ -655,7 +1023,7 @@ This vulnerability could potentially lead to [describe specific impact based on
             analysis_metrics: metrics.clone(),
             coverage_report,
             tool_configurations,
-            analysis_duration: duration,
+            analysis_duration: results.analysis_summary.analysis_duration,
         })
     }
 
@@ -670,11 +1038,337 @@ This vulnerability could potentially lead to [describe specific impact based on
             appendix_type: "configuration".to_string(),
         });
 
+        // Add completeness appendix
+        if let Some(completeness) = &results.completeness {
+            let mut content = format!("Completeness score: {:.1}%\n\n", completeness.score);
+            if let Some(compiled) = completeness.files_compiled {
+                content.push_str(&format!("Files compiled: {} of {}\n\n", compiled, completeness.files_total));
+            }
+            content.push_str("| Tool | Contract | Status | Reason | Details |\n|------|----------|--------|--------|---------|\n");
+            for run in &completeness.tool_runs {
+                let status = match run.status {
+                    ToolStatus::Completed if run.reason.is_some() => "⏳ Partial",
+                    ToolStatus::Completed => "✅ Completed",
+                    ToolStatus::Failed => "❌ Failed",
+                    ToolStatus::Unavailable => "⚠️ Unavailable",
+                    ToolStatus::NotRequested => "➖ Not requested",
+                };
+                content.push_str(&format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    run.tool,
+                    run.contract.as_deref().unwrap_or("all"),
+                    status,
+                    run.reason.map(|reason| reason.to_string()).unwrap_or_default(),
+                    run.detail.as_deref().unwrap_or_default().replace('|', "\\|"),
+                ));
+            }
+
+            appendices.push(Appendix {
+                title: "Analysis Completeness".to_string(),
+                content,
+                appendix_type: "completeness".to_string(),
+            });
+        }
+
+        // Add per-contract appendix for runs over several contracts
+        if results.contracts.len() > 1 {
+            let mut content = String::from(
+                "| Contract | File | Lines | Findings | Critical | High | Medium | Low | Info | Score | Time |\n\
+                 |----------|------|-------|----------|----------|------|--------|-----|------|-------|------|\n",
+            );
+            for contract in &results.contracts {
+                content.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                    contract.name,
+                    contract.file,
+                    contract.lines_of_code,
+                    contract.findings,
+                    contract.critical,
+                    contract.high,
+                    contract.medium,
+                    contract.low,
+                    contract.info,
+                    contract.security_score.map_or("-".to_string(), |score| format!("{:.1}", score)),
+                    if contract.cached { "cached".to_string() } else { format!("{:.1}s", contract.duration) }
+                ));
+            }
+            appendices.push(Appendix {
+                title: "Per-Contract Results".to_string(),
+                content,
+                appendix_type: "contracts".to_string(),
+            });
+        }
+
+        // Add fuzzing coverage appendix for contracts Echidna fuzzed or the project's tests covered
+        if !results.fuzz_coverage.is_empty() {
+            let mut content = String::new();
+            for coverage in &results.fuzz_coverage {
+                content.push_str(&format!("#### {}\n\n", coverage.contract));
+                content.push_str(&format!(
+                    "- **Line coverage:** {:.1}% ({}/{} lines)\n",
+                    coverage.coverage_percentage, coverage.lines_covered, coverage.total_lines
+                ));
+                if coverage.tool == "forge" {
+                    content.push_str("- **Measured by:** forge coverage\n\n");
+                } else {
+                    content.push_str(&format!(
+                        "- **Corpus size:** {} sequences\n- **Unique transitions:** {}\n\n",
+                        coverage.corpus_size, coverage.unique_transitions
+                    ));
+                }
+                content.push_str("| Function | Line | Calls | Lines Covered |\n|----------|------|-------|---------------|\n");
+                for function in &coverage.functions {
+                    content.push_str(&format!(
+                        "| {} | {} | {} | {} |\n",
+                        function.name, function.line, function.calls, function.lines_covered
+                    ));
+                }
+
+                let unreached: Vec<String> = coverage
+                    .unreached()
+                    .map(|function| format!("- `{}` (line {})\n", function.name, function.line))
+                    .collect();
+                if !unreached.is_empty() {
+                    content.push_str(if coverage.tool == "forge" {
+                        "\n**Residual risk:** the project's tests never exercised these functions:\n\n"
+                    } else {
+                        "\n**Residual risk:** fuzzing never reached these functions, so no property was checked after them:\n\n"
+                    });
+                    content.push_str(&unreached.concat());
+                }
+                content.push('\n');
+            }
+
+            appendices.push(Appendix {
+                title: "Fuzzing Coverage".to_string(),
+                content,
+                appendix_type: "fuzz_coverage".to_string(),
+            });
+        }
+
+        // Add error handling appendix
+        if let Some(quality) = &results.error_handling {
+            let total = quality.requires + quality.reverts;
+            let content = format!(
+                "Error handling score: {:.1}/100\n\n\
+                 | Checks | Requires | Reverts | Without Reason | String Reasons | Over 32 Bytes | Custom Errors | Convertible |\n\
+                 |--------|----------|---------|----------------|----------------|---------------|---------------|-------------|\n\
+                 | {} | {} | {} | {} | {} | {} | {} | {} |\n\n\
+                 Convertible string reasons are in files whose version pragma allows custom errors (Solidity 0.8.4 on).",
+                quality.score,
+                total,
+                quality.requires,
+                quality.reverts,
+                quality.without_reason,
+                quality.string_reasons,
+                quality.long_reasons,
+                quality.custom_errors,
+                quality.convertible
+            );
+            appendices.push(Appendix {
+                title: "Error Handling".to_string(),
+                content,
+                appendix_type: "error_handling".to_string(),
+            });
+        }
+
+        // Add error and event inventory appendix, for decoding failures and building alerting
+        if let Some(inventory) = &results.inventory {
+            appendices.push(Appendix {
+                title: "Error and Event Inventory".to_string(),
+                content: self.inventory_appendix(inventory, &self.finding_numbers(results)),
+                appendix_type: "inventory".to_string(),
+            });
+        }
+
+        // Add imported findings appendix, one row per import of another firm's findings
+        if !results.imports.is_empty() {
+            let mut content = String::from(
+                "Findings reported by other firms, listed among the findings with the firm as their tool.\n\n\
+                 | Vendor | File | Format | Imported | Added | Already Reported |\n\
+                 |--------|------|--------|----------|-------|------------------|\n",
+            );
+            for import in &results.imports {
+                content.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} |\n",
+                    import.vendor,
+                    import.source,
+                    import.format,
+                    import.imported_at.format("%Y-%m-%d"),
+                    import.added.len(),
+                    import.duplicates.len()
+                ));
+            }
+            appendices.push(Appendix {
+                title: "Imported Findings".to_string(),
+                content,
+                appendix_type: "imports".to_string(),
+            });
+        }
+
+        // Add suppressed findings appendix, so what the project chose not to see stays on record
+        if !results.suppressed.is_empty() {
+            let mut content = format!(
+                "Findings left out of this report by the project's `{}`, with the rule that matched.\n\n\
+                 | Finding | Severity | Location | Tool | Rule | Justification | Owner | Expires |\n\
+                 |---------|----------|----------|------|------|---------------|-------|---------|\n",
+                suppression::FILE_NAME
+            );
+            for suppressed in &results.suppressed {
+                let finding = &suppressed.finding;
+                let location = match finding.line_number {
+                    Some(line) => format!("{}:{}", finding.file_path, line),
+                    None => finding.file_path.clone(),
+                };
+                content.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                    finding.title,
+                    finding.severity,
+                    location,
+                    finding.tools().join(", "),
+                    suppressed.rule,
+                    suppressed.justification.as_deref().unwrap_or("-"),
+                    suppressed.owner.as_deref().unwrap_or("-"),
+                    suppressed.expires.map(|date| date.to_string()).unwrap_or_else(|| "-".to_string())
+                ));
+            }
+            appendices.push(Appendix {
+                title: "Suppressed Findings".to_string(),
+                content,
+                appendix_type: "suppressed".to_string(),
+            });
+        }
+
+        // Add audit checklist appendix
+        appendices.push(Appendix {
+            title: "Audit Checklist".to_string(),
+            content: Checklist::for_results(results, &self.config)?.markdown_table(),
+            appendix_type: "checklist".to_string(),
+        });
+
+        // Add deployment parameters appendix
+        if !results.deployment_parameters.is_empty() {
+            let mut content = String::from("| Parameter | Type | Value |\n|-----------|------|-------|\n");
+            for param in &results.deployment_parameters {
+                content.push_str(&format!("| {} | {} | `{}` |\n", param.name, param.type_name, param.value));
+            }
+
+            appendices.push(Appendix {
+                title: "Deployment Parameters".to_string(),
+                content,
+                appendix_type: "deployment".to_string(),
+            });
+        }
+
+        // Add diamond routing table appendix
+        if !results.selector_routes.is_empty() {
+            let mut content = String::from("| Selector | Signature | Facet |\n|----------|-----------|-------|\n");
+            for route in &results.selector_routes {
+                content.push_str(&format!("| `{}` | `{}` | {} |\n", route.selector, route.signature, route.facet));
+            }
+
+            appendices.push(Appendix {
+                title: "Facet Selector Routing Table".to_string(),
+                content,
+                appendix_type: "selectors".to_string(),
+            });
+        }
+
+        // Add diamond appendices
+        if let Some(diamond) = &results.diamond {
+            let mut content = String::from("| Facet | Selectors |\n|-------|-----------|\n");
+            for facet in &diamond.facets {
+                content.push_str(&format!("| {} | {} |\n", facet.label(), facet.selectors.len()));
+            }
+            appendices.push(Appendix {
+                title: "Diamond Facets".to_string(),
+                content,
+                appendix_type: "diamond".to_string(),
+            });
+
+            if !diamond.storage.is_empty() {
+                let mut content = String::from("| Struct | Pattern | Position | Declared In | Fields |\n|--------|---------|----------|-------------|--------|\n");
+                for layout in &diamond.storage {
+                    content.push_str(&format!(
+                        "| {} | {} | `{}` | {} | {} |\n",
+                        layout.name,
+                        layout.pattern,
+                        layout.position,
+                        layout.declared_in,
+                        layout.fields.iter().map(|f| format!("`{}`", f)).collect::<Vec<_>>().join("<br>")
+                    ));
+                }
+                appendices.push(Appendix {
+                    title: "Diamond Storage Layout".to_string(),
+                    content,
+                    appendix_type: "diamond".to_string(),
+                });
+            }
+
+            if !diamond.cut_history.is_empty() {
+                let mut content = String::from("| Block | Transaction | Action | Facet | Selectors |\n|-------|-------------|--------|-------|-----------|\n");
+                for event in &diamond.cut_history {
+                    for cut in &event.cuts {
+                        content.push_str(&format!(
+                            "| {} | `{}` | {} | `{}` | {} |\n",
+                            event.block_number,
+                            event.transaction_hash,
+                            cut.action,
+                            cut.facet_address,
+                            cut.selectors.len()
+                        ));
+                    }
+                }
+                appendices.push(Appendix {
+                    title: "Diamond Cut History".to_string(),
+                    content,
+                    appendix_type: "diamond".to_string(),
+                });
+            }
+        }
+
+        // Add integrator appendix
+        if !results.integration.is_empty() {
+            appendices.push(Appendix {
+                title: "Integrator Risk Guide".to_string(),
+                content: self.integrator_guide(&results.integration),
+                appendix_type: "integration".to_string(),
+            });
+        }
+
+        // Add bytecode appendix, for contracts analysed without source
+        if let Some(bytecode) = &results.bytecode {
+            appendices.push(Appendix {
+                title: "Bytecode".to_string(),
+                content: bytecode.to_markdown(),
+                appendix_type: "bytecode".to_string(),
+            });
+        }
+
+        // Add AI usage appendix, for what the run's prompts are estimated to have cost
+        if let Some(usage) = &results.ai_usage {
+            appendices.push(Appendix {
+                title: "AI Usage".to_string(),
+                content: usage.to_markdown(),
+                appendix_type: "ai_usage".to_string(),
+            });
+        }
+
+        // Add reproduction appendix from the run manifest
+        if let Some(manifest) = &results.manifest {
+            appendices.push(Appendix {
+                title: "Reproduction".to_string(),
+                content: self.reproduction_instructions(manifest, results),
+                appendix_type: "reproduction".to_string(),
+            });
+        }
+
         // Add metrics appendix
         appendices.push(Appendix {
             title: "Analysis Metrics".to_string(),
-            content: format!("Security Score: {:.2}\nComplexity Score: {:.2}\nLines of Code: {}", 
+            content: format!("Security Score: {:.2}\nNormalized Security Score: {:.2}\nComplexity Score: {:.2}\nLines of Code: {}", 
                 results.metrics.security_score, 
+                normalized_score(results),
                 results.metrics.complexity_score, 
                 results.metrics.lines_of_code),
             appendix_type: "metrics".to_string(),
@@ -683,6 +1377,253 @@ This vulnerability could potentially lead to [describe specific impact based on
         Ok(appendices)
     }
 
+    /// Trust assumptions of each contract, for protocols integrating it
+    /// Custom errors, revert strings and events, with the functions raising them and the findings affecting them
+    fn inventory_appendix(&self, inventory: &Inventory, numbers: &HashMap<String, String>) -> String {
+        let list = |items: &[String]| match items.is_empty() {
+            true => "-".to_string(),
+            false => items.iter().map(|item| format!("`{}`", item)).collect::<Vec<_>>().join(", "),
+        };
+        let findings = |surface: &Surface| match surface.findings.is_empty() {
+            true => "-".to_string(),
+            false => surface.findings.iter().map(|id| numbers.get(id).cloned().unwrap_or_else(|| id.clone())).collect::<Vec<_>>().join(", "),
+        };
+
+        let mut content = String::from(
+            "Failure and log surfaces of the contracts, with the external functions that can raise or emit each one, \
+             directly, through a modifier or through the internal functions they call, and the findings in that code.\n",
+        );
+        if !inventory.errors.is_empty() {
+            content.push_str("\n**Custom errors**\n\n| Error | Selector | Declared In | Raised By | Findings |\n|-------|----------|-------------|-----------|----------|\n");
+            for error in &inventory.errors {
+                content.push_str(&format!(
+                    "| `{}` | {} | {} | {} | {} |\n",
+                    error.signature,
+                    error.selector.as_deref().map(|selector| format!("`{}`", selector)).unwrap_or_else(|| "-".to_string()),
+                    error.declared_in.as_deref().unwrap_or("outside the run"),
+                    list(&error.raised_by),
+                    findings(error)
+                ));
+            }
+        }
+        if !inventory.revert_strings.is_empty() {
+            content.push_str("\n**Revert strings**\n\n| Message | Raised By | Findings |\n|---------|-----------|----------|\n");
+            for reason in &inventory.revert_strings {
+                content.push_str(&format!(
+                    "| \"{}\" | {} | {} |\n",
+                    reason.signature.replace('|', "\\|"),
+                    list(&reason.raised_by),
+                    findings(reason)
+                ));
+            }
+        }
+        if !inventory.events.is_empty() {
+            content.push_str("\n**Events**\n\n| Event | Topic | Declared In | Emitted By | Findings |\n|-------|-------|-------------|------------|----------|\n");
+            for event in &inventory.events {
+                content.push_str(&format!(
+                    "| `{}` | {} | {} | {} | {} |\n",
+                    event.signature,
+                    event.selector.as_deref().map(|topic| format!("`{}`", topic)).unwrap_or_else(|| "anonymous".to_string()),
+                    event.declared_in.as_deref().unwrap_or("-"),
+                    list(&event.raised_by),
+                    findings(event)
+                ));
+            }
+        }
+        content
+    }
+
+    fn integrator_guide(&self, surfaces: &[ContractSurface]) -> String {
+        let callers = |roles: &[String]| {
+            if roles.is_empty() {
+                "**Anyone**".to_string()
+            } else {
+                roles.iter().map(|role| format!("`{}`", role)).collect::<Vec<_>>().join(", ")
+            }
+        };
+        let code_list = |items: &[&str]| items.iter().map(|item| format!("`{}`", item)).collect::<Vec<_>>().join(", ");
+
+        let mut content = String::from(
+            "What protocols building on these contracts have to trust, derived from each external function and the internal functions it reaches.\n",
+        );
+        for surface in surfaces {
+            content.push_str(&format!("\n### {} (`{}`)\n\n", surface.name, surface.file));
+
+            if !surface.controls.is_empty() {
+                content.push_str("**Administrative controls**\n\n| Capability | Function | Callable By |\n|------------|----------|-------------|\n");
+                for control in &surface.controls {
+                    content.push_str(&format!("| {} | `{}` | {} |\n", control.capability, control.function, callers(&control.callable_by)));
+                }
+                content.push('\n');
+            }
+
+            content.push_str("**External functions**\n\n| Function | Callable By | May Revert | Hands Control To | Reentrancy Guard | Findings |\n|----------|-------------|------------|------------------|------------------|----------|\n");
+            for function in &surface.functions {
+                content.push_str(&format!(
+                    "| `{}` | {} | {} | {} | {} | {} |\n",
+                    function.signature,
+                    callers(&function.callable_by),
+                    function.reverts.join("; "),
+                    function.callbacks.join("; "),
+                    if function.reentrancy_guarded { "Yes" } else if function.callbacks.is_empty() { "-" } else { "No" },
+                    function.findings
+                ));
+            }
+
+            // What to defend against, one note per capability
+            let mut notes: Vec<String> = Vec::new();
+            let mut capabilities: Vec<&str> = Vec::new();
+            for control in &surface.controls {
+                if !capabilities.contains(&control.capability.as_str()) {
+                    capabilities.push(control.capability.as_str());
+                }
+            }
+            for capability in capabilities {
+                let controls: Vec<_> = surface.controls.iter().filter(|control| control.capability == capability).collect();
+                let functions: Vec<&str> = controls.iter().map(|control| control.function.as_str()).collect();
+                let mut roles: Vec<String> = Vec::new();
+                for control in &controls {
+                    if control.callable_by.is_empty() {
+                        roles.clear();
+                        break;
+                    }
+                    for role in &control.callable_by {
+                        if !roles.contains(role) {
+                            roles.push(role.clone());
+                        }
+                    }
+                }
+                let who = if roles.is_empty() { "Anyone".to_string() } else { format!("Callers passing {}", callers(&roles)) };
+
+                let note = match capability {
+                    "Pause" => {
+                        let paused: Vec<&str> = surface
+                            .functions
+                            .iter()
+                            .filter(|function| function.reverts.iter().any(|condition| condition == "while paused"))
+                            .map(|function| function.signature.as_str())
+                            .collect();
+                        if paused.is_empty() {
+                            format!("{} can pause the contract with {}. Integrations need a path that does not depend on it while paused.", who, code_list(&functions))
+                        } else {
+                            format!("{} can pause the contract with {}, after which {} revert. Integrations need a fallback for that.", who, code_list(&functions), code_list(&paused))
+                        }
+                    }
+                    "Upgrade" => format!("{} can replace the implementation with {}, which can change everything described here. Monitor upgrades and re-review after each one.", who, code_list(&functions)),
+                    "Change fees" => format!("{} can change fees with {}. Read fees at call time and enforce minimum amounts received.", who, code_list(&functions)),
+                    "Change oracle" => format!("{} can replace price sources with {}. Do not assume prices from this contract stay consistent across calls.", who, code_list(&functions)),
+                    "Block accounts" => format!("{} can block accounts with {}. An integrating contract that gets blocked loses access to the funds it holds here.", who, code_list(&functions)),
+                    "Move funds" => format!("{} can move funds held by the contract with {}.", who, code_list(&functions)),
+                    "Mint" => format!("{} can mint with {}, so supply is not fixed.", who, code_list(&functions)),
+                    "Transfer control" => format!("Control changes hands with {}, so the roles above are not permanent.", code_list(&functions)),
+                    _ => format!("{} can change parameters with {}.", who, code_list(&functions)),
+                };
+                notes.push(note);
+            }
+
+            let reentrant: Vec<&str> = surface
+                .functions
+                .iter()
+                .filter(|function| function.reentrant_callbacks())
+                .map(|function| function.signature.as_str())
+                .collect();
+            if !reentrant.is_empty() {
+                notes.push(format!(
+                    "{} hand control to other contracts without a reentrancy guard. State read from this contract inside a callback may be mid-update.",
+                    code_list(&reentrant)
+                ));
+            }
+
+            if !notes.is_empty() {
+                content.push_str("\n**What to defend against**\n\n");
+                for note in notes {
+                    content.push_str(&format!("- {}\n", note));
+                }
+            }
+        }
+
+        content
+    }
+
+    /// Commands, versions and environment needed to reproduce each finding
+    fn reproduction_instructions(&self, manifest: &RunManifest, results: &AnalysisResults) -> String {
+        let vulnerabilities = &results.vulnerabilities;
+        let finding_files = results.finding_files();
+        let mut content = String::from("| Item | Value |\n|------|-------|\n");
+        content.push_str(&format!("| SecureChain | {} |\n", manifest.securechain_version));
+        content.push_str(&format!("| Started | {} |\n", manifest.started_at.to_rfc3339()));
+        content.push_str(&format!("| Environment | {} / {} |\n", manifest.os, manifest.arch));
+        content.push_str(&format!("| Input | `{}` |\n", manifest.input_path));
+        content.push_str(&format!(
+            "| Commit | {} |\n",
+            match &manifest.git_commit {
+                Some(commit) if manifest.git_dirty => format!("`{}` (with uncommitted changes)", commit),
+                Some(commit) => format!("`{}`", commit),
+                None => "not a git repository".to_string(),
+            }
+        ));
+        for tool in &manifest.tools {
+            content.push_str(&format!(
+                "| {} | {} |\n",
+                tool.tool,
+                tool.version.as_deref().unwrap_or("not installed")
+            ));
+        }
+        for source in &manifest.sources {
+            content.push_str(&format!("| `{}` | keccak256 `{}` |\n", source.path, source.keccak256));
+        }
+
+        content.push_str("\nRe-run the full analysis with the recorded configuration (`config` in the JSON results):\n\n```bash\n");
+        if let Some(commit) = &manifest.git_commit {
+            content.push_str(&format!("git checkout {}\n", commit));
+        }
+        content.push_str(&format!("{}\n```\n\n", manifest.invocation));
+
+        content.push_str("| # | Finding | Reproduce |\n|---|---------|-----------|\n");
+        for (i, vuln) in vulnerabilities.iter().enumerate() {
+            let file = manifest.source_path(&vuln.file_path, finding_files.get(vuln.id.as_str()).copied());
+            let mut steps = Vec::new();
+
+            match manifest.tool(&vuln.tool) {
+                Some(tool) => {
+                    if let Some(setup) = &tool.setup {
+                        steps.push(setup.clone());
+                    }
+                    let mut command = tool.command_for(file);
+                    if let Some(check) = vuln.title.strip_prefix("Slither: ") {
+                        command.push_str(&format!(" --detect {}", check));
+                    }
+                    steps.push(command);
+                }
+                None if vuln.tool == "AI Assistant" => {
+                    steps.push(format!("{} (AI output is not deterministic)", manifest.invocation));
+                }
+                None => steps.push(manifest.invocation.clone()),
+            }
+
+            // The exploit command numbers PoCs by position in the results
+            if vuln.severity == "Critical" || vuln.severity == "High" {
+                let platform = PocPlatform::for_vulnerability(vuln);
+                let poc_path = format!("exploits/exploit_{}.{}", i + 1, platform.file_extension());
+                steps.push("securechain exploit -r results.json -o exploits".to_string());
+                steps.push(platform.run_command(&poc_path));
+            }
+
+            content.push_str(&format!(
+                "| {} | {} | {} |\n",
+                i + 1,
+                vuln.title.replace('|', "\\|"),
+                steps
+                    .iter()
+                    .map(|step| format!("`{}`", step.replace('|', "\\|")))
+                    .collect::<Vec<_>>()
+                    .join("<br>")
+            ));
+        }
+
+        content
+    }
+
     /// Extract key findings from vulnerabilities
     fn extract_key_findings(&self, vulnerabilities: &[Vulnerability]) -> Vec<String> {
         let mut key_findings = Vec::new();
@@ -717,39 +1658,144 @@ This vulnerability could potentially lead to [describe specific impact based on
 
     /// Generate markdown report
     pub fn generate_markdown_report(&self, report: &ComprehensiveReport) -> Result<String> {
+        let mut markdown = Vec::new();
+        self.write_markdown_report(report, &mut markdown)?;
+        Ok(String::from_utf8(markdown)?)
+    }
+
+    /// Write the markdown report to `out` as its sections are rendered
+    fn write_markdown_report(&self, report: &ComprehensiveReport, out: &mut dyn Write) -> Result<()> {
+        let catalogs = i18n::catalogs(&self.config)?;
+        if let [text] = catalogs.as_slice() {
+            return self.render_markdown(report, text, &mut |section| Ok(out.write_all(section.as_bytes())?));
+        }
+
+        // Bilingual versions are paired by section, so each is rendered whole first
+        let versions = catalogs
+            .iter()
+            .map(|text| self.markdown_string(report, text))
+            .collect::<Result<Vec<_>>>()?;
+        // Markdown has no columns, so side-by-side layouts fall back to sequential here
+        let markdown = bilingual_sections(&versions).map_or_else(|| versions.concat(), |rows| rows.concat().concat());
+        Ok(out.write_all(markdown.as_bytes())?)
+    }
+
+    /// The markdown report in `text`'s language as one string
+    fn markdown_string(&self, report: &ComprehensiveReport, text: &Catalog) -> Result<String> {
+        let mut markdown = String::new();
+        self.render_markdown(report, text, &mut |section| {
+            markdown.push_str(section);
+            Ok(())
+        })?;
+        Ok(markdown)
+    }
+
+    /// The markdown report with the fixed text of `text`'s language, handed to `emit` section by section
+    fn render_markdown(&self, report: &ComprehensiveReport, text: &Catalog, emit: &mut dyn FnMut(&str) -> Result<()>) -> Result<()> {
         let mut markdown = String::new();
 
         // Title and metadata
-        markdown.push_str(&format!("# Security Audit Report: {}\n\n", report.metadata.contract_name));
-        markdown.push_str(&format!("**Report ID:** {}\n", report.metadata.report_id));
-        markdown.push_str(&format!("**Generated:** {}\n", report.metadata.generated_at.format("%Y-%m-%d %H:%M:%S UTC")));
-        markdown.push_str(&format!("**Version:** {}\n", report.metadata.version));
-        markdown.push_str(&format!("**Tools Used:** {}\n\n", report.metadata.analysis_tools.join(", ")));
+        markdown.push_str(&format!("# {}: {}\n\n", text.get("report_title"), report.metadata.project.title));
+        markdown.push_str(&format!("**{}:** {}\n", text.get("report_id"), report.metadata.report_id));
+        markdown.push_str(&format!("**{}:** {}\n", text.get("generated"), report.metadata.generated_at.format("%Y-%m-%d %H:%M:%S UTC")));
+        markdown.push_str(&format!("**{}:** {}\n", text.get("version"), report.metadata.version));
+        markdown.push_str(&format!("**{}:** {}\n", text.get("tools_used"), report.metadata.analysis_tools.join(", ")));
+        if !report.metadata.tool_versions.is_empty() {
+            markdown.push_str(&format!("**{}:** {}\n", text.get("tool_versions"), report.metadata.tool_versions.join(", ")));
+        }
+        if let Some(completeness) = &report.completeness {
+            markdown.push_str(&format!("**{}:** {:.1}%\n", text.get("completeness"), completeness.score));
+        }
+        if !report.metadata.entrypoints.is_empty() {
+            markdown.push_str(&format!("**{}:** {}\n", text.get("entrypoints"), report.metadata.entrypoints.join(", ")));
+        }
+        markdown.push('\n');
 
-        // Executive Summary
-        markdown.push_str("## Executive Summary\n\n");
-        markdown.push_str(&format!("**Overall Risk Level:** {}\n", report.executive_summary.overall_risk_level));
-        markdown.push_str(&format!("**Security Score:** {:.2}/100\n", report.executive_summary.security_score));
-        markdown.push_str(&format!("**Total Vulnerabilities:** {}\n\n", report.executive_summary.total_vulnerabilities));
+        // An incomplete run must not read as a clean bill of health
+        if let Some(completeness) = report.completeness.as_ref().filter(|c| c.score < 100.0) {
+            markdown.push_str(&format!("> ⚠️ **{}** ", text.get("incomplete")));
+            if let Some(compiled) = completeness.files_compiled {
+                markdown.push_str(&format!("{} ", text.fill("files_compiled", &[&compiled, &completeness.files_total])));
+            }
+            markdown.push_str(&format!("{}\n", text.get("incomplete_note")));
+            for run in completeness.incomplete_runs() {
+                let reason = run.reason.map(|reason| reason.to_string()).unwrap_or_default();
+                match &run.contract {
+                    Some(contract) => markdown.push_str(&format!("> - {}: {}\n", text.fill("tool_on_contract", &[&run.tool, contract]), reason)),
+                    None => markdown.push_str(&format!("> - {}: {}\n", run.tool, reason)),
+                }
+            }
+            markdown.push('\n');
+        }
+        // Tools cut short still found something, but not everything they would have
+        if let Some(completeness) = report.completeness.as_ref().filter(|c| c.partial_runs().next().is_some()) {
+            markdown.push_str(&format!("> ⏳ **{}**\n", text.get("partial_results")));
+            for run in completeness.partial_runs() {
+                let detail = run.detail.as_deref().unwrap_or_default();
+                match &run.contract {
+                    Some(contract) => markdown.push_str(&format!("> - {}: {}\n", text.fill("tool_on_contract", &[&run.tool, contract]), detail)),
+                    None => markdown.push_str(&format!("> - {}: {}\n", run.tool, detail)),
+                }
+            }
+            markdown.push('\n');
+        }
+        // Risks accepted for a limited time come back until someone accepts them again
+        if !report.expired_acceptances.is_empty() {
+            markdown.push_str(&format!("> ⏰ **{}**\n", text.get("expired_acceptances")));
+            for expired in &report.expired_acceptances {
+                let title = match report.finding_numbers.get(&expired.finding_id) {
+                    Some(number) => format!("{} {}", number, expired.title),
+                    None => expired.title.clone(),
+                };
+                markdown.push_str(&format!("> - {}", text.fill("expired_acceptance", &[&title, &expired.expired])));
+                if let Some(owner) = &expired.owner {
+                    markdown.push_str(&format!(" ({}: {})", text.get("owner"), owner));
+                }
+                markdown.push('\n');
+            }
+            markdown.push('\n');
+        }
+        if let Some(reason) = report.completeness.as_ref().and_then(|c| c.ai_unavailable.as_ref()) {
+            markdown.push_str(&format!("> 🤖 **{}:** {}\n\n", text.get("ai_unavailable"), reason));
+        }
+
+        self.add_scope_section(&mut markdown, &report.metadata.project, text);
+        if let Some(architecture) = &report.architecture {
+            markdown.push_str(&format!("## {}\n\n", text.get("architecture")));
+            markdown.push_str(&self.architecture_overview(architecture));
+        }
 
-        markdown.push_str("### Severity Distribution\n\n");
-        markdown.push_str(&format!("- 🔴 Critical: {}\n", report.executive_summary.critical_findings));
-        markdown.push_str(&format!("- 🟠 High: {}\n", report.executive_summary.high_risk_findings));
-        markdown.push_str(&format!("- 🟡 Medium: {}\n", report.executive_summary.medium_risk_findings));
-        markdown.push_str(&format!("- 🟢 Low: {}\n\n", report.executive_summary.low_risk_findings));
+        // Executive Summary
+        markdown.push_str(&format!("## {}\n\n", text.get("executive_summary")));
+        markdown.push_str(&format!("**{}:** {}\n", text.get("risk_level"), report.executive_summary.overall_risk_level));
+        markdown.push_str(&format!("**{}:** {:.2}/100\n", text.get("security_score"), report.executive_summary.security_score));
+        markdown.push_str(&format!("**{}:** {:.2}/100\n", text.get("normalized_score"), report.executive_summary.normalized_security_score));
+        markdown.push_str(&format!("**{}:** {}\n\n", text.get("total_vulnerabilities"), report.executive_summary.total_vulnerabilities));
+
+        markdown.push_str(&format!("### {}\n\n", text.get("severity_distribution")));
+        markdown.push_str(&format!("- 🔴 {}: {}\n", text.get("critical"), report.executive_summary.critical_findings));
+        markdown.push_str(&format!("- 🟠 {}: {}\n", text.get("high"), report.executive_summary.high_risk_findings));
+        markdown.push_str(&format!("- 🟡 {}: {}\n", text.get("medium"), report.executive_summary.medium_risk_findings));
+        markdown.push_str(&format!("- 🟢 {}: {}\n\n", text.get("low"), report.executive_summary.low_risk_findings));
 
         // Key Findings
         if !report.executive_summary.key_findings.is_empty() {
-            markdown.push_str("### Key Findings\n\n");
+            markdown.push_str(&format!("### {}\n\n", text.get("key_findings")));
             for finding in &report.executive_summary.key_findings {
                 markdown.push_str(&format!("- {}\n", finding));
             }
-            markdown.push_str("\n");
+            markdown.push('\n');
+        }
+
+        if let Some(comparison) = &report.baseline {
+            self.add_baseline_section(&mut markdown, report, comparison, text);
         }
 
         // Vulnerabilities
-        markdown.push_str("## Vulnerability Analysis\n\n");
-        
+        markdown.push_str(&format!("## {}\n\n", text.get("vulnerability_analysis")));
+        emit(&markdown)?;
+        markdown.clear();
+
         // Group vulnerabilities by severity
         let mut critical = Vec::new();
         let mut high = Vec::new();
@@ -766,77 +1812,262 @@ This vulnerability could potentially lead to [describe specific impact based on
                 _ => info.push(vuln),
             }
         }
+        // Highest score first within each severity
+        for section in [&mut critical, &mut high, &mut medium, &mut low, &mut info] {
+            section.sort_by(|a, b| b.score().partial_cmp(&a.score()).unwrap_or(std::cmp::Ordering::Equal));
+        }
+
+        // Severity sections are independent, so they render in parallel and are written in order
+        let sections = [
+            ("Critical", critical, "🔴"),
+            ("High", high, "🟠"),
+            ("Medium", medium, "🟡"),
+            ("Low", low, "🟢"),
+            ("Informational", info, "🔵"),
+        ];
+        std::thread::scope(|scope| -> Result<()> {
+            let renders: Vec<_> = sections
+                .iter()
+                .map(|(severity, vulns, icon)| {
+                    scope.spawn(move || -> Result<String> {
+                        let mut section = String::new();
+                        self.add_vulnerability_section(&mut section, severity, vulns, report, icon, text)?;
+                        Ok(section)
+                    })
+                })
+                .collect();
+            for render in renders {
+                let section = render.join().map_err(|_| anyhow!("Rendering a severity section panicked"))??;
+                emit(&section)?;
+            }
+            Ok(())
+        })?;
+
+        if let Some(review) = &report.test_review {
+            markdown.push_str(&format!("## {}\n\n", text.get("test_blind_spots")));
+            markdown.push_str(&self.test_blind_spots(review));
+        }
 
-        self.add_vulnerability_section(&mut markdown, "Critical", &critical, "🔴")?;
-        self.add_vulnerability_section(&mut markdown, "High", &high, "🟠")?;
-        self.add_vulnerability_section(&mut markdown, "Medium", &medium, "🟡")?;
-        self.add_vulnerability_section(&mut markdown, "Low", &low, "🟢")?;
-        self.add_vulnerability_section(&mut markdown, "Informational", &info, "🔵")?;
+        let gas_score = report.technical_details.analysis_metrics.gas_optimization_score;
+        let gas: Vec<&Vulnerability> = report
+            .vulnerability_analysis
+            .vulnerabilities
+            .iter()
+            .filter(|vuln| vuln.category == VulnerabilityCategory::GasOptimization)
+            .collect();
+        if gas_score > 0.0 || !gas.is_empty() {
+            self.add_gas_section(&mut markdown, report, &gas, text);
+        }
 
         // Recommendations
-        markdown.push_str("## Recommendations\n\n");
+        markdown.push_str(&format!("## {}\n\n", text.get("recommendations")));
         for (i, rec) in report.recommendations.iter().enumerate() {
             markdown.push_str(&format!("### {}. {}\n\n", i + 1, rec.title));
-            markdown.push_str(&format!("**Priority:** {}\n", rec.priority));
-            markdown.push_str(&format!("**Effort:** {}\n", rec.effort));
-            markdown.push_str(&format!("**Impact:** {}\n\n", rec.impact));
+            markdown.push_str(&format!("**{}:** {}\n", text.get("priority"), rec.priority));
+            markdown.push_str(&format!("**{}:** {}\n", text.get("effort"), rec.effort));
+            markdown.push_str(&format!("**{}:** {}\n\n", text.get("impact"), rec.impact));
+            let related: Vec<&str> =
+                rec.related_vulnerabilities.iter().filter_map(|id| report.finding_numbers.get(id)).map(String::as_str).collect();
+            if !related.is_empty() {
+                markdown.push_str(&format!("**{}:** {}\n\n", text.get("related_findings"), related.join(", ")));
+            }
             markdown.push_str(&format!("{}\n\n", rec.description));
         }
 
         // Technical Details
-        markdown.push_str("## Technical Details\n\n");
-        markdown.push_str(&format!("**Analysis Duration:** {:.2} seconds\n", report.technical_details.analysis_duration));
-        markdown.push_str(&format!("**Lines of Code:** {}\n", report.technical_details.analysis_metrics.lines_of_code));
-        markdown.push_str(&format!("**Functions Analyzed:** {}\n", report.technical_details.analysis_metrics.functions_analyzed));
-        markdown.push_str(&format!("**Complexity Score:** {:.2}\n\n", report.technical_details.analysis_metrics.complexity_score));
+        markdown.push_str(&format!("## {}\n\n", text.get("technical_details")));
+        let duration = format!("{:.2}", report.technical_details.analysis_duration);
+        markdown.push_str(&format!("**{}:** {}\n", text.get("analysis_duration"), text.fill("seconds", &[&duration])));
+        markdown.push_str(&format!("**{}:** {}\n", text.get("lines_of_code"), report.technical_details.analysis_metrics.lines_of_code));
+        markdown.push_str(&format!("**{}:** {}\n", text.get("functions_analyzed"), report.technical_details.analysis_metrics.functions_analyzed));
+        markdown.push_str(&format!("**{}:** {:.2}\n", text.get("complexity_score"), report.technical_details.analysis_metrics.complexity_score));
+        markdown.push_str(&format!("**{}:** {:.1}/100\n", text.get("gas_score"), gas_score));
+        markdown.push_str(&format!(
+            "**{}:** {:.2}/100 = {}\n\n",
+            text.get("normalized_score"),
+            report.executive_summary.normalized_security_score,
+            text.fill("score_formula", &[&SCORE_UNIT_LINES])
+        ));
 
         // Appendices
         if !report.appendices.is_empty() {
-            markdown.push_str("## Appendices\n\n");
+            markdown.push_str(&format!("## {}\n\n", text.get("appendices")));
             for appendix in &report.appendices {
                 markdown.push_str(&format!("### {}\n\n", appendix.title));
                 markdown.push_str(&format!("{}\n\n", appendix.content));
             }
         }
 
-        Ok(markdown)
+        emit(&markdown)
+    }
+
+    /// New and fixed findings since the baseline; persistent ones are only counted
+    fn add_baseline_section(&self, markdown: &mut String, report: &ComprehensiveReport, comparison: &BaselineComparison, text: &Catalog) {
+        markdown.push_str(&format!("## {}\n\n", text.get("baseline_changes")));
+        markdown.push_str(&format!(
+            "{}\n\n",
+            text.fill(
+                "baseline_counts",
+                &[&comparison.new.len(), &comparison.fixed.len(), &comparison.persistent.len(), &comparison.baseline]
+            )
+        ));
+
+        let row = |vuln: &Vulnerability| {
+            let file = report.finding_files.get(&vuln.id).unwrap_or(&vuln.file_path);
+            let location = match vuln.line_number {
+                Some(line) => format!("{}:{}", file, line),
+                None => file.clone(),
+            };
+            // Fixed findings are no longer in the report, so only new ones have a number
+            let title = match report.finding_numbers.get(&vuln.id) {
+                Some(number) => format!("{} {}", number, vuln.title),
+                None => vuln.title.clone(),
+            };
+            format!("| {} | {} | {} | {} |\n", text.severity(&vuln.severity), title, location, vuln.tools().join(", "))
+        };
+        let header = format!(
+            "| {} | {} | {} | {} |\n|---|---|---|---|\n",
+            text.get("severity"),
+            text.get("finding"),
+            text.get("file"),
+            text.get("tool")
+        );
+
+        let new: HashSet<&str> = comparison.new.iter().map(String::as_str).collect();
+        let mut added: Vec<&Vulnerability> =
+            report.vulnerability_analysis.vulnerabilities.iter().filter(|vuln| new.contains(vuln.id.as_str())).collect();
+        if !added.is_empty() {
+            added.sort_by_key(|vuln| std::cmp::Reverse(vuln.severity_priority()));
+            markdown.push_str(&format!("### {}\n\n{}", text.get("new_findings"), header));
+            for vuln in added {
+                markdown.push_str(&row(vuln));
+            }
+            markdown.push('\n');
+        }
+        if !comparison.fixed.is_empty() {
+            markdown.push_str(&format!("### {}\n\n{}", text.get("fixed_findings"), header));
+            for vuln in &comparison.fixed {
+                markdown.push_str(&row(vuln));
+            }
+            markdown.push('\n');
+        }
     }
 
     /// Add vulnerability section to markdown
-    fn add_vulnerability_section(&self, markdown: &mut String, severity: &str, vulnerabilities: &[&Vulnerability], icon: &str) -> Result<()> {
+    /// Gas score and the gas findings in one table, in the order of their numbers
+    fn add_gas_section(&self, markdown: &mut String, report: &ComprehensiveReport, gas: &[&Vulnerability], text: &Catalog) {
+        let gas_score = report.technical_details.analysis_metrics.gas_optimization_score;
+        markdown.push_str(&format!("## {}\n\n", text.get("gas_optimization")));
+        markdown.push_str(&format!("**{}:** {:.1}/100\n\n", text.get("gas_score"), gas_score));
+        if gas.is_empty() {
+            markdown.push_str(&format!("{}\n\n", text.get("gas_clean")));
+            return;
+        }
+
+        // Reports number findings by severity, then highest score first
+        let mut gas = gas.to_vec();
+        gas.sort_by(|a, b| {
+            b.severity_priority()
+                .cmp(&a.severity_priority())
+                .then(b.score().partial_cmp(&a.score()).unwrap_or(std::cmp::Ordering::Equal))
+        });
+        markdown.push_str(&format!("| {} | {} |\n|---|---|\n", text.get("finding"), text.get("file")));
+        for vuln in gas {
+            let file = report.finding_files.get(&vuln.id).unwrap_or(&vuln.file_path);
+            let location = match vuln.line_number {
+                Some(line) => format!("{}:{}", file, line),
+                None => file.clone(),
+            };
+            let title = match report.finding_numbers.get(&vuln.id) {
+                Some(number) => format!("{} {}", number, vuln.title),
+                None => vuln.title.clone(),
+            };
+            markdown.push_str(&format!("| {} | {} |\n", title, location));
+        }
+        markdown.push('\n');
+    }
+
+    fn add_vulnerability_section(
+        &self,
+        markdown: &mut String,
+        severity: &str,
+        vulnerabilities: &[&Vulnerability],
+        report: &ComprehensiveReport,
+        icon: &str,
+        text: &Catalog,
+    ) -> Result<()> {
         if vulnerabilities.is_empty() {
             return Ok(());
         }
 
-        markdown.push_str(&format!("### {} {} Vulnerabilities\n\n", icon, severity));
+        markdown.push_str(&format!("### {} {}\n\n", icon, text.fill("severity_vulnerabilities", &[&text.severity(severity)])));
 
-        for (i, vuln) in vulnerabilities.iter().enumerate() {
-            markdown.push_str(&format!("#### {}.{} {}\n\n", severity.chars().next().unwrap(), i + 1, vuln.title));
-            markdown.push_str(&format!("**Description:** {}\n\n", vuln.description));
-            markdown.push_str(&format!("**File:** {}\n", vuln.file_path));
+        for vuln in vulnerabilities {
+            // Numbered once for the report, so finding ids are the same in every language and format
+            let number = report.finding_numbers.get(&vuln.id).map(String::as_str).unwrap_or_default();
+            markdown.push_str(&format!("#### {} {}\n\n", number, vuln.title));
+            if let Some(latest) = vuln.severity_overrides.last() {
+                markdown.push_str(&format!(
+                    "**{}:** {}\n\n> {}\n\n",
+                    text.get("adjusted_severity"),
+                    text.fill("originally", &[&text.severity(&vuln.severity), &text.severity(vuln.original_severity())]),
+                    text.fill("adjusted_by", &[&latest.author, &latest.at.format("%Y-%m-%d"), &latest.justification])
+                ));
+            }
+            markdown.push_str(&format!("**{}:** {}\n\n", text.get("description"), vuln.description));
+            let file = report.finding_files.get(&vuln.id).unwrap_or(&vuln.file_path);
+            markdown.push_str(&format!("**{}:** {}\n", text.get("file"), file));
             if let Some(line) = vuln.line_number {
-                markdown.push_str(&format!("**Line:** {}\n", line));
+                markdown.push_str(&format!("**{}:** {}\n", text.get("line"), line));
+            }
+            markdown.push_str(&format!("**{}:** {}\n", text.get("tool"), vuln.tools().join(", ")));
+            if let Some(surfaces) = report.finding_surfaces.get(&vuln.id) {
+                let surfaces: Vec<String> = surfaces.iter().map(|surface| format!("`{}`", surface)).collect();
+                markdown.push_str(&format!("**{}:** {}\n", text.get("surfaces"), surfaces.join(", ")));
+            }
+            if let Some(provenance) = &vuln.provenance {
+                markdown.push_str(&format!(
+                    "**{}:** {} ({} {}{})\n",
+                    text.get("first_seen"),
+                    provenance.detected_at.format("%Y-%m-%d %H:%M UTC"),
+                    vuln.tool,
+                    provenance.tool_version.as_deref().unwrap_or("unknown version"),
+                    provenance.git_commit.as_deref().map(|commit| format!(", commit {}", &commit[..commit.len().min(12)])).unwrap_or_default()
+                ));
+            }
+            if let Some(cvss) = &vuln.cvss {
+                markdown.push_str(&format!("**{}:** {:.1} (`{}`)\n", text.get("cvss"), cvss.score, cvss.vector));
+            }
+            markdown.push_str(&format!("**{}:** {:.2}\n", text.get("confidence"), vuln.confidence));
+            if let Some(verification) = report.poc_verification.get(&vuln.id) {
+                let outcome = match verification.status {
+                    PocStatus::Confirmed => "poc_confirmed",
+                    PocStatus::CompileFailed => "poc_compile_failed",
+                    PocStatus::NotExploited => "poc_not_exploited",
+                };
+                markdown.push_str(&format!("**{}:** {}\n", text.get("poc"), text.get(outcome)));
             }
-            markdown.push_str(&format!("**Tool:** {}\n", vuln.tool));
-            markdown.push_str(&format!("**Confidence:** {:.2}\n\n", vuln.confidence));
+            // Reports saved before fingerprints were numbered lack them
+            let fingerprint = report.finding_fingerprints.get(&vuln.id).cloned().unwrap_or_else(|| vuln.fingerprint(0));
+            markdown.push_str(&format!("**{}:** `{}`\n\n", text.get("fingerprint"), fingerprint));
 
             if let Some(code) = &vuln.code_snippet {
-                markdown.push_str("**Code Snippet:**\n");
+                markdown.push_str(&format!("**{}:**\n", text.get("code_snippet")));
                 markdown.push_str("```solidity\n");
                 markdown.push_str(code);
                 markdown.push_str("\n```\n\n");
             }
 
             if let Some(recommendation) = &vuln.recommendation {
-                markdown.push_str(&format!("**Recommendation:** {}\n\n", recommendation));
+                markdown.push_str(&format!("**{}:** {}\n\n", text.get("recommendation"), recommendation));
             }
 
             if !vuln.references.is_empty() {
-                markdown.push_str("**References:**\n");
+                markdown.push_str(&format!("**{}:**\n", text.get("references")));
                 for reference in &vuln.references {
                     markdown.push_str(&format!("- {}\n", reference));
                 }
-                markdown.push_str("\n");
+                markdown.push('\n');
             }
 
             markdown.push_str("---\n\n");
@@ -845,16 +2076,152 @@ This vulnerability could potentially lead to [describe specific impact based on
         Ok(())
     }
 
+    /// Module summary and diagram of the system, in the configured notation
+    fn architecture_overview(&self, architecture: &Architecture) -> String {
+        let in_run = architecture.components.iter().filter(|component| !component.file.is_empty()).count();
+        let modules: Vec<String> = Module::ALL
+            .iter()
+            .filter_map(|&module| match architecture.members(module).count() {
+                0 => None,
+                count => Some(format!("{}: {}", module, count)),
+            })
+            .collect();
+        format!(
+            "{} contracts, interfaces and libraries in scope ({}). Dashed arrows are inheritance; \
+             solid arrows are external calls, library use and deployments.\n\n{}\n",
+            in_run,
+            modules.join(", "),
+            architecture.to_markdown(&self.config.reporting.architecture_format)
+        )
+    }
+
+    /// Tests that pass without showing the code is correct, grouped by test
+    fn test_blind_spots(&self, review: &TestReview) -> String {
+        if review.blind_spots.is_empty() {
+            return format!(
+                "No testing shortcuts found in {} test(s) across {} file(s).\n\n",
+                review.tests_reviewed, review.files_reviewed
+            );
+        }
+
+        let mut section = format!(
+            "{} of {} test(s) in {} file(s) can pass for reasons other than the code being correct. \
+             Their passing gives false comfort until the shortcuts below are removed.\n\n",
+            review.tests_affected,
+            review.tests_reviewed,
+            review.files_reviewed
+        );
+        section.push_str("| Test | Line | Shortcut | Why it misleads |\n|------|------|----------|-----------------|\n");
+        for spot in &review.blind_spots {
+            section.push_str(&format!(
+                "| `{}.{}` ({}) | {} | {} | {} |\n",
+                spot.test_contract,
+                spot.test,
+                spot.file,
+                spot.line,
+                spot.shortcut,
+                spot.detail.replace('|', "\\|")
+            ));
+        }
+        section.push_str("\nShortcuts in `setUp` apply to every test of their contract.\n\n");
+        section
+    }
+
+    /// Front matter describing the audited contracts
+    fn add_scope_section(&self, markdown: &mut String, project: &ProjectMetadata, text: &Catalog) {
+        if project.contracts.is_empty() {
+            return;
+        }
+
+        markdown.push_str(&format!("## {}\n\n", text.get("scope")));
+        markdown.push_str(&format!(
+            "**{}:** {}\n",
+            text.get("contracts"),
+            text.fill("contracts_summary", &[&project.contracts.len(), &project.deployable().count(), &project.lines_of_code()])
+        ));
+        if !project.compiler_versions.is_empty() {
+            markdown.push_str(&format!("**{}:** {}\n", text.get("compiler_versions"), project.compiler_versions.join(", ")));
+        }
+        if !project.licenses.is_empty() {
+            markdown.push_str(&format!("**{}:** {}\n", text.get("licenses"), project.licenses.join(", ")));
+        }
+        markdown.push('\n');
+
+        markdown.push_str(&format!(
+            "| {} | {} | {} | Solidity | {} | {} | {} |\n",
+            text.get("contract"),
+            text.get("kind"),
+            text.get("file"),
+            text.get("license"),
+            text.get("loc"),
+            text.get("address")
+        ));
+        markdown.push_str("|----------|------|------|----------|---------|-----|---------|\n");
+        for contract in &project.contracts {
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} | {} |\n",
+                contract.name,
+                contract.kind,
+                contract.file,
+                contract.solc_version.as_deref().unwrap_or("-"),
+                contract.license.as_deref().unwrap_or("-"),
+                contract.lines_of_code,
+                match (&contract.address, &contract.network) {
+                    (Some(address), Some(network)) => format!("`{}` ({})", address, network),
+                    (Some(address), None) => format!("`{}`", address),
+                    (None, _) => "-".to_string(),
+                }
+            ));
+        }
+        markdown.push('\n');
+
+        if !project.dependencies.is_empty() {
+            markdown.push_str(&format!("### {}\n\n", text.get("dependencies")));
+            markdown.push_str(&format!("| {} | {} | {} |\n", text.get("package"), text.get("version"), text.get("source")));
+            markdown.push_str("|---------|---------|--------|\n");
+            for dependency in &project.dependencies {
+                markdown.push_str(&format!(
+                    "| {} | {} | {} |\n",
+                    dependency.name,
+                    dependency.version.as_deref().unwrap_or("unknown"),
+                    dependency.source.as_deref().unwrap_or("-")
+                ));
+            }
+            markdown.push('\n');
+        }
+    }
+
     /// Generate HTML report
+    #[cfg(feature = "reporting")]
     fn generate_html_report(&self, report: &ComprehensiveReport) -> Result<String> {
-        let markdown = self.generate_markdown_report(report)?;
-        
+        let mut html = Vec::new();
+        self.write_html_report(report, &mut html)?;
+        Ok(String::from_utf8(html)?)
+    }
+
+    /// Write the HTML report to `out`, a single-language body as its sections are rendered
+    #[cfg(feature = "reporting")]
+    fn write_html_report(&self, report: &ComprehensiveReport, out: &mut dyn Write) -> Result<()> {
+        let catalogs = i18n::catalogs(&self.config)?;
+        let open_pre = |text: &Catalog| format!("<pre lang=\"{}\">", explorer::escape_html(&text.code));
+
+        // Each language gets its own font, so CJK text is not drawn from fallbacks
+        let fonts: String = catalogs
+            .iter()
+            .filter_map(|text| {
+                let font = text.font.as_deref()?.replace(['<', '>', '{', '}'], "");
+                Some(format!("[lang=\"{}\"] {{ font-family: {}; }}\n        ", explorer::escape_html(&text.code), font))
+            })
+            .collect();
+
         // Convert markdown to HTML (simplified implementation)
-        let html = format!(
+        write!(
+            out,
             r#"<!DOCTYPE html>
-<html>
+<html lang="{}">
 <head>
-    <title>Security Audit Report - {}</title>
+    <meta charset="utf-8">
+    <title>{} - {}</title>
     <style>
         body {{ font-family: Arial, sans-serif; margin: 40px; }}
         h1 {{ color: #333; }}
@@ -866,17 +2233,54 @@ This vulnerability could potentially lead to [describe specific impact based on
         .severity-low {{ color: #28a745; }}
         .code {{ background-color: #f8f9fa; padding: 10px; border-radius: 4px; }}
         .vulnerability {{ border: 1px solid #ddd; padding: 15px; margin: 10px 0; border-radius: 5px; }}
+        .bilingual {{ display: grid; grid-template-columns: 1fr 1fr; gap: 24px; }}
+        .bilingual pre {{ white-space: pre-wrap; }}
+        {}{}
     </style>
 </head>
 <body>
-    <pre>{}</pre>
-</body>
-</html>"#,
-            report.metadata.contract_name,
-            markdown
-        );
+    "#,
+            explorer::escape_html(&catalogs[0].code),
+            explorer::escape_html(catalogs[0].get("report_title")),
+            explorer::escape_html(&report.metadata.project.title),
+            fonts,
+            explorer::STYLE
+        )?;
+
+        if let [text] = catalogs.as_slice() {
+            let links = LocationLinks::new(report, text);
+            out.write_all(open_pre(text).as_bytes())?;
+            self.render_markdown(report, text, &mut |section| Ok(out.write_all(links.apply(&explorer::escape_html(section)).as_bytes())?))?;
+            out.write_all(b"</pre>")?;
+        } else {
+            let versions = catalogs
+                .iter()
+                .map(|text| Ok(LocationLinks::new(report, text).apply(&explorer::escape_html(&self.markdown_string(report, text)?))))
+                .collect::<Result<Vec<_>>>()?;
+            let pre = |text: &Catalog, body: &str| format!("{}{}</pre>", open_pre(text), body);
+            let body = match bilingual_sections(&versions) {
+                Some(rows) if self.config.reporting.bilingual_layout == "side-by-side" => rows
+                    .iter()
+                    .map(|row| format!("<div class=\"bilingual\">{}{}</div>", pre(&catalogs[0], &row[0]), pre(&catalogs[1], &row[1])))
+                    .collect::<Vec<_>>()
+                    .join("\n    "),
+                Some(rows) => rows
+                    .iter()
+                    .map(|row| format!("{}\n    {}", pre(&catalogs[0], &row[0]), pre(&catalogs[1], &row[1])))
+                    .collect::<Vec<_>>()
+                    .join("\n    "),
+                None => catalogs.iter().zip(&versions).map(|(text, body)| pre(text, body)).collect::<Vec<_>>().join("\n    "),
+            };
+            out.write_all(body.as_bytes())?;
+        }
 
-        Ok(html)
+        let source_explorer = if report.sources.is_empty() {
+            String::new()
+        } else {
+            explorer::render(&report.sources, &report.vulnerability_analysis.vulnerabilities)
+        };
+        write!(out, "\n    {}\n    {}\n</body>\n</html>", source_explorer, explorer::SCRIPT)?;
+        Ok(())
     }
 
     /// Generate JSON report
@@ -886,6 +2290,7 @@ This vulnerability could potentially lead to [describe specific impact based on
     }
 
     /// Generate PDF report (placeholder implementation)
+    #[cfg(feature = "reporting")]
     fn generate_pdf_report(&self, report: &ComprehensiveReport) -> Result<String> {
         // This would require a PDF generation library like wkhtmltopdf or similar
         // For now, return HTML that can be converted to PDF
@@ -893,6 +2298,122 @@ This vulnerability could potentially lead to [describe specific impact based on
     }
 }
 
+/// The two language versions of a bilingual report, paired by `## ` section.
+/// `None` for a single language, or when the versions do not line up.
+fn bilingual_sections(versions: &[String]) -> Option<Vec<[String; 2]>> {
+    let [first, second] = versions else {
+        return None;
+    };
+    let split = |markdown: &str| {
+        let mut sections = vec![String::new()];
+        for line in markdown.split_inclusive('\n') {
+            if line.starts_with("## ") {
+                sections.push(String::new());
+            }
+            sections.last_mut().unwrap().push_str(line);
+        }
+        sections
+    };
+    let (first, second) = (split(first), split(second));
+    if first.len() != second.len() {
+        return None;
+    }
+    Some(first.into_iter().zip(second).map(|(a, b)| [a, b]).collect())
+}
+
+/// Links from the locations of findings in escaped report text to their lines in the bundled sources
+///
+/// One pass over each section finds every `File:`/`Line:` pair, instead of
+/// a search of the whole report per finding.
+#[cfg(feature = "reporting")]
+struct LocationLinks {
+    location: regex::Regex,
+    anchors: HashMap<(String, usize), String>,
+}
+
+#[cfg(feature = "reporting")]
+impl LocationLinks {
+    fn new(report: &ComprehensiveReport, text: &Catalog) -> Self {
+        let label = |key: &str| regex::escape(&format!("**{}:** ", explorer::escape_html(text.get(key))));
+        let location = regex::Regex::new(&format!("{}(.*)\n{}(\\d+)\n", label("file"), label("line"))).expect("valid location pattern");
+        let anchors = report
+            .vulnerability_analysis
+            .vulnerabilities
+            .iter()
+            .filter_map(|vuln| {
+                let line = vuln.line_number?;
+                let anchor = explorer::line_anchor(&report.sources, &vuln.file_path, line)?;
+                Some(((explorer::escape_html(&vuln.file_path), line), anchor))
+            })
+            .collect();
+        Self { location, anchors }
+    }
+
+    fn apply(&self, body: &str) -> String {
+        if self.anchors.is_empty() {
+            return body.to_string();
+        }
+        self.location
+            .replace_all(body, |captures: &regex::Captures| {
+                let line = captures[2].parse().unwrap_or(0);
+                match self.anchors.get(&(captures[1].to_string(), line)) {
+                    Some(anchor) => format!("<a href=\"#{}\">{}</a>", anchor, &captures[0]),
+                    None => captures[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+}
+
+/// Error for report formats left out of slim builds
+#[cfg(not(feature = "reporting"))]
+fn missing_reporting(format: &str) -> anyhow::Error {
+    anyhow!("{} reports need a build with the `reporting` feature", format.to_uppercase())
+}
+
+/// Line coverage as the metrics table shows it
+fn coverage_text(coverage: Option<f64>) -> String {
+    match coverage {
+        Some(percentage) => format!("{:.1}%", percentage),
+        None => "not measured".to_string(),
+    }
+}
+
+/// Functions of the measured contracts no fuzzing call or test exercised, as `Contract.function`
+fn unexercised_functions(results: &AnalysisResults) -> Vec<String> {
+    results
+        .fuzz_coverage
+        .iter()
+        .flat_map(|coverage| coverage.unreached().map(move |function| format!("{}.{}", coverage.contract, function.name)))
+        .collect()
+}
+
+/// Note under the metrics table naming the functions nothing exercised
+fn unexercised_note(results: &AnalysisResults) -> String {
+    let functions = unexercised_functions(results);
+    if functions.is_empty() {
+        return String::new();
+    }
+    let names: Vec<String> = functions.iter().map(|function| format!("`{}`", function)).collect();
+    format!("\n**Never exercised:** {} function(s) no fuzzing call or test reached: {}\n", functions.len(), names.join(", "))
+}
+
+/// Normalized security score of a run, computed for results saved without it
+fn normalized_score(results: &AnalysisResults) -> f64 {
+    results
+        .metrics
+        .normalized_security_score
+        .unwrap_or_else(|| normalized_security_score(&results.vulnerabilities, results.metrics.lines_of_code))
+}
+
+/// Project metadata of a run, falling back to its contract name for results saved without it
+fn project_metadata(results: &AnalysisResults) -> ProjectMetadata {
+    results.metadata.clone().unwrap_or_else(|| ProjectMetadata {
+        title: results.contract_name.clone(),
+        ..ProjectMetadata::default()
+    })
+}
+
 impl Default for ReportGenerator {
     fn default() -> Self {
         Self::new(Config::default())
@@ -0,0 +1,100 @@
+//! Report post-processing hooks
+//!
+//! Teams add client-specific sections to deliverables or push them to an
+//! internal wiki. Hooks configured under `[reporting.hooks]` run after each
+//! report is written, a shell command or a WASI module each, with the report
+//! JSON on stdin and the report's path in `SECURECHAIN_REPORT`. What a hook
+//! writes to stdout is saved to its `output` file, next to the report and
+//! only when the hook succeeds; the report itself is never touched, and a
+//! hook that fails or times out is reported without failing the command.
+
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::status;
+use crate::utils::artifact;
+use crate::utils::config::{ReportHook, ReportingConfig};
+use crate::utils::platform;
+use crate::utils::retention;
+
+/// Run every hook for the report written to `report`, given its JSON form
+pub fn run(reporting: &ReportingConfig, report: &Path, format: &str, json: &str) {
+    for (name, hook) in &reporting.hooks {
+        match run_hook(name, hook, &reporting.wasm_runtime, report, format, json) {
+            Ok(Some(output)) => status!("🪝 Report hook {} wrote {}", name, output.display()),
+            Ok(None) => status!("🪝 Report hook {} ran", name),
+            Err(e) => {
+                log::warn!("Report hook {} failed: {}", name, e);
+                status!("⚠️  Report hook {} failed: {}", name, e);
+            }
+        }
+    }
+}
+
+/// Run one hook, returning the file its output was saved to
+fn run_hook(name: &str, hook: &ReportHook, wasm_runtime: &str, report: &Path, format: &str, json: &str) -> Result<Option<PathBuf>> {
+    let output = match hook.output.is_empty() {
+        true => None,
+        false => {
+            let stem = report.file_stem().unwrap_or_default().to_string_lossy();
+            let path = report.parent().unwrap_or(Path::new("")).join(hook.output.replace("{stem}", &stem));
+            if path == report {
+                return Err(anyhow!("output {} is the report itself", path.display()));
+            }
+            Some(path)
+        }
+    };
+
+    let mut command = match (&hook.command, &hook.wasm) {
+        (Some(command), _) => {
+            let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+            let mut shell = std::process::Command::new(shell);
+            shell.arg(flag).arg(command);
+            shell
+        }
+        (None, Some(module)) => {
+            let mut runtime = platform::command(wasm_runtime);
+            runtime.arg("run").arg(module);
+            runtime
+        }
+        (None, None) => return Err(anyhow!("no command or wasm module")),
+    };
+
+    let stdin = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).suffix(".json").tempfile()?;
+    std::fs::write(stdin.path(), json)?;
+    let stdout = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).tempfile()?;
+    let stderr = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).tempfile()?;
+    let mut child = command
+        .env("SECURECHAIN_REPORT", report)
+        .env("SECURECHAIN_REPORT_FORMAT", format)
+        .env("SECURECHAIN_HOOK", name)
+        .stdin(std::fs::File::open(stdin.path())?)
+        .stdout(stdout.reopen()?)
+        .stderr(stderr.reopen()?)
+        .spawn()
+        .map_err(|e| anyhow!("could not start: {}", e))?;
+
+    let deadline = Instant::now() + Duration::from_secs(hook.timeout);
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow!("timed out after {}s", hook.timeout));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    if !status.success() {
+        let errors = std::fs::read_to_string(stderr.path()).unwrap_or_default();
+        let reason = errors.lines().map(str::trim).rfind(|line| !line.is_empty()).unwrap_or("no output");
+        return Err(anyhow!("{}: {}", status, reason));
+    }
+
+    if let Some(path) = &output {
+        artifact::write_atomic(path, std::fs::read(stdout.path())?)?;
+    }
+    Ok(output)
+}
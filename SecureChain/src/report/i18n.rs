@@ -0,0 +1,354 @@
+//! Report translations
+//!
+//! The fixed text of the audit report, its headings, field labels and
+//! severity names, comes from a message catalog per language. English and
+//! Korean are built in. Other languages are added with a TOML file holding a
+//! `code`, an optional `font` and a `[messages]` table; messages it leaves out
+//! fall back to English. Findings, tool output and AI-written text are shown
+//! as they were produced.
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::utils::config::Config;
+
+/// Languages with a built-in catalog
+pub const BUILT_IN: &[&str] = &["en", "ko"];
+
+/// Layouts of a bilingual report
+pub const LAYOUTS: &[&str] = &["sequential", "side-by-side"];
+
+const ENGLISH: &[(&str, &str)] = &[
+    ("report_title", "Security Audit Report"),
+    ("report_id", "Report ID"),
+    ("generated", "Generated"),
+    ("version", "Version"),
+    ("tools_used", "Tools Used"),
+    ("tool_versions", "Tool Versions"),
+    ("completeness", "Analysis Completeness"),
+    ("entrypoints", "Only Code Reached From"),
+    ("incomplete", "Incomplete analysis."),
+    ("files_compiled", "{0} of {1} files compiled."),
+    ("incomplete_note", "Absence of findings below does not mean the code is free of them. Not completed:"),
+    ("tool_on_contract", "{0} on {1}"),
+    ("ai_unavailable", "AI analysis unavailable"),
+    ("partial_results", "Partial results:"),
+    ("expired_acceptances", "Accepted risks past their expiry, reported again:"),
+    ("expired_acceptance", "{0}, accepted until {1}"),
+    ("owner", "owner"),
+    ("surfaces", "Error/event surfaces"),
+    ("poc", "Proof of Concept"),
+    ("poc_confirmed", "Verified: the PoC exploited it"),
+    ("poc_compile_failed", "Not verified: the PoC failed to compile"),
+    ("poc_not_exploited", "Not verified: the PoC did not exploit it"),
+    ("architecture", "Architecture Overview"),
+    ("executive_summary", "Executive Summary"),
+    ("risk_level", "Overall Risk Level"),
+    ("security_score", "Security Score"),
+    ("normalized_score", "Normalized Security Score"),
+    ("total_vulnerabilities", "Total Vulnerabilities"),
+    ("severity_distribution", "Severity Distribution"),
+    ("critical", "Critical"),
+    ("high", "High"),
+    ("medium", "Medium"),
+    ("low", "Low"),
+    ("informational", "Informational"),
+    ("key_findings", "Key Findings"),
+    ("baseline_changes", "Changes Since Baseline"),
+    ("baseline_counts", "{0} new, {1} fixed and {2} persistent findings compared with `{3}`."),
+    ("new_findings", "New Findings"),
+    ("severity", "Severity"),
+    ("finding", "Finding"),
+    ("fixed_findings", "Fixed Findings"),
+    ("vulnerability_analysis", "Vulnerability Analysis"),
+    ("severity_vulnerabilities", "{0} Vulnerabilities"),
+    ("adjusted_severity", "Adjusted Severity"),
+    ("originally", "{0} (originally {1})"),
+    ("adjusted_by", "{0}, {1}: {2}"),
+    ("description", "Description"),
+    ("file", "File"),
+    ("line", "Line"),
+    ("tool", "Tool"),
+    ("first_seen", "First Seen"),
+    ("confidence", "Confidence"),
+    ("cvss", "CVSS"),
+    ("fingerprint", "Fingerprint"),
+    ("code_snippet", "Code Snippet"),
+    ("recommendation", "Recommendation"),
+    ("references", "References"),
+    ("test_blind_spots", "Test Blind Spots"),
+    ("gas_optimization", "Gas Optimization"),
+    ("gas_score", "Gas Optimization Score"),
+    ("gas_clean", "No gas optimizations were found."),
+    ("recommendations", "Recommendations"),
+    ("priority", "Priority"),
+    ("effort", "Effort"),
+    ("impact", "Impact"),
+    ("related_findings", "Related Findings"),
+    ("technical_details", "Technical Details"),
+    ("analysis_duration", "Analysis Duration"),
+    ("seconds", "{0} seconds"),
+    ("lines_of_code", "Lines of Code"),
+    ("functions_analyzed", "Functions Analyzed"),
+    ("complexity_score", "Complexity Score"),
+    (
+        "score_formula",
+        "100 − (25 × Critical + 15 × High + 8 × Medium + 3 × Low + 1 × other findings) ÷ max(1, lines of code ÷ {0}). The security score subtracts the same penalties undivided, so it only compares projects of similar size.",
+    ),
+    ("appendices", "Appendices"),
+    ("scope", "Scope"),
+    ("contracts", "Contracts"),
+    ("contracts_summary", "{0} ({1} deployable, {2} lines of code)"),
+    ("compiler_versions", "Compiler Versions"),
+    ("licenses", "Licenses"),
+    ("contract", "Contract"),
+    ("kind", "Kind"),
+    ("license", "License"),
+    ("loc", "LOC"),
+    ("address", "Address"),
+    ("dependencies", "Dependencies"),
+    ("package", "Package"),
+    ("source", "Source"),
+];
+
+const KOREAN: &[(&str, &str)] = &[
+    ("report_title", "보안 감사 보고서"),
+    ("report_id", "보고서 ID"),
+    ("generated", "생성 일시"),
+    ("version", "버전"),
+    ("tools_used", "사용 도구"),
+    ("tool_versions", "도구 버전"),
+    ("completeness", "분석 완료도"),
+    ("entrypoints", "다음 진입점에서 도달하는 코드만 분석"),
+    ("incomplete", "분석이 완료되지 않았습니다."),
+    ("files_compiled", "파일 {1}개 중 {0}개가 컴파일되었습니다."),
+    ("incomplete_note", "아래에 발견 사항이 없다고 해서 코드에 취약점이 없다는 뜻은 아닙니다. 완료되지 않은 항목:"),
+    ("tool_on_contract", "{1}의 {0}"),
+    ("ai_unavailable", "AI 분석 불가"),
+    ("partial_results", "부분 결과:"),
+    ("expired_acceptances", "수용 기한이 지나 다시 보고되는 위험:"),
+    ("expired_acceptance", "{0}, {1}까지 수용됨"),
+    ("owner", "담당자"),
+    ("surfaces", "영향받는 오류/이벤트"),
+    ("poc", "개념 증명(PoC)"),
+    ("poc_confirmed", "검증됨: PoC로 악용이 확인됨"),
+    ("poc_compile_failed", "검증되지 않음: PoC 컴파일 실패"),
+    ("poc_not_exploited", "검증되지 않음: PoC로 악용되지 않음"),
+    ("architecture", "아키텍처 개요"),
+    ("executive_summary", "요약"),
+    ("risk_level", "전체 위험 수준"),
+    ("security_score", "보안 점수"),
+    ("normalized_score", "정규화 보안 점수"),
+    ("total_vulnerabilities", "전체 취약점 수"),
+    ("severity_distribution", "심각도 분포"),
+    ("critical", "치명적"),
+    ("high", "높음"),
+    ("medium", "중간"),
+    ("low", "낮음"),
+    ("informational", "정보"),
+    ("key_findings", "주요 발견 사항"),
+    ("baseline_changes", "기준선 대비 변경 사항"),
+    ("baseline_counts", "`{3}` 대비 신규 {0}건, 해결 {1}건, 유지 {2}건입니다."),
+    ("new_findings", "신규 발견 사항"),
+    ("severity", "심각도"),
+    ("finding", "발견 사항"),
+    ("fixed_findings", "해결된 발견 사항"),
+    ("vulnerability_analysis", "취약점 분석"),
+    ("severity_vulnerabilities", "심각도 {0} 취약점"),
+    ("adjusted_severity", "조정된 심각도"),
+    ("originally", "{0} (원래 {1})"),
+    ("adjusted_by", "{0}, {1}: {2}"),
+    ("description", "설명"),
+    ("file", "파일"),
+    ("line", "라인"),
+    ("tool", "도구"),
+    ("first_seen", "최초 발견"),
+    ("confidence", "신뢰도"),
+    ("cvss", "CVSS"),
+    ("fingerprint", "지문"),
+    ("code_snippet", "코드"),
+    ("recommendation", "권고 사항"),
+    ("references", "참고 자료"),
+    ("test_blind_spots", "테스트 사각지대"),
+    ("gas_optimization", "가스 최적화"),
+    ("gas_score", "가스 최적화 점수"),
+    ("gas_clean", "가스 최적화 대상이 발견되지 않았습니다."),
+    ("recommendations", "개선 권고"),
+    ("priority", "우선순위"),
+    ("effort", "작업량"),
+    ("impact", "영향"),
+    ("related_findings", "관련 발견 사항"),
+    ("technical_details", "기술 세부 정보"),
+    ("analysis_duration", "분석 소요 시간"),
+    ("seconds", "{0}초"),
+    ("lines_of_code", "코드 라인 수"),
+    ("functions_analyzed", "분석한 함수 수"),
+    ("complexity_score", "복잡도 점수"),
+    (
+        "score_formula",
+        "100 − (25 × 치명적 + 15 × 높음 + 8 × 중간 + 3 × 낮음 + 1 × 기타 발견 수) ÷ max(1, 코드 라인 수 ÷ {0}). 보안 점수는 같은 감점을 나누지 않고 적용하므로 규모가 비슷한 프로젝트끼리만 비교할 수 있습니다.",
+    ),
+    ("appendices", "부록"),
+    ("scope", "감사 범위"),
+    ("contracts", "컨트랙트"),
+    ("contracts_summary", "{0}개 (배포 가능 {1}개, 코드 {2}줄)"),
+    ("compiler_versions", "컴파일러 버전"),
+    ("licenses", "라이선스"),
+    ("contract", "컨트랙트"),
+    ("kind", "종류"),
+    ("license", "라이선스"),
+    ("loc", "라인 수"),
+    ("address", "주소"),
+    ("dependencies", "의존성"),
+    ("package", "패키지"),
+    ("source", "출처"),
+];
+
+/// Messages of one report language
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    /// Language code, for the `lang` attribute of HTML reports
+    pub code: String,
+    /// CSS font family for HTML and PDF reports
+    pub font: Option<String>,
+    messages: HashMap<String, String>,
+    /// Messages with placeholders, split at them once instead of on every use
+    templates: HashMap<String, Template>,
+}
+
+/// A message split at its `{0}`, `{1}`, ... placeholders
+#[derive(Debug, Clone)]
+struct Template(Vec<Part>);
+
+#[derive(Debug, Clone)]
+enum Part {
+    Text(String),
+    Arg(usize),
+}
+
+impl Template {
+    fn compile(message: &str, placeholder: &regex::Regex) -> Self {
+        let mut parts = Vec::new();
+        let mut rest = 0;
+        for captures in placeholder.captures_iter(message) {
+            let whole = captures.get(0).expect("match");
+            if whole.start() > rest {
+                parts.push(Part::Text(message[rest..whole.start()].to_string()));
+            }
+            parts.push(Part::Arg(captures[1].parse().unwrap_or(usize::MAX)));
+            rest = whole.end();
+        }
+        if rest < message.len() {
+            parts.push(Part::Text(message[rest..].to_string()));
+        }
+        Self(parts)
+    }
+
+    fn fill(&self, args: &[&dyn std::fmt::Display]) -> String {
+        let mut text = String::new();
+        for part in &self.0 {
+            match part {
+                Part::Text(literal) => text.push_str(literal),
+                Part::Arg(index) => match args.get(*index) {
+                    Some(arg) => text.push_str(&arg.to_string()),
+                    // Placeholders without an argument stay as written
+                    None => text.push_str(&format!("{{{}}}", index)),
+                },
+            }
+        }
+        text
+    }
+}
+
+/// Layout of a catalog file
+#[derive(Debug, Deserialize)]
+struct CatalogFile {
+    code: String,
+    font: Option<String>,
+    #[serde(default)]
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// A built-in language code, otherwise the path of a TOML catalog
+    pub fn load(spec: &str) -> Result<Self> {
+        match spec.to_ascii_lowercase().as_str() {
+            "en" => return Ok(Self::english()),
+            "ko" => return Ok(Self::built_in("ko", Some("'Noto Sans Mono CJK KR', 'D2Coding', 'Nanum Gothic Coding', monospace"), KOREAN)),
+            _ => {}
+        }
+
+        let path = Path::new(spec);
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Could not read report language {}: {} (built in: {})", path.display(), e, BUILT_IN.join(", ")))?;
+        let file: CatalogFile =
+            toml::from_str(&content).map_err(|e| anyhow!("Invalid report language catalog {}: {}", path.display(), e))?;
+        Ok(Self::new(file.code, file.font, file.messages))
+    }
+
+    pub fn english() -> Self {
+        Self::built_in("en", None, ENGLISH)
+    }
+
+    fn built_in(code: &str, font: Option<&str>, messages: &[(&str, &str)]) -> Self {
+        let messages = messages.iter().map(|(key, text)| (key.to_string(), text.to_string())).collect();
+        Self::new(code.to_string(), font.map(str::to_string), messages)
+    }
+
+    fn new(code: String, font: Option<String>, messages: HashMap<String, String>) -> Self {
+        let mut catalog = Self { code, font, messages, templates: HashMap::new() };
+        let placeholder = regex::Regex::new(r"\{(\d+)\}").expect("valid placeholder pattern");
+        let keys = catalog.messages.keys().map(String::as_str).chain(ENGLISH.iter().map(|(key, _)| *key));
+        let templates = keys
+            .filter_map(|key| {
+                let message = catalog.get(key);
+                message.contains('{').then(|| (key.to_string(), Template::compile(message, &placeholder)))
+            })
+            .collect();
+        catalog.templates = templates;
+        catalog
+    }
+
+    /// Message for `key`, in English when the catalog lacks it
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.messages
+            .get(key)
+            .map(String::as_str)
+            .or_else(|| ENGLISH.iter().find(|(english_key, _)| *english_key == key).map(|(_, text)| *text))
+            .unwrap_or(key)
+    }
+
+    /// Message for `key` with `{0}`, `{1}`, ... replaced by `args`
+    pub fn fill(&self, key: &str, args: &[&dyn std::fmt::Display]) -> String {
+        match self.templates.get(key) {
+            Some(template) => template.fill(args),
+            None => self.get(key).to_string(),
+        }
+    }
+
+    /// Name of a severity as findings spell it
+    pub fn severity(&self, severity: &str) -> String {
+        match severity {
+            "Critical" | "High" | "Medium" | "Low" | "Informational" => self.get(&severity.to_ascii_lowercase()).to_string(),
+            "Info" => self.get("informational").to_string(),
+            other => other.to_string(),
+        }
+    }
+}
+
+/// Catalogs of the configured report languages, with their configured fonts
+pub fn catalogs(config: &Config) -> Result<Vec<Catalog>> {
+    let specs = if config.reporting.languages.is_empty() { vec!["en".to_string()] } else { config.reporting.languages.clone() };
+    specs
+        .iter()
+        .map(|spec| {
+            let mut catalog = Catalog::load(spec)?;
+            if let Some(font) = config.reporting.fonts.get(&catalog.code) {
+                catalog.font = Some(font.clone());
+            }
+            Ok(catalog)
+        })
+        .collect()
+}
@@ -3,5 +3,14 @@
 //! This module handles the generation of various report formats
 //! including vulnerability reports, analysis summaries, and audit documentation.
 
+pub mod checklist;
+pub mod cvss;
+pub mod explorer;
 pub mod generator;
+pub mod hooks;
+pub mod i18n;
+pub mod naming;
+pub mod poc;
+pub mod review;
+pub mod sarif;
 pub mod vulnerability;
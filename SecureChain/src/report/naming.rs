@@ -0,0 +1,191 @@
+//! Deliverable file names and finding numbers
+//!
+//! Firms name deliverables after a fixed convention, such as
+//! `ACME-SC-2024-001_TechnicalReport_v1.2.pdf`, and refer to findings by
+//! numbers like `SC-001` in every document they hand over. A file name
+//! pattern fills `{client}`, `{project}`, `{date}`, `{year}`, `{version}` and
+//! `{type}` in from the engagement settings, and a finding id scheme numbers
+//! findings with `{n}`, zero-padded as `{n:3}`, counting within each severity
+//! when it also has `{severity}`. Numbers follow the order reports list
+//! findings in, most severe first and highest score first within a
+//! severity, so Markdown, HTML, JSON, SARIF and bounty reports of the same
+//! results agree.
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use std::collections::HashMap;
+
+use crate::core::analyzer::AnalysisResults;
+use crate::report::vulnerability::Vulnerability;
+use crate::utils::config::ReportingConfig;
+
+/// Variables a file name pattern can use
+pub const FILE_VARIABLES: &[&str] = &["client", "project", "date", "year", "version", "type"];
+
+/// Severity sections of a report, in order, with the letter `{severity}` numbers them by
+const SECTIONS: &[(&str, char)] = &[("Critical", 'C'), ("High", 'H'), ("Medium", 'M'), ("Low", 'L')];
+
+/// Letter of the informational section, which takes every other severity
+const OTHER_SECTION: char = 'I';
+
+/// Report types as `{type}` names them, with the built-in file name used without a pattern
+pub enum Deliverable {
+    TechnicalReport,
+    Checklist,
+    ExecutiveSummary,
+    Report,
+}
+
+impl Deliverable {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Deliverable::TechnicalReport => "TechnicalReport",
+            Deliverable::Checklist => "Checklist",
+            Deliverable::ExecutiveSummary => "ExecutiveSummary",
+            Deliverable::Report => "Report",
+        }
+    }
+
+    fn default_stem(&self) -> &'static str {
+        match self {
+            Deliverable::TechnicalReport => "security_audit_report",
+            Deliverable::Checklist => "audit_checklist",
+            Deliverable::ExecutiveSummary => "executive_summary",
+            Deliverable::Report => "report",
+        }
+    }
+}
+
+/// File name of a deliverable with `extension`, after `reporting.filename_pattern`
+/// when one is set; `project` stands in for an unset `reporting.project`
+pub fn file_name(config: &ReportingConfig, project: &str, deliverable: Deliverable, extension: &str) -> Result<String> {
+    if config.filename_pattern.is_empty() {
+        return Ok(format!("{}.{}", deliverable.default_stem(), extension));
+    }
+
+    let project = if config.project.is_empty() { project } else { config.project.as_str() };
+    let now = Utc::now();
+    let values: HashMap<&str, String> = [
+        ("client", config.client.clone()),
+        ("project", project.to_string()),
+        ("date", now.format("%Y-%m-%d").to_string()),
+        ("year", now.format("%Y").to_string()),
+        ("version", config.report_version.clone()),
+        ("type", deliverable.type_name().to_string()),
+    ]
+    .into_iter()
+    .collect();
+
+    let stem = fill(&config.filename_pattern, |name| {
+        values.get(name).map(|value| sanitize(value)).ok_or_else(|| {
+            anyhow!("Unknown variable {{{}}} in reporting.filename_pattern ({})", name, FILE_VARIABLES.join(", "))
+        })
+    })?;
+    Ok(format!("{}.{}", stem, extension))
+}
+
+/// Title of the project `results` audited, for `{project}`
+pub fn project_title(results: &AnalysisResults) -> &str {
+    results.metadata.as_ref().map(|metadata| metadata.title.as_str()).unwrap_or(&results.contract_name)
+}
+
+/// Check a file name pattern uses only known variables and names a file, not a path
+pub fn check_file_pattern(pattern: &str) -> Result<()> {
+    if pattern.contains(['/', '\\']) {
+        return Err(anyhow!("reporting.filename_pattern names a file, not a path: {}", pattern));
+    }
+    fill(pattern, |name| match FILE_VARIABLES.contains(&name) {
+        true => Ok(String::new()),
+        false => Err(anyhow!("Unknown variable {{{}}} in reporting.filename_pattern ({})", name, FILE_VARIABLES.join(", "))),
+    })
+    .map(|_| ())
+}
+
+/// Check a finding id scheme numbers findings and uses only known variables
+pub fn check_finding_scheme(scheme: &str) -> Result<()> {
+    let mut numbered = false;
+    fill(scheme, |name| {
+        if name == "severity" {
+            return Ok(String::new());
+        }
+        number_width(name)?;
+        numbered = true;
+        Ok(String::new())
+    })?;
+    match numbered {
+        true => Ok(()),
+        false => Err(anyhow!("reporting.finding_ids needs {{n}} to number findings: {}", scheme)),
+    }
+}
+
+/// Id of each finding under `scheme`, by vulnerability id
+pub fn finding_numbers(vulnerabilities: &[Vulnerability], scheme: &str) -> HashMap<String, String> {
+    let per_severity = scheme.contains("{severity}");
+    let mut numbers = HashMap::with_capacity(vulnerabilities.len());
+    let mut counter = 0;
+    for letter in SECTIONS.iter().map(|(_, letter)| *letter).chain([OTHER_SECTION]) {
+        if per_severity {
+            counter = 0;
+        }
+        for vuln in section(vulnerabilities, letter) {
+            counter += 1;
+            // The scheme was checked when the configuration loaded
+            let id = fill(scheme, |name| match name {
+                "severity" => Ok(letter.to_string()),
+                _ => Ok(format!("{:0width$}", counter, width = number_width(name)?)),
+            })
+            .unwrap_or_else(|_| counter.to_string());
+            numbers.insert(vuln.id.clone(), id);
+        }
+    }
+    numbers
+}
+
+/// Findings of the section `letter` stands for, highest score first
+fn section(vulnerabilities: &[Vulnerability], letter: char) -> Vec<&Vulnerability> {
+    let mut findings: Vec<&Vulnerability> = vulnerabilities
+        .iter()
+        .filter(|vuln| match SECTIONS.iter().find(|(severity, _)| *severity == vuln.severity) {
+            Some((_, section)) => *section == letter,
+            None => letter == OTHER_SECTION,
+        })
+        .collect();
+    findings.sort_by(|a, b| b.score().partial_cmp(&a.score()).unwrap_or(std::cmp::Ordering::Equal));
+    findings
+}
+
+/// Zero padding of a number variable: `n` or `n:width`
+fn number_width(name: &str) -> Result<usize> {
+    match name.split_once(':') {
+        None if name == "n" => Ok(0),
+        Some(("n", width)) => width.parse().map_err(|_| anyhow!("Invalid number width in {{{}}}", name)),
+        _ => Err(anyhow!("Unknown variable {{{}}} in reporting.finding_ids (severity, n, n:width)", name)),
+    }
+}
+
+/// `template` with each `{name}` replaced by `value(name)`
+fn fill(template: &str, mut value: impl FnMut(&str) -> Result<String>) -> Result<String> {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| anyhow!("Unclosed {{ in {}", template))?;
+        filled.push_str(&value(&rest[start + 1..start + end])?);
+        rest = &rest[start + end + 1..];
+    }
+    filled.push_str(rest);
+    Ok(filled)
+}
+
+/// A value as it can appear in a file name
+fn sanitize(value: &str) -> String {
+    value
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_whitespace() => '_',
+            c => c,
+        })
+        .collect()
+}
@@ -0,0 +1,721 @@
+//! Proof-of-concept generation for discovered vulnerabilities
+//!
+//! PoCs are emitted as test templates in the language of the audited
+//! contract: Foundry-style Solidity for EVM, snforge tests for Cairo,
+//! Move unit tests and ink! end-to-end tests. The platform is taken from the
+//! plugin that produced the finding, falling back to the file extension.
+//!
+//! When the audited Solidity source can be read, the PoC is a Foundry test
+//! against the real contract: its source is inlined (or imported, when it has
+//! imports of its own), the contract is deployed with its constructor's
+//! parameters, and the function at the finding's line is called with its
+//! actual signature and attacker-controlled arguments. An internal function
+//! is reached through a public function calling it, and a reentrancy gets an
+//! attacker contract re-entering from `receive`. Otherwise a generic template
+//! is emitted.
+//!
+//! Which findings get one is up to the engagement: `reporting.poc_severities`
+//! and `reporting.poc_categories` select them, a flag set during triage
+//! includes or excludes a single finding, and clients that forbid exploit
+//! code in deliverables get reproduction steps in place of every PoC.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::core::parser::{self, ContractParser, ContractUnit, UnitFunction, UserTypes};
+use crate::report::vulnerability::{Vulnerability, VulnerabilityCategory};
+use crate::utils::config::Config;
+
+/// Platform a PoC template is generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PocPlatform {
+    Solidity,
+    Cairo,
+    Move,
+    Ink,
+}
+
+impl fmt::Display for PocPlatform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PocPlatform::Solidity => write!(f, "Solidity"),
+            PocPlatform::Cairo => write!(f, "Cairo"),
+            PocPlatform::Move => write!(f, "Move"),
+            PocPlatform::Ink => write!(f, "Ink!"),
+        }
+    }
+}
+
+impl PocPlatform {
+    /// Select the platform for a finding
+    pub fn for_vulnerability(vulnerability: &Vulnerability) -> Self {
+        match vulnerability.tool.as_str() {
+            "Cairo Plugin" => return PocPlatform::Cairo,
+            "Move Plugin" => return PocPlatform::Move,
+            "Ink Plugin" => return PocPlatform::Ink,
+            "EVM Plugin" | "Slither" | "Mythril" | "Echidna" => return PocPlatform::Solidity,
+            _ => {}
+        }
+
+        match Path::new(&vulnerability.file_path).extension().and_then(|ext| ext.to_str()) {
+            Some("cairo") => PocPlatform::Cairo,
+            Some("move") => PocPlatform::Move,
+            Some("rs") => PocPlatform::Ink,
+            _ => PocPlatform::Solidity,
+        }
+    }
+
+    /// Command running a generated PoC written to `path`
+    pub fn run_command(&self, path: &str) -> String {
+        match self {
+            PocPlatform::Solidity => format!("forge test --match-path {} -vvvv", path),
+            PocPlatform::Cairo => "snforge test test_exploit".to_string(),
+            PocPlatform::Move => "aptos move test --filter exploit".to_string(),
+            PocPlatform::Ink => "cargo test --features e2e-tests".to_string(),
+        }
+    }
+
+    /// File extension of the generated PoC
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            PocPlatform::Solidity => "sol",
+            PocPlatform::Cairo => "cairo",
+            PocPlatform::Move => "move",
+            PocPlatform::Ink => "rs",
+        }
+    }
+}
+
+/// A generated PoC template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedPoc {
+    pub platform: PocPlatform,
+    pub code: String,
+}
+
+impl GeneratedPoc {
+    /// File name for the `index`-th PoC of a run
+    pub fn file_name(&self, index: usize) -> String {
+        format!("exploit_{}.{}", index, self.platform.file_extension())
+    }
+}
+
+/// What is delivered for a finding: PoC code, or how to reproduce it without any
+#[derive(Debug, Clone)]
+pub enum PocOutput {
+    Code(GeneratedPoc),
+    Steps(String),
+}
+
+impl PocOutput {
+    /// File name for the `index`-th PoC of a run
+    pub fn file_name(&self, index: usize) -> String {
+        match self {
+            PocOutput::Code(poc) => poc.file_name(index),
+            PocOutput::Steps(_) => format!("reproduction_{}.md", index),
+        }
+    }
+
+    pub fn content(&self) -> &str {
+        match self {
+            PocOutput::Code(poc) => &poc.code,
+            PocOutput::Steps(steps) => steps,
+        }
+    }
+}
+
+/// Which findings get a PoC, and whether it may contain exploit code
+#[derive(Debug, Clone)]
+pub struct PocPolicy {
+    pub severities: Vec<String>,
+    /// Empty for every category
+    pub categories: Vec<String>,
+    pub no_exploit_code: bool,
+}
+
+impl PocPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            severities: config.reporting.poc_severities.clone(),
+            categories: config.reporting.poc_categories.clone(),
+            no_exploit_code: config.reporting.no_exploit_code,
+        }
+    }
+
+    /// Whether `vulnerability` gets a PoC; its triage flag in `flags`, by id, overrides severity and category
+    pub fn includes(&self, vulnerability: &Vulnerability, flags: &HashMap<String, bool>) -> bool {
+        if let Some(&included) = flags.get(&vulnerability.id) {
+            return included;
+        }
+        let severity = self.severities.iter().any(|severity| severity.eq_ignore_ascii_case(&vulnerability.severity));
+        let category = self.categories.is_empty()
+            || self.categories.iter().any(|category| category_matches(category, &vulnerability.category));
+        severity && category
+    }
+
+    /// PoC of a finding, or its reproduction steps when exploit code is not allowed
+    pub fn generate(&self, vulnerability: &Vulnerability) -> PocOutput {
+        if self.no_exploit_code {
+            PocOutput::Steps(reproduction_steps(vulnerability))
+        } else {
+            PocOutput::Code(generate_poc(vulnerability))
+        }
+    }
+}
+
+/// Whether a configured category names `category`, as `AccessControl`, `access control` or `access-control`
+fn category_matches(name: &str, category: &VulnerabilityCategory) -> bool {
+    let normalize = |text: &str| text.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase();
+    normalize(name) == normalize(&format!("{:?}", category))
+}
+
+/// How to reproduce a finding in words, for deliverables that may not contain exploit code
+pub fn reproduction_steps(vulnerability: &Vulnerability) -> String {
+    let location = match vulnerability.line_number {
+        Some(line) => format!("{}:{}", vulnerability.file_path, line),
+        None => vulnerability.file_path.clone(),
+    };
+    format!(
+        "# Reproduction: {}\n\n**Severity:** {}\n**Location:** `{}`\n\n{}\n\n## Steps\n\n{}",
+        vulnerability.title,
+        vulnerability.severity,
+        location,
+        one_line(&vulnerability.description),
+        steps(vulnerability)
+    )
+}
+
+/// Numbered steps reaching a finding, with the code they reach
+pub fn steps(vulnerability: &Vulnerability) -> String {
+    let mut steps = format!("1. Deploy the contract in `{}` on a local fork or test network.\n", vulnerability.file_path);
+    steps.push_str(&format!("2. {}\n", trigger(&vulnerability.category)));
+    steps.push_str(&format!("3. Observe the effect: {}.\n", vulnerability.category.description().to_lowercase()));
+    if let Some(code) = &vulnerability.code_snippet {
+        steps.push_str(&format!("\nAffected code:\n\n```\n{}\n```\n", code.trim_end()));
+    }
+    steps
+}
+
+/// The action that reaches the flawed code, described rather than written as an exploit
+fn trigger(category: &VulnerabilityCategory) -> &'static str {
+    match category {
+        VulnerabilityCategory::Reentrancy => "Call the affected function from a contract whose fallback calls back into it before the first call updates state.",
+        VulnerabilityCategory::AccessControl => "Call the affected function from an account that holds none of the contract's roles.",
+        VulnerabilityCategory::IntegerOverflow => "Call the affected function with amounts at the bounds of their type.",
+        VulnerabilityCategory::UnhandledExceptions | VulnerabilityCategory::LowLevelCalls => {
+            "Make the external call fail, for example with a callee that reverts, and call the affected function."
+        }
+        VulnerabilityCategory::TimestampDependence => "Call the affected function in blocks whose timestamps the block producer chose.",
+        VulnerabilityCategory::DenialOfService => "Grow the state the affected function iterates over, or make a callee revert, then call it.",
+        VulnerabilityCategory::InputValidation => "Call the affected function with zero, empty or out-of-range arguments.",
+        VulnerabilityCategory::RaceCondition => "Send two transactions to the affected function and order them against the first sender.",
+        _ => "Call the affected function with inputs that reach the flagged code.",
+    }
+}
+
+/// Generate a PoC template for a vulnerability
+pub fn generate_poc(vulnerability: &Vulnerability) -> GeneratedPoc {
+    let platform = PocPlatform::for_vulnerability(vulnerability);
+    let code = match platform {
+        PocPlatform::Solidity => solidity_poc(vulnerability),
+        PocPlatform::Cairo => cairo_poc(vulnerability),
+        PocPlatform::Move => move_poc(vulnerability),
+        PocPlatform::Ink => ink_poc(vulnerability),
+    };
+
+    GeneratedPoc { platform, code }
+}
+
+/// Collapse a description onto one line so it fits in a comment
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Contract/module name derived from the finding's file
+fn target_name(vulnerability: &Vulnerability) -> String {
+    let stem = Path::new(&vulnerability.file_path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("target");
+
+    let name: String = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("target_{}", name)
+    } else {
+        name
+    }
+}
+
+fn solidity_poc(vulnerability: &Vulnerability) -> String {
+    match SolidityTarget::resolve(vulnerability) {
+        Some(target) => foundry_test(vulnerability, &target),
+        None => solidity_template(vulnerability),
+    }
+}
+
+/// The audited contract and function of a Solidity finding, resolved from its source
+struct SolidityTarget {
+    source: String,
+    /// Comments blanked, offsets as in `source`
+    code: String,
+    units: Vec<ContractUnit>,
+    types: UserTypes,
+    contract: ContractUnit,
+    /// Function the attacker calls
+    entry: UnitFunction,
+    /// Function at the finding's line, when it is internal and reached through `entry`
+    flagged: Option<String>,
+}
+
+impl SolidityTarget {
+    /// Contract declared at or before the finding's line, and its function there
+    fn resolve(vulnerability: &Vulnerability) -> Option<Self> {
+        if parser::is_vyper_file(&vulnerability.file_path) {
+            return None;
+        }
+        let source = std::fs::read_to_string(&vulnerability.file_path).ok()?;
+        let parser = ContractParser::new().ok()?;
+        let units = parser.extract_units(&source).ok()?;
+        let types = parser.extract_user_types(&source).ok()?;
+        let line = vulnerability.line_number;
+
+        let contract = units
+            .iter()
+            .filter(|unit| unit.kind == "contract")
+            .filter(|unit| line.is_none_or(|line| unit.line_number <= line))
+            .max_by_key(|unit| unit.line_number)?
+            .clone();
+        let at_line = line.and_then(|line| {
+            contract.functions.iter().filter(|function| function.line_number <= line).max_by_key(|function| function.line_number)
+        });
+
+        let (entry, flagged) = match at_line {
+            Some(function) if callable(function) => (function.clone(), None),
+            // An internal function is reached through a public one calling it
+            Some(function) => {
+                let call = format!("{}(", function.name);
+                let caller = contract
+                    .functions
+                    .iter()
+                    .filter(|caller| callable(caller) && changes_state(caller))
+                    .find(|caller| caller.body.as_ref().is_some_and(|body| body.contains(&call)))?;
+                (caller.clone(), Some(function.name.clone()))
+            }
+            None => (contract.functions.iter().find(|function| callable(function) && changes_state(function))?.clone(), None),
+        };
+
+        let code = parser::strip_comments(&source);
+        Some(Self { source, code, units, types, contract, entry, flagged })
+    }
+
+    /// `pragma solidity` of the source, for the PoC to compile with the same compiler
+    fn pragma(&self) -> String {
+        self.source
+            .lines()
+            .map(str::trim)
+            .find(|line| line.starts_with("pragma solidity"))
+            .unwrap_or("pragma solidity ^0.8.0;")
+            .to_string()
+    }
+
+    /// `ty` as named outside the contracts, qualified by the unit declaring it
+    fn qualified(&self, ty: &str) -> String {
+        let declaration = match Regex::new(&format!(r"\b(?:struct|enum)\s+{}\b", regex::escape(ty))) {
+            Ok(pattern) => pattern.find(&self.code),
+            Err(_) => None,
+        };
+        let position = match declaration {
+            Some(declaration) if !ty.contains('.') => declaration.start(),
+            _ => return ty.to_string(),
+        };
+        let before = &self.code[..position];
+        if before.matches('{').count() == before.matches('}').count() {
+            return ty.to_string();
+        }
+        let line = before.matches('\n').count() + 1;
+        match self.units.iter().filter(|unit| unit.line_number <= line).max_by_key(|unit| unit.line_number) {
+            Some(unit) => format!("{}.{}", unit.name, ty),
+            None => ty.to_string(),
+        }
+    }
+
+    /// Attacker-controlled value for a parameter of type `ty`, with the local it needs declared in `locals`
+    ///
+    /// Addresses are `attacker`, amounts one ether, or the type's maximum
+    /// for an overflow.
+    fn argument(&self, ty: &str, name: &str, attacker: &str, overflow: bool, locals: &mut Vec<String>) -> String {
+        let (base, dimensions) = match ty.find('[') {
+            Some(bracket) => ty.split_at(bracket),
+            None => (ty, ""),
+        };
+        let base = match elementary(base) {
+            true => base.to_string(),
+            false => self.qualified(base),
+        };
+        let ty = format!("{}{}", base, dimensions);
+
+        if dimensions.ends_with("[]") {
+            return format!("new {}(2)", ty);
+        }
+        if !dimensions.is_empty() || self.types.structs.contains_key(base.rsplit('.').next().unwrap_or(&base)) {
+            locals.push(format!("{} memory {};", ty, name));
+            return name.to_string();
+        }
+        if self.types.enums.iter().any(|known| known == base.rsplit('.').next().unwrap_or(&base)) {
+            return format!("{}(0)", ty);
+        }
+        match ty.as_str() {
+            "address" => format!("payable({})", attacker),
+            "bool" => "true".to_string(),
+            "string" => "\"exploit\"".to_string(),
+            "bytes" => "\"\"".to_string(),
+            bytes if bytes.starts_with("bytes") => format!("{}(0)", bytes),
+            integer if integer.starts_with("uint") || integer.starts_with("int") => {
+                let bits: usize = integer.trim_start_matches('u').trim_start_matches("int").parse().unwrap_or(256);
+                match (overflow, bits >= 64) {
+                    (true, _) => format!("type({}).max", integer),
+                    (false, true) => "1 ether".to_string(),
+                    (false, false) => "1".to_string(),
+                }
+            }
+            // A contract or interface: the attacker stands in for it
+            contract => format!("{}(payable({}))", contract, attacker),
+        }
+    }
+
+    /// Call of `function` on `target` with attacker-controlled arguments, after the locals it needs
+    fn call(&self, target: &str, function: &UnitFunction, attacker: &str, value: &str, overflow: bool) -> Vec<String> {
+        let mut lines = Vec::new();
+        let arguments: Vec<String> = function
+            .parameter_types
+            .iter()
+            .enumerate()
+            .map(|(index, ty)| {
+                let name = match function.parameter_names.get(index) {
+                    Some(name) if !name.is_empty() => format!("{}_", name),
+                    _ => format!("arg{}", index),
+                };
+                self.argument(ty, &name, attacker, overflow, &mut lines)
+            })
+            .collect();
+        let value = match function.state_mutability == "payable" {
+            true => format!("{{value: {}}}", value),
+            false => String::new(),
+        };
+        lines.push(format!("{}.{}{}({});", target, function.name, value, arguments.join(", ")));
+        lines
+    }
+
+    /// A payable function other than `entry` through which the attacker can put funds in first
+    fn deposit(&self) -> Option<&UnitFunction> {
+        self.contract
+            .functions
+            .iter()
+            .filter(|function| function.name != self.entry.name && callable(function))
+            .find(|function| function.state_mutability == "payable")
+    }
+}
+
+/// Whether an account outside the contract can call `function`
+fn callable(function: &UnitFunction) -> bool {
+    matches!(function.visibility.as_str(), "public" | "external")
+        && !matches!(function.name.as_str(), "fallback" | "receive")
+        && function.body.is_some()
+}
+
+fn changes_state(function: &UnitFunction) -> bool {
+    !matches!(function.state_mutability.as_str(), "view" | "pure" | "constant")
+}
+
+/// Whether `ty` is a Solidity elementary type rather than a user-defined one
+fn elementary(ty: &str) -> bool {
+    matches!(ty, "address" | "bool" | "string" | "bytes")
+        || ["uint", "int", "bytes"].iter().any(|prefix| ty.strip_prefix(prefix).is_some_and(|bits| bits.chars().all(|c| c.is_ascii_digit())))
+}
+
+/// Foundry test calling the vulnerable function of the audited contract
+fn foundry_test(vulnerability: &Vulnerability, target: &SolidityTarget) -> String {
+    let name = &target.contract.name;
+    let entry = &target.entry;
+    let overflow = vulnerability.category == VulnerabilityCategory::IntegerOverflow;
+
+    // The audited source travels with the PoC; one with imports of its own is imported from the project
+    let audited = match target.source.lines().any(|line| line.trim_start().starts_with("import ")) {
+        true => format!("import \"{}\";", vulnerability.file_path),
+        false => {
+            let body: Vec<&str> = target
+                .source
+                .lines()
+                .filter(|line| {
+                    let line = line.trim_start();
+                    !line.starts_with("// SPDX-License-Identifier") && !line.starts_with("pragma solidity")
+                })
+                .collect();
+            format!("// Audited source: {}\n{}", vulnerability.file_path, body.join("\n").trim())
+        }
+    };
+
+    let location = match vulnerability.line_number {
+        Some(line) => format!("{}:{}", vulnerability.file_path, line),
+        None => vulnerability.file_path.clone(),
+    };
+    let mut header = format!(
+        "/**\n * PoC Exploit for: {}\n * Severity: {}\n * Location: {}, {}.{}({})\n",
+        vulnerability.title,
+        vulnerability.severity,
+        location,
+        name,
+        entry.name,
+        entry.parameter_types.join(",")
+    );
+    if let Some(flagged) = &target.flagged {
+        header.push_str(&format!(" * Reached through {}, which calls {}\n", entry.name, flagged));
+    }
+    header.push_str(&format!(" * Description: {}\n */", one_line(&vulnerability.description)));
+
+    let mut constructor_locals = Vec::new();
+    let constructor_arguments: Vec<String> = target
+        .contract
+        .constructor
+        .iter()
+        .flat_map(|constructor| constructor.parameter_types.iter().enumerate())
+        .map(|(index, ty)| target.argument(ty, &format!("arg{}", index), "address(this)", false, &mut constructor_locals))
+        .collect();
+    let mut set_up: Vec<String> = constructor_locals;
+    set_up.push(format!("target = new {}({});", name, constructor_arguments.join(", ")));
+    set_up.push("// Funds of other users, for the exploit to take".to_string());
+    set_up.push("vm.deal(address(target), 10 ether);".to_string());
+
+    let mut attacker_contract = String::new();
+    let mut exploit = vec!["vm.deal(attacker, 1 ether);".to_string(), "vm.startPrank(attacker);".to_string()];
+    let assertion = if vulnerability.category == VulnerabilityCategory::Reentrancy {
+        let mut attack = Vec::new();
+        if let (false, Some(deposit)) = (entry.state_mutability == "payable", target.deposit()) {
+            attack.extend(target.call("target", deposit, "address(this)", "msg.value", false));
+        }
+        attack.extend(target.call("target", entry, "address(this)", "msg.value", false));
+        let reenter = target.call("target", entry, "address(this)", "0", false);
+        attacker_contract = format!(
+            "\n/// Re-enters {name}.{function} from `receive` while the first call is still running\ncontract Attacker {{\n    {name} public target;\n    uint256 public reentered;\n\n    constructor({name} _target) {{\n        target = _target;\n    }}\n\n    function attack() external payable {{\n{attack}\n    }}\n\n    receive() external payable {{\n        if (reentered < 3 && address(target).balance > 0) {{\n            reentered++;\n{reenter}\n        }}\n    }}\n}}\n",
+            name = name,
+            function = entry.name,
+            attack = indent(&attack, 2),
+            reenter = indent(&reenter, 3),
+        );
+        exploit.push("Attacker attack = new Attacker(target);".to_string());
+        exploit.push("attack.attack{value: 1 ether}();".to_string());
+        exploit.push("vm.stopPrank();".to_string());
+        vec![
+            "// The attacker got more out than it put in".to_string(),
+            "assertGt(address(attack).balance, 1 ether, \"no more withdrawn than deposited\");".to_string(),
+        ]
+    } else {
+        exploit.extend(target.call("target", entry, "attacker", "1 ether", overflow));
+        exploit.push("vm.stopPrank();".to_string());
+        vec![match vulnerability.category {
+            VulnerabilityCategory::AccessControl => {
+                "// Reaching this line shows an account without any role made the call; assert the state it changed".to_string()
+            }
+            VulnerabilityCategory::IntegerOverflow => {
+                "// Arguments are at the bounds of their types; assert the wrapped state, e.g. a balance above the supply".to_string()
+            }
+            _ => "// Assert the invariant the finding breaks, e.g. a balance or the owner".to_string(),
+        }]
+    };
+
+    format!(
+        "// SPDX-License-Identifier: MIT\n{pragma}\n\nimport \"forge-std/Test.sol\";\n\n{audited}\n\n{header}\n{attacker_contract}\ncontract ExploitTest is Test {{\n    {name} target;\n    address attacker = makeAddr(\"attacker\");\n\n    function setUp() public {{\n{set_up}\n    }}\n\n    function test_exploit() public {{\n{exploit}\n\n{assertion}\n    }}\n}}\n",
+        pragma = target.pragma(),
+        audited = audited,
+        header = header,
+        attacker_contract = attacker_contract,
+        name = name,
+        set_up = indent(&set_up, 2),
+        exploit = indent(&exploit, 2),
+        assertion = indent(&assertion, 2),
+    )
+}
+
+/// `lines` indented by `depth` levels of four spaces
+fn indent(lines: &[String], depth: usize) -> String {
+    let prefix = "    ".repeat(depth);
+    lines.iter().map(|line| format!("{}{}", prefix, line)).collect::<Vec<_>>().join("\n")
+}
+
+/// Generic exploit contract, for findings whose function cannot be resolved from the source
+fn solidity_template(vulnerability: &Vulnerability) -> String {
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * PoC Exploit for: {}
+ * Severity: {}
+ * Description: {}
+ */
+
+contract ExploitPoC {{
+    address public target;
+
+    constructor(address _target) {{
+        target = _target;
+    }}
+
+    function exploit() external {{
+        // Exploit implementation based on vulnerability type
+        // This is a template - actual implementation depends on specific vulnerability
+
+        // Example: Reentrancy exploit
+        (bool success, ) = target.call(
+            abi.encodeWithSignature("vulnerableFunction()")
+        );
+        require(success, "Exploit failed");
+    }}
+
+    // Add fallback function for reentrancy attacks
+    fallback() external payable {{
+        if (address(target).balance > 0) {{
+            (bool success, ) = target.call(
+                abi.encodeWithSignature("vulnerableFunction()")
+            );
+            require(success, "Reentrancy failed");
+        }}
+    }}
+}}
+"#,
+        vulnerability.title,
+        vulnerability.severity,
+        one_line(&vulnerability.description)
+    )
+}
+
+fn cairo_poc(vulnerability: &Vulnerability) -> String {
+    format!(
+        r#"// PoC Exploit for: {title}
+// Severity: {severity}
+// Description: {description}
+//
+// Run with: snforge test test_exploit
+
+use snforge_std::{{declare, ContractClassTrait, DeclareResultTrait, start_cheat_caller_address}};
+use starknet::{{ContractAddress, contract_address_const}};
+
+// Replace with the dispatcher generated for the audited contract
+use {module}::{{ITargetDispatcher, ITargetDispatcherTrait}};
+
+fn deploy_target() -> ContractAddress {{
+    let contract = declare("{module}").unwrap().contract_class();
+    let (address, _) = contract.deploy(@array![]).unwrap();
+    address
+}}
+
+#[test]
+fn test_exploit() {{
+    let target = deploy_target();
+    let dispatcher = ITargetDispatcher {{ contract_address: target }};
+
+    // Act as an arbitrary, unprivileged account
+    let attacker: ContractAddress = contract_address_const::<'attacker'>();
+    start_cheat_caller_address(target, attacker);
+
+    // This is a template - call the vulnerable entry point here
+    dispatcher.vulnerable_function();
+
+    // Assert the invariant the finding breaks
+    assert(false, 'exploit not implemented');
+}}
+"#,
+        title = vulnerability.title,
+        severity = vulnerability.severity,
+        description = one_line(&vulnerability.description),
+        module = target_name(vulnerability),
+    )
+}
+
+fn move_poc(vulnerability: &Vulnerability) -> String {
+    format!(
+        r#"// PoC Exploit for: {title}
+// Severity: {severity}
+// Description: {description}
+//
+// Run with: aptos move test --filter exploit (or: sui move test exploit)
+
+#[test_only]
+module exploit::{module}_exploit {{
+    use std::signer;
+
+    // Replace with the audited module, e.g. `use target::{module};`
+
+    #[test(admin = @0xA, attacker = @0xBAD)]
+    fun test_exploit(admin: &signer, attacker: &signer) {{
+        let _admin_addr = signer::address_of(admin);
+        let _attacker_addr = signer::address_of(attacker);
+
+        // This is a template - initialise the module as `admin`,
+        // then call the vulnerable function as `attacker`
+        // {module}::vulnerable_function(attacker);
+
+        // Assert the invariant the finding breaks
+        assert!(false, 0);
+    }}
+}}
+"#,
+        title = vulnerability.title,
+        severity = vulnerability.severity,
+        description = one_line(&vulnerability.description),
+        module = target_name(vulnerability),
+    )
+}
+
+fn ink_poc(vulnerability: &Vulnerability) -> String {
+    format!(
+        r#"//! PoC Exploit for: {title}
+//! Severity: {severity}
+//! Description: {description}
+//!
+//! Run with: cargo test --features e2e-tests
+
+#[cfg(all(test, feature = "e2e-tests"))]
+mod exploit_tests {{
+    use super::{module}::*;
+    use ink_e2e::ContractsBackend;
+
+    type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+    #[ink_e2e::test]
+    async fn test_exploit<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {{
+        // Deploy the audited contract as Alice
+        let mut constructor = ContractRef::new();
+        let contract = client
+            .instantiate("{module}", &ink_e2e::alice(), &mut constructor)
+            .submit()
+            .await
+            .expect("instantiate failed");
+        let mut call_builder = contract.call_builder::<Contract>();
+
+        // This is a template - call the vulnerable message as Bob
+        let exploit = call_builder.vulnerable_message();
+        let result = client
+            .call(&ink_e2e::bob(), &exploit)
+            .submit()
+            .await;
+
+        // Assert the invariant the finding breaks
+        assert!(result.is_err(), "exploit not implemented");
+        Ok(())
+    }}
+}}
+"#,
+        title = vulnerability.title,
+        severity = vulnerability.severity,
+        description = one_line(&vulnerability.description),
+        module = target_name(vulnerability),
+    )
+}
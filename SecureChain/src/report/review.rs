@@ -0,0 +1,177 @@
+//! Code review comments
+//!
+//! Review mode reports what it sees as comments on lines of code, the way a
+//! human reviewer would, instead of as findings in a separate report. The
+//! comments can be written out as JSON or posted as a GitHub pull request
+//! review. GitHub only accepts inline comments on lines the pull request
+//! touches, so the others are listed in the review's body instead of being
+//! dropped.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ai")]
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+#[cfg(feature = "ai")]
+use crate::utils::config::Config;
+
+/// A comment on one line of a reviewed file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewComment {
+    /// Path relative to the repository root, as GitHub expects it
+    pub path: String,
+    pub line: usize,
+    /// Function the comment is about
+    pub function: String,
+    pub body: String,
+}
+
+/// A pull request, written `owner/repo#123`
+#[derive(Debug, Clone)]
+pub struct PullRequest {
+    pub repo: String,
+    pub number: u64,
+}
+
+impl std::str::FromStr for PullRequest {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = || anyhow!("Invalid pull request: {} (expected owner/repo#123)", value);
+        let (repo, number) = value.split_once('#').ok_or_else(invalid)?;
+        if repo.split('/').filter(|part| !part.is_empty()).count() != 2 {
+            return Err(invalid());
+        }
+        Ok(Self {
+            repo: repo.to_string(),
+            number: number.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Where a posted review ended up
+#[cfg(feature = "ai")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostedReview {
+    pub url: String,
+    /// Comments placed on their line
+    pub inline: usize,
+    /// Comments on lines outside the diff, listed in the review body
+    pub in_body: usize,
+}
+
+/// Directory of `input` relative to its repository root, empty outside a repository
+pub fn repo_prefix(input: &Path) -> String {
+    let directory = if input.is_dir() { input } else { input.parent().unwrap_or(Path::new(".")) };
+    let directory = if directory.as_os_str().is_empty() { Path::new(".") } else { directory };
+
+    Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(["rev-parse", "--show-prefix"])
+        .stdin(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Post `comments` as one review on the pull request, using `GITHUB_TOKEN`
+#[cfg(feature = "ai")]
+pub async fn post_github_review(config: &Config, pr: &PullRequest, comments: &[ReviewComment]) -> Result<PostedReview> {
+    let token = std::env::var("GITHUB_TOKEN")
+        .map_err(|_| anyhow!("Posting a review needs a GITHUB_TOKEN with pull request write access"))?;
+    let github = &config.networks.github;
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(github.timeout))
+        .user_agent("SecureChain")
+        .build()?;
+    let base = format!("{}/repos/{}/pulls/{}", github.api_url.trim_end_matches('/'), pr.repo, pr.number);
+
+    // Lines each changed file can take comments on
+    let mut commentable: HashMap<String, HashSet<usize>> = HashMap::new();
+    for page in 1.. {
+        let files: Vec<serde_json::Value> = client
+            .get(format!("{}/files?per_page=100&page={}", base, page))
+            .bearer_auth(&token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        for file in &files {
+            if let (Some(path), Some(patch)) = (file["filename"].as_str(), file["patch"].as_str()) {
+                commentable.insert(path.to_string(), commentable_lines(patch));
+            }
+        }
+        if files.len() < 100 {
+            break;
+        }
+    }
+
+    let (inline, outside): (Vec<&ReviewComment>, Vec<&ReviewComment>) = comments
+        .iter()
+        .partition(|comment| commentable.get(&comment.path).is_some_and(|lines| lines.contains(&comment.line)));
+
+    let mut body = format!("SecureChain reviewed this pull request and left {} comments.", comments.len());
+    if !outside.is_empty() {
+        body.push_str("\n\nOn lines this pull request does not change:\n");
+        for comment in &outside {
+            body.push_str(&format!("\n- `{}:{}` ({}): {}", comment.path, comment.line, comment.function, comment.body));
+        }
+    }
+    let payload = serde_json::json!({
+        "event": "COMMENT",
+        "body": body,
+        "comments": inline
+            .iter()
+            .map(|comment| serde_json::json!({
+                "path": comment.path,
+                "line": comment.line,
+                "side": "RIGHT",
+                "body": comment.body,
+            }))
+            .collect::<Vec<_>>(),
+    });
+
+    let response = client.post(format!("{}/reviews", base)).bearer_auth(&token).json(&payload).send().await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let detail = response.text().await.unwrap_or_default();
+        return Err(anyhow!("GitHub rejected the review ({}): {}", status, detail));
+    }
+    let review: serde_json::Value = response.json().await?;
+
+    Ok(PostedReview {
+        url: review["html_url"].as_str().unwrap_or_default().to_string(),
+        inline: inline.len(),
+        in_body: outside.len(),
+    })
+}
+
+/// New-file lines of a unified diff patch that are added or context
+#[cfg(feature = "ai")]
+fn commentable_lines(patch: &str) -> HashSet<usize> {
+    let mut lines = HashSet::new();
+    let mut line = 0;
+    for text in patch.lines() {
+        if let Some(header) = text.strip_prefix("@@ ") {
+            // @@ -old,count +new,count @@
+            line = header
+                .split_whitespace()
+                .find_map(|range| range.strip_prefix('+'))
+                .and_then(|range| range.split(',').next())
+                .and_then(|start| start.parse().ok())
+                .unwrap_or(0);
+        } else if text.starts_with('-') || text.starts_with('\\') {
+            continue;
+        } else if line > 0 {
+            lines.insert(line);
+            line += 1;
+        }
+    }
+    lines
+}
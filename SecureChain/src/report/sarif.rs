@@ -0,0 +1,157 @@
+//! SARIF output
+//!
+//! Analysis results as a SARIF 2.1.0 log, the format GitHub code scanning
+//! accepts uploads in and most IDEs can show inline. Each distinct check
+//! becomes a rule: built-in findings use their detector id, external tools
+//! the check name in their title. Findings become results on the source file
+//! the manifest recorded for them, with a fingerprint that stays the same
+//! across runs so code scanning can tell new alerts from known ones.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::core::analyzer::AnalysisResults;
+use crate::core::detector_catalog;
+use crate::report::vulnerability::Vulnerability;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// SARIF log of the findings of `results`, each with its id in the reports
+pub fn render(results: &AnalysisResults, finding_numbers: &HashMap<String, String>) -> Result<String> {
+    let mut rules: BTreeMap<String, Value> = BTreeMap::new();
+    let mut rule_ids = Vec::new();
+    for vuln in &results.vulnerabilities {
+        let id = detector_catalog::check_id(vuln);
+        rules.entry(id.clone()).or_insert_with(|| rule(&id, vuln));
+        rule_ids.push(id);
+    }
+    let indices: BTreeMap<&String, usize> = rules.keys().enumerate().map(|(index, id)| (id, index)).collect();
+    let finding_files = results.finding_files();
+    let fingerprints = results.fingerprints();
+
+    let findings: Vec<Value> = results
+        .vulnerabilities
+        .iter()
+        .zip(&rule_ids)
+        .map(|(vuln, id)| {
+            let mut location = json!({ "artifactLocation": { "uri": uri(results, vuln, finding_files.get(vuln.id.as_str()).copied()), "uriBaseId": "%SRCROOT%" } });
+            if let Some(line) = vuln.line_number.filter(|line| *line > 0) {
+                location["region"] = json!({ "startLine": line });
+            }
+            let mut properties = json!({ "severity": vuln.severity, "confidence": vuln.confidence, "tool": vuln.tool });
+            if !vuln.severity_overrides.is_empty() {
+                properties["originalSeverity"] = json!(vuln.original_severity());
+            }
+            if !vuln.detected_by.is_empty() {
+                properties["detectedBy"] = json!(vuln.detected_by);
+            }
+            if let Some(number) = finding_numbers.get(&vuln.id) {
+                properties["findingId"] = json!(number);
+            }
+            if let Some(cvss) = &vuln.cvss {
+                properties["cvss"] = json!(cvss.vector);
+                properties["cvssScore"] = json!(cvss.score);
+            }
+            json!({
+                "ruleId": id,
+                "ruleIndex": indices[id],
+                "level": level(&vuln.severity),
+                "message": { "text": format!("{}: {}", vuln.title, vuln.description) },
+                "locations": [{ "physicalLocation": location }],
+                "partialFingerprints": { "securechain/v1": fingerprints[vuln.id.as_str()] },
+                "properties": properties,
+            })
+        })
+        .collect();
+
+    let mut run = json!({
+        "tool": {
+            "driver": {
+                "name": "SecureChain",
+                "version": env!("CARGO_PKG_VERSION"),
+                "rules": rules.into_values().collect::<Vec<Value>>(),
+            }
+        },
+        "results": findings,
+        "properties": { "toolsUsed": results.analysis_summary.tools_used },
+    });
+    if let Some(commit) = results.manifest.as_ref().and_then(|manifest| manifest.git_commit.as_ref()) {
+        run["properties"]["gitCommit"] = json!(commit);
+    }
+
+    Ok(serde_json::to_string_pretty(&json!({ "$schema": SCHEMA, "version": "2.1.0", "runs": [run] }))?)
+}
+
+/// Rule for the check `id`, described by its detector or its first finding
+fn rule(id: &str, vuln: &Vulnerability) -> Value {
+    let detector = detector_catalog::detector_of(vuln);
+    let mut tags = vec!["security".to_string()];
+    if let Some(cwe) = vuln.cwe_id.as_deref().and_then(|cwe| cwe.strip_prefix("CWE-")) {
+        tags.push(format!("external/cwe/cwe-{}", cwe));
+    }
+
+    // Titles of built-in findings name the code they were found in, so the detector describes the rule
+    let (name, summary, description) = match detector {
+        Some(detector) => (detector.id, detector.description, detector.description),
+        None => (vuln.title.split_once(": ").map_or(vuln.title.as_str(), |(_, check)| check), vuln.title.as_str(), vuln.description.as_str()),
+    };
+    let mut rule = json!({
+        "id": id,
+        "name": name,
+        "shortDescription": { "text": summary },
+        "fullDescription": { "text": description },
+        "defaultConfiguration": { "level": level(detector.map_or(&vuln.severity, |detector| detector.severity)) },
+        "properties": {
+            "tags": tags,
+            "security-severity": security_severity(vuln),
+        },
+    });
+    if let Some(recommendation) = &vuln.recommendation {
+        rule["help"] = json!({ "text": recommendation });
+    }
+    if let Some(reference) = vuln.references.first() {
+        rule["helpUri"] = json!(reference);
+    }
+    rule
+}
+
+/// SARIF level of a severity
+fn level(severity: &str) -> &'static str {
+    match severity {
+        "Critical" | "High" => "error",
+        "Medium" => "warning",
+        _ => "note",
+    }
+}
+
+/// Score GitHub code scanning ranks security alerts by: the CVSS base score
+/// if known, otherwise one inside the severity's band
+///
+/// Findings are sorted by score, so the first finding of a rule, which
+/// describes it, carries its highest score.
+fn security_severity(vuln: &Vulnerability) -> String {
+    if let Some(cvss) = &vuln.cvss {
+        return format!("{:.1}", cvss.score);
+    }
+    match vuln.severity.as_str() {
+        "Critical" => "9.5",
+        "High" => "8.0",
+        "Medium" => "5.5",
+        "Low" => "3.0",
+        _ => "0.0",
+    }
+    .to_string()
+}
+
+/// Path of the source a finding refers to, relative to the working directory when it is below it
+fn uri(results: &AnalysisResults, vuln: &Vulnerability, file: Option<&str>) -> String {
+    let path = match &results.manifest {
+        Some(manifest) => manifest.source_path(&vuln.file_path, file),
+        None => file.unwrap_or(&vuln.file_path),
+    };
+    let path = std::path::Path::new(path);
+    let relative = std::env::current_dir().ok().and_then(|cwd| path.strip_prefix(cwd).ok().map(|p| p.to_path_buf()));
+    let path = relative.as_deref().unwrap_or(path);
+    path.to_string_lossy().replace('\\', "/").trim_start_matches("./").to_string()
+}
@@ -3,9 +3,13 @@
 //! This module defines the core vulnerability types and categories
 //! used throughout the BugForgeX analysis system.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::report::cvss::Cvss;
+use crate::utils::abi::{encode_hex, keccak256};
+
 /// Represents a security vulnerability found in smart contract code
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Vulnerability {
@@ -19,6 +23,7 @@ pub struct Vulnerability {
     pub description: String,
     
     /// Severity level (Critical, High, Medium, Low, Info)
+    #[serde(deserialize_with = "canonical_severity")]
     pub severity: String,
     
     /// Category of the vulnerability
@@ -47,6 +52,55 @@ pub struct Vulnerability {
     
     /// Confidence level of the detection (0.0 to 1.0)
     pub confidence: f64,
+
+    /// When, by which tool version and in which run the finding was first produced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+
+    /// Severity changes made by auditors, oldest first; `severity` holds the latest
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub severity_overrides: Vec<SeverityOverride>,
+
+    /// CVSS 3.1 vector and base score, given by the reporting tool or
+    /// derived for built-in detectors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cvss: Option<Cvss>,
+
+    /// Every tool that reported this finding, when several did; `tool` is
+    /// the one whose report was kept
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub detected_by: Vec<String>,
+}
+
+/// Severity as `Severity::as_str` spells it, whatever case or `informational` results saved
+fn canonical_severity<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let severity = String::deserialize(deserializer)?;
+    Ok(match severity.parse::<Severity>() {
+        Ok(level) => level.as_str().to_string(),
+        Err(_) => severity,
+    })
+}
+
+/// Origin of a finding, kept across runs so its first sighting stays known
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub detected_at: DateTime<Utc>,
+    /// Version of the detecting tool; SecureChain's own for built-in detectors
+    pub tool_version: Option<String>,
+    /// Run id of the manifest of the run that produced the finding
+    pub run_id: String,
+    /// Commit of the analysed code, if it was in a git repository
+    pub git_commit: Option<String>,
+}
+
+/// An auditor's change to the severity a tool assigned, with the reason for it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeverityOverride {
+    pub from: String,
+    pub to: String,
+    pub justification: String,
+    pub author: String,
+    pub at: DateTime<Utc>,
 }
 
 /// Categories of vulnerabilities that can be detected
@@ -88,6 +142,9 @@ pub enum VulnerabilityCategory {
     /// Code quality issues
     CodeQuality,
     
+    /// Unreachable functions, unused state and constant-condition branches
+    DeadCode,
+    
     /// Fuzzing-related findings
     Fuzzing,
     
@@ -113,6 +170,7 @@ impl fmt::Display for VulnerabilityCategory {
             VulnerabilityCategory::Cryptography => write!(f, "Cryptography"),
             VulnerabilityCategory::GasOptimization => write!(f, "Gas Optimization"),
             VulnerabilityCategory::CodeQuality => write!(f, "Code Quality"),
+            VulnerabilityCategory::DeadCode => write!(f, "Dead Code"),
             VulnerabilityCategory::Fuzzing => write!(f, "Fuzzing"),
             VulnerabilityCategory::SymbolicExecution => write!(f, "Symbolic Execution"),
             VulnerabilityCategory::Other => write!(f, "Other"),
@@ -136,6 +194,7 @@ impl VulnerabilityCategory {
             VulnerabilityCategory::Cryptography => "High",
             VulnerabilityCategory::GasOptimization => "Low",
             VulnerabilityCategory::CodeQuality => "Low",
+            VulnerabilityCategory::DeadCode => "Info",
             VulnerabilityCategory::Fuzzing => "Medium",
             VulnerabilityCategory::SymbolicExecution => "Medium",
             VulnerabilityCategory::Other => "Medium",
@@ -157,6 +216,7 @@ impl VulnerabilityCategory {
             VulnerabilityCategory::Cryptography => "Issues with cryptographic implementations or randomness",
             VulnerabilityCategory::GasOptimization => "Inefficient gas usage patterns",
             VulnerabilityCategory::CodeQuality => "General code quality and maintainability issues",
+            VulnerabilityCategory::DeadCode => "Code that can never run or is never used, inflating audit scope",
             VulnerabilityCategory::Fuzzing => "Issues discovered through fuzzing and property testing",
             VulnerabilityCategory::SymbolicExecution => "Vulnerabilities found through symbolic execution analysis",
             VulnerabilityCategory::Other => "Other types of vulnerabilities not covered by specific categories",
@@ -226,6 +286,11 @@ impl VulnerabilityCategory {
                 "Add comprehensive documentation",
                 "Implement proper testing",
             ],
+            VulnerabilityCategory::DeadCode => vec![
+                "Remove unreachable functions and unused state",
+                "Delete branches behind constant conditions",
+                "Finish or fully remove partially removed features",
+            ],
             VulnerabilityCategory::Fuzzing => vec![
                 "Fix property violations",
                 "Add proper invariants",
@@ -259,6 +324,7 @@ impl VulnerabilityCategory {
             VulnerabilityCategory::Cryptography,
             VulnerabilityCategory::GasOptimization,
             VulnerabilityCategory::CodeQuality,
+            VulnerabilityCategory::DeadCode,
             VulnerabilityCategory::Fuzzing,
             VulnerabilityCategory::SymbolicExecution,
             VulnerabilityCategory::Other,
@@ -290,6 +356,60 @@ impl Vulnerability {
             cwe_id: None,
             tool,
             confidence: 0.5,
+            provenance: None,
+            severity_overrides: Vec::new(),
+            cvss: None,
+            detected_by: Vec::new(),
+        }
+    }
+
+    /// Keep the earlier provenance of this finding and `other`, the same issue seen again
+    pub fn merge_provenance(&mut self, other: &Vulnerability) {
+        if let Some(theirs) = &other.provenance {
+            let earlier = match &self.provenance {
+                Some(ours) => theirs.detected_at < ours.detected_at,
+                None => true,
+            };
+            if earlier {
+                self.provenance = Some(theirs.clone());
+            }
+        }
+    }
+
+    /// Severity the tool assigned, before any override
+    pub fn original_severity(&self) -> &str {
+        self.severity_overrides.first().map_or(&self.severity, |first| &first.from)
+    }
+
+    /// Change the severity to `to`, recording who did it and why
+    pub fn override_severity(&mut self, to: &str, justification: &str, author: &str) -> &SeverityOverride {
+        self.severity_overrides.push(SeverityOverride {
+            from: self.severity.clone(),
+            to: to.to_string(),
+            justification: justification.to_string(),
+            author: author.to_string(),
+            at: Utc::now(),
+        });
+        self.severity = to.to_string();
+        // A vector scoring into another band no longer describes the finding
+        if self.cvss.as_ref().is_some_and(|cvss| Some(cvss.severity()) != self.severity_level()) {
+            self.cvss = None;
+        }
+        &self.severity_overrides[self.severity_overrides.len() - 1]
+    }
+
+    /// Take over the overrides of `other`, the same issue seen again, while
+    /// its tool still assigns the severity they overrode
+    pub fn merge_severity_overrides(&mut self, other: &Vulnerability) {
+        if self.severity_overrides.is_empty()
+            && !other.severity_overrides.is_empty()
+            && self.severity == other.original_severity()
+        {
+            self.severity_overrides = other.severity_overrides.clone();
+            self.severity = other.severity.clone();
+            if self.cvss.as_ref().is_some_and(|cvss| Some(cvss.severity()) != self.severity_level()) {
+                self.cvss = None;
+            }
         }
     }
 
@@ -341,13 +461,45 @@ impl Vulnerability {
 
     /// Get severity priority (higher number = more severe)
     pub fn severity_priority(&self) -> u8 {
-        match self.severity.as_str() {
-            "Critical" => 5,
-            "High" => 4,
-            "Medium" => 3,
-            "Low" => 2,
-            "Info" => 1,
-            _ => 0,
+        self.severity_level().map_or(0, |severity| severity.priority())
+    }
+
+    /// Tools that reported the finding, the one whose report was kept first
+    pub fn tools(&self) -> Vec<&str> {
+        if self.detected_by.is_empty() {
+            vec![self.tool.as_str()]
+        } else {
+            self.detected_by.iter().map(String::as_str).collect()
+        }
+    }
+
+    /// Stable identity across runs: same tool, file, title and flagged code, since lines move when code around a finding changes
+    ///
+    /// `occurrence` tells apart findings alike in all of these, numbered in
+    /// line order; [`utils::fingerprints`] numbers them.
+    pub fn fingerprint(&self, occurrence: usize) -> String {
+        let mut key = format!("{}\0{}\0{}", self.tool, self.file_path, self.title);
+        if let Some(snippet) = &self.code_snippet {
+            key.push('\0');
+            key.push_str(&snippet.split_whitespace().collect::<Vec<_>>().join(" "));
+        }
+        if occurrence > 0 {
+            key.push_str(&format!("\0#{}", occurrence));
+        }
+        encode_hex(&keccak256(key.as_bytes()))
+    }
+
+    /// Parsed severity, if it is one of the known levels
+    pub fn severity_level(&self) -> Option<Severity> {
+        self.severity.parse().ok()
+    }
+
+    /// Numeric score for ordering and filtering: the CVSS base score if
+    /// known, otherwise the severity's nominal score
+    pub fn score(&self) -> f64 {
+        match &self.cvss {
+            Some(cvss) => cvss.score,
+            None => self.severity_level().map_or(0.0, |severity| severity.nominal_score()),
         }
     }
 
@@ -387,6 +539,21 @@ impl fmt::Display for Severity {
     }
 }
 
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "critical" => Ok(Severity::Critical),
+            "high" => Ok(Severity::High),
+            "medium" => Ok(Severity::Medium),
+            "low" => Ok(Severity::Low),
+            "info" | "informational" => Ok(Severity::Info),
+            _ => Err(format!("Unknown severity: {}", s)),
+        }
+    }
+}
+
 impl Severity {
     /// Get all severity levels
     pub fn all_levels() -> Vec<Severity> {
@@ -409,16 +576,90 @@ impl Severity {
             Severity::Info => 1,
         }
     }
+
+    /// Name as stored in `Vulnerability::severity`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Critical => "Critical",
+            Severity::High => "High",
+            Severity::Medium => "Medium",
+            Severity::Low => "Low",
+            Severity::Info => "Info",
+        }
+    }
+
+    /// CVSS 3.1 qualitative rating of a base score
+    pub fn from_score(score: f64) -> Severity {
+        if score >= 9.0 {
+            Severity::Critical
+        } else if score >= 7.0 {
+            Severity::High
+        } else if score >= 4.0 {
+            Severity::Medium
+        } else if score > 0.0 {
+            Severity::Low
+        } else {
+            Severity::Info
+        }
+    }
+
+    /// Score standing in for findings without a CVSS vector, inside the
+    /// level's CVSS band
+    pub fn nominal_score(&self) -> f64 {
+        match self {
+            Severity::Critical => 10.0,
+            Severity::High => 7.5,
+            Severity::Medium => 5.3,
+            Severity::Low => 2.0,
+            Severity::Info => 0.0,
+        }
+    }
+}
+
+/// Lowest severity a report keeps: a level, compared by severity, or a
+/// score from 0 to 10, compared by `Vulnerability::score`
+#[derive(Debug, Clone, PartialEq)]
+pub enum MinSeverity {
+    Level(Severity),
+    Score(f64),
+}
+
+impl std::str::FromStr for MinSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().parse::<f64>() {
+            Ok(score) if (0.0..=10.0).contains(&score) => Ok(MinSeverity::Score(score)),
+            Ok(score) => Err(format!("Severity score {} is outside 0 to 10", score)),
+            Err(_) => s.trim().parse().map(MinSeverity::Level),
+        }
+    }
+}
+
+impl MinSeverity {
+    /// Whether `vuln` is at or above this threshold
+    pub fn admits(&self, vuln: &Vulnerability) -> bool {
+        match self {
+            MinSeverity::Level(level) => vuln.severity_priority() >= level.priority(),
+            MinSeverity::Score(score) => vuln.score() >= *score,
+        }
+    }
 }
 
 /// Utility functions for working with vulnerabilities
 pub mod utils {
     use super::*;
 
-    /// Sort vulnerabilities by severity and confidence
+    /// Sort vulnerabilities by score, severity and confidence
     pub fn sort_vulnerabilities(vulnerabilities: &mut [Vulnerability]) {
         vulnerabilities.sort_by(|a, b| {
-            // First sort by severity (descending)
+            // First sort by score (descending)
+            let score_cmp = b.score().partial_cmp(&a.score()).unwrap_or(std::cmp::Ordering::Equal);
+            if score_cmp != std::cmp::Ordering::Equal {
+                return score_cmp;
+            }
+
+            // Then by severity (descending)
             let severity_cmp = b.severity_priority().cmp(&a.severity_priority());
             if severity_cmp != std::cmp::Ordering::Equal {
                 return severity_cmp;
@@ -435,6 +676,33 @@ pub mod utils {
         });
     }
 
+    /// Fingerprint of each of `vulnerabilities`, in order
+    ///
+    /// Findings that would otherwise share one are numbered by line, so each
+    /// keeps its own as long as the others of its kind stay in the results.
+    pub fn fingerprints<'a>(vulnerabilities: impl IntoIterator<Item = &'a Vulnerability>) -> Vec<String> {
+        let vulnerabilities: Vec<&Vulnerability> = vulnerabilities.into_iter().collect();
+        let bases: Vec<String> = vulnerabilities.iter().map(|vuln| vuln.fingerprint(0)).collect();
+        let mut order: Vec<usize> = (0..vulnerabilities.len()).collect();
+        order.sort_by(|&a, &b| {
+            bases[a]
+                .cmp(&bases[b])
+                .then(vulnerabilities[a].line_number.cmp(&vulnerabilities[b].line_number))
+                .then(vulnerabilities[a].description.cmp(&vulnerabilities[b].description))
+        });
+
+        let mut fingerprints = vec![String::new(); vulnerabilities.len()];
+        let mut occurrence = 0;
+        for (position, &index) in order.iter().enumerate() {
+            occurrence = match position.checked_sub(1) {
+                Some(previous) if bases[order[previous]] == bases[index] => occurrence + 1,
+                _ => 0,
+            };
+            fingerprints[index] = if occurrence == 0 { bases[index].clone() } else { vulnerabilities[index].fingerprint(occurrence) };
+        }
+        fingerprints
+    }
+
     /// Filter vulnerabilities by severity
     pub fn filter_by_severity<'a>(vulnerabilities: &'a [Vulnerability], severity: &'a str) -> Vec<&'a Vulnerability> {
         vulnerabilities.iter().filter(|v| v.severity == severity).collect()
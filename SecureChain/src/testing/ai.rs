@@ -0,0 +1,142 @@
+//! Mock AI provider
+//!
+//! A local HTTP server answering like Ollama: `/api/tags` lists the
+//! configured model, `/api/generate` and `/api/chat` reply with a canned
+//! completion, and anything else gets an empty JSON object. Every request is
+//! recorded, so tests can check what was sent to the model.
+
+use anyhow::Result;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use crate::utils::config::Config;
+
+/// A request the mock received
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+/// Ollama stand-in on a free local port, stopped on drop
+pub struct MockAiProvider {
+    url: String,
+    model: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+    stopped: Arc<AtomicBool>,
+    server: Option<JoinHandle<()>>,
+}
+
+impl MockAiProvider {
+    /// Serve `model`, answering every prompt with `completion`
+    pub fn start(model: &str, completion: &str) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let tags = serde_json::json!({ "models": [{ "name": model, "model": model }] }).to_string();
+        let generate = serde_json::json!({ "model": model, "response": completion, "done": true }).to_string();
+        let chat = serde_json::json!({
+            "model": model,
+            "message": { "role": "assistant", "content": completion },
+            "done": true,
+        })
+        .to_string();
+
+        let server = {
+            let requests = requests.clone();
+            let stopped = stopped.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let mut stream = match stream {
+                        Ok(stream) => stream,
+                        Err(_) => continue,
+                    };
+                    let request = match read_request(&stream) {
+                        Some(request) => request,
+                        None => continue,
+                    };
+                    let body = match request.path.split('?').next().unwrap_or_default() {
+                        "/api/tags" => tags.as_str(),
+                        "/api/generate" => generate.as_str(),
+                        "/api/chat" => chat.as_str(),
+                        _ => "{}",
+                    };
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    if let Ok(mut requests) = requests.lock() {
+                        requests.push(request);
+                    }
+                }
+            })
+        };
+
+        Ok(Self { url, model: model.to_string(), requests, stopped, server: Some(server) })
+    }
+
+    /// Base URL, e.g. `http://127.0.0.1:41234`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Requests received so far, in order
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().map(|requests| requests.clone()).unwrap_or_default()
+    }
+
+    /// Use the local backend, served by the mock
+    pub fn configure(&self, config: &mut Config) {
+        config.ai.backend = "local".to_string();
+        config.ai.local.ollama_url = self.url.clone();
+        config.ai.local.default_model = self.model.clone();
+    }
+}
+
+impl Drop for MockAiProvider {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wake the server from accept so it sees the flag
+        let _ = TcpStream::connect(self.url.trim_start_matches("http://"));
+        if let Some(server) = self.server.take() {
+            let _ = server.join();
+        }
+    }
+}
+
+/// Request line, headers and `Content-Length` body of one HTTP request
+fn read_request(stream: &TcpStream) -> Option<MockRequest> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).ok()? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+    Some(MockRequest { method, path, body: String::from_utf8_lossy(&body).to_string() })
+}
@@ -0,0 +1,47 @@
+//! Fixture contracts
+//!
+//! The contracts of the self-test corpus, each with the weakness it
+//! contains, so a test can assert on what a pipeline should find.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::core::selftest::CORPUS;
+use crate::report::vulnerability::VulnerabilityCategory;
+
+/// A known-vulnerable contract
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    /// SWC identifier, or `NSSC` for the not-so-smart-contracts examples
+    pub id: &'static str,
+    pub title: &'static str,
+    pub file: &'static str,
+    pub source: &'static str,
+    pub category: VulnerabilityCategory,
+}
+
+/// Every fixture contract, in corpus order
+pub fn fixtures() -> Vec<Fixture> {
+    CORPUS
+        .iter()
+        .map(|case| Fixture { id: case.id, title: case.title, file: case.file, source: case.source, category: case.category.clone() })
+        .collect()
+}
+
+/// The fixture in `file`, e.g. `SWC-107_reentrancy.sol`
+pub fn fixture(file: &str) -> Option<Fixture> {
+    fixtures().into_iter().find(|fixture| fixture.file == file)
+}
+
+/// Write every fixture into `dir`, returning the paths written
+pub fn write_fixtures(dir: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)?;
+    fixtures()
+        .iter()
+        .map(|fixture| {
+            let path = dir.join(fixture.file);
+            std::fs::write(&path, fixture.source)?;
+            Ok(path)
+        })
+        .collect()
+}
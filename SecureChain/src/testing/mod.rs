@@ -0,0 +1,17 @@
+//! Test support for pipelines built on SecureChain
+//!
+//! Exercising a full audit needs Slither, Mythril and an AI backend, which
+//! CI images and downstream projects rarely have, and whose output changes
+//! from release to release. This module, behind the `test-support` feature,
+//! stands in for them: the self-test corpus as fixture contracts, mock tool
+//! executables that print canned JSON, and a local HTTP server answering
+//! like Ollama. Each mock points a [`Config`](crate::utils::config::Config)
+//! at itself, so the engine runs unchanged and deterministically.
+
+pub mod ai;
+pub mod fixtures;
+pub mod tools;
+
+pub use ai::{MockAiProvider, MockRequest};
+pub use fixtures::{fixture, fixtures, write_fixtures, Fixture};
+pub use tools::MockTools;
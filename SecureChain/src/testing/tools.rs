@@ -0,0 +1,163 @@
+//! Mock tool executables
+//!
+//! Each mock is a small script in a temporary directory that answers
+//! `--version` like the real tool, prints its canned output for any other
+//! invocation and exits with the configured code. The arguments of every
+//! invocation are logged next to it, so tests can check how a tool was
+//! called. [`MockTools::configure`] points `tools.<name>.executable` at the
+//! scripts; tools without a mock keep their configured executable.
+
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+
+use crate::utils::config::Config;
+use crate::utils::retention;
+
+/// Slither report with a reentrancy in `SWC-107_reentrancy.sol`'s `withdraw`
+pub const SLITHER_JSON: &str = r#"{
+  "success": true,
+  "error": null,
+  "results": {
+    "detectors": [
+      {
+        "check": "reentrancy-eth",
+        "impact": "High",
+        "confidence": "Medium",
+        "description": "Reentrancy in SimpleDAO.withdraw(uint256) (SWC-107_reentrancy.sol#11-17):\n\tExternal calls:\n\t- (ok,None) = msg.sender.call{value: amount}() (SWC-107_reentrancy.sol#13)\n\tState variables written after the call(s):\n\t- credit[msg.sender] -= amount (SWC-107_reentrancy.sol#15)\n",
+        "elements": [
+          {
+            "type": "function",
+            "name": "withdraw",
+            "source_mapping": {
+              "filename_relative": "SWC-107_reentrancy.sol",
+              "lines": [11, 12, 13, 14, 15, 16, 17]
+            }
+          }
+        ]
+      }
+    ]
+  }
+}
+"#;
+
+/// Mythril report with an external call to `msg.sender` in `SWC-107_reentrancy.sol`
+pub const MYTHRIL_JSON: &str = r#"{
+  "success": true,
+  "error": null,
+  "issues": [
+    {
+      "title": "External Call To User-Supplied Address",
+      "swc-id": "107",
+      "severity": "Medium",
+      "description": "A call to a user-supplied address is executed.",
+      "contract": "SimpleDAO",
+      "function": "withdraw(uint256)",
+      "source_map": { "line": 13 }
+    }
+  ]
+}
+"#;
+
+/// Mock executables in a temporary directory, removed on drop
+pub struct MockTools {
+    dir: tempfile::TempDir,
+    tools: Vec<(String, PathBuf)>,
+}
+
+impl MockTools {
+    pub fn new() -> Result<Self> {
+        let dir = tempfile::Builder::new().prefix(retention::TEMP_PREFIX).tempdir()?;
+        Ok(Self { dir, tools: Vec::new() })
+    }
+
+    /// Mock Slither, printing `json` (e.g. [`SLITHER_JSON`]) and exiting 255 as it does with findings
+    pub fn with_slither(self, json: &str) -> Result<Self> {
+        self.with_tool("slither", "0.10.0", json, 255)
+    }
+
+    /// Mock Mythril, printing `json` (e.g. [`MYTHRIL_JSON`]) and exiting 1 as it does with issues
+    pub fn with_mythril(self, json: &str) -> Result<Self> {
+        self.with_tool("mythril", "Mythril version v0.24.8", json, 1)
+    }
+
+    /// Mock the tool configured as `tools.<name>`, answering `--version` with `version`
+    pub fn with_tool(mut self, name: &str, version: &str, stdout: &str, exit_code: i32) -> Result<Self> {
+        let output = self.dir.path().join(format!("{}.out", name));
+        let log = self.dir.path().join(format!("{}.log", name));
+        std::fs::write(&output, stdout)?;
+
+        let path = match cfg!(windows) {
+            true => {
+                let path = self.dir.path().join(format!("{}.cmd", name));
+                let script = format!(
+                    "@echo off\r\nif \"%~1\"==\"--version\" (\r\n  echo {}\r\n  exit /b 0\r\n)\r\necho %*>>\"{}\"\r\ntype \"{}\"\r\nexit /b {}\r\n",
+                    version,
+                    log.display(),
+                    output.display(),
+                    exit_code
+                );
+                std::fs::write(&path, script)?;
+                path
+            }
+            false => {
+                let path = self.dir.path().join(name);
+                let script = format!(
+                    "#!/bin/sh\nif [ \"$1\" = \"--version\" ]; then\n  echo '{}'\n  exit 0\nfi\necho \"$*\" >> '{}'\ncat '{}'\nexit {}\n",
+                    version,
+                    log.display(),
+                    output.display(),
+                    exit_code
+                );
+                std::fs::write(&path, script)?;
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+                }
+                path
+            }
+        };
+
+        self.tools.retain(|(known, _)| known != name);
+        self.tools.push((name.to_string(), path));
+        Ok(self)
+    }
+
+    /// Path of the mock for `name`
+    pub fn path(&self, name: &str) -> Option<&Path> {
+        self.tools.iter().find(|(known, _)| known == name).map(|(_, path)| path.as_path())
+    }
+
+    /// Arguments of each run of the mock for `name`, `--version` checks left out
+    pub fn invocations(&self, name: &str) -> Vec<String> {
+        std::fs::read_to_string(self.dir.path().join(format!("{}.log", name)))
+            .map(|log| log.lines().map(|line| line.trim().to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Point the configured tools at the mocks, enabling those that are off by default
+    pub fn configure(&self, config: &mut Config) -> Result<()> {
+        for (name, path) in &self.tools {
+            let executable = path.to_string_lossy().to_string();
+            let tools = &mut config.tools;
+            match name.as_str() {
+                "slither" => tools.slither.executable = executable,
+                "mythril" => tools.mythril.executable = executable,
+                "echidna" => tools.echidna.executable = executable,
+                "foundry" => tools.foundry.executable = executable,
+                "solhint" => {
+                    tools.solhint.executable = executable;
+                    tools.solhint.enabled = true;
+                }
+                "semgrep" => {
+                    tools.semgrep.executable = executable;
+                    tools.semgrep.enabled = true;
+                }
+                "solc" => tools.solc.executable = executable,
+                "vyper" => tools.vyper.executable = executable,
+                other => return Err(anyhow!("no tools.{}.executable to point at the mock", other)),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+//! Low-level helpers for Ethereum ABI data
+//!
+//! These helpers read 32-byte words out of ABI-encoded data (constructor
+//! arguments, `eth_call` results, event data) and convert between hex
+//! strings and bytes.
+
+use anyhow::{anyhow, Result};
+use sha3::{Digest, Keccak256};
+
+/// Keccak-256 hash of the input
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// The 32-byte word starting at `offset`
+pub fn word(data: &[u8], offset: usize) -> Option<&[u8]> {
+    data.get(offset..offset.checked_add(32)?)
+}
+
+/// Read a word as an offset or length, rejecting values that cannot be one
+pub fn read_usize(data: &[u8], offset: usize) -> Option<usize> {
+    let word = word(data, offset)?;
+    if word[..24].iter().any(|b| *b != 0) {
+        return None;
+    }
+    Some(word[24..].iter().fold(0usize, |acc, b| (acc << 8) | *b as usize))
+}
+
+/// Read length-prefixed `bytes`/`string` data starting at `offset`
+pub fn read_bytes(data: &[u8], offset: usize) -> Option<&[u8]> {
+    let length = read_usize(data, offset)?;
+    data.get(offset + 32..(offset + 32).checked_add(length)?)
+}
+
+/// Read a word as an address
+pub fn read_address(data: &[u8], offset: usize) -> Option<String> {
+    word(data, offset).map(|word| format!("0x{}", encode_hex(&word[12..])))
+}
+
+/// Render a big-endian unsigned integer in decimal
+pub fn to_decimal(bytes: &[u8]) -> String {
+    let mut digits = Vec::new();
+    let mut number = bytes.to_vec();
+
+    while number.iter().any(|b| *b != 0) {
+        let mut remainder = 0u32;
+        for byte in number.iter_mut() {
+            let value = (remainder << 8) | *byte as u32;
+            *byte = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(char::from(b'0' + remainder as u8));
+    }
+
+    if digits.is_empty() {
+        "0".to_string()
+    } else {
+        digits.iter().rev().collect()
+    }
+}
+
+/// Decode a hex string, with or without `0x` prefix
+pub fn decode_hex(input: &str) -> Result<Vec<u8>> {
+    let input = input.trim().trim_start_matches("0x");
+    if !input.len().is_multiple_of(2) {
+        return Err(anyhow!("odd number of hex digits"));
+    }
+
+    (0..input.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&input[i..i + 2], 16).map_err(|e| anyhow!("{}", e)))
+        .collect()
+}
+
+/// Encode bytes as lowercase hex without prefix
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
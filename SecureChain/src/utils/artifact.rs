@@ -0,0 +1,162 @@
+//! Crash-safe artifact files
+//!
+//! Results, reports and state files used to be written in place, so a crash
+//! or a full disk in the middle of a write left a truncated file that later
+//! commands failed on with a bare parse error. Artifacts are now written to
+//! a temporary file next to the target, synced to disk and renamed over it,
+//! so readers see either the old content or the new, never a mix. Loading
+//! checks what is read and says how to recover when it is unusable.
+
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Writes started by this process, numbering their temporary files
+static WRITES: AtomicU64 = AtomicU64::new(0);
+
+/// Write `contents` to `path` so that it is replaced whole or not at all
+///
+/// An existing file keeps its permissions, e.g. an executable hook or a
+/// state file readable only by its owner.
+pub fn write_atomic(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    replace(path.as_ref(), |file| file.write_all(contents.as_ref()))
+}
+
+/// Like `write_atomic`, with the contents written by `write` as they are produced
+///
+/// Large reports go to the temporary file section by section instead of
+/// being assembled in memory first; an error from `write` leaves the target
+/// untouched.
+pub fn write_atomic_with(path: impl AsRef<Path>, write: impl FnOnce(&mut dyn Write) -> Result<()>) -> Result<()> {
+    replace(path.as_ref(), |file| {
+        let mut out = BufWriter::new(file);
+        write(&mut out)?;
+        out.flush()?;
+        Ok(())
+    })
+}
+
+fn replace<E: From<std::io::Error>>(path: &Path, fill: impl FnOnce(&mut File) -> std::result::Result<(), E>) -> std::result::Result<(), E> {
+    let temp_path = temp_path(path);
+    let result = write_temp(&temp_path, path, fill).and_then(|()| fs::rename(&temp_path, path).map_err(E::from));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+        return result;
+    }
+
+    // The rename itself is only durable once the directory is synced
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+        if let Ok(dir) = File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
+fn write_temp<E: From<std::io::Error>>(
+    temp_path: &Path,
+    path: &Path,
+    fill: impl FnOnce(&mut File) -> std::result::Result<(), E>,
+) -> std::result::Result<(), E> {
+    let mut file = OpenOptions::new().write(true).create(true).truncate(true).open(temp_path)?;
+    if let Ok(metadata) = fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+    fill(&mut file)?;
+    Ok(file.sync_all()?)
+}
+
+/// Hidden sibling of `path` that a write in progress goes to, unique to the write so that
+/// concurrent writes of one process, as in the daemon and the server, never share one
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let write = WRITES.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{}.{}.{}.tmp", name, std::process::id(), write))
+}
+
+/// Read a JSON artifact, describing `what` it should be when it is unusable
+pub fn read_json<T: DeserializeOwned>(path: &Path, what: &str) -> Result<T> {
+    let content = read_text(path, what)?;
+    serde_json::from_str(&content).map_err(|e| invalid(path, what, &e))
+}
+
+/// Read an artifact's text, rejecting a missing or empty file with a recovery hint
+pub fn read_text(path: &Path, what: &str) -> Result<String> {
+    let content = fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => anyhow!("{} not found: {}", capitalize(what), path.display()),
+        _ => anyhow!("Could not read {} {}: {}", what, path.display(), e),
+    })?;
+    if content.trim().is_empty() {
+        return Err(anyhow!(
+            "{} is empty, so it is not a usable {}{}",
+            path.display(),
+            what,
+            recovery(path)
+        ));
+    }
+    Ok(content)
+}
+
+/// Error for an artifact that was read but could not be parsed
+pub fn invalid(path: &Path, what: &str, error: &serde_json::Error) -> anyhow::Error {
+    if error.is_eof() {
+        anyhow!(
+            "{} ends unexpectedly ({}); the write that produced it was probably interrupted{}",
+            path.display(),
+            error,
+            recovery(path)
+        )
+    } else {
+        anyhow!("{} is not a valid {}: {}", path.display(), what, error)
+    }
+}
+
+/// How to get a usable file back, pointing at leftovers of interrupted writes
+fn recovery(path: &Path) -> String {
+    let leftovers = leftover_temp_files(path);
+    let mut hint = String::from(". Re-run the command that produced it");
+    if !leftovers.is_empty() {
+        let names: Vec<String> = leftovers.iter().map(|leftover| leftover.display().to_string()).collect();
+        hint.push_str(&format!(
+            ", and delete the unfinished write{} {}",
+            if names.len() == 1 { "" } else { "s" },
+            names.join(", ")
+        ));
+    }
+    hint
+}
+
+/// Temporary files of writes to `path` that never finished
+fn leftover_temp_files(path: &Path) -> Vec<PathBuf> {
+    let (parent, name) = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => (parent, name.to_string_lossy()),
+        _ => return Vec::new(),
+    };
+    let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+    let prefix = format!(".{}.", name);
+    let mut leftovers: Vec<PathBuf> = fs::read_dir(parent)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            let entry_name = entry.file_name().to_string_lossy().into_owned();
+            entry_name.starts_with(&prefix) && entry_name.ends_with(".tmp")
+        })
+        .map(|entry| entry.path())
+        .collect();
+    leftovers.sort();
+    leftovers
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,337 @@
+//! Per-run resource budgets
+//!
+//! One pathological contract can make a compiler or fuzzer fill the disk or
+//! eat all memory of a shared runner. When budgets are configured, a run
+//! keeps its temporary files, and those of the tools it starts, in a scratch
+//! directory whose size is watched, and each external tool gets a memory and
+//! CPU time limit: a cgroup where one can be created (Linux with cgroup v2
+//! delegated to us, as in most containers), otherwise per-process resource
+//! limits. A tool that goes over its budget is stopped and reported as such;
+//! the run then either carries on without it or aborts, as configured.
+
+use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+use std::process::Output;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::process::Command;
+
+use crate::status;
+use crate::utils::config::BudgetConfig;
+use crate::utils::error::ToolError;
+
+/// How often the scratch directory is measured while a tool runs
+const DISK_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+static BUDGET: OnceLock<Budget> = OnceLock::new();
+
+/// Tools started so far, numbering their temporary directories
+static TOOL_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// Budgets exceeded so far in this run
+static EXCEEDED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct Budget {
+    limits: BudgetConfig,
+    /// Directory temporary files go to, when disk is budgeted
+    scratch: Option<PathBuf>,
+    /// Cgroup tools are moved into, when memory is budgeted and cgroups are usable
+    cgroup: Option<PathBuf>,
+    /// Whether `--max-*` flags set any of the limits
+    from_command_line: bool,
+}
+
+/// Apply `limits` to the rest of the run
+///
+/// With a disk budget, temporary files of this process and of the tools it
+/// starts go to a scratch directory, which [`finish`] removes.
+pub fn init(limits: &BudgetConfig, from_command_line: bool) -> Result<()> {
+    let scratch = if limits.max_disk_mb > 0 {
+        let scratch = std::env::temp_dir().join(format!("securechain-run-{}", std::process::id()));
+        std::fs::create_dir_all(&scratch)?;
+        for variable in ["TMPDIR", "TMP", "TEMP"] {
+            std::env::set_var(variable, &scratch);
+        }
+        Some(scratch)
+    } else {
+        None
+    };
+    let cgroup = if limits.max_memory_mb > 0 { create_cgroup(limits.max_memory_mb) } else { None };
+    if limits.max_memory_mb > 0 && cgroup.is_none() && !cfg!(unix) {
+        log::warn!("Memory budget is not enforced on this platform");
+    }
+
+    let _ = BUDGET.set(Budget { limits: limits.clone(), scratch, cgroup, from_command_line });
+    Ok(())
+}
+
+/// Whether the run's limits came from `--max-*` flags rather than the configuration alone
+pub fn from_command_line() -> bool {
+    BUDGET.get().is_some_and(|budget| budget.from_command_line)
+}
+
+/// Temporary directory of the system, where the scratch directory of a disk budget lives
+pub fn system_temp_dir() -> PathBuf {
+    let scratch = BUDGET.get().and_then(|budget| budget.scratch.as_deref());
+    match scratch.and_then(|scratch| scratch.parent()) {
+        Some(dir) => dir.to_path_buf(),
+        None => std::env::temp_dir(),
+    }
+}
+
+/// Remove the scratch directory and cgroup of the run
+pub fn finish() {
+    if let Some(budget) = BUDGET.get() {
+        if let Some(scratch) = &budget.scratch {
+            let _ = std::fs::remove_dir_all(scratch);
+        }
+        if let Some(cgroup) = &budget.cgroup {
+            let _ = std::fs::remove_dir(cgroup);
+        }
+    }
+}
+
+/// Fail when a budget was exceeded and the configuration says to abort
+pub fn check() -> Result<()> {
+    let budget = match BUDGET.get() {
+        Some(budget) if budget.limits.on_exceeded == "abort" => budget,
+        _ => return Ok(()),
+    };
+    let exceeded = EXCEEDED.lock().map(|exceeded| exceeded.clone()).unwrap_or_default();
+    match exceeded.first() {
+        Some(reason) => Err(anyhow!(
+            "Run aborted, resource budget exceeded: {} (budget.on_exceeded = {})",
+            reason,
+            budget.limits.on_exceeded
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Run an external tool within the budget and `timeout`, killing it when it goes over either
+///
+/// Errors for exceeded budgets start with "budget exceeded", so completeness
+/// records them apart from tool errors.
+pub async fn run(command: &mut Command, timeout: Option<Duration>) -> Result<Output> {
+    let budget = BUDGET.get();
+    let disk_limit = budget.filter(|budget| budget.scratch.is_some()).map(|budget| budget.limits.max_disk_mb);
+    if let (Some(budget), Some(limit)) = (budget, disk_limit) {
+        let used = disk_used(budget);
+        if used > limit * MB {
+            return Err(exceeded(format!("disk, {} MB of temporary files with {} MB allowed", used / MB, limit)));
+        }
+    }
+
+    // Each tool gets its own temporary directory, so what a stopped tool leaves behind goes with it
+    let tool_scratch = budget.and_then(|budget| budget.scratch.as_ref()).map(|scratch| {
+        let tool_scratch = scratch.join(format!("tool-{}", TOOL_RUNS.fetch_add(1, Ordering::Relaxed)));
+        let _ = std::fs::create_dir_all(&tool_scratch);
+        for variable in ["TMPDIR", "TMP", "TEMP"] {
+            command.env(variable, &tool_scratch);
+        }
+        tool_scratch
+    });
+    let output = run_within(command, budget, disk_limit, timeout).await;
+    if let Some(tool_scratch) = tool_scratch {
+        let _ = std::fs::remove_dir_all(tool_scratch);
+    }
+    output
+}
+
+async fn run_within(
+    command: &mut Command,
+    budget: Option<&'static Budget>,
+    disk_limit: Option<u64>,
+    timeout: Option<Duration>,
+) -> Result<Output> {
+    command
+        .kill_on_drop(true)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    #[cfg(unix)]
+    if let Some(budget) = budget {
+        limit_process(command, budget);
+    }
+    let child = command.spawn().map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            anyhow::Error::new(ToolError::ToolNotFound { program: command.as_std().get_program().to_string_lossy().into_owned() })
+        }
+        _ => anyhow::Error::new(e),
+    })?;
+
+    let oom_kills_before = budget.and_then(oom_kills);
+    if let (Some(budget), Some(pid)) = (budget, child.id()) {
+        if let Some(cgroup) = &budget.cgroup {
+            if let Err(e) = std::fs::write(cgroup.join("cgroup.procs"), pid.to_string()) {
+                log::debug!("Could not move tool {} into {}: {}", pid, cgroup.display(), e);
+            }
+        }
+    }
+
+    // Dropping the output future kills the tool, so the other branches stop it too
+    let output = child.wait_with_output();
+    let deadline = async {
+        match timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+    let disk_watch = async {
+        match (budget, disk_limit) {
+            (Some(budget), Some(limit)) => loop {
+                tokio::time::sleep(DISK_POLL_INTERVAL).await;
+                let used = disk_used(budget);
+                if used > limit * MB {
+                    return used;
+                }
+            },
+            _ => std::future::pending().await,
+        }
+    };
+
+    let output = tokio::select! {
+        output = output => output?,
+        _ = deadline => return Err(ToolError::ToolTimeout { seconds: timeout.unwrap_or_default().as_secs() }.into()),
+        used = disk_watch => {
+            return Err(exceeded(format!(
+                "disk, {} MB of temporary files with {} MB allowed",
+                used / MB,
+                disk_limit.unwrap_or_default()
+            )));
+        }
+    };
+
+    if let Some(budget) = budget.filter(|_| !output.status.success()) {
+        if let Some(reason) = over_budget(budget, &output, oom_kills_before) {
+            return Err(exceeded(reason));
+        }
+    }
+    Ok(output)
+}
+
+const MB: u64 = 1024 * 1024;
+
+/// Record and announce an exceeded budget
+fn exceeded(reason: String) -> anyhow::Error {
+    let mut exceeded = EXCEEDED.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if !exceeded.contains(&reason) {
+        status!("  ⛔ Resource budget exceeded: {}", reason);
+        exceeded.push(reason.clone());
+    }
+    anyhow!("budget exceeded: {}", reason)
+}
+
+/// Which budget, if any, made a failed tool stop
+fn over_budget(budget: &Budget, output: &Output, oom_kills_before: Option<u64>) -> Option<String> {
+    let limits = &budget.limits;
+    let memory = || format!("memory, {} MB allowed", limits.max_memory_mb);
+    if limits.max_memory_mb > 0 {
+        if let (Some(before), Some(after)) = (oom_kills_before, oom_kills(budget)) {
+            if after > before {
+                return Some(memory());
+            }
+        }
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        // SIGKILL comes from the hard limit, for tools that ignore SIGXCPU
+        let signal = output.status.signal();
+        if limits.max_cpu_secs > 0 && (signal == Some(libc::SIGXCPU) || signal == Some(libc::SIGKILL)) {
+            return Some(format!("CPU, {}s of CPU time allowed", limits.max_cpu_secs));
+        }
+    }
+    if limits.max_memory_mb == 0 {
+        return None;
+    }
+    // Without a cgroup the tool only sees its allocations fail
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let out_of_memory = ["MemoryError", "out of memory", "Cannot allocate memory", "heap exhausted", "std::bad_alloc"]
+        .iter()
+        .any(|message| stderr.contains(message));
+    out_of_memory.then(memory)
+}
+
+/// Bytes under the scratch directory
+fn disk_used(budget: &Budget) -> u64 {
+    budget.scratch.as_deref().map_or(0, |scratch| {
+        walkdir::WalkDir::new(scratch)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.metadata().ok())
+            .filter(|metadata| metadata.is_file())
+            .map(|metadata| metadata.len())
+            .sum()
+    })
+}
+
+/// Limit the tool's CPU time, and its memory when no cgroup does
+#[cfg(unix)]
+fn limit_process(command: &mut Command, budget: &Budget) {
+    let cpu_secs = budget.limits.max_cpu_secs;
+    let memory_bytes = match budget.cgroup {
+        Some(_) => 0,
+        None => budget.limits.max_memory_mb * MB,
+    };
+    if cpu_secs == 0 && memory_bytes == 0 {
+        return;
+    }
+
+    // SAFETY: the closure only calls setrlimit, which is async-signal-safe
+    unsafe {
+        command.pre_exec(move || {
+            if cpu_secs > 0 {
+                // The hard limit a little later kills tools that ignore SIGXCPU
+                let limit = libc::rlimit { rlim_cur: cpu_secs as libc::rlim_t, rlim_max: (cpu_secs + 5) as libc::rlim_t };
+                libc::setrlimit(libc::RLIMIT_CPU, &limit);
+            }
+            if memory_bytes > 0 {
+                // Data rather than address space, which runtimes such as GHC reserve by the terabyte
+                let limit = libc::rlimit { rlim_cur: memory_bytes as libc::rlim_t, rlim_max: memory_bytes as libc::rlim_t };
+                libc::setrlimit(libc::RLIMIT_DATA, &limit);
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Child cgroup of ours limited to `memory_mb`, if cgroup v2 lets us create one
+#[cfg(target_os = "linux")]
+fn create_cgroup(memory_mb: u64) -> Option<PathBuf> {
+    let membership = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+    let own = membership.lines().find_map(|line| line.strip_prefix("0::"))?;
+    let parent = std::path::Path::new("/sys/fs/cgroup").join(own.trim_start_matches('/'));
+    let controllers = std::fs::read_to_string(parent.join("cgroup.subtree_control")).ok()?;
+    if !controllers.split_whitespace().any(|controller| controller == "memory") {
+        log::debug!("No memory controller delegated to {}, limiting tools per process", parent.display());
+        return None;
+    }
+
+    let cgroup = parent.join(format!("securechain-{}", std::process::id()));
+    let created = std::fs::create_dir_all(&cgroup)
+        .and_then(|()| std::fs::write(cgroup.join("memory.max"), (memory_mb * MB).to_string()));
+    match created {
+        Ok(()) => {
+            // Without swap the limit would only slow the tool down
+            let _ = std::fs::write(cgroup.join("memory.swap.max"), "0");
+            Some(cgroup)
+        }
+        Err(e) => {
+            log::debug!("Could not create cgroup {}: {}, limiting tools per process", cgroup.display(), e);
+            let _ = std::fs::remove_dir(&cgroup);
+            None
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn create_cgroup(_memory_mb: u64) -> Option<PathBuf> {
+    None
+}
+
+/// Processes the cgroup's memory limit has killed so far
+fn oom_kills(budget: &Budget) -> Option<u64> {
+    let events = std::fs::read_to_string(budget.cgroup.as_ref()?.join("memory.events")).ok()?;
+    events.lines().find_map(|line| line.strip_prefix("oom_kill ")?.trim().parse().ok())
+}
@@ -0,0 +1,130 @@
+//! On-disk analysis cache
+//!
+//! Keeps the analysis of each contract between runs, so analysing a contract
+//! that has not changed again is instant. Entries are keyed by a hash of the
+//! source, the analysis options and the version and command line of every
+//! tool, so upgrading Slither or changing its detectors analyses afresh
+//! instead of reusing stale findings. Entries older than `analysis.cache_ttl`
+//! seconds are ignored; `securechain purge` removes them as the retention
+//! policy says and `securechain cache clear` removes all of them.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::utils::abi;
+use crate::utils::artifact;
+use crate::utils::config::Config;
+
+/// Version of the entry layout, bumped on incompatible changes
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// One cached value with the time it was stored
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry<T> {
+    schema_version: u32,
+    created_at: DateTime<Utc>,
+    value: T,
+}
+
+/// What `clear` removed
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Cleared {
+    pub entries: usize,
+    pub bytes: u64,
+}
+
+/// Cache directory with an expiry
+pub struct DiskCache {
+    dir: PathBuf,
+    /// Entries older than this many seconds are ignored, 0 keeps them until cleared
+    ttl: u64,
+}
+
+impl DiskCache {
+    /// Analysis cache of `config`, `None` when `analysis.cache_results` is off
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if !config.analysis.cache_results {
+            return None;
+        }
+        Some(Self { dir: cache_dir(config), ttl: config.analysis.cache_ttl })
+    }
+
+    /// Value stored under `key`, unless it is missing, expired or unreadable
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let content = std::fs::read_to_string(self.path(key)).ok()?;
+        let entry: Entry<T> = match serde_json::from_str(&content) {
+            Ok(entry) => entry,
+            Err(e) => {
+                log::debug!("Ignoring unreadable cache entry {}: {}", key, e);
+                return None;
+            }
+        };
+        if entry.schema_version != SCHEMA_VERSION {
+            return None;
+        }
+        let age = Utc::now().signed_duration_since(entry.created_at).num_seconds();
+        if self.ttl > 0 && age > self.ttl as i64 {
+            return None;
+        }
+        Some(entry.value)
+    }
+
+    /// Store `value` under `key`
+    pub fn put<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let entry = Entry { schema_version: SCHEMA_VERSION, created_at: Utc::now(), value };
+        artifact::write_atomic(self.path(key), serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+/// Cache key of `parts`, which are hashed in order
+pub fn key(parts: &[&str]) -> String {
+    let mut data = Vec::new();
+    for part in parts {
+        data.extend_from_slice(part.as_bytes());
+        data.push(0);
+    }
+    abi::encode_hex(&abi::keccak256(&data))
+}
+
+/// Directory holding the analysis cache, below `general.cache_dir`
+pub fn cache_dir(config: &Config) -> PathBuf {
+    expand_home(&config.general.cache_dir).join("analysis")
+}
+
+/// Remove every cached analysis
+pub fn clear(config: &Config) -> Result<Cleared> {
+    let dir = cache_dir(config);
+    let mut cleared = Cleared::default();
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(cleared),
+        Err(e) => return Err(e.into()),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+            continue;
+        }
+        cleared.bytes += std::fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+        std::fs::remove_file(&path)?;
+        cleared.entries += 1;
+    }
+    Ok(cleared)
+}
+
+/// `path` with a leading `~` replaced by the home directory, as configuration files write it
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
@@ -4,7 +4,7 @@
 //! from various sources including files, environment variables, and CLI arguments.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 
 use crate::utils::error::{BugForgeXError, Result};
@@ -29,6 +29,22 @@ pub struct Config {
     
     /// Report generation settings
     pub reporting: ReportingConfig,
+
+    /// Resource limits of a run and the tools it starts
+    #[serde(default)]
+    pub budget: BudgetConfig,
+
+    /// How long caches, history and leftover temporary files are kept
+    #[serde(default)]
+    pub retention: RetentionConfig,
+
+    /// Webhooks told about outcomes needing attention outside the report
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+
+    /// Tenants of `securechain serve` when business units share one server
+    #[serde(default)]
+    pub server: ServerConfig,
 }
 
 /// General application configuration
@@ -37,7 +53,7 @@ pub struct GeneralConfig {
     /// Application log level
     pub log_level: String,
     
-    /// Maximum concurrent analysis tasks
+    /// Maximum concurrent analysis tasks, and contracts of one run analysed at once
     pub max_concurrent_tasks: usize,
     
     /// Default output directory
@@ -45,18 +61,46 @@ pub struct GeneralConfig {
     
     /// Cache directory for downloaded contracts
     pub cache_dir: PathBuf,
+
+    /// Directory for run history, usage metrics and the AI upload log
+    #[serde(default = "default_data_dir")]
+    pub data_dir: PathBuf,
     
     /// Enable colored output
     pub colored_output: bool,
     
     /// Default timeout for operations (in seconds)
     pub default_timeout: u64,
+
+    /// Keep a local usage summary for `securechain stats`; never sent anywhere
+    #[serde(default)]
+    pub metrics_enabled: bool,
+
+    /// Deep audits the daemon runs at once, within `max_concurrent_tasks`
+    #[serde(default = "default_max_deep_tasks")]
+    pub max_deep_tasks: usize,
+
+    /// Analyses the daemon holds waiting before it turns new ones away
+    #[serde(default = "default_max_queued_jobs")]
+    pub max_queued_jobs: usize,
+}
+
+fn default_data_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")).join(".config/bugforgex")
+}
+
+fn default_max_deep_tasks() -> usize {
+    1
+}
+
+fn default_max_queued_jobs() -> usize {
+    32
 }
 
 /// AI assistant configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AiConfig {
-    /// AI backend to use (openai, anthropic, local)
+    /// AI backend to use (openai, anthropic, local, azure, gemini, openrouter)
     pub backend: String,
     
     /// OpenAI configuration
@@ -67,6 +111,22 @@ pub struct AiConfig {
     
     /// Local LLM configuration
     pub local: LocalLlmConfig,
+
+    /// Azure OpenAI configuration
+    #[serde(default)]
+    pub azure: AzureOpenAiConfig,
+
+    /// Google Gemini configuration
+    #[serde(default)]
+    pub gemini: GeminiConfig,
+
+    /// OpenRouter configuration
+    #[serde(default)]
+    pub openrouter: OpenRouterConfig,
+
+    /// Knowledge base of past vulnerabilities retrieved into analysis prompts
+    #[serde(default)]
+    pub knowledge: KnowledgeConfig,
     
     /// Enable AI-powered analysis by default
     pub enabled_by_default: bool,
@@ -76,6 +136,78 @@ pub struct AiConfig {
     
     /// Temperature for creative analysis
     pub temperature: f64,
+
+    /// Similarity (0.0-1.0) above which contracts share one AI probe query
+    #[serde(default = "default_probe_cluster_threshold")]
+    pub probe_cluster_threshold: f64,
+
+    /// Backends tried in order when `backend` does not answer its health check
+    #[serde(default)]
+    pub fallback_backends: Vec<String>,
+
+    /// Health check retries per backend before moving on to the next
+    #[serde(default = "default_ai_retries")]
+    pub retries: u32,
+
+    /// Seconds a backend health check may take
+    #[serde(default = "default_ai_health_timeout")]
+    pub health_timeout: u64,
+
+    /// Append every prompt sent to a provider to the upload audit log (`securechain ai-log`)
+    #[serde(default = "default_upload_log")]
+    pub upload_log: bool,
+
+    /// What is removed from code before it is sent: none, or comments
+    #[serde(default = "default_redaction_profile")]
+    pub redaction_profile: String,
+
+    /// Tokens of code one prompt may carry; larger contracts are analyzed in chunks
+    #[serde(default = "default_context_tokens")]
+    pub context_tokens: usize,
+
+    /// Times a response that is not valid findings JSON is sent back to the model to fix
+    #[serde(default = "default_response_retries")]
+    pub response_retries: u32,
+
+    /// Estimated US dollars the AI requests of one run may cost; 0 for no limit
+    #[serde(default)]
+    pub max_cost: f64,
+
+    /// Echo answers to the terminal token by token as the provider streams them
+    #[serde(default = "default_ai_stream")]
+    pub stream: bool,
+}
+
+fn default_probe_cluster_threshold() -> f64 {
+    0.9
+}
+
+fn default_ai_retries() -> u32 {
+    2
+}
+
+fn default_ai_health_timeout() -> u64 {
+    5
+}
+
+fn default_upload_log() -> bool {
+    true
+}
+
+fn default_redaction_profile() -> String {
+    "none".to_string()
+}
+
+fn default_context_tokens() -> usize {
+    8000
+}
+
+fn default_response_retries() -> u32 {
+    2
+}
+
+fn default_ai_stream() -> bool {
+    true
 }
 
 /// OpenAI API configuration
@@ -92,6 +224,22 @@ pub struct OpenAiConfig {
     
     /// Rate limit (requests per minute)
     pub rate_limit: u32,
+
+    /// US dollars per million prompt tokens
+    #[serde(default = "default_openai_prompt_price")]
+    pub prompt_price: f64,
+
+    /// US dollars per million completion tokens
+    #[serde(default = "default_openai_completion_price")]
+    pub completion_price: f64,
+}
+
+fn default_openai_prompt_price() -> f64 {
+    30.0
+}
+
+fn default_openai_completion_price() -> f64 {
+    60.0
 }
 
 /// Anthropic API configuration
@@ -105,6 +253,149 @@ pub struct AnthropicConfig {
     
     /// Rate limit (requests per minute)
     pub rate_limit: u32,
+
+    /// US dollars per million prompt tokens
+    #[serde(default = "default_anthropic_prompt_price")]
+    pub prompt_price: f64,
+
+    /// US dollars per million completion tokens
+    #[serde(default = "default_anthropic_completion_price")]
+    pub completion_price: f64,
+}
+
+fn default_anthropic_prompt_price() -> f64 {
+    3.0
+}
+
+fn default_anthropic_completion_price() -> f64 {
+    15.0
+}
+
+/// Azure OpenAI configuration, addressed by deployment rather than model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AzureOpenAiConfig {
+    /// Resource endpoint, e.g. `https://my-resource.openai.azure.com`
+    pub endpoint: String,
+
+    /// Deployment prompts are sent to
+    pub deployment: String,
+
+    /// REST API version
+    pub api_version: String,
+
+    /// Model behind the deployment, for the upload log
+    pub model: String,
+
+    /// Rate limit (requests per minute)
+    pub rate_limit: u32,
+
+    /// US dollars per million prompt tokens
+    pub prompt_price: f64,
+
+    /// US dollars per million completion tokens
+    pub completion_price: f64,
+}
+
+impl Default for AzureOpenAiConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: String::new(),
+            deployment: String::new(),
+            api_version: "2024-02-01".to_string(),
+            model: "gpt-4o".to_string(),
+            rate_limit: 60,
+            prompt_price: 5.0,
+            completion_price: 15.0,
+        }
+    }
+}
+
+/// Google Gemini configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeminiConfig {
+    /// API endpoint URL
+    pub api_url: String,
+
+    /// Model to use for analysis
+    pub model: String,
+
+    /// Rate limit (requests per minute)
+    pub rate_limit: u32,
+
+    /// US dollars per million prompt tokens
+    pub prompt_price: f64,
+
+    /// US dollars per million completion tokens
+    pub completion_price: f64,
+}
+
+impl Default for GeminiConfig {
+    fn default() -> Self {
+        Self {
+            api_url: "https://generativelanguage.googleapis.com/v1beta".to_string(),
+            model: "gemini-1.5-pro".to_string(),
+            rate_limit: 60,
+            prompt_price: 3.5,
+            completion_price: 10.5,
+        }
+    }
+}
+
+/// OpenRouter configuration, one API in front of many providers' models
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenRouterConfig {
+    /// API endpoint URL
+    pub api_url: String,
+
+    /// Model to use for analysis, as `provider/model`
+    pub model: String,
+
+    /// Rate limit (requests per minute)
+    pub rate_limit: u32,
+
+    /// US dollars per million prompt tokens, as OpenRouter lists the model
+    pub prompt_price: f64,
+
+    /// US dollars per million completion tokens, as OpenRouter lists the model
+    pub completion_price: f64,
+}
+
+impl Default for OpenRouterConfig {
+    fn default() -> Self {
+        Self {
+            api_url: "https://openrouter.ai/api/v1".to_string(),
+            model: "openai/gpt-4o".to_string(),
+            rate_limit: 60,
+            prompt_price: 5.0,
+            completion_price: 15.0,
+        }
+    }
+}
+
+/// Knowledge base configuration: which embeddings rank the corpus, and how much of it a prompt carries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KnowledgeConfig {
+    /// Add the closest SWC entries and past exploits to each analysis prompt
+    pub enabled: bool,
+
+    /// Backend computing the embeddings (local, openai, azure, gemini)
+    pub backend: String,
+
+    /// Embedding model, or on Azure the embedding deployment
+    pub model: String,
+
+    /// Entries added to a prompt
+    pub entries: usize,
+}
+
+impl Default for KnowledgeConfig {
+    fn default() -> Self {
+        Self { enabled: false, backend: "local".to_string(), model: "nomic-embed-text".to_string(), entries: 3 }
+    }
 }
 
 /// Local LLM configuration
@@ -146,12 +437,47 @@ pub struct NetworkConfig {
     
     /// GitHub API configuration
     pub github: GitHubConfig,
+    
+    /// Function signature lookups for bytecode analysis
+    #[serde(default)]
+    pub signatures: SignatureConfig,
+
+    /// Further EVM chains by name, such as those with only Sourcify or a Blockscout explorer
+    #[serde(default)]
+    pub chains: BTreeMap<String, BlockchainNetworkConfig>,
+}
+
+impl NetworkConfig {
+    /// Settings of an EVM network by name
+    pub fn evm_network(&self, name: &str) -> Option<&BlockchainNetworkConfig> {
+        match name {
+            "ethereum" | "etherscan" => Some(&self.ethereum),
+            "polygon" => Some(&self.polygon),
+            "arbitrum" => Some(&self.arbitrum),
+            "optimism" => Some(&self.optimism),
+            "bsc" => Some(&self.bsc),
+            _ => self.chains.get(name),
+        }
+    }
+
+    /// Mutable settings of an EVM network by name
+    pub fn evm_network_mut(&mut self, name: &str) -> Option<&mut BlockchainNetworkConfig> {
+        match name {
+            "ethereum" | "etherscan" => Some(&mut self.ethereum),
+            "polygon" => Some(&mut self.polygon),
+            "arbitrum" => Some(&mut self.arbitrum),
+            "optimism" => Some(&mut self.optimism),
+            "bsc" => Some(&mut self.bsc),
+            _ => self.chains.get_mut(name),
+        }
+    }
 }
 
 /// Blockchain network configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockchainNetworkConfig {
-    /// Explorer API URL
+    /// Etherscan-compatible explorer API URL, empty when the chain has none
+    #[serde(default)]
     pub explorer_url: String,
     
     /// RPC endpoint URL
@@ -162,6 +488,30 @@ pub struct BlockchainNetworkConfig {
     
     /// Request timeout (seconds)
     pub timeout: u64,
+
+    /// Chain id, which Sourcify files contracts under
+    #[serde(default)]
+    pub chain_id: Option<u64>,
+
+    /// Sourcify server, empty to skip Sourcify
+    #[serde(default = "default_sourcify_url")]
+    pub sourcify_url: String,
+
+    /// Blockscout instance, e.g. `https://eth.blockscout.com`, empty when the chain has none
+    #[serde(default)]
+    pub blockscout_url: String,
+
+    /// Sources `fetch` tries in turn until one has the verified source (etherscan, sourcify, blockscout)
+    #[serde(default = "default_fetch_sources")]
+    pub sources: Vec<String>,
+}
+
+fn default_sourcify_url() -> String {
+    "https://sourcify.dev/server".to_string()
+}
+
+fn default_fetch_sources() -> Vec<String> {
+    vec!["etherscan".to_string(), "sourcify".to_string(), "blockscout".to_string()]
 }
 
 /// Solana-specific network configuration
@@ -193,9 +543,37 @@ pub struct GitHubConfig {
     pub timeout: u64,
 }
 
+/// Function signature database for naming the selectors of unverified contracts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SignatureConfig {
+    /// Look up selectors missing from the bundled list online
+    pub lookup: bool,
+    
+    /// 4byte.directory signature API URL
+    pub api_url: String,
+    
+    /// Request timeout (seconds)
+    pub timeout: u64,
+}
+
+impl Default for SignatureConfig {
+    fn default() -> Self {
+        Self {
+            lookup: true,
+            api_url: "https://www.4byte.directory/api/v1/signatures/".to_string(),
+            timeout: 10,
+        }
+    }
+}
+
 /// Analysis tool configurations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolsConfig {
+    /// Fuzzing backend: echidna or foundry
+    #[serde(default = "default_fuzzer")]
+    pub fuzzer: String,
+    
     /// Slither configuration
     pub slither: SlitherConfig,
     
@@ -205,10 +583,42 @@ pub struct ToolsConfig {
     /// Echidna configuration
     pub echidna: EchidnaConfig,
     
+    /// Foundry (forge) fuzzing configuration
+    #[serde(default)]
+    pub foundry: FoundryConfig,
+
+    /// Anvil node for fork-based dynamic analysis
+    #[serde(default)]
+    pub anvil: AnvilConfig,
+    
+    /// Solhint configuration
+    #[serde(default)]
+    pub solhint: SolhintConfig,
+    
+    /// Semgrep configuration
+    #[serde(default)]
+    pub semgrep: SemgrepConfig,
+    
+    /// Solidity compiler warnings configuration
+    #[serde(default)]
+    pub solc: SolcConfig,
+    
+    /// Vyper compiler configuration
+    #[serde(default)]
+    pub vyper: VyperConfig,
+    
+    /// In-memory execution of wrapping arithmetic
+    #[serde(default)]
+    pub arithmetic: ArithmeticConfig,
+    
     /// Custom tool configurations
     pub custom: HashMap<String, CustomToolConfig>,
 }
 
+fn default_fuzzer() -> String {
+    "echidna".to_string()
+}
+
 /// Slither static analyzer configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlitherConfig {
@@ -226,6 +636,71 @@ pub struct SlitherConfig {
     
     /// Timeout for analysis (seconds)
     pub timeout: u64,
+    
+    /// Import remappings passed as --solc-remaps (e.g. "@openzeppelin/=lib/openzeppelin-contracts/")
+    #[serde(default)]
+    pub remappings: Vec<String>,
+    
+    /// Framework compiling the project (hardhat, foundry, ...); empty to compile each file on its own
+    #[serde(default)]
+    pub framework: String,
+    
+    /// Triage database written by `slither --triage-mode`, whose hidden results stay hidden
+    #[serde(default)]
+    pub triage_database: Option<PathBuf>,
+}
+
+/// Frameworks Slither can compile a project with, besides compiling each file with solc
+pub const SLITHER_FRAMEWORKS: &[&str] = &["hardhat", "foundry", "truffle", "brownie", "dapp", "waffle", "embark", "etherlime"];
+
+impl Default for SlitherConfig {
+    fn default() -> Self {
+        Self {
+            executable: "slither".to_string(),
+            args: Vec::new(),
+            exclude_detectors: Vec::new(),
+            include_detectors: Vec::new(),
+            timeout: 300,
+            remappings: Vec::new(),
+            framework: String::new(),
+            triage_database: None,
+        }
+    }
+}
+
+impl SlitherConfig {
+    /// Whether Slither compiles the whole project with a framework instead of each file on its own
+    pub fn compiles_project(&self) -> bool {
+        !self.framework.is_empty()
+    }
+
+    /// Arguments after the target: JSON on stdout, the detector selection, the
+    /// remappings, the triage database and the additional arguments
+    pub fn arguments(&self, include: &[String], exclude: &[String]) -> Vec<String> {
+        let mut arguments = vec!["--json".to_string(), "-".to_string()];
+        if !include.is_empty() {
+            arguments.extend(["--detect".to_string(), include.join(",")]);
+        }
+        if !exclude.is_empty() {
+            arguments.extend(["--exclude".to_string(), exclude.join(",")]);
+        }
+        if !self.remappings.is_empty() {
+            arguments.extend(["--solc-remaps".to_string(), self.remappings.join(" ")]);
+        }
+        if let Some(database) = &self.triage_database {
+            arguments.extend(["--triage-database".to_string(), database.to_string_lossy().to_string()]);
+        }
+        let mut extra = self.args.iter();
+        while let Some(arg) = extra.next() {
+            // Findings are read from stdout, so output stays there whatever older configurations asked for
+            if arg == "--json" {
+                extra.next();
+                continue;
+            }
+            arguments.push(arg.clone());
+        }
+        arguments
+    }
 }
 
 /// Mythril symbolic execution configuration
@@ -245,6 +720,36 @@ pub struct MythrilConfig {
     
     /// Solver timeout (seconds)
     pub solver_timeout: u64,
+    
+    /// Number of highest-risk functions analyzed at full depth
+    #[serde(default = "default_mythril_top_functions")]
+    pub top_functions: usize,
+    
+    /// Transaction depth for the remaining functions
+    #[serde(default = "default_mythril_reduced_max_depth")]
+    pub reduced_max_depth: u32,
+}
+
+impl Default for MythrilConfig {
+    fn default() -> Self {
+        Self {
+            executable: "myth".to_string(),
+            args: vec!["analyze".to_string(), "--output".to_string(), "json".to_string()],
+            timeout: 600,
+            max_depth: 22,
+            solver_timeout: 10000,
+            top_functions: default_mythril_top_functions(),
+            reduced_max_depth: default_mythril_reduced_max_depth(),
+        }
+    }
+}
+
+fn default_mythril_top_functions() -> usize {
+    5
+}
+
+fn default_mythril_reduced_max_depth() -> u32 {
+    8
 }
 
 /// Echidna fuzzer configuration
@@ -266,64 +771,555 @@ pub struct EchidnaConfig {
     pub timeout: u64,
 }
 
-/// Custom tool configuration
+/// Foundry fuzzing configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FoundryConfig {
+    /// forge executable path
+    pub executable: String,
+    
+    /// Runs per fuzz test (--fuzz-runs)
+    pub fuzz_runs: u32,
+    
+    /// Call sequences per invariant test
+    pub invariant_runs: u32,
+    
+    /// Calls per invariant sequence
+    pub invariant_depth: u32,
+    
+    /// Timeout for the test run (seconds)
+    pub timeout: u64,
+
+    /// forge-std checkout PoCs are verified with; empty to use the `lib/forge-std` of the audited project
+    pub forge_std: String,
+
+    /// Measure line coverage of the generated suite with `forge coverage` after the fuzz run
+    pub coverage: bool,
+}
+
+impl Default for FoundryConfig {
+    fn default() -> Self {
+        Self {
+            executable: "forge".to_string(),
+            fuzz_runs: 256,
+            invariant_runs: 64,
+            invariant_depth: 32,
+            timeout: 600,
+            forge_std: String::new(),
+            coverage: true,
+        }
+    }
+}
+
+/// Anvil configuration for `analyze --simulate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnvilConfig {
+    /// anvil executable path
+    pub executable: String,
+
+    /// Seconds to wait for the node, and the fork it fetches, to answer
+    pub startup_timeout: u64,
+}
+
+impl Default for AnvilConfig {
+    fn default() -> Self {
+        Self {
+            executable: "anvil".to_string(),
+            startup_timeout: 30,
+        }
+    }
+}
+
+/// Solhint linter configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SolhintConfig {
+    /// Run Solhint during EVM analysis
+    pub enabled: bool,
+    
+    /// Solhint executable path
+    pub executable: String,
+    
+    /// Rule packs to extend (e.g. "solhint:recommended", "solhint:all")
+    pub rule_packs: Vec<String>,
+    
+    /// Existing .solhint.json to use instead of the rule packs
+    pub config_file: Option<PathBuf>,
+    
+    /// Timeout for analysis (seconds)
+    pub timeout: u64,
+}
+
+impl Default for SolhintConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            executable: "solhint".to_string(),
+            rule_packs: vec!["solhint:recommended".to_string()],
+            config_file: None,
+            timeout: 120,
+        }
+    }
+}
+
+/// Semgrep configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SemgrepConfig {
+    /// Run Semgrep during EVM analysis
+    pub enabled: bool,
+    
+    /// Semgrep executable path
+    pub executable: String,
+    
+    /// Rule packs or rule files passed as --config
+    pub rule_packs: Vec<String>,
+    
+    /// Timeout for analysis (seconds)
+    pub timeout: u64,
+}
+
+impl Default for SemgrepConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            executable: "semgrep".to_string(),
+            rule_packs: vec!["p/smart-contracts".to_string()],
+            timeout: 300,
+        }
+    }
+}
+
+/// Solidity compiler configuration for warning ingestion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SolcConfig {
+    /// Compile during EVM analysis and report compiler warnings
+    pub enabled: bool,
+    
+    /// solc executable path
+    pub executable: String,
+    
+    /// Timeout for compilation (seconds)
+    pub timeout: u64,
+
+    /// Take functions, modifiers and inheritance from solc's AST instead of
+    /// regular expressions, falling back to them when solc cannot parse a source
+    pub ast: bool,
+}
+
+impl Default for SolcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            executable: "solc".to_string(),
+            timeout: 120,
+            ast: false,
+        }
+    }
+}
+
+/// Vyper compiler configuration for Vyper sources
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VyperConfig {
+    /// Compile Vyper sources during EVM analysis and report compiler warnings
+    pub enabled: bool,
+    
+    /// vyper executable path
+    pub executable: String,
+    
+    /// Timeout for compilation (seconds)
+    pub timeout: u64,
+}
+
+impl Default for VyperConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            executable: "vyper".to_string(),
+            timeout: 120,
+        }
+    }
+}
+
+/// Execution of `unchecked` and pre-0.8 arithmetic in an in-memory EVM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArithmeticConfig {
+    /// Execute wrapping arithmetic on standard and deep runs and report the
+    /// functions where boundary inputs make it overflow
+    pub enabled: bool,
+    
+    /// Boundary input combinations tried per function and round
+    pub max_calls: usize,
+    
+    /// Gas limit of each transaction, which bounds loops over the inputs
+    pub gas_limit: u64,
+}
+
+impl Default for ArithmeticConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_calls: 64,
+            gas_limit: 30_000_000,
+        }
+    }
+}
+
+/// Custom tool configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomToolConfig {
+    /// Tool executable path
+    pub executable: String,
+    
+    /// Command line arguments template
+    pub args_template: String,
+    
+    /// Output format (json, text, xml)
+    pub output_format: String,
+    
+    /// Timeout (seconds)
+    pub timeout: u64,
+}
+
+/// Analysis configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisConfig {
+    /// Default analysis depth
+    pub default_depth: String,
+    
+    /// Enable parallel analysis
+    pub parallel_analysis: bool,
+    
+    /// Maximum analysis threads
+    pub max_threads: usize,
+    
+    /// Cache analysis results
+    pub cache_results: bool,
+    
+    /// Cache TTL (seconds), 0 keeps entries until `cache clear`
+    pub cache_ttl: u64,
+    
+    /// Minimum confidence threshold for reporting
+    pub min_confidence: f64,
+    
+    /// Vulnerability severity filters
+    pub severity_filters: Vec<String>,
+
+    /// Lowest severity that makes the pre-commit hook block a commit
+    #[serde(default = "default_hook_fail_on")]
+    pub hook_fail_on: String,
+
+    /// Lowest severity of a finding new since the `--baseline` that fails the run
+    #[serde(default = "default_hook_fail_on")]
+    pub baseline_fail_on: String,
+
+    /// Lowest severity that makes the `ci github` check fail
+    #[serde(default = "default_hook_fail_on")]
+    pub ci_fail_on: String,
+
+    /// Exit code of a run that fails its baseline check, 0 to only report
+    #[serde(default = "default_baseline_exit_code")]
+    pub baseline_exit_code: i32,
+
+    /// Built-in detectors whose findings are dropped, by catalog id
+    #[serde(default)]
+    pub disabled_detectors: Vec<String>,
+}
+
+fn default_hook_fail_on() -> String {
+    "High".to_string()
+}
+
+fn default_baseline_exit_code() -> i32 {
+    1
+}
+
+fn default_cache_ttl() -> u64 {
+    3600
+}
+
+/// Report generation configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportingConfig {
+    /// Default output format
+    pub default_format: String,
+    
+    /// Include executive summary by default
+    pub include_summary: bool,
+    
+    /// Template directory
+    pub template_dir: PathBuf,
+    
+    /// Custom report templates
+    pub custom_templates: HashMap<String, String>,
+    
+    /// Maximum report size (MB)
+    pub max_report_size: u64,
+
+    /// Standard the audit checklist follows: `scsvs` or a TOML/JSON file of requirements
+    #[serde(default = "default_checklist_standard")]
+    pub checklist_standard: String,
+
+    /// Architecture diagram notation: `mermaid` or `graphviz`
+    #[serde(default = "default_architecture_format")]
+    pub architecture_format: String,
+
+    /// Report languages: built-in codes (`en`, `ko`) or paths of TOML catalogs; two make reports bilingual
+    #[serde(default = "default_report_languages")]
+    pub languages: Vec<String>,
+
+    /// How bilingual reports place each section's two versions: `sequential` or `side-by-side`
+    #[serde(default = "default_bilingual_layout")]
+    pub bilingual_layout: String,
+
+    /// CSS font family of each language in HTML and PDF reports, by language code
+    #[serde(default)]
+    pub fonts: HashMap<String, String>,
+
+    /// Severities whose findings get a PoC
+    #[serde(default = "default_poc_severities")]
+    pub poc_severities: Vec<String>,
+
+    /// Categories whose findings get a PoC (`Reentrancy`, `AccessControl`, ...), empty for all
+    #[serde(default)]
+    pub poc_categories: Vec<String>,
+
+    /// Replace every PoC with reproduction steps, for clients that forbid exploit code in deliverables
+    #[serde(default)]
+    pub no_exploit_code: bool,
+
+    /// Client the deliverables are for, as `{client}` in file names
+    #[serde(default)]
+    pub client: String,
+
+    /// Project name for `{project}` in file names; empty for the audited project's title
+    #[serde(default)]
+    pub project: String,
+
+    /// Deliverable version, as `{version}` in file names
+    #[serde(default = "default_report_version")]
+    pub report_version: String,
+
+    /// File name of deliverables without extension, e.g. `{client}-SC-{year}_{type}_v{version}`; empty for built-in names
+    #[serde(default)]
+    pub filename_pattern: String,
+
+    /// How findings are numbered in every report, e.g. `SC-{n:3}`; `{severity}` numbers within each severity
+    #[serde(default = "default_finding_ids")]
+    pub finding_ids: String,
+
+    /// Commands and WASI filters run after each report is written, by name
+    #[serde(default)]
+    pub hooks: BTreeMap<String, ReportHook>,
+
+    /// Runtime WASI hooks run with, as `<runtime> run <module>`
+    #[serde(default = "default_wasm_runtime")]
+    pub wasm_runtime: String,
+}
+
+/// A step run after a report is written, with the report JSON on stdin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportHook {
+    /// Shell command
+    pub command: Option<String>,
+
+    /// WASI module, run with `reporting.wasm_runtime`
+    pub wasm: Option<PathBuf>,
+
+    /// File the hook's output is saved to, relative to the report; `{stem}` is
+    /// the report's file name without extension. Empty discards the output.
+    pub output: String,
+
+    /// Seconds the hook may run before it is stopped
+    pub timeout: u64,
+}
+
+impl Default for ReportHook {
+    fn default() -> Self {
+        Self {
+            command: None,
+            wasm: None,
+            output: String::new(),
+            timeout: 60,
+        }
+    }
+}
+
+fn default_checklist_standard() -> String {
+    "scsvs".to_string()
+}
+
+fn default_architecture_format() -> String {
+    "mermaid".to_string()
+}
+
+fn default_report_languages() -> Vec<String> {
+    vec!["en".to_string()]
+}
+
+fn default_poc_severities() -> Vec<String> {
+    vec!["Critical".to_string(), "High".to_string()]
+}
+
+fn default_bilingual_layout() -> String {
+    "sequential".to_string()
+}
+
+fn default_report_version() -> String {
+    "1.0".to_string()
+}
+
+fn default_finding_ids() -> String {
+    "{severity}.{n}".to_string()
+}
+
+fn default_wasm_runtime() -> String {
+    "wasmtime".to_string()
+}
+
+/// Resource budget of a run; 0 leaves a resource unlimited
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetConfig {
+    /// Disk used by temporary files, compiler artifacts and fuzzing corpora (MB)
+    #[serde(default)]
+    pub max_disk_mb: u64,
+
+    /// Memory of each external tool, its child processes included (MB)
+    #[serde(default)]
+    pub max_memory_mb: u64,
+
+    /// CPU time of each external tool (seconds)
+    #[serde(default)]
+    pub max_cpu_secs: u64,
+
+    /// What happens when a budget is exceeded: `degrade` skips the tool, `abort` stops the run
+    #[serde(default = "default_budget_on_exceeded")]
+    pub on_exceeded: String,
+}
+
+fn default_budget_on_exceeded() -> String {
+    "degrade".to_string()
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self { max_disk_mb: 0, max_memory_mb: 0, max_cpu_secs: 0, on_exceeded: default_budget_on_exceeded() }
+    }
+}
+
+/// Webhooks notifications are posted to
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CustomToolConfig {
-    /// Tool executable path
-    pub executable: String,
-    
-    /// Command line arguments template
-    pub args_template: String,
-    
-    /// Output format (json, text, xml)
-    pub output_format: String,
-    
-    /// Timeout (seconds)
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// URLs each notification is posted to as JSON, Slack and Mattermost incoming webhooks included
+    pub webhooks: Vec<String>,
+
+    /// Request timeout (seconds)
     pub timeout: u64,
 }
 
-/// Analysis configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AnalysisConfig {
-    /// Default analysis depth
-    pub default_depth: String,
-    
-    /// Enable parallel analysis
-    pub parallel_analysis: bool,
-    
-    /// Maximum analysis threads
-    pub max_threads: usize,
-    
-    /// Cache analysis results
-    pub cache_results: bool,
-    
-    /// Cache TTL (seconds)
-    pub cache_ttl: u64,
-    
-    /// Minimum confidence threshold for reporting
-    pub min_confidence: f64,
-    
-    /// Vulnerability severity filters
-    pub severity_filters: Vec<String>,
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self { webhooks: Vec::new(), timeout: 10 }
+    }
 }
 
-/// Report generation configuration
+/// Settings of `securechain serve`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Tenants by name; when any are set, requests carry a tenant's key instead of the server token
+    pub tenants: BTreeMap<String, TenantConfig>,
+}
+
+/// One tenant of a shared server
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TenantConfig {
+    /// Environment variable holding the tenant's API key
+    pub key_env: String,
+
+    /// Directory below `general.output_dir/tenants` its runs and caches are kept in; the tenant name when empty
+    pub storage_prefix: String,
+
+    /// Jobs it may submit a minute; 0 is unlimited
+    pub rate_limit: u32,
+
+    /// Estimated AI spend allowed each calendar month (USD); 0 is unlimited
+    pub ai_budget: f64,
+
+    /// Webhooks its failed scheduled scans are posted to, instead of `notifications.webhooks`
+    pub webhooks: Vec<String>,
+}
+
+/// What `securechain purge` removes; 0 keeps a store regardless of age or size
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ReportingConfig {
-    /// Default output format
-    pub default_format: String,
-    
-    /// Include executive summary by default
-    pub include_summary: bool,
-    
-    /// Template directory
-    pub template_dir: PathBuf,
-    
-    /// Custom report templates
-    pub custom_templates: HashMap<String, String>,
-    
-    /// Maximum report size (MB)
-    pub max_report_size: u64,
+pub struct RetentionConfig {
+    /// Cached contract analyses not written for this many days
+    #[serde(default = "default_cache_max_age_days")]
+    pub cache_max_age_days: u64,
+
+    /// Size the analysis cache is trimmed to, oldest entries first (MB)
+    #[serde(default = "default_cache_max_size_mb")]
+    pub cache_max_size_mb: u64,
+
+    /// Run history rows older than this many days
+    #[serde(default = "default_history_max_age_days")]
+    pub history_max_age_days: u64,
+
+    /// Contract copies, corpora and run directories left in the temporary directory for this many hours
+    #[serde(default = "default_temp_max_age_hours")]
+    pub temp_max_age_hours: u64,
+
+    /// Size leftover temporary files are trimmed to, oldest first (MB)
+    #[serde(default = "default_temp_max_size_mb")]
+    pub temp_max_size_mb: u64,
+
+    /// Apply the policy when a command starts, at most once a day
+    #[serde(default = "default_enforce_on_startup")]
+    pub enforce_on_startup: bool,
+}
+
+fn default_cache_max_age_days() -> u64 {
+    30
+}
+
+fn default_cache_max_size_mb() -> u64 {
+    2048
+}
+
+fn default_history_max_age_days() -> u64 {
+    365
+}
+
+fn default_temp_max_age_hours() -> u64 {
+    24
+}
+
+fn default_temp_max_size_mb() -> u64 {
+    10240
+}
+
+fn default_enforce_on_startup() -> bool {
+    true
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            cache_max_age_days: default_cache_max_age_days(),
+            cache_max_size_mb: default_cache_max_size_mb(),
+            history_max_age_days: default_history_max_age_days(),
+            temp_max_age_hours: default_temp_max_age_hours(),
+            temp_max_size_mb: default_temp_max_size_mb(),
+            enforce_on_startup: default_enforce_on_startup(),
+        }
+    }
 }
 
 impl Config {
@@ -376,7 +1372,7 @@ impl Config {
                 .map_err(|e| BugForgeXError::config(format!("Failed to create config directory: {}", e)))?;
         }
         
-        std::fs::write(path.as_ref(), content)
+        crate::utils::artifact::write_atomic(path.as_ref(), content)
             .map_err(|e| BugForgeXError::config(format!("Failed to write config file: {}", e)))?;
         
         Ok(())
@@ -427,28 +1423,411 @@ impl Config {
         if other.general.log_level != self.general.log_level && other.general.log_level != "info" {
             self.general.log_level = other.general.log_level;
         }
+        if other.general.metrics_enabled {
+            self.general.metrics_enabled = true;
+        }
+        if other.general.max_concurrent_tasks != 4 {
+            self.general.max_concurrent_tasks = other.general.max_concurrent_tasks;
+        }
+        if other.general.data_dir != default_data_dir() {
+            self.general.data_dir = other.general.data_dir;
+        }
+        if other.general.max_deep_tasks != default_max_deep_tasks() {
+            self.general.max_deep_tasks = other.general.max_deep_tasks;
+        }
+        if other.general.max_queued_jobs != default_max_queued_jobs() {
+            self.general.max_queued_jobs = other.general.max_queued_jobs;
+        }
         
         // Merge AI settings
         if other.ai.backend != "local" {
             self.ai.backend = other.ai.backend;
         }
+        if other.ai.probe_cluster_threshold != default_probe_cluster_threshold() {
+            self.ai.probe_cluster_threshold = other.ai.probe_cluster_threshold;
+        }
+        if !other.ai.fallback_backends.is_empty() {
+            self.ai.fallback_backends = other.ai.fallback_backends;
+        }
+        if other.ai.retries != default_ai_retries() {
+            self.ai.retries = other.ai.retries;
+        }
+        if other.ai.health_timeout != default_ai_health_timeout() {
+            self.ai.health_timeout = other.ai.health_timeout;
+        }
+        if !other.ai.upload_log {
+            self.ai.upload_log = false;
+        }
+        if other.ai.redaction_profile != default_redaction_profile() {
+            self.ai.redaction_profile = other.ai.redaction_profile;
+        }
+        if other.ai.context_tokens != default_context_tokens() {
+            self.ai.context_tokens = other.ai.context_tokens;
+        }
+        if other.ai.response_retries != default_response_retries() {
+            self.ai.response_retries = other.ai.response_retries;
+        }
+        if other.ai.max_cost != 0.0 {
+            self.ai.max_cost = other.ai.max_cost;
+        }
+        if !other.ai.stream {
+            self.ai.stream = false;
+        }
+        if other.ai.openai.prompt_price != default_openai_prompt_price() {
+            self.ai.openai.prompt_price = other.ai.openai.prompt_price;
+        }
+        if other.ai.openai.completion_price != default_openai_completion_price() {
+            self.ai.openai.completion_price = other.ai.openai.completion_price;
+        }
+        if other.ai.anthropic.prompt_price != default_anthropic_prompt_price() {
+            self.ai.anthropic.prompt_price = other.ai.anthropic.prompt_price;
+        }
+        if other.ai.anthropic.completion_price != default_anthropic_completion_price() {
+            self.ai.anthropic.completion_price = other.ai.anthropic.completion_price;
+        }
+        // Models and endpoints of each provider; the newer providers' sections are taken whole
+        let ai_defaults = Config::default().ai;
+        if other.ai.openai.model != ai_defaults.openai.model {
+            self.ai.openai.model = other.ai.openai.model;
+        }
+        if other.ai.openai.api_url != ai_defaults.openai.api_url {
+            self.ai.openai.api_url = other.ai.openai.api_url;
+        }
+        if other.ai.anthropic.model != ai_defaults.anthropic.model {
+            self.ai.anthropic.model = other.ai.anthropic.model;
+        }
+        if other.ai.anthropic.api_url != ai_defaults.anthropic.api_url {
+            self.ai.anthropic.api_url = other.ai.anthropic.api_url;
+        }
+        if other.ai.local.default_model != ai_defaults.local.default_model {
+            self.ai.local.default_model = other.ai.local.default_model;
+        }
+        if other.ai.local.ollama_url != ai_defaults.local.ollama_url {
+            self.ai.local.ollama_url = other.ai.local.ollama_url;
+        }
+        if other.ai.azure != ai_defaults.azure {
+            self.ai.azure = other.ai.azure;
+        }
+        if other.ai.gemini != ai_defaults.gemini {
+            self.ai.gemini = other.ai.gemini;
+        }
+        if other.ai.openrouter != ai_defaults.openrouter {
+            self.ai.openrouter = other.ai.openrouter;
+        }
+        if other.ai.knowledge != ai_defaults.knowledge {
+            self.ai.knowledge = other.ai.knowledge;
+        }
         
         // Merge tool settings
         if !other.tools.slither.args.is_empty() {
             self.tools.slither.args = other.tools.slither.args;
         }
+        if !other.tools.slither.include_detectors.is_empty() {
+            self.tools.slither.include_detectors = other.tools.slither.include_detectors;
+        }
+        if !other.tools.slither.exclude_detectors.is_empty() {
+            self.tools.slither.exclude_detectors = other.tools.slither.exclude_detectors;
+        }
+        if !other.tools.slither.remappings.is_empty() {
+            self.tools.slither.remappings = other.tools.slither.remappings;
+        }
+        if !other.tools.slither.framework.is_empty() {
+            self.tools.slither.framework = other.tools.slither.framework;
+        }
+        if other.tools.slither.triage_database.is_some() {
+            self.tools.slither.triage_database = other.tools.slither.triage_database;
+        }
+        let mythril_defaults = MythrilConfig::default();
+        if other.tools.mythril.timeout != mythril_defaults.timeout {
+            self.tools.mythril.timeout = other.tools.mythril.timeout;
+        }
+        if other.tools.mythril.max_depth != mythril_defaults.max_depth {
+            self.tools.mythril.max_depth = other.tools.mythril.max_depth;
+        }
+        if other.tools.mythril.solver_timeout != mythril_defaults.solver_timeout {
+            self.tools.mythril.solver_timeout = other.tools.mythril.solver_timeout;
+        }
+        if other.tools.mythril.top_functions != default_mythril_top_functions() {
+            self.tools.mythril.top_functions = other.tools.mythril.top_functions;
+        }
+        if other.tools.mythril.reduced_max_depth != default_mythril_reduced_max_depth() {
+            self.tools.mythril.reduced_max_depth = other.tools.mythril.reduced_max_depth;
+        }
+        if other.tools.solc.ast {
+            self.tools.solc.ast = true;
+        }
+        if other.tools.fuzzer != default_fuzzer() {
+            self.tools.fuzzer = other.tools.fuzzer;
+        }
+        let foundry_defaults = FoundryConfig::default();
+        if other.tools.foundry.executable != foundry_defaults.executable {
+            self.tools.foundry.executable = other.tools.foundry.executable;
+        }
+        if other.tools.foundry.fuzz_runs != foundry_defaults.fuzz_runs {
+            self.tools.foundry.fuzz_runs = other.tools.foundry.fuzz_runs;
+        }
+        if other.tools.foundry.invariant_runs != foundry_defaults.invariant_runs {
+            self.tools.foundry.invariant_runs = other.tools.foundry.invariant_runs;
+        }
+        if other.tools.foundry.invariant_depth != foundry_defaults.invariant_depth {
+            self.tools.foundry.invariant_depth = other.tools.foundry.invariant_depth;
+        }
+        if other.tools.foundry.timeout != foundry_defaults.timeout {
+            self.tools.foundry.timeout = other.tools.foundry.timeout;
+        }
+        if other.tools.foundry.forge_std != foundry_defaults.forge_std {
+            self.tools.foundry.forge_std = other.tools.foundry.forge_std;
+        }
+        if other.tools.foundry.coverage != foundry_defaults.coverage {
+            self.tools.foundry.coverage = other.tools.foundry.coverage;
+        }
+        let anvil_defaults = AnvilConfig::default();
+        if other.tools.anvil.executable != anvil_defaults.executable {
+            self.tools.anvil.executable = other.tools.anvil.executable;
+        }
+        if other.tools.anvil.startup_timeout != anvil_defaults.startup_timeout {
+            self.tools.anvil.startup_timeout = other.tools.anvil.startup_timeout;
+        }
+        let signature_defaults = SignatureConfig::default();
+        if other.networks.signatures.lookup != signature_defaults.lookup {
+            self.networks.signatures.lookup = other.networks.signatures.lookup;
+        }
+        if other.networks.signatures.api_url != signature_defaults.api_url {
+            self.networks.signatures.api_url = other.networks.signatures.api_url.clone();
+        }
+        if other.networks.signatures.timeout != signature_defaults.timeout {
+            self.networks.signatures.timeout = other.networks.signatures.timeout;
+        }
+        // Only the fetch sources of the built-in networks are merged; further chains come whole
+        let network_defaults = Config::default().networks;
+        for name in ["ethereum", "polygon", "arbitrum", "optimism", "bsc"] {
+            let (Some(network), Some(theirs), Some(defaults)) = (
+                self.networks.evm_network_mut(name),
+                other.networks.evm_network(name),
+                network_defaults.evm_network(name),
+            ) else {
+                continue;
+            };
+            if theirs.chain_id.is_some() && theirs.chain_id != defaults.chain_id {
+                network.chain_id = theirs.chain_id;
+            }
+            if theirs.sourcify_url != defaults.sourcify_url {
+                network.sourcify_url = theirs.sourcify_url.clone();
+            }
+            if !theirs.blockscout_url.is_empty() && theirs.blockscout_url != defaults.blockscout_url {
+                network.blockscout_url = theirs.blockscout_url.clone();
+            }
+            if theirs.sources != defaults.sources {
+                network.sources = theirs.sources.clone();
+            }
+        }
+        self.networks.chains.extend(other.networks.chains);
+        
+        // Merge analysis settings
+        if !other.analysis.cache_results {
+            self.analysis.cache_results = false;
+        }
+        if other.analysis.cache_ttl != default_cache_ttl() {
+            self.analysis.cache_ttl = other.analysis.cache_ttl;
+        }
+        if other.analysis.hook_fail_on != default_hook_fail_on() {
+            self.analysis.hook_fail_on = other.analysis.hook_fail_on;
+        }
+        if other.analysis.baseline_fail_on != default_hook_fail_on() {
+            self.analysis.baseline_fail_on = other.analysis.baseline_fail_on;
+        }
+        if other.analysis.ci_fail_on != default_hook_fail_on() {
+            self.analysis.ci_fail_on = other.analysis.ci_fail_on;
+        }
+        if other.analysis.baseline_exit_code != default_baseline_exit_code() {
+            self.analysis.baseline_exit_code = other.analysis.baseline_exit_code;
+        }
+        if !other.analysis.disabled_detectors.is_empty() {
+            self.analysis.disabled_detectors = other.analysis.disabled_detectors;
+        }
+
+        // Merge reporting settings
+        if other.reporting.checklist_standard != default_checklist_standard() {
+            self.reporting.checklist_standard = other.reporting.checklist_standard;
+        }
+        if other.reporting.architecture_format != default_architecture_format() {
+            self.reporting.architecture_format = other.reporting.architecture_format;
+        }
+        if other.reporting.languages != default_report_languages() {
+            self.reporting.languages = other.reporting.languages;
+        }
+        if other.reporting.bilingual_layout != default_bilingual_layout() {
+            self.reporting.bilingual_layout = other.reporting.bilingual_layout;
+        }
+        self.reporting.fonts.extend(other.reporting.fonts);
+        if other.reporting.poc_severities != default_poc_severities() {
+            self.reporting.poc_severities = other.reporting.poc_severities;
+        }
+        if !other.reporting.poc_categories.is_empty() {
+            self.reporting.poc_categories = other.reporting.poc_categories;
+        }
+        if other.reporting.no_exploit_code {
+            self.reporting.no_exploit_code = true;
+        }
+        if !other.reporting.client.is_empty() {
+            self.reporting.client = other.reporting.client;
+        }
+        if !other.reporting.project.is_empty() {
+            self.reporting.project = other.reporting.project;
+        }
+        if other.reporting.report_version != default_report_version() {
+            self.reporting.report_version = other.reporting.report_version;
+        }
+        if !other.reporting.filename_pattern.is_empty() {
+            self.reporting.filename_pattern = other.reporting.filename_pattern;
+        }
+        if other.reporting.finding_ids != default_finding_ids() {
+            self.reporting.finding_ids = other.reporting.finding_ids;
+        }
+        self.reporting.hooks.extend(other.reporting.hooks);
+        if other.reporting.wasm_runtime != default_wasm_runtime() {
+            self.reporting.wasm_runtime = other.reporting.wasm_runtime;
+        }
+
+        // Merge resource budgets
+        if other.budget.max_disk_mb != 0 {
+            self.budget.max_disk_mb = other.budget.max_disk_mb;
+        }
+        if other.budget.max_memory_mb != 0 {
+            self.budget.max_memory_mb = other.budget.max_memory_mb;
+        }
+        if other.budget.max_cpu_secs != 0 {
+            self.budget.max_cpu_secs = other.budget.max_cpu_secs;
+        }
+        if other.budget.on_exceeded != default_budget_on_exceeded() {
+            self.budget.on_exceeded = other.budget.on_exceeded;
+        }
+
+        // Merge retention policy
+        if other.retention.cache_max_age_days != default_cache_max_age_days() {
+            self.retention.cache_max_age_days = other.retention.cache_max_age_days;
+        }
+        if other.retention.cache_max_size_mb != default_cache_max_size_mb() {
+            self.retention.cache_max_size_mb = other.retention.cache_max_size_mb;
+        }
+        if other.retention.history_max_age_days != default_history_max_age_days() {
+            self.retention.history_max_age_days = other.retention.history_max_age_days;
+        }
+        if other.retention.temp_max_age_hours != default_temp_max_age_hours() {
+            self.retention.temp_max_age_hours = other.retention.temp_max_age_hours;
+        }
+        if other.retention.temp_max_size_mb != default_temp_max_size_mb() {
+            self.retention.temp_max_size_mb = other.retention.temp_max_size_mb;
+        }
+        if !other.retention.enforce_on_startup {
+            self.retention.enforce_on_startup = false;
+        }
+        let notification_defaults = NotificationsConfig::default();
+        if !other.notifications.webhooks.is_empty() {
+            self.notifications.webhooks = other.notifications.webhooks;
+        }
+        if other.notifications.timeout != notification_defaults.timeout {
+            self.notifications.timeout = other.notifications.timeout;
+        }
+        self.server.tenants.extend(other.server.tenants);
         
         Ok(self)
     }
     
     /// Validate configuration settings
     fn validate(&self) -> Result<()> {
-        // Validate AI backend
-        match self.ai.backend.as_str() {
-            "openai" | "anthropic" | "local" => {},
-            _ => return Err(BugForgeXError::config(format!("Invalid AI backend: {}", self.ai.backend))),
+        // Validate AI backends
+        for backend in std::iter::once(&self.ai.backend).chain(&self.ai.fallback_backends) {
+            if !crate::core::ai_provider::PROVIDERS.contains(&backend.as_str()) {
+                return Err(BugForgeXError::config(format!(
+                    "Invalid AI backend: {} ({})",
+                    backend,
+                    crate::core::ai_provider::PROVIDERS.join(", ")
+                )));
+            }
+        }
+        let uses = |provider: &str| std::iter::once(&self.ai.backend).chain(&self.ai.fallback_backends).any(|backend| backend == provider);
+        if uses("azure") && (self.ai.azure.endpoint.is_empty() || self.ai.azure.deployment.is_empty()) {
+            return Err(BugForgeXError::config("The azure backend needs ai.azure.endpoint and ai.azure.deployment"));
+        }
+        if self.ai.knowledge.enabled {
+            if !crate::core::ai_provider::EMBEDDING_PROVIDERS.contains(&self.ai.knowledge.backend.as_str()) {
+                return Err(BugForgeXError::config(format!(
+                    "Invalid knowledge backend: {} ({})",
+                    self.ai.knowledge.backend,
+                    crate::core::ai_provider::EMBEDDING_PROVIDERS.join(", ")
+                )));
+            }
+            if self.ai.knowledge.backend == "azure" && self.ai.azure.endpoint.is_empty() {
+                return Err(BugForgeXError::config("The azure knowledge backend needs ai.azure.endpoint"));
+            }
+        }
+        if !crate::core::ai_log::REDACTION_PROFILES.contains(&self.ai.redaction_profile.as_str()) {
+            return Err(BugForgeXError::config(format!(
+                "Invalid redaction profile: {} ({})",
+                self.ai.redaction_profile,
+                crate::core::ai_log::REDACTION_PROFILES.join(", ")
+            )));
         }
         
+        // Validate disabled detectors
+        for id in &self.analysis.disabled_detectors {
+            if crate::core::detector_catalog::find(id).is_none() {
+                return Err(BugForgeXError::config(format!("Unknown detector: {} (see `securechain detectors list`)", id)));
+            }
+        }
+
+        // Validate Slither framework
+        if !self.tools.slither.framework.is_empty() && !SLITHER_FRAMEWORKS.contains(&self.tools.slither.framework.as_str()) {
+            return Err(BugForgeXError::config(format!(
+                "Invalid Slither framework: {} ({}, or empty to compile each file on its own)",
+                self.tools.slither.framework,
+                SLITHER_FRAMEWORKS.join(", ")
+            )));
+        }
+
+        // Validate Mythril limits
+        if self.tools.mythril.timeout == 0 || self.tools.mythril.max_depth == 0 || self.tools.mythril.solver_timeout == 0 {
+            return Err(BugForgeXError::config("Mythril timeout, max_depth and solver_timeout must be greater than 0"));
+        }
+
+        // Validate fuzzing backend
+        match self.tools.fuzzer.as_str() {
+            "echidna" | "foundry" => {},
+            _ => return Err(BugForgeXError::config(format!("Invalid fuzzer: {} (echidna or foundry)", self.tools.fuzzer))),
+        }
+
+        // Validate budget policy
+        match self.budget.on_exceeded.as_str() {
+            "degrade" | "abort" => {},
+            _ => return Err(BugForgeXError::config(format!("Invalid budget policy: {} (degrade or abort)", self.budget.on_exceeded))),
+        }
+
+        // Validate report languages
+        if self.reporting.languages.len() > 2 {
+            return Err(BugForgeXError::config(format!(
+                "Too many report languages: {} (one, or two for a bilingual report)",
+                self.reporting.languages.join(", ")
+            )));
+        }
+        if !crate::report::i18n::LAYOUTS.contains(&self.reporting.bilingual_layout.as_str()) {
+            return Err(BugForgeXError::config(format!(
+                "Invalid bilingual layout: {} (sequential or side-by-side)",
+                self.reporting.bilingual_layout
+            )));
+        }
+        crate::report::naming::check_file_pattern(&self.reporting.filename_pattern)
+            .and_then(|_| crate::report::naming::check_finding_scheme(&self.reporting.finding_ids))
+            .map_err(|e| BugForgeXError::config(e.to_string()))?;
+        for (name, hook) in &self.reporting.hooks {
+            if hook.command.is_some() == hook.wasm.is_some() {
+                return Err(BugForgeXError::config(format!("Report hook {} needs either a command or a wasm module", name)));
+            }
+            if hook.timeout == 0 {
+                return Err(BugForgeXError::config(format!("Report hook {} timeout must be greater than 0", name)));
+            }
+        }
+
         // Validate log level
         match self.general.log_level.as_str() {
             "trace" | "debug" | "info" | "warn" | "error" => {},
@@ -496,9 +1875,325 @@ impl Config {
             "general.log_level" => self.general.log_level = value.to_string(),
             "ai.backend" => self.ai.backend = value.to_string(),
             "ai.local.ollama_url" => self.ai.local.ollama_url = value.to_string(),
+            "ai.local.default_model" => self.ai.local.default_model = value.to_string(),
+            "ai.openai.model" => self.ai.openai.model = value.to_string(),
+            "ai.anthropic.model" => self.ai.anthropic.model = value.to_string(),
+            "ai.azure.endpoint" => self.ai.azure.endpoint = value.trim_end_matches('/').to_string(),
+            "ai.azure.deployment" => self.ai.azure.deployment = value.to_string(),
+            "ai.azure.api_version" => self.ai.azure.api_version = value.to_string(),
+            "ai.azure.model" => self.ai.azure.model = value.to_string(),
+            "ai.gemini.api_url" => self.ai.gemini.api_url = value.to_string(),
+            "ai.gemini.model" => self.ai.gemini.model = value.to_string(),
+            "ai.openrouter.api_url" => self.ai.openrouter.api_url = value.to_string(),
+            "ai.openrouter.model" => self.ai.openrouter.model = value.to_string(),
+            "ai.probe_cluster_threshold" => {
+                self.ai.probe_cluster_threshold = value
+                    .parse()
+                    .ok()
+                    .filter(|threshold: &f64| (0.0..=1.0).contains(threshold))
+                    .ok_or_else(|| BugForgeXError::config(format!("Invalid similarity threshold: {}", value)))?
+            }
+            "ai.fallback_backends" => {
+                self.ai.fallback_backends = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|backend| !backend.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            }
+            "ai.retries" => {
+                self.ai.retries = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid retry count: {}", value)))?
+            }
+            "ai.health_timeout" => {
+                self.ai.health_timeout = value
+                    .parse()
+                    .ok()
+                    .filter(|timeout: &u64| *timeout > 0)
+                    .ok_or_else(|| BugForgeXError::config(format!("Invalid timeout: {}", value)))?
+            }
+            "ai.upload_log" => {
+                self.ai.upload_log = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid boolean: {}", value)))?
+            }
+            "ai.stream" => {
+                self.ai.stream = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid boolean: {}", value)))?
+            }
+            "ai.knowledge.enabled" => {
+                self.ai.knowledge.enabled = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid boolean: {}", value)))?
+            }
+            "ai.knowledge.backend" => self.ai.knowledge.backend = value.to_string(),
+            "ai.knowledge.model" => self.ai.knowledge.model = value.to_string(),
+            "ai.knowledge.entries" => {
+                self.ai.knowledge.entries = value
+                    .parse()
+                    .ok()
+                    .filter(|entries: &usize| *entries > 0)
+                    .ok_or_else(|| BugForgeXError::config(format!("Invalid entry count: {}", value)))?
+            }
+            "ai.redaction_profile" => self.ai.redaction_profile = value.to_string(),
+            "ai.context_tokens" => {
+                self.ai.context_tokens = value
+                    .parse()
+                    .ok()
+                    .filter(|tokens: &usize| *tokens > 0)
+                    .ok_or_else(|| BugForgeXError::config(format!("Invalid token count: {}", value)))?
+            }
+            "ai.response_retries" => {
+                self.ai.response_retries = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid retry count: {}", value)))?
+            }
+            "ai.max_cost"
+            | "ai.openai.prompt_price"
+            | "ai.openai.completion_price"
+            | "ai.anthropic.prompt_price"
+            | "ai.anthropic.completion_price"
+            | "ai.azure.prompt_price"
+            | "ai.azure.completion_price"
+            | "ai.gemini.prompt_price"
+            | "ai.gemini.completion_price"
+            | "ai.openrouter.prompt_price"
+            | "ai.openrouter.completion_price" => {
+                let amount = value
+                    .parse()
+                    .ok()
+                    .filter(|amount: &f64| amount.is_finite() && *amount >= 0.0)
+                    .ok_or_else(|| BugForgeXError::config(format!("Invalid amount in US dollars: {}", value)))?;
+                match key {
+                    "ai.max_cost" => self.ai.max_cost = amount,
+                    "ai.openai.prompt_price" => self.ai.openai.prompt_price = amount,
+                    "ai.openai.completion_price" => self.ai.openai.completion_price = amount,
+                    "ai.anthropic.prompt_price" => self.ai.anthropic.prompt_price = amount,
+                    "ai.anthropic.completion_price" => self.ai.anthropic.completion_price = amount,
+                    "ai.azure.prompt_price" => self.ai.azure.prompt_price = amount,
+                    "ai.azure.completion_price" => self.ai.azure.completion_price = amount,
+                    "ai.gemini.prompt_price" => self.ai.gemini.prompt_price = amount,
+                    "ai.gemini.completion_price" => self.ai.gemini.completion_price = amount,
+                    "ai.openrouter.prompt_price" => self.ai.openrouter.prompt_price = amount,
+                    _ => self.ai.openrouter.completion_price = amount,
+                }
+            }
             "general.output_dir" => self.general.output_dir = PathBuf::from(value),
+            "general.metrics_enabled" => {
+                self.general.metrics_enabled = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid boolean: {}", value)))?
+            }
+            "general.max_concurrent_tasks" | "general.max_deep_tasks" | "general.max_queued_jobs" => {
+                let limit = value
+                    .parse()
+                    .ok()
+                    .filter(|limit: &usize| *limit > 0)
+                    .ok_or_else(|| BugForgeXError::config(format!("Invalid limit: {}", value)))?;
+                match key {
+                    "general.max_concurrent_tasks" => self.general.max_concurrent_tasks = limit,
+                    "general.max_deep_tasks" => self.general.max_deep_tasks = limit,
+                    _ => self.general.max_queued_jobs = limit,
+                }
+            }
+            "tools.slither.include_detectors" | "tools.slither.exclude_detectors" | "tools.slither.remappings" => {
+                let list = value.split(',').map(str::trim).filter(|item| !item.is_empty()).map(str::to_string).collect();
+                match key {
+                    "tools.slither.include_detectors" => self.tools.slither.include_detectors = list,
+                    "tools.slither.exclude_detectors" => self.tools.slither.exclude_detectors = list,
+                    _ => self.tools.slither.remappings = list,
+                }
+            }
+            "tools.slither.framework" => self.tools.slither.framework = value.to_ascii_lowercase(),
+            "tools.slither.triage_database" => {
+                self.tools.slither.triage_database = Some(PathBuf::from(value)).filter(|_| !value.is_empty())
+            }
+            "tools.mythril.timeout" | "tools.mythril.solver_timeout" => {
+                let timeout = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid timeout: {}", value)))?;
+                match key {
+                    "tools.mythril.timeout" => self.tools.mythril.timeout = timeout,
+                    _ => self.tools.mythril.solver_timeout = timeout,
+                }
+            }
+            "tools.mythril.max_depth" => {
+                self.tools.mythril.max_depth = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid transaction depth: {}", value)))?
+            }
+            "tools.mythril.top_functions" => {
+                self.tools.mythril.top_functions = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid function count: {}", value)))?
+            }
+            "tools.mythril.reduced_max_depth" => {
+                self.tools.mythril.reduced_max_depth = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid transaction depth: {}", value)))?
+            }
+            "tools.solc.ast" => {
+                self.tools.solc.ast = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid boolean: {}", value)))?
+            }
+            "tools.fuzzer" => self.tools.fuzzer = value.to_ascii_lowercase(),
+            "tools.foundry.fuzz_runs" | "tools.foundry.invariant_runs" | "tools.foundry.invariant_depth" => {
+                let runs = value
+                    .parse()
+                    .ok()
+                    .filter(|runs: &u32| *runs > 0)
+                    .ok_or_else(|| BugForgeXError::config(format!("Invalid run count: {}", value)))?;
+                match key {
+                    "tools.foundry.fuzz_runs" => self.tools.foundry.fuzz_runs = runs,
+                    "tools.foundry.invariant_runs" => self.tools.foundry.invariant_runs = runs,
+                    _ => self.tools.foundry.invariant_depth = runs,
+                }
+            }
+            "tools.foundry.forge_std" => self.tools.foundry.forge_std = value.to_string(),
+            "tools.foundry.coverage" => {
+                self.tools.foundry.coverage = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid boolean: {}", value)))?
+            }
+            "tools.anvil.startup_timeout" => {
+                self.tools.anvil.startup_timeout = value
+                    .parse()
+                    .ok()
+                    .filter(|timeout: &u64| *timeout > 0)
+                    .ok_or_else(|| BugForgeXError::config(format!("Invalid timeout: {}", value)))?
+            }
+            "networks.signatures.lookup" => {
+                self.networks.signatures.lookup = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid boolean: {}", value)))?
+            }
             "analysis.default_depth" => self.analysis.default_depth = value.to_string(),
+            "analysis.cache_results" => {
+                self.analysis.cache_results = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid boolean: {}", value)))?
+            }
+            "analysis.cache_ttl" => {
+                self.analysis.cache_ttl = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid TTL: {}", value)))?
+            }
+            "analysis.hook_fail_on" => self.analysis.hook_fail_on = value.to_string(),
+            "analysis.baseline_fail_on" => self.analysis.baseline_fail_on = value.to_string(),
+            "analysis.ci_fail_on" => self.analysis.ci_fail_on = value.to_string(),
+            "analysis.baseline_exit_code" => {
+                self.analysis.baseline_exit_code = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid exit code: {}", value)))?
+            }
+            "analysis.disabled_detectors" => {
+                self.analysis.disabled_detectors = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            }
             "reporting.default_format" => self.reporting.default_format = value.to_string(),
+            "reporting.checklist_standard" => self.reporting.checklist_standard = value.to_string(),
+            "reporting.languages" => {
+                self.reporting.languages = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|language| !language.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            }
+            "reporting.bilingual_layout" => self.reporting.bilingual_layout = value.to_string(),
+            "reporting.poc_severities" | "reporting.poc_categories" => {
+                let values = value.split(',').map(str::trim).filter(|v| !v.is_empty()).map(str::to_string).collect();
+                match key {
+                    "reporting.poc_severities" => self.reporting.poc_severities = values,
+                    _ => self.reporting.poc_categories = values,
+                }
+            }
+            "reporting.no_exploit_code" => {
+                self.reporting.no_exploit_code = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid boolean: {}", value)))?
+            }
+            "reporting.client" => self.reporting.client = value.to_string(),
+            "reporting.project" => self.reporting.project = value.to_string(),
+            "reporting.report_version" => self.reporting.report_version = value.to_string(),
+            "reporting.filename_pattern" => self.reporting.filename_pattern = value.to_string(),
+            "reporting.finding_ids" => self.reporting.finding_ids = value.to_string(),
+            "reporting.architecture_format" => match value {
+                "mermaid" | "graphviz" => self.reporting.architecture_format = value.to_string(),
+                _ => return Err(BugForgeXError::config(format!("Invalid diagram format: {} (mermaid or graphviz)", value))),
+            },
+            "budget.max_disk_mb" | "budget.max_memory_mb" | "budget.max_cpu_secs" => {
+                let limit = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid limit: {} (0 for unlimited)", value)))?;
+                match key {
+                    "budget.max_disk_mb" => self.budget.max_disk_mb = limit,
+                    "budget.max_memory_mb" => self.budget.max_memory_mb = limit,
+                    _ => self.budget.max_cpu_secs = limit,
+                }
+            }
+            "budget.on_exceeded" => self.budget.on_exceeded = value.to_string(),
+            "retention.cache_max_age_days"
+            | "retention.cache_max_size_mb"
+            | "retention.history_max_age_days"
+            | "retention.temp_max_age_hours"
+            | "retention.temp_max_size_mb" => {
+                let limit = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid limit: {} (0 keeps everything)", value)))?;
+                match key {
+                    "retention.cache_max_age_days" => self.retention.cache_max_age_days = limit,
+                    "retention.cache_max_size_mb" => self.retention.cache_max_size_mb = limit,
+                    "retention.history_max_age_days" => self.retention.history_max_age_days = limit,
+                    "retention.temp_max_age_hours" => self.retention.temp_max_age_hours = limit,
+                    _ => self.retention.temp_max_size_mb = limit,
+                }
+            }
+            "notifications.webhooks" => {
+                self.notifications.webhooks = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|url| !url.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            }
+            "notifications.timeout" => {
+                self.notifications.timeout = value
+                    .parse()
+                    .ok()
+                    .filter(|timeout: &u64| *timeout > 0)
+                    .ok_or_else(|| BugForgeXError::config(format!("Invalid timeout: {}", value)))?
+            }
+            "retention.enforce_on_startup" => {
+                self.retention.enforce_on_startup = value
+                    .parse()
+                    .map_err(|_| BugForgeXError::config(format!("Invalid boolean: {}", value)))?
+            }
+            _ if key.starts_with("reporting.fonts.") => {
+                self.reporting.fonts.insert(key["reporting.fonts.".len()..].to_string(), value.to_string());
+            }
+            "reporting.wasm_runtime" => self.reporting.wasm_runtime = value.to_string(),
+            // A command hook; an empty command removes the hook
+            _ if key.starts_with("reporting.hooks.") => {
+                let name = key["reporting.hooks.".len()..].to_string();
+                match value.is_empty() {
+                    true => {
+                        self.reporting.hooks.remove(&name);
+                    }
+                    false => {
+                        let hook = self.reporting.hooks.entry(name).or_default();
+                        hook.command = Some(value.to_string());
+                        hook.wasm = None;
+                    }
+                }
+            }
             _ => return Err(BugForgeXError::config(format!("Unknown configuration key: {}", key))),
         }
         
@@ -512,9 +2207,109 @@ impl Config {
             "general.log_level" => Some(self.general.log_level.clone()),
             "ai.backend" => Some(self.ai.backend.clone()),
             "ai.local.ollama_url" => Some(self.ai.local.ollama_url.clone()),
+            "ai.probe_cluster_threshold" => Some(self.ai.probe_cluster_threshold.to_string()),
+            "ai.fallback_backends" => Some(self.ai.fallback_backends.join(",")),
+            "ai.retries" => Some(self.ai.retries.to_string()),
+            "ai.health_timeout" => Some(self.ai.health_timeout.to_string()),
+            "ai.upload_log" => Some(self.ai.upload_log.to_string()),
+            "ai.redaction_profile" => Some(self.ai.redaction_profile.clone()),
+            "ai.context_tokens" => Some(self.ai.context_tokens.to_string()),
+            "ai.response_retries" => Some(self.ai.response_retries.to_string()),
+            "ai.max_cost" => Some(self.ai.max_cost.to_string()),
+            "ai.stream" => Some(self.ai.stream.to_string()),
+            "ai.knowledge.enabled" => Some(self.ai.knowledge.enabled.to_string()),
+            "ai.knowledge.backend" => Some(self.ai.knowledge.backend.clone()),
+            "ai.knowledge.model" => Some(self.ai.knowledge.model.clone()),
+            "ai.knowledge.entries" => Some(self.ai.knowledge.entries.to_string()),
+            "ai.openai.prompt_price" => Some(self.ai.openai.prompt_price.to_string()),
+            "ai.openai.completion_price" => Some(self.ai.openai.completion_price.to_string()),
+            "ai.anthropic.prompt_price" => Some(self.ai.anthropic.prompt_price.to_string()),
+            "ai.anthropic.completion_price" => Some(self.ai.anthropic.completion_price.to_string()),
+            "ai.azure.prompt_price" => Some(self.ai.azure.prompt_price.to_string()),
+            "ai.azure.completion_price" => Some(self.ai.azure.completion_price.to_string()),
+            "ai.gemini.prompt_price" => Some(self.ai.gemini.prompt_price.to_string()),
+            "ai.gemini.completion_price" => Some(self.ai.gemini.completion_price.to_string()),
+            "ai.openrouter.prompt_price" => Some(self.ai.openrouter.prompt_price.to_string()),
+            "ai.openrouter.completion_price" => Some(self.ai.openrouter.completion_price.to_string()),
+            "ai.local.default_model" => Some(self.ai.local.default_model.clone()),
+            "ai.openai.model" => Some(self.ai.openai.model.clone()),
+            "ai.anthropic.model" => Some(self.ai.anthropic.model.clone()),
+            "ai.azure.endpoint" => Some(self.ai.azure.endpoint.clone()),
+            "ai.azure.deployment" => Some(self.ai.azure.deployment.clone()),
+            "ai.azure.api_version" => Some(self.ai.azure.api_version.clone()),
+            "ai.azure.model" => Some(self.ai.azure.model.clone()),
+            "ai.gemini.api_url" => Some(self.ai.gemini.api_url.clone()),
+            "ai.gemini.model" => Some(self.ai.gemini.model.clone()),
+            "ai.openrouter.api_url" => Some(self.ai.openrouter.api_url.clone()),
+            "ai.openrouter.model" => Some(self.ai.openrouter.model.clone()),
             "general.output_dir" => Some(self.general.output_dir.to_string_lossy().to_string()),
+            "general.metrics_enabled" => Some(self.general.metrics_enabled.to_string()),
+            "general.max_concurrent_tasks" => Some(self.general.max_concurrent_tasks.to_string()),
+            "general.max_deep_tasks" => Some(self.general.max_deep_tasks.to_string()),
+            "general.max_queued_jobs" => Some(self.general.max_queued_jobs.to_string()),
+            "tools.slither.include_detectors" => Some(self.tools.slither.include_detectors.join(",")),
+            "tools.slither.exclude_detectors" => Some(self.tools.slither.exclude_detectors.join(",")),
+            "tools.slither.remappings" => Some(self.tools.slither.remappings.join(",")),
+            "tools.slither.framework" => Some(self.tools.slither.framework.clone()),
+            "tools.slither.triage_database" => {
+                Some(self.tools.slither.triage_database.as_ref().map(|path| path.to_string_lossy().to_string()).unwrap_or_default())
+            }
+            "tools.mythril.timeout" => Some(self.tools.mythril.timeout.to_string()),
+            "tools.mythril.max_depth" => Some(self.tools.mythril.max_depth.to_string()),
+            "tools.mythril.solver_timeout" => Some(self.tools.mythril.solver_timeout.to_string()),
+            "tools.mythril.top_functions" => Some(self.tools.mythril.top_functions.to_string()),
+            "tools.mythril.reduced_max_depth" => Some(self.tools.mythril.reduced_max_depth.to_string()),
+            "tools.solc.ast" => Some(self.tools.solc.ast.to_string()),
+            "tools.fuzzer" => Some(self.tools.fuzzer.clone()),
+            "tools.foundry.fuzz_runs" => Some(self.tools.foundry.fuzz_runs.to_string()),
+            "tools.foundry.invariant_runs" => Some(self.tools.foundry.invariant_runs.to_string()),
+            "tools.foundry.invariant_depth" => Some(self.tools.foundry.invariant_depth.to_string()),
+            "tools.foundry.forge_std" => Some(self.tools.foundry.forge_std.clone()),
+            "tools.foundry.coverage" => Some(self.tools.foundry.coverage.to_string()),
+            "tools.anvil.startup_timeout" => Some(self.tools.anvil.startup_timeout.to_string()),
+            "networks.signatures.lookup" => Some(self.networks.signatures.lookup.to_string()),
             "analysis.default_depth" => Some(self.analysis.default_depth.clone()),
+            "analysis.cache_results" => Some(self.analysis.cache_results.to_string()),
+            "analysis.cache_ttl" => Some(self.analysis.cache_ttl.to_string()),
+            "analysis.hook_fail_on" => Some(self.analysis.hook_fail_on.clone()),
+            "analysis.baseline_fail_on" => Some(self.analysis.baseline_fail_on.clone()),
+            "analysis.ci_fail_on" => Some(self.analysis.ci_fail_on.clone()),
+            "analysis.baseline_exit_code" => Some(self.analysis.baseline_exit_code.to_string()),
+            "analysis.disabled_detectors" => Some(self.analysis.disabled_detectors.join(",")),
             "reporting.default_format" => Some(self.reporting.default_format.clone()),
+            "reporting.checklist_standard" => Some(self.reporting.checklist_standard.clone()),
+            "reporting.architecture_format" => Some(self.reporting.architecture_format.clone()),
+            "reporting.languages" => Some(self.reporting.languages.join(",")),
+            "reporting.bilingual_layout" => Some(self.reporting.bilingual_layout.clone()),
+            "reporting.poc_severities" => Some(self.reporting.poc_severities.join(",")),
+            "reporting.poc_categories" => Some(self.reporting.poc_categories.join(",")),
+            "reporting.no_exploit_code" => Some(self.reporting.no_exploit_code.to_string()),
+            "reporting.client" => Some(self.reporting.client.clone()),
+            "reporting.project" => Some(self.reporting.project.clone()),
+            "reporting.report_version" => Some(self.reporting.report_version.clone()),
+            "reporting.filename_pattern" => Some(self.reporting.filename_pattern.clone()),
+            "reporting.finding_ids" => Some(self.reporting.finding_ids.clone()),
+            "budget.max_disk_mb" => Some(self.budget.max_disk_mb.to_string()),
+            "budget.max_memory_mb" => Some(self.budget.max_memory_mb.to_string()),
+            "budget.max_cpu_secs" => Some(self.budget.max_cpu_secs.to_string()),
+            "budget.on_exceeded" => Some(self.budget.on_exceeded.clone()),
+            "retention.cache_max_age_days" => Some(self.retention.cache_max_age_days.to_string()),
+            "retention.cache_max_size_mb" => Some(self.retention.cache_max_size_mb.to_string()),
+            "retention.history_max_age_days" => Some(self.retention.history_max_age_days.to_string()),
+            "retention.temp_max_age_hours" => Some(self.retention.temp_max_age_hours.to_string()),
+            "retention.temp_max_size_mb" => Some(self.retention.temp_max_size_mb.to_string()),
+            "retention.enforce_on_startup" => Some(self.retention.enforce_on_startup.to_string()),
+            "notifications.webhooks" => Some(self.notifications.webhooks.join(",")),
+            "notifications.timeout" => Some(self.notifications.timeout.to_string()),
+            _ if key.starts_with("reporting.fonts.") => self.reporting.fonts.get(&key["reporting.fonts.".len()..]).cloned(),
+            "reporting.wasm_runtime" => Some(self.reporting.wasm_runtime.clone()),
+            _ if key.starts_with("reporting.hooks.") => self.reporting.hooks.get(&key["reporting.hooks.".len()..]).map(|hook| {
+                match (&hook.command, &hook.wasm) {
+                    (Some(command), _) => command.clone(),
+                    (None, Some(module)) => module.to_string_lossy().to_string(),
+                    (None, None) => String::new(),
+                }
+            }),
             _ => None,
         }
     }
@@ -530,8 +2325,12 @@ impl Default for Config {
                 max_concurrent_tasks: 4,
                 output_dir: PathBuf::from("./output"),
                 cache_dir: home_dir.join(".cache/bugforgex"),
+                data_dir: default_data_dir(),
                 colored_output: true,
                 default_timeout: 300,
+                metrics_enabled: false,
+                max_deep_tasks: default_max_deep_tasks(),
+                max_queued_jobs: default_max_queued_jobs(),
             },
             ai: AiConfig {
                 backend: "local".to_string(),
@@ -540,11 +2339,15 @@ impl Default for Config {
                     model: "gpt-4".to_string(),
                     organization: None,
                     rate_limit: 60,
+                    prompt_price: default_openai_prompt_price(),
+                    completion_price: default_openai_completion_price(),
                 },
                 anthropic: AnthropicConfig {
                     api_url: "https://api.anthropic.com/v1".to_string(),
                     model: "claude-3-sonnet-20240229".to_string(),
                     rate_limit: 60,
+                    prompt_price: default_anthropic_prompt_price(),
+                    completion_price: default_anthropic_completion_price(),
                 },
                 local: LocalLlmConfig {
                     ollama_url: "http://localhost:11434".to_string(),
@@ -557,9 +2360,23 @@ impl Default for Config {
                     ],
                     gpu_acceleration: true,
                 },
+                azure: AzureOpenAiConfig::default(),
+                gemini: GeminiConfig::default(),
+                openrouter: OpenRouterConfig::default(),
+                knowledge: KnowledgeConfig::default(),
                 enabled_by_default: false,
                 max_tokens: 4000,
                 temperature: 0.1,
+                probe_cluster_threshold: default_probe_cluster_threshold(),
+                fallback_backends: Vec::new(),
+                retries: default_ai_retries(),
+                health_timeout: default_ai_health_timeout(),
+                upload_log: default_upload_log(),
+                redaction_profile: default_redaction_profile(),
+                context_tokens: default_context_tokens(),
+                response_retries: default_response_retries(),
+                max_cost: 0.0,
+                stream: default_ai_stream(),
             },
             networks: NetworkConfig {
                 ethereum: BlockchainNetworkConfig {
@@ -567,30 +2384,50 @@ impl Default for Config {
                     rpc_url: "https://mainnet.infura.io/v3/YOUR_PROJECT_ID".to_string(),
                     rate_limit: 5,
                     timeout: 30,
+                    chain_id: Some(1),
+                    sourcify_url: default_sourcify_url(),
+                    blockscout_url: "https://eth.blockscout.com".to_string(),
+                    sources: default_fetch_sources(),
                 },
                 polygon: BlockchainNetworkConfig {
                     explorer_url: "https://api.polygonscan.com/api".to_string(),
                     rpc_url: "https://polygon-mainnet.infura.io/v3/YOUR_PROJECT_ID".to_string(),
                     rate_limit: 5,
                     timeout: 30,
+                    chain_id: Some(137),
+                    sourcify_url: default_sourcify_url(),
+                    blockscout_url: "https://polygon.blockscout.com".to_string(),
+                    sources: default_fetch_sources(),
                 },
                 arbitrum: BlockchainNetworkConfig {
                     explorer_url: "https://api.arbiscan.io/api".to_string(),
                     rpc_url: "https://arbitrum-mainnet.infura.io/v3/YOUR_PROJECT_ID".to_string(),
                     rate_limit: 5,
                     timeout: 30,
+                    chain_id: Some(42161),
+                    sourcify_url: default_sourcify_url(),
+                    blockscout_url: "https://arbitrum.blockscout.com".to_string(),
+                    sources: default_fetch_sources(),
                 },
                 optimism: BlockchainNetworkConfig {
                     explorer_url: "https://api-optimistic.etherscan.io/api".to_string(),
                     rpc_url: "https://optimism-mainnet.infura.io/v3/YOUR_PROJECT_ID".to_string(),
                     rate_limit: 5,
                     timeout: 30,
+                    chain_id: Some(10),
+                    sourcify_url: default_sourcify_url(),
+                    blockscout_url: "https://optimism.blockscout.com".to_string(),
+                    sources: default_fetch_sources(),
                 },
                 bsc: BlockchainNetworkConfig {
                     explorer_url: "https://api.bscscan.com/api".to_string(),
                     rpc_url: "https://bsc-dataseed.binance.org".to_string(),
                     rate_limit: 5,
                     timeout: 30,
+                    chain_id: Some(56),
+                    sourcify_url: default_sourcify_url(),
+                    blockscout_url: String::new(),
+                    sources: default_fetch_sources(),
                 },
                 solana: SolanaNetworkConfig {
                     rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
@@ -603,22 +2440,13 @@ impl Default for Config {
                     rate_limit: 5000,
                     timeout: 30,
                 },
+                signatures: SignatureConfig::default(),
+                chains: BTreeMap::new(),
             },
             tools: ToolsConfig {
-                slither: SlitherConfig {
-                    executable: "slither".to_string(),
-                    args: vec!["--json".to_string(), "-".to_string()],
-                    exclude_detectors: vec![],
-                    include_detectors: vec![],
-                    timeout: 300,
-                },
-                mythril: MythrilConfig {
-                    executable: "myth".to_string(),
-                    args: vec!["analyze".to_string(), "--output".to_string(), "json".to_string()],
-                    timeout: 600,
-                    max_depth: 22,
-                    solver_timeout: 10000,
-                },
+                fuzzer: default_fuzzer(),
+                slither: SlitherConfig::default(),
+                mythril: MythrilConfig::default(),
                 echidna: EchidnaConfig {
                     executable: "echidna-test".to_string(),
                     test_limit: 10000,
@@ -626,6 +2454,13 @@ impl Default for Config {
                     shrink_limit: 5000,
                     timeout: 600,
                 },
+                foundry: FoundryConfig::default(),
+                anvil: AnvilConfig::default(),
+                solhint: SolhintConfig::default(),
+                semgrep: SemgrepConfig::default(),
+                solc: SolcConfig::default(),
+                vyper: VyperConfig::default(),
+                arithmetic: ArithmeticConfig::default(),
                 custom: HashMap::new(),
             },
             analysis: AnalysisConfig {
@@ -633,7 +2468,7 @@ impl Default for Config {
                 parallel_analysis: true,
                 max_threads: 4,
                 cache_results: true,
-                cache_ttl: 3600,
+                cache_ttl: default_cache_ttl(),
                 min_confidence: 0.5,
                 severity_filters: vec![
                     "Critical".to_string(),
@@ -641,6 +2476,11 @@ impl Default for Config {
                     "Medium".to_string(),
                     "Low".to_string(),
                 ],
+                hook_fail_on: default_hook_fail_on(),
+                baseline_fail_on: default_hook_fail_on(),
+                ci_fail_on: default_hook_fail_on(),
+                baseline_exit_code: default_baseline_exit_code(),
+                disabled_detectors: Vec::new(),
             },
             reporting: ReportingConfig {
                 default_format: "markdown".to_string(),
@@ -648,7 +2488,26 @@ impl Default for Config {
                 template_dir: PathBuf::from("templates"),
                 custom_templates: HashMap::new(),
                 max_report_size: 100,
+                checklist_standard: default_checklist_standard(),
+                architecture_format: default_architecture_format(),
+                languages: default_report_languages(),
+                bilingual_layout: default_bilingual_layout(),
+                fonts: HashMap::new(),
+                poc_severities: default_poc_severities(),
+                poc_categories: Vec::new(),
+                no_exploit_code: false,
+                client: String::new(),
+                project: String::new(),
+                report_version: default_report_version(),
+                filename_pattern: String::new(),
+                finding_ids: default_finding_ids(),
+                hooks: BTreeMap::new(),
+                wasm_runtime: default_wasm_runtime(),
             },
+            budget: BudgetConfig::default(),
+            retention: RetentionConfig::default(),
+            notifications: NotificationsConfig::default(),
+            server: ServerConfig::default(),
         }
     }
 }
@@ -0,0 +1,140 @@
+//! Cron expressions of scheduled scans
+//!
+//! The five fields of crontab, minute, hour, day of month, month and day of
+//! week (0 or 7 for Sunday), each `*`, a number, a range `a-b`, a step
+//! `*/n` or `a-b/n`, or a comma-separated list of those. `@hourly`,
+//! `@daily`, `@weekly`, `@monthly` and `@yearly` stand for the usual
+//! expressions. As in cron, when both day fields are restricted a day
+//! matches if either does. Times are UTC.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Duration, DurationRound, Timelike, Utc};
+
+/// How far ahead the next run is looked for; February 29th can be eight years away
+const HORIZON_DAYS: i64 = 366 * 9;
+
+/// A parsed expression, one bit per value each field allows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cron {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether the day of month field is `*`, leaving days to the day of week
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl std::str::FromStr for Cron {
+    type Err = anyhow::Error;
+
+    fn from_str(expression: &str) -> Result<Self> {
+        let expanded = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(anyhow!("Invalid cron expression: {} (expected 5 fields, e.g. `0 3 * * *`)", expression));
+        };
+        let invalid = |e: anyhow::Error| anyhow!("Invalid cron expression: {} ({})", expression, e);
+
+        let mut weekdays = field(weekday, 0, 7, "day of week").map_err(invalid)?;
+        // Sunday is both 0 and 7
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: field(minute, 0, 59, "minute").map_err(invalid)?,
+            hours: field(hour, 0, 23, "hour").map_err(invalid)?,
+            days: field(day, 1, 31, "day of month").map_err(invalid)?,
+            months: field(month, 1, 12, "month").map_err(invalid)?,
+            weekdays,
+            any_day: day.starts_with('*'),
+            any_weekday: weekday.starts_with('*'),
+        })
+    }
+}
+
+impl Cron {
+    /// Whether a run is due in the minute of `time`
+    pub fn matches(&self, time: &DateTime<Utc>) -> bool {
+        bit(self.minutes, time.minute()) && bit(self.hours, time.hour()) && self.matches_day(time)
+    }
+
+    /// Start of the first minute after `time` a run is due in, if any within a few years
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut next = time.duration_trunc(Duration::minutes(1)).ok()? + Duration::minutes(1);
+        let horizon = time + Duration::days(HORIZON_DAYS);
+        while next <= horizon {
+            if !self.matches_day(&next) {
+                next = next.duration_trunc(Duration::days(1)).ok()? + Duration::days(1);
+            } else if !bit(self.hours, next.hour()) {
+                next = next.duration_trunc(Duration::hours(1)).ok()? + Duration::hours(1);
+            } else if !bit(self.minutes, next.minute()) {
+                next += Duration::minutes(1);
+            } else {
+                return Some(next);
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, time: &DateTime<Utc>) -> bool {
+        if !bit(self.months, time.month()) {
+            return false;
+        }
+        let day = bit(self.days, time.day());
+        let weekday = bit(self.weekdays, time.weekday().num_days_from_sunday());
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday,
+            (false, true) => day,
+            (false, false) => day || weekday,
+        }
+    }
+}
+
+fn bit(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Values `text` allows between `min` and `max`, as bits
+fn field(text: &str, min: u32, max: u32, name: &str) -> Result<u64> {
+    let number = |value: &str| -> Result<u32> {
+        value
+            .parse()
+            .ok()
+            .filter(|value| (min..=max).contains(value))
+            .ok_or_else(|| anyhow!("{} must be {} to {}, not {}", name, min, max, value))
+    };
+
+    let mut set = 0;
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse().ok().filter(|step| *step > 0).ok_or_else(|| anyhow!("invalid step in {}", part))?),
+            None => (part, 0),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (number(start)?, number(end)?),
+                // `5/15` runs from 5 to the end of the range
+                None if step > 0 => (number(range)?, max),
+                None => (number(range)?, number(range)?),
+            },
+        };
+        if start > end {
+            return Err(anyhow!("{} range {} runs backwards", name, range));
+        }
+        for value in (start..=end).step_by(step.max(1)) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
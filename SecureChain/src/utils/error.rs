@@ -4,6 +4,7 @@
 //! utilities for the application.
 
 use anyhow;
+use serde::{Deserialize, Serialize};
 use serde_json;
 
 use thiserror::Error;
@@ -64,6 +65,10 @@ pub enum BugForgeXError {
     #[error("Tool execution error: {tool}: {message}")]
     ToolExecution { tool: String, message: String },
 
+    /// Classified tool, fetch and fuzzing failures
+    #[error(transparent)]
+    Tool(#[from] ToolError),
+
     /// Network errors
     #[error("Network error: {message}")]
     Network { message: String },
@@ -170,6 +175,109 @@ impl BugForgeXError {
     }
 }
 
+/// Why an external tool, a fetch or a fuzzing campaign failed
+///
+/// Recorded on tool runs, fetch manifests and fuzzing results under a
+/// `kind` tag, so programs reading them can tell a missing tool from a
+/// crash or a network outage without matching on messages.
+#[derive(Error, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ToolError {
+    /// The executable is not on the `PATH`
+    #[error("{program} is not installed")]
+    ToolNotFound { program: String },
+
+    /// The tool ran past its timeout and was stopped
+    #[error("timed out after {seconds}s")]
+    ToolTimeout { seconds: u64 },
+
+    /// The tool exited without a usable result; `exit_code` is `None` when it was killed
+    #[error("{}: {message}", exit_status(.exit_code))]
+    ToolCrashed { exit_code: Option<i32>, message: String },
+
+    /// The source, or the tool's output, could not be read
+    #[error("parse error: {message}")]
+    ParseError { message: String },
+
+    /// A remote service could not be reached or refused the request
+    #[error("network error: {message}")]
+    NetworkError { message: String },
+
+    /// The configuration, or the installed tool's version, does not fit the invocation
+    #[error("configuration error: {message}")]
+    ConfigError { message: String },
+}
+
+fn exit_status(exit_code: &Option<i32>) -> String {
+    match exit_code {
+        Some(code) => format!("exit status: {}", code),
+        None => "killed".to_string(),
+    }
+}
+
+impl ToolError {
+    /// Failure of a process that exited with `status`, described by the last line of its stderr
+    pub fn crashed(status: std::process::ExitStatus, stderr: &[u8]) -> Self {
+        let stderr = String::from_utf8_lossy(stderr);
+        let message = match stderr.lines().map(str::trim).rfind(|line| !line.is_empty()) {
+            Some(line) => line.to_string(),
+            None => "no output".to_string(),
+        };
+        ToolError::ToolCrashed { exit_code: status.code(), message }
+    }
+
+    /// Kind of an error `tool` failed with
+    ///
+    /// Errors raised as a `ToolError` keep their kind; others are told by
+    /// their type, or by the message prefixes the tool runners use.
+    pub fn classify(tool: &str, error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(tool_error) = cause.downcast_ref::<ToolError>() {
+                return tool_error.clone();
+            }
+            if let Some(io_error) = cause.downcast_ref::<std::io::Error>() {
+                if io_error.kind() == std::io::ErrorKind::NotFound {
+                    return ToolError::ToolNotFound { program: tool.to_string() };
+                }
+            }
+            if cause.is::<serde_json::Error>() || cause.is::<toml::de::Error>() {
+                return ToolError::ParseError { message: error.to_string() };
+            }
+            #[cfg(feature = "explorers")]
+            if cause.is::<ureq::Error>() {
+                return ToolError::NetworkError { message: error.to_string() };
+            }
+            #[cfg(feature = "ai")]
+            if cause.is::<reqwest::Error>() {
+                return ToolError::NetworkError { message: error.to_string() };
+            }
+            if let Some(error) = cause.downcast_ref::<BugForgeXError>() {
+                match error {
+                    BugForgeXError::Tool(tool_error) => return tool_error.clone(),
+                    BugForgeXError::Config { message } => return ToolError::ConfigError { message: message.clone() },
+                    BugForgeXError::Network { message } | BugForgeXError::Http { message } | BugForgeXError::RateLimit { message } => {
+                        return ToolError::NetworkError { message: message.clone() }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let message = error.to_string();
+        if let Some(rest) = message.strip_prefix("timed out after ") {
+            let seconds = rest.trim_end_matches('s').parse().unwrap_or_default();
+            ToolError::ToolTimeout { seconds }
+        } else if message.starts_with("compilation failed") {
+            ToolError::ParseError { message }
+        } else if message.starts_with("unsupported version") {
+            ToolError::ConfigError { message }
+        } else {
+            // Budget stops included: the tool was killed before it finished
+            ToolError::ToolCrashed { exit_code: None, message }
+        }
+    }
+}
+
 /// Result type alias for BugForgeX operations
 pub type Result<T> = std::result::Result<T, BugForgeXError>;
 
@@ -227,7 +335,10 @@ pub mod utils {
     pub fn is_retryable_error(err: &BugForgeXError) -> bool {
         matches!(
             err,
-            BugForgeXError::Network { .. } | BugForgeXError::RateLimit { .. } | BugForgeXError::Http { .. }
+            BugForgeXError::Network { .. }
+                | BugForgeXError::RateLimit { .. }
+                | BugForgeXError::Http { .. }
+                | BugForgeXError::Tool(ToolError::NetworkError { .. } | ToolError::ToolTimeout { .. })
         )
     }
 
@@ -247,6 +358,7 @@ pub mod utils {
             BugForgeXError::AiAssistant { .. } => "ai",
             BugForgeXError::ReportGeneration { .. } => "report",
             BugForgeXError::ToolExecution { .. } => "tool",
+            BugForgeXError::Tool(_) => "tool",
             BugForgeXError::Network { .. } => "network",
             BugForgeXError::Authentication { .. } => "auth",
             BugForgeXError::RateLimit { .. } => "rate_limit",
@@ -0,0 +1,183 @@
+//! Local usage metrics
+//!
+//! When `general.metrics_enabled` is set, every command adds to a summary
+//! file in `general.data_dir`: how often each command ran, how long
+//! it took, and how often each tool failed. Nothing is sent over the network.
+//! The file holds counts only, no paths, contract names or findings, and is
+//! versioned JSON so teams can collect the files themselves and aggregate them.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::core::completeness::{Completeness, SkipReason, ToolStatus};
+use crate::utils::artifact;
+use crate::utils::cache;
+use crate::utils::config::Config;
+
+/// Version of the file layout, bumped on incompatible changes
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Outcome of one tool run, with the metrics file it counts towards
+type ToolRun = (PathBuf, String, ToolStatus, Option<SkipReason>);
+
+/// Tool outcomes of the running commands, written out with their command record
+static PENDING_TOOL_RUNS: Mutex<Vec<ToolRun>> = Mutex::new(Vec::new());
+
+/// Usage summary of one installation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageMetrics {
+    pub schema_version: u32,
+    /// Random identifier, so collected files from different machines can be told apart
+    pub installation_id: String,
+    /// SecureChain version that last updated the file
+    pub securechain_version: String,
+    pub os: String,
+    pub arch: String,
+    pub since: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub commands: BTreeMap<String, CommandStats>,
+    pub tools: BTreeMap<String, ToolStats>,
+}
+
+/// Runs of one command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandStats {
+    pub runs: u64,
+    pub failures: u64,
+    pub total_duration_ms: u64,
+    pub max_duration_ms: u64,
+}
+
+/// Runs of one tool, counted per contract
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolStats {
+    pub runs: u64,
+    pub completed: u64,
+    pub failed: u64,
+    pub unavailable: u64,
+    /// Why runs did not complete, by skip reason
+    pub skip_reasons: BTreeMap<String, u64>,
+}
+
+impl UsageMetrics {
+    fn new() -> Self {
+        let now = Utc::now();
+        Self {
+            schema_version: SCHEMA_VERSION,
+            installation_id: uuid::Uuid::new_v4().to_string(),
+            securechain_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            since: now,
+            updated_at: now,
+            commands: BTreeMap::new(),
+            tools: BTreeMap::new(),
+        }
+    }
+}
+
+impl CommandStats {
+    pub fn average_duration_ms(&self) -> u64 {
+        self.total_duration_ms.checked_div(self.runs).unwrap_or(0)
+    }
+}
+
+impl ToolStats {
+    /// Share of runs that did not complete (0.0-1.0)
+    pub fn failure_rate(&self) -> f64 {
+        if self.runs == 0 {
+            0.0
+        } else {
+            (self.failed + self.unavailable) as f64 / self.runs as f64
+        }
+    }
+}
+
+/// Location of the metrics file, in `general.data_dir`
+pub fn metrics_path(config: &Config) -> PathBuf {
+    cache::expand_home(&config.general.data_dir).join("metrics.json")
+}
+
+/// Remember the tool outcomes of an analysis under `config` for the current command's record
+pub fn note_tool_runs(config: &Config, completeness: &Completeness) {
+    let path = metrics_path(config);
+    let mut pending = PENDING_TOOL_RUNS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    pending.extend(
+        completeness
+            .tool_runs
+            .iter()
+            .filter(|run| run.status != ToolStatus::NotRequested)
+            .map(|run| (path.clone(), run.tool.clone(), run.status, run.reason)),
+    );
+}
+
+/// Add one command run, and the tool outcomes noted for `path` during it, to the metrics file at `path`
+pub fn record(path: &Path, command: &str, duration: Duration, succeeded: bool) -> Result<()> {
+    let mut metrics = read(path)?.unwrap_or_else(UsageMetrics::new);
+
+    let duration_ms = duration.as_millis() as u64;
+    let stats = metrics.commands.entry(command.to_string()).or_default();
+    stats.runs += 1;
+    stats.failures += (!succeeded) as u64;
+    stats.total_duration_ms += duration_ms;
+    stats.max_duration_ms = stats.max_duration_ms.max(duration_ms);
+
+    let pending: Vec<ToolRun> = {
+        let mut noted = PENDING_TOOL_RUNS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let (ours, others) = std::mem::take(&mut *noted).into_iter().partition(|(file, ..)| file == path);
+        *noted = others;
+        ours
+    };
+    for (_, tool, status, reason) in pending {
+        let stats = metrics.tools.entry(tool).or_default();
+        stats.runs += 1;
+        match status {
+            ToolStatus::Completed => stats.completed += 1,
+            ToolStatus::Failed => stats.failed += 1,
+            ToolStatus::Unavailable => stats.unavailable += 1,
+            ToolStatus::NotRequested => {}
+        }
+        if let Some(reason) = reason {
+            *stats.skip_reasons.entry(reason.to_string()).or_default() += 1;
+        }
+    }
+
+    metrics.schema_version = SCHEMA_VERSION;
+    metrics.securechain_version = env!("CARGO_PKG_VERSION").to_string();
+    metrics.updated_at = Utc::now();
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    artifact::write_atomic(path, serde_json::to_string_pretty(&metrics)?)?;
+    Ok(())
+}
+
+/// Metrics recorded so far, `None` before the first recorded command
+pub fn load(config: &Config) -> Result<Option<UsageMetrics>> {
+    read(&metrics_path(config))
+}
+
+fn read(path: &Path) -> Result<Option<UsageMetrics>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let metrics = artifact::read_json(path, "metrics file")
+        .map_err(|e| anyhow!("{} (or reset it with `securechain stats --reset`)", e))?;
+    Ok(Some(metrics))
+}
+
+/// Delete the metrics file, starting a new summary with a new installation id
+pub fn reset(config: &Config) -> Result<bool> {
+    let path = metrics_path(config);
+    if !path.exists() {
+        return Ok(false);
+    }
+    std::fs::remove_file(path)?;
+    Ok(true)
+}
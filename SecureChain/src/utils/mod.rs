@@ -3,6 +3,15 @@
 //! This module contains various utility functions and types
 //! used throughout the application.
 
+pub mod abi;
+pub mod artifact;
+pub mod budget;
+pub mod cache;
 pub mod config;
+pub mod cron;
 pub mod simple_config;
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod metrics;
+pub mod output;
+pub mod platform;
+pub mod retention;
\ No newline at end of file
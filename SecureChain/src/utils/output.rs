@@ -0,0 +1,185 @@
+//! Console output control for SecureChain
+//!
+//! This module decides what is allowed to reach stdout. In JSON mode the
+//! banner and all decorative progress output are suppressed so that stdout
+//! carries exactly one JSON document, while logs keep going to stderr.
+//! Progress bars are drawn on stderr, and only on a terminal.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+static JSON_MODE: AtomicBool = AtomicBool::new(false);
+
+/// The progress bar currently drawn on stderr, cleared and redrawn around status lines
+static PROGRESS_LINE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Width of a progress bar, in cells
+const BAR_WIDTH: usize = 30;
+
+/// Enable or disable JSON mode for the rest of the process
+pub fn set_json_mode(enabled: bool) {
+    JSON_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// Check if JSON mode is active
+pub fn is_json_mode() -> bool {
+    JSON_MODE.load(Ordering::Relaxed)
+}
+
+/// Write the single JSON document for this invocation to stdout
+pub fn emit_json<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Print a status line to stdout, keeping a progress bar below it
+pub fn print_status(line: std::fmt::Arguments) {
+    let progress = PROGRESS_LINE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    match progress.as_deref() {
+        Some(bar) => {
+            eprint!("\r\x1b[2K");
+            println!("{}", line);
+            let _ = std::io::stdout().flush();
+            eprint!("{}", bar);
+        }
+        None => println!("{}", line),
+    }
+}
+
+/// Print decorative/progress output unless JSON mode is active
+#[macro_export]
+macro_rules! status {
+    () => {
+        if !$crate::utils::output::is_json_mode() {
+            $crate::utils::output::print_status(format_args!(""));
+        }
+    };
+    ($($arg:tt)*) => {
+        if !$crate::utils::output::is_json_mode() {
+            $crate::utils::output::print_status(format_args!($($arg)*));
+        }
+    };
+}
+
+/// A progress bar over a known number of items
+///
+/// Nothing is drawn in JSON mode, when stderr is not a terminal, or for a
+/// single item. The bar is removed when the value is dropped.
+pub struct Progress {
+    label: &'static str,
+    total: usize,
+    done: usize,
+    started: Instant,
+    enabled: bool,
+}
+
+impl Progress {
+    pub fn new(label: &'static str, total: usize) -> Self {
+        let progress = Self {
+            label,
+            total,
+            done: 0,
+            started: Instant::now(),
+            enabled: total > 1 && !is_json_mode() && std::io::stderr().is_terminal(),
+        };
+        progress.draw("");
+        progress
+    }
+
+    /// Count `item` as done
+    pub fn advance(&mut self, item: &str) {
+        self.done = (self.done + 1).min(self.total);
+        self.draw(item);
+    }
+
+    fn draw(&self, item: &str) {
+        if !self.enabled {
+            return;
+        }
+        let filled = BAR_WIDTH * self.done / self.total.max(1);
+        let eta = match self.done {
+            0 => String::new(),
+            done => {
+                let left = self.started.elapsed().as_secs_f64() / done as f64 * (self.total - done) as f64;
+                format!(", ~{:.0}s left", left)
+            }
+        };
+        let bar = format!(
+            "\r\x1b[2K{} [{}{}] {}/{}{} {}",
+            self.label,
+            "#".repeat(filled),
+            "-".repeat(BAR_WIDTH - filled),
+            self.done,
+            self.total,
+            eta,
+            item
+        );
+        let mut progress = PROGRESS_LINE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        eprint!("{}", bar);
+        let _ = std::io::stderr().flush();
+        *progress = Some(bar);
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if self.enabled {
+            let mut progress = PROGRESS_LINE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            eprint!("\r\x1b[2K");
+            *progress = None;
+        }
+    }
+}
+
+/// An AI answer echoed to stderr as its tokens arrive
+///
+/// Only on a terminal and outside JSON mode; a progress bar is cleared while
+/// the answer is written and redrawn below it when the value is dropped.
+pub struct TokenEcho {
+    label: String,
+    enabled: bool,
+    started: bool,
+}
+
+impl TokenEcho {
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            enabled: !is_json_mode() && std::io::stderr().is_terminal(),
+            started: false,
+        }
+    }
+
+    /// Echo the next tokens of the answer
+    pub fn push(&mut self, tokens: &str) {
+        if !self.enabled || tokens.is_empty() {
+            return;
+        }
+        let progress = PROGRESS_LINE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if !self.started {
+            if progress.is_some() {
+                eprint!("\r\x1b[2K");
+            }
+            eprint!("{} ", self.label);
+            self.started = true;
+        }
+        eprint!("{}", tokens);
+        let _ = std::io::stderr().flush();
+    }
+}
+
+impl Drop for TokenEcho {
+    fn drop(&mut self) {
+        if self.started {
+            let progress = PROGRESS_LINE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            eprintln!();
+            if let Some(bar) = progress.as_deref() {
+                eprint!("{}", bar);
+            }
+        }
+    }
+}
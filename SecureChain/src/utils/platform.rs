@@ -0,0 +1,178 @@
+//! Platform-aware tool discovery
+//!
+//! External tools are installed differently per platform: `.exe` and `.cmd`
+//! shims on Windows, Python entry points that may only be reachable through
+//! the `py` launcher, and renamed binaries such as `echidna-test`, which
+//! newer releases ship as `echidna`. Tool commands are built through this
+//! module so each call site does not have to know about these differences.
+//! Versions installed with `securechain install` come before anything on
+//! PATH.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::core::toolchain;
+
+/// Other names a tool is installed under
+const ALIASES: &[(&str, &[&str])] = &[
+    ("echidna-test", &["echidna"]),
+    ("echidna", &["echidna-test"]),
+];
+
+/// Python modules that run a tool when its entry point is not on PATH
+const PYTHON_MODULES: &[(&str, &str)] = &[
+    ("slither", "slither"),
+    ("myth", "mythril.interfaces.cli"),
+    ("semgrep", "semgrep"),
+];
+
+/// Full path of an executable, searching managed installs, PATH and platform extensions
+///
+/// Names containing a path separator are checked as given.
+pub fn find_executable(name: &str) -> Option<PathBuf> {
+    let candidates = std::iter::once(name).chain(aliases(name).iter().copied());
+
+    for candidate in candidates {
+        let path = Path::new(candidate);
+        if path.components().count() > 1 {
+            if let Some(found) = with_extensions(path).find(|p| is_executable(p)) {
+                return Some(found);
+            }
+            continue;
+        }
+
+        let search_path = std::env::var_os("PATH").unwrap_or_default();
+        let managed = toolchain::bin_dir();
+        for directory in managed.into_iter().chain(std::env::split_paths(&search_path)) {
+            if let Some(found) = with_extensions(&directory.join(candidate)).find(|p| is_executable(p)) {
+                return Some(found);
+            }
+        }
+    }
+
+    None
+}
+
+/// Whether a tool can be started, directly or through the Python launcher
+pub fn is_tool_available(name: &str) -> bool {
+    find_executable(name).is_some() || python_fallback(name).is_some()
+}
+
+/// Command running a tool, resolved for the current platform
+///
+/// Falls back to the bare name when nothing is found, so spawning fails
+/// with the usual not-found error. Managed installs go first on the tool's
+/// PATH too, so a tool that starts another, as Slither starts solc, gets the
+/// managed version.
+pub fn command(name: &str) -> Command {
+    let mut command = resolve(name);
+    if let Some(managed) = toolchain::bin_dir() {
+        let search_path = std::env::var_os("PATH").unwrap_or_default();
+        let directories = std::iter::once(managed).chain(std::env::split_paths(&search_path));
+        if let Ok(path) = std::env::join_paths(directories) {
+            command.env("PATH", path);
+        }
+    }
+    command
+}
+
+fn resolve(name: &str) -> Command {
+    if let Some(path) = find_executable(name) {
+        return Command::new(path);
+    }
+
+    if let Some((launcher, module)) = python_fallback(name) {
+        let mut command = Command::new(launcher);
+        if cfg!(windows) {
+            command.arg("-3");
+        }
+        command.arg("-m").arg(module);
+        return command;
+    }
+
+    Command::new(name)
+}
+
+/// Async variant of [`command`]
+pub fn async_command(name: &str) -> tokio::process::Command {
+    tokio::process::Command::from(command(name))
+}
+
+/// Command running a setup script with the platform's shell
+pub fn script_command(script: &Path) -> Command {
+    let is_powershell = script.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ps1"));
+    if is_powershell {
+        let shell = if find_executable("pwsh").is_some() { "pwsh" } else { "powershell" };
+        let mut command = Command::new(shell);
+        command.args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File"]).arg(script);
+        command
+    } else {
+        let mut command = Command::new("bash");
+        command.arg(script);
+        command
+    }
+}
+
+/// File name of the setup script for this platform
+pub fn setup_script_name() -> &'static str {
+    if cfg!(windows) {
+        "setup.ps1"
+    } else {
+        "setup.sh"
+    }
+}
+
+fn aliases(name: &str) -> &'static [&'static str] {
+    ALIASES
+        .iter()
+        .find(|(tool, _)| *tool == name)
+        .map(|(_, aliases)| *aliases)
+        .unwrap_or(&[])
+}
+
+/// Python launcher and module for tools installed as Python packages
+///
+/// Only used on Windows, where pip's `Scripts` directory is often missing from PATH.
+fn python_fallback(name: &str) -> Option<(PathBuf, &'static str)> {
+    if !cfg!(windows) {
+        return None;
+    }
+    let module = PYTHON_MODULES.iter().find(|(tool, _)| *tool == name)?.1;
+    let launcher = find_executable("py").or_else(|| find_executable("python"))?;
+    Some((launcher, module))
+}
+
+/// Candidate files for a path: on Windows the path with each PATHEXT extension
+/// unless it already has one, elsewhere the path itself
+fn with_extensions(path: &Path) -> impl Iterator<Item = PathBuf> + '_ {
+    let extensions: Vec<OsString> = if cfg!(windows) && path.extension().is_none() {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+            .split(';')
+            .filter(|ext| !ext.is_empty())
+            .map(|ext| OsString::from(ext.to_lowercase()))
+            .collect()
+    } else {
+        vec![OsString::new()]
+    };
+
+    extensions.into_iter().map(move |ext| {
+        let mut with_ext = path.as_os_str().to_os_string();
+        with_ext.push(ext);
+        PathBuf::from(with_ext)
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
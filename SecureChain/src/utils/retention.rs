@@ -0,0 +1,238 @@
+//! Retention of caches, run history and leftover temporary files
+//!
+//! Long-lived audit machines filled their disks: expired analyses stayed in
+//! the cache, which only ignored them, and runs that were interrupted left
+//! their contract copies, Echidna corpora and scratch directories behind in
+//! the temporary directory. Temporary files of a run are named
+//! `securechain-*`, so leftovers can be told apart from those of other
+//! programs. The `[retention]` policy removes cached analyses by age and
+//! total size, run history rows by age, and leftovers by age and total size,
+//! oldest first. `securechain purge` applies it, and commands apply it when
+//! they start, at most once a day. Triage overrides, usage metrics and rule
+//! packs are records the user keeps and are never purged.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::core::history;
+use crate::status;
+use crate::utils::budget;
+use crate::utils::cache;
+use crate::utils::config::Config;
+
+/// Name prefix of every temporary file and directory a run creates
+pub const TEMP_PREFIX: &str = "securechain-";
+
+/// Leftovers written to this recently may belong to a run still going, and are not trimmed for size
+const ACTIVE_GRACE: Duration = Duration::from_secs(3600);
+
+/// Least time between two purges when commands start
+const STARTUP_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+const MB: u64 = 1024 * 1024;
+
+/// A file or directory the policy removes
+#[derive(Debug, Clone, Serialize)]
+pub struct PurgeItem {
+    /// "cache" or "temp"
+    pub store: &'static str,
+    pub path: PathBuf,
+    pub bytes: u64,
+    /// Last write to the item, or to anything below it
+    pub modified: DateTime<Utc>,
+    /// Limit the item is over
+    pub reason: String,
+}
+
+/// What a purge removes, or removed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Purge {
+    pub items: Vec<PurgeItem>,
+    /// Run history rows older than `retention.history_max_age_days`
+    pub history_runs: usize,
+}
+
+impl Purge {
+    pub fn bytes(&self) -> u64 {
+        self.items.iter().map(|item| item.bytes).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.history_runs == 0
+    }
+}
+
+/// A file or directory of one store, before the policy is applied
+struct Stored {
+    path: PathBuf,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+/// Apply the retention policy of `config`, or with `dry_run` only list what it would remove
+///
+/// Items that cannot be removed are left out of the result with a warning.
+pub fn purge(config: &Config, dry_run: bool) -> Result<Purge> {
+    let retention = &config.retention;
+    let mut items = select(
+        "cache",
+        cache_entries(&cache::cache_dir(config))?,
+        retention.cache_max_age_days * 24 * 3600,
+        &format!("{} days", retention.cache_max_age_days),
+        retention.cache_max_size_mb,
+        Duration::ZERO,
+    );
+    items.extend(select(
+        "temp",
+        leftovers(&budget::system_temp_dir())?,
+        retention.temp_max_age_hours * 3600,
+        &format!("{} hours", retention.temp_max_age_hours),
+        retention.temp_max_size_mb,
+        ACTIVE_GRACE,
+    ));
+
+    let history_runs = match retention.history_max_age_days {
+        0 => 0,
+        days => history::prune(config, Utc::now() - chrono::Duration::days(days as i64), dry_run)?,
+    };
+
+    if !dry_run {
+        items.retain(|item| {
+            let removed = if item.path.is_dir() { std::fs::remove_dir_all(&item.path) } else { std::fs::remove_file(&item.path) };
+            match removed {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("Could not remove {}: {}", item.path.display(), e);
+                    false
+                }
+            }
+        });
+    }
+    Ok(Purge { items, history_runs })
+}
+
+/// Apply the retention policy when a command starts, unless it was applied within the last day
+///
+/// A failure only costs disk space, so it is logged and the command goes on.
+pub fn enforce_on_startup(config: &Config) {
+    if !config.retention.enforce_on_startup {
+        return;
+    }
+    let stamp = cache::expand_home(&config.general.cache_dir).join("last-purge");
+    let recent = std::fs::metadata(&stamp)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|elapsed| elapsed < STARTUP_INTERVAL);
+    if recent {
+        return;
+    }
+    if let Err(e) = stamp.parent().map_or(Ok(()), std::fs::create_dir_all).and_then(|_| std::fs::write(&stamp, "")) {
+        log::warn!("Could not record the retention run in {}: {}", stamp.display(), e);
+    }
+
+    match purge(config, false) {
+        Ok(purged) if !purged.is_empty() => status!(
+            "🧹 Retention policy removed {} item{} ({:.1} MB) and {} run history row{}",
+            purged.items.len(),
+            if purged.items.len() == 1 { "" } else { "s" },
+            purged.bytes() as f64 / MB as f64,
+            purged.history_runs,
+            if purged.history_runs == 1 { "" } else { "s" }
+        ),
+        Ok(_) => {}
+        Err(e) => log::warn!("Retention policy not applied: {}", e),
+    }
+}
+
+/// Items of a store past `max_age_secs`, then the oldest of the rest until it fits in `max_size_mb`
+fn select(
+    store: &'static str,
+    mut entries: Vec<Stored>,
+    max_age_secs: u64,
+    max_age: &str,
+    max_size_mb: u64,
+    grace: Duration,
+) -> Vec<PurgeItem> {
+    entries.sort_by_key(|entry| entry.modified);
+    let age = |entry: &Stored| entry.modified.elapsed().unwrap_or_default();
+    let item = |entry: Stored, reason: String| PurgeItem {
+        store,
+        path: entry.path,
+        bytes: entry.bytes,
+        modified: DateTime::<Utc>::from(entry.modified),
+        reason,
+    };
+
+    let mut items = Vec::new();
+    let mut kept = Vec::new();
+    for entry in entries {
+        if max_age_secs > 0 && age(&entry).as_secs() > max_age_secs {
+            items.push(item(entry, format!("older than {}", max_age)));
+        } else {
+            kept.push(entry);
+        }
+    }
+
+    let mut total: u64 = kept.iter().map(|entry| entry.bytes).sum();
+    if max_size_mb > 0 {
+        for entry in kept {
+            if total <= max_size_mb * MB {
+                break;
+            }
+            if age(&entry) < grace {
+                continue;
+            }
+            total -= entry.bytes;
+            items.push(item(entry, format!("{} over {} MB", store, max_size_mb)));
+        }
+    }
+    items
+}
+
+/// Entries of the analysis cache
+fn cache_entries(dir: &Path) -> Result<Vec<Stored>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut stored = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+            continue;
+        }
+        if let Ok(metadata) = entry.metadata() {
+            let modified = metadata.modified().unwrap_or_else(|_| SystemTime::now());
+            stored.push(Stored { path, bytes: metadata.len(), modified });
+        }
+    }
+    Ok(stored)
+}
+
+/// Temporary files and directories of runs in `dir`, other than this run's own
+fn leftovers(dir: &Path) -> Result<Vec<Stored>> {
+    let own_scratch = format!("{}run-{}", TEMP_PREFIX, std::process::id());
+    let mut stored = Vec::new();
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !name.starts_with(TEMP_PREFIX) || name == own_scratch {
+            continue;
+        }
+        // A directory counts as written to when anything below it was
+        let mut bytes = 0;
+        let mut modified = SystemTime::UNIX_EPOCH;
+        for metadata in walkdir::WalkDir::new(entry.path()).into_iter().filter_map(|entry| entry.ok()?.metadata().ok()) {
+            if metadata.is_file() {
+                bytes += metadata.len();
+            }
+            modified = modified.max(metadata.modified().unwrap_or_else(|_| SystemTime::now()));
+        }
+        stored.push(Stored { path: entry.path(), bytes, modified });
+    }
+    Ok(stored)
+}
@@ -0,0 +1,146 @@
+//! Memory of parsing and analysing a large flattened contract
+//!
+//! A multi-megabyte flattened source used to be copied for every function
+//! body and every clone of the parsed contract. A counting allocator keeps
+//! the peak and retained memory of each stage to a small multiple of the
+//! source, so copies creeping back in fail here before they reach users.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use securechain::core::fetcher::ContractInfo;
+use securechain::core::parser::{ContractParser, ParsedContract};
+
+/// Counts the bytes allocated now and at most since the last reset
+struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(current, Ordering::SeqCst);
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Tests measure one at a time, since they share the counters
+static MEASURING: Mutex<()> = Mutex::new(());
+
+/// Bytes allocated by `stage` at its peak, and still held when it returns
+fn measure<T>(stage: impl FnOnce() -> T) -> (T, usize, usize) {
+    let before = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(before, Ordering::SeqCst);
+    let value = stage();
+    let peak = PEAK.load(Ordering::SeqCst) - before;
+    let retained = CURRENT.load(Ordering::SeqCst).saturating_sub(before);
+    (value, peak, retained)
+}
+
+/// A flattened file of `contracts` contracts, about 1 KB each
+fn large_source(contracts: usize) -> String {
+    let mut source = String::from("// SPDX-License-Identifier: MIT\npragma solidity ^0.8.19;\n\n");
+    for index in 0..contracts {
+        source.push_str(&format!(
+            r#"/// Vault number {index}
+contract Vault{index} {{
+    mapping(address => uint256) public balances;
+    address public owner;
+    uint256 public total;
+
+    modifier onlyOwner() {{
+        require(msg.sender == owner, "not owner");
+        _;
+    }}
+
+    constructor() {{
+        owner = msg.sender;
+    }}
+
+    function deposit() external payable {{
+        balances[msg.sender] += msg.value;
+        total += msg.value;
+    }}
+
+    function withdraw(uint256 amount) external {{
+        require(balances[msg.sender] >= amount, "insufficient");
+        (bool ok, ) = msg.sender.call{{value: amount}}("");
+        require(ok, "transfer failed");
+        balances[msg.sender] -= amount;
+        total -= amount;
+    }}
+
+    function sweep(address payable to) external onlyOwner {{
+        // Send everything that is not accounted for
+        uint256 extra = address(this).balance - total;
+        to.transfer(extra);
+    }}
+
+    function share(address account) public view returns (uint256) {{
+        if (total == 0) {{
+            return 0;
+        }}
+        return balances[account] * 1e18 / total;
+    }}
+}}
+
+"#
+        ));
+    }
+    source
+}
+
+fn parse(source: String) -> ParsedContract {
+    let info = ContractInfo {
+        name: "Flattened".to_string(),
+        address: String::new(),
+        source_code: source,
+        compiler_version: "0.8.19".to_string(),
+        optimization: false,
+        network: "local".to_string(),
+        verified: false,
+        metadata: HashMap::new(),
+        project: None,
+    };
+    ContractParser::new().unwrap().parse_contract(&info).unwrap()
+}
+
+/// Contracts in the fixture, about 430 KB; debug builds parse it in seconds
+const CONTRACTS: usize = 400;
+
+#[test]
+fn large_flattened_contract_stays_within_a_multiple_of_its_source() {
+    let _measuring = MEASURING.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let source = large_source(CONTRACTS);
+    let size = source.len();
+
+    // About 6x is held by the parse, and regexes and scratch add a fixed few MB at the peak
+    let (contract, peak, retained) = measure(|| parse(source));
+    assert_eq!(contract.units.len(), CONTRACTS);
+    assert!(peak < 20 * size, "parsing {} bytes peaked at {} bytes", size, peak);
+    assert!(retained < 8 * size, "parsing {} bytes retained {} bytes", size, retained);
+
+    let bodies: Vec<_> = contract.units.iter().flat_map(|unit| &unit.functions).filter_map(|function| function.body.as_ref()).collect();
+    assert_eq!(bodies.len(), CONTRACTS * 4);
+    assert!(bodies.iter().all(|body| contract.offset_of(body).is_some()), "function bodies were copied out of the source");
+
+    // Only names and signatures are copied, never the source
+    let (copy, _, retained) = measure(|| contract.clone());
+    assert!(std::ptr::eq(copy.source_code.as_ptr(), contract.source_code.as_ptr()));
+    assert!(std::ptr::eq(copy.code().as_ptr(), contract.code().as_ptr()));
+    assert!(retained < 4 * size, "cloning a parse of {} bytes retained {} bytes", size, retained);
+}
@@ -0,0 +1,104 @@
+//! The analysis engine run against `securechain::testing`: fixture
+//! contracts, mock tool executables and the mock AI provider
+#![cfg(feature = "test-support")]
+
+use std::path::PathBuf;
+
+use securechain::core::analyzer::AnalysisEngine;
+use securechain::plugins::PluginManager;
+use securechain::report::vulnerability::VulnerabilityCategory;
+use securechain::testing::tools::{MYTHRIL_JSON, SLITHER_JSON};
+use securechain::testing::{fixture, fixtures, write_fixtures, MockTools};
+use securechain::utils::config::Config;
+
+const REENTRANCY: &str = "SWC-107_reentrancy.sol";
+
+/// A configuration that leaves no cache behind
+fn config() -> Config {
+    let mut config = Config::default();
+    config.analysis.cache_results = false;
+    config
+}
+
+/// The reentrancy fixture, written to a fresh directory
+fn reentrancy() -> (tempfile::TempDir, PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(REENTRANCY);
+    std::fs::write(&path, fixture(REENTRANCY).unwrap().source).unwrap();
+    (dir, path)
+}
+
+#[test]
+fn fixtures_are_written_with_their_sources() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = write_fixtures(dir.path()).unwrap();
+
+    assert_eq!(paths.len(), fixtures().len());
+    for (path, fixture) in paths.iter().zip(fixtures()) {
+        assert_eq!(std::fs::read_to_string(path).unwrap(), fixture.source);
+    }
+    assert_eq!(fixture(REENTRANCY).unwrap().category, VulnerabilityCategory::Reentrancy);
+}
+
+#[tokio::test]
+async fn mock_slither_findings_reach_the_results() {
+    let tools = MockTools::new().unwrap().with_slither(SLITHER_JSON).unwrap();
+    let mut config = config();
+    tools.configure(&mut config).unwrap();
+    let (_dir, path) = reentrancy();
+
+    let engine = AnalysisEngine::new(config, PluginManager::new());
+    let results = engine.analyze_contracts(&path, "evm", "standard", false).await.unwrap();
+
+    let invocations = tools.invocations("slither");
+    assert!(!invocations.is_empty(), "Slither was not run");
+    assert!(invocations.iter().any(|arguments| arguments.contains("--json")), "{:?}", invocations);
+    assert!(
+        results
+            .vulnerabilities
+            .iter()
+            .any(|vulnerability| vulnerability.category == VulnerabilityCategory::Reentrancy && vulnerability.line_number.is_some()),
+        "no reentrancy among {:?}",
+        results.vulnerabilities.iter().map(|vulnerability| &vulnerability.title).collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn mock_mythril_runs_only_in_deep_audits() {
+    let tools = MockTools::new().unwrap().with_slither(SLITHER_JSON).unwrap().with_mythril(MYTHRIL_JSON).unwrap();
+    let mut config = config();
+    tools.configure(&mut config).unwrap();
+    let (_dir, path) = reentrancy();
+    let engine = AnalysisEngine::new(config, PluginManager::new());
+
+    engine.analyze_contracts(&path, "evm", "standard", false).await.unwrap();
+    assert!(tools.invocations("mythril").is_empty());
+
+    let results = engine.analyze_contracts(&path, "evm", "deep", false).await.unwrap();
+    assert!(!tools.invocations("mythril").is_empty(), "Mythril was not run");
+    assert!(results.vulnerabilities.iter().any(|vulnerability| vulnerability.title.contains("External Call")));
+}
+
+#[cfg(feature = "ai")]
+#[tokio::test]
+async fn ai_analysis_checks_the_mock_provider() {
+    use securechain::testing::MockAiProvider;
+
+    let provider = MockAiProvider::start("mock-model", "{}").unwrap();
+    let mut config = config();
+    provider.configure(&mut config);
+    let (_dir, path) = reentrancy();
+
+    let engine = AnalysisEngine::new(config, PluginManager::new());
+    let results = engine.analyze_contracts(&path, "evm", "standard", true).await.unwrap();
+
+    let requests = provider.requests();
+    assert!(
+        requests.iter().any(|request| request.method == "GET" && request.path == "/api/tags"),
+        "the provider's health was not checked: {:?}",
+        requests
+    );
+    let usage = results.ai_usage.expect("no AI usage recorded");
+    assert!(usage.requests > 0);
+    assert!(usage.stages.contains_key("analysis"), "{:?}", usage.stages.keys().collect::<Vec<_>>());
+}